@@ -0,0 +1,156 @@
+//! Command-line interface for EvorBrain
+//!
+//! Works directly on the desktop app's SQLite database through the shared
+//! `evorbrain-core` repository, so scripts and terminals see exactly the same
+//! data and validation rules as the app.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::db::{self, models::TaskInput, models::TaskPriority, repository::Repository};
+use evorbrain_core::settings;
+use evorbrain_core::validation::{validate_optional_id, validate_title};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Bundle identifier of the desktop app, used to locate its data directory
+const APP_IDENTIFIER: &str = "com.evorbrain.evorbrain";
+
+#[derive(Parser)]
+#[command(name = "evorbrain-cli", version, about = "Manage your EvorBrain data from the terminal")]
+struct Cli {
+    /// Path to the database file (defaults to the desktop app's database)
+    #[arg(long, global = true, env = "EVORBRAIN_DB")]
+    database: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create and list tasks
+    Task {
+        #[command(subcommand)]
+        command: TaskCommand,
+    },
+    /// Export all data as JSON
+    Export {
+        /// Include archived items in the export
+        #[arg(long)]
+        include_archived: bool,
+        /// Write the export to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TaskCommand {
+    /// Add a task (to the inbox unless a project is given)
+    Add {
+        /// Task title
+        title: String,
+        /// Project ID to add the task to
+        #[arg(long)]
+        project: Option<String>,
+        /// Priority: low, medium, high or urgent
+        #[arg(long, default_value = "medium")]
+        priority: TaskPriority,
+        /// Due date in YYYY-MM-DD format
+        #[arg(long)]
+        due: Option<NaiveDate>,
+        /// Optional longer description
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// List open tasks
+    List,
+}
+
+fn default_database_path() -> Result<String> {
+    let data_dir = dirs::data_dir()
+        .context("Could not determine the platform data directory; pass --database")?
+        .join(APP_IDENTIFIER);
+    db::connection::database_path_in(&data_dir)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let database_path = match cli.database {
+        Some(path) => path.to_string_lossy().into_owned(),
+        None => default_database_path()?,
+    };
+    let pool = db::init_database(&database_path)
+        .await
+        .with_context(|| format!("Failed to open database at {}", database_path))?;
+    let repo = Repository::new(Arc::new(pool));
+
+    match cli.command {
+        Command::Task { command } => run_task_command(&repo, command).await,
+        Command::Export {
+            include_archived,
+            output,
+        } => {
+            let export = repo.export_all_data(include_archived).await?;
+            let json = serde_json::to_string_pretty(&export)?;
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, json)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    eprintln!("Exported {} items to {}", export.item_count, path.display());
+                }
+                None => println!("{}", json),
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn run_task_command(repo: &Repository, command: TaskCommand) -> Result<()> {
+    match command {
+        TaskCommand::Add {
+            title,
+            project,
+            priority,
+            due,
+            description,
+        } => {
+            validate_title("title", &title)?;
+            validate_optional_id(project.as_deref())?;
+            let calendar = Calendar::from_settings(&settings::load(repo).await?);
+
+            let task = repo
+                .create_task(TaskInput {
+                    project_id: project,
+                    parent_task_id: None,
+                    title: title.trim().to_string(),
+                    description,
+                    priority,
+                    due_date: due.map(|d| calendar.day(d).start),
+                })
+                .await?;
+
+            println!("Created task {} ({})", task.title, task.id);
+            Ok(())
+        }
+        TaskCommand::List => {
+            let calendar = Calendar::from_settings(&settings::load(repo).await?);
+            for task in repo.get_tasks().await? {
+                if task.is_completed() {
+                    continue;
+                }
+                let due = task
+                    .due_date
+                    .map(|d| format!(", due {}", calendar.format_date(calendar.local_date(d))))
+                    .unwrap_or_default();
+                println!("{}  {} [{}{}]", task.id, task.title, task.priority, due);
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Achievements, completion streaks and points
+//!
+//! Achievements are defined in code and unlocked once their rule is met;
+//! only the unlock time is stored (in the `achievements` table), so an
+//! achievement stays unlocked even if the tasks that earned it are deleted
+//! later. Streaks count consecutive working days (per the `working_days` and
+//! `holidays` settings and the holiday sets) with at least one completed
+//! task, so a free weekend doesn't break a streak. Points are derived: a
+//! fixed amount per completed task, project and goal, plus the points of
+//! every unlocked achievement.
+
+use crate::calendar::Calendar;
+use crate::db::models::CompletionCounts;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+const TASK_POINTS: i64 = 10;
+const PROJECT_POINTS: i64 = 50;
+const GOAL_POINTS: i64 = 100;
+
+/// What has to happen for an achievement to unlock
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    TasksCompleted(i64),
+    ProjectsCompleted(i64),
+    GoalsCompleted(i64),
+    /// Longest streak, in working days
+    Streak(i64),
+}
+
+struct Definition {
+    key: &'static str,
+    name: &'static str,
+    description: &'static str,
+    points: i64,
+    rule: Rule,
+}
+
+const DEFINITIONS: &[Definition] = &[
+    Definition {
+        key: "first_task",
+        name: "First step",
+        description: "Complete your first task",
+        points: 10,
+        rule: Rule::TasksCompleted(1),
+    },
+    Definition {
+        key: "tasks_100",
+        name: "Centurion",
+        description: "Complete 100 tasks",
+        points: 100,
+        rule: Rule::TasksCompleted(100),
+    },
+    Definition {
+        key: "tasks_1000",
+        name: "Unstoppable",
+        description: "Complete 1000 tasks",
+        points: 500,
+        rule: Rule::TasksCompleted(1000),
+    },
+    Definition {
+        key: "first_project",
+        name: "Shipped",
+        description: "Finish your first project",
+        points: 50,
+        rule: Rule::ProjectsCompleted(1),
+    },
+    Definition {
+        key: "first_goal",
+        name: "Goal getter",
+        description: "Finish your first goal",
+        points: 100,
+        rule: Rule::GoalsCompleted(1),
+    },
+    Definition {
+        key: "goals_10",
+        name: "Visionary",
+        description: "Finish 10 goals",
+        points: 300,
+        rule: Rule::GoalsCompleted(10),
+    },
+    Definition {
+        key: "streak_7",
+        name: "On a roll",
+        description: "Complete a task on 7 working days in a row",
+        points: 50,
+        rule: Rule::Streak(7),
+    },
+    Definition {
+        key: "streak_30",
+        name: "Habit formed",
+        description: "Complete a task on 30 working days in a row",
+        points: 200,
+        rule: Rule::Streak(30),
+    },
+];
+
+/// An achievement with the user's progress towards it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Achievement {
+    pub key: String,
+    pub name: String,
+    pub description: String,
+    pub points: i64,
+    /// Set once the achievement is unlocked
+    pub unlocked_at: Option<DateTime<Utc>>,
+    /// Current value of what the rule counts, capped at `target`
+    pub progress: i64,
+    pub target: i64,
+}
+
+/// Everything `get_achievements` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AchievementSummary {
+    pub points: i64,
+    /// Working days in a row, up to today, with a completed task
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub completions: CompletionCounts,
+    pub achievements: Vec<Achievement>,
+}
+
+/// Current and longest streak in working days
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Streaks {
+    current: i64,
+    longest: i64,
+}
+
+/// Computes streaks from the dates tasks were completed on, in order
+fn streaks(calendar: &Calendar, completed_on: &[NaiveDate], today: NaiveDate) -> Streaks {
+    let mut streaks = Streaks::default();
+    let mut last: Option<NaiveDate> = None;
+    let mut run = 0;
+
+    for &date in completed_on.iter().filter(|date| calendar.is_working_day(**date)) {
+        if last == Some(date) {
+            continue;
+        }
+        let continues = last.is_some_and(|last| calendar.previous_working_day(date - Duration::days(1)) == last);
+        run = if continues { run + 1 } else { 1 };
+        streaks.longest = streaks.longest.max(run);
+        last = Some(date);
+    }
+
+    // The streak is still alive until the working day after the last one passes
+    let alive_since = calendar.previous_working_day(today - Duration::days(1));
+    if last.is_some_and(|last| last >= alive_since) {
+        streaks.current = run;
+    }
+    streaks
+}
+
+struct Progress {
+    completions: CompletionCounts,
+    streaks: Streaks,
+}
+
+impl Progress {
+    async fn load(repo: &Repository) -> AppResult<Self> {
+        let calendar = Calendar::load(repo).await?;
+        let completed_on: Vec<NaiveDate> = repo
+            .get_task_completion_times()
+            .await?
+            .into_iter()
+            .map(|instant| calendar.local_date(instant))
+            .collect();
+
+        Ok(Self {
+            completions: repo.get_completion_counts().await?,
+            streaks: streaks(&calendar, &completed_on, calendar.today()),
+        })
+    }
+
+    /// Progress and target for a rule
+    fn towards(&self, rule: Rule) -> (i64, i64) {
+        match rule {
+            Rule::TasksCompleted(target) => (self.completions.tasks, target),
+            Rule::ProjectsCompleted(target) => (self.completions.projects, target),
+            Rule::GoalsCompleted(target) => (self.completions.goals, target),
+            Rule::Streak(target) => (self.streaks.longest, target),
+        }
+    }
+
+    fn is_met(&self, rule: Rule) -> bool {
+        let (progress, target) = self.towards(rule);
+        progress >= target
+    }
+}
+
+fn achievement(definition: &Definition, progress: &Progress, unlocked_at: Option<DateTime<Utc>>) -> Achievement {
+    let (value, target) = progress.towards(definition.rule);
+    Achievement {
+        key: definition.key.to_string(),
+        name: definition.name.to_string(),
+        description: definition.description.to_string(),
+        points: definition.points,
+        unlocked_at,
+        progress: if unlocked_at.is_some() { target } else { value.min(target) },
+        target,
+    }
+}
+
+/// Returns every achievement with its progress, plus streaks and points
+pub async fn get_achievements(repo: &Repository) -> AppResult<AchievementSummary> {
+    let progress = Progress::load(repo).await?;
+    let unlocked = repo.get_unlocked_achievements().await?;
+
+    let achievements: Vec<Achievement> = DEFINITIONS
+        .iter()
+        .map(|definition| achievement(definition, &progress, unlocked.get(definition.key).copied()))
+        .collect();
+
+    let completions = progress.completions;
+    let points = completions.tasks * TASK_POINTS
+        + completions.projects * PROJECT_POINTS
+        + completions.goals * GOAL_POINTS
+        + achievements.iter().filter(|a| a.unlocked_at.is_some()).map(|a| a.points).sum::<i64>();
+
+    Ok(AchievementSummary {
+        points,
+        current_streak: progress.streaks.current,
+        longest_streak: progress.streaks.longest,
+        completions,
+        achievements,
+    })
+}
+
+/// Unlocks every achievement whose rule is now met; returns the ones that
+/// were newly unlocked
+pub async fn check_achievements(repo: &Repository) -> AppResult<Vec<Achievement>> {
+    let progress = Progress::load(repo).await?;
+    let unlocked = repo.get_unlocked_achievements().await?;
+
+    let reached: Vec<&str> = DEFINITIONS
+        .iter()
+        .filter(|definition| !unlocked.contains_key(definition.key) && progress.is_met(definition.rule))
+        .map(|definition| definition.key)
+        .collect();
+    if reached.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let newly_unlocked = repo.unlock_achievements(&reached).await?;
+    let now = Utc::now();
+    Ok(DEFINITIONS
+        .iter()
+        .filter(|definition| newly_unlocked.iter().any(|key| key == definition.key))
+        .map(|definition| achievement(definition, &progress, Some(now)))
+        .collect())
+}
@@ -0,0 +1,253 @@
+//! Task aging and completion latency
+//!
+//! The aging report shows how long open tasks have been waiting, counted in
+//! days of the user's calendar since the task was created: grouped into age
+//! buckets split by priority, per priority, and per project so the projects
+//! where work keeps getting put off stand out. Alongside, it shows how many
+//! days tasks completed in each of the last weeks took from creation to
+//! completion, to tell whether the backlog is getting worked off faster or
+//! slower.
+//!
+//! Every change of a due date is kept as well. Open tasks rescheduled more
+//! than a few times are chronic postponements, for the weekly review to
+//! break down into smaller steps or drop.
+
+use crate::calendar::Calendar;
+use crate::db::models::{OpenTaskAge, TaskPriority};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Age buckets as (label, oldest age in days); the last bucket has no limit
+const AGE_BUCKETS: &[(&str, Option<i64>)] = &[
+    ("0-7 days", Some(7)),
+    ("8-30 days", Some(30)),
+    ("31-90 days", Some(90)),
+    ("91-180 days", Some(180)),
+    ("over 180 days", None),
+];
+
+/// Open tasks older than this count as stale in the project breakdown
+const STALE_AFTER_DAYS: i64 = 30;
+
+/// Projects listed in the report, those with the oldest open tasks first
+const MAX_PROJECTS: usize = 10;
+
+/// Reschedules an open task may have before it counts as chronically
+/// postponed
+pub const CHRONIC_POSTPONEMENT_THRESHOLD: u32 = 3;
+
+/// Weeks of completion history, including the current one
+const TREND_WEEKS: i64 = 8;
+
+const PRIORITIES: [TaskPriority; 4] = [
+    TaskPriority::Low,
+    TaskPriority::Medium,
+    TaskPriority::High,
+    TaskPriority::Urgent,
+];
+
+/// Open tasks of each priority
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PriorityCounts {
+    pub low: i64,
+    pub medium: i64,
+    pub high: i64,
+    pub urgent: i64,
+}
+
+impl PriorityCounts {
+    fn add(&mut self, priority: TaskPriority) {
+        match priority {
+            TaskPriority::Low => self.low += 1,
+            TaskPriority::Medium => self.medium += 1,
+            TaskPriority::High => self.high += 1,
+            TaskPriority::Urgent => self.urgent += 1,
+        }
+    }
+}
+
+/// Open tasks whose age falls in a range of days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgeBucket {
+    pub label: String,
+    pub min_days: i64,
+    /// `None` for the last, open-ended bucket
+    pub max_days: Option<i64>,
+    pub total: i64,
+    pub by_priority: PriorityCounts,
+}
+
+/// Age of the open tasks of one priority
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityAging {
+    pub priority: TaskPriority,
+    pub open_tasks: i64,
+    pub average_age_days: f64,
+    pub oldest_age_days: i64,
+}
+
+/// Age of the open tasks of one project; tasks without a project are the inbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectAging {
+    pub project_id: Option<String>,
+    pub project_title: Option<String>,
+    pub open_tasks: i64,
+    pub average_age_days: f64,
+    /// Open tasks older than 30 days
+    pub stale_tasks: i64,
+}
+
+/// Tasks completed in one calendar week and how long they took
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionLatencyWeek {
+    pub first_day: NaiveDate,
+    /// e.g. `31/03/2024 – 06/04/2024` in the user's date format
+    pub label: String,
+    pub completed: i64,
+    /// Average days from creation to completion; `None` without completions
+    pub average_days_to_complete: Option<f64>,
+}
+
+/// Everything `get_task_aging_report` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskAgingReport {
+    pub open_tasks: i64,
+    pub average_age_days: f64,
+    /// Youngest bucket first
+    pub buckets: Vec<AgeBucket>,
+    /// Low to urgent
+    pub by_priority: Vec<PriorityAging>,
+    /// Projects with the highest average age first
+    pub projects: Vec<ProjectAging>,
+    /// The last weeks, oldest first, ending with the current week
+    pub completion_trend: Vec<CompletionLatencyWeek>,
+}
+
+fn average(total: i64, count: i64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total as f64 / count as f64
+    }
+}
+
+/// Builds the aging report for all open tasks and the recent completions
+pub async fn get_task_aging_report(repo: &Repository) -> AppResult<TaskAgingReport> {
+    let calendar = Calendar::load(repo).await?;
+    let today = calendar.today();
+
+    let open: Vec<(OpenTaskAge, i64)> = repo
+        .get_open_task_ages()
+        .await?
+        .into_iter()
+        .map(|task| {
+            let age = (today - calendar.local_date(task.created_at)).num_days().max(0);
+            (task, age)
+        })
+        .collect();
+
+    let mut min_days = 0;
+    let buckets = AGE_BUCKETS
+        .iter()
+        .map(|(label, max_days)| {
+            let mut bucket = AgeBucket {
+                label: label.to_string(),
+                min_days,
+                max_days: *max_days,
+                total: 0,
+                by_priority: PriorityCounts::default(),
+            };
+            for (task, _) in open
+                .iter()
+                .filter(|(_, age)| *age >= min_days && max_days.is_none_or(|max| *age <= max))
+            {
+                bucket.total += 1;
+                bucket.by_priority.add(task.priority);
+            }
+            min_days = max_days.map_or(min_days, |max| max + 1);
+            bucket
+        })
+        .collect();
+
+    let by_priority = PRIORITIES
+        .iter()
+        .map(|priority| {
+            let ages: Vec<i64> = open
+                .iter()
+                .filter(|(task, _)| task.priority == *priority)
+                .map(|(_, age)| *age)
+                .collect();
+            PriorityAging {
+                priority: *priority,
+                open_tasks: ages.len() as i64,
+                average_age_days: average(ages.iter().sum(), ages.len() as i64),
+                oldest_age_days: ages.iter().copied().max().unwrap_or(0),
+            }
+        })
+        .collect();
+
+    let mut projects: Vec<ProjectAging> = Vec::new();
+    let mut age_totals: Vec<i64> = Vec::new();
+    for (task, age) in &open {
+        let index = match projects.iter().position(|p| p.project_id == task.project_id) {
+            Some(index) => index,
+            None => {
+                projects.push(ProjectAging {
+                    project_id: task.project_id.clone(),
+                    project_title: task.project_title.clone(),
+                    open_tasks: 0,
+                    average_age_days: 0.0,
+                    stale_tasks: 0,
+                });
+                age_totals.push(0);
+                projects.len() - 1
+            }
+        };
+        projects[index].open_tasks += 1;
+        age_totals[index] += age;
+        if *age > STALE_AFTER_DAYS {
+            projects[index].stale_tasks += 1;
+        }
+    }
+    for (project, total) in projects.iter_mut().zip(age_totals) {
+        project.average_age_days = average(total, project.open_tasks);
+    }
+    projects.sort_by(|a, b| b.average_age_days.total_cmp(&a.average_age_days));
+    projects.truncate(MAX_PROJECTS);
+
+    let current_week = calendar.week(today).first_day;
+    let weeks: Vec<_> = (0..TREND_WEEKS)
+        .rev()
+        .map(|ago| calendar.week(current_week - Duration::weeks(ago)))
+        .collect();
+    let spans = repo.get_completion_spans(weeks[0].range.start).await?;
+    let completion_trend = weeks
+        .into_iter()
+        .map(|week| {
+            let hours: Vec<i64> = spans
+                .iter()
+                .filter(|(_, completed_at)| (week.first_day..=week.last_day).contains(&calendar.local_date(*completed_at)))
+                .map(|(created_at, completed_at)| (*completed_at - *created_at).num_hours().max(0))
+                .collect();
+            CompletionLatencyWeek {
+                first_day: week.first_day,
+                label: week.label,
+                completed: hours.len() as i64,
+                average_days_to_complete: (!hours.is_empty())
+                    .then(|| average(hours.iter().sum(), hours.len() as i64) / 24.0),
+            }
+        })
+        .collect();
+
+    let open_tasks = open.len() as i64;
+    Ok(TaskAgingReport {
+        open_tasks,
+        average_age_days: average(open.iter().map(|(_, age)| age).sum(), open_tasks),
+        buckets,
+        by_priority,
+        projects,
+        completion_trend,
+    })
+}
@@ -0,0 +1,101 @@
+//! Completing projects and goals once their work is done
+//!
+//! When the last open task of a project is completed, the project can be
+//! completed along with it, and when the last open project of a goal is
+//! completed, the goal as well; goals are the milestones projects work
+//! towards. The `auto_complete` setting decides whether that happens on its
+//! own, is only suggested so the UI can ask, or is left alone entirely.
+//! Cancelled, archived and already completed projects and goals are never
+//! touched.
+
+use crate::db::models::ProjectStatus;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use serde::{Deserialize, Serialize};
+
+/// What happens when the work of a project or goal is all done
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoComplete {
+    /// Nothing
+    #[default]
+    Off,
+    /// A `*.completion_suggested` event is published for the UI to ask
+    Suggest,
+    /// The project or goal is completed and `*.auto_completed` published
+    Complete,
+}
+
+/// A project or goal whose work is all done
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finished {
+    /// `project` or `goal`
+    pub entity_type: &'static str,
+    pub id: String,
+    /// It was completed rather than only suggested
+    pub completed: bool,
+}
+
+impl Finished {
+    /// Name of the event announcing it
+    pub fn event_name(&self) -> String {
+        let action = if self.completed { "auto_completed" } else { "completion_suggested" };
+        format!("{}.{}", self.entity_type, action)
+    }
+}
+
+/// The task's project, if the task was the last open one in it
+pub async fn after_task_completed(repo: &Repository, task_id: &str, mode: AutoComplete) -> AppResult<Option<Finished>> {
+    if mode == AutoComplete::Off {
+        return Ok(None);
+    }
+    let Some(project_id) = repo.get_task(task_id).await?.project_id else {
+        return Ok(None);
+    };
+    let project = repo.get_project(&project_id).await?;
+    if project.archived_at.is_some() || matches!(project.status, ProjectStatus::Completed | ProjectStatus::Cancelled) {
+        return Ok(None);
+    }
+    if repo.get_remaining_work(&project_id).await?.task_count > 0 {
+        return Ok(None);
+    }
+
+    let completed = mode == AutoComplete::Complete;
+    if completed {
+        repo.update_project_status(&project_id, ProjectStatus::Completed).await?;
+    }
+    Ok(Some(Finished {
+        entity_type: "project",
+        id: project_id,
+        completed,
+    }))
+}
+
+/// The project's goal, if the project was the last open one in it
+pub async fn after_project_completed(
+    repo: &Repository,
+    project_id: &str,
+    mode: AutoComplete,
+) -> AppResult<Option<Finished>> {
+    if mode == AutoComplete::Off {
+        return Ok(None);
+    }
+    let goal_id = repo.get_project(project_id).await?.goal_id;
+    let goal = repo.get_goal(&goal_id).await?;
+    if goal.archived_at.is_some() || goal.completed_at.is_some() {
+        return Ok(None);
+    }
+    if repo.count_open_projects(&goal_id).await? > 0 {
+        return Ok(None);
+    }
+
+    let completed = mode == AutoComplete::Complete;
+    if completed {
+        repo.set_goal_completed(&goal_id, true).await?;
+    }
+    Ok(Some(Finished {
+        entity_type: "goal",
+        id: goal_id,
+        completed,
+    }))
+}
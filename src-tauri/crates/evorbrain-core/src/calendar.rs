@@ -0,0 +1,265 @@
+//! Day and week boundaries in the user's time zone
+//!
+//! Due dates are stored in UTC, but "today" and "this week" depend on the
+//! `timezone` and `week_start` settings. Queries that group or filter by day
+//! or week take their ranges from a `Calendar` built from the settings
+//! instead of assuming UTC days and Monday weeks.
+//!
+//! The calendar also knows which days are working days (the `working_days`
+//! and `holidays` settings, plus the holidays of enabled holiday sets), so
+//! scheduling features can move dates to the next working day or count
+//! working days instead of calendar days, and how many minutes a working day
+//! has (the `working_hours_*` settings).
+
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::locale::Locale;
+use crate::settings::{self, Settings};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// First day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+    Saturday,
+}
+
+impl From<WeekStart> for Weekday {
+    fn from(start: WeekStart) -> Self {
+        match start {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+            WeekStart::Saturday => Weekday::Sat,
+        }
+    }
+}
+
+/// How dates are written in backend output such as the CLI and exports
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    /// 2024-03-31
+    Iso,
+    /// 03/31/2024
+    Us,
+    /// 31/03/2024
+    European,
+    /// 31.03.2024
+    Dotted,
+}
+
+impl DateFormat {
+    fn pattern(self) -> &'static str {
+        match self {
+            DateFormat::Iso => "%Y-%m-%d",
+            DateFormat::Us => "%m/%d/%Y",
+            DateFormat::European => "%d/%m/%Y",
+            DateFormat::Dotted => "%d.%m.%Y",
+        }
+    }
+
+    pub fn format(self, date: NaiveDate) -> String {
+        date.format(self.pattern()).to_string()
+    }
+}
+
+/// A UTC range covering `[start, end)`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// A week in the user's calendar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Week {
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+    pub range: DateRange,
+    /// e.g. `31/03/2024 – 06/04/2024` in the user's date format
+    pub label: String,
+}
+
+/// Longest run of non-working days the working-day helpers look past; keeps
+/// them finite whatever the holiday list contains
+const MAX_NON_WORKING_RUN: i64 = 366;
+
+/// Resolves days and weeks using the user's settings
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    timezone: Tz,
+    week_start: WeekStart,
+    date_format: DateFormat,
+    locale: Locale,
+    working_days: Vec<Weekday>,
+    holidays: HashSet<NaiveDate>,
+    working_hours: (NaiveTime, NaiveTime),
+}
+
+impl Calendar {
+    /// Invalid time zones (which `settings::update` rejects) fall back to UTC
+    ///
+    /// Only the `holidays` setting counts; use `load` where holiday sets
+    /// matter for working days.
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            timezone: settings.timezone.parse().unwrap_or(Tz::UTC),
+            week_start: settings.week_start,
+            date_format: settings.date_format,
+            locale: settings.locale,
+            working_days: settings.working_days.clone(),
+            holidays: settings.holidays.iter().copied().collect(),
+            working_hours: (settings.working_hours_start, settings.working_hours_end),
+        }
+    }
+
+    /// The calendar from the current settings and enabled holiday sets
+    pub async fn load(repo: &Repository) -> AppResult<Self> {
+        let mut calendar = Self::from_settings(&settings::load(repo).await?);
+        calendar.holidays.extend(repo.get_holiday_dates().await?);
+        Ok(calendar)
+    }
+
+    /// The user's time zone
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// The current date in the user's time zone
+    pub fn today(&self) -> NaiveDate {
+        self.local_date(Utc::now())
+    }
+
+    /// The date an instant falls on in the user's time zone
+    pub fn local_date(&self, instant: DateTime<Utc>) -> NaiveDate {
+        instant.with_timezone(&self.timezone).date_naive()
+    }
+
+    /// Converts a local time to UTC; times in a DST gap move an hour later
+    fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self.timezone.from_local_datetime(&local) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.with_timezone(&Utc),
+            LocalResult::None => self
+                .timezone
+                .from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+                .map(|time| time.with_timezone(&Utc))
+                .unwrap_or_else(|| local.and_utc()),
+        }
+    }
+
+    /// Start of `date` in UTC
+    fn start_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        self.to_utc(date.and_hms_opt(0, 0, 0).unwrap_or_default())
+    }
+
+    /// Moves an instant to another date, keeping its local time of day
+    pub fn with_date(&self, instant: DateTime<Utc>, date: NaiveDate) -> DateTime<Utc> {
+        let local_time = instant.with_timezone(&self.timezone).time();
+        self.to_utc(date.and_time(local_time))
+    }
+
+    /// The UTC range covering `date`
+    pub fn day(&self, date: NaiveDate) -> DateRange {
+        DateRange {
+            start: self.start_of(date),
+            end: self.start_of(date + Duration::days(1)),
+        }
+    }
+
+    /// The week containing `date`
+    pub fn week(&self, date: NaiveDate) -> Week {
+        let first_day = date.week(self.week_start.into()).first_day();
+        let last_day = first_day + Duration::days(6);
+        Week {
+            first_day,
+            last_day,
+            range: DateRange {
+                start: self.start_of(first_day),
+                end: self.start_of(last_day + Duration::days(1)),
+            },
+            label: format!("{} – {}", self.format_date(first_day), self.format_date(last_day)),
+        }
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        self.date_format.format(date)
+    }
+
+    /// The date with its weekday, e.g. `Thursday 2026-10-22`
+    pub fn format_date_with_weekday(&self, date: NaiveDate) -> String {
+        format!("{} {}", self.locale.format_weekday(date), self.format_date(date))
+    }
+
+    /// The language weekdays and numbers are written in
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Whether `date` is a working day and not a holiday
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.working_days.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// The UTC range of the working hours on `date`, whether or not it is a
+    /// working day
+    pub fn working_hours(&self, date: NaiveDate) -> DateRange {
+        let (start, end) = self.working_hours;
+        DateRange {
+            start: self.to_utc(date.and_time(start)),
+            end: self.to_utc(date.and_time(end)),
+        }
+    }
+
+    /// Working minutes available on `date`; 0 on non-working days
+    pub fn working_minutes(&self, date: NaiveDate) -> i64 {
+        if !self.is_working_day(date) {
+            return 0;
+        }
+        let (start, end) = self.working_hours;
+        (end - start).num_minutes().max(0)
+    }
+
+    /// `date` itself if it is a working day, otherwise the next one
+    pub fn next_working_day(&self, date: NaiveDate) -> NaiveDate {
+        (0..=MAX_NON_WORKING_RUN)
+            .map(|offset| date + Duration::days(offset))
+            .find(|day| self.is_working_day(*day))
+            .unwrap_or(date)
+    }
+
+    /// `date` itself if it is a working day, otherwise the one before it
+    pub fn previous_working_day(&self, date: NaiveDate) -> NaiveDate {
+        (0..=MAX_NON_WORKING_RUN)
+            .map(|offset| date - Duration::days(offset))
+            .find(|day| self.is_working_day(*day))
+            .unwrap_or(date)
+    }
+
+    /// Moves `days` working days from `date` (backwards when negative); a
+    /// non-working start counts from the next working day, so adding 1 to a
+    /// Saturday gives Tuesday for a Monday-to-Friday week
+    pub fn add_working_days(&self, date: NaiveDate, days: i64) -> NaiveDate {
+        let step = Duration::days(days.signum());
+        let mut current = if days < 0 {
+            self.previous_working_day(date)
+        } else {
+            self.next_working_day(date)
+        };
+        for _ in 0..days.unsigned_abs() {
+            current = if days < 0 {
+                self.previous_working_day(current + step)
+            } else {
+                self.next_working_day(current + step)
+            };
+        }
+        current
+    }
+}
@@ -0,0 +1,97 @@
+//! Markdown checklists in notes
+//!
+//! Open `- [ ]` items of a note (also with `*` or `+`, at any indentation)
+//! can be turned into real tasks. Each item that becomes a task is rewritten
+//! into a link to it, `- [ ] [Call the plumber](evorbrain://task/<id>)`, so
+//! the note shows where the work went and extracting again skips it. Ticked
+//! items and those inside fenced code blocks are left alone.
+
+use crate::references;
+
+/// An open checklist item not yet turned into a task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    /// Index of the line in the note
+    pub line: usize,
+    pub title: String,
+}
+
+/// Splits a checklist line into its `- [ ] ` prefix, whether it is ticked
+/// and its text
+fn split_item(line: &str) -> Option<(&str, bool, &str)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let mut chars = trimmed.chars();
+    if !matches!(chars.next(), Some('-' | '*' | '+')) || chars.next() != Some(' ') {
+        return None;
+    }
+    let checked = match trimmed.get(2..5)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let text = trimmed[5..].strip_prefix(' ')?;
+    Some((&line[..indent + 6], checked, text.trim_end()))
+}
+
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// The open items of a note that don't link to a task yet
+pub fn open_items(markdown: &str) -> Vec<ChecklistItem> {
+    let mut items = Vec::new();
+    let mut in_code = false;
+
+    for (line, text) in markdown.lines().enumerate() {
+        if is_fence(text) {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let Some((_, false, title)) = split_item(text) else { continue };
+        let linked = references::extract(title)
+            .entities
+            .iter()
+            .any(|entity| entity.entity_type == "task");
+        if !title.is_empty() && !linked {
+            items.push(ChecklistItem {
+                line,
+                title: title.to_string(),
+            });
+        }
+    }
+    items
+}
+
+/// Escapes the characters that would end the text of a Markdown link
+pub fn escape_link_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('[', "\\[").replace(']', "\\]")
+}
+
+/// Rewrites the given items into links to the tasks made from them
+pub fn link_items(markdown: &str, tasks: &[(ChecklistItem, String)]) -> String {
+    markdown
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(line, text)| {
+            let Some((item, task_id)) = tasks.iter().find(|(item, _)| item.line == line) else {
+                return text.to_string();
+            };
+            let Some((prefix, _, _)) = split_item(text.trim_end_matches(['\r', '\n'])) else {
+                return text.to_string();
+            };
+            let ending = &text[text.trim_end_matches(['\r', '\n']).len()..];
+            format!(
+                "{}[{}](evorbrain://task/{}){}",
+                prefix,
+                escape_link_text(&item.title),
+                task_id,
+                ending
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,62 @@
+//! Conflicting edits
+//!
+//! An update can carry the `updated_at` of the entity as the client loaded
+//! it. When the entity has changed since, in another window, through the
+//! local API or by a job, the update is refused with a `CONFLICT` error
+//! instead of silently overwriting that change. The error data holds the
+//! entity as it is now and the change that was rejected, so the UI can show
+//! both side by side and save the merged result without another round trip.
+//! Updates without an expected timestamp overwrite as before.
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Data of a `CONFLICT` error
+#[derive(Debug, Clone, Serialize)]
+pub struct Conflict<'a, S: Serialize, C: Serialize> {
+    pub entity_type: &'a str,
+    pub id: &'a str,
+    /// `updated_at` the client sent
+    pub expected_updated_at: DateTime<Utc>,
+    /// `updated_at` of the stored entity
+    pub server_updated_at: DateTime<Utc>,
+    /// The entity as it is stored now
+    pub server: &'a S,
+    /// The rejected update, as the client sent it
+    pub client: &'a C,
+}
+
+/// Refuses an update if the entity changed after `expected_updated_at`
+pub fn ensure_unchanged<S: Serialize, C: Serialize>(
+    entity_type: &str,
+    id: &str,
+    expected_updated_at: Option<DateTime<Utc>>,
+    server_updated_at: DateTime<Utc>,
+    server: &S,
+    client: &C,
+) -> AppResult<()> {
+    let Some(expected_updated_at) = expected_updated_at else {
+        return Ok(());
+    };
+    if expected_updated_at == server_updated_at {
+        return Ok(());
+    }
+
+    let conflict = Conflict {
+        entity_type,
+        id,
+        expected_updated_at,
+        server_updated_at,
+        server,
+        client,
+    };
+    let data = serde_json::to_value(&conflict).map_err(|e| {
+        AppError::new(ErrorCode::InternalError, "Failed to describe the conflict").with_details(e.to_string())
+    })?;
+    Err(AppError::new(
+        ErrorCode::Conflict,
+        format!("This {} was changed since it was loaded", entity_type.replace('_', " ")),
+    )
+    .with_data(data))
+}
@@ -0,0 +1,177 @@
+//! Critical path of a project
+//!
+//! The open tasks of a project and the dependencies between them form a
+//! graph weighted by the tasks' estimates; tasks without one count as
+//! `DEFAULT_TASK_MINUTES`. The longest chain through it is the critical
+//! path: how long the project takes at the very least, however much work
+//! happens in parallel. Each task gets its slack, the minutes it can slip
+//! without moving the project's completion; the tasks without slack are the
+//! ones that actually gate it. The completion date works the path off over
+//! the working minutes of the coming working days, starting today.
+//!
+//! Dependencies on completed tasks and on tasks of other projects are left
+//! out, since they no longer hold up this project or aren't part of its
+//! plan.
+
+use crate::calendar::Calendar;
+use crate::db::repository::Repository;
+use crate::due_dates::DEFAULT_TASK_MINUTES;
+use crate::error::AppResult;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Days looked ahead for the completion date, about ten years
+const MAX_COMPLETION_DAYS: i64 = 3660;
+
+/// An open task placed on the project's schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub id: String,
+    pub title: String,
+    pub estimated_minutes: Option<i64>,
+    /// The estimate, or the default when there is none
+    pub minutes: i64,
+    /// Minutes into the project the task can start at the earliest, once
+    /// everything it depends on is done
+    pub earliest_start: i64,
+    pub earliest_finish: i64,
+    /// Minutes the task can slip without delaying the project
+    pub slack_minutes: i64,
+    /// Without slack, so it gates the project's completion
+    pub critical: bool,
+    /// Open tasks of the project it waits for
+    pub depends_on: Vec<String>,
+}
+
+/// Everything `get_critical_path` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriticalPath {
+    pub project_id: String,
+    /// Length of the critical path
+    pub total_minutes: i64,
+    /// The task IDs of the longest chain, first to last
+    pub path: Vec<String>,
+    /// Day the critical path is worked off, working through the working
+    /// hours from today; `None` when that is too far off to tell
+    pub estimated_completion: Option<NaiveDate>,
+    /// Every open task, earliest start first
+    pub tasks: Vec<ScheduledTask>,
+    /// Tasks counted with the default estimate
+    pub unestimated_count: i64,
+}
+
+/// The day `minutes` of work are done, working the working hours from `today`
+fn completion_date(calendar: &Calendar, minutes: i64, today: NaiveDate) -> Option<NaiveDate> {
+    let mut remaining = minutes;
+    for offset in 0..MAX_COMPLETION_DAYS {
+        let date = today + Duration::days(offset);
+        remaining -= calendar.working_minutes(date);
+        if remaining <= 0 {
+            return Some(date);
+        }
+    }
+    None
+}
+
+/// Computes the critical path of a project's open tasks
+pub async fn get_critical_path(repo: &Repository, project_id: &str) -> AppResult<CriticalPath> {
+    repo.get_project(project_id).await?;
+    let tasks: Vec<_> = repo
+        .get_tasks_by_project(project_id)
+        .await?
+        .into_iter()
+        .filter(|task| task.completed_at.is_none())
+        .collect();
+    let dependencies = repo.get_open_project_dependencies(project_id).await?;
+
+    let index: HashMap<&str, usize> = tasks.iter().enumerate().map(|(i, task)| (task.id.as_str(), i)).collect();
+    let minutes: Vec<i64> = tasks
+        .iter()
+        .map(|task| task.estimated_minutes.unwrap_or(DEFAULT_TASK_MINUTES))
+        .collect();
+    let mut predecessors = vec![Vec::new(); tasks.len()];
+    let mut successors = vec![Vec::new(); tasks.len()];
+    for dependency in &dependencies {
+        let (Some(&task), Some(&blocker)) =
+            (index.get(dependency.task_id.as_str()), index.get(dependency.depends_on_id.as_str()))
+        else {
+            continue;
+        };
+        predecessors[task].push(blocker);
+        successors[blocker].push(task);
+    }
+
+    // Topological order; adding a dependency refuses cycles, so every task
+    // ends up in it
+    let mut waiting: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    let mut ready: VecDeque<usize> = (0..tasks.len()).filter(|&i| waiting[i] == 0).collect();
+    let mut order = Vec::with_capacity(tasks.len());
+    while let Some(i) = ready.pop_front() {
+        order.push(i);
+        for &next in &successors[i] {
+            waiting[next] -= 1;
+            if waiting[next] == 0 {
+                ready.push_back(next);
+            }
+        }
+    }
+
+    let mut earliest_start = vec![0; tasks.len()];
+    for &i in &order {
+        earliest_start[i] = predecessors[i]
+            .iter()
+            .map(|&p| earliest_start[p] + minutes[p])
+            .max()
+            .unwrap_or(0);
+    }
+    let total_minutes = order.iter().map(|&i| earliest_start[i] + minutes[i]).max().unwrap_or(0);
+
+    let mut latest_start = vec![total_minutes; tasks.len()];
+    for &i in order.iter().rev() {
+        let latest_finish = successors[i].iter().map(|&s| latest_start[s]).min().unwrap_or(total_minutes);
+        latest_start[i] = latest_finish - minutes[i];
+    }
+    let slack = |i: usize| latest_start[i] - earliest_start[i];
+
+    // Walk back from the task that finishes last along tasks without slack
+    let mut path = Vec::new();
+    let mut current = order
+        .iter()
+        .copied()
+        .find(|&i| slack(i) == 0 && earliest_start[i] + minutes[i] == total_minutes);
+    while let Some(i) = current {
+        path.push(tasks[i].id.clone());
+        current = predecessors[i]
+            .iter()
+            .copied()
+            .find(|&p| slack(p) == 0 && earliest_start[p] + minutes[p] == earliest_start[i]);
+    }
+    path.reverse();
+
+    let calendar = Calendar::load(repo).await?;
+    let mut scheduled: Vec<ScheduledTask> = order
+        .iter()
+        .map(|&i| ScheduledTask {
+            id: tasks[i].id.clone(),
+            title: tasks[i].title.clone(),
+            estimated_minutes: tasks[i].estimated_minutes,
+            minutes: minutes[i],
+            earliest_start: earliest_start[i],
+            earliest_finish: earliest_start[i] + minutes[i],
+            slack_minutes: slack(i),
+            critical: slack(i) == 0,
+            depends_on: predecessors[i].iter().map(|&p| tasks[p].id.clone()).collect(),
+        })
+        .collect();
+    scheduled.sort_by_key(|task| (task.earliest_start, task.slack_minutes));
+
+    Ok(CriticalPath {
+        project_id: project_id.to_string(),
+        total_minutes,
+        path,
+        estimated_completion: completion_date(&calendar, total_minutes, calendar.today()),
+        unestimated_count: tasks.iter().filter(|task| task.estimated_minutes.is_none()).count() as i64,
+        tasks: scheduled,
+    })
+}
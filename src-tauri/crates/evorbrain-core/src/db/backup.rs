@@ -0,0 +1,69 @@
+//! Safety snapshots of the database file
+//!
+//! Before anything that can lose data in bulk (applying migrations, a reset,
+//! purging archived items) a copy of the database is written with
+//! `VACUUM INTO` to a `backups` directory next to the database file. Only
+//! the newest `MAX_BACKUPS` snapshots are kept.
+
+use crate::log_info;
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+
+/// Directory, next to the database file, the snapshots are written to
+pub const BACKUP_DIR_NAME: &str = "backups";
+/// Snapshots kept; older ones are deleted when a new one is taken
+pub const MAX_BACKUPS: usize = 10;
+
+const BACKUP_PREFIX: &str = "evorbrain-";
+
+/// Writes a consistent copy of the database to a timestamped file named
+/// after `reason`, e.g. `backups/evorbrain-migrate-20240131T120000123.db`
+///
+/// Returns `None` for an in-memory database, which has no file to copy.
+pub async fn snapshot(pool: &SqlitePool, reason: &str) -> Result<Option<PathBuf>> {
+    let (_, _, file): (i64, String, String) = sqlx::query_as("PRAGMA database_list")
+        .fetch_one(pool)
+        .await?;
+    if file.is_empty() {
+        return Ok(None);
+    }
+
+    let dir = Path::new(&file)
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(BACKUP_DIR_NAME);
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!(
+        "{}{}-{}.db",
+        BACKUP_PREFIX,
+        reason,
+        Utc::now().format("%Y%m%dT%H%M%S%3f")
+    ));
+    sqlx::query("VACUUM INTO ?1")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+    log_info!("Database backup written", path.display());
+
+    prune(&dir)?;
+    Ok(Some(path))
+}
+
+/// Deletes all but the newest `MAX_BACKUPS` snapshots
+fn prune(dir: &Path) -> Result<()> {
+    let mut backups: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(BACKUP_PREFIX))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for (_, path) in backups.into_iter().take(excess) {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
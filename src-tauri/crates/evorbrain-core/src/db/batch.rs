@@ -0,0 +1,361 @@
+//! Transactional batch execution
+//!
+//! Runs a list of heterogeneous operations in one transaction: either all of
+//! them are applied or none are. Later operations can refer to the entity
+//! created by an earlier one with `"$<index>"` wherever an ID is expected,
+//! e.g. create a task and then tag it with `{"op": "tag_task", "task_id": "$0", ...}`.
+//! This makes batches usable for importers, macros and replaying offline queues.
+
+use super::models::{NoteInput, TaskInput};
+use super::repository::Repository;
+use super::write_queue::BulkProgress;
+use crate::error::{AppError, AppResult};
+use crate::validation::{validate_id, validate_not_private, validate_title};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Sqlite, Transaction};
+use uuid::Uuid;
+
+/// Upper bound on operations per batch, to keep the write lock short
+pub const MAX_BATCH_OPERATIONS: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    CreateTask {
+        #[serde(flatten)]
+        input: TaskInput,
+    },
+    UpdateTask {
+        id: String,
+        #[serde(flatten)]
+        input: TaskInput,
+    },
+    CompleteTask { id: String },
+    /// Archives the task with its subtasks and notes
+    ArchiveTask { id: String },
+    CreateNote {
+        #[serde(flatten)]
+        input: NoteInput,
+    },
+    /// Adds a tag by name, creating the tag if needed
+    TagTask { task_id: String, tag: String },
+    UntagTask { task_id: String, tag: String },
+}
+
+impl BatchOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            BatchOperation::CreateTask { .. } => "create_task",
+            BatchOperation::UpdateTask { .. } => "update_task",
+            BatchOperation::CompleteTask { .. } => "complete_task",
+            BatchOperation::ArchiveTask { .. } => "archive_task",
+            BatchOperation::CreateNote { .. } => "create_note",
+            BatchOperation::TagTask { .. } => "tag_task",
+            BatchOperation::UntagTask { .. } => "untag_task",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchOperationStatus {
+    Ok,
+    Failed,
+    /// Not run because an earlier operation failed
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchOperationResult {
+    pub index: usize,
+    pub op: &'static str,
+    pub status: BatchOperationStatus,
+    /// ID of the entity the operation created or changed
+    pub id: Option<String>,
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchResult {
+    /// False when an operation failed and the whole batch was rolled back
+    pub committed: bool,
+    pub results: Vec<BatchOperationResult>,
+}
+
+/// Runs `operations` in one transaction, stopping at the first failure
+///
+/// A failing operation doesn't make this return an error: the transaction is
+/// rolled back and the result reports which operation failed and why, with
+/// the operations after it marked as skipped.
+pub async fn execute_batch(
+    repo: &Repository,
+    operations: Vec<BatchOperation>,
+    progress: &BulkProgress,
+) -> AppResult<BatchResult> {
+    if operations.len() > MAX_BATCH_OPERATIONS {
+        return Err(AppError::validation_error(
+            "operations",
+            &format!("at most {} operations per batch", MAX_BATCH_OPERATIONS),
+        ));
+    }
+
+    progress.set_total(operations.len());
+    let mut tx = repo.begin_transaction().await?;
+    let mut results: Vec<BatchOperationResult> = Vec::with_capacity(operations.len());
+    let mut failed = false;
+
+    for (index, operation) in operations.into_iter().enumerate() {
+        let op = operation.name();
+        if failed {
+            results.push(BatchOperationResult {
+                index,
+                op,
+                status: BatchOperationStatus::Skipped,
+                id: None,
+                error: None,
+            });
+            continue;
+        }
+
+        let applied = apply(&mut tx, operation, &results).await;
+        progress.advance(1);
+        match applied {
+            Ok(id) => results.push(BatchOperationResult {
+                index,
+                op,
+                status: BatchOperationStatus::Ok,
+                id: Some(id),
+                error: None,
+            }),
+            Err(e) => {
+                failed = true;
+                results.push(BatchOperationResult {
+                    index,
+                    op,
+                    status: BatchOperationStatus::Failed,
+                    id: None,
+                    error: Some(e),
+                });
+            }
+        }
+    }
+
+    if failed {
+        tx.rollback().await
+            .map_err(|e| AppError::database_error("roll back batch", e))?;
+        // Nothing was applied, so earlier IDs don't refer to anything
+        for result in &mut results {
+            result.id = None;
+        }
+    } else {
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit batch", e))?;
+    }
+
+    Ok(BatchResult {
+        committed: !failed,
+        results,
+    })
+}
+
+/// Resolves `"$<index>"` references to the ID produced by that operation
+fn resolve(id: &str, results: &[BatchOperationResult]) -> AppResult<String> {
+    let Some(reference) = id.strip_prefix('$') else {
+        validate_id(id)?;
+        return Ok(id.to_string());
+    };
+
+    reference
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| results.get(index))
+        .and_then(|result| result.id.clone())
+        .ok_or_else(|| {
+            AppError::validation_error("id", &format!("'{}' does not refer to an earlier operation", id))
+        })
+}
+
+fn resolve_optional(id: Option<&str>, results: &[BatchOperationResult]) -> AppResult<Option<String>> {
+    id.map(|id| resolve(id, results)).transpose()
+}
+
+fn resolve_task_input(mut input: TaskInput, results: &[BatchOperationResult]) -> AppResult<TaskInput> {
+    validate_title("title", &input.title)?;
+    input.project_id = resolve_optional(input.project_id.as_deref(), results)?;
+    input.parent_task_id = resolve_optional(input.parent_task_id.as_deref(), results)?;
+    Ok(input)
+}
+
+async fn apply(
+    tx: &mut Transaction<'_, Sqlite>,
+    operation: BatchOperation,
+    results: &[BatchOperationResult],
+) -> AppResult<String> {
+    let now = Utc::now();
+
+    match operation {
+        BatchOperation::CreateTask { input } => {
+            let input = resolve_task_input(input, results)?;
+            let id = Uuid::new_v4().to_string();
+
+            sqlx::query(
+                r#"
+                INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, due_date, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                "#
+            )
+            .bind(&id)
+            .bind(&input.project_id)
+            .bind(&input.parent_task_id)
+            .bind(&input.title)
+            .bind(&input.description)
+            .bind(input.priority.to_string())
+            .bind(input.due_date)
+            .bind(now)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("create task", e))?;
+
+            Ok(id)
+        }
+        BatchOperation::UpdateTask { id, input } => {
+            let id = resolve(&id, results)?;
+            let input = resolve_task_input(input, results)?;
+
+            let result = sqlx::query(
+                r#"
+                UPDATE tasks
+                SET project_id = ?1, parent_task_id = ?2, title = ?3, description = ?4,
+                    priority = ?5, due_date = ?6
+                WHERE id = ?7
+                "#
+            )
+            .bind(&input.project_id)
+            .bind(&input.parent_task_id)
+            .bind(&input.title)
+            .bind(&input.description)
+            .bind(input.priority.to_string())
+            .bind(input.due_date)
+            .bind(&id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("update task", e))?;
+
+            if result.rows_affected() == 0 {
+                return Err(AppError::not_found("Task", &id));
+            }
+            Ok(id)
+        }
+        BatchOperation::CompleteTask { id } => {
+            let id = resolve(&id, results)?;
+
+            let result = sqlx::query("UPDATE tasks SET completed_at = ?1 WHERE id = ?2")
+                .bind(now)
+                .bind(&id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("complete task", e))?;
+
+            if result.rows_affected() == 0 {
+                return Err(AppError::not_found("Task", &id));
+            }
+            Ok(id)
+        }
+        BatchOperation::ArchiveTask { id } => {
+            let id = resolve(&id, results)?;
+
+            let result = sqlx::query("UPDATE tasks SET archived_at = ?1 WHERE id = ?2")
+                .bind(now)
+                .bind(&id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("archive task", e))?;
+            if result.rows_affected() == 0 {
+                return Err(AppError::not_found("Task", &id));
+            }
+
+            sqlx::query("UPDATE tasks SET archived_at = ?1 WHERE parent_task_id = ?2 AND archived_at IS NULL")
+                .bind(now)
+                .bind(&id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("cascade archive subtasks", e))?;
+
+            sqlx::query("UPDATE notes SET archived_at = ?1 WHERE task_id = ?2 AND archived_at IS NULL")
+                .bind(now)
+                .bind(&id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("cascade archive notes", e))?;
+
+            Ok(id)
+        }
+        BatchOperation::CreateNote { input } => {
+            validate_title("title", &input.title)?;
+            validate_not_private(input.is_private)?;
+            let id = Uuid::new_v4().to_string();
+
+            sqlx::query(
+                r#"
+                INSERT INTO notes (id, task_id, project_id, goal_id, life_area_id, title, content, created_at, updated_at, is_private)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                "#
+            )
+            .bind(&id)
+            .bind(resolve_optional(input.task_id.as_deref(), results)?)
+            .bind(resolve_optional(input.project_id.as_deref(), results)?)
+            .bind(resolve_optional(input.goal_id.as_deref(), results)?)
+            .bind(resolve_optional(input.life_area_id.as_deref(), results)?)
+            .bind(&input.title)
+            .bind(&input.content)
+            .bind(now)
+            .bind(now)
+            .bind(input.is_private)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("create note", e))?;
+
+            Ok(id)
+        }
+        BatchOperation::TagTask { task_id, tag } => {
+            let task_id = resolve(&task_id, results)?;
+            let tag = tag.trim();
+            validate_title("tag", tag)?;
+
+            sqlx::query("INSERT OR IGNORE INTO tags (id, name, created_at) VALUES (?1, ?2, ?3)")
+                .bind(Uuid::new_v4().to_string())
+                .bind(tag)
+                .bind(now)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("create tag", e))?;
+
+            sqlx::query(
+                "INSERT OR IGNORE INTO task_tags (task_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2"
+            )
+            .bind(&task_id)
+            .bind(tag)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("tag task", e))?;
+
+            Ok(task_id)
+        }
+        BatchOperation::UntagTask { task_id, tag } => {
+            let task_id = resolve(&task_id, results)?;
+
+            sqlx::query(
+                "DELETE FROM task_tags WHERE task_id = ?1 AND tag_id IN (SELECT id FROM tags WHERE name = ?2)"
+            )
+            .bind(&task_id)
+            .bind(tag.trim())
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("untag task", e))?;
+
+            Ok(task_id)
+        }
+    }
+}
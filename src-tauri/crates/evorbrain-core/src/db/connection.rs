@@ -0,0 +1,41 @@
+use anyhow::Result;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+/// File name of the SQLite database inside the application data directory
+pub const DATABASE_FILE_NAME: &str = "evorbrain.db";
+
+pub async fn create_pool(database_url: &str) -> Result<SqlitePool> {
+    let connect_options = SqliteConnectOptions::new()
+        .filename(database_url)
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+        .foreign_keys(true);
+
+    // The option is applied per connection; checking it on each new one
+    // keeps a connection without enforcement out of the pool
+    let pool = SqlitePoolOptions::new()
+        .max_connections(5)
+        .after_connect(|conn, _meta| Box::pin(async move {
+            let enabled: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+                .fetch_one(&mut *conn)
+                .await?;
+            if !enabled {
+                return Err(sqlx::Error::Configuration("foreign key enforcement is off".into()));
+            }
+            Ok(())
+        }))
+        .connect_with(connect_options)
+        .await?;
+
+    Ok(pool)
+}
+
+/// Returns the database path inside `data_dir`, creating the directory if needed
+pub fn database_path_in(data_dir: &Path) -> Result<String> {
+    std::fs::create_dir_all(data_dir)?;
+    
+    let db_path = data_dir.join(DATABASE_FILE_NAME);
+    Ok(db_path.to_string_lossy().into_owned())
+}
\ No newline at end of file
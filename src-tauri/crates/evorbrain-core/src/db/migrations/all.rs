@@ -0,0 +1,264 @@
+use super::Migration;
+
+pub fn get_migrations() -> Vec<Migration> {
+    vec![
+        Migration::new(
+            1,
+            "Initial schema",
+            include_str!("./sql/001_initial_schema.up.sql"),
+            include_str!("./sql/001_initial_schema.down.sql"),
+        ),
+        Migration::new(
+            2,
+            "Add tags system",
+            include_str!("./sql/002_add_tags.up.sql"),
+            include_str!("./sql/002_add_tags.down.sql"),
+        ),
+        Migration::new(
+            3,
+            "Add change log",
+            include_str!("./sql/003_change_log.up.sql"),
+            include_str!("./sql/003_change_log.down.sql"),
+        ),
+        Migration::new(
+            4,
+            "Add webhooks",
+            include_str!("./sql/004_webhooks.up.sql"),
+            include_str!("./sql/004_webhooks.down.sql"),
+        ),
+        Migration::new(
+            5,
+            "Add email import",
+            include_str!("./sql/005_email_import.up.sql"),
+            include_str!("./sql/005_email_import.down.sql"),
+        ),
+        Migration::new(
+            6,
+            "Add external links",
+            include_str!("./sql/006_external_links.up.sql"),
+            include_str!("./sql/006_external_links.down.sql"),
+        ),
+        Migration::new(
+            7,
+            "Add link metadata",
+            include_str!("./sql/007_link_metadata.up.sql"),
+            include_str!("./sql/007_link_metadata.down.sql"),
+        ),
+        Migration::new(
+            8,
+            "Add script hooks",
+            include_str!("./sql/008_script_hooks.up.sql"),
+            include_str!("./sql/008_script_hooks.down.sql"),
+        ),
+        Migration::new(
+            9,
+            "Add settings",
+            include_str!("./sql/009_settings.up.sql"),
+            include_str!("./sql/009_settings.down.sql"),
+        ),
+        Migration::new(
+            10,
+            "Add private notes",
+            include_str!("./sql/010_private_notes.up.sql"),
+            include_str!("./sql/010_private_notes.down.sql"),
+        ),
+        Migration::new(
+            11,
+            "Add workflow statuses",
+            include_str!("./sql/011_workflow_statuses.up.sql"),
+            include_str!("./sql/011_workflow_statuses.down.sql"),
+        ),
+        Migration::new(
+            12,
+            "Add day plans",
+            include_str!("./sql/012_day_plans.up.sql"),
+            include_str!("./sql/012_day_plans.down.sql"),
+        ),
+        Migration::new(
+            13,
+            "Add contexts",
+            include_str!("./sql/013_contexts.up.sql"),
+            include_str!("./sql/013_contexts.down.sql"),
+        ),
+        Migration::new(
+            14,
+            "Add people and task delegation",
+            include_str!("./sql/014_people.up.sql"),
+            include_str!("./sql/014_people.down.sql"),
+        ),
+        Migration::new(
+            15,
+            "Add attachments",
+            include_str!("./sql/015_attachments.up.sql"),
+            include_str!("./sql/015_attachments.down.sql"),
+        ),
+        Migration::new(
+            16,
+            "Add achievements",
+            include_str!("./sql/016_achievements.up.sql"),
+            include_str!("./sql/016_achievements.down.sql"),
+        ),
+        Migration::new(
+            17,
+            "Add project review cadence",
+            include_str!("./sql/017_project_reviews.up.sql"),
+            include_str!("./sql/017_project_reviews.down.sql"),
+        ),
+        Migration::new(
+            18,
+            "Add someday/maybe incubation for goals and projects",
+            include_str!("./sql/018_incubation.up.sql"),
+            include_str!("./sql/018_incubation.down.sql"),
+        ),
+        Migration::new(
+            19,
+            "Add task time estimates",
+            include_str!("./sql/019_task_estimates.up.sql"),
+            include_str!("./sql/019_task_estimates.down.sql"),
+        ),
+        Migration::new(
+            20,
+            "Add planned minutes to day plan items",
+            include_str!("./sql/020_day_plan_items.up.sql"),
+            include_str!("./sql/020_day_plan_items.down.sql"),
+        ),
+        Migration::new(
+            21,
+            "Add time entries",
+            include_str!("./sql/021_time_entries.up.sql"),
+            include_str!("./sql/021_time_entries.down.sql"),
+        ),
+        Migration::new(
+            22,
+            "Add holiday sets",
+            include_str!("./sql/022_holiday_sets.up.sql"),
+            include_str!("./sql/022_holiday_sets.down.sql"),
+        ),
+        Migration::new(
+            23,
+            "Add note tags and dates from frontmatter",
+            include_str!("./sql/023_note_metadata.up.sql"),
+            include_str!("./sql/023_note_metadata.down.sql"),
+        ),
+        Migration::new(
+            24,
+            "Add sort order to goals and projects",
+            include_str!("./sql/024_sort_order.up.sql"),
+            include_str!("./sql/024_sort_order.down.sql"),
+        ),
+        Migration::new(
+            25,
+            "Maintain updated_at with triggers",
+            include_str!("./sql/025_updated_at_triggers.up.sql"),
+            include_str!("./sql/025_updated_at_triggers.down.sql"),
+        ),
+        Migration::new(
+            26,
+            "Explicit delete behavior for every foreign key",
+            include_str!("./sql/026_explicit_delete_actions.up.sql"),
+            include_str!("./sql/026_explicit_delete_actions.down.sql"),
+        ),
+        Migration::new(
+            27,
+            "Add audit log",
+            include_str!("./sql/027_audit_log.up.sql"),
+            include_str!("./sql/027_audit_log.down.sql"),
+        ),
+        Migration::new(
+            28,
+            "Add actual minutes and recurrence rules to tasks and progress to goals and projects",
+            include_str!("./sql/028_effort_recurrence_progress.up.sql"),
+            include_str!("./sql/028_effort_recurrence_progress.down.sql"),
+        ),
+        Migration::new(
+            29,
+            "Add scheduled jobs",
+            include_str!("./sql/029_jobs.up.sql"),
+            include_str!("./sql/029_jobs.down.sql"),
+        ),
+        Migration::new(
+            30,
+            "Add personal and work contexts to life areas",
+            include_str!("./sql/030_life_area_contexts.up.sql"),
+            include_str!("./sql/030_life_area_contexts.down.sql"),
+        ),
+        Migration::new(
+            31,
+            "Add references extracted from task descriptions",
+            include_str!("./sql/031_task_references.up.sql"),
+            include_str!("./sql/031_task_references.down.sql"),
+        ),
+        Migration::new(
+            32,
+            "Add the weekly digest job",
+            include_str!("./sql/032_weekly_digest_job.up.sql"),
+            include_str!("./sql/032_weekly_digest_job.down.sql"),
+        ),
+        Migration::new(
+            33,
+            "Add review schedules for resurfacing notes",
+            include_str!("./sql/033_note_reviews.up.sql"),
+            include_str!("./sql/033_note_reviews.down.sql"),
+        ),
+        Migration::new(
+            34,
+            "Add colors and icons of goals and projects inherited from their life area",
+            include_str!("./sql/034_entity_appearance.up.sql"),
+            include_str!("./sql/034_entity_appearance.down.sql"),
+        ),
+        Migration::new(
+            35,
+            "Add pins of entities to the dashboard",
+            include_str!("./sql/035_pins.up.sql"),
+            include_str!("./sql/035_pins.down.sql"),
+        ),
+        Migration::new(
+            36,
+            "Add task dependencies and snoozes",
+            include_str!("./sql/036_task_blockers.up.sql"),
+            include_str!("./sql/036_task_blockers.down.sql"),
+        ),
+        Migration::new(
+            37,
+            "Add the due date history of tasks",
+            include_str!("./sql/037_due_date_history.up.sql"),
+            include_str!("./sql/037_due_date_history.down.sql"),
+        ),
+        Migration::new(
+            38,
+            "Add weekly time budgets of life areas",
+            include_str!("./sql/038_life_area_budgets.up.sql"),
+            include_str!("./sql/038_life_area_budgets.down.sql"),
+        ),
+        Migration::new(
+            39,
+            "Add saved view preferences of projects",
+            include_str!("./sql/039_view_preferences.up.sql"),
+            include_str!("./sql/039_view_preferences.down.sql"),
+        ),
+        Migration::new(
+            40,
+            "Add the daily workspace statistics history and its job",
+            include_str!("./sql/040_stats_history.up.sql"),
+            include_str!("./sql/040_stats_history.down.sql"),
+        ),
+        Migration::new(
+            41,
+            "Add the tasks and projects notes mention",
+            include_str!("./sql/041_note_mentions.up.sql"),
+            include_str!("./sql/041_note_mentions.down.sql"),
+        ),
+        Migration::new(
+            42,
+            "Add the exception dates of repeating tasks",
+            include_str!("./sql/042_recurrence_exceptions.up.sql"),
+            include_str!("./sql/042_recurrence_exceptions.down.sql"),
+        ),
+        Migration::new(
+            43,
+            "Add the full-text index of attachment content and its job",
+            include_str!("./sql/043_attachment_text.up.sql"),
+            include_str!("./sql/043_attachment_text.down.sql"),
+        ),
+    ]
+}
\ No newline at end of file
@@ -1,8 +1,10 @@
 pub mod all;
-pub mod commands;
 
+use super::backup;
+use crate::{log_info, log_warn};
 use anyhow::Result;
-use sqlx::{migrate::MigrateDatabase, Sqlite, SqlitePool};
+use sqlx::{migrate::MigrateDatabase, Connection, Sqlite, SqliteConnection, SqlitePool};
+use std::path::PathBuf;
 
 pub struct Migration {
     pub version: i64,
@@ -48,14 +50,59 @@ impl MigrationRunner {
         Ok(())
     }
 
-    pub async fn migrate(&self, migrations: &[Migration]) -> Result<()> {
+    /// Applies the pending migrations; returns the path of the backup taken
+    /// first, when an existing database had any pending
+    pub async fn migrate(&self, migrations: &[Migration]) -> Result<Option<PathBuf>> {
         self.init().await?;
 
-        let mut tx = self.pool.begin().await?;
+        let applied = self.get_applied_migrations().await?;
+        let has_pending = migrations.iter().any(|m| !applied.contains(&m.version));
+        let backup = if has_pending && !applied.is_empty() {
+            backup::snapshot(&self.pool, "migrate").await?
+        } else {
+            None
+        };
+
+        // Rebuilding a table means dropping it, which must not cascade, and
+        // PRAGMA foreign_keys is a no-op inside a transaction, so enforcement
+        // is turned off around the whole run. The connection is detached and
+        // closed afterwards, so none with enforcement off returns to the pool
+        // whatever way the run ends.
+        let mut conn = self.pool.acquire().await?.detach();
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut conn)
+            .await?;
+
+        let result = self.apply(&mut conn, migrations).await;
+
+        if let Err(e) = conn.close().await {
+            log_warn!(&format!("Failed to close the migration connection: {}", e));
+        }
+        result.map(|()| backup)
+    }
+
+    /// Whether any of the migrations hasn't been applied yet; reads only, so
+    /// the migrations table isn't created when it is missing
+    pub async fn has_pending(&self, migrations: &[Migration]) -> Result<bool> {
+        let tracked: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_migrations'"
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if tracked == 0 {
+            return Ok(!migrations.is_empty());
+        }
+
+        let applied = self.get_applied_migrations().await?;
+        Ok(migrations.iter().any(|m| !applied.contains(&m.version)))
+    }
+
+    async fn apply(&self, conn: &mut SqliteConnection, migrations: &[Migration]) -> Result<()> {
+        let mut tx = conn.begin().await?;
 
         for migration in migrations {
             if !self.is_applied(migration.version).await? {
-                println!("Applying migration {}: {}", migration.version, migration.description);
+                log_info!(&format!("Applying migration {}: {}", migration.version, migration.description));
                 
                 sqlx::query(&migration.up)
                     .execute(&mut *tx)
@@ -88,7 +135,7 @@ impl MigrationRunner {
                 break;
             }
             
-            println!("Rolling back migration {}", version);
+            log_info!(&format!("Rolling back migration {}", version));
             
             sqlx::query("DELETE FROM _migrations WHERE version = ?")
                 .bind(version)
@@ -142,7 +189,7 @@ impl MigrationRunner {
 
 pub async fn ensure_database_exists(database_url: &str) -> Result<()> {
     if !Sqlite::database_exists(database_url).await? {
-        println!("Creating database: {}", database_url);
+        log_info!("Creating database", database_url);
         Sqlite::create_database(database_url).await?;
     }
     Ok(())
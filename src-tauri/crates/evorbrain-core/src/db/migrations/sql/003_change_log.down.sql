@@ -0,0 +1,26 @@
+-- Drop change tracking triggers
+DROP TRIGGER IF EXISTS trg_life_areas_log_insert;
+DROP TRIGGER IF EXISTS trg_life_areas_log_update;
+DROP TRIGGER IF EXISTS trg_life_areas_log_delete;
+DROP TRIGGER IF EXISTS trg_goals_log_insert;
+DROP TRIGGER IF EXISTS trg_goals_log_update;
+DROP TRIGGER IF EXISTS trg_goals_log_delete;
+DROP TRIGGER IF EXISTS trg_projects_log_insert;
+DROP TRIGGER IF EXISTS trg_projects_log_update;
+DROP TRIGGER IF EXISTS trg_projects_log_delete;
+DROP TRIGGER IF EXISTS trg_tasks_log_insert;
+DROP TRIGGER IF EXISTS trg_tasks_log_update;
+DROP TRIGGER IF EXISTS trg_tasks_log_delete;
+DROP TRIGGER IF EXISTS trg_notes_log_insert;
+DROP TRIGGER IF EXISTS trg_notes_log_update;
+DROP TRIGGER IF EXISTS trg_notes_log_delete;
+DROP TRIGGER IF EXISTS trg_tags_log_insert;
+DROP TRIGGER IF EXISTS trg_tags_log_update;
+DROP TRIGGER IF EXISTS trg_tags_log_delete;
+DROP TRIGGER IF EXISTS trg_task_tags_log_insert;
+DROP TRIGGER IF EXISTS trg_task_tags_log_delete;
+DROP TRIGGER IF EXISTS trg_project_tags_log_insert;
+DROP TRIGGER IF EXISTS trg_project_tags_log_delete;
+
+DROP INDEX IF EXISTS idx_change_log_entity;
+DROP TABLE IF EXISTS change_log;
\ No newline at end of file
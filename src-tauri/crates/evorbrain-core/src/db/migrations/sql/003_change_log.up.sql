@@ -0,0 +1,159 @@
+-- Append-only log of entity mutations. The sequence number gives clients a
+-- cursor for incremental sync via get_changes_since.
+CREATE TABLE change_log (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    operation TEXT NOT NULL CHECK (operation IN ('insert', 'update', 'delete', 'archive', 'restore', 'complete', 'uncomplete')),
+    changed_at TIMESTAMP NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX idx_change_log_entity ON change_log(entity_type, entity_id);
+
+-- Life areas
+CREATE TRIGGER trg_life_areas_log_insert AFTER INSERT ON life_areas
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('life_area', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_life_areas_log_update AFTER UPDATE ON life_areas
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('life_area', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        ELSE 'update'
+    END);
+END;
+
+CREATE TRIGGER trg_life_areas_log_delete AFTER DELETE ON life_areas
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('life_area', OLD.id, 'delete');
+END;
+
+-- Goals
+CREATE TRIGGER trg_goals_log_insert AFTER INSERT ON goals
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('goal', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_goals_log_update AFTER UPDATE ON goals
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('goal', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+CREATE TRIGGER trg_goals_log_delete AFTER DELETE ON goals
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('goal', OLD.id, 'delete');
+END;
+
+-- Projects
+CREATE TRIGGER trg_projects_log_insert AFTER INSERT ON projects
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('project', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_projects_log_update AFTER UPDATE ON projects
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('project', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+CREATE TRIGGER trg_projects_log_delete AFTER DELETE ON projects
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('project', OLD.id, 'delete');
+END;
+
+-- Tasks
+CREATE TRIGGER trg_tasks_log_insert AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('task', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_tasks_log_update AFTER UPDATE ON tasks
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('task', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+CREATE TRIGGER trg_tasks_log_delete AFTER DELETE ON tasks
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('task', OLD.id, 'delete');
+END;
+
+-- Notes
+CREATE TRIGGER trg_notes_log_insert AFTER INSERT ON notes
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('note', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_notes_log_update AFTER UPDATE ON notes
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('note', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        ELSE 'update'
+    END);
+END;
+
+CREATE TRIGGER trg_notes_log_delete AFTER DELETE ON notes
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('note', OLD.id, 'delete');
+END;
+
+-- Tags
+CREATE TRIGGER trg_tags_log_insert AFTER INSERT ON tags
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('tag', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_tags_log_update AFTER UPDATE ON tags
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('tag', NEW.id, 'update');
+END;
+
+CREATE TRIGGER trg_tags_log_delete AFTER DELETE ON tags
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('tag', OLD.id, 'delete');
+END;
+
+-- Tag assignments are reported as updates of the tagged entity
+CREATE TRIGGER trg_task_tags_log_insert AFTER INSERT ON task_tags
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('task', NEW.task_id, 'update');
+END;
+
+CREATE TRIGGER trg_task_tags_log_delete AFTER DELETE ON task_tags
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('task', OLD.task_id, 'update');
+END;
+
+CREATE TRIGGER trg_project_tags_log_insert AFTER INSERT ON project_tags
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('project', NEW.project_id, 'update');
+END;
+
+CREATE TRIGGER trg_project_tags_log_delete AFTER DELETE ON project_tags
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('project', OLD.project_id, 'update');
+END;
\ No newline at end of file
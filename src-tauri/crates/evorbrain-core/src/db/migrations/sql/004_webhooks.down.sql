@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_webhooks_is_active;
+DROP TABLE IF EXISTS webhooks;
@@ -0,0 +1,18 @@
+-- Outgoing webhooks notified when matching events occur
+CREATE TABLE webhooks (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    -- Shared secret used to HMAC-sign every delivery
+    secret TEXT NOT NULL,
+    -- JSON array of event filters, e.g. ["task.completed", "goal.*"]
+    events TEXT NOT NULL DEFAULT '["*"]',
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    last_delivery_at TIMESTAMP,
+    last_status_code INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_webhooks_is_active ON webhooks(is_active);
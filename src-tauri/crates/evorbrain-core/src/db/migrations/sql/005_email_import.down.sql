@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS imported_emails;
+DROP TABLE IF EXISTS email_accounts;
@@ -0,0 +1,29 @@
+-- IMAP accounts polled for emails to turn into inbox tasks
+CREATE TABLE email_accounts (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    host TEXT NOT NULL,
+    port INTEGER NOT NULL DEFAULT 993,
+    username TEXT NOT NULL,
+    password TEXT NOT NULL,
+    folder TEXT NOT NULL DEFAULT 'INBOX',
+    -- IMAP SEARCH criteria selecting the emails to import
+    search_query TEXT NOT NULL DEFAULT 'FLAGGED',
+    poll_interval_minutes INTEGER NOT NULL DEFAULT 15 CHECK (poll_interval_minutes > 0),
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    last_polled_at TIMESTAMP,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Emails already converted to tasks, keyed by Message-ID for de-duplication
+CREATE TABLE imported_emails (
+    account_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    task_id TEXT,
+    imported_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (account_id, message_id),
+    FOREIGN KEY (account_id) REFERENCES email_accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_external_links_provider;
+DROP INDEX IF EXISTS idx_external_links_entity;
+DROP TABLE IF EXISTS external_links;
@@ -0,0 +1,23 @@
+-- Links from entities to external resources (GitHub issues, web pages, ...)
+CREATE TABLE external_links (
+    id TEXT PRIMARY KEY NOT NULL,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('life_area', 'goal', 'project', 'task', 'note')),
+    entity_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    provider TEXT NOT NULL DEFAULT 'web',
+    -- Provider-specific identifier, e.g. 'owner/repo#123' for GitHub
+    external_id TEXT,
+    title TEXT,
+    -- Remote state as last synced, e.g. 'open', 'closed' or 'merged'
+    state TEXT,
+    -- Complete the linked task when the remote item closes
+    complete_on_close BOOLEAN NOT NULL DEFAULT 0,
+    last_synced_at TIMESTAMP,
+    sync_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (entity_type, entity_id, url)
+);
+
+CREATE INDEX idx_external_links_entity ON external_links(entity_type, entity_id);
+CREATE INDEX idx_external_links_provider ON external_links(provider);
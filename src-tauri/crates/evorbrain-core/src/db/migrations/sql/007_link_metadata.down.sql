@@ -0,0 +1,2 @@
+ALTER TABLE external_links DROP COLUMN favicon;
+ALTER TABLE external_links DROP COLUMN description;
@@ -0,0 +1,4 @@
+-- Page metadata fetched when a web link is saved
+ALTER TABLE external_links ADD COLUMN description TEXT;
+-- Favicon as a data: URI, so it renders offline and without remote requests
+ALTER TABLE external_links ADD COLUMN favicon TEXT;
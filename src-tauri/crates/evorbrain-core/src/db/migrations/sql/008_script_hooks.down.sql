@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_script_hooks_is_active;
+DROP TABLE IF EXISTS script_hooks;
@@ -0,0 +1,15 @@
+-- User scripts (Rhai) run when matching events occur
+CREATE TABLE script_hooks (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    -- JSON array of event filters, e.g. ["task.completed", "note.*"]
+    events TEXT NOT NULL DEFAULT '["*"]',
+    script TEXT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    last_run_at TIMESTAMP,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_script_hooks_is_active ON script_hooks(is_active);
@@ -0,0 +1,7 @@
+-- Application settings. Only values changed from their defaults are stored;
+-- each value is JSON so it can be decoded into its typed field.
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- Private notes store their content encrypted (see ENCRYPTED_CONTENT_PREFIX)
+ALTER TABLE notes ADD COLUMN is_private INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS trg_tasks_status_update;
+DROP TRIGGER IF EXISTS trg_tasks_status_insert;
+DROP VIEW IF EXISTS effective_workflow_statuses;
+DROP INDEX IF EXISTS idx_tasks_status;
+ALTER TABLE tasks DROP COLUMN status;
+DROP TABLE IF EXISTS workflow_statuses;
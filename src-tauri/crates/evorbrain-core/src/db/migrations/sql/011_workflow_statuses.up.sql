@@ -0,0 +1,70 @@
+-- Per-project task workflows. Projects without rows here use the default
+-- todo -> in_progress -> done workflow.
+CREATE TABLE workflow_statuses (
+    project_id TEXT NOT NULL,
+    -- Stable identifier stored in tasks.status, e.g. "in_review"
+    key TEXT NOT NULL,
+    name TEXT NOT NULL,
+    -- Tasks in a 'done' status are completed
+    category TEXT NOT NULL CHECK (category IN ('todo', 'in_progress', 'done')),
+    position INTEGER NOT NULL,
+    -- JSON array of keys tasks may move to from this status; NULL allows any
+    next_statuses TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, key),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+ALTER TABLE tasks ADD COLUMN status TEXT NOT NULL DEFAULT 'todo';
+UPDATE tasks SET status = 'done' WHERE completed_at IS NOT NULL;
+
+CREATE INDEX idx_tasks_status ON tasks(status);
+
+-- Statuses in effect for every project (and for tasks without a project)
+CREATE VIEW effective_workflow_statuses AS
+SELECT project_id, key, category, position FROM workflow_statuses
+UNION ALL
+SELECT p.id, d.key, d.category, d.position
+FROM (SELECT id FROM projects UNION ALL SELECT NULL) p
+CROSS JOIN (
+    SELECT 'todo' AS key, 'todo' AS category, 0 AS position
+    UNION ALL SELECT 'in_progress', 'in_progress', 1
+    UNION ALL SELECT 'done', 'done', 2
+) d
+WHERE NOT EXISTS (SELECT 1 FROM workflow_statuses w WHERE w.project_id IS p.id);
+
+-- Keep every task's status valid for its project's workflow and in line with
+-- completed_at: tasks created, moved to another project, completed or
+-- reopened without a matching status get the first status of the right
+-- category.
+CREATE TRIGGER trg_tasks_status_insert AFTER INSERT ON tasks
+WHEN NOT EXISTS (
+    SELECT 1 FROM effective_workflow_statuses s
+    WHERE s.project_id IS NEW.project_id AND s.key = NEW.status
+      AND (s.category = 'done') = (NEW.completed_at IS NOT NULL)
+)
+BEGIN
+    UPDATE tasks SET status = (
+        SELECT key FROM effective_workflow_statuses s
+        WHERE s.project_id IS NEW.project_id
+          AND s.category = CASE WHEN NEW.completed_at IS NULL THEN 'todo' ELSE 'done' END
+        ORDER BY s.position LIMIT 1
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_tasks_status_update AFTER UPDATE OF project_id, completed_at ON tasks
+WHEN NOT EXISTS (
+    SELECT 1 FROM effective_workflow_statuses s
+    WHERE s.project_id IS NEW.project_id AND s.key = NEW.status
+      AND (s.category = 'done') = (NEW.completed_at IS NOT NULL)
+)
+BEGIN
+    UPDATE tasks SET status = (
+        SELECT key FROM effective_workflow_statuses s
+        WHERE s.project_id IS NEW.project_id
+          AND s.category = CASE WHEN NEW.completed_at IS NULL THEN 'todo' ELSE 'done' END
+        ORDER BY s.position LIMIT 1
+    )
+    WHERE id = NEW.id;
+END;
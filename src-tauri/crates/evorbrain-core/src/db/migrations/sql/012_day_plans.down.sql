@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_day_plans_task_id;
+DROP TABLE IF EXISTS day_plans;
@@ -0,0 +1,13 @@
+-- Tasks the user chose to focus on for a given day ("My Day"). Each date
+-- has its own list, so the focus resets naturally when the day changes.
+CREATE TABLE day_plans (
+    -- Local date in the user's time zone, YYYY-MM-DD
+    plan_date TEXT NOT NULL,
+    task_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (plan_date, task_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_day_plans_task_id ON day_plans(task_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_task_contexts_context_id;
+DROP TABLE IF EXISTS task_contexts;
+DROP TABLE IF EXISTS contexts;
@@ -0,0 +1,18 @@
+-- GTD contexts (@home, @computer, @errands): where or with what a task can
+-- be done. Kept apart from tags so context lists stay short and focused.
+CREATE TABLE contexts (
+    id TEXT PRIMARY KEY NOT NULL,
+    -- Stored without the leading '@', lowercase
+    name TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE task_contexts (
+    task_id TEXT NOT NULL,
+    context_id TEXT NOT NULL,
+    PRIMARY KEY (task_id, context_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (context_id) REFERENCES contexts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_contexts_context_id ON task_contexts(context_id);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_task_delegations_follow_up_at;
+DROP INDEX IF EXISTS idx_task_delegations_person_id;
+DROP TABLE IF EXISTS task_delegations;
+DROP TABLE IF EXISTS people;
@@ -0,0 +1,23 @@
+-- People tasks can be delegated to
+CREATE TABLE people (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    email TEXT,
+    notes TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A delegated task is "waiting for" its person until it is completed
+CREATE TABLE task_delegations (
+    task_id TEXT PRIMARY KEY NOT NULL,
+    person_id TEXT NOT NULL,
+    -- When to check in on the task; NULL means no follow-up planned
+    follow_up_at TIMESTAMP,
+    delegated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (person_id) REFERENCES people(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_delegations_person_id ON task_delegations(person_id);
+CREATE INDEX idx_task_delegations_follow_up_at ON task_delegations(follow_up_at);
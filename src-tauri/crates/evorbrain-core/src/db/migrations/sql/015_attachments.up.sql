@@ -0,0 +1,17 @@
+-- Files attached to entities. Contents are stored outside the database,
+-- content-addressed by SHA-256, so identical files share one copy on disk.
+CREATE TABLE attachments (
+    id TEXT PRIMARY KEY NOT NULL,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('life_area', 'goal', 'project', 'task', 'note')),
+    entity_id TEXT NOT NULL,
+    -- Original file name, shown to the user
+    file_name TEXT NOT NULL,
+    mime_type TEXT,
+    size_bytes INTEGER NOT NULL,
+    -- Lowercase hex SHA-256 of the content; also the stored file's name
+    content_hash TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_attachments_entity ON attachments(entity_type, entity_id);
+CREATE INDEX idx_attachments_content_hash ON attachments(content_hash);
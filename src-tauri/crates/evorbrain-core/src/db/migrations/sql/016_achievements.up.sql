@@ -0,0 +1,5 @@
+-- Unlocked achievements; the catalog itself lives in code, keyed by `key`
+CREATE TABLE achievements (
+    key TEXT PRIMARY KEY NOT NULL,
+    unlocked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN last_reviewed_at;
+ALTER TABLE projects DROP COLUMN review_interval_days;
@@ -0,0 +1,3 @@
+-- Per-project review cadence; NULL falls back to the review_interval_days setting
+ALTER TABLE projects ADD COLUMN review_interval_days INTEGER CHECK (review_interval_days > 0);
+ALTER TABLE projects ADD COLUMN last_reviewed_at TIMESTAMP;
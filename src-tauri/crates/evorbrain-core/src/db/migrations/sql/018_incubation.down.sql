@@ -0,0 +1,3 @@
+DROP VIEW IF EXISTS incubated_projects;
+ALTER TABLE projects DROP COLUMN incubated_at;
+ALTER TABLE goals DROP COLUMN incubated_at;
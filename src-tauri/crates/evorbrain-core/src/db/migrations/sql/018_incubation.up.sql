@@ -0,0 +1,13 @@
+-- Someday/maybe: parked goals and projects stay visible in their own list
+-- but drop out of the active views. Unlike archiving this is not a cleanup
+-- state, so retention never deletes incubated items.
+ALTER TABLE goals ADD COLUMN incubated_at TIMESTAMP;
+ALTER TABLE projects ADD COLUMN incubated_at TIMESTAMP;
+
+-- Projects that are parked themselves or through their goal; their tasks
+-- are left out of dashboards
+CREATE VIEW incubated_projects AS
+SELECT p.id
+FROM projects p
+JOIN goals g ON g.id = p.goal_id
+WHERE p.incubated_at IS NOT NULL OR g.incubated_at IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Estimated effort, used to compare a day's planned work with working hours
+ALTER TABLE tasks ADD COLUMN estimated_minutes INTEGER CHECK (estimated_minutes >= 0);
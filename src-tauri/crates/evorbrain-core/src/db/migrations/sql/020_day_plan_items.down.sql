@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_day_plan_items_task_id;
+ALTER TABLE day_plan_items DROP COLUMN planned_minutes;
+ALTER TABLE day_plan_items RENAME TO day_plans;
+CREATE INDEX idx_day_plans_task_id ON day_plans(task_id);
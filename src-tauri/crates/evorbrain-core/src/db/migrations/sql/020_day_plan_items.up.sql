@@ -0,0 +1,7 @@
+-- Day plans become timeboxed: each item can carry the minutes planned for
+-- it, overriding the task's own estimate for that day
+ALTER TABLE day_plans RENAME TO day_plan_items;
+ALTER TABLE day_plan_items ADD COLUMN planned_minutes INTEGER CHECK (planned_minutes >= 0);
+
+DROP INDEX IF EXISTS idx_day_plans_task_id;
+CREATE INDEX idx_day_plan_items_task_id ON day_plan_items(task_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_time_entries_running;
+DROP INDEX IF EXISTS idx_time_entries_task_id;
+DROP TABLE IF EXISTS time_entries;
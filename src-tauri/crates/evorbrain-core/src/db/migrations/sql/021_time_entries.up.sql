@@ -0,0 +1,15 @@
+-- Time tracked on tasks; at most one entry is running (ended_at IS NULL).
+-- An entry stopped because the machine went idle or asleep ends when the
+-- idle gap began and records the gap until it is reconciled
+CREATE TABLE time_entries (
+    id TEXT PRIMARY KEY NOT NULL,
+    task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    started_at TIMESTAMP NOT NULL,
+    ended_at TIMESTAMP,
+    idle_started_at TIMESTAMP,
+    idle_ended_at TIMESTAMP,
+    reconciled_at TIMESTAMP
+);
+
+CREATE INDEX idx_time_entries_task_id ON time_entries(task_id);
+CREATE UNIQUE INDEX idx_time_entries_running ON time_entries((1)) WHERE ended_at IS NULL;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_holidays_date;
+DROP TABLE IF EXISTS holidays;
+DROP TABLE IF EXISTS holiday_sets;
@@ -0,0 +1,18 @@
+-- Named sets of holidays, e.g. an imported national calendar; holidays of
+-- enabled sets are non-working days on top of the `holidays` setting
+CREATE TABLE holiday_sets (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE holidays (
+    set_id TEXT NOT NULL REFERENCES holiday_sets(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (set_id, date)
+);
+
+CREATE INDEX idx_holidays_date ON holidays(date);
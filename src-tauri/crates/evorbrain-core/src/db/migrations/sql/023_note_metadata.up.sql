@@ -0,0 +1,12 @@
+-- Structured metadata kept in sync with a note's YAML frontmatter
+ALTER TABLE notes ADD COLUMN note_date DATE;
+
+CREATE TABLE note_tags (
+    note_id TEXT NOT NULL,
+    tag_id TEXT NOT NULL,
+    PRIMARY KEY (note_id, tag_id),
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_note_tags_tag_id ON note_tags(tag_id);
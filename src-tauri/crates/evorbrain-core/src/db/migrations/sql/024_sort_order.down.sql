@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS trg_projects_sort_order_move;
+DROP TRIGGER IF EXISTS trg_projects_sort_order_insert;
+DROP TRIGGER IF EXISTS trg_goals_sort_order_move;
+DROP TRIGGER IF EXISTS trg_goals_sort_order_insert;
+DROP INDEX IF EXISTS idx_projects_sort_order;
+DROP INDEX IF EXISTS idx_goals_sort_order;
+ALTER TABLE projects DROP COLUMN sort_order;
+ALTER TABLE goals DROP COLUMN sort_order;
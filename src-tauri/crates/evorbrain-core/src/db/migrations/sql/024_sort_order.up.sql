@@ -0,0 +1,58 @@
+-- User-defined order of goals within their life area and projects within
+-- their goal. Existing rows keep their creation order.
+ALTER TABLE goals ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE projects ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+
+UPDATE goals SET sort_order = (
+    SELECT COUNT(*) FROM goals g
+    WHERE g.life_area_id = goals.life_area_id
+      AND (g.created_at < goals.created_at OR (g.created_at = goals.created_at AND g.id < goals.id))
+);
+UPDATE projects SET sort_order = (
+    SELECT COUNT(*) FROM projects p
+    WHERE p.goal_id = projects.goal_id
+      AND (p.created_at < projects.created_at OR (p.created_at = projects.created_at AND p.id < projects.id))
+);
+
+CREATE INDEX idx_goals_sort_order ON goals(life_area_id, sort_order);
+CREATE INDEX idx_projects_sort_order ON projects(goal_id, sort_order);
+
+-- New goals and projects, and ones moved to another parent, go after their
+-- new siblings, so inserts never reshuffle the existing order.
+CREATE TRIGGER trg_goals_sort_order_insert AFTER INSERT ON goals
+BEGIN
+    UPDATE goals SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM goals
+        WHERE life_area_id = NEW.life_area_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_goals_sort_order_move AFTER UPDATE OF life_area_id ON goals
+WHEN NEW.life_area_id != OLD.life_area_id
+BEGIN
+    UPDATE goals SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM goals
+        WHERE life_area_id = NEW.life_area_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_projects_sort_order_insert AFTER INSERT ON projects
+BEGIN
+    UPDATE projects SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM projects
+        WHERE goal_id = NEW.goal_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_projects_sort_order_move AFTER UPDATE OF goal_id ON projects
+WHEN NEW.goal_id != OLD.goal_id
+BEGIN
+    UPDATE projects SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM projects
+        WHERE goal_id = NEW.goal_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
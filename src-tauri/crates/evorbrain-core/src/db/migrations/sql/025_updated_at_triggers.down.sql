@@ -0,0 +1,82 @@
+DROP TRIGGER IF EXISTS trg_life_areas_log_update;
+CREATE TRIGGER trg_life_areas_log_update AFTER UPDATE ON life_areas
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('life_area', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER IF EXISTS trg_goals_log_update;
+CREATE TRIGGER trg_goals_log_update AFTER UPDATE ON goals
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('goal', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER IF EXISTS trg_projects_log_update;
+CREATE TRIGGER trg_projects_log_update AFTER UPDATE ON projects
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('project', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER IF EXISTS trg_tasks_log_update;
+CREATE TRIGGER trg_tasks_log_update AFTER UPDATE ON tasks
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('task', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER IF EXISTS trg_notes_log_update;
+CREATE TRIGGER trg_notes_log_update AFTER UPDATE ON notes
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('note', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER IF EXISTS trg_external_links_touch;
+
+DROP TRIGGER IF EXISTS trg_email_accounts_touch;
+
+DROP TRIGGER IF EXISTS trg_script_hooks_touch;
+
+DROP TRIGGER IF EXISTS trg_webhooks_touch;
+
+DROP TRIGGER IF EXISTS trg_holiday_sets_touch;
+
+DROP TRIGGER IF EXISTS trg_people_touch;
+
+DROP TRIGGER IF EXISTS trg_notes_touch;
+
+DROP TRIGGER IF EXISTS trg_tasks_touch;
+
+DROP TRIGGER IF EXISTS trg_projects_touch;
+
+DROP TRIGGER IF EXISTS trg_goals_touch;
+
+DROP TRIGGER IF EXISTS trg_life_areas_touch;
@@ -0,0 +1,138 @@
+-- Keep updated_at current on every update, including ad-hoc writes that
+-- don't set it. Statements that set updated_at themselves are left alone;
+-- for the rest a follow-up update stamps the row with the current time.
+
+CREATE TRIGGER trg_life_areas_touch AFTER UPDATE ON life_areas
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE life_areas SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_goals_touch AFTER UPDATE ON goals
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE goals SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_projects_touch AFTER UPDATE ON projects
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE projects SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_tasks_touch AFTER UPDATE ON tasks
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE tasks SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_notes_touch AFTER UPDATE ON notes
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE notes SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_people_touch AFTER UPDATE ON people
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE people SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_holiday_sets_touch AFTER UPDATE ON holiday_sets
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE holiday_sets SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_webhooks_touch AFTER UPDATE ON webhooks
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE webhooks SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_script_hooks_touch AFTER UPDATE ON script_hooks
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE script_hooks SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_email_accounts_touch AFTER UPDATE ON email_accounts
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE email_accounts SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_external_links_touch AFTER UPDATE ON external_links
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE external_links SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+-- The stamping update isn't a change of its own: the change log records
+-- the update that caused it, so skip rows whose only change is the stamp.
+
+DROP TRIGGER trg_life_areas_log_update;
+CREATE TRIGGER trg_life_areas_log_update AFTER UPDATE ON life_areas
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('life_area', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER trg_goals_log_update;
+CREATE TRIGGER trg_goals_log_update AFTER UPDATE ON goals
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('goal', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER trg_projects_log_update;
+CREATE TRIGGER trg_projects_log_update AFTER UPDATE ON projects
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('project', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER trg_tasks_log_update;
+CREATE TRIGGER trg_tasks_log_update AFTER UPDATE ON tasks
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('task', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+DROP TRIGGER trg_notes_log_update;
+CREATE TRIGGER trg_notes_log_update AFTER UPDATE ON notes
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('note', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        ELSE 'update'
+    END);
+END;
@@ -0,0 +1,298 @@
+-- Back to cascading deletes from every parent
+
+DROP VIEW effective_workflow_statuses;
+DROP VIEW incubated_projects;
+
+CREATE TABLE goals_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    life_area_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    target_date TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP,
+    archived_at TIMESTAMP, incubated_at TIMESTAMP, sort_order INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (life_area_id) REFERENCES life_areas(id) ON DELETE CASCADE
+);
+INSERT INTO goals_new SELECT * FROM goals;
+
+CREATE TABLE projects_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    goal_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    status TEXT NOT NULL CHECK (status IN ('planning', 'active', 'onhold', 'completed', 'cancelled')),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP,
+    archived_at TIMESTAMP, review_interval_days INTEGER CHECK (review_interval_days > 0), last_reviewed_at TIMESTAMP, incubated_at TIMESTAMP, sort_order INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE
+);
+INSERT INTO projects_new SELECT * FROM projects;
+
+CREATE TABLE tasks_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    project_id TEXT,
+    parent_task_id TEXT,
+    title TEXT NOT NULL,
+    description TEXT,
+    priority TEXT NOT NULL CHECK (priority IN ('low', 'medium', 'high', 'urgent')),
+    due_date TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP,
+    archived_at TIMESTAMP, status TEXT NOT NULL DEFAULT 'todo', estimated_minutes INTEGER CHECK (estimated_minutes >= 0),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+INSERT INTO tasks_new SELECT * FROM tasks;
+
+CREATE TABLE notes_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    task_id TEXT,
+    project_id TEXT,
+    goal_id TEXT,
+    life_area_id TEXT,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    archived_at TIMESTAMP, is_private INTEGER NOT NULL DEFAULT 0, note_date DATE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (life_area_id) REFERENCES life_areas(id) ON DELETE CASCADE
+);
+INSERT INTO notes_new SELECT * FROM notes;
+
+DROP TABLE notes;
+DROP TABLE tasks;
+DROP TABLE projects;
+DROP TABLE goals;
+
+ALTER TABLE goals_new RENAME TO goals;
+ALTER TABLE projects_new RENAME TO projects;
+ALTER TABLE tasks_new RENAME TO tasks;
+ALTER TABLE notes_new RENAME TO notes;
+
+CREATE VIEW effective_workflow_statuses AS
+SELECT project_id, key, category, position FROM workflow_statuses
+UNION ALL
+SELECT p.id, d.key, d.category, d.position
+FROM (SELECT id FROM projects UNION ALL SELECT NULL) p
+CROSS JOIN (
+    SELECT 'todo' AS key, 'todo' AS category, 0 AS position
+    UNION ALL SELECT 'in_progress', 'in_progress', 1
+    UNION ALL SELECT 'done', 'done', 2
+) d
+WHERE NOT EXISTS (SELECT 1 FROM workflow_statuses w WHERE w.project_id IS p.id);
+
+CREATE VIEW incubated_projects AS
+SELECT p.id
+FROM projects p
+JOIN goals g ON g.id = p.goal_id
+WHERE p.incubated_at IS NOT NULL OR g.incubated_at IS NOT NULL;
+
+CREATE INDEX idx_goals_life_area_id ON goals(life_area_id);
+CREATE INDEX idx_goals_sort_order ON goals(life_area_id, sort_order);
+
+CREATE TRIGGER trg_goals_log_insert AFTER INSERT ON goals
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('goal', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_goals_log_delete AFTER DELETE ON goals
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('goal', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER trg_goals_sort_order_insert AFTER INSERT ON goals
+BEGIN
+    UPDATE goals SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM goals
+        WHERE life_area_id = NEW.life_area_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_goals_sort_order_move AFTER UPDATE OF life_area_id ON goals
+WHEN NEW.life_area_id != OLD.life_area_id
+BEGIN
+    UPDATE goals SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM goals
+        WHERE life_area_id = NEW.life_area_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_goals_touch AFTER UPDATE ON goals
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE goals SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_goals_log_update AFTER UPDATE ON goals
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('goal', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+CREATE INDEX idx_projects_goal_id ON projects(goal_id);
+CREATE INDEX idx_projects_status ON projects(status);
+CREATE INDEX idx_projects_sort_order ON projects(goal_id, sort_order);
+
+CREATE TRIGGER trg_projects_log_insert AFTER INSERT ON projects
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('project', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_projects_log_delete AFTER DELETE ON projects
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('project', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER trg_projects_sort_order_insert AFTER INSERT ON projects
+BEGIN
+    UPDATE projects SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM projects
+        WHERE goal_id = NEW.goal_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_projects_sort_order_move AFTER UPDATE OF goal_id ON projects
+WHEN NEW.goal_id != OLD.goal_id
+BEGIN
+    UPDATE projects SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), -1) + 1 FROM projects
+        WHERE goal_id = NEW.goal_id AND id != NEW.id
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_projects_touch AFTER UPDATE ON projects
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE projects SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_projects_log_update AFTER UPDATE ON projects
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('project', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+CREATE INDEX idx_tasks_project_id ON tasks(project_id);
+CREATE INDEX idx_tasks_parent_task_id ON tasks(parent_task_id);
+CREATE INDEX idx_tasks_priority ON tasks(priority);
+CREATE INDEX idx_tasks_due_date ON tasks(due_date);
+CREATE INDEX idx_tasks_completed_at ON tasks(completed_at);
+CREATE INDEX idx_tasks_status ON tasks(status);
+
+CREATE TRIGGER trg_tasks_log_insert AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('task', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_tasks_log_delete AFTER DELETE ON tasks
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('task', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER trg_tasks_status_insert AFTER INSERT ON tasks
+WHEN NOT EXISTS (
+    SELECT 1 FROM effective_workflow_statuses s
+    WHERE s.project_id IS NEW.project_id AND s.key = NEW.status
+      AND (s.category = 'done') = (NEW.completed_at IS NOT NULL)
+)
+BEGIN
+    UPDATE tasks SET status = (
+        SELECT key FROM effective_workflow_statuses s
+        WHERE s.project_id IS NEW.project_id
+          AND s.category = CASE WHEN NEW.completed_at IS NULL THEN 'todo' ELSE 'done' END
+        ORDER BY s.position LIMIT 1
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_tasks_status_update AFTER UPDATE OF project_id, completed_at ON tasks
+WHEN NOT EXISTS (
+    SELECT 1 FROM effective_workflow_statuses s
+    WHERE s.project_id IS NEW.project_id AND s.key = NEW.status
+      AND (s.category = 'done') = (NEW.completed_at IS NOT NULL)
+)
+BEGIN
+    UPDATE tasks SET status = (
+        SELECT key FROM effective_workflow_statuses s
+        WHERE s.project_id IS NEW.project_id
+          AND s.category = CASE WHEN NEW.completed_at IS NULL THEN 'todo' ELSE 'done' END
+        ORDER BY s.position LIMIT 1
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_tasks_touch AFTER UPDATE ON tasks
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE tasks SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_tasks_log_update AFTER UPDATE ON tasks
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('task', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        WHEN OLD.completed_at IS NULL AND NEW.completed_at IS NOT NULL THEN 'complete'
+        WHEN OLD.completed_at IS NOT NULL AND NEW.completed_at IS NULL THEN 'uncomplete'
+        ELSE 'update'
+    END);
+END;
+
+CREATE INDEX idx_notes_task_id ON notes(task_id);
+CREATE INDEX idx_notes_project_id ON notes(project_id);
+CREATE INDEX idx_notes_goal_id ON notes(goal_id);
+CREATE INDEX idx_notes_life_area_id ON notes(life_area_id);
+
+CREATE TRIGGER trg_notes_log_insert AFTER INSERT ON notes
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('note', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER trg_notes_log_delete AFTER DELETE ON notes
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation) VALUES ('note', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER trg_notes_touch AFTER UPDATE ON notes
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE notes SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_notes_log_update AFTER UPDATE ON notes
+WHEN NOT (NEW.updated_at IS NOT OLD.updated_at AND NEW.updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, operation)
+    VALUES ('note', NEW.id, CASE
+        WHEN OLD.archived_at IS NULL AND NEW.archived_at IS NOT NULL THEN 'archive'
+        WHEN OLD.archived_at IS NOT NULL AND NEW.archived_at IS NULL THEN 'restore'
+        ELSE 'update'
+    END);
+END;
@@ -0,0 +1,13 @@
+-- Record of user-level operations that change many rows at once (merges,
+-- repairs), kept with enough detail to see afterwards what was done.
+-- Unlike change_log it is written by the application, one row per operation.
+CREATE TABLE audit_log (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    details TEXT NOT NULL DEFAULT '{}',
+    recorded_at TIMESTAMP NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX idx_audit_log_entity ON audit_log(entity_type, entity_id);
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS trg_time_entries_actual_minutes;
+ALTER TABLE projects DROP COLUMN progress;
+ALTER TABLE goals DROP COLUMN progress;
+ALTER TABLE tasks DROP COLUMN recurrence_rule;
+ALTER TABLE tasks DROP COLUMN actual_minutes;
@@ -0,0 +1,39 @@
+-- Fields the validation layer already works with: time actually spent on a
+-- task, its recurrence rule, and a completion percentage for goals and
+-- projects.
+ALTER TABLE tasks ADD COLUMN actual_minutes INTEGER NOT NULL DEFAULT 0 CHECK (actual_minutes >= 0);
+ALTER TABLE tasks ADD COLUMN recurrence_rule TEXT;
+ALTER TABLE goals ADD COLUMN progress INTEGER NOT NULL DEFAULT 0 CHECK (progress BETWEEN 0 AND 100);
+ALTER TABLE projects ADD COLUMN progress INTEGER NOT NULL DEFAULT 0 CHECK (progress BETWEEN 0 AND 100);
+
+-- Time already tracked with timers counts towards the actual effort. Only
+-- tasks with finished timers change, and they keep their updated_at: the
+-- touch trigger stamps any update that leaves updated_at as it was, so it
+-- is set aside for the backfill.
+DROP TRIGGER trg_tasks_touch;
+
+UPDATE tasks SET
+    actual_minutes = (
+        SELECT COALESCE(SUM(CAST(ROUND((julianday(e.ended_at) - julianday(e.started_at)) * 1440) AS INTEGER)), 0)
+        FROM time_entries e
+        WHERE e.task_id = tasks.id AND e.ended_at IS NOT NULL
+    ),
+    updated_at = updated_at
+WHERE EXISTS (SELECT 1 FROM time_entries e WHERE e.task_id = tasks.id AND e.ended_at IS NOT NULL);
+
+CREATE TRIGGER trg_tasks_touch AFTER UPDATE ON tasks
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE tasks SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+-- Stopping a timer, or moving its end while reconciling idle time, adds the
+-- difference to the task; a hand-corrected total is kept and added to.
+CREATE TRIGGER trg_time_entries_actual_minutes AFTER UPDATE OF ended_at ON time_entries
+WHEN NEW.ended_at IS NOT OLD.ended_at
+BEGIN
+    UPDATE tasks SET actual_minutes = MAX(0, actual_minutes
+        + COALESCE(CAST(ROUND((julianday(NEW.ended_at) - julianday(NEW.started_at)) * 1440) AS INTEGER), 0)
+        - COALESCE(CAST(ROUND((julianday(OLD.ended_at) - julianday(OLD.started_at)) * 1440) AS INTEGER), 0))
+    WHERE id = NEW.task_id;
+END;
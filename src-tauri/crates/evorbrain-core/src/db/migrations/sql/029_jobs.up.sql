@@ -0,0 +1,34 @@
+-- Periodic background work, one row per job type. The scheduler runs an
+-- enabled job once its cron schedule (in the user's time zone) has come up
+-- since the last run.
+CREATE TABLE jobs (
+    job_type TEXT PRIMARY KEY NOT NULL,
+    schedule TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_run_at TIMESTAMP,
+    last_duration_ms INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TIMESTAMP NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- The intervals the background loops used so far
+INSERT INTO jobs (job_type, schedule) VALUES
+    ('archive_retention', '0 */6 * * *'),
+    ('email_import', '* * * * *'),
+    ('github_sync', '*/15 * * * *');
+
+-- The GitHub sync interval setting becomes the job's schedule
+UPDATE jobs
+SET schedule = (
+    SELECT CASE
+        WHEN CAST(value AS INTEGER) < 60 THEN '*/' || CAST(value AS INTEGER) || ' * * * *'
+        ELSE '0 */' || (CAST(value AS INTEGER) / 60) || ' * * *'
+    END
+    FROM settings
+    WHERE key = 'github_sync_interval_minutes'
+)
+WHERE job_type = 'github_sync'
+  AND EXISTS (SELECT 1 FROM settings WHERE key = 'github_sync_interval_minutes');
+
+DELETE FROM settings WHERE key = 'github_sync_interval_minutes';
@@ -0,0 +1,6 @@
+DROP VIEW IF EXISTS hidden_notes;
+DROP VIEW IF EXISTS hidden_projects;
+DROP VIEW IF EXISTS hidden_goals;
+DROP VIEW IF EXISTS hidden_life_areas;
+DELETE FROM settings WHERE key = 'active_context';
+ALTER TABLE life_areas DROP COLUMN context;
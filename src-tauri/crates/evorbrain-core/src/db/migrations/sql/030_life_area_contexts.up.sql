@@ -0,0 +1,30 @@
+-- Life areas can be marked personal or work. While the `active_context`
+-- setting names one of them, life areas marked with the other drop out of
+-- the lists and dashboards together with everything beneath them; unmarked
+-- life areas are always shown.
+ALTER TABLE life_areas ADD COLUMN context TEXT CHECK (context IN ('personal', 'work'));
+
+CREATE VIEW hidden_life_areas AS
+SELECT la.id
+FROM life_areas la
+JOIN settings s ON s.key = 'active_context'
+WHERE la.context IS NOT NULL AND la.context <> json_extract(s.value, '$');
+
+CREATE VIEW hidden_goals AS
+SELECT g.id
+FROM goals g
+WHERE g.life_area_id IN (SELECT id FROM hidden_life_areas);
+
+CREATE VIEW hidden_projects AS
+SELECT p.id
+FROM projects p
+WHERE p.goal_id IN (SELECT id FROM hidden_goals);
+
+CREATE VIEW hidden_notes AS
+SELECT n.id
+FROM notes n
+LEFT JOIN tasks t ON t.id = n.task_id
+WHERE n.life_area_id IN (SELECT id FROM hidden_life_areas)
+   OR n.goal_id IN (SELECT id FROM hidden_goals)
+   OR n.project_id IN (SELECT id FROM hidden_projects)
+   OR t.project_id IN (SELECT id FROM hidden_projects);
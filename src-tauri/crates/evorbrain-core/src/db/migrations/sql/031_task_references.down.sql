@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_task_references_entity;
+DROP TABLE IF EXISTS task_references;
+DELETE FROM external_links WHERE extracted = 1;
+ALTER TABLE external_links DROP COLUMN extracted;
@@ -0,0 +1,16 @@
+-- URLs and evorbrain:// references found in task descriptions. Extracted
+-- URLs are external links marked as such, so saving the task again can
+-- drop the ones no longer in the description without touching links the
+-- user added by hand.
+ALTER TABLE external_links ADD COLUMN extracted BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE TABLE task_references (
+    task_id TEXT NOT NULL,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('task', 'project', 'goal', 'note')),
+    entity_id TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (task_id, entity_type, entity_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_references_entity ON task_references(entity_type, entity_id);
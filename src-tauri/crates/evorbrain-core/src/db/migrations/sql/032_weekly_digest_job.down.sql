@@ -0,0 +1,2 @@
+DELETE FROM jobs WHERE job_type = 'weekly_digest';
+DELETE FROM settings WHERE key = 'digest_delivery';
@@ -0,0 +1,3 @@
+-- Composes the previous week's digest on Monday morning; off until the user
+-- turns it on
+INSERT INTO jobs (job_type, schedule, enabled) VALUES ('weekly_digest', '0 8 * * 1', 0);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_notes_next_review_at;
+ALTER TABLE notes DROP COLUMN last_reviewed_at;
+ALTER TABLE notes DROP COLUMN next_review_at;
+ALTER TABLE notes DROP COLUMN review_stage;
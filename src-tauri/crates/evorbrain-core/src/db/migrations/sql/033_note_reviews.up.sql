@@ -0,0 +1,8 @@
+-- Spaced repetition for notes: a scheduled note resurfaces after each of
+-- the review intervals in turn. review_stage counts the reviews done;
+-- next_review_at is NULL when the note isn't scheduled or is done.
+ALTER TABLE notes ADD COLUMN review_stage INTEGER CHECK (review_stage >= 0);
+ALTER TABLE notes ADD COLUMN next_review_at TIMESTAMP;
+ALTER TABLE notes ADD COLUMN last_reviewed_at TIMESTAMP;
+
+CREATE INDEX idx_notes_next_review_at ON notes(next_review_at) WHERE next_review_at IS NOT NULL;
@@ -0,0 +1,6 @@
+DROP VIEW IF EXISTS project_appearance;
+DROP VIEW IF EXISTS goal_appearance;
+ALTER TABLE projects DROP COLUMN icon;
+ALTER TABLE projects DROP COLUMN color;
+ALTER TABLE goals DROP COLUMN icon;
+ALTER TABLE goals DROP COLUMN color;
@@ -0,0 +1,21 @@
+-- Goals and projects can have their own color and icon. Where they don't,
+-- they show those of the level above, up to the life area; tasks show those
+-- of their project. The views resolve what is displayed.
+ALTER TABLE goals ADD COLUMN color TEXT;
+ALTER TABLE goals ADD COLUMN icon TEXT;
+ALTER TABLE projects ADD COLUMN color TEXT;
+ALTER TABLE projects ADD COLUMN icon TEXT;
+
+CREATE VIEW goal_appearance AS
+SELECT g.id,
+       COALESCE(g.color, la.color) AS display_color,
+       COALESCE(g.icon, la.icon) AS display_icon
+FROM goals g
+LEFT JOIN life_areas la ON la.id = g.life_area_id;
+
+CREATE VIEW project_appearance AS
+SELECT p.id,
+       COALESCE(p.color, ga.display_color) AS display_color,
+       COALESCE(p.icon, ga.display_icon) AS display_icon
+FROM projects p
+LEFT JOIN goal_appearance ga ON ga.id = p.goal_id;
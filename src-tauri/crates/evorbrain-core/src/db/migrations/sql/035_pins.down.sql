@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS trg_notes_unpin;
+DROP TRIGGER IF EXISTS trg_tasks_unpin;
+DROP TRIGGER IF EXISTS trg_projects_unpin;
+DROP TRIGGER IF EXISTS trg_goals_unpin;
+DROP TRIGGER IF EXISTS trg_life_areas_unpin;
+DROP TABLE IF EXISTS pins;
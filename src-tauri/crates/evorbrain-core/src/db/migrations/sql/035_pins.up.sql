@@ -0,0 +1,35 @@
+-- Entities pinned to the dashboard, in the order the user arranged them.
+-- A pin goes away with its entity; archived entities keep their pin but
+-- aren't shown until restored.
+CREATE TABLE pins (
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('life_area', 'goal', 'project', 'task', 'note')),
+    entity_id TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (entity_type, entity_id)
+);
+
+CREATE TRIGGER trg_life_areas_unpin AFTER DELETE ON life_areas
+BEGIN
+    DELETE FROM pins WHERE entity_type = 'life_area' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_goals_unpin AFTER DELETE ON goals
+BEGIN
+    DELETE FROM pins WHERE entity_type = 'goal' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_projects_unpin AFTER DELETE ON projects
+BEGIN
+    DELETE FROM pins WHERE entity_type = 'project' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_tasks_unpin AFTER DELETE ON tasks
+BEGIN
+    DELETE FROM pins WHERE entity_type = 'task' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_notes_unpin AFTER DELETE ON notes
+BEGIN
+    DELETE FROM pins WHERE entity_type = 'note' AND entity_id = OLD.id;
+END;
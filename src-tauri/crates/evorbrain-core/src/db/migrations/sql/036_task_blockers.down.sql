@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS task_snoozes;
+DROP INDEX IF EXISTS idx_task_dependencies_depends_on_id;
+DROP TABLE IF EXISTS task_dependencies;
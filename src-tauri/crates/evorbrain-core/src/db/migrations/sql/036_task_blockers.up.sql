@@ -0,0 +1,23 @@
+-- Tasks that can't be started before others are completed, and tasks put
+-- out of sight until later. While either holds, the task is left out of the
+-- Today view.
+CREATE TABLE task_dependencies (
+    task_id TEXT NOT NULL,
+    -- The task that has to be completed first
+    depends_on_id TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (task_id, depends_on_id),
+    CHECK (task_id <> depends_on_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_dependencies_depends_on_id ON task_dependencies(depends_on_id);
+
+-- A snooze past its time is simply no longer in effect
+CREATE TABLE task_snoozes (
+    task_id TEXT PRIMARY KEY NOT NULL,
+    snoozed_until TIMESTAMP NOT NULL,
+    snoozed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS trg_tasks_due_date_history;
+DROP INDEX IF EXISTS idx_task_due_date_changes_task_id;
+DROP TABLE IF EXISTS task_due_date_changes;
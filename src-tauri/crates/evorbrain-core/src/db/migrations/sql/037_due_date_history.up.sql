@@ -0,0 +1,20 @@
+-- Every change of a task's due date, to spot tasks that keep getting
+-- rescheduled. Giving a task its first due date is recorded as well but
+-- isn't a reschedule.
+CREATE TABLE task_due_date_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    old_due_date TIMESTAMP,
+    new_due_date TIMESTAMP,
+    changed_at TIMESTAMP NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_due_date_changes_task_id ON task_due_date_changes(task_id);
+
+CREATE TRIGGER trg_tasks_due_date_history AFTER UPDATE OF due_date ON tasks
+WHEN NEW.due_date IS NOT OLD.due_date
+BEGIN
+    INSERT INTO task_due_date_changes (task_id, old_due_date, new_due_date)
+    VALUES (NEW.id, OLD.due_date, NEW.due_date);
+END;
@@ -0,0 +1,8 @@
+-- Time the user means to spend on a life area each week. Life areas
+-- without a row have no budget.
+CREATE TABLE life_area_budgets (
+    life_area_id TEXT PRIMARY KEY NOT NULL,
+    weekly_minutes INTEGER NOT NULL CHECK (weekly_minutes > 0),
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (life_area_id) REFERENCES life_areas(id) ON DELETE CASCADE
+);
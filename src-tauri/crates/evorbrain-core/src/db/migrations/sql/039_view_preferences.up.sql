@@ -0,0 +1,15 @@
+-- How the user arranged each view of a project: grouping, sorting, folded
+-- columns and the task fields shown. Views without a row are arranged as
+-- by default.
+CREATE TABLE view_preferences (
+    project_id TEXT NOT NULL,
+    view_type TEXT NOT NULL CHECK (view_type IN ('board', 'list', 'calendar', 'timeline')),
+    group_by TEXT,
+    sort_by TEXT,
+    sort_descending BOOLEAN NOT NULL DEFAULT 0,
+    collapsed_columns TEXT NOT NULL DEFAULT '[]',
+    visible_fields TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, view_type),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
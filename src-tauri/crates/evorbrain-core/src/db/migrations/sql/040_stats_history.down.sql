@@ -0,0 +1,2 @@
+DELETE FROM jobs WHERE job_type = 'stats_snapshot';
+DROP TABLE IF EXISTS stats_history;
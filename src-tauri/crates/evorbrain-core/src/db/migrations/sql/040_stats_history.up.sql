@@ -0,0 +1,14 @@
+-- Counts of each day in the user's time zone, recorded by the nightly
+-- stats_snapshot job so long-term trends don't have to be computed from
+-- every task and note again
+CREATE TABLE stats_history (
+    day DATE PRIMARY KEY NOT NULL,
+    -- Tasks neither completed nor archived when the snapshot was taken
+    open_tasks INTEGER NOT NULL CHECK (open_tasks >= 0),
+    completed_tasks INTEGER NOT NULL CHECK (completed_tasks >= 0),
+    notes_created INTEGER NOT NULL CHECK (notes_created >= 0),
+    recorded_at TIMESTAMP NOT NULL
+);
+
+-- Shortly after midnight, recording the day that just ended
+INSERT INTO jobs (job_type, schedule) VALUES ('stats_snapshot', '5 0 * * *');
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_note_mentions_entity;
+DROP TABLE IF EXISTS note_mentions;
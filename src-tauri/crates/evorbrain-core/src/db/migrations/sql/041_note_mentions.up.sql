@@ -0,0 +1,13 @@
+-- Tasks and projects a note mentions by title or as '#<id>', found when the
+-- note is saved so the relationship exists without linking it by hand
+CREATE TABLE note_mentions (
+    note_id TEXT NOT NULL,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('task', 'project')),
+    entity_id TEXT NOT NULL,
+    matched_by TEXT NOT NULL CHECK (matched_by IN ('title', 'id')),
+    created_at TIMESTAMP NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (note_id, entity_type, entity_id),
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_note_mentions_entity ON note_mentions(entity_type, entity_id);
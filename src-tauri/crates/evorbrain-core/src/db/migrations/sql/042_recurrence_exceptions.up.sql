@@ -0,0 +1,8 @@
+-- Dates a repeating task skips, like EXDATE in iCalendar. They move on to
+-- each new instance when one is completed.
+CREATE TABLE task_recurrence_exceptions (
+    task_id TEXT NOT NULL,
+    date DATE NOT NULL,
+    PRIMARY KEY (task_id, date),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+DELETE FROM jobs WHERE job_type = 'attachment_text';
+DROP TABLE IF EXISTS attachment_extractions;
+DROP TABLE IF EXISTS attachment_text;
@@ -0,0 +1,22 @@
+-- Text extracted from attachment content for search. Content is stored
+-- once per hash, so attachments of the same file share its text.
+CREATE VIRTUAL TABLE attachment_text USING fts5(
+    content_hash UNINDEXED,
+    text,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- Content the attachment_text job has looked at, so each file is only
+-- extracted once
+CREATE TABLE attachment_extractions (
+    content_hash TEXT PRIMARY KEY NOT NULL,
+    -- 'unsupported' covers files that aren't text or PDF and files over the
+    -- size limit; 'failed' ones couldn't be read or took too long
+    status TEXT NOT NULL CHECK (status IN ('indexed', 'unsupported', 'failed')),
+    -- Characters of text indexed
+    text_length INTEGER NOT NULL DEFAULT 0 CHECK (text_length >= 0),
+    error TEXT,
+    extracted_at TIMESTAMP NOT NULL
+);
+
+INSERT INTO jobs (job_type, schedule) VALUES ('attachment_text', '*/10 * * * *');
@@ -0,0 +1,86 @@
+pub mod connection;
+pub mod models;
+pub mod schema;
+pub mod repository;
+pub mod batch;
+pub mod migrations;
+pub mod backup;
+pub mod write_queue;
+
+use crate::log_warn;
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::sqlite::SqlitePool;
+
+/// A row whose foreign key points at a row that doesn't exist
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    pub rowid: Option<i64>,
+    pub parent: String,
+}
+
+/// Opens the database and brings it up to date: `open_pool`, `migrate` and
+/// `report_foreign_key_violations` in one go
+pub async fn init_database(database_url: &str) -> Result<SqlitePool> {
+    let pool = open_pool(database_url).await?;
+    migrate(&pool).await?;
+    report_foreign_key_violations(&pool).await?;
+
+    Ok(pool)
+}
+
+/// Creates the database file if needed and connects to it
+pub async fn open_pool(database_url: &str) -> Result<SqlitePool> {
+    migrations::ensure_database_exists(database_url).await?;
+    connection::create_pool(database_url).await
+}
+
+/// Applies the pending migrations, backing the database up first
+pub async fn migrate(pool: &SqlitePool) -> Result<()> {
+    let runner = migrations::MigrationRunner::new(pool.clone());
+    runner.migrate(&migrations::all::get_migrations()).await?;
+
+    Ok(())
+}
+
+/// Whether the database is older than this version and needs migrating,
+/// checked without writing to it
+pub async fn needs_migration(pool: &SqlitePool) -> Result<bool> {
+    let runner = migrations::MigrationRunner::new(pool.clone());
+    runner.has_pending(&migrations::all::get_migrations()).await
+}
+
+/// Logs every foreign key violation
+///
+/// Rows written while enforcement was off may still be dangling; they are
+/// reported rather than fixed, so nothing is deleted behind the user's back.
+pub async fn report_foreign_key_violations(pool: &SqlitePool) -> Result<()> {
+    for violation in foreign_key_check(pool).await? {
+        log_warn!(&format!(
+            "Foreign key violation: {} row {} references a missing {} row",
+            violation.table,
+            violation.rowid.map_or_else(|| "?".to_string(), |rowid| rowid.to_string()),
+            violation.parent,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Refreshes the query planner statistics of tables that need it
+/// (`PRAGMA optimize`, which runs `ANALYZE` where it pays off)
+pub async fn optimize(pool: &SqlitePool) -> Result<()> {
+    sqlx::query("PRAGMA optimize").execute(pool).await?;
+
+    Ok(())
+}
+
+/// Runs `PRAGMA foreign_key_check` over the whole database
+pub async fn foreign_key_check(pool: &SqlitePool) -> Result<Vec<ForeignKeyViolation>> {
+    let violations = sqlx::query_as::<_, ForeignKeyViolation>("PRAGMA foreign_key_check")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(violations)
+}
\ No newline at end of file
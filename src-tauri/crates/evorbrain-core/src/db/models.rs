@@ -0,0 +1,2107 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{FromRow, Row, Type};
+use std::collections::BTreeMap;
+use uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LifeArea {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Hidden while the other context is active; always shown when unset
+    #[sqlx(default)]
+    #[serde(default)]
+    pub context: Option<AreaContext>,
+}
+
+/// Part of life a life area belongs to, for switching between them with the
+/// `active_context` setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AreaContext {
+    Personal,
+    Work,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Goal {
+    pub id: String,
+    pub life_area_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub target_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Set while the goal is parked on the someday/maybe list
+    #[serde(default)]
+    pub incubated_at: Option<DateTime<Utc>>,
+    /// Position among the siblings under the same life area, lowest first
+    #[serde(default)]
+    pub sort_order: i64,
+    /// Completion percentage, 0-100
+    #[serde(default)]
+    pub progress: i64,
+    /// Own color and icon, overriding those of the life area
+    #[sqlx(default)]
+    #[serde(default)]
+    pub color: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Color and icon to show: its own, or else the life area's
+    #[sqlx(default)]
+    #[serde(default)]
+    pub display_color: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub display_icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Project {
+    pub id: String,
+    pub goal_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: ProjectStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Days between reviews; the `review_interval_days` setting applies when unset
+    #[serde(default)]
+    pub review_interval_days: Option<i64>,
+    #[serde(default)]
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+    /// Set while the project is parked on the someday/maybe list
+    #[serde(default)]
+    pub incubated_at: Option<DateTime<Utc>>,
+    /// Position among the siblings under the same goal, lowest first
+    #[serde(default)]
+    pub sort_order: i64,
+    /// Completion percentage, 0-100
+    #[serde(default)]
+    pub progress: i64,
+    /// Own color and icon, overriding those of the goal and life area
+    #[sqlx(default)]
+    #[serde(default)]
+    pub color: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Color and icon to show: its own, or else the goal's, or else the life area's
+    #[sqlx(default)]
+    #[serde(default)]
+    pub display_color: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub display_icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Task {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub parent_task_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: TaskPriority,
+    pub due_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Key of a status in the project's workflow
+    #[serde(default = "default_task_status")]
+    pub status: String,
+    /// Expected effort, used for daily capacity planning
+    #[serde(default)]
+    pub estimated_minutes: Option<i64>,
+    /// Time spent so far; stopped timers add to it
+    #[serde(default)]
+    pub actual_minutes: i64,
+    /// iCalendar RRULE (`FREQ=WEEKLY;BYDAY=MO`) for tasks that repeat
+    #[serde(default)]
+    pub recurrence_rule: Option<String>,
+    /// Color and icon to show: those its project displays
+    #[sqlx(default)]
+    #[serde(default)]
+    pub display_color: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub display_icon: Option<String>,
+}
+
+/// Status given to new tasks in projects without a custom workflow
+pub const DEFAULT_TASK_STATUS: &str = "todo";
+
+fn default_task_status() -> String {
+    DEFAULT_TASK_STATUS.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Note {
+    pub id: String,
+    pub task_id: Option<String>,
+    pub project_id: Option<String>,
+    pub goal_id: Option<String>,
+    pub life_area_id: Option<String>,
+    pub title: String,
+    /// Encrypted (prefixed with `ENCRYPTED_CONTENT_PREFIX`) when `is_private`
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub is_private: bool,
+    /// The day the note is about, e.g. for a journal entry
+    #[sqlx(default)]
+    #[serde(default)]
+    pub note_date: Option<NaiveDate>,
+    /// Reviews done so far on the resurface schedule; `None` when the note
+    /// was never scheduled
+    #[sqlx(default)]
+    #[serde(default)]
+    pub review_stage: Option<i64>,
+    /// When the note resurfaces next; `None` when not scheduled or when the
+    /// schedule is finished
+    #[sqlx(default)]
+    #[serde(default)]
+    pub next_review_at: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// Days until a scheduled note resurfaces, by review stage: a week after it
+/// is scheduled, a month after the first review and three months after the
+/// second; the third review finishes the schedule
+pub const NOTE_REVIEW_INTERVAL_DAYS: &[i64] = &[7, 30, 90];
+
+/// Limits a note search to the subtree of one project, goal or life area:
+/// notes attached to it or to anything beneath it, down to tasks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteSearchScope {
+    pub project_id: Option<String>,
+    pub goal_id: Option<String>,
+    pub life_area_id: Option<String>,
+}
+
+/// Marks note content encrypted with the user's note key
+pub const ENCRYPTED_CONTENT_PREFIX: &str = "enc:v1:";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// GTD context such as `@home` or `@errands`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Context {
+    pub id: String,
+    /// Lowercase, without the leading `@`
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Open tasks in this context
+    #[sqlx(default)]
+    pub open_task_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskTag {
+    pub task_id: String,
+    pub tag_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProjectTag {
+    pub project_id: String,
+    pub tag_id: String,
+}
+
+/// A single recorded mutation, ordered by `seq`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChangeLogEntry {
+    pub seq: i64,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub operation: ChangeOperation,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// An operation recorded in the audit log, with its details as JSON
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditLogEntry {
+    pub seq: i64,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub details: sqlx::types::Json<serde_json::Value>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOperation {
+    Insert,
+    Update,
+    Delete,
+    Archive,
+    Restore,
+    Complete,
+    Uncomplete,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectStatus {
+    Planning,
+    Active,
+    OnHold,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    Medium,
+    High,
+    /// Older frontend builds call this level `critical`
+    #[serde(alias = "critical")]
+    Urgent,
+}
+
+impl Default for TaskPriority {
+    fn default() -> Self {
+        TaskPriority::Medium
+    }
+}
+
+impl std::fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectStatus::Planning => write!(f, "planning"),
+            ProjectStatus::Active => write!(f, "active"),
+            ProjectStatus::OnHold => write!(f, "onhold"),
+            ProjectStatus::Completed => write!(f, "completed"),
+            ProjectStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProjectStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "planning" => Ok(ProjectStatus::Planning),
+            "active" => Ok(ProjectStatus::Active),
+            "onhold" => Ok(ProjectStatus::OnHold),
+            "completed" => Ok(ProjectStatus::Completed),
+            "cancelled" => Ok(ProjectStatus::Cancelled),
+            _ => Err(format!("Invalid project status: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for TaskPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskPriority::Low => write!(f, "low"),
+            TaskPriority::Medium => write!(f, "medium"),
+            TaskPriority::High => write!(f, "high"),
+            TaskPriority::Urgent => write!(f, "urgent"),
+        }
+    }
+}
+
+impl std::str::FromStr for TaskPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(TaskPriority::Low),
+            "medium" => Ok(TaskPriority::Medium),
+            "high" => Ok(TaskPriority::High),
+            "urgent" | "critical" => Ok(TaskPriority::Urgent),
+            _ => Err(format!("Invalid task priority: {}", s)),
+        }
+    }
+}
+
+// Implementation helpers for models
+impl LifeArea {
+    pub fn new(name: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            description: None,
+            color: None,
+            icon: None,
+            created_at: now,
+            updated_at: now,
+            archived_at: None,
+            context: None,
+        }
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
+impl Goal {
+    pub fn new(life_area_id: String, title: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            life_area_id,
+            title,
+            description: None,
+            target_date: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            archived_at: None,
+            incubated_at: None,
+            sort_order: 0,
+            progress: 0,
+            color: None,
+            icon: None,
+            display_color: None,
+            display_icon: None,
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    pub fn is_incubated(&self) -> bool {
+        self.incubated_at.is_some()
+    }
+}
+
+impl Project {
+    pub fn new(goal_id: String, title: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            goal_id,
+            title,
+            description: None,
+            status: ProjectStatus::Planning,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            archived_at: None,
+            review_interval_days: None,
+            last_reviewed_at: None,
+            incubated_at: None,
+            sort_order: 0,
+            progress: 0,
+            color: None,
+            icon: None,
+            display_color: None,
+            display_icon: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.status, ProjectStatus::Active)
+    }
+
+    pub fn is_completed(&self) -> bool {
+        matches!(self.status, ProjectStatus::Completed) || self.completed_at.is_some()
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    pub fn is_incubated(&self) -> bool {
+        self.incubated_at.is_some()
+    }
+}
+
+impl Task {
+    pub fn new(title: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: None,
+            parent_task_id: None,
+            title,
+            description: None,
+            priority: TaskPriority::default(),
+            due_date: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            archived_at: None,
+            status: DEFAULT_TASK_STATUS.to_string(),
+            estimated_minutes: None,
+            actual_minutes: 0,
+            recurrence_rule: None,
+            display_color: None,
+            display_icon: None,
+        }
+    }
+
+    /// A new task with the given writable fields and defaults for the rest
+    pub fn from_input(input: TaskInput) -> Self {
+        Self {
+            project_id: input.project_id,
+            parent_task_id: input.parent_task_id,
+            description: input.description,
+            priority: input.priority,
+            due_date: input.due_date,
+            ..Self::new(input.title)
+        }
+    }
+
+    pub fn with_project(mut self, project_id: String) -> Self {
+        self.project_id = Some(project_id);
+        self
+    }
+
+    pub fn with_parent(mut self, parent_task_id: String) -> Self {
+        self.parent_task_id = Some(parent_task_id);
+        self
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    pub fn is_overdue(&self) -> bool {
+        if let Some(due) = self.due_date {
+            !self.is_completed() && due < Utc::now()
+        } else {
+            false
+        }
+    }
+}
+
+impl Note {
+    pub fn new(title: String, content: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            task_id: None,
+            project_id: None,
+            goal_id: None,
+            life_area_id: None,
+            title,
+            content,
+            created_at: now,
+            updated_at: now,
+            archived_at: None,
+            is_private: false,
+            note_date: None,
+            review_stage: None,
+            next_review_at: None,
+            last_reviewed_at: None,
+        }
+    }
+
+    pub fn for_task(mut self, task_id: String) -> Self {
+        self.task_id = Some(task_id);
+        self
+    }
+
+    pub fn for_project(mut self, project_id: String) -> Self {
+        self.project_id = Some(project_id);
+        self
+    }
+
+    pub fn for_goal(mut self, goal_id: String) -> Self {
+        self.goal_id = Some(goal_id);
+        self
+    }
+
+    pub fn for_life_area(mut self, life_area_id: String) -> Self {
+        self.life_area_id = Some(life_area_id);
+        self
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
+impl Tag {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            color: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_color(mut self, color: String) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// Writable goal fields shared by create and update operations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GoalInput {
+    pub life_area_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub target_date: Option<DateTime<Utc>>,
+    /// Leave unset to show the life area's color and icon
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// Writable project fields shared by create and update operations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectInput {
+    pub goal_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// New projects start in planning; updates keep the current status when unset
+    #[serde(default)]
+    pub status: Option<ProjectStatus>,
+    /// Leave unset to show the goal's or life area's color and icon
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// Writable task fields shared by create and update operations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskInput {
+    pub project_id: Option<String>,
+    pub parent_task_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub priority: TaskPriority,
+    pub due_date: Option<DateTime<Utc>>,
+}
+
+/// Writable note fields shared by create and update operations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteInput {
+    pub task_id: Option<String>,
+    pub project_id: Option<String>,
+    pub goal_id: Option<String>,
+    pub life_area_id: Option<String>,
+    pub title: String,
+    pub content: String,
+    /// Content must already be encrypted when set
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub note_date: Option<NaiveDate>,
+}
+
+/// Full JSON snapshot of the workspace produced by `Repository::export_all_data`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub data: serde_json::Value,
+    pub item_count: usize,
+    pub export_date: DateTime<Utc>,
+}
+
+/// What `Repository::import_all_data` does with an item whose ID already
+/// exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportCollision {
+    /// Keep the stored item
+    #[default]
+    Skip,
+    /// Replace the stored item with the imported one
+    Overwrite,
+    /// Import the item as a copy under a new ID
+    Duplicate,
+}
+
+/// What an import did with one section of the export
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSection {
+    /// `life_areas`, `goals`, `projects`, `tasks` or `notes`
+    pub section: String,
+    pub created: usize,
+    pub overwritten: usize,
+    pub duplicated: usize,
+    pub skipped: usize,
+}
+
+/// What `Repository::import_all_data` did, or would do on a dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataImportReport {
+    /// Nothing was written
+    pub dry_run: bool,
+    pub collision: ImportCollision,
+    pub sections: Vec<ImportSection>,
+    /// New ID of every item imported as a copy, keyed by its ID in the export
+    pub ids: BTreeMap<String, String>,
+}
+
+/// A tag on a project or task
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tagging {
+    /// `project` or `task`
+    pub entity_type: String,
+    pub entity_id: String,
+    pub tag_id: String,
+}
+
+/// A project with everything that belongs to it, as a project bundle
+/// carries it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectContents {
+    pub project: Project,
+    /// Its tasks and their subtasks, parents first
+    pub tasks: Vec<Task>,
+    /// Notes of the project and of its tasks, private ones left out
+    pub notes: Vec<Note>,
+    /// The tags used by the project and its tasks
+    pub tags: Vec<Tag>,
+    pub taggings: Vec<Tagging>,
+    /// Dependencies between its tasks
+    pub dependencies: Vec<TaskDependency>,
+    /// Files attached to the project, its tasks or its notes
+    pub attachments: Vec<Attachment>,
+    /// Custom statuses of the project; empty for the default workflow
+    pub workflow: Vec<WorkflowStatusInput>,
+    /// Private notes that were left out, since their content can only be
+    /// read in this vault
+    pub private_notes: usize,
+}
+
+/// What `Repository::import_project_contents` created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectImportReport {
+    pub project: Project,
+    pub tasks: usize,
+    pub notes: usize,
+    /// Names of the tags that didn't exist yet
+    pub tags_created: Vec<String>,
+    /// Names of the tags that already existed and were used as they are
+    pub tags_reused: Vec<String>,
+    pub dependencies: usize,
+    pub attachments: usize,
+    /// New ID of every project, task and note, keyed by its ID in the bundle
+    pub ids: BTreeMap<String, String>,
+    /// Human-readable reasons for every part of the bundle that wasn't imported
+    pub skipped: Vec<String>,
+}
+
+/// Outcome of `Repository::secure_delete`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecureDeleteReport {
+    /// The entity itself plus everything below it in the hierarchy
+    pub entities_deleted: usize,
+    /// External links attached to any of the deleted entities
+    pub links_deleted: u64,
+    /// Attachment rows of the deleted entities
+    pub attachments_deleted: u64,
+    /// Content hashes no longer referenced by any attachment, whose files
+    /// the caller must shred
+    #[serde(skip)]
+    pub unreferenced_hashes: Vec<String>,
+}
+
+/// File attached to an entity; the content lives in the attachment store
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Attachment {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub file_name: String,
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+    /// Hex SHA-256 of the content, which names the stored file
+    pub content_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Stored attachment content the text extraction job hasn't looked at yet
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingAttachmentContent {
+    pub content_hash: String,
+    /// Name and type of one of the attachments with this content
+    pub file_name: String,
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+}
+
+/// An attachment whose text matches a search, with the item it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AttachmentSearchHit {
+    pub attachment_id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Title of the item, or name for life areas
+    pub title: String,
+    pub archived: bool,
+    pub file_name: String,
+    /// The matching part of the text, matches wrapped in `**`
+    pub snippet: String,
+}
+
+/// Result of `scan_orphan_attachments`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OrphanAttachmentReport {
+    /// Stored files no attachment row refers to
+    pub orphaned_files: Vec<String>,
+    /// Attachment rows whose entity no longer exists
+    pub dangling_rows: u64,
+    /// Disk space used by the orphaned files
+    pub reclaimable_bytes: u64,
+    /// Orphans were deleted rather than only reported
+    pub deleted: bool,
+}
+
+/// Attachment storage used by one entity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EntityAttachmentUsage {
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Title or name of the entity; `None` once it no longer exists
+    pub title: Option<String>,
+    pub attachment_count: i64,
+    /// Sum of the entity's attachment sizes, so content attached twice
+    /// counts twice
+    pub size_bytes: i64,
+}
+
+/// Result of `get_attachment_usage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentUsage {
+    /// Size of the stored files, each counted once however often attached
+    pub total_bytes: i64,
+    pub attachment_count: i64,
+    /// Per-file limit of the `attachment_max_file_mb` setting
+    pub max_file_bytes: u64,
+    /// Total quota of the `attachment_quota_mb` setting, if any
+    pub quota_bytes: Option<u64>,
+    /// Largest first
+    pub entities: Vec<EntityAttachmentUsage>,
+}
+
+/// Open task counts shown on tray, menu-bar and dock badges
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct BadgeCounts {
+    pub due_today: i64,
+    pub overdue: i64,
+    /// Open tasks without a project
+    pub inbox: i64,
+}
+
+/// Open task counts shown in the sidebar
+///
+/// The maps are keyed by ID and leave out entities without open tasks. A
+/// goal or life area counts the open tasks of all its projects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SidebarCounts {
+    pub life_areas: BTreeMap<String, i64>,
+    pub goals: BTreeMap<String, i64>,
+    pub projects: BTreeMap<String, i64>,
+    /// Open tasks without a project
+    pub inbox: i64,
+    pub due_today: i64,
+    pub overdue: i64,
+}
+
+/// A project with the number of tasks in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTaskCounts {
+    #[serde(flatten)]
+    pub project: Project,
+    pub open_tasks: i64,
+    pub completed_tasks: i64,
+    /// Open tasks past their due date
+    pub overdue_tasks: i64,
+}
+
+/// A goal with what its detail page shows, from `Repository::get_goal_breakdown`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalBreakdown {
+    /// Includes the stored `progress` percentage
+    pub goal: Goal,
+    /// Active projects, in sidebar order
+    pub projects: Vec<ProjectTaskCounts>,
+    /// Most recently updated notes of the goal, its projects and their tasks
+    pub latest_notes: Vec<Note>,
+    /// Next open tasks by due date, the goal's upcoming milestones
+    pub upcoming_tasks: Vec<Task>,
+    /// Totals over all projects
+    pub open_tasks: i64,
+    pub completed_tasks: i64,
+}
+
+/// Goals and projects parked on the someday/maybe list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SomedayMaybe {
+    pub goals: Vec<Goal>,
+    pub projects: Vec<Project>,
+}
+
+/// An open project without activity or a review within its review interval
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StaleProject {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub project: Project,
+    /// Whole days since the project, its tasks or notes last changed, or
+    /// since it was last reviewed
+    pub days_idle: i64,
+    /// The project's own review interval, or the default one
+    pub effective_review_interval_days: i64,
+}
+
+/// Per-task inputs to the urgency score that aren't task columns
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UrgencyFactors {
+    pub task_id: String,
+    pub tag_count: i64,
+    /// An open task depends on this one, or it is a subtask of an open task
+    pub blocking: bool,
+    /// The task depends on an open task, or has open subtasks
+    pub blocked: bool,
+    /// The task's status is in the in-progress category
+    pub in_progress: bool,
+}
+
+/// How many tasks, projects and goals have been completed, archived or not
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, FromRow)]
+pub struct CompletionCounts {
+    pub tasks: i64,
+    pub projects: i64,
+    pub goals: i64,
+}
+
+/// Someone tasks can be delegated to
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Person {
+    pub id: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Writable person fields shared by create and update operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonInput {
+    pub name: String,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// A task delegated to someone
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Delegation {
+    pub task_id: String,
+    pub person_id: String,
+    pub follow_up_at: Option<DateTime<Utc>>,
+    pub delegated_at: DateTime<Utc>,
+}
+
+/// An action item of a meeting note turned into a delegated task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingActionItem {
+    pub task: Task,
+    pub delegation: Delegation,
+}
+
+/// What `process_meeting_note` found in a meeting note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingNoteSummary {
+    pub decisions: Vec<String>,
+    /// Action items turned into tasks this time
+    pub action_items: Vec<MeetingActionItem>,
+    /// Action items left in the note because their mentions don't match
+    /// exactly one person
+    pub unassigned: Vec<String>,
+}
+
+/// A task or project a note mentions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct NoteMention {
+    /// `task` or `project`
+    pub entity_type: String,
+    pub entity_id: String,
+    /// `title`, or `id` for a `#<id>` reference
+    pub matched_by: String,
+}
+
+/// The next instance of a repeating task, created when the current one is
+/// completed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskOccurrence {
+    /// The occurrence in the user's time zone
+    pub date: NaiveDate,
+    pub due_date: DateTime<Utc>,
+    /// The rule the instance carries on with, its COUNT lowered by the
+    /// occurrences passed
+    pub recurrence_rule: String,
+}
+
+/// A task that can't be started before another one is completed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskDependency {
+    pub task_id: String,
+    /// The task that has to be completed first
+    pub depends_on_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An open task another one waits for
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Blocker {
+    pub id: String,
+    pub title: String,
+}
+
+/// An open task that waits for open tasks it depends on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockedTask {
+    pub task_id: String,
+    pub title: String,
+    pub project_id: Option<String>,
+    pub priority: TaskPriority,
+    pub due_date: Option<DateTime<Utc>>,
+    pub blocked_by: Vec<Blocker>,
+}
+
+/// A task put out of sight until later
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskSnooze {
+    pub task_id: String,
+    pub snoozed_until: DateTime<Utc>,
+    pub snoozed_at: DateTime<Utc>,
+}
+
+/// Why a task that would be due today isn't in the Today view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodayExclusionReason {
+    /// It depends on tasks that are still open
+    Blocked,
+    /// It is snoozed until later
+    Snoozed,
+}
+
+/// A task left out of the Today view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodayExclusion {
+    pub task_id: String,
+    pub title: String,
+    pub priority: TaskPriority,
+    pub due_date: Option<DateTime<Utc>>,
+    /// `blocked` when the task is both blocked and snoozed
+    pub reason: TodayExclusionReason,
+    /// Open tasks it depends on
+    pub blocked_by: Vec<String>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+}
+
+/// An open delegated task in the waiting-for list
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WaitingForItem {
+    #[sqlx(flatten)]
+    pub task: Task,
+    pub follow_up_at: Option<DateTime<Utc>>,
+    pub delegated_at: DateTime<Utc>,
+}
+
+/// Open tasks delegated to one person, earliest follow-up first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitingForGroup {
+    pub person: Person,
+    pub items: Vec<WaitingForItem>,
+}
+
+/// One day's focus list together with the tasks due that day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MyDay {
+    pub date: NaiveDate,
+    /// Tasks picked for the day, in the order they were added; completed
+    /// ones stay so progress is visible
+    pub focused: Vec<Task>,
+    /// Open tasks due that day (and, for today, overdue ones) not already focused
+    pub due: Vec<Task>,
+    /// Unfinished tasks from the most recent earlier plan, offered for
+    /// carrying over instead of silently dropping them
+    pub carried_over: Vec<Task>,
+}
+
+/// Planned work for a day compared with its working hours
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayCapacity {
+    pub date: NaiveDate,
+    /// Working minutes available; 0 on non-working days
+    pub capacity_minutes: i64,
+    /// Sum of the estimates of open tasks focused for or due that day
+    pub planned_minutes: i64,
+    pub task_count: i64,
+    /// Tasks counted without an estimate, so `planned_minutes` may be low
+    pub unestimated_count: i64,
+    pub over_committed: bool,
+    /// Human-readable over-commitment warning
+    pub warning: Option<String>,
+}
+
+/// What archiving a life area would archive along with it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct DeleteImpact {
+    pub goals: i64,
+    pub projects: i64,
+    pub tasks: i64,
+    pub notes: i64,
+}
+
+impl DeleteImpact {
+    pub fn total(&self) -> i64 {
+        self.goals + self.projects + self.tasks + self.notes
+    }
+}
+
+/// An archived entity listed in a restore preview
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ArchivedItem {
+    pub id: String,
+    pub title: String,
+}
+
+/// What a cascading restore would bring back, by entity type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestorePreview {
+    pub goals: Vec<ArchivedItem>,
+    pub projects: Vec<ArchivedItem>,
+    pub tasks: Vec<ArchivedItem>,
+    pub notes: Vec<ArchivedItem>,
+}
+
+/// How an archived life area was archived, from `Repository::get_archive_summary`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveSummary {
+    pub life_area: LifeArea,
+    /// Everything archived along with the life area carries this same time
+    pub archived_at: DateTime<Utc>,
+    /// Descendants archived together with the life area
+    pub cascaded: DeleteImpact,
+    /// Descendants that were archived on their own earlier; a restore
+    /// leaves them archived
+    pub archived_separately: DeleteImpact,
+    /// Exactly what `restore_life_area` with `cascade` brings back
+    pub restore_preview: RestorePreview,
+}
+
+/// What `merge_projects` moved from the source project into the target
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectMergeReport {
+    pub tasks_moved: u64,
+    pub notes_moved: u64,
+    /// Tags the target didn't have yet
+    pub tags_added: u64,
+}
+
+/// Kind of inconsistency reported by `scan_data_issues`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataIssueKind {
+    /// Refers to a parent that no longer exists
+    MissingParent,
+    /// Still active although its parent was archived after the row last
+    /// changed, so the archive cascade missed it
+    MissedArchive,
+    /// Active and changed since its parent was archived, e.g. moved under it
+    ArchivedParent,
+    /// Completed at a time that hasn't come yet
+    FutureCompletion,
+}
+
+impl std::fmt::Display for DataIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataIssueKind::MissingParent => write!(f, "missing_parent"),
+            DataIssueKind::MissedArchive => write!(f, "missed_archive"),
+            DataIssueKind::ArchivedParent => write!(f, "archived_parent"),
+            DataIssueKind::FutureCompletion => write!(f, "future_completion"),
+        }
+    }
+}
+
+/// One inconsistency in the stored data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataIssue {
+    /// Stable across scans: `kind:entity_type:entity_id`, plus `:parent_type`
+    /// for issues about a parent
+    pub id: String,
+    pub kind: DataIssueKind,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub title: String,
+    pub parent_type: Option<String>,
+    pub parent_id: Option<String>,
+    /// When the parent was archived, or the future completion time
+    pub at: Option<DateTime<Utc>>,
+}
+
+impl DataIssue {
+    pub fn new(kind: DataIssueKind, entity_type: &str, entity_id: String, title: String) -> Self {
+        Self {
+            id: format!("{}:{}:{}", kind, entity_type, entity_id),
+            kind,
+            entity_type: entity_type.to_string(),
+            entity_id,
+            title,
+            parent_type: None,
+            parent_id: None,
+            at: None,
+        }
+    }
+
+    pub fn with_parent(mut self, parent_type: &str, parent_id: String) -> Self {
+        self.id = format!("{}:{}", self.id, parent_type);
+        self.parent_type = Some(parent_type.to_string());
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn at(mut self, at: Option<DateTime<Utc>>) -> Self {
+        self.at = at;
+        self
+    }
+}
+
+/// How `repair_data_issues` fixes the issues it is given
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairStrategy {
+    /// Drops the bad parent reference: tasks move to the inbox (or become
+    /// top-level tasks), notes are unlinked. Only for tasks and notes.
+    Detach,
+    /// Archives the row with its children, at the time its parent was
+    /// archived when there is one, so restoring the parent brings it back
+    Archive,
+    /// Clears a completion time that lies in the future
+    ClearTimestamp,
+}
+
+impl std::fmt::Display for RepairStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepairStrategy::Detach => write!(f, "detach"),
+            RepairStrategy::Archive => write!(f, "archive"),
+            RepairStrategy::ClearTimestamp => write!(f, "clear_timestamp"),
+        }
+    }
+}
+
+/// What `repair_data_issues` fixed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataRepairReport {
+    /// IDs of the repaired issues
+    pub repaired: Vec<String>,
+    /// Issues a new scan still finds, including ones the repair didn't touch
+    pub remaining: usize,
+}
+
+/// A life area, goal, project, task or note as the quick switcher lists it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SwitcherEntry {
+    pub entity_type: String,
+    pub id: String,
+    pub title: String,
+}
+
+/// An entity pinned to the dashboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pin {
+    /// `life_area`, `goal`, `project`, `task` or `note`
+    pub entity_type: String,
+    pub entity_id: String,
+}
+
+/// What the dashboard shows of a pinned entity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PinnedItem {
+    pub entity_type: String,
+    pub entity_id: String,
+    pub position: i64,
+    pub pinned_at: DateTime<Utc>,
+    /// Title, or the name of a life area
+    pub title: String,
+    pub display_color: Option<String>,
+    pub display_icon: Option<String>,
+    /// Progress percentage of a goal or project
+    pub progress: Option<i64>,
+    /// Open tasks of a life area, goal or project, or open subtasks of a
+    /// task; `None` for notes
+    pub open_tasks: Option<i64>,
+    /// Due date of a task or target date of a goal
+    pub due_date: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What `bulk_tag` changed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BulkTagResult {
+    /// Tasks or projects retagged
+    pub entities: usize,
+    /// Tags put on an entity that didn't have them yet
+    pub added: usize,
+    /// Tags taken off an entity that had them
+    pub removed: usize,
+}
+
+/// Time the user means to spend on a life area each week
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LifeAreaBudget {
+    pub life_area_id: String,
+    pub weekly_minutes: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How tracked time compares with a budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetStatus {
+    /// Behind the share of the budget the days gone by account for
+    Under,
+    OnTrack,
+    /// Past the whole budget of the week
+    Over,
+}
+
+/// Time tracked in a life area over a week against its budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeBudget {
+    pub life_area_id: String,
+    pub name: String,
+    pub budget_minutes: i64,
+    pub tracked_minutes: i64,
+    /// The share of the budget for the days of the week gone by, today
+    /// included; the whole budget for past weeks
+    pub expected_minutes: i64,
+    /// Tracked time as a percentage of the budget
+    pub percent: f64,
+    pub status: BudgetStatus,
+}
+
+/// Everything `get_time_budget_report` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeBudgetReport {
+    pub from: NaiveDate,
+    /// Last day of the week, included
+    pub to: NaiveDate,
+    /// Life areas with a budget, by name
+    pub budgets: Vec<TimeBudget>,
+}
+
+/// Everything `get_dashboard` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub counts: BadgeCounts,
+    /// In the order the user arranged them
+    pub pins: Vec<PinnedItem>,
+    /// This week's time budgets of the life areas that have one
+    pub budgets: Vec<TimeBudget>,
+}
+
+/// Row counts and file size that storage advice is based on
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StorageCounts {
+    /// Completed tasks that aren't archived
+    pub completed_tasks: i64,
+    /// Those of them `archive_completed_tasks` would archive with the cutoff
+    /// the advice was computed for
+    pub archivable_tasks: i64,
+    /// Archived rows of all entity types, kept until purged
+    pub archived_items: i64,
+    pub database_bytes: i64,
+    /// Space of deleted rows that only a vacuum gives back
+    pub free_bytes: i64,
+}
+
+/// An archived parent that blocks restoring one of its descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedAncestor {
+    /// `task`, `project`, `goal` or `life_area`
+    pub entity_type: String,
+    pub id: String,
+    pub title: String,
+}
+
+/// A task with all of its unarchived subtasks, nested
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskTreeNode {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub task: Task,
+    /// 0 for the root of the tree
+    pub depth: i64,
+    #[sqlx(skip)]
+    pub children: Vec<TaskTreeNode>,
+}
+
+/// A task on a day's plan with its timebox
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DayPlanItem {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub task: Task,
+    pub position: i64,
+    /// Minutes set aside for the task that day; its estimate applies when unset
+    pub planned_minutes: Option<i64>,
+    /// When the item's timebox starts, if it has a duration
+    #[sqlx(skip)]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    pub ends_at: Option<DateTime<Utc>>,
+    /// The timebox ends after the working day does
+    #[sqlx(skip)]
+    pub overflows: bool,
+}
+
+impl DayPlanItem {
+    /// Planned minutes, or else the task's estimate
+    pub fn duration_minutes(&self) -> Option<i64> {
+        self.planned_minutes.or(self.task.estimated_minutes)
+    }
+}
+
+/// A day's timeboxed plan, laid out from the start of the working day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayPlan {
+    pub date: NaiveDate,
+    pub items: Vec<DayPlanItem>,
+    /// Sum of the item durations
+    pub planned_minutes: i64,
+    pub capacity_minutes: i64,
+}
+
+/// Open tasks planned for a day and their estimated effort
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, FromRow)]
+pub struct DayLoad {
+    pub task_count: i64,
+    pub planned_minutes: i64,
+    pub unestimated_count: i64,
+}
+
+/// An open task that keeps getting rescheduled
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChronicPostponement {
+    pub task_id: String,
+    pub title: String,
+    pub project_id: Option<String>,
+    pub project_title: Option<String>,
+    pub due_date: Option<DateTime<Utc>>,
+    /// Times its due date changed after it had one
+    pub reschedules: i64,
+    /// Reschedules that moved the due date later or cleared it
+    pub postponements: i64,
+    /// The due date it had before the first reschedule
+    pub first_due_date: Option<DateTime<Utc>>,
+    pub last_rescheduled_at: DateTime<Utc>,
+}
+
+/// Open tasks left in a project and their estimated effort
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, FromRow)]
+pub struct RemainingWork {
+    pub task_count: i64,
+    pub estimated_minutes: i64,
+    pub unestimated_count: i64,
+}
+
+/// Open tasks and their estimated and tracked effort
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Effort {
+    pub task_count: i64,
+    pub estimated_minutes: i64,
+    /// Time already spent on the open tasks
+    pub actual_minutes: i64,
+    /// Tasks counted without an estimate
+    pub unestimated_count: i64,
+}
+
+impl std::ops::AddAssign for Effort {
+    fn add_assign(&mut self, other: Self) {
+        self.task_count += other.task_count;
+        self.estimated_minutes += other.estimated_minutes;
+        self.actual_minutes += other.actual_minutes;
+        self.unestimated_count += other.unestimated_count;
+    }
+}
+
+/// Effort of the open tasks per project, rolled up to goals and life areas
+///
+/// The maps are keyed by ID and leave out entities without open tasks.
+/// Like the sidebar counts, tasks of incubated projects and of life areas
+/// hidden by the active context aren't counted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EffortRollup {
+    pub life_areas: BTreeMap<String, Effort>,
+    pub goals: BTreeMap<String, Effort>,
+    pub projects: BTreeMap<String, Effort>,
+    /// Open tasks without a project
+    pub inbox: Effort,
+    pub total: Effort,
+}
+
+/// An open task as counted by the aging report
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OpenTaskAge {
+    pub task_id: String,
+    pub project_id: Option<String>,
+    pub project_title: Option<String>,
+    pub priority: TaskPriority,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Time tracked on one task within a range, with the goal and life area it
+/// rolls up to; both are `None` for tasks outside a project
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskFocus {
+    pub task_id: String,
+    pub goal_id: Option<String>,
+    pub goal_title: Option<String>,
+    pub life_area_id: Option<String>,
+    pub life_area_name: Option<String>,
+    pub seconds: i64,
+    pub entry_count: i64,
+}
+
+/// A task listed in a digest, with the time it was completed or is due
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DigestTask {
+    pub id: String,
+    pub title: String,
+    pub project_title: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// An active goal in a digest with the tasks done towards it in the period
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DigestGoal {
+    pub id: String,
+    pub title: String,
+    /// Completion percentage, 0-100
+    pub progress: i64,
+    pub completed_tasks: i64,
+    pub open_tasks: i64,
+}
+
+/// Counts of one day, as the `stats_snapshot` job recorded them
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StatsSnapshot {
+    /// The day in the user's time zone
+    pub day: NaiveDate,
+    /// Tasks neither completed nor archived when the snapshot was taken
+    pub open_tasks: i64,
+    /// Tasks completed during the day
+    pub completed_tasks: i64,
+    /// Notes created during the day
+    pub notes_created: i64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Outgoing webhook registration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// Shared secret used to sign deliveries (HMAC-SHA256)
+    pub secret: String,
+    /// Event filters such as `task.completed`, `goal.*` or `*`
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub last_delivery_at: Option<DateTime<Utc>>,
+    pub last_status_code: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Events are stored as a JSON array in a TEXT column
+impl<'r> FromRow<'r, SqliteRow> for Webhook {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let events: String = row.try_get("events")?;
+        let events = serde_json::from_str(&events).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "events".to_string(),
+            source: Box::new(e),
+        })?;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            url: row.try_get("url")?,
+            secret: row.try_get("secret")?,
+            events,
+            is_active: row.try_get("is_active")?,
+            last_delivery_at: row.try_get("last_delivery_at")?,
+            last_status_code: row.try_get("last_status_code")?,
+            last_error: row.try_get("last_error")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// Writable webhook fields shared by create and update operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookInput {
+    pub name: String,
+    pub url: String,
+    pub events: Vec<String>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Groups workflow statuses so completion and the "today" views work the
+/// same across custom workflows
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum StatusCategory {
+    Todo,
+    InProgress,
+    /// Tasks in a done status are completed
+    Done,
+}
+
+/// One status of a project's task workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStatus {
+    /// Identifier stored on tasks, e.g. `in_review`
+    pub key: String,
+    pub name: String,
+    pub category: StatusCategory,
+    /// Statuses tasks may move to from this one; any status when `None`
+    pub next_statuses: Option<Vec<String>>,
+}
+
+// Allowed transitions are stored as a JSON array in a TEXT column
+impl<'r> FromRow<'r, SqliteRow> for WorkflowStatus {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let next_statuses: Option<String> = row.try_get("next_statuses")?;
+        let next_statuses = next_statuses
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|e| sqlx::Error::ColumnDecode {
+                index: "next_statuses".to_string(),
+                source: Box::new(e),
+            })?;
+
+        Ok(Self {
+            key: row.try_get("key")?,
+            name: row.try_get("name")?,
+            category: row.try_get("category")?,
+            next_statuses,
+        })
+    }
+}
+
+/// Ordered task statuses in effect for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub project_id: Option<String>,
+    /// The project defines its own statuses rather than using the default
+    pub is_custom: bool,
+    pub statuses: Vec<WorkflowStatus>,
+}
+
+impl Workflow {
+    /// The todo -> in progress -> done workflow used by projects without
+    /// custom statuses and by tasks without a project
+    pub fn default_for(project_id: Option<String>) -> Self {
+        let status = |key: &str, name: &str, category| WorkflowStatus {
+            key: key.to_string(),
+            name: name.to_string(),
+            category,
+            next_statuses: None,
+        };
+        Self {
+            project_id,
+            is_custom: false,
+            statuses: vec![
+                status(DEFAULT_TASK_STATUS, "To do", StatusCategory::Todo),
+                status("in_progress", "In progress", StatusCategory::InProgress),
+                status("done", "Done", StatusCategory::Done),
+            ],
+        }
+    }
+
+    pub fn find(&self, key: &str) -> Option<&WorkflowStatus> {
+        self.statuses.iter().find(|s| s.key == key)
+    }
+
+    /// Whether a task may move from `from` to `to`; staying put is always allowed
+    pub fn allows_transition(&self, from: &str, to: &str) -> bool {
+        if from == to {
+            return true;
+        }
+        match self.find(from) {
+            Some(status) => status
+                .next_statuses
+                .as_ref()
+                .is_none_or(|next| next.iter().any(|key| key == to)),
+            // A status that is no longer part of the workflow can move anywhere
+            None => true,
+        }
+    }
+}
+
+/// Writable fields of a workflow status; order in the list sets the position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStatusInput {
+    pub key: String,
+    pub name: String,
+    pub category: StatusCategory,
+    #[serde(default)]
+    pub next_statuses: Option<Vec<String>>,
+}
+
+/// The ways a project's tasks can be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ViewType {
+    Board,
+    List,
+    Calendar,
+    Timeline,
+}
+
+/// How the user arranged one view of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewPreferences {
+    pub project_id: String,
+    pub view_type: ViewType,
+    /// Task field the tasks are grouped by, e.g. `status` for board columns
+    pub group_by: Option<String>,
+    /// Task field the tasks are sorted by; their manual order when `None`
+    pub sort_by: Option<String>,
+    pub sort_descending: bool,
+    /// Keys of the folded groups, e.g. the status keys of board columns
+    pub collapsed_columns: Vec<String>,
+    /// Task fields shown for each task; the view's own choice when `None`
+    pub visible_fields: Option<Vec<String>>,
+    /// `None` while the view is still arranged as by default
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ViewPreferences {
+    /// How a view is arranged until the user changes it: boards have a
+    /// column per status, the other views no groups
+    pub fn default_for(project_id: String, view_type: ViewType) -> Self {
+        Self {
+            project_id,
+            view_type,
+            group_by: (view_type == ViewType::Board).then(|| "status".to_string()),
+            sort_by: None,
+            sort_descending: false,
+            collapsed_columns: Vec::new(),
+            visible_fields: None,
+            updated_at: None,
+        }
+    }
+}
+
+// Column and field lists are stored as JSON arrays in TEXT columns
+impl<'r> FromRow<'r, SqliteRow> for ViewPreferences {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let decode = |index: &str, json: &str| {
+            serde_json::from_str(json).map_err(|e| sqlx::Error::ColumnDecode {
+                index: index.to_string(),
+                source: Box::new(e),
+            })
+        };
+        let collapsed_columns: String = row.try_get("collapsed_columns")?;
+        let visible_fields: Option<String> = row.try_get("visible_fields")?;
+
+        Ok(Self {
+            project_id: row.try_get("project_id")?,
+            view_type: row.try_get("view_type")?,
+            group_by: row.try_get("group_by")?,
+            sort_by: row.try_get("sort_by")?,
+            sort_descending: row.try_get("sort_descending")?,
+            collapsed_columns: decode("collapsed_columns", &collapsed_columns)?,
+            visible_fields: visible_fields
+                .map(|json| decode("visible_fields", &json))
+                .transpose()?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// Writable fields of a view's arrangement
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewPreferencesInput {
+    #[serde(default)]
+    pub group_by: Option<String>,
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub sort_descending: bool,
+    #[serde(default)]
+    pub collapsed_columns: Vec<String>,
+    #[serde(default)]
+    pub visible_fields: Option<Vec<String>>,
+}
+
+/// User script run when matching events occur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptHook {
+    pub id: String,
+    pub name: String,
+    /// Event filters such as `task.completed`, `note.*` or `*`
+    pub events: Vec<String>,
+    /// Rhai source
+    pub script: String,
+    pub is_active: bool,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Events are stored as a JSON array in a TEXT column, as for webhooks
+impl<'r> FromRow<'r, SqliteRow> for ScriptHook {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let events: String = row.try_get("events")?;
+        let events = serde_json::from_str(&events).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "events".to_string(),
+            source: Box::new(e),
+        })?;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            events,
+            script: row.try_get("script")?,
+            is_active: row.try_get("is_active")?,
+            last_run_at: row.try_get("last_run_at")?,
+            last_error: row.try_get("last_error")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// Writable script hook fields shared by create and update operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptHookInput {
+    pub name: String,
+    pub events: Vec<String>,
+    pub script: String,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+/// IMAP account polled for emails to import as inbox tasks
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmailAccount {
+    pub id: String,
+    pub name: String,
+    pub host: String,
+    pub port: i64,
+    pub username: String,
+    /// Never sent to the frontend
+    #[serde(skip_serializing, default)]
+    pub password: String,
+    pub folder: String,
+    /// IMAP SEARCH criteria, e.g. `FLAGGED` or `UNSEEN SUBJECT "todo"`
+    pub search_query: String,
+    pub poll_interval_minutes: i64,
+    pub is_active: bool,
+    pub last_polled_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EmailAccount {
+    /// Whether the poll interval has elapsed since the last poll
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match self.last_polled_at {
+            Some(last) => now - last >= chrono::Duration::minutes(self.poll_interval_minutes),
+            None => true,
+        }
+    }
+}
+
+/// Writable email account fields shared by create and update operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailAccountInput {
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_imap_port")]
+    pub port: i64,
+    pub username: String,
+    /// Required on create; `None` keeps the stored password on update
+    pub password: Option<String>,
+    #[serde(default = "default_imap_folder")]
+    pub folder: String,
+    #[serde(default = "default_imap_search_query")]
+    pub search_query: String,
+    #[serde(default = "default_poll_interval_minutes")]
+    pub poll_interval_minutes: i64,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+fn default_imap_port() -> i64 {
+    993
+}
+
+fn default_imap_folder() -> String {
+    "INBOX".to_string()
+}
+
+fn default_imap_search_query() -> String {
+    "FLAGGED".to_string()
+}
+
+fn default_poll_interval_minutes() -> i64 {
+    15
+}
+
+/// A fetched email to convert into an inbox task with the body as a note
+#[derive(Debug, Clone)]
+pub struct EmailImport {
+    pub message_id: String,
+    pub subject: String,
+    pub from: Option<String>,
+    pub body: String,
+}
+
+/// Periodic background work run by the job scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum JobType {
+    /// Deletes archived items past the `archive_retention_days` setting
+    ArchiveRetention,
+    /// Polls email accounts whose poll interval has elapsed
+    EmailImport,
+    /// Refreshes the state of linked GitHub issues and pull requests
+    GithubSync,
+    /// Composes the digest of the past week and delivers it per the
+    /// `digest_delivery` setting
+    WeeklyDigest,
+    /// Records the counts of the day that just ended in the stats history
+    StatsSnapshot,
+    /// Extracts the text of new attachments for search
+    AttachmentText,
+}
+
+impl std::fmt::Display for JobType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobType::ArchiveRetention => write!(f, "archive_retention"),
+            JobType::EmailImport => write!(f, "email_import"),
+            JobType::GithubSync => write!(f, "github_sync"),
+            JobType::WeeklyDigest => write!(f, "weekly_digest"),
+            JobType::StatsSnapshot => write!(f, "stats_snapshot"),
+            JobType::AttachmentText => write!(f, "attachment_text"),
+        }
+    }
+}
+
+/// A job definition with the outcome of its last run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Job {
+    pub job_type: JobType,
+    /// Cron expression evaluated in the user's time zone, e.g. `*/15 * * * *`
+    pub schedule: String,
+    pub enabled: bool,
+    /// When the last run started
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_duration_ms: Option<i64>,
+    /// Error of the last run; cleared by a successful one
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// When the scheduler runs the job next; unset for disabled jobs
+    #[sqlx(skip)]
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl Job {
+    /// The first scheduled time after the last run, or after the job was
+    /// created if it never ran
+    pub fn next_run(&self, timezone: chrono_tz::Tz) -> Option<DateTime<Utc>> {
+        let schedule = crate::schedule::Schedule::parse(&self.schedule).ok()?;
+        schedule.next_after(self.last_run_at.unwrap_or(self.created_at), timezone)
+    }
+}
+
+/// Link from an entity to an external resource such as a web page or GitHub issue
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExternalLink {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub url: String,
+    /// `github` for synced issues/PRs, `web` for plain links
+    pub provider: String,
+    pub external_id: Option<String>,
+    pub title: Option<String>,
+    /// Page description, fetched for web links
+    pub description: Option<String>,
+    /// Favicon as a `data:` URI, fetched for web links
+    pub favicon: Option<String>,
+    pub state: Option<String>,
+    pub complete_on_close: bool,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub sync_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Found in the task's description rather than added by hand; removed
+    /// again when the description no longer contains the URL
+    #[sqlx(default)]
+    #[serde(default)]
+    pub extracted: bool,
+}
+
+/// An entity referenced from a task description, or a task referencing one
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReferencedEntity {
+    pub entity_type: String,
+    pub id: String,
+    pub title: String,
+    pub archived: bool,
+}
+
+/// Material related to a task through links and references
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskReferences {
+    /// The task's external links, those from the description included
+    pub links: Vec<ExternalLink>,
+    /// Entities the description refers to that still exist
+    pub references: Vec<ReferencedEntity>,
+    /// Tasks whose description refers to this task
+    pub referenced_by: Vec<ReferencedEntity>,
+}
+
+/// Fields needed to create an external link
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExternalLinkInput {
+    pub entity_type: String,
+    pub entity_id: String,
+    pub url: String,
+    pub provider: String,
+    pub external_id: Option<String>,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub complete_on_close: bool,
+}
+
+/// Time tracked on a task; running while `ended_at` is unset
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TimeEntry {
+    pub id: String,
+    pub task_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Set when the timer was stopped because the machine went idle or
+    /// asleep; the entry then ends when the gap began
+    pub idle_started_at: Option<DateTime<Utc>>,
+    /// When the idle gap was detected to be over
+    pub idle_ended_at: Option<DateTime<Utc>>,
+    /// Set once the user decided whether the idle gap counts
+    pub reconciled_at: Option<DateTime<Utc>>,
+}
+
+/// A named set of holidays, such as an imported national calendar
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct HolidaySet {
+    pub id: String,
+    pub name: String,
+    /// Holidays of disabled sets are kept but count as normal days
+    pub enabled: bool,
+    pub holiday_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A non-working day, from a holiday set or the `holidays` setting
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+    /// Unset for dates from the `holidays` setting
+    pub set_id: Option<String>,
+    pub set_name: Option<String>,
+}
+
+/// A holiday to add to a set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolidayInput {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+// Additional type aliases for common query results
+pub type TaskWithTags = (Task, Vec<Tag>);
+pub type ProjectWithTags = (Project, Vec<Tag>);
+
+// Query builder helpers
+#[derive(Debug, Default)]
+pub struct TaskFilter {
+    pub project_id: Option<String>,
+    pub parent_task_id: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub completed: Option<bool>,
+    pub archived: Option<bool>,
+    pub overdue: Option<bool>,
+}
+
+#[derive(Debug, Default)]
+pub struct ProjectFilter {
+    pub goal_id: Option<String>,
+    pub status: Option<ProjectStatus>,
+    pub completed: Option<bool>,
+    pub archived: Option<bool>,
+}
\ No newline at end of file
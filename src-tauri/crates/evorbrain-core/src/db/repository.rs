@@ -0,0 +1,7292 @@
+use sqlx::{FromRow, Row, SqliteConnection, SqlitePool, Transaction, Sqlite};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use super::models::{
+    ArchiveSummary, ArchivedAncestor, ArchivedItem, AreaContext, Attachment, AttachmentSearchHit,
+    AuditLogEntry, BadgeCounts, BlockedTask, Blocker, BulkTagResult, ChangeLogEntry,
+    ChronicPostponement, CompletionCounts, Context, DataImportReport, DataIssue, DataIssueKind,
+    DataRepairReport, DayLoad, DayPlanItem, Delegation, DeleteImpact, DigestGoal, DigestTask,
+    Effort, EffortRollup, EmailAccount, EmailAccountInput, EmailImport, EntityAttachmentUsage,
+    ExportResult, ExternalLink, ExternalLinkInput, Goal, GoalBreakdown, GoalInput, Holiday,
+    HolidayInput, HolidaySet, ImportCollision, ImportSection, Job, JobType, LifeArea,
+    LifeAreaBudget, MeetingActionItem, MeetingNoteSummary, MyDay, NOTE_REVIEW_INTERVAL_DAYS, Note,
+    NoteInput, NoteMention, NoteSearchScope, OpenTaskAge, PendingAttachmentContent, Person,
+    PersonInput, Pin, PinnedItem, Project, ProjectContents, ProjectImportReport, ProjectInput,
+    ProjectMergeReport, ProjectStatus, ProjectTaskCounts, ReferencedEntity, RemainingWork,
+    RepairStrategy, RestorePreview, ScriptHook, ScriptHookInput, SecureDeleteReport, SidebarCounts,
+    SomedayMaybe, StaleProject, StatsSnapshot, StatusCategory, StorageCounts, SwitcherEntry, Tag,
+    Tagging, Task, TaskDependency, TaskFocus, TaskInput, TaskOccurrence, TaskPriority,
+    TaskReferences, TaskSnooze, TaskTreeNode, TimeEntry, TodayExclusion, TodayExclusionReason,
+    UrgencyFactors, ViewPreferences, ViewPreferencesInput, ViewType, WaitingForGroup,
+    WaitingForItem, Webhook, WebhookInput, Workflow, WorkflowStatus, WorkflowStatusInput,
+};
+use super::batch::MAX_BATCH_OPERATIONS;
+use crate::checklists::{self, ChecklistItem};
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::frontmatter;
+use crate::meetings;
+use crate::references;
+use crate::validation::{
+    normalize_context_name, validate_id, validate_note_privacy, validate_person, validate_title,
+    validate_view_preferences, validate_workflow, MAX_TITLE_LENGTH,
+};
+
+/// Deepest subtask level `get_task_tree` follows; also stops parent cycles
+const MAX_TASK_TREE_DEPTH: i64 = 64;
+
+/// What a cascading life area restore brings back, as (table, filter) pairs
+/// for `restore_archived_with`, in restore order: tasks first, while their
+/// projects still carry the life area's archive time, then projects while
+/// their goals still do
+const LIFE_AREA_CASCADE: &[(&str, &str)] = &[
+    (
+        "tasks",
+        "project_id IN (SELECT p.id FROM projects p JOIN goals g ON g.id = p.goal_id \
+         WHERE g.life_area_id = ?2 AND p.archived_at = ?1)",
+    ),
+    ("projects", "goal_id IN (SELECT id FROM goals WHERE life_area_id = ?2 AND archived_at = ?1)"),
+    ("goals", "life_area_id = ?2"),
+    ("notes", "life_area_id = ?2"),
+];
+
+/// Notes and upcoming tasks returned by `get_goal_breakdown`
+const GOAL_BREAKDOWN_LIMIT: i64 = 5;
+
+/// Every parent reference between entities, as (table, entity type, column,
+/// parent table, parent entity type)
+const PARENT_LINKS: &[(&str, &str, &str, &str, &str)] = &[
+    ("goals", "goal", "life_area_id", "life_areas", "life_area"),
+    ("projects", "project", "goal_id", "goals", "goal"),
+    ("tasks", "task", "project_id", "projects", "project"),
+    ("tasks", "task", "parent_task_id", "tasks", "task"),
+    ("notes", "note", "task_id", "tasks", "task"),
+    ("notes", "note", "project_id", "projects", "project"),
+    ("notes", "note", "goal_id", "goals", "goal"),
+    ("notes", "note", "life_area_id", "life_areas", "life_area"),
+];
+
+pub struct Repository {
+    pool: Arc<SqlitePool>,
+}
+
+impl Repository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    // Transaction helper
+    pub async fn begin_transaction(&self) -> AppResult<Transaction<'_, Sqlite>> {
+        self.pool
+            .begin()
+            .await
+            .map_err(|e| AppError::database_error("begin transaction", e))
+    }
+
+    // Life Area operations
+    pub async fn create_life_area(&self, name: String, description: Option<String>, color: Option<String>, icon: Option<String>) -> AppResult<LifeArea> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        
+        sqlx::query(
+            r#"
+            INSERT INTO life_areas (id, name, description, color, icon, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#
+        )
+        .bind(&id)
+        .bind(&name)
+        .bind(&description)
+        .bind(&color)
+        .bind(&icon)
+        .bind(&now)
+        .bind(&now)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(LifeArea {
+            id,
+            name,
+            description,
+            color,
+            icon,
+            created_at: now,
+            updated_at: now,
+            archived_at: None,
+            context: None,
+        })
+    }
+
+    pub async fn get_life_areas(&self) -> AppResult<Vec<LifeArea>> {
+        let areas = sqlx::query_as::<_, LifeArea>(
+            r#"
+            SELECT id, name, description, color, icon, 
+                   created_at, updated_at, archived_at, context
+            FROM life_areas
+            WHERE archived_at IS NULL
+              AND id NOT IN (SELECT id FROM hidden_life_areas)
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(areas)
+    }
+    
+    pub async fn get_life_area(&self, id: &str) -> AppResult<LifeArea> {
+        sqlx::query_as::<_, LifeArea>(
+            r#"
+            SELECT id, name, description, color, icon, 
+                   created_at, updated_at, archived_at, context
+            FROM life_areas
+            WHERE id = ?1
+            "#
+        )
+        .bind(id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::not_found("Life area", id),
+            _ => AppError::database_error("get life area", e),
+        })
+    }
+    
+    pub async fn update_life_area(
+        &self, 
+        id: &str, 
+        name: String, 
+        description: Option<String>, 
+        color: Option<String>, 
+        icon: Option<String>
+    ) -> AppResult<LifeArea> {
+        sqlx::query(
+            r#"
+            UPDATE life_areas 
+            SET name = ?1, description = ?2, color = ?3, icon = ?4
+            WHERE id = ?5 AND archived_at IS NULL
+            "#
+        )
+        .bind(&name)
+        .bind(&description)
+        .bind(&color)
+        .bind(&icon)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("update life area", e))?;
+        
+        self.get_life_area(id).await
+    }
+
+    /// Marks a life area as personal or work; `None` shows it in every context
+    pub async fn set_life_area_context(&self, id: &str, context: Option<AreaContext>) -> AppResult<LifeArea> {
+        let result = sqlx::query("UPDATE life_areas SET context = ?1 WHERE id = ?2 AND archived_at IS NULL")
+            .bind(context)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set life area context", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Life area", id));
+        }
+        self.get_life_area(id).await
+    }
+
+    /// Sets the weekly time budget of a life area; `None` removes it
+    pub async fn set_life_area_budget(
+        &self,
+        life_area_id: &str,
+        weekly_minutes: Option<i64>,
+    ) -> AppResult<Option<LifeAreaBudget>> {
+        let area = self.get_life_area(life_area_id).await?;
+        if area.archived_at.is_some() {
+            return Err(AppError::validation_error("life_area_id", "is archived"));
+        }
+
+        let Some(weekly_minutes) = weekly_minutes else {
+            sqlx::query("DELETE FROM life_area_budgets WHERE life_area_id = ?1")
+                .bind(life_area_id)
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| AppError::database_error("remove life area budget", e))?;
+            return Ok(None);
+        };
+        sqlx::query_as::<_, LifeAreaBudget>(
+            r#"
+            INSERT INTO life_area_budgets (life_area_id, weekly_minutes, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(life_area_id) DO UPDATE SET
+                weekly_minutes = excluded.weekly_minutes,
+                updated_at = excluded.updated_at
+            RETURNING life_area_id, weekly_minutes, updated_at
+            "#
+        )
+        .bind(life_area_id)
+        .bind(weekly_minutes)
+        .bind(Utc::now())
+        .fetch_one(&*self.pool)
+        .await
+        .map(Some)
+        .map_err(|e| AppError::database_error("set life area budget", e))
+    }
+
+    pub async fn get_life_area_budgets(&self) -> AppResult<Vec<LifeAreaBudget>> {
+        sqlx::query_as::<_, LifeAreaBudget>("SELECT life_area_id, weekly_minutes, updated_at FROM life_area_budgets")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get life area budgets", e))
+    }
+
+    /// Counts the active goals, projects, tasks and notes that archiving the
+    /// life area would archive with it
+    async fn life_area_delete_impact(tx: &mut Transaction<'_, Sqlite>, id: &str) -> AppResult<DeleteImpact> {
+        sqlx::query_as::<_, DeleteImpact>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM goals WHERE life_area_id = ?1 AND archived_at IS NULL) AS goals,
+                (SELECT COUNT(*) FROM projects
+                 WHERE goal_id IN (SELECT id FROM goals WHERE life_area_id = ?1)
+                   AND archived_at IS NULL) AS projects,
+                (SELECT COUNT(*) FROM tasks
+                 WHERE project_id IN (
+                     SELECT p.id FROM projects p
+                     JOIN goals g ON p.goal_id = g.id
+                     WHERE g.life_area_id = ?1
+                 ) AND archived_at IS NULL) AS tasks,
+                (SELECT COUNT(*) FROM notes WHERE life_area_id = ?1 AND archived_at IS NULL) AS notes
+            "#
+        )
+        .bind(id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("get delete impact", e))
+    }
+
+    pub async fn get_delete_impact(&self, id: &str) -> AppResult<DeleteImpact> {
+        self.get_life_area(id).await?;
+        let mut tx = self.begin_transaction().await?;
+        Self::life_area_delete_impact(&mut tx, id).await
+    }
+
+    /// Archives a life area with everything in it; unless `force` is set,
+    /// refuses with `CannotDelete` (the impact in the error data) when that
+    /// would archive anything besides the life area itself
+    pub async fn delete_life_area(&self, id: &str, force: bool) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        Self::archive_life_area_in(&mut tx, id, force, Utc::now()).await?;
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit cascade delete", e))
+    }
+
+    async fn archive_life_area_in(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        force: bool,
+        now: DateTime<Utc>,
+    ) -> AppResult<()> {
+        if !force {
+            let impact = Self::life_area_delete_impact(tx, id).await?;
+            if impact.total() > 0 {
+                return Err(AppError::new(
+                    ErrorCode::CannotDelete,
+                    format!(
+                        "Deleting this life area also archives {} goals, {} projects, {} tasks and {} notes; \
+                         pass force to confirm",
+                        impact.goals, impact.projects, impact.tasks, impact.notes
+                    ),
+                )
+                .with_data(serde_json::to_value(&impact)?));
+            }
+        }
+        
+        // Archive the life area
+        let result = sqlx::query(
+            r#"
+            UPDATE life_areas 
+            SET archived_at = ?1
+            WHERE id = ?2 AND archived_at IS NULL
+            "#
+        )
+        .bind(&now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("delete life area", e))?;
+        
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Life area", id));
+        }
+        
+        // Cascade archive to all goals in this life area
+        sqlx::query(
+            r#"
+            UPDATE goals 
+            SET archived_at = ?1
+            WHERE life_area_id = ?2 AND archived_at IS NULL
+            "#
+        )
+        .bind(&now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("cascade delete goals", e))?;
+        
+        // Cascade archive to all projects in goals of this life area
+        sqlx::query(
+            r#"
+            UPDATE projects 
+            SET archived_at = ?1
+            WHERE goal_id IN (
+                SELECT id FROM goals WHERE life_area_id = ?2
+            ) AND archived_at IS NULL
+            "#
+        )
+        .bind(&now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("cascade delete projects", e))?;
+        
+        // Cascade archive to all tasks in projects of goals in this life area
+        sqlx::query(
+            r#"
+            UPDATE tasks 
+            SET archived_at = ?1
+            WHERE project_id IN (
+                SELECT p.id FROM projects p
+                JOIN goals g ON p.goal_id = g.id
+                WHERE g.life_area_id = ?2
+            ) AND archived_at IS NULL
+            "#
+        )
+        .bind(&now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("cascade delete tasks", e))?;
+        
+        // Cascade archive to all notes associated with this life area
+        sqlx::query(
+            r#"
+            UPDATE notes 
+            SET archived_at = ?1
+            WHERE life_area_id = ?2 AND archived_at IS NULL
+            "#
+        )
+        .bind(&now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("cascade delete notes", e))?;
+        
+        Ok(())
+    }
+    
+    /// Restores an archived life area; with `cascade`, also everything that
+    /// was archived together with it (see `get_archive_summary`)
+    pub async fn restore_life_area(&self, id: &str, cascade: bool) -> AppResult<LifeArea> {
+        let mut tx = self.begin_transaction().await?;
+        let archived_at = Self::archived_at(&mut tx, "life_areas", "life area", id).await?;
+
+        if cascade {
+            for (table, filter) in LIFE_AREA_CASCADE {
+                Self::restore_archived_with(&mut tx, table, filter, archived_at, id).await?;
+            }
+        }
+        Self::restore_archived_with(&mut tx, "life_areas", "id = ?2", archived_at, id).await?;
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit life area restore", e))?;
+        self.get_life_area(id).await
+    }
+
+    /// What was archived together with an archived life area, what was
+    /// archived separately before, and exactly what a cascading restore
+    /// would bring back
+    pub async fn get_archive_summary(&self, id: &str) -> AppResult<ArchiveSummary> {
+        let life_area = self.get_life_area(id).await?;
+        let Some(archived_at) = life_area.archived_at else {
+            return Err(AppError::not_found("Archived life area", id));
+        };
+
+        let mut preview = RestorePreview::default();
+        for (table, filter) in LIFE_AREA_CASCADE {
+            let items = sqlx::query_as::<_, ArchivedItem>(&format!(
+                "SELECT id, title FROM {} WHERE archived_at = ?1 AND {} ORDER BY created_at",
+                table, filter
+            ))
+            .bind(archived_at)
+            .bind(id)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get restore preview", e))?;
+            match *table {
+                "goals" => preview.goals = items,
+                "projects" => preview.projects = items,
+                "tasks" => preview.tasks = items,
+                _ => preview.notes = items,
+            }
+        }
+
+        let archived_separately = sqlx::query_as::<_, DeleteImpact>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM goals WHERE life_area_id = ?1 AND archived_at != ?2) AS goals,
+                (SELECT COUNT(*) FROM projects
+                 WHERE goal_id IN (SELECT id FROM goals WHERE life_area_id = ?1)
+                   AND archived_at != ?2) AS projects,
+                (SELECT COUNT(*) FROM tasks
+                 WHERE project_id IN (
+                     SELECT p.id FROM projects p
+                     JOIN goals g ON p.goal_id = g.id
+                     WHERE g.life_area_id = ?1
+                 ) AND archived_at != ?2) AS tasks,
+                (SELECT COUNT(*) FROM notes WHERE life_area_id = ?1 AND archived_at != ?2) AS notes
+            "#
+        )
+        .bind(id)
+        .bind(archived_at)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("count separately archived", e))?;
+
+        Ok(ArchiveSummary {
+            archived_at,
+            cascaded: DeleteImpact {
+                goals: preview.goals.len() as i64,
+                projects: preview.projects.len() as i64,
+                tasks: preview.tasks.len() as i64,
+                notes: preview.notes.len() as i64,
+            },
+            archived_separately,
+            restore_preview: preview,
+            life_area,
+        })
+    }
+
+    // Goal operations
+    pub async fn create_goal(&self, input: GoalInput) -> AppResult<Goal> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO goals (id, life_area_id, title, description, target_date, created_at, updated_at, color, icon)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.life_area_id)
+        .bind(&input.title)
+        .bind(&input.description)
+        .bind(input.target_date)
+        .bind(now)
+        .bind(now)
+        .bind(&input.color)
+        .bind(&input.icon)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("create goal", e))?;
+
+        self.get_goal(&id).await
+    }
+
+    /// Goals that are neither archived nor parked on the someday/maybe list,
+    /// leaving out those hidden by the active context
+    pub async fn get_goals(&self) -> AppResult<Vec<Goal>> {
+        sqlx::query_as::<_, Goal>(
+            r#"
+            SELECT id, life_area_id, title, description, target_date,
+                   created_at, updated_at, completed_at, archived_at, incubated_at, sort_order, progress,
+                   color, icon,
+                   (SELECT display_color FROM goal_appearance a WHERE a.id = goals.id) AS display_color,
+                   (SELECT display_icon FROM goal_appearance a WHERE a.id = goals.id) AS display_icon
+            FROM goals
+            WHERE archived_at IS NULL AND incubated_at IS NULL
+              AND id NOT IN (SELECT id FROM hidden_goals)
+            ORDER BY sort_order, created_at
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get goals", e))
+    }
+
+    pub async fn get_goals_by_life_area(&self, life_area_id: &str) -> AppResult<Vec<Goal>> {
+        sqlx::query_as::<_, Goal>(
+            r#"
+            SELECT id, life_area_id, title, description, target_date,
+                   created_at, updated_at, completed_at, archived_at, incubated_at, sort_order, progress,
+                   color, icon,
+                   (SELECT display_color FROM goal_appearance a WHERE a.id = goals.id) AS display_color,
+                   (SELECT display_icon FROM goal_appearance a WHERE a.id = goals.id) AS display_icon
+            FROM goals
+            WHERE life_area_id = ?1 AND archived_at IS NULL AND incubated_at IS NULL
+            ORDER BY sort_order, created_at
+            "#
+        )
+        .bind(life_area_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get goals by life area", e))
+    }
+
+    pub async fn get_goal(&self, id: &str) -> AppResult<Goal> {
+        sqlx::query_as::<_, Goal>(
+            r#"
+            SELECT id, life_area_id, title, description, target_date,
+                   created_at, updated_at, completed_at, archived_at, incubated_at, sort_order, progress,
+                   color, icon,
+                   (SELECT display_color FROM goal_appearance a WHERE a.id = goals.id) AS display_color,
+                   (SELECT display_icon FROM goal_appearance a WHERE a.id = goals.id) AS display_icon
+            FROM goals
+            WHERE id = ?1
+            "#
+        )
+        .bind(id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::not_found("Goal", id),
+            _ => AppError::database_error("get goal", e),
+        })
+    }
+
+    /// Everything a goal's detail page shows, loaded together
+    ///
+    /// Notes are those of the goal itself and of its projects and their
+    /// tasks; upcoming tasks are the open ones with a due date from `now` on.
+    pub async fn get_goal_breakdown(&self, id: &str, now: DateTime<Utc>) -> AppResult<GoalBreakdown> {
+        let goal = self.get_goal(id).await?;
+        let projects = self.get_projects_by_goal(id).await?;
+
+        let counts: Vec<(String, i64, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT project_id,
+                   COALESCE(SUM(completed_at IS NULL), 0),
+                   COALESCE(SUM(completed_at IS NOT NULL), 0),
+                   COALESCE(SUM(completed_at IS NULL AND due_date < ?2), 0)
+            FROM tasks
+            WHERE archived_at IS NULL
+              AND project_id IN (SELECT id FROM projects WHERE goal_id = ?1)
+            GROUP BY project_id
+            "#
+        )
+        .bind(id)
+        .bind(now)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("count goal tasks", e))?;
+
+        let projects: Vec<ProjectTaskCounts> = projects
+            .into_iter()
+            .map(|project| {
+                let (open_tasks, completed_tasks, overdue_tasks) = counts
+                    .iter()
+                    .find(|(project_id, ..)| *project_id == project.id)
+                    .map_or((0, 0, 0), |(_, open, completed, overdue)| (*open, *completed, *overdue));
+                ProjectTaskCounts {
+                    project,
+                    open_tasks,
+                    completed_tasks,
+                    overdue_tasks,
+                }
+            })
+            .collect();
+
+        let latest_notes = sqlx::query_as::<_, Note>(
+            r#"
+            SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
+                   created_at, updated_at, archived_at, is_private, note_date,
+                   review_stage, next_review_at, last_reviewed_at
+            FROM notes
+            WHERE archived_at IS NULL
+              AND (goal_id = ?1
+                   OR project_id IN (SELECT id FROM projects WHERE goal_id = ?1)
+                   OR task_id IN (SELECT t.id FROM tasks t JOIN projects p ON p.id = t.project_id
+                                  WHERE p.goal_id = ?1))
+            ORDER BY updated_at DESC
+            LIMIT ?2
+            "#
+        )
+        .bind(id)
+        .bind(GOAL_BREAKDOWN_LIMIT)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get goal notes", e))?;
+
+        let upcoming_tasks = sqlx::query_as::<_, Task>(
+            r#"
+            SELECT t.* FROM tasks t
+            JOIN projects p ON p.id = t.project_id
+            WHERE p.goal_id = ?1 AND p.archived_at IS NULL
+              AND t.archived_at IS NULL AND t.completed_at IS NULL
+              AND t.due_date >= ?2
+            ORDER BY t.due_date
+            LIMIT ?3
+            "#
+        )
+        .bind(id)
+        .bind(now)
+        .bind(GOAL_BREAKDOWN_LIMIT)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get goal upcoming tasks", e))?;
+
+        let open_tasks = projects.iter().map(|p| p.open_tasks).sum();
+        let completed_tasks = projects.iter().map(|p| p.completed_tasks).sum();
+        Ok(GoalBreakdown {
+            goal,
+            projects,
+            latest_notes,
+            upcoming_tasks,
+            open_tasks,
+            completed_tasks,
+        })
+    }
+
+    pub async fn update_goal(&self, id: &str, input: GoalInput) -> AppResult<Goal> {
+        sqlx::query(
+            r#"
+            UPDATE goals
+            SET life_area_id = ?1, title = ?2, description = ?3, target_date = ?4, color = ?5, icon = ?6
+            WHERE id = ?7
+            "#
+        )
+        .bind(&input.life_area_id)
+        .bind(&input.title)
+        .bind(&input.description)
+        .bind(input.target_date)
+        .bind(&input.color)
+        .bind(&input.icon)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("update goal", e))?;
+
+        self.get_goal(id).await
+    }
+
+    /// Marks a goal completed now, or reopens it
+    pub async fn set_goal_completed(&self, id: &str, completed: bool) -> AppResult<Goal> {
+        sqlx::query("UPDATE goals SET completed_at = ?1 WHERE id = ?2")
+            .bind(completed.then(Utc::now))
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set goal completion", e))?;
+
+        self.get_goal(id).await
+    }
+
+    // Task operations
+
+    /// Unarchived tasks, leaving out those hidden by the active context
+    pub async fn get_tasks(&self) -> AppResult<Vec<Task>> {
+        sqlx::query_as::<_, Task>(
+            r#"
+            SELECT id, project_id, parent_task_id, title, description, priority, due_date,
+                   created_at, updated_at, completed_at, archived_at, status, estimated_minutes,
+                   actual_minutes, recurrence_rule,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = tasks.project_id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = tasks.project_id) AS display_icon
+            FROM tasks
+            WHERE archived_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = tasks.project_id)
+            ORDER BY 
+                CASE priority 
+                    WHEN 'urgent' THEN 1
+                    WHEN 'high' THEN 2
+                    WHEN 'medium' THEN 3
+                    WHEN 'low' THEN 4
+                END,
+                due_date ASC NULLS LAST,
+                created_at DESC
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get tasks", e))
+    }
+
+    pub async fn get_task(&self, id: &str) -> AppResult<Task> {
+        sqlx::query_as::<_, Task>(
+            r#"
+            SELECT id, project_id, parent_task_id, title, description, priority, due_date,
+                   created_at, updated_at, completed_at, archived_at, status, estimated_minutes,
+                   actual_minutes, recurrence_rule,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = tasks.project_id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = tasks.project_id) AS display_icon
+            FROM tasks
+            WHERE id = ?1
+            "#
+        )
+        .bind(id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::not_found("Task", id),
+            _ => AppError::database_error("get task", e),
+        })
+    }
+
+    pub async fn create_task(&self, input: TaskInput) -> AppResult<Task> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, due_date, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.project_id)
+        .bind(&input.parent_task_id)
+        .bind(&input.title)
+        .bind(&input.description)
+        .bind(input.priority.to_string())
+        .bind(&input.due_date)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("create task", e))?;
+
+        Self::write_task_references(&mut tx, &id, input.description.as_deref()).await?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit task", e))?;
+
+        self.get_task(&id).await
+    }
+
+    /// Counts open tasks due today, overdue and in the inbox with one query
+    ///
+    /// `today_start`/`tomorrow_start` bound the current day; anything due
+    /// before `today_start` is overdue.
+    pub async fn get_badge_counts(
+        &self,
+        today_start: DateTime<Utc>,
+        tomorrow_start: DateTime<Utc>,
+    ) -> AppResult<BadgeCounts> {
+        sqlx::query_as::<_, BadgeCounts>(
+            r#"
+            SELECT
+                COALESCE(SUM(due_date >= ?1 AND due_date < ?2), 0) AS due_today,
+                COALESCE(SUM(due_date < ?1), 0) AS overdue,
+                COALESCE(SUM(project_id IS NULL), 0) AS inbox
+            FROM tasks
+            WHERE archived_at IS NULL AND completed_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = tasks.project_id)
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = tasks.project_id)
+            "#
+        )
+        .bind(&today_start)
+        .bind(&tomorrow_start)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get badge counts", e))
+    }
+
+    /// Open task counts per life area, goal and project, with the inbox,
+    /// due today and overdue totals, from one grouped query
+    pub async fn get_sidebar_counts(
+        &self,
+        today_start: DateTime<Utc>,
+        tomorrow_start: DateTime<Utc>,
+    ) -> AppResult<SidebarCounts> {
+        // Project, goal, life area, open, due today, overdue
+        type ProjectCounts = (Option<String>, Option<String>, Option<String>, i64, i64, i64);
+
+        // One row per project (and one for tasks without a project), rolled
+        // up to goals and life areas below
+        let rows: Vec<ProjectCounts> = sqlx::query_as(
+            r#"
+            SELECT t.project_id, p.goal_id, g.life_area_id,
+                   COUNT(*),
+                   COALESCE(SUM(t.due_date >= ?1 AND t.due_date < ?2), 0),
+                   COALESCE(SUM(t.due_date < ?1), 0)
+            FROM tasks t
+            LEFT JOIN projects p ON p.id = t.project_id
+            LEFT JOIN goals g ON g.id = p.goal_id
+            WHERE t.archived_at IS NULL AND t.completed_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = t.project_id)
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = t.project_id)
+            GROUP BY t.project_id
+            "#
+        )
+        .bind(today_start)
+        .bind(tomorrow_start)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get sidebar counts", e))?;
+
+        let mut counts = SidebarCounts::default();
+        for (project_id, goal_id, life_area_id, open, due_today, overdue) in rows {
+            counts.due_today += due_today;
+            counts.overdue += overdue;
+            let Some(project_id) = project_id else {
+                counts.inbox += open;
+                continue;
+            };
+            counts.projects.insert(project_id, open);
+            if let Some(goal_id) = goal_id {
+                *counts.goals.entry(goal_id).or_default() += open;
+            }
+            if let Some(life_area_id) = life_area_id {
+                *counts.life_areas.entry(life_area_id).or_default() += open;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Urgency inputs for every open, unarchived task
+    pub async fn get_urgency_factors(&self) -> AppResult<Vec<UrgencyFactors>> {
+        sqlx::query_as::<_, UrgencyFactors>(
+            r#"
+            SELECT t.id AS task_id,
+                   (SELECT COUNT(*) FROM task_tags tt WHERE tt.task_id = t.id) AS tag_count,
+                   EXISTS (SELECT 1 FROM task_dependencies d JOIN tasks w ON w.id = d.task_id
+                           WHERE d.depends_on_id = t.id AND w.completed_at IS NULL AND w.archived_at IS NULL)
+                   OR EXISTS (SELECT 1 FROM tasks p
+                              WHERE p.id = t.parent_task_id AND p.completed_at IS NULL AND p.archived_at IS NULL) AS blocking,
+                   EXISTS (SELECT 1 FROM task_dependencies d JOIN tasks b ON b.id = d.depends_on_id
+                           WHERE d.task_id = t.id AND b.completed_at IS NULL AND b.archived_at IS NULL)
+                   OR EXISTS (SELECT 1 FROM tasks s
+                              WHERE s.parent_task_id = t.id AND s.completed_at IS NULL AND s.archived_at IS NULL) AS blocked,
+                   EXISTS (SELECT 1 FROM effective_workflow_statuses w
+                           WHERE w.project_id IS t.project_id AND w.key = t.status
+                             AND w.category = 'in_progress') AS in_progress
+            FROM tasks t
+            WHERE t.archived_at IS NULL AND t.completed_at IS NULL
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get urgency factors", e))
+    }
+
+    /// Creates a task with a single statement for quick capture
+    ///
+    /// `project_id` is resolved inside the insert: when it does not match an
+    /// active project the task lands in the inbox instead of failing.
+    pub async fn quick_add_task(&self, title: &str, project_id: Option<&str>) -> AppResult<Task> {
+        let now = Utc::now();
+
+        sqlx::query_as::<_, Task>(
+            r#"
+            INSERT INTO tasks (id, project_id, title, priority, created_at, updated_at)
+            VALUES (
+                ?1,
+                (SELECT id FROM projects WHERE id = ?2 AND archived_at IS NULL),
+                ?3, ?4, ?5, ?6
+            )
+            RETURNING id, project_id, parent_task_id, title, description, priority, due_date,
+                      created_at, updated_at, completed_at, archived_at, status, estimated_minutes,
+                      actual_minutes, recurrence_rule
+            "#
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(project_id)
+        .bind(title)
+        .bind(TaskPriority::default().to_string())
+        .bind(&now)
+        .bind(&now)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("quick add task", e))
+    }
+
+    pub async fn update_task(&self, id: &str, input: TaskInput) -> AppResult<Task> {
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE tasks 
+            SET project_id = ?1, parent_task_id = ?2, title = ?3, description = ?4, 
+                priority = ?5, due_date = ?6
+            WHERE id = ?7
+            "#
+        )
+        .bind(&input.project_id)
+        .bind(&input.parent_task_id)
+        .bind(&input.title)
+        .bind(&input.description)
+        .bind(input.priority.to_string())
+        .bind(&input.due_date)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("update task", e))?;
+
+        Self::write_task_references(&mut tx, id, input.description.as_deref()).await?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit task", e))?;
+
+        self.get_task(id).await
+    }
+
+    /// Replaces the references and extracted links of a task with those
+    /// found in its description
+    async fn write_task_references(
+        tx: &mut Transaction<'_, Sqlite>,
+        task_id: &str,
+        description: Option<&str>,
+    ) -> AppResult<()> {
+        let found = references::extract(description.unwrap_or_default());
+
+        sqlx::query("DELETE FROM task_references WHERE task_id = ?1")
+            .bind(task_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("clear task references", e))?;
+        for entity in &found.entities {
+            if entity.entity_type == "task" && entity.id == task_id {
+                continue;
+            }
+            sqlx::query("INSERT OR IGNORE INTO task_references (task_id, entity_type, entity_id) VALUES (?1, ?2, ?3)")
+                .bind(task_id)
+                .bind(&entity.entity_type)
+                .bind(&entity.id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("add task reference", e))?;
+        }
+
+        let known: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, url FROM external_links WHERE entity_type = 'task' AND entity_id = ?1 AND extracted = 1",
+        )
+        .bind(task_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("get extracted links", e))?;
+        for (id, url) in &known {
+            if !found.urls.contains(url) {
+                sqlx::query("DELETE FROM external_links WHERE id = ?1")
+                    .bind(id)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| AppError::database_error("remove extracted link", e))?;
+            }
+        }
+
+        let now = Utc::now();
+        for url in found.urls.iter().filter(|url| !known.iter().any(|(_, known)| known == *url)) {
+            // A link the user added by hand already covers the URL
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO external_links (id, entity_type, entity_id, url, provider, extracted,
+                                                      created_at, updated_at)
+                VALUES (?1, 'task', ?2, ?3, 'web', 1, ?4, ?4)
+                "#
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(task_id)
+            .bind(url)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("add extracted link", e))?;
+        }
+        Ok(())
+    }
+
+    /// The external links of a task, the entities its description refers to
+    /// and the tasks that refer to it
+    pub async fn get_task_references(&self, task_id: &str) -> AppResult<TaskReferences> {
+        self.get_task(task_id).await?;
+
+        let references = sqlx::query_as::<_, ReferencedEntity>(
+            r#"
+            SELECT r.entity_type, r.entity_id AS id,
+                   COALESCE(t.title, p.title, g.title, n.title) AS title,
+                   COALESCE(t.archived_at, p.archived_at, g.archived_at, n.archived_at) IS NOT NULL AS archived
+            FROM task_references r
+            LEFT JOIN tasks t ON r.entity_type = 'task' AND t.id = r.entity_id
+            LEFT JOIN projects p ON r.entity_type = 'project' AND p.id = r.entity_id
+            LEFT JOIN goals g ON r.entity_type = 'goal' AND g.id = r.entity_id
+            LEFT JOIN notes n ON r.entity_type = 'note' AND n.id = r.entity_id
+            WHERE r.task_id = ?1 AND COALESCE(t.title, p.title, g.title, n.title) IS NOT NULL
+            ORDER BY r.created_at, r.rowid
+            "#
+        )
+        .bind(task_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get task references", e))?;
+
+        let referenced_by = sqlx::query_as::<_, ReferencedEntity>(
+            r#"
+            SELECT 'task' AS entity_type, t.id, t.title, t.archived_at IS NOT NULL AS archived
+            FROM task_references r
+            JOIN tasks t ON t.id = r.task_id
+            WHERE r.entity_type = 'task' AND r.entity_id = ?1
+            ORDER BY t.created_at
+            "#
+        )
+        .bind(task_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get task backlinks", e))?;
+
+        Ok(TaskReferences {
+            links: self.get_external_links_for("task", task_id).await?,
+            references,
+            referenced_by,
+        })
+    }
+
+    /// A task with its unarchived subtasks at every level, fetched with one
+    /// recursive query; siblings are ordered by creation
+    pub async fn get_task_tree(&self, root_id: &str) -> AppResult<TaskTreeNode> {
+        let rows = sqlx::query_as::<_, TaskTreeNode>(
+            r#"
+            WITH RECURSIVE tree(id, depth) AS (
+                SELECT id, 0 FROM tasks WHERE id = ?1
+                UNION
+                SELECT t.id, tree.depth + 1
+                FROM tasks t
+                JOIN tree ON t.parent_task_id = tree.id
+                WHERE t.archived_at IS NULL AND tree.depth < ?2
+            )
+            SELECT t.id, t.project_id, t.parent_task_id, t.title, t.description, t.priority, t.due_date,
+                   t.created_at, t.updated_at, t.completed_at, t.archived_at, t.status, t.estimated_minutes,
+                   t.actual_minutes, t.recurrence_rule,
+                   MIN(tree.depth) AS depth
+            FROM tree
+            JOIN tasks t ON t.id = tree.id
+            GROUP BY t.id
+            ORDER BY depth, t.created_at
+            "#
+        )
+        .bind(root_id)
+        .bind(MAX_TASK_TREE_DEPTH)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get task tree", e))?;
+
+        // Deepest rows come first in reverse, so every node's children are
+        // complete by the time the node itself is attached
+        let mut children: std::collections::HashMap<String, Vec<TaskTreeNode>> = std::collections::HashMap::new();
+        let mut root = None;
+        for mut node in rows.into_iter().rev() {
+            node.children = children.remove(&node.task.id).unwrap_or_default();
+            node.children.reverse();
+            match (node.depth, node.task.parent_task_id.clone()) {
+                (0, _) => root = Some(node),
+                (_, Some(parent_id)) => children.entry(parent_id).or_default().push(node),
+                (_, None) => {}
+            }
+        }
+        root.ok_or_else(|| AppError::not_found("Task", root_id))
+    }
+
+    // Task operations with transactions
+    pub async fn create_task_with_subtasks(
+        &self, 
+        task: Task, 
+        subtasks: Vec<Task>
+    ) -> AppResult<String> {
+        let mut tx = self.begin_transaction().await?;
+        
+        // Insert main task
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, due_date, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#
+        )
+        .bind(&task.id)
+        .bind(&task.project_id)
+        .bind(&task.parent_task_id)
+        .bind(&task.title)
+        .bind(&task.description)
+        .bind(task.priority.to_string())
+        .bind(&task.due_date)
+        .bind(&task.created_at)
+        .bind(&task.updated_at)
+        .execute(&mut *tx)
+        .await?;
+
+        // Insert subtasks
+        for subtask in subtasks {
+            sqlx::query(
+                r#"
+                INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, due_date, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                "#
+            )
+            .bind(&subtask.id)
+            .bind(&subtask.project_id)
+            .bind(&task.id) // Parent is the main task
+            .bind(&subtask.title)
+            .bind(&subtask.description)
+            .bind(subtask.priority.to_string())
+            .bind(&subtask.due_date)
+            .bind(&subtask.created_at)
+            .bind(&subtask.updated_at)
+            .execute(&mut *tx)
+            .await?;
+            Self::write_task_references(&mut tx, &subtask.id, subtask.description.as_deref()).await?;
+        }
+        Self::write_task_references(&mut tx, &task.id, task.description.as_deref()).await?;
+
+        tx.commit().await?;
+        Ok(task.id)
+    }
+
+    /// Changes only a task's due date; `None` clears it
+    pub async fn set_task_due_date(&self, id: &str, due_date: Option<DateTime<Utc>>) -> AppResult<Task> {
+        let result = sqlx::query("UPDATE tasks SET due_date = ?1 WHERE id = ?2")
+            .bind(due_date)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set task due date", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Task", id));
+        }
+        self.get_task(id).await
+    }
+
+    pub async fn set_task_estimate(&self, id: &str, minutes: Option<u32>) -> AppResult<Task> {
+        let result = sqlx::query("UPDATE tasks SET estimated_minutes = ?1 WHERE id = ?2")
+            .bind(minutes)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set task estimate", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Task", id));
+        }
+        self.get_task(id).await
+    }
+
+    /// Overwrites the time spent on a task; timers stopped later add to it
+    pub async fn set_task_actual_minutes(&self, id: &str, minutes: u32) -> AppResult<Task> {
+        let result = sqlx::query("UPDATE tasks SET actual_minutes = ?1 WHERE id = ?2")
+            .bind(minutes)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set task actual minutes", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Task", id));
+        }
+        self.get_task(id).await
+    }
+
+    /// Sets or clears a task's recurrence rule; `rule` must already be
+    /// normalized by `validation::normalize_recurrence_rule`
+    pub async fn set_task_recurrence(&self, id: &str, rule: Option<&str>) -> AppResult<Task> {
+        let result = sqlx::query("UPDATE tasks SET recurrence_rule = ?1 WHERE id = ?2")
+            .bind(rule)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set task recurrence", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Task", id));
+        }
+        self.get_task(id).await
+    }
+
+    pub async fn get_tasks_by_project(&self, project_id: &str) -> AppResult<Vec<Task>> {
+        sqlx::query_as::<_, Task>(
+            r#"
+            SELECT id, project_id, parent_task_id, title, description, priority, due_date,
+                   created_at, updated_at, completed_at, archived_at, status, estimated_minutes,
+                   actual_minutes, recurrence_rule,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = tasks.project_id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = tasks.project_id) AS display_icon
+            FROM tasks
+            WHERE project_id = ?1 AND archived_at IS NULL
+            ORDER BY
+                CASE priority
+                    WHEN 'urgent' THEN 1
+                    WHEN 'high' THEN 2
+                    WHEN 'medium' THEN 3
+                    WHEN 'low' THEN 4
+                END,
+                due_date ASC NULLS LAST,
+                created_at DESC
+            "#
+        )
+        .bind(project_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get tasks by project", e))
+    }
+
+    pub async fn get_subtasks(&self, parent_task_id: &str) -> AppResult<Vec<Task>> {
+        sqlx::query_as::<_, Task>(
+            r#"
+            SELECT id, project_id, parent_task_id, title, description, priority, due_date,
+                   created_at, updated_at, completed_at, archived_at, status, estimated_minutes,
+                   actual_minutes, recurrence_rule,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = tasks.project_id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = tasks.project_id) AS display_icon
+            FROM tasks
+            WHERE parent_task_id = ?1 AND archived_at IS NULL
+            ORDER BY created_at ASC
+            "#
+        )
+        .bind(parent_task_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get subtasks", e))
+    }
+
+    /// Open tasks due within `[start, end)` plus every urgent one, leaving out
+    /// tasks of incubated projects and those hidden by the active context
+    pub async fn get_todays_tasks(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> AppResult<Vec<Task>> {
+        sqlx::query_as::<_, Task>(
+            r#"
+            SELECT id, project_id, parent_task_id, title, description, priority, due_date,
+                   created_at, updated_at, completed_at, archived_at, status, estimated_minutes,
+                   actual_minutes, recurrence_rule,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = tasks.project_id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = tasks.project_id) AS display_icon
+            FROM tasks
+            WHERE archived_at IS NULL
+              AND completed_at IS NULL
+              AND (
+                  (due_date >= ?1 AND due_date < ?2)
+                  OR priority = 'urgent'
+              )
+              AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = tasks.project_id)
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = tasks.project_id)
+              AND NOT EXISTS (SELECT 1 FROM task_snoozes s WHERE s.task_id = tasks.id AND s.snoozed_until > ?3)
+              AND NOT EXISTS (
+                  SELECT 1 FROM task_dependencies d JOIN tasks b ON b.id = d.depends_on_id
+                  WHERE d.task_id = tasks.id AND b.completed_at IS NULL AND b.archived_at IS NULL
+              )
+            ORDER BY
+                CASE priority
+                    WHEN 'urgent' THEN 1
+                    WHEN 'high' THEN 2
+                    WHEN 'medium' THEN 3
+                    WHEN 'low' THEN 4
+                END,
+                due_date ASC NULLS LAST
+            "#
+        )
+        .bind(start)
+        .bind(end)
+        .bind(Utc::now())
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get today's tasks", e))
+    }
+
+    /// Tasks `get_todays_tasks` would list but leaves out because they are
+    /// blocked by open dependencies or snoozed
+    pub async fn get_todays_exclusions(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> AppResult<Vec<TodayExclusion>> {
+        type Row = (String, String, TaskPriority, Option<DateTime<Utc>>, Option<DateTime<Utc>>, Option<String>);
+        let rows = sqlx::query_as::<_, Row>(
+            r#"
+            WITH candidates AS (
+                SELECT t.id, t.title, t.priority, t.due_date, s.snoozed_until,
+                       (SELECT GROUP_CONCAT(d.depends_on_id) FROM task_dependencies d
+                        JOIN tasks b ON b.id = d.depends_on_id
+                        WHERE d.task_id = t.id AND b.completed_at IS NULL AND b.archived_at IS NULL) AS blocked_by
+                FROM tasks t
+                LEFT JOIN task_snoozes s ON s.task_id = t.id AND s.snoozed_until > ?3
+                WHERE t.archived_at IS NULL
+                  AND t.completed_at IS NULL
+                  AND ((t.due_date >= ?1 AND t.due_date < ?2) OR t.priority = 'urgent')
+                  AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = t.project_id)
+                  AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = t.project_id)
+            )
+            SELECT id, title, priority, due_date, snoozed_until, blocked_by
+            FROM candidates
+            WHERE snoozed_until IS NOT NULL OR blocked_by IS NOT NULL
+            ORDER BY due_date ASC NULLS LAST, title
+            "#
+        )
+        .bind(start)
+        .bind(end)
+        .bind(Utc::now())
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get today's exclusions", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(task_id, title, priority, due_date, snoozed_until, blocked_by)| {
+                let blocked_by: Vec<String> = blocked_by
+                    .map(|ids| ids.split(',').map(str::to_string).collect())
+                    .unwrap_or_default();
+                TodayExclusion {
+                    task_id,
+                    title,
+                    priority,
+                    due_date,
+                    reason: if blocked_by.is_empty() {
+                        TodayExclusionReason::Snoozed
+                    } else {
+                        TodayExclusionReason::Blocked
+                    },
+                    blocked_by,
+                    snoozed_until,
+                }
+            })
+            .collect())
+    }
+
+    /// Completes a task; a task blocked by open dependencies can't be
+    /// completed
+    pub async fn complete_task(&self, task_id: &str) -> AppResult<()> {
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+        Self::ensure_unblocked_in(&mut tx, task_id).await?;
+        
+        sqlx::query(
+            r#"
+            UPDATE tasks 
+            SET completed_at = ?1
+            WHERE id = ?2
+            "#
+        )
+        .bind(&now)
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit task completion", e))
+    }
+
+    pub async fn uncomplete_task(&self, task_id: &str) -> AppResult<()> {
+        sqlx::query("UPDATE tasks SET completed_at = NULL WHERE id = ?1")
+            .bind(task_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("uncomplete task", e))?;
+
+        Ok(())
+    }
+
+    // Archive operations with cascading
+    pub async fn archive_project_cascade(&self, project_id: &str) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        Self::archive_project_in(&mut tx, project_id, Utc::now()).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn archive_project_in(tx: &mut Transaction<'_, Sqlite>, project_id: &str, now: DateTime<Utc>) -> AppResult<()> {
+        // Archive the project
+        let result = sqlx::query("UPDATE projects SET archived_at = ?1 WHERE id = ?2")
+            .bind(&now)
+            .bind(project_id)
+            .execute(&mut **tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Project", project_id));
+        }
+
+        // Archive all tasks in the project
+        sqlx::query("UPDATE tasks SET archived_at = ?1 WHERE project_id = ?2 AND archived_at IS NULL")
+            .bind(&now)
+            .bind(project_id)
+            .execute(&mut **tx)
+            .await?;
+
+        // Archive all notes associated with the project
+        sqlx::query("UPDATE notes SET archived_at = ?1 WHERE project_id = ?2 AND archived_at IS NULL")
+            .bind(&now)
+            .bind(project_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    // Archive operations for goals with cascading
+    pub async fn archive_goal_cascade(&self, goal_id: &str) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        Self::archive_goal_in(&mut tx, goal_id, Utc::now()).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn archive_goal_in(tx: &mut Transaction<'_, Sqlite>, goal_id: &str, now: DateTime<Utc>) -> AppResult<()> {
+        // Archive the goal
+        let result = sqlx::query("UPDATE goals SET archived_at = ?1 WHERE id = ?2")
+            .bind(&now)
+            .bind(goal_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("archive goal", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Goal", goal_id));
+        }
+
+        // Archive all projects in the goal
+        sqlx::query("UPDATE projects SET archived_at = ?1 WHERE goal_id = ?2 AND archived_at IS NULL")
+            .bind(&now)
+            .bind(goal_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("cascade archive projects", e))?;
+
+        // Archive all tasks in projects of this goal
+        sqlx::query(
+            r#"
+            UPDATE tasks 
+            SET archived_at = ?1
+            WHERE project_id IN (
+                SELECT id FROM projects WHERE goal_id = ?2
+            ) AND archived_at IS NULL
+            "#
+        )
+        .bind(&now)
+        .bind(goal_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("cascade archive tasks", e))?;
+
+        // Archive all notes associated with the goal
+        sqlx::query("UPDATE notes SET archived_at = ?1 WHERE goal_id = ?2 AND archived_at IS NULL")
+            .bind(&now)
+            .bind(goal_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("cascade archive notes", e))?;
+
+        Ok(())
+    }
+
+    // Archive operations for tasks with cascading
+    pub async fn archive_task_cascade(&self, task_id: &str) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        Self::archive_task_in(&mut tx, task_id, Utc::now()).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn archive_task_in(tx: &mut Transaction<'_, Sqlite>, task_id: &str, now: DateTime<Utc>) -> AppResult<()> {
+        // Archive the task
+        let result = sqlx::query("UPDATE tasks SET archived_at = ?1 WHERE id = ?2")
+            .bind(&now)
+            .bind(task_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("archive task", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Task", task_id));
+        }
+
+        // Archive all subtasks
+        sqlx::query("UPDATE tasks SET archived_at = ?1 WHERE parent_task_id = ?2 AND archived_at IS NULL")
+            .bind(&now)
+            .bind(task_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("cascade archive subtasks", e))?;
+
+        // Archive all notes associated with the task
+        sqlx::query("UPDATE notes SET archived_at = ?1 WHERE task_id = ?2 AND archived_at IS NULL")
+            .bind(&now)
+            .bind(task_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("cascade archive notes", e))?;
+
+        Ok(())
+    }
+
+    // Restore operations with cascading
+    //
+    // With `cascade`, descendants archived at the same moment as the parent
+    // (i.e. by the archive cascade) are restored with it; ones archived on
+    // their own before keep their archived state.
+
+    /// Returns when an archived row was archived
+    async fn archived_at(
+        tx: &mut Transaction<'_, Sqlite>,
+        table: &str,
+        entity: &str,
+        id: &str,
+    ) -> AppResult<DateTime<Utc>> {
+        let archived_at: Option<DateTime<Utc>> =
+            sqlx::query_scalar(&format!("SELECT archived_at FROM {} WHERE id = ?1", table))
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("get archived state", e))?
+                .flatten();
+        archived_at.ok_or_else(|| AppError::not_found(&format!("Archived {}", entity), id))
+    }
+
+    /// Clears `archived_at` on the rows matching `filter` (which binds the
+    /// parent id as ?2) that were archived at `archived_at` (?1)
+    async fn restore_archived_with(
+        tx: &mut Transaction<'_, Sqlite>,
+        table: &str,
+        filter: &str,
+        archived_at: DateTime<Utc>,
+        parent_id: &str,
+    ) -> AppResult<()> {
+        sqlx::query(&format!(
+            "UPDATE {} SET archived_at = NULL WHERE archived_at = ?1 AND {}",
+            table, filter
+        ))
+        .bind(archived_at)
+        .bind(parent_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error(&format!("cascade restore {}", table), e))?;
+        Ok(())
+    }
+
+    /// Archived parents of an entity, in the order they have to be restored
+    async fn archived_ancestors(
+        tx: &mut Transaction<'_, Sqlite>,
+        entity_type: &str,
+        id: &str,
+    ) -> AppResult<Vec<ArchivedAncestor>> {
+        let mut ancestors = Vec::new();
+        let mut current = (entity_type.to_string(), id.to_string());
+        // Bounded so a corrupt parent cycle can't loop forever
+        for depth in 0..MAX_TASK_TREE_DEPTH + 3 {
+            let (table, title_column, parent_columns) = match current.0.as_str() {
+                "task" => ("tasks", "title", "parent_task_id, project_id"),
+                "project" => ("projects", "title", "NULL, goal_id"),
+                "goal" => ("goals", "title", "NULL, life_area_id"),
+                _ => ("life_areas", "name", "NULL, NULL"),
+            };
+            let Some(row) = sqlx::query(&format!(
+                "SELECT {}, archived_at IS NOT NULL, {} FROM {} WHERE id = ?1",
+                title_column, parent_columns, table
+            ))
+            .bind(&current.1)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("get archived parents", e))?
+            else {
+                break;
+            };
+
+            if depth > 0 && row.get::<bool, _>(1) {
+                ancestors.push(ArchivedAncestor {
+                    entity_type: current.0.clone(),
+                    id: current.1.clone(),
+                    title: row.get(0),
+                });
+            }
+
+            let parent_task: Option<String> = row.get(2);
+            let parent: Option<String> = row.get(3);
+            current = match (current.0.as_str(), parent_task, parent) {
+                ("task", Some(task_id), _) => ("task".to_string(), task_id),
+                ("task", None, Some(project_id)) => ("project".to_string(), project_id),
+                ("project", _, Some(goal_id)) => ("goal".to_string(), goal_id),
+                ("goal", _, Some(life_area_id)) => ("life_area".to_string(), life_area_id),
+                _ => break,
+            };
+        }
+        ancestors.reverse();
+        Ok(ancestors)
+    }
+
+    /// Fails with `ArchivedParent`, listing the parents in `data`, when any
+    /// parent of the entity is still archived, since a restored child would
+    /// stay hidden under it
+    async fn ensure_parents_active(
+        tx: &mut Transaction<'_, Sqlite>,
+        entity_type: &str,
+        id: &str,
+    ) -> AppResult<()> {
+        let ancestors = Self::archived_ancestors(tx, entity_type, id).await?;
+        if ancestors.is_empty() {
+            return Ok(());
+        }
+
+        let names: Vec<String> = ancestors
+            .iter()
+            .map(|a| format!("{} '{}'", a.entity_type.replace('_', " "), a.title))
+            .collect();
+        Err(AppError::new(
+            ErrorCode::ArchivedParent,
+            format!("Restore the archived {} first", names.join(", then ")),
+        )
+        .with_data(serde_json::json!({ "archived_parents": ancestors })))
+    }
+
+    pub async fn restore_project(&self, id: &str, cascade: bool) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        let archived_at = Self::archived_at(&mut tx, "projects", "project", id).await?;
+        Self::ensure_parents_active(&mut tx, "project", id).await?;
+
+        if cascade {
+            Self::restore_archived_with(&mut tx, "tasks", "project_id = ?2", archived_at, id).await?;
+            Self::restore_archived_with(&mut tx, "notes", "project_id = ?2", archived_at, id).await?;
+        }
+        Self::restore_archived_with(&mut tx, "projects", "id = ?2", archived_at, id).await?;
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit project restore", e))
+    }
+
+    pub async fn restore_goal(&self, id: &str, cascade: bool) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        let archived_at = Self::archived_at(&mut tx, "goals", "goal", id).await?;
+        Self::ensure_parents_active(&mut tx, "goal", id).await?;
+
+        if cascade {
+            // Tasks first, while their projects still carry the goal's timestamp
+            let in_cascaded_projects = "project_id IN (SELECT id FROM projects WHERE goal_id = ?2 AND archived_at = ?1)";
+            Self::restore_archived_with(&mut tx, "tasks", in_cascaded_projects, archived_at, id).await?;
+            Self::restore_archived_with(&mut tx, "projects", "goal_id = ?2", archived_at, id).await?;
+            Self::restore_archived_with(&mut tx, "notes", "goal_id = ?2", archived_at, id).await?;
+        }
+        Self::restore_archived_with(&mut tx, "goals", "id = ?2", archived_at, id).await?;
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit goal restore", e))
+    }
+
+    pub async fn restore_task(&self, id: &str, cascade: bool) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        let archived_at = Self::archived_at(&mut tx, "tasks", "task", id).await?;
+        Self::ensure_parents_active(&mut tx, "task", id).await?;
+
+        if cascade {
+            Self::restore_archived_with(&mut tx, "tasks", "parent_task_id = ?2", archived_at, id).await?;
+            Self::restore_archived_with(&mut tx, "notes", "task_id = ?2", archived_at, id).await?;
+        }
+        Self::restore_archived_with(&mut tx, "tasks", "id = ?2", archived_at, id).await?;
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit task restore", e))
+    }
+
+    // Sort order operations
+    /// Renumbers the children of one parent: `ids` first, in that order, then
+    /// the other children (archived ones included) in their current order
+    async fn reorder_children(
+        &self,
+        table: &str,
+        parent_column: &str,
+        parent_id: &str,
+        ids: &[String],
+        entity: &str,
+    ) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+
+        let current: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT id FROM {} WHERE {} = ?1 ORDER BY sort_order, created_at",
+            table, parent_column
+        ))
+        .bind(parent_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(&format!("reorder {}", table), e))?;
+
+        if let Some(unknown) = ids.iter().find(|id| !current.contains(id)) {
+            return Err(AppError::not_found(entity, unknown));
+        }
+        let order = ids.iter().chain(current.iter().filter(|id| !ids.contains(id)));
+
+        for (sort_order, id) in order.enumerate() {
+            sqlx::query(&format!("UPDATE {} SET sort_order = ?1 WHERE id = ?2", table))
+                .bind(sort_order as i64)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error(&format!("reorder {}", table), e))?;
+        }
+
+        tx.commit().await.map_err(|e| AppError::database_error(&format!("commit {} order", table), e))
+    }
+
+    pub async fn reorder_goals(&self, life_area_id: &str, goal_ids: &[String]) -> AppResult<()> {
+        self.reorder_children("goals", "life_area_id", life_area_id, goal_ids, "Goal in this life area")
+            .await
+    }
+
+    pub async fn reorder_projects(&self, goal_id: &str, project_ids: &[String]) -> AppResult<()> {
+        self.reorder_children("projects", "goal_id", goal_id, project_ids, "Project in this goal")
+            .await
+    }
+
+    /// Moves the tasks, notes and tags of `source_id` into `target_id`, then
+    /// archives the source and records the merge in the audit log
+    pub async fn merge_projects(&self, source_id: &str, target_id: &str) -> AppResult<ProjectMergeReport> {
+        if source_id == target_id {
+            return Err(AppError::validation_error("target_id", "must differ from the source project"));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+
+        let target_archived: Option<Option<DateTime<Utc>>> =
+            sqlx::query_scalar("SELECT archived_at FROM projects WHERE id = ?1")
+                .bind(target_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("get target project", e))?;
+        match target_archived {
+            None => return Err(AppError::not_found("Project", target_id)),
+            Some(Some(_)) => return Err(AppError::validation_error("target_id", "is archived")),
+            Some(None) => {}
+        }
+
+        // Statuses the target's workflow doesn't have are remapped by the
+        // task status triggers
+        let tasks_moved = sqlx::query("UPDATE tasks SET project_id = ?1 WHERE project_id = ?2")
+            .bind(target_id)
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("move tasks", e))?
+            .rows_affected();
+
+        let notes_moved = sqlx::query("UPDATE notes SET project_id = ?1 WHERE project_id = ?2")
+            .bind(target_id)
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("move notes", e))?
+            .rows_affected();
+
+        let tags_added = sqlx::query(
+            "INSERT OR IGNORE INTO project_tags (project_id, tag_id) SELECT ?1, tag_id FROM project_tags WHERE project_id = ?2"
+        )
+        .bind(target_id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("move project tags", e))?
+        .rows_affected();
+        sqlx::query("DELETE FROM project_tags WHERE project_id = ?1")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("move project tags", e))?;
+
+        // Nothing is left under the source, so this only archives the project
+        Self::archive_project_in(&mut tx, source_id, Utc::now()).await?;
+
+        let report = ProjectMergeReport { tasks_moved, notes_moved, tags_added };
+        Self::record_audit(
+            &mut tx,
+            "merge_projects",
+            "project",
+            source_id,
+            serde_json::json!({ "target_id": target_id, "result": report }),
+        )
+        .await?;
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit project merge", e))?;
+        Ok(report)
+    }
+
+    // Progress operations
+    async fn set_progress(&self, table: &str, entity: &str, id: &str, progress: u8) -> AppResult<()> {
+        let result = sqlx::query(&format!("UPDATE {} SET progress = ?1 WHERE id = ?2", table))
+            .bind(progress)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error(&format!("set {} progress", entity.to_lowercase()), e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found(entity, id));
+        }
+        Ok(())
+    }
+
+    pub async fn set_goal_progress(&self, id: &str, progress: u8) -> AppResult<Goal> {
+        self.set_progress("goals", "Goal", id, progress).await?;
+        self.get_goal(id).await
+    }
+
+    pub async fn set_project_progress(&self, id: &str, progress: u8) -> AppResult<Project> {
+        self.set_progress("projects", "Project", id, progress).await?;
+        self.get_project(id).await
+    }
+
+    // Workflow operations
+    /// Returns the statuses in effect for a project, or the default workflow
+    /// for tasks without a project
+    pub async fn get_workflow(&self, project_id: Option<&str>) -> AppResult<Workflow> {
+        let Some(project_id) = project_id else {
+            return Ok(Workflow::default_for(None));
+        };
+
+        let statuses = sqlx::query_as::<_, WorkflowStatus>(
+            r#"
+            SELECT key, name, category, next_statuses
+            FROM workflow_statuses
+            WHERE project_id = ?1
+            ORDER BY position
+            "#
+        )
+        .bind(project_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get workflow", e))?;
+
+        if statuses.is_empty() {
+            return Ok(Workflow::default_for(Some(project_id.to_string())));
+        }
+        Ok(Workflow {
+            project_id: Some(project_id.to_string()),
+            is_custom: true,
+            statuses,
+        })
+    }
+
+    /// Returns the workflows of all projects that define their own statuses
+    pub async fn get_custom_workflows(&self) -> AppResult<Vec<Workflow>> {
+        let project_ids: Vec<String> =
+            sqlx::query_scalar("SELECT DISTINCT project_id FROM workflow_statuses ORDER BY project_id")
+                .fetch_all(&*self.pool)
+                .await
+                .map_err(|e| AppError::database_error("get custom workflows", e))?;
+
+        let mut workflows = Vec::with_capacity(project_ids.len());
+        for project_id in project_ids {
+            workflows.push(self.get_workflow(Some(&project_id)).await?);
+        }
+        Ok(workflows)
+    }
+
+    /// Replaces a project's workflow; an empty list restores the default
+    ///
+    /// Tasks whose status is no longer part of the workflow move to the first
+    /// todo (or, when completed, done) status.
+    pub async fn set_workflow(&self, project_id: &str, statuses: Vec<WorkflowStatusInput>) -> AppResult<Workflow> {
+        if !statuses.is_empty() {
+            validate_workflow(&statuses)?;
+        }
+        let exists: Option<String> = sqlx::query_scalar("SELECT id FROM projects WHERE id = ?1")
+            .bind(project_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get project", e))?;
+        if exists.is_none() {
+            return Err(AppError::not_found("Project", project_id));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        let now = Utc::now();
+
+        sqlx::query("DELETE FROM workflow_statuses WHERE project_id = ?1")
+            .bind(project_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("clear workflow", e))?;
+
+        Self::insert_workflow_statuses(&mut tx, project_id, &statuses, now).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE tasks
+            SET status = (
+                    SELECT key FROM effective_workflow_statuses s
+                    WHERE s.project_id IS tasks.project_id
+                      AND s.category = CASE WHEN tasks.completed_at IS NULL THEN 'todo' ELSE 'done' END
+                    ORDER BY s.position LIMIT 1
+                )
+            WHERE project_id = ?1
+              AND NOT EXISTS (
+                  SELECT 1 FROM effective_workflow_statuses s
+                  WHERE s.project_id IS tasks.project_id AND s.key = tasks.status
+                    AND (s.category = 'done') = (tasks.completed_at IS NOT NULL)
+              )
+            "#
+        )
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("move tasks to new workflow", e))?;
+
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit workflow", e))?;
+
+        self.get_workflow(Some(project_id)).await
+    }
+
+    async fn insert_workflow_statuses(
+        tx: &mut Transaction<'_, Sqlite>,
+        project_id: &str,
+        statuses: &[WorkflowStatusInput],
+        now: DateTime<Utc>,
+    ) -> AppResult<()> {
+        for (position, status) in statuses.iter().enumerate() {
+            let next_statuses = status
+                .next_statuses
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?;
+            sqlx::query(
+                r#"
+                INSERT INTO workflow_statuses (project_id, key, name, category, position, next_statuses, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#
+            )
+            .bind(project_id)
+            .bind(&status.key)
+            .bind(status.name.trim())
+            .bind(status.category)
+            .bind(position as i64)
+            .bind(next_statuses)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("create workflow status", e))?;
+        }
+        Ok(())
+    }
+
+    /// Moves a task to another status of its project's workflow
+    ///
+    /// Moving into a done status completes the task and moving out of one
+    /// reopens it. A task blocked by open dependencies can't be completed.
+    pub async fn set_task_status(&self, id: &str, status: &str) -> AppResult<Task> {
+        let task = self.get_task(id).await?;
+        let workflow = self.get_workflow(task.project_id.as_deref()).await?;
+
+        let target = workflow
+            .find(status)
+            .ok_or_else(|| AppError::validation_error("status", &format!("'{}' is not a status in this project's workflow", status)))?;
+        if !workflow.allows_transition(&task.status, status) {
+            return Err(AppError::new(
+                ErrorCode::CannotUpdate,
+                format!("Tasks can't move from '{}' to '{}' in this workflow", task.status, status),
+            ));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        if target.category == StatusCategory::Done && task.completed_at.is_none() {
+            Self::ensure_unblocked_in(&mut tx, id).await?;
+        }
+
+        let now = Utc::now();
+        let completed_at = match target.category {
+            StatusCategory::Done => Some(task.completed_at.unwrap_or(now)),
+            _ => None,
+        };
+
+        sqlx::query("UPDATE tasks SET status = ?1, completed_at = ?2 WHERE id = ?3")
+            .bind(status)
+            .bind(completed_at)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("set task status", e))?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit task status", e))?;
+
+        self.get_task(id).await
+    }
+
+    // View preference operations
+    /// Returns how a view of a project is arranged, or its defaults if the
+    /// user never changed it
+    pub async fn get_view_preferences(&self, project_id: &str, view_type: ViewType) -> AppResult<ViewPreferences> {
+        self.get_project(project_id).await?;
+
+        let preferences = sqlx::query_as::<_, ViewPreferences>(
+            r#"
+            SELECT project_id, view_type, group_by, sort_by, sort_descending,
+                   collapsed_columns, visible_fields, updated_at
+            FROM view_preferences
+            WHERE project_id = ?1 AND view_type = ?2
+            "#
+        )
+        .bind(project_id)
+        .bind(view_type)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get view preferences", e))?;
+
+        Ok(preferences.unwrap_or_else(|| ViewPreferences::default_for(project_id.to_string(), view_type)))
+    }
+
+    /// Saves how a view of a project is arranged; `None` restores its defaults
+    pub async fn set_view_preferences(
+        &self,
+        project_id: &str,
+        view_type: ViewType,
+        input: Option<ViewPreferencesInput>,
+    ) -> AppResult<ViewPreferences> {
+        self.get_project(project_id).await?;
+
+        let Some(input) = input else {
+            sqlx::query("DELETE FROM view_preferences WHERE project_id = ?1 AND view_type = ?2")
+                .bind(project_id)
+                .bind(view_type)
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| AppError::database_error("reset view preferences", e))?;
+            return Ok(ViewPreferences::default_for(project_id.to_string(), view_type));
+        };
+        validate_view_preferences(&input)?;
+
+        let collapsed_columns = serde_json::to_string(&input.collapsed_columns)?;
+        let visible_fields = input.visible_fields.as_ref().map(serde_json::to_string).transpose()?;
+        sqlx::query_as::<_, ViewPreferences>(
+            r#"
+            INSERT INTO view_preferences (
+                project_id, view_type, group_by, sort_by, sort_descending,
+                collapsed_columns, visible_fields, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(project_id, view_type) DO UPDATE SET
+                group_by = excluded.group_by,
+                sort_by = excluded.sort_by,
+                sort_descending = excluded.sort_descending,
+                collapsed_columns = excluded.collapsed_columns,
+                visible_fields = excluded.visible_fields,
+                updated_at = excluded.updated_at
+            RETURNING project_id, view_type, group_by, sort_by, sort_descending,
+                      collapsed_columns, visible_fields, updated_at
+            "#
+        )
+        .bind(project_id)
+        .bind(view_type)
+        .bind(&input.group_by)
+        .bind(&input.sort_by)
+        .bind(input.sort_descending)
+        .bind(collapsed_columns)
+        .bind(visible_fields)
+        .bind(Utc::now())
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("set view preferences", e))
+    }
+
+    // Recurrence operations
+    /// The dates a repeating task skips, earliest first
+    pub async fn get_recurrence_exceptions(&self, task_id: &str) -> AppResult<Vec<NaiveDate>> {
+        sqlx::query_scalar::<_, NaiveDate>(
+            "SELECT date FROM task_recurrence_exceptions WHERE task_id = ?1 ORDER BY date",
+        )
+        .bind(task_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get recurrence exceptions", e))
+    }
+
+    /// Replaces the dates a repeating task skips
+    pub async fn set_recurrence_exceptions(&self, task_id: &str, dates: &[NaiveDate]) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query("DELETE FROM task_recurrence_exceptions WHERE task_id = ?1")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("clear recurrence exceptions", e))?;
+        for date in dates {
+            sqlx::query("INSERT OR IGNORE INTO task_recurrence_exceptions (task_id, date) VALUES (?1, ?2)")
+                .bind(task_id)
+                .bind(date)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("add recurrence exception", e))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit recurrence exceptions", e))
+    }
+
+    /// Completes a task and, given its next occurrence, creates the next
+    /// instance in the same transaction
+    ///
+    /// The new task copies the title, description, priority, estimate,
+    /// parent and tags, and takes over the rule and the exception dates after
+    /// its own; the completed task keeps neither. Returns the new task.
+    pub async fn complete_and_repeat_task(&self, task_id: &str, next: Option<TaskOccurrence>) -> AppResult<Option<Task>> {
+        let task = self.get_task(task_id).await?;
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+        Self::ensure_unblocked_in(&mut tx, task_id).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE tasks
+            SET completed_at = ?1,
+                recurrence_rule = CASE WHEN ?2 THEN NULL ELSE recurrence_rule END
+            WHERE id = ?3 AND completed_at IS NULL
+            "#
+        )
+        .bind(now)
+        .bind(next.is_some())
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("complete task", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::new(ErrorCode::CannotUpdate, "The task is already completed"));
+        }
+
+        let Some(next) = next else {
+            tx.commit()
+                .await
+                .map_err(|e| AppError::database_error("commit task completion", e))?;
+            return Ok(None);
+        };
+
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, due_date,
+                               estimated_minutes, recurrence_rule, created_at, updated_at)
+            SELECT ?1, project_id, parent_task_id, title, description, priority, ?2,
+                   estimated_minutes, ?3, ?4, ?4
+            FROM tasks
+            WHERE id = ?5
+            "#
+        )
+        .bind(&id)
+        .bind(next.due_date)
+        .bind(&next.recurrence_rule)
+        .bind(now)
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("create next task instance", e))?;
+
+        sqlx::query("INSERT INTO task_tags (task_id, tag_id) SELECT ?1, tag_id FROM task_tags WHERE task_id = ?2")
+            .bind(&id)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("copy task tags", e))?;
+        sqlx::query(
+            r#"
+            INSERT INTO task_recurrence_exceptions (task_id, date)
+            SELECT ?1, date FROM task_recurrence_exceptions WHERE task_id = ?2 AND date > ?3
+            "#
+        )
+        .bind(&id)
+        .bind(task_id)
+        .bind(next.date)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("copy recurrence exceptions", e))?;
+        Self::write_task_references(&mut tx, &id, task.description.as_deref()).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit task completion", e))?;
+
+        self.get_task(&id).await.map(Some)
+    }
+
+    // Dependency and snooze operations
+    /// Makes a task wait for another one to be completed; adding it again
+    /// keeps the original
+    pub async fn add_task_dependency(&self, task_id: &str, depends_on_id: &str) -> AppResult<TaskDependency> {
+        if task_id == depends_on_id {
+            return Err(AppError::validation_error("depends_on_id", "a task can't depend on itself"));
+        }
+        self.get_task(task_id).await?;
+        self.get_task(depends_on_id).await?;
+
+        let creates_cycle: bool = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE upstream(id) AS (
+                SELECT ?2
+                UNION
+                SELECT d.depends_on_id FROM task_dependencies d JOIN upstream u ON d.task_id = u.id
+            )
+            SELECT EXISTS (SELECT 1 FROM upstream WHERE id = ?1)
+            "#
+        )
+        .bind(task_id)
+        .bind(depends_on_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("check task dependencies", e))?;
+        if creates_cycle {
+            return Err(AppError::validation_error(
+                "depends_on_id",
+                "already depends on this task, directly or through others",
+            ));
+        }
+
+        sqlx::query_as::<_, TaskDependency>(
+            r#"
+            INSERT INTO task_dependencies (task_id, depends_on_id, created_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(task_id, depends_on_id) DO UPDATE SET created_at = task_dependencies.created_at
+            RETURNING task_id, depends_on_id, created_at
+            "#
+        )
+        .bind(task_id)
+        .bind(depends_on_id)
+        .bind(Utc::now())
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("add task dependency", e))
+    }
+
+    /// Returns false if the task didn't depend on the other one
+    pub async fn remove_task_dependency(&self, task_id: &str, depends_on_id: &str) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM task_dependencies WHERE task_id = ?1 AND depends_on_id = ?2")
+            .bind(task_id)
+            .bind(depends_on_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("remove task dependency", e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Tasks a task depends on, completed ones included
+    pub async fn get_task_dependencies(&self, task_id: &str) -> AppResult<Vec<TaskDependency>> {
+        sqlx::query_as::<_, TaskDependency>(
+            "SELECT task_id, depends_on_id, created_at FROM task_dependencies WHERE task_id = ?1 ORDER BY created_at"
+        )
+        .bind(task_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get task dependencies", e))
+    }
+
+    /// Open tasks a task depends on, the ones that keep it from being
+    /// completed; archived tasks don't block
+    pub async fn get_open_blockers(&self, task_id: &str) -> AppResult<Vec<Blocker>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(|e| AppError::database_error("acquire connection", e))?;
+        Self::open_blockers_in(&mut conn, task_id).await
+    }
+
+    async fn open_blockers_in(conn: &mut SqliteConnection, task_id: &str) -> AppResult<Vec<Blocker>> {
+        sqlx::query_as::<_, Blocker>(
+            r#"
+            SELECT b.id, b.title
+            FROM task_dependencies d
+            JOIN tasks b ON b.id = d.depends_on_id
+            WHERE d.task_id = ?1 AND b.completed_at IS NULL AND b.archived_at IS NULL
+            ORDER BY d.created_at
+            "#
+        )
+        .bind(task_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::database_error("get open blockers", e))
+    }
+
+    /// Rejects completing a task that still waits for open tasks; the data
+    /// lists them. Runs inside the completing transaction, so a blocker
+    /// reopened meanwhile isn't missed.
+    async fn ensure_unblocked_in(tx: &mut Transaction<'_, Sqlite>, task_id: &str) -> AppResult<()> {
+        let blockers = Self::open_blockers_in(tx, task_id).await?;
+        if blockers.is_empty() {
+            return Ok(());
+        }
+        Err(AppError::new(
+            ErrorCode::CannotUpdate,
+            "The task waits for open tasks it depends on; complete them or remove the dependencies first",
+        )
+        .with_data(serde_json::json!({ "blocked_by": blockers })))
+    }
+
+    /// Open tasks waiting for open tasks they depend on, optionally of one
+    /// project, earliest due first
+    pub async fn get_blocked_tasks(&self, project_id: Option<&str>) -> AppResult<Vec<BlockedTask>> {
+        type Row = (String, String, Option<String>, TaskPriority, Option<DateTime<Utc>>, String, String);
+        let rows = sqlx::query_as::<_, Row>(
+            r#"
+            SELECT t.id, t.title, t.project_id, t.priority, t.due_date, b.id, b.title
+            FROM tasks t
+            JOIN task_dependencies d ON d.task_id = t.id
+            JOIN tasks b ON b.id = d.depends_on_id
+            WHERE t.completed_at IS NULL AND t.archived_at IS NULL
+              AND b.completed_at IS NULL AND b.archived_at IS NULL
+              AND (?1 IS NULL OR t.project_id = ?1)
+            ORDER BY t.due_date ASC NULLS LAST, t.title, t.id, d.created_at
+            "#
+        )
+        .bind(project_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get blocked tasks", e))?;
+
+        let mut blocked: Vec<BlockedTask> = Vec::new();
+        for (task_id, title, project_id, priority, due_date, blocker_id, blocker_title) in rows {
+            let blocker = Blocker { id: blocker_id, title: blocker_title };
+            match blocked.last_mut() {
+                Some(task) if task.task_id == task_id => task.blocked_by.push(blocker),
+                _ => blocked.push(BlockedTask {
+                    task_id,
+                    title,
+                    project_id,
+                    priority,
+                    due_date,
+                    blocked_by: vec![blocker],
+                }),
+            }
+        }
+        Ok(blocked)
+    }
+
+    /// Dependencies between the open tasks of a project
+    pub async fn get_open_project_dependencies(&self, project_id: &str) -> AppResult<Vec<TaskDependency>> {
+        sqlx::query_as::<_, TaskDependency>(
+            r#"
+            SELECT d.task_id, d.depends_on_id, d.created_at
+            FROM task_dependencies d
+            JOIN tasks t ON t.id = d.task_id
+            JOIN tasks b ON b.id = d.depends_on_id
+            WHERE t.project_id = ?1 AND t.completed_at IS NULL AND t.archived_at IS NULL
+              AND b.project_id = ?1 AND b.completed_at IS NULL AND b.archived_at IS NULL
+            ORDER BY d.created_at
+            "#
+        )
+        .bind(project_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project dependencies", e))
+    }
+
+    /// Keeps a task out of the Today view until `until`; snoozing it again
+    /// moves the time
+    pub async fn snooze_task(&self, task_id: &str, until: DateTime<Utc>) -> AppResult<TaskSnooze> {
+        let now = Utc::now();
+        if until <= now {
+            return Err(AppError::validation_error("until", "must be in the future"));
+        }
+        self.get_task(task_id).await?;
+
+        sqlx::query_as::<_, TaskSnooze>(
+            r#"
+            INSERT INTO task_snoozes (task_id, snoozed_until, snoozed_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(task_id) DO UPDATE SET
+                snoozed_until = excluded.snoozed_until,
+                snoozed_at = excluded.snoozed_at
+            RETURNING task_id, snoozed_until, snoozed_at
+            "#
+        )
+        .bind(task_id)
+        .bind(until)
+        .bind(now)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("snooze task", e))
+    }
+
+    /// Returns false if the task wasn't snoozed
+    pub async fn unsnooze_task(&self, task_id: &str) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM task_snoozes WHERE task_id = ?1 AND snoozed_until > ?2")
+            .bind(task_id)
+            .bind(Utc::now())
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("unsnooze task", e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Project operations
+    pub async fn create_project(&self, input: ProjectInput) -> AppResult<Project> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO projects (id, goal_id, title, description, status, created_at, updated_at, color, icon)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.goal_id)
+        .bind(&input.title)
+        .bind(&input.description)
+        .bind(input.status.unwrap_or(ProjectStatus::Planning).to_string())
+        .bind(now)
+        .bind(now)
+        .bind(&input.color)
+        .bind(&input.icon)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("create project", e))?;
+
+        self.get_project(&id).await
+    }
+
+    /// Projects that are neither archived nor incubated, directly or through
+    /// their goal, nor hidden by the active context
+    pub async fn get_projects(&self) -> AppResult<Vec<Project>> {
+        sqlx::query_as::<_, Project>(
+            r#"
+            SELECT id, goal_id, title, description, status,
+                   created_at, updated_at, completed_at, archived_at,
+                   review_interval_days, last_reviewed_at, incubated_at, sort_order, progress,
+                   color, icon,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = projects.id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = projects.id) AS display_icon
+            FROM projects
+            WHERE archived_at IS NULL
+              AND id NOT IN (SELECT id FROM incubated_projects)
+              AND id NOT IN (SELECT id FROM hidden_projects)
+            ORDER BY sort_order, created_at
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get projects", e))
+    }
+
+    pub async fn get_projects_by_goal(&self, goal_id: &str) -> AppResult<Vec<Project>> {
+        sqlx::query_as::<_, Project>(
+            r#"
+            SELECT id, goal_id, title, description, status,
+                   created_at, updated_at, completed_at, archived_at,
+                   review_interval_days, last_reviewed_at, incubated_at, sort_order, progress,
+                   color, icon,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = projects.id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = projects.id) AS display_icon
+            FROM projects
+            WHERE goal_id = ?1 AND archived_at IS NULL AND incubated_at IS NULL
+            ORDER BY sort_order, created_at
+            "#
+        )
+        .bind(goal_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get projects by goal", e))
+    }
+
+    pub async fn get_project(&self, id: &str) -> AppResult<Project> {
+        sqlx::query_as::<_, Project>(
+            r#"
+            SELECT id, goal_id, title, description, status,
+                   created_at, updated_at, completed_at, archived_at,
+                   review_interval_days, last_reviewed_at, incubated_at, sort_order, progress,
+                   color, icon,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = projects.id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = projects.id) AS display_icon
+            FROM projects
+            WHERE id = ?1
+            "#
+        )
+        .bind(id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::not_found("Project", id),
+            _ => AppError::database_error("get project", e),
+        })
+    }
+
+    pub async fn update_project(&self, id: &str, input: ProjectInput) -> AppResult<Project> {
+        sqlx::query(
+            r#"
+            UPDATE projects
+            SET goal_id = ?1, title = ?2, description = ?3, status = COALESCE(?4, status),
+                color = ?5, icon = ?6
+            WHERE id = ?7
+            "#
+        )
+        .bind(&input.goal_id)
+        .bind(&input.title)
+        .bind(&input.description)
+        .bind(input.status.map(|status| status.to_string()))
+        .bind(&input.color)
+        .bind(&input.icon)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("update project", e))?;
+
+        self.get_project(id).await
+    }
+
+    /// Changes a project's status; moving to completed stamps `completed_at`,
+    /// any other status clears it
+    pub async fn update_project_status(&self, id: &str, status: ProjectStatus) -> AppResult<Project> {
+        let completed_at = (status == ProjectStatus::Completed).then(Utc::now);
+
+        sqlx::query("UPDATE projects SET status = ?1, completed_at = ?2 WHERE id = ?3")
+            .bind(status.to_string())
+            .bind(completed_at)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("update project status", e))?;
+
+        self.get_project(id).await
+    }
+
+    // Project review operations
+
+    /// Open projects where nothing changed, and no review happened, within
+    /// their review interval; the most overdue come first
+    ///
+    /// Activity is the latest update to the project itself or to any of its
+    /// tasks or notes. Completed, cancelled, archived and incubated projects
+    /// are never stale.
+    pub async fn get_stale_projects(&self, now: DateTime<Utc>, default_interval_days: u32) -> AppResult<Vec<StaleProject>> {
+        sqlx::query_as::<_, StaleProject>(
+            r#"
+            SELECT p.*,
+                   CAST(julianday(?1) - p.last_activity AS INTEGER) AS days_idle,
+                   COALESCE(p.review_interval_days, ?2) AS effective_review_interval_days
+            FROM (
+                SELECT projects.*,
+                       MAX(
+                           julianday(projects.updated_at),
+                           COALESCE(julianday(projects.last_reviewed_at), 0),
+                           COALESCE((SELECT MAX(julianday(t.updated_at)) FROM tasks t WHERE t.project_id = projects.id), 0),
+                           COALESCE((SELECT MAX(julianday(n.updated_at)) FROM notes n WHERE n.project_id = projects.id), 0)
+                       ) AS last_activity
+                FROM projects
+                WHERE archived_at IS NULL AND status IN ('planning', 'active', 'onhold')
+                  AND id NOT IN (SELECT id FROM incubated_projects)
+                  AND id NOT IN (SELECT id FROM hidden_projects)
+            ) p
+            WHERE julianday(?1) - p.last_activity >= COALESCE(p.review_interval_days, ?2)
+            ORDER BY (julianday(?1) - p.last_activity) - COALESCE(p.review_interval_days, ?2) DESC
+            "#
+        )
+        .bind(now)
+        .bind(default_interval_days)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get stale projects", e))
+    }
+
+    /// Parks a goal or project on the someday/maybe list, or reactivates it
+    ///
+    /// Only open items can be parked; reactivating an item that isn't parked
+    /// does nothing.
+    pub async fn set_incubated(&self, entity_type: &str, id: &str, incubated: bool) -> AppResult<()> {
+        let (table, entity) = match entity_type {
+            "goal" => ("goals", "Goal"),
+            "project" => ("projects", "Project"),
+            _ => return Err(AppError::validation_error("entity_type", "must be goal or project")),
+        };
+
+        let sql = if incubated {
+            format!(
+                "UPDATE {} SET incubated_at = COALESCE(incubated_at, ?1) \
+                 WHERE id = ?2 AND archived_at IS NULL AND completed_at IS NULL",
+                table
+            )
+        } else {
+            format!("UPDATE {} SET incubated_at = NULL WHERE id = ?2 AND incubated_at IS NOT NULL", table)
+        };
+        // Both statements bind the time as ?1, though reactivating doesn't use it
+        let result = sqlx::query(&sql)
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set incubation", e))?;
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        let exists: bool = sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = ?1)", table))
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set incubation", e))?;
+        match (exists, incubated) {
+            (false, _) => Err(AppError::not_found(entity, id)),
+            (true, true) => Err(AppError::validation_error("id", "archived or completed items can't be parked")),
+            (true, false) => Ok(()),
+        }
+    }
+
+    /// Parked goals and projects that aren't archived, most recently parked first
+    pub async fn get_someday_maybe(&self) -> AppResult<SomedayMaybe> {
+        let goals = sqlx::query_as::<_, Goal>(
+            "SELECT * FROM goals WHERE incubated_at IS NOT NULL AND archived_at IS NULL ORDER BY incubated_at DESC"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get someday/maybe goals", e))?;
+
+        let projects = sqlx::query_as::<_, Project>(
+            "SELECT * FROM projects WHERE incubated_at IS NOT NULL AND archived_at IS NULL ORDER BY incubated_at DESC"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get someday/maybe projects", e))?;
+
+        Ok(SomedayMaybe { goals, projects })
+    }
+
+    /// Records that a project was reviewed now, which resets its staleness
+    pub async fn mark_project_reviewed(&self, id: &str) -> AppResult<Project> {
+        let result = sqlx::query("UPDATE projects SET last_reviewed_at = ?1 WHERE id = ?2")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("mark project reviewed", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Project", id));
+        }
+        self.get_project(id).await
+    }
+
+    /// Sets how often a project should be reviewed; `None` uses the default
+    pub async fn set_project_review_interval(&self, id: &str, days: Option<u32>) -> AppResult<Project> {
+        let result = sqlx::query("UPDATE projects SET review_interval_days = ?1 WHERE id = ?2")
+            .bind(days)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set project review interval", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Project", id));
+        }
+        self.get_project(id).await
+    }
+
+    // Day plan operations
+    /// Adds a task to the focus list of `date`; adding it twice keeps the
+    /// original position, and only replaces the planned minutes when given
+    pub async fn add_to_day_plan(&self, date: NaiveDate, task_id: &str, planned_minutes: Option<u32>) -> AppResult<()> {
+        self.get_task(task_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO day_plan_items (plan_date, task_id, position, added_at, planned_minutes)
+            VALUES (?1, ?2, (SELECT COALESCE(MAX(position) + 1, 0) FROM day_plan_items WHERE plan_date = ?1), ?3, ?4)
+            ON CONFLICT(plan_date, task_id)
+                DO UPDATE SET planned_minutes = COALESCE(excluded.planned_minutes, planned_minutes)
+            "#
+        )
+        .bind(date)
+        .bind(task_id)
+        .bind(Utc::now())
+        .bind(planned_minutes)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("add to day plan", e))?;
+
+        Ok(())
+    }
+
+    /// Removes a task from the focus list of `date`; returns false if it wasn't on it
+    pub async fn remove_from_day_plan(&self, date: NaiveDate, task_id: &str) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM day_plan_items WHERE plan_date = ?1 AND task_id = ?2")
+            .bind(date)
+            .bind(task_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("remove from day plan", e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Items of the plan for `date` in plan order, completed ones included
+    pub async fn get_day_plan_items(&self, date: NaiveDate) -> AppResult<Vec<DayPlanItem>> {
+        sqlx::query_as::<_, DayPlanItem>(
+            r#"
+            SELECT t.id, t.project_id, t.parent_task_id, t.title, t.description, t.priority, t.due_date,
+                   t.created_at, t.updated_at, t.completed_at, t.archived_at, t.status, t.estimated_minutes,
+                   t.actual_minutes, t.recurrence_rule,
+                   d.position, d.planned_minutes
+            FROM day_plan_items d
+            JOIN tasks t ON t.id = d.task_id
+            WHERE d.plan_date = ?1 AND t.archived_at IS NULL
+            ORDER BY d.position
+            "#
+        )
+        .bind(date)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get day plan", e))
+    }
+
+    /// Sets the minutes planned for a task on `date`; `None` falls back to
+    /// the task's estimate
+    pub async fn set_day_plan_minutes(&self, date: NaiveDate, task_id: &str, minutes: Option<u32>) -> AppResult<()> {
+        let result = sqlx::query("UPDATE day_plan_items SET planned_minutes = ?1 WHERE plan_date = ?2 AND task_id = ?3")
+            .bind(minutes)
+            .bind(date)
+            .bind(task_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set planned minutes", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Day plan item", task_id));
+        }
+        Ok(())
+    }
+
+    /// Reorders the plan for `date`: the given tasks come first in the given
+    /// order, any others keep their relative order after them
+    pub async fn reorder_day_plan(&self, date: NaiveDate, task_ids: &[String]) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+
+        let current: Vec<String> =
+            sqlx::query_scalar("SELECT task_id FROM day_plan_items WHERE plan_date = ?1 ORDER BY position")
+                .bind(date)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("reorder day plan", e))?;
+
+        if let Some(unknown) = task_ids.iter().find(|id| !current.contains(id)) {
+            return Err(AppError::not_found("Day plan item", unknown));
+        }
+        let order = task_ids
+            .iter()
+            .chain(current.iter().filter(|id| !task_ids.contains(id)));
+
+        for (position, task_id) in order.enumerate() {
+            sqlx::query("UPDATE day_plan_items SET position = ?1 WHERE plan_date = ?2 AND task_id = ?3")
+                .bind(position as i64)
+                .bind(date)
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("reorder day plan", e))?;
+        }
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit day plan order", e))
+    }
+
+    /// Open tasks focused for `date` or due within `[day_start, day_end)`,
+    /// with their total planned minutes (or estimate); tasks of incubated
+    /// projects are left out
+    ///
+    /// With `include_overdue` open tasks due before the day count too, as in
+    /// `get_my_day`.
+    pub async fn get_day_load(
+        &self,
+        date: NaiveDate,
+        day_start: DateTime<Utc>,
+        day_end: DateTime<Utc>,
+        include_overdue: bool,
+    ) -> AppResult<DayLoad> {
+        sqlx::query_as::<_, DayLoad>(
+            r#"
+            SELECT COUNT(*) AS task_count,
+                   COALESCE(SUM(COALESCE(d.planned_minutes, t.estimated_minutes)), 0) AS planned_minutes,
+                   COALESCE(SUM(COALESCE(d.planned_minutes, t.estimated_minutes) IS NULL), 0) AS unestimated_count
+            FROM tasks t
+            LEFT JOIN day_plan_items d ON d.task_id = t.id AND d.plan_date = ?1
+            WHERE t.archived_at IS NULL
+              AND t.completed_at IS NULL
+              AND (d.task_id IS NOT NULL OR (t.due_date < ?3 AND (t.due_date >= ?2 OR ?4)))
+              AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = t.project_id)
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = t.project_id)
+            "#
+        )
+        .bind(date)
+        .bind(day_start)
+        .bind(day_end)
+        .bind(include_overdue)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get day load", e))
+    }
+
+    /// Builds the "My Day" view for `date`, whose UTC range is `[day_start, day_end)`
+    ///
+    /// With `include_overdue` open tasks due before the day are listed as due too.
+    pub async fn get_my_day(
+        &self,
+        date: NaiveDate,
+        day_start: DateTime<Utc>,
+        day_end: DateTime<Utc>,
+        include_overdue: bool,
+    ) -> AppResult<MyDay> {
+        let focused = sqlx::query_as::<_, Task>(
+            r#"
+            SELECT t.id, t.project_id, t.parent_task_id, t.title, t.description, t.priority, t.due_date,
+                   t.created_at, t.updated_at, t.completed_at, t.archived_at, t.status, t.estimated_minutes,
+                   t.actual_minutes, t.recurrence_rule
+            FROM day_plan_items d
+            JOIN tasks t ON t.id = d.task_id
+            WHERE d.plan_date = ?1 AND t.archived_at IS NULL
+            ORDER BY d.position
+            "#
+        )
+        .bind(date)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get day plan", e))?;
+
+        let due = sqlx::query_as::<_, Task>(
+            r#"
+            SELECT id, project_id, parent_task_id, title, description, priority, due_date,
+                   created_at, updated_at, completed_at, archived_at, status, estimated_minutes,
+                   actual_minutes, recurrence_rule,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = tasks.project_id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = tasks.project_id) AS display_icon
+            FROM tasks
+            WHERE archived_at IS NULL
+              AND completed_at IS NULL
+              AND due_date < ?2
+              AND (due_date >= ?1 OR ?3)
+              AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = tasks.project_id)
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = tasks.project_id)
+              AND id NOT IN (SELECT task_id FROM day_plan_items WHERE plan_date = ?4)
+            ORDER BY due_date
+            "#
+        )
+        .bind(day_start)
+        .bind(day_end)
+        .bind(include_overdue)
+        .bind(date)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get tasks due", e))?;
+
+        let carried_over = sqlx::query_as::<_, Task>(
+            r#"
+            SELECT t.id, t.project_id, t.parent_task_id, t.title, t.description, t.priority, t.due_date,
+                   t.created_at, t.updated_at, t.completed_at, t.archived_at, t.status, t.estimated_minutes,
+                   t.actual_minutes, t.recurrence_rule
+            FROM day_plan_items d
+            JOIN tasks t ON t.id = d.task_id
+            WHERE d.plan_date = (SELECT MAX(plan_date) FROM day_plan_items WHERE plan_date < ?1)
+              AND t.archived_at IS NULL
+              AND t.completed_at IS NULL
+              AND t.id NOT IN (SELECT task_id FROM day_plan_items WHERE plan_date = ?1)
+            ORDER BY d.position
+            "#
+        )
+        .bind(date)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get unfinished focus tasks", e))?;
+
+        Ok(MyDay {
+            date,
+            focused,
+            due,
+            carried_over,
+        })
+    }
+
+    // Context operations
+    /// Lists all contexts with their open task counts
+    pub async fn get_contexts(&self) -> AppResult<Vec<Context>> {
+        sqlx::query_as::<_, Context>(
+            r#"
+            SELECT c.id, c.name, c.created_at,
+                   COUNT(t.id) AS open_task_count
+            FROM contexts c
+            LEFT JOIN task_contexts tc ON tc.context_id = c.id
+            LEFT JOIN tasks t ON t.id = tc.task_id AND t.archived_at IS NULL AND t.completed_at IS NULL
+            GROUP BY c.id
+            ORDER BY c.name
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get contexts", e))
+    }
+
+    pub async fn get_task_contexts(&self, task_id: &str) -> AppResult<Vec<Context>> {
+        sqlx::query_as::<_, Context>(
+            r#"
+            SELECT c.id, c.name, c.created_at
+            FROM contexts c
+            JOIN task_contexts tc ON tc.context_id = c.id
+            WHERE tc.task_id = ?1
+            ORDER BY c.name
+            "#
+        )
+        .bind(task_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get task contexts", e))
+    }
+
+    /// Replaces a task's contexts, creating contexts that don't exist yet
+    pub async fn set_task_contexts(&self, task_id: &str, names: &[String]) -> AppResult<Vec<Context>> {
+        let names = names
+            .iter()
+            .map(|name| normalize_context_name(name))
+            .collect::<AppResult<Vec<_>>>()?;
+        self.get_task(task_id).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        let now = Utc::now();
+
+        sqlx::query("DELETE FROM task_contexts WHERE task_id = ?1")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("clear task contexts", e))?;
+
+        for name in &names {
+            sqlx::query("INSERT OR IGNORE INTO contexts (id, name, created_at) VALUES (?1, ?2, ?3)")
+                .bind(Uuid::new_v4().to_string())
+                .bind(name)
+                .bind(now)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("create context", e))?;
+
+            sqlx::query(
+                "INSERT OR IGNORE INTO task_contexts (task_id, context_id) SELECT ?1, id FROM contexts WHERE name = ?2"
+            )
+            .bind(task_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("add task context", e))?;
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit task contexts", e))?;
+
+        self.get_task_contexts(task_id).await
+    }
+
+    /// Lists open tasks in a context, most urgent first
+    pub async fn get_tasks_by_context(&self, name: &str) -> AppResult<Vec<Task>> {
+        let name = normalize_context_name(name)?;
+
+        sqlx::query_as::<_, Task>(
+            r#"
+            SELECT t.id, t.project_id, t.parent_task_id, t.title, t.description, t.priority, t.due_date,
+                   t.created_at, t.updated_at, t.completed_at, t.archived_at, t.status, t.estimated_minutes,
+                   t.actual_minutes, t.recurrence_rule,
+                   (SELECT display_color FROM project_appearance a WHERE a.id = t.project_id) AS display_color,
+                   (SELECT display_icon FROM project_appearance a WHERE a.id = t.project_id) AS display_icon
+            FROM tasks t
+            JOIN task_contexts tc ON tc.task_id = t.id
+            JOIN contexts c ON c.id = tc.context_id
+            WHERE c.name = ?1 AND t.archived_at IS NULL AND t.completed_at IS NULL
+            ORDER BY
+                CASE t.priority
+                    WHEN 'urgent' THEN 1
+                    WHEN 'high' THEN 2
+                    WHEN 'medium' THEN 3
+                    WHEN 'low' THEN 4
+                END,
+                t.due_date ASC NULLS LAST
+            "#
+        )
+        .bind(name)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get tasks by context", e))
+    }
+
+    /// Deletes a context; tasks only lose the context
+    pub async fn delete_context(&self, id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM contexts WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete context", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Context", id));
+        }
+        Ok(())
+    }
+
+    // People operations
+    pub async fn get_people(&self) -> AppResult<Vec<Person>> {
+        sqlx::query_as::<_, Person>(
+            "SELECT id, name, email, notes, created_at, updated_at FROM people ORDER BY name COLLATE NOCASE"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get people", e))
+    }
+
+    pub async fn get_person(&self, id: &str) -> AppResult<Person> {
+        sqlx::query_as::<_, Person>("SELECT id, name, email, notes, created_at, updated_at FROM people WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("Person", id),
+                _ => AppError::database_error("get person", e),
+            })
+    }
+
+    pub async fn create_person(&self, input: PersonInput) -> AppResult<Person> {
+        validate_person(&input)?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        sqlx::query(
+            "INSERT INTO people (id, name, email, notes, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        )
+        .bind(&id)
+        .bind(input.name.trim())
+        .bind(input.email.as_deref().map(str::trim).filter(|e| !e.is_empty()))
+        .bind(&input.notes)
+        .bind(now)
+        .bind(now)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("create person", e))?;
+
+        self.get_person(&id).await
+    }
+
+    pub async fn update_person(&self, id: &str, input: PersonInput) -> AppResult<Person> {
+        validate_person(&input)?;
+
+        let result = sqlx::query("UPDATE people SET name = ?1, email = ?2, notes = ?3 WHERE id = ?4")
+            .bind(input.name.trim())
+            .bind(input.email.as_deref().map(str::trim).filter(|e| !e.is_empty()))
+            .bind(&input.notes)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("update person", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Person", id));
+        }
+        self.get_person(id).await
+    }
+
+    /// Deletes a person; tasks delegated to them are no longer delegated
+    pub async fn delete_person(&self, id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM people WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete person", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Person", id));
+        }
+        Ok(())
+    }
+
+    /// Delegates a task to a person, replacing any earlier delegation
+    pub async fn delegate_task(
+        &self,
+        task_id: &str,
+        person_id: &str,
+        follow_up_at: Option<DateTime<Utc>>,
+    ) -> AppResult<Delegation> {
+        self.get_task(task_id).await?;
+        self.get_person(person_id).await?;
+
+        sqlx::query_as::<_, Delegation>(
+            r#"
+            INSERT INTO task_delegations (task_id, person_id, follow_up_at, delegated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(task_id) DO UPDATE SET
+                person_id = excluded.person_id,
+                follow_up_at = excluded.follow_up_at,
+                delegated_at = CASE
+                    WHEN task_delegations.person_id = excluded.person_id THEN task_delegations.delegated_at
+                    ELSE excluded.delegated_at
+                END
+            RETURNING task_id, person_id, follow_up_at, delegated_at
+            "#
+        )
+        .bind(task_id)
+        .bind(person_id)
+        .bind(follow_up_at)
+        .bind(Utc::now())
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("delegate task", e))
+    }
+
+    /// Takes a task back; returns false if it wasn't delegated
+    pub async fn undelegate_task(&self, task_id: &str) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM task_delegations WHERE task_id = ?1")
+            .bind(task_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("undelegate task", e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_task_delegation(&self, task_id: &str) -> AppResult<Option<Delegation>> {
+        sqlx::query_as::<_, Delegation>(
+            "SELECT task_id, person_id, follow_up_at, delegated_at FROM task_delegations WHERE task_id = ?1"
+        )
+        .bind(task_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get task delegation", e))
+    }
+
+    /// Lists open delegated tasks grouped by person, optionally only those
+    /// whose follow-up is due by `follow_up_before`
+    pub async fn get_waiting_for(&self, follow_up_before: Option<DateTime<Utc>>) -> AppResult<Vec<WaitingForGroup>> {
+        let rows: Vec<(String, WaitingForItem)> = sqlx::query(
+            r#"
+            SELECT d.person_id, d.follow_up_at, d.delegated_at,
+                   t.id, t.project_id, t.parent_task_id, t.title, t.description, t.priority, t.due_date,
+                   t.created_at, t.updated_at, t.completed_at, t.archived_at, t.status, t.estimated_minutes,
+                   t.actual_minutes, t.recurrence_rule
+            FROM task_delegations d
+            JOIN tasks t ON t.id = d.task_id
+            WHERE t.archived_at IS NULL
+              AND t.completed_at IS NULL
+              AND (?1 IS NULL OR d.follow_up_at < ?1)
+            ORDER BY d.follow_up_at ASC NULLS LAST, d.delegated_at
+            "#
+        )
+        .bind(follow_up_before)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get waiting for", e))?
+        .iter()
+        .map(|row| Ok((row.try_get("person_id")?, WaitingForItem::from_row(row)?)))
+        .collect::<Result<_, sqlx::Error>>()
+        .map_err(|e| AppError::database_error("get waiting for", e))?;
+
+        let mut groups: Vec<WaitingForGroup> = self
+            .get_people()
+            .await?
+            .into_iter()
+            .map(|person| WaitingForGroup { person, items: Vec::new() })
+            .collect();
+        for (person_id, item) in rows {
+            if let Some(group) = groups.iter_mut().find(|g| g.person.id == person_id) {
+                group.items.push(item);
+            }
+        }
+        groups.retain(|g| !g.items.is_empty());
+        Ok(groups)
+    }
+
+    // Note operations
+
+    /// Unarchived notes, leaving out those hidden by the active context
+    pub async fn get_notes(&self) -> AppResult<Vec<Note>> {
+        sqlx::query_as::<_, Note>(
+            r#"
+            SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
+                   created_at, updated_at, archived_at, is_private, note_date,
+                   review_stage, next_review_at, last_reviewed_at
+            FROM notes
+            WHERE archived_at IS NULL
+              AND id NOT IN (SELECT id FROM hidden_notes)
+            ORDER BY updated_at DESC
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get notes", e))
+    }
+
+    pub async fn get_note(&self, id: &str) -> AppResult<Note> {
+        sqlx::query_as::<_, Note>(
+            r#"
+            SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
+                   created_at, updated_at, archived_at, is_private, note_date,
+                   review_stage, next_review_at, last_reviewed_at
+            FROM notes
+            WHERE id = ?1
+            "#
+        )
+        .bind(id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::not_found("Note", id),
+            _ => AppError::database_error("get note", e),
+        })
+    }
+
+    /// Applies the frontmatter of a non-private note's content to `input`;
+    /// returns the tags it lists, which are the note's only tags
+    async fn apply_frontmatter(&self, input: &mut NoteInput) -> AppResult<Vec<String>> {
+        if input.is_private {
+            return Ok(Vec::new());
+        }
+        let Some(frontmatter) = frontmatter::parse(&input.content)? else {
+            return Ok(Vec::new());
+        };
+        frontmatter.apply(input);
+
+        let links = [
+            ("tasks", "Task", &input.task_id),
+            ("projects", "Project", &input.project_id),
+            ("goals", "Goal", &input.goal_id),
+            ("life_areas", "Life area", &input.life_area_id),
+        ];
+        for (table, entity, id) in links {
+            let Some(id) = id else { continue };
+            validate_id(id)?;
+            let exists: bool = sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = ?1)", table))
+                .bind(id)
+                .fetch_one(&*self.pool)
+                .await
+                .map_err(|e| AppError::database_error("check note link", e))?;
+            if !exists {
+                return Err(AppError::not_found(entity, id));
+            }
+        }
+
+        let tags = frontmatter.tags.unwrap_or_default();
+        for tag in &tags {
+            validate_title("tag", tag)?;
+        }
+        Ok(tags)
+    }
+
+    /// Replaces a note's tags, creating tags that don't exist yet
+    async fn set_note_tags(tx: &mut Transaction<'_, Sqlite>, note_id: &str, tags: &[String]) -> AppResult<()> {
+        sqlx::query("DELETE FROM note_tags WHERE note_id = ?1")
+            .bind(note_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("clear note tags", e))?;
+
+        for tag in tags {
+            sqlx::query("INSERT OR IGNORE INTO tags (id, name, created_at) VALUES (?1, ?2, ?3)")
+                .bind(Uuid::new_v4().to_string())
+                .bind(tag)
+                .bind(Utc::now())
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("create tag", e))?;
+
+            sqlx::query("INSERT OR IGNORE INTO note_tags (note_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2")
+                .bind(note_id)
+                .bind(tag)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("tag note", e))?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_note_tags(&self, note_id: &str) -> AppResult<Vec<Tag>> {
+        sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.* FROM tags t
+            JOIN note_tags nt ON nt.tag_id = t.id
+            WHERE nt.note_id = ?1
+            ORDER BY t.name
+            "#
+        )
+        .bind(note_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get note tags", e))
+    }
+
+    /// Creates a note; frontmatter in its content sets its tags, links and date
+    pub async fn create_note(&self, mut input: NoteInput) -> AppResult<Note> {
+        validate_note_privacy(input.is_private, &input.content)?;
+        let tags = self.apply_frontmatter(&mut input).await?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO notes (id, task_id, project_id, goal_id, life_area_id, title, content, created_at, updated_at, is_private, note_date)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.task_id)
+        .bind(&input.project_id)
+        .bind(&input.goal_id)
+        .bind(&input.life_area_id)
+        .bind(&input.title)
+        .bind(&input.content)
+        .bind(&now)
+        .bind(&now)
+        .bind(input.is_private)
+        .bind(input.note_date)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("create note", e))?;
+
+        Self::set_note_tags(&mut tx, &id, &tags).await?;
+        tx.commit().await.map_err(|e| AppError::database_error("commit note", e))?;
+
+        self.get_note(&id).await
+    }
+
+    /// Updates a note; frontmatter in its content sets its tags, links and date
+    pub async fn update_note(&self, id: &str, mut input: NoteInput) -> AppResult<Note> {
+        validate_note_privacy(input.is_private, &input.content)?;
+        let tags = self.apply_frontmatter(&mut input).await?;
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE notes 
+            SET task_id = ?1, project_id = ?2, goal_id = ?3, life_area_id = ?4, 
+                title = ?5, content = ?6, is_private = ?8, note_date = ?9
+            WHERE id = ?7
+            "#
+        )
+        .bind(&input.task_id)
+        .bind(&input.project_id)
+        .bind(&input.goal_id)
+        .bind(&input.life_area_id)
+        .bind(&input.title)
+        .bind(&input.content)
+        .bind(id)
+        .bind(input.is_private)
+        .bind(input.note_date)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("update note", e))?;
+
+        Self::set_note_tags(&mut tx, id, &tags).await?;
+        tx.commit().await.map_err(|e| AppError::database_error("commit note", e))?;
+
+        self.get_note(id).await
+    }
+
+    /// Notes whose title, or content unless private, contains `query`,
+    /// optionally only those within `scope`
+    pub async fn search_notes(&self, query: &str, scope: &NoteSearchScope, limit: i64) -> AppResult<Vec<Note>> {
+        let search_pattern = format!("%{}%", query);
+        let scoped = scope.project_id.is_some() || scope.goal_id.is_some() || scope.life_area_id.is_some();
+
+        sqlx::query_as::<_, Note>(
+            r#"
+            WITH scope_goals AS (
+                SELECT id FROM goals WHERE id = ?4 OR life_area_id = ?5
+            ),
+            scope_projects AS (
+                SELECT id FROM projects WHERE id = ?3 OR goal_id IN (SELECT id FROM scope_goals)
+            )
+            SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
+                   created_at, updated_at, archived_at, is_private, note_date,
+                   review_stage, next_review_at, last_reviewed_at
+            FROM notes
+            WHERE archived_at IS NULL
+              AND (title LIKE ?1 OR (is_private = 0 AND content LIKE ?1))
+              AND (
+                  NOT ?6
+                  OR life_area_id = ?5
+                  OR goal_id IN (SELECT id FROM scope_goals)
+                  OR project_id IN (SELECT id FROM scope_projects)
+                  OR task_id IN (SELECT id FROM tasks WHERE project_id IN (SELECT id FROM scope_projects))
+              )
+            ORDER BY updated_at DESC
+            LIMIT ?2
+            "#
+        )
+        .bind(&search_pattern)
+        .bind(limit)
+        .bind(&scope.project_id)
+        .bind(&scope.goal_id)
+        .bind(&scope.life_area_id)
+        .bind(scoped)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("search notes", e))
+    }
+
+    /// Counts private notes, including archived ones
+    pub async fn count_private_notes(&self) -> AppResult<i64> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM notes WHERE is_private = 1")
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("count private notes", e))
+    }
+
+    /// Puts a note on the resurface schedule from the start, or takes it off
+    pub async fn set_note_review(&self, id: &str, enabled: bool) -> AppResult<Note> {
+        let next_review_at = enabled.then(|| Utc::now() + chrono::Duration::days(NOTE_REVIEW_INTERVAL_DAYS[0]));
+        let result = sqlx::query(
+            "UPDATE notes SET review_stage = ?1, next_review_at = ?2 WHERE id = ?3 AND archived_at IS NULL"
+        )
+        .bind(enabled.then_some(0))
+        .bind(next_review_at)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("set note review", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Note", id));
+        }
+        self.get_note(id).await
+    }
+
+    /// Records a review of a scheduled note and moves it to the next
+    /// interval; after the last one the note stops resurfacing
+    pub async fn mark_note_reviewed(&self, id: &str) -> AppResult<Note> {
+        let note = self.get_note(id).await?;
+        let Some(stage) = note.review_stage.filter(|_| note.next_review_at.is_some()) else {
+            return Err(AppError::validation_error("id", "the note isn't scheduled for review"));
+        };
+
+        let now = Utc::now();
+        let stage = stage + 1;
+        let next_review_at = usize::try_from(stage)
+            .ok()
+            .and_then(|stage| NOTE_REVIEW_INTERVAL_DAYS.get(stage))
+            .map(|days| now + chrono::Duration::days(*days));
+        sqlx::query("UPDATE notes SET review_stage = ?1, next_review_at = ?2, last_reviewed_at = ?3 WHERE id = ?4")
+            .bind(stage)
+            .bind(next_review_at)
+            .bind(now)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("mark note reviewed", e))?;
+        self.get_note(id).await
+    }
+
+    /// Notes due to resurface before `until`, the longest overdue first
+    pub async fn get_notes_due_for_review(&self, until: DateTime<Utc>, limit: i64) -> AppResult<Vec<Note>> {
+        sqlx::query_as::<_, Note>(
+            r#"
+            SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
+                   created_at, updated_at, archived_at, is_private, note_date,
+                   review_stage, next_review_at, last_reviewed_at
+            FROM notes
+            WHERE next_review_at < ?1
+              AND archived_at IS NULL
+              AND id NOT IN (SELECT id FROM hidden_notes)
+            ORDER BY next_review_at
+            LIMIT ?2
+            "#
+        )
+        .bind(until)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get notes due for review", e))
+    }
+
+    // Archive a note
+    pub async fn archive_note(&self, note_id: &str) -> AppResult<()> {
+        let now = Utc::now();
+        
+        sqlx::query("UPDATE notes SET archived_at = ?1 WHERE id = ?2")
+            .bind(&now)
+            .bind(note_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("archive note", e))?;
+
+        Ok(())
+    }
+
+    /// Archives (soft-deletes) entities of one type in a single transaction,
+    /// cascading as the single deletes do; `force` is passed on to
+    /// `delete_life_area`
+    ///
+    /// If any ID fails, nothing is archived and the error's data names the
+    /// failing ID and its position.
+    pub async fn batch_delete(&self, entity_type: &str, ids: &[String], force: bool) -> AppResult<usize> {
+        if ids.len() > MAX_BATCH_OPERATIONS {
+            return Err(AppError::validation_error(
+                "ids",
+                &format!("at most {} IDs per batch", MAX_BATCH_OPERATIONS),
+            ));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        let now = Utc::now();
+
+        for (index, id) in ids.iter().enumerate() {
+            validate_id(id)?;
+            let result = match entity_type {
+                "life_area" => Self::archive_life_area_in(&mut tx, id, force, now).await,
+                "goal" => Self::archive_goal_in(&mut tx, id, now).await,
+                "project" => Self::archive_project_in(&mut tx, id, now).await,
+                "task" => Self::archive_task_in(&mut tx, id, now).await,
+                "note" => sqlx::query("UPDATE notes SET archived_at = ?1 WHERE id = ?2")
+                    .bind(now)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| AppError::database_error("archive note", e))
+                    .and_then(|result| match result.rows_affected() {
+                        0 => Err(AppError::not_found("Note", id)),
+                        _ => Ok(()),
+                    }),
+                _ => return Err(AppError::validation_error("entity_type", "unknown entity type")),
+            };
+
+            if let Err(mut e) = result {
+                // Dropping the transaction rolls back the earlier IDs
+                let data = e.data.take();
+                return Err(e.with_data(serde_json::json!({
+                    "failed_id": id,
+                    "index": index,
+                    "data": data,
+                })));
+            }
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit batch delete", e))?;
+        Ok(ids.len())
+    }
+
+    /// Adds and removes tags on tasks or projects in a single transaction;
+    /// tags that don't exist yet are created
+    ///
+    /// If any ID doesn't exist, nothing changes and the error's data names the
+    /// failing ID and its position.
+    pub async fn bulk_tag(
+        &self,
+        entity_type: &str,
+        ids: &[String],
+        add_tags: &[String],
+        remove_tags: &[String],
+    ) -> AppResult<BulkTagResult> {
+        let (label, table, tag_table, id_column) = match entity_type {
+            "task" => ("Task", "tasks", "task_tags", "task_id"),
+            "project" => ("Project", "projects", "project_tags", "project_id"),
+            "note" => {
+                return Err(AppError::validation_error(
+                    "entity_type",
+                    "note tags come from their frontmatter; edit it instead",
+                ))
+            }
+            _ => return Err(AppError::validation_error("entity_type", "only tasks and projects have tags")),
+        };
+        if ids.len() > MAX_BATCH_OPERATIONS {
+            return Err(AppError::validation_error(
+                "ids",
+                &format!("at most {} IDs per batch", MAX_BATCH_OPERATIONS),
+            ));
+        }
+        let mut add: Vec<&str> = Vec::new();
+        for tag in add_tags.iter().map(|tag| tag.trim()) {
+            validate_title("add_tags", tag)?;
+            if !add.contains(&tag) {
+                add.push(tag);
+            }
+        }
+        let remove: Vec<&str> = remove_tags.iter().map(|tag| tag.trim()).collect();
+        if let Some(tag) = add.iter().find(|tag| remove.contains(tag)) {
+            return Err(AppError::validation_error(
+                "remove_tags",
+                &format!("'{}' can't be both added and removed", tag),
+            ));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        let now = Utc::now();
+        for tag in &add {
+            sqlx::query("INSERT OR IGNORE INTO tags (id, name, created_at) VALUES (?1, ?2, ?3)")
+                .bind(Uuid::new_v4().to_string())
+                .bind(tag)
+                .bind(now)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("create tag", e))?;
+        }
+
+        let mut result = BulkTagResult::default();
+        for (index, id) in ids.iter().enumerate() {
+            validate_id(id)?;
+            let exists: bool = sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = ?1)", table))
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("bulk tag", e))?;
+            if !exists {
+                // Dropping the transaction rolls back the earlier IDs
+                return Err(AppError::not_found(label, id).with_data(serde_json::json!({
+                    "failed_id": id,
+                    "index": index,
+                })));
+            }
+
+            for tag in &add {
+                result.added += sqlx::query(&format!(
+                    "INSERT OR IGNORE INTO {} ({}, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+                    tag_table, id_column
+                ))
+                .bind(id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("add tag", e))?
+                .rows_affected() as usize;
+            }
+            for tag in &remove {
+                result.removed += sqlx::query(&format!(
+                    "DELETE FROM {} WHERE {} = ?1 AND tag_id IN (SELECT id FROM tags WHERE name = ?2)",
+                    tag_table, id_column
+                ))
+                .bind(id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("remove tag", e))?
+                .rows_affected() as usize;
+            }
+            result.entities += 1;
+        }
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit bulk tag", e))?;
+        Ok(result)
+    }
+
+    // Note task operations
+    /// A note whose checklist can be turned into tasks
+    async fn get_note_for_tasks(&self, note_id: &str) -> AppResult<Note> {
+        let note = self.get_note(note_id).await?;
+        if note.archived_at.is_some() {
+            return Err(AppError::validation_error("note_id", "is archived"));
+        }
+        if note.is_private {
+            return Err(AppError::validation_error("note_id", "is private, so its content can't be read"));
+        }
+        Ok(note)
+    }
+
+    /// Where tasks made from a note go: under the note's task if it has one,
+    /// else in its project, else in the inbox
+    async fn note_task_home(&self, note: &Note) -> AppResult<(Option<String>, Option<String>)> {
+        match &note.task_id {
+            Some(task_id) => {
+                let task = self.get_task(task_id).await?;
+                Ok((task.project_id, Some(task.id)))
+            }
+            None => Ok((note.project_id.clone(), None)),
+        }
+    }
+
+    async fn insert_note_task(
+        tx: &mut Transaction<'_, Sqlite>,
+        project_id: Option<&str>,
+        parent_task_id: Option<&str>,
+        title: &str,
+        description: &str,
+        now: DateTime<Utc>,
+    ) -> AppResult<String> {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, created_at, updated_at)
+            VALUES (?1, (SELECT id FROM projects WHERE id = ?2 AND archived_at IS NULL), ?3, ?4, ?5, ?6, ?7, ?7)
+            "#
+        )
+        .bind(&id)
+        .bind(project_id)
+        .bind(parent_task_id)
+        .bind(title)
+        .bind(description)
+        .bind(TaskPriority::default().to_string())
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("create task from note", e))?;
+
+        Self::write_task_references(tx, &id, Some(description)).await?;
+        Ok(id)
+    }
+
+    /// Turns checklist items of a note, each with the title of its task,
+    /// into tasks referring back to the note and links each item to its task
+    async fn extract_note_items(
+        tx: &mut Transaction<'_, Sqlite>,
+        note: &Note,
+        items: Vec<(ChecklistItem, String)>,
+        project_id: Option<&str>,
+        parent_task_id: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> AppResult<Vec<String>> {
+        let description = format!(
+            "From [{}](evorbrain://note/{})",
+            checklists::escape_link_text(&note.title),
+            note.id
+        );
+        let mut tasks = Vec::new();
+        for (item, title) in items {
+            let id = Self::insert_note_task(tx, project_id, parent_task_id, &title, &description, now).await?;
+            tasks.push((item, id));
+        }
+        if tasks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        sqlx::query("UPDATE notes SET content = ?1 WHERE id = ?2")
+            .bind(checklists::link_items(&note.content, &tasks))
+            .bind(&note.id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("link note checklist", e))?;
+        Ok(tasks.into_iter().map(|(_, id)| id).collect())
+    }
+
+    /// Checklist items with their text as the title of their task
+    fn titled_items(items: Vec<ChecklistItem>) -> Vec<(ChecklistItem, String)> {
+        items
+            .into_iter()
+            .map(|item| {
+                let title = item.title.clone();
+                (item, title)
+            })
+            .collect()
+    }
+
+    /// Turns a note into a task with its open checklist items as subtasks;
+    /// the note is kept, attached to the new task
+    pub async fn convert_note_to_task(&self, note_id: &str) -> AppResult<Task> {
+        let note = self.get_note_for_tasks(note_id).await?;
+        let (project_id, parent_task_id) = self.note_task_home(&note).await?;
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+
+        let description = format!(
+            "Converted from [{}](evorbrain://note/{})",
+            checklists::escape_link_text(&note.title),
+            note.id
+        );
+        let task_id = Self::insert_note_task(
+            &mut tx,
+            project_id.as_deref(),
+            parent_task_id.as_deref(),
+            &note.title,
+            &description,
+            now,
+        )
+        .await?;
+        let items = Self::titled_items(checklists::open_items(&note.content));
+        Self::extract_note_items(&mut tx, &note, items, project_id.as_deref(), Some(&task_id), now).await?;
+
+        sqlx::query("UPDATE notes SET task_id = ?1 WHERE id = ?2")
+            .bind(&task_id)
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("attach note to task", e))?;
+        tx.commit().await.map_err(|e| AppError::database_error("commit note conversion", e))?;
+
+        self.get_task(&task_id).await
+    }
+
+    /// Turns the open checklist items of a note into tasks, subtasks of the
+    /// note's task if it has one; items already linked to a task are skipped
+    pub async fn extract_tasks_from_note(&self, note_id: &str) -> AppResult<Vec<Task>> {
+        let note = self.get_note_for_tasks(note_id).await?;
+        let (project_id, parent_task_id) = self.note_task_home(&note).await?;
+        let mut tx = self.begin_transaction().await?;
+
+        let items = Self::titled_items(checklists::open_items(&note.content));
+        let ids =
+            Self::extract_note_items(&mut tx, &note, items, project_id.as_deref(), parent_task_id.as_deref(), Utc::now())
+                .await?;
+        tx.commit().await.map_err(|e| AppError::database_error("commit note tasks", e))?;
+
+        let mut tasks = Vec::with_capacity(ids.len());
+        for id in &ids {
+            tasks.push(self.get_task(id).await?);
+        }
+        Ok(tasks)
+    }
+
+    /// Collects the decisions of a note tagged as a meeting and turns its
+    /// action items into tasks delegated to the people they mention
+    ///
+    /// Items whose mentions don't match exactly one person are left in the
+    /// note and listed as unassigned, so running this again after adding
+    /// the person picks them up.
+    pub async fn process_meeting_note(&self, note_id: &str) -> AppResult<MeetingNoteSummary> {
+        let note = self.get_note_for_tasks(note_id).await?;
+        let tags = self.get_note_tags(note_id).await?;
+        if !tags.iter().any(|tag| tag.name.eq_ignore_ascii_case(meetings::MEETING_TAG)) {
+            return Err(AppError::validation_error(
+                "note_id",
+                &format!("isn't tagged as a meeting (add `tags: [{}]` to its frontmatter)", meetings::MEETING_TAG),
+            ));
+        }
+        let people = self.get_people().await?;
+        let (project_id, parent_task_id) = self.note_task_home(&note).await?;
+
+        let mut assigned = Vec::new();
+        let mut unassigned = Vec::new();
+        for action in meetings::action_items(checklists::open_items(&note.content), &people) {
+            match action.person {
+                Some(person) => assigned.push((action.item, person.id.clone())),
+                None => unassigned.push(action.item.title),
+            }
+        }
+
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+        let person_ids: Vec<String> = assigned.iter().map(|(_, person_id)| person_id.clone()).collect();
+        let items = assigned
+            .into_iter()
+            .map(|(item, _)| {
+                let title = meetings::strip_mentions(&item.title);
+                (item, title)
+            })
+            .collect();
+        let task_ids =
+            Self::extract_note_items(&mut tx, &note, items, project_id.as_deref(), parent_task_id.as_deref(), now)
+                .await?;
+
+        let mut delegations = Vec::with_capacity(task_ids.len());
+        for (task_id, person_id) in task_ids.iter().zip(&person_ids) {
+            let delegation = sqlx::query_as::<_, Delegation>(
+                r#"
+                INSERT INTO task_delegations (task_id, person_id, follow_up_at, delegated_at)
+                VALUES (?1, ?2, NULL, ?3)
+                RETURNING task_id, person_id, follow_up_at, delegated_at
+                "#
+            )
+            .bind(task_id)
+            .bind(person_id)
+            .bind(now)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("delegate action item", e))?;
+            delegations.push(delegation);
+        }
+        tx.commit().await.map_err(|e| AppError::database_error("commit meeting note", e))?;
+
+        let mut action_items = Vec::with_capacity(delegations.len());
+        for delegation in delegations {
+            action_items.push(MeetingActionItem {
+                task: self.get_task(&delegation.task_id).await?,
+                delegation,
+            });
+        }
+        Ok(MeetingNoteSummary {
+            decisions: meetings::decisions(&note.content),
+            action_items,
+            unassigned,
+        })
+    }
+
+    // Data issue operations
+    /// Finds rows that are inconsistent with their parents or with the clock:
+    /// references to parents that no longer exist, active rows under archived
+    /// parents and completions dated in the future
+    pub async fn scan_data_issues(&self) -> AppResult<Vec<DataIssue>> {
+        let mut issues = Vec::new();
+
+        for &(table, entity_type, column, parent_table, parent_type) in PARENT_LINKS {
+            let missing: Vec<(String, String, String)> = sqlx::query_as(&format!(
+                r#"
+                SELECT c.id, c.title, c.{column}
+                FROM {table} c
+                WHERE c.{column} IS NOT NULL
+                  AND NOT EXISTS (SELECT 1 FROM {parent_table} p WHERE p.id = c.{column})
+                "#
+            ))
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("scan for missing parents", e))?;
+
+            issues.extend(missing.into_iter().map(|(id, title, parent_id)| {
+                DataIssue::new(DataIssueKind::MissingParent, entity_type, id, title)
+                    .with_parent(parent_type, parent_id)
+            }));
+
+            let under_archived: Vec<(String, String, String, DateTime<Utc>, bool)> = sqlx::query_as(&format!(
+                r#"
+                SELECT c.id, c.title, p.id, p.archived_at,
+                       julianday(c.updated_at) < julianday(p.archived_at) AS missed
+                FROM {table} c
+                JOIN {parent_table} p ON p.id = c.{column}
+                WHERE c.archived_at IS NULL AND p.archived_at IS NOT NULL
+                "#
+            ))
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("scan for archived parents", e))?;
+
+            issues.extend(under_archived.into_iter().map(|(id, title, parent_id, archived_at, missed)| {
+                let kind = if missed { DataIssueKind::MissedArchive } else { DataIssueKind::ArchivedParent };
+                DataIssue::new(kind, entity_type, id, title)
+                    .with_parent(parent_type, parent_id)
+                    .at(Some(archived_at))
+            }));
+        }
+
+        for (table, entity_type) in [("goals", "goal"), ("projects", "project"), ("tasks", "task")] {
+            let future: Vec<(String, String, DateTime<Utc>)> = sqlx::query_as(&format!(
+                "SELECT id, title, completed_at FROM {} WHERE julianday(completed_at) > julianday('now')",
+                table
+            ))
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("scan for future completions", e))?;
+
+            issues.extend(future.into_iter().map(|(id, title, completed_at)| {
+                DataIssue::new(DataIssueKind::FutureCompletion, entity_type, id, title).at(Some(completed_at))
+            }));
+        }
+
+        Ok(issues)
+    }
+
+    /// Fixes the given issues from `scan_data_issues` with one strategy, in a
+    /// single transaction, recording each repair in the audit log
+    ///
+    /// Fails without changing anything if an ID isn't among the current
+    /// issues or the strategy doesn't apply to one of them.
+    pub async fn repair_data_issues(
+        &self,
+        issue_ids: &[String],
+        strategy: RepairStrategy,
+    ) -> AppResult<DataRepairReport> {
+        let issues = self.scan_data_issues().await?;
+        let selected = issue_ids
+            .iter()
+            .map(|id| {
+                issues
+                    .iter()
+                    .find(|issue| &issue.id == id)
+                    .ok_or_else(|| AppError::not_found("Data issue", id))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let mut tx = self.begin_transaction().await?;
+        for issue in &selected {
+            Self::repair_data_issue_in(&mut tx, issue, strategy).await?;
+            Self::record_audit(
+                &mut tx,
+                "repair_data_issue",
+                &issue.entity_type,
+                &issue.entity_id,
+                serde_json::json!({
+                    "issue_id": issue.id,
+                    "kind": issue.kind,
+                    "strategy": strategy,
+                    "parent_type": issue.parent_type,
+                    "parent_id": issue.parent_id,
+                }),
+            )
+            .await?;
+        }
+        tx.commit().await.map_err(|e| AppError::database_error("commit data repair", e))?;
+
+        Ok(DataRepairReport {
+            repaired: selected.into_iter().map(|issue| issue.id.clone()).collect(),
+            remaining: self.scan_data_issues().await?.len(),
+        })
+    }
+
+    async fn repair_data_issue_in(
+        tx: &mut Transaction<'_, Sqlite>,
+        issue: &DataIssue,
+        strategy: RepairStrategy,
+    ) -> AppResult<()> {
+        let not_applicable = || {
+            AppError::validation_error("strategy", &format!("{} does not apply to issue {}", strategy, issue.id))
+        };
+        let id = issue.entity_id.as_str();
+
+        match (strategy, issue.kind) {
+            (RepairStrategy::ClearTimestamp, DataIssueKind::FutureCompletion) => {
+                let table = match issue.entity_type.as_str() {
+                    "goal" => "goals",
+                    "project" => "projects",
+                    _ => "tasks",
+                };
+                sqlx::query(&format!("UPDATE {} SET completed_at = NULL WHERE id = ?1", table))
+                    .bind(id)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| AppError::database_error("clear completion time", e))?;
+            }
+            (RepairStrategy::ClearTimestamp, _) | (_, DataIssueKind::FutureCompletion) => {
+                return Err(not_applicable());
+            }
+            (RepairStrategy::Detach, _) => {
+                let (table, column) = PARENT_LINKS
+                    .iter()
+                    .find(|link| link.1 == issue.entity_type && Some(link.4) == issue.parent_type.as_deref())
+                    .filter(|link| matches!(link.1, "task" | "note"))
+                    .map(|link| (link.0, link.2))
+                    .ok_or_else(not_applicable)?;
+                sqlx::query(&format!("UPDATE {} SET {} = NULL WHERE id = ?1", table, column))
+                    .bind(id)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| AppError::database_error("detach from parent", e))?;
+            }
+            (RepairStrategy::Archive, _) => {
+                let archived_at = issue.at.unwrap_or_else(Utc::now);
+                match issue.entity_type.as_str() {
+                    "goal" => Self::archive_goal_in(tx, id, archived_at).await?,
+                    "project" => Self::archive_project_in(tx, id, archived_at).await?,
+                    "task" => Self::archive_task_in(tx, id, archived_at).await?,
+                    _ => {
+                        sqlx::query("UPDATE notes SET archived_at = ?1 WHERE id = ?2")
+                            .bind(archived_at)
+                            .bind(id)
+                            .execute(&mut **tx)
+                            .await
+                            .map_err(|e| AppError::database_error("archive note", e))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Pin operations
+    /// Pins an entity to the end of the dashboard; pinning it again keeps
+    /// its place
+    pub async fn pin_entity(&self, entity_type: &str, entity_id: &str) -> AppResult<()> {
+        match self.get_entity_snapshot(entity_type, entity_id).await? {
+            None => return Err(AppError::not_found(entity_type, entity_id)),
+            Some(snapshot) if !snapshot["archived_at"].is_null() => {
+                return Err(AppError::validation_error("entity_id", "is archived"));
+            }
+            Some(_) => {}
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO pins (entity_type, entity_id, position, created_at)
+            VALUES (?1, ?2, (SELECT COALESCE(MAX(position), -1) + 1 FROM pins), ?3)
+            ON CONFLICT(entity_type, entity_id) DO NOTHING
+            "#
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(Utc::now())
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("pin entity", e))?;
+        Ok(())
+    }
+
+    pub async fn unpin_entity(&self, entity_type: &str, entity_id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM pins WHERE entity_type = ?1 AND entity_id = ?2")
+            .bind(entity_type)
+            .bind(entity_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("unpin entity", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Pin", entity_id));
+        }
+        Ok(())
+    }
+
+    /// Puts the given pins first, in order; the others keep their order
+    /// after them
+    pub async fn reorder_pins(&self, pins: &[Pin]) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+
+        let current: Vec<Pin> = sqlx::query_as::<_, (String, String)>(
+            "SELECT entity_type, entity_id FROM pins ORDER BY position, created_at"
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("reorder pins", e))?
+        .into_iter()
+        .map(|(entity_type, entity_id)| Pin { entity_type, entity_id })
+        .collect();
+
+        if let Some(unknown) = pins.iter().find(|pin| !current.contains(pin)) {
+            return Err(AppError::not_found("Pin", &unknown.entity_id));
+        }
+        let order = pins.iter().chain(current.iter().filter(|pin| !pins.contains(pin)));
+
+        for (position, pin) in order.enumerate() {
+            sqlx::query("UPDATE pins SET position = ?1 WHERE entity_type = ?2 AND entity_id = ?3")
+                .bind(position as i64)
+                .bind(&pin.entity_type)
+                .bind(&pin.entity_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("reorder pins", e))?;
+        }
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit pin order", e))
+    }
+
+    /// Pinned entities in dashboard order, leaving out archived ones
+    pub async fn get_pinned_items(&self) -> AppResult<Vec<PinnedItem>> {
+        sqlx::query_as::<_, PinnedItem>(
+            r#"
+            WITH open_tasks AS (
+                SELECT * FROM tasks WHERE archived_at IS NULL AND completed_at IS NULL
+            )
+            SELECT pins.entity_type, pins.entity_id, pins.position, pins.created_at AS pinned_at,
+                   la.name AS title, la.color AS display_color, la.icon AS display_icon, NULL AS progress,
+                   (SELECT COUNT(*) FROM open_tasks t
+                    JOIN projects p ON p.id = t.project_id
+                    JOIN goals g ON g.id = p.goal_id
+                    WHERE g.life_area_id = la.id) AS open_tasks,
+                   NULL AS due_date, NULL AS completed_at, la.updated_at
+            FROM pins JOIN life_areas la ON pins.entity_type = 'life_area' AND la.id = pins.entity_id
+            WHERE la.archived_at IS NULL
+            UNION ALL
+            SELECT pins.entity_type, pins.entity_id, pins.position, pins.created_at,
+                   g.title, a.display_color, a.display_icon, g.progress,
+                   (SELECT COUNT(*) FROM open_tasks t
+                    JOIN projects p ON p.id = t.project_id
+                    WHERE p.goal_id = g.id),
+                   g.target_date, g.completed_at, g.updated_at
+            FROM pins JOIN goals g ON pins.entity_type = 'goal' AND g.id = pins.entity_id
+            LEFT JOIN goal_appearance a ON a.id = g.id
+            WHERE g.archived_at IS NULL
+            UNION ALL
+            SELECT pins.entity_type, pins.entity_id, pins.position, pins.created_at,
+                   p.title, a.display_color, a.display_icon, p.progress,
+                   (SELECT COUNT(*) FROM open_tasks t WHERE t.project_id = p.id),
+                   NULL, p.completed_at, p.updated_at
+            FROM pins JOIN projects p ON pins.entity_type = 'project' AND p.id = pins.entity_id
+            LEFT JOIN project_appearance a ON a.id = p.id
+            WHERE p.archived_at IS NULL
+            UNION ALL
+            SELECT pins.entity_type, pins.entity_id, pins.position, pins.created_at,
+                   t.title, a.display_color, a.display_icon, NULL,
+                   (SELECT COUNT(*) FROM open_tasks s WHERE s.parent_task_id = t.id),
+                   t.due_date, t.completed_at, t.updated_at
+            FROM pins JOIN tasks t ON pins.entity_type = 'task' AND t.id = pins.entity_id
+            LEFT JOIN project_appearance a ON a.id = t.project_id
+            WHERE t.archived_at IS NULL
+            UNION ALL
+            SELECT pins.entity_type, pins.entity_id, pins.position, pins.created_at,
+                   n.title, NULL, NULL, NULL, NULL, NULL, NULL, n.updated_at
+            FROM pins JOIN notes n ON pins.entity_type = 'note' AND n.id = pins.entity_id
+            WHERE n.archived_at IS NULL
+            ORDER BY position, pinned_at
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get pinned items", e))
+    }
+
+    // Switcher operations
+    /// Titles of every life area, goal, project, task and note that isn't
+    /// archived, for the quick switcher's index
+    pub async fn get_switcher_entries(&self) -> AppResult<Vec<SwitcherEntry>> {
+        sqlx::query_as::<_, SwitcherEntry>(
+            r#"
+            SELECT 'life_area' AS entity_type, id, name AS title FROM life_areas WHERE archived_at IS NULL
+            UNION ALL
+            SELECT 'goal', id, title FROM goals WHERE archived_at IS NULL
+            UNION ALL
+            SELECT 'project', id, title FROM projects WHERE archived_at IS NULL
+            UNION ALL
+            SELECT 'task', id, title FROM tasks WHERE archived_at IS NULL
+            UNION ALL
+            SELECT 'note', id, title FROM notes WHERE archived_at IS NULL
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get switcher entries", e))
+    }
+
+    /// The switcher's entry for one entity, `None` once it is deleted or archived
+    pub async fn get_switcher_entry(&self, entity_type: &str, id: &str) -> AppResult<Option<SwitcherEntry>> {
+        let (table, title) = match entity_type {
+            "life_area" => ("life_areas", "name"),
+            "goal" => ("goals", "title"),
+            "project" => ("projects", "title"),
+            "task" => ("tasks", "title"),
+            "note" => ("notes", "title"),
+            _ => return Ok(None),
+        };
+        sqlx::query_as::<_, SwitcherEntry>(&format!(
+            "SELECT ?1 AS entity_type, id, {} AS title FROM {} WHERE id = ?2 AND archived_at IS NULL",
+            title, table
+        ))
+        .bind(entity_type)
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get switcher entry", e))
+    }
+
+    // Note graph operations
+    /// The tags of every note, as `(note_id, tag_id, tag_name)`
+    pub async fn get_note_tag_links(&self) -> AppResult<Vec<(String, String, String)>> {
+        sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT nt.note_id, t.id, t.name
+            FROM note_tags nt
+            JOIN tags t ON t.id = nt.tag_id
+            ORDER BY t.name
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get note tags", e))
+    }
+
+    /// What each task, project and goal that isn't archived sits under, as
+    /// `(entity_type, id, parent_type, parent_id)`; subtasks sit under their
+    /// parent task
+    pub async fn get_entity_parents(&self) -> AppResult<Vec<(String, String, String, String)>> {
+        sqlx::query_as::<_, (String, String, String, String)>(
+            r#"
+            SELECT 'task', id,
+                   CASE WHEN parent_task_id IS NULL THEN 'project' ELSE 'task' END,
+                   COALESCE(parent_task_id, project_id)
+            FROM tasks
+            WHERE archived_at IS NULL AND COALESCE(parent_task_id, project_id) IS NOT NULL
+            UNION ALL
+            SELECT 'project', id, 'goal', goal_id FROM projects WHERE archived_at IS NULL
+            UNION ALL
+            SELECT 'goal', id, 'life_area', life_area_id FROM goals WHERE archived_at IS NULL
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get entity parents", e))
+    }
+
+    // Note mention operations
+    /// Tasks and projects that aren't archived, as `(entity_type, id, title)`,
+    /// for finding the ones notes mention
+    pub async fn get_mention_candidates(&self) -> AppResult<Vec<(String, String, String)>> {
+        sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT 'task', id, title FROM tasks WHERE archived_at IS NULL
+            UNION ALL
+            SELECT 'project', id, title FROM projects WHERE archived_at IS NULL
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get mention candidates", e))
+    }
+
+    /// Every note as `(id, content, is_private)`, archived ones included
+    pub async fn get_note_contents(&self) -> AppResult<Vec<(String, String, bool)>> {
+        sqlx::query_as::<_, (String, String, bool)>("SELECT id, content, is_private FROM notes ORDER BY created_at")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get note contents", e))
+    }
+
+    /// Replaces the mentions recorded for a note
+    pub async fn set_note_mentions(&self, note_id: &str, mentions: &[NoteMention]) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query("DELETE FROM note_mentions WHERE note_id = ?1")
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("clear note mentions", e))?;
+        for mention in mentions {
+            sqlx::query(
+                "INSERT OR IGNORE INTO note_mentions (note_id, entity_type, entity_id, matched_by) VALUES (?1, ?2, ?3, ?4)",
+            )
+            .bind(note_id)
+            .bind(&mention.entity_type)
+            .bind(&mention.entity_id)
+            .bind(&mention.matched_by)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("add note mention", e))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit note mentions", e))
+    }
+
+    /// The tasks and projects a note mentions that still exist, in the order
+    /// they appear in the note
+    pub async fn get_note_mentions(&self, note_id: &str) -> AppResult<Vec<ReferencedEntity>> {
+        self.get_note(note_id).await?;
+
+        sqlx::query_as::<_, ReferencedEntity>(
+            r#"
+            SELECT m.entity_type, m.entity_id AS id,
+                   COALESCE(t.title, p.title) AS title,
+                   COALESCE(t.archived_at, p.archived_at) IS NOT NULL AS archived
+            FROM note_mentions m
+            LEFT JOIN tasks t ON m.entity_type = 'task' AND t.id = m.entity_id
+            LEFT JOIN projects p ON m.entity_type = 'project' AND p.id = m.entity_id
+            WHERE m.note_id = ?1 AND COALESCE(t.title, p.title) IS NOT NULL
+            ORDER BY m.rowid
+            "#
+        )
+        .bind(note_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get note mentions", e))
+    }
+
+    /// Every recorded mention, as `(note_id, entity_type, entity_id)`
+    pub async fn get_note_mention_links(&self) -> AppResult<Vec<(String, String, String)>> {
+        sqlx::query_as::<_, (String, String, String)>(
+            "SELECT note_id, entity_type, entity_id FROM note_mentions ORDER BY rowid",
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get note mentions", e))
+    }
+
+    // Storage operations
+    /// Counts what storage advice looks at; tasks completed before `cutoff`
+    /// without open subtasks count as archivable
+    pub async fn get_storage_counts(&self, cutoff: DateTime<Utc>) -> AppResult<StorageCounts> {
+        sqlx::query_as::<_, StorageCounts>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM tasks WHERE completed_at IS NOT NULL AND archived_at IS NULL) AS completed_tasks,
+                (SELECT COUNT(*) FROM tasks WHERE completed_at < ?1 AND archived_at IS NULL
+                   AND NOT EXISTS (SELECT 1 FROM tasks s WHERE s.parent_task_id = tasks.id
+                                   AND s.completed_at IS NULL AND s.archived_at IS NULL)) AS archivable_tasks,
+                (SELECT COUNT(*) FROM life_areas WHERE archived_at IS NOT NULL)
+                  + (SELECT COUNT(*) FROM goals WHERE archived_at IS NOT NULL)
+                  + (SELECT COUNT(*) FROM projects WHERE archived_at IS NOT NULL)
+                  + (SELECT COUNT(*) FROM tasks WHERE archived_at IS NOT NULL)
+                  + (SELECT COUNT(*) FROM notes WHERE archived_at IS NOT NULL) AS archived_items,
+                (SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()) AS database_bytes,
+                (SELECT freelist_count * page_size FROM pragma_freelist_count(), pragma_page_size()) AS free_bytes
+            "#
+        )
+        .bind(cutoff)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get storage counts", e))
+    }
+
+    /// Archives the tasks completed before `cutoff` that have no open
+    /// subtasks, cascading to their subtasks and notes as archiving a single
+    /// task does; returns how many tasks were archived, subtasks included
+    pub async fn archive_completed_tasks(&self, cutoff: DateTime<Utc>) -> AppResult<u64> {
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+
+        let archived = sqlx::query(
+            r#"
+            UPDATE tasks SET archived_at = ?1
+            WHERE completed_at < ?2 AND archived_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM tasks s WHERE s.parent_task_id = tasks.id
+                              AND s.completed_at IS NULL AND s.archived_at IS NULL)
+            "#
+        )
+        .bind(now)
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("archive completed tasks", e))?
+        .rows_affected();
+
+        // Subtasks completed after the cutoff go with their parent
+        let subtasks = sqlx::query(
+            r#"
+            UPDATE tasks SET archived_at = ?1
+            WHERE archived_at IS NULL
+              AND parent_task_id IN (SELECT id FROM tasks WHERE archived_at = ?1)
+            "#
+        )
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("cascade archive subtasks", e))?
+        .rows_affected();
+
+        sqlx::query(
+            "UPDATE notes SET archived_at = ?1 WHERE archived_at IS NULL AND task_id IN (SELECT id FROM tasks WHERE archived_at = ?1)"
+        )
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("cascade archive notes", e))?;
+
+        tx.commit().await?;
+        Ok(archived + subtasks)
+    }
+
+    // Audit log operations
+    async fn record_audit(
+        tx: &mut Transaction<'_, Sqlite>,
+        action: &str,
+        entity_type: &str,
+        entity_id: &str,
+        details: serde_json::Value,
+    ) -> AppResult<()> {
+        sqlx::query("INSERT INTO audit_log (action, entity_type, entity_id, details) VALUES (?1, ?2, ?3, ?4)")
+            .bind(action)
+            .bind(entity_type)
+            .bind(entity_id)
+            .bind(details.to_string())
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("record audit entry", e))?;
+        Ok(())
+    }
+
+    /// Returns the most recent audit entries, newest first
+    pub async fn get_audit_log(&self, limit: i64) -> AppResult<Vec<AuditLogEntry>> {
+        sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            SELECT seq, action, entity_type, entity_id, details, recorded_at
+            FROM audit_log
+            ORDER BY seq DESC
+            LIMIT ?1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get audit log", e))
+    }
+
+    // Change log operations
+    pub async fn get_changes_since(&self, since_seq: i64, limit: i64) -> AppResult<Vec<ChangeLogEntry>> {
+        sqlx::query_as::<_, ChangeLogEntry>(
+            r#"
+            SELECT seq, entity_type, entity_id, operation, changed_at
+            FROM change_log
+            WHERE seq > ?1
+            ORDER BY seq ASC
+            LIMIT ?2
+            "#
+        )
+        .bind(since_seq)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get changes since", e))
+    }
+
+    pub async fn get_latest_change_seq(&self) -> AppResult<i64> {
+        let seq: Option<i64> = sqlx::query_scalar("SELECT MAX(seq) FROM change_log")
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get latest change seq", e))?;
+
+        Ok(seq.unwrap_or(0))
+    }
+
+    /// Loads the current row of a change-log entity as JSON, if it still exists
+    pub async fn get_entity_snapshot(&self, entity_type: &str, id: &str) -> AppResult<Option<serde_json::Value>> {
+        let snapshot = match entity_type {
+            "life_area" => sqlx::query_as::<_, LifeArea>("SELECT * FROM life_areas WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&*self.pool)
+                .await?
+                .map(serde_json::to_value),
+            "goal" => sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&*self.pool)
+                .await?
+                .map(serde_json::to_value),
+            "project" => sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&*self.pool)
+                .await?
+                .map(serde_json::to_value),
+            "task" => sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&*self.pool)
+                .await?
+                .map(serde_json::to_value),
+            "note" => sqlx::query_as::<_, Note>("SELECT * FROM notes WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&*self.pool)
+                .await?
+                .map(serde_json::to_value),
+            "tag" => sqlx::query_as::<_, Tag>("SELECT * FROM tags WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&*self.pool)
+                .await?
+                .map(serde_json::to_value),
+            _ => None,
+        };
+
+        Ok(snapshot.transpose()?)
+    }
+
+    // Webhook operations
+    pub async fn get_webhooks(&self) -> AppResult<Vec<Webhook>> {
+        sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks ORDER BY created_at")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get webhooks", e))
+    }
+
+    pub async fn get_active_webhooks(&self) -> AppResult<Vec<Webhook>> {
+        sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE is_active = 1 ORDER BY created_at")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get active webhooks", e))
+    }
+
+    pub async fn get_webhook(&self, id: &str) -> AppResult<Webhook> {
+        sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("Webhook", id),
+                _ => AppError::database_error("get webhook", e),
+            })
+    }
+
+    /// Registers a webhook with a freshly generated signing secret
+    pub async fn create_webhook(&self, input: WebhookInput) -> AppResult<Webhook> {
+        let id = Uuid::new_v4().to_string();
+        let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let events = serde_json::to_string(&input.events)?;
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO webhooks (id, name, url, secret, events, is_active, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.name)
+        .bind(&input.url)
+        .bind(&secret)
+        .bind(&events)
+        .bind(input.is_active)
+        .bind(&now)
+        .bind(&now)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("create webhook", e))?;
+
+        self.get_webhook(&id).await
+    }
+
+    pub async fn update_webhook(&self, id: &str, input: WebhookInput) -> AppResult<Webhook> {
+        let events = serde_json::to_string(&input.events)?;
+
+        sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET name = ?1, url = ?2, events = ?3, is_active = ?4
+            WHERE id = ?5
+            "#
+        )
+        .bind(&input.name)
+        .bind(&input.url)
+        .bind(&events)
+        .bind(input.is_active)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("update webhook", e))?;
+
+        self.get_webhook(id).await
+    }
+
+    pub async fn delete_webhook(&self, id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete webhook", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Webhook", id));
+        }
+
+        Ok(())
+    }
+
+    /// Stores the outcome of the most recent delivery attempt
+    pub async fn record_webhook_delivery(
+        &self,
+        id: &str,
+        status_code: Option<i64>,
+        error: Option<&str>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET last_delivery_at = ?1, last_status_code = ?2, last_error = ?3
+            WHERE id = ?4
+            "#
+        )
+        .bind(Utc::now())
+        .bind(status_code)
+        .bind(error)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("record webhook delivery", e))?;
+
+        Ok(())
+    }
+
+    // Settings operations
+    /// Returns the stored (non-default) settings as JSON values by key
+    pub async fn get_setting_values(&self) -> AppResult<serde_json::Map<String, serde_json::Value>> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM settings")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get settings", e))?;
+
+        rows.into_iter()
+            .map(|(key, value)| Ok((key, serde_json::from_str(&value)?)))
+            .collect()
+    }
+
+    /// Writes settings in one transaction; a null value removes the stored
+    /// value so the setting falls back to its default
+    pub async fn write_setting_values(&self, values: &serde_json::Map<String, serde_json::Value>) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+        let now = Utc::now();
+
+        for (key, value) in values {
+            if value.is_null() {
+                sqlx::query("DELETE FROM settings WHERE key = ?1")
+                    .bind(key)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| AppError::database_error("reset setting", e))?;
+            } else {
+                sqlx::query(
+                    r#"
+                    INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+                    ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+                    "#
+                )
+                .bind(key)
+                .bind(value.to_string())
+                .bind(now)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("write setting", e))?;
+            }
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit settings", e))?;
+        Ok(())
+    }
+
+    /// Permanently deletes entities archived before `cutoff`, skipping the
+    /// entity types in `excluded`
+    ///
+    /// Children are purged before their parents, and a parent is only purged
+    /// once nothing is left below it, since the hierarchy's foreign keys
+    /// refuse to delete a parent that still has children.
+    /// Returns (table, deleted rows) for each table that had rows deleted.
+    pub async fn purge_archived_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        excluded: &[String],
+    ) -> AppResult<Vec<(&'static str, u64)>> {
+        const TABLES: &[(&str, &str, &str)] = &[
+            ("note", "notes", ""),
+            (
+                "task",
+                "tasks",
+                "AND NOT EXISTS (SELECT 1 FROM tasks c WHERE c.parent_task_id = tasks.id)
+                 AND NOT EXISTS (SELECT 1 FROM notes c WHERE c.task_id = tasks.id)",
+            ),
+            (
+                "project",
+                "projects",
+                "AND NOT EXISTS (SELECT 1 FROM tasks c WHERE c.project_id = projects.id)
+                 AND NOT EXISTS (SELECT 1 FROM notes c WHERE c.project_id = projects.id)",
+            ),
+            (
+                "goal",
+                "goals",
+                "AND NOT EXISTS (SELECT 1 FROM projects c WHERE c.goal_id = goals.id)
+                 AND NOT EXISTS (SELECT 1 FROM notes c WHERE c.goal_id = goals.id)",
+            ),
+            (
+                "life_area",
+                "life_areas",
+                "AND NOT EXISTS (SELECT 1 FROM goals c WHERE c.life_area_id = life_areas.id)
+                 AND NOT EXISTS (SELECT 1 FROM notes c WHERE c.life_area_id = life_areas.id)",
+            ),
+        ];
+
+        let mut deleted = Vec::new();
+
+        for (entity_type, table, no_children) in TABLES {
+            if excluded.iter().any(|t| t == entity_type) {
+                continue;
+            }
+
+            // Subtasks are only gone after their own pass, so repeat until
+            // nothing more can be deleted
+            let mut total = 0;
+            loop {
+                let result = sqlx::query(&format!(
+                    "DELETE FROM {} WHERE archived_at IS NOT NULL AND archived_at < ?1 {}",
+                    table, no_children
+                ))
+                .bind(cutoff)
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| AppError::database_error("purge archived items", e))?;
+
+                total += result.rows_affected();
+                if result.rows_affected() == 0 || *table != "tasks" {
+                    break;
+                }
+            }
+
+            if total > 0 {
+                deleted.push((*table, total));
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Permanently deletes an entity with everything below it, so that none of
+    /// its data can be recovered from the database file
+    ///
+    /// The rows are deleted with `secure_delete` on, which zeroes the freed
+    /// content, then the database is vacuumed and the WAL checkpointed and
+    /// truncated so no stale copies of the pages remain. Attachment files
+    /// live outside the database; the report lists the content hashes no
+    /// longer referenced so the caller can shred those files.
+    pub async fn secure_delete(&self, entity_type: &str, id: &str) -> AppResult<SecureDeleteReport> {
+        let name = match entity_type {
+            "life_area" => "Life area",
+            "goal" => "Goal",
+            "project" => "Project",
+            "task" => "Task",
+            "note" => "Note",
+            _ => return Err(AppError::validation_error("entity_type", "must be life_area, goal, project, task or note")),
+        };
+
+        // The entity and everything below it, parents before children; the
+        // hierarchy doesn't cascade, so rows are deleted in reverse order.
+        // Links and attachments have no foreign key and go along with them
+        let entities: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            WITH RECURSIVE
+                la(id) AS (SELECT id FROM life_areas WHERE ?2 = 'life_area' AND id = ?1),
+                g(id) AS (
+                    SELECT id FROM goals
+                    WHERE (?2 = 'goal' AND id = ?1) OR life_area_id IN (SELECT id FROM la)
+                ),
+                p(id) AS (
+                    SELECT id FROM projects
+                    WHERE (?2 = 'project' AND id = ?1) OR goal_id IN (SELECT id FROM g)
+                ),
+                t(id, depth) AS (
+                    SELECT id, 0 FROM tasks
+                    WHERE (?2 = 'task' AND id = ?1) OR project_id IN (SELECT id FROM p)
+                    UNION
+                    SELECT tasks.id, t.depth + 1 FROM tasks JOIN t ON tasks.parent_task_id = t.id
+                ),
+                n(id) AS (
+                    SELECT id FROM notes
+                    WHERE (?2 = 'note' AND id = ?1)
+                       OR life_area_id IN (SELECT id FROM la)
+                       OR goal_id IN (SELECT id FROM g)
+                       OR project_id IN (SELECT id FROM p)
+                       OR task_id IN (SELECT id FROM t)
+                )
+            SELECT 'life_area', id FROM la
+            UNION ALL SELECT 'goal', id FROM g
+            UNION ALL SELECT 'project', id FROM p
+            UNION ALL SELECT 'task', id FROM (SELECT id FROM t GROUP BY id ORDER BY MAX(depth))
+            UNION ALL SELECT 'note', id FROM n
+            "#
+        )
+        .bind(id)
+        .bind(entity_type)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("collect entities to delete", e))?;
+
+        if entities.is_empty() {
+            return Err(AppError::not_found(name, id));
+        }
+
+        let mut conn = self.pool.acquire().await
+            .map_err(|e| AppError::database_error("acquire connection", e))?;
+        sqlx::query("PRAGMA secure_delete = ON")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::database_error("enable secure delete", e))?;
+
+        let result = async {
+            let mut tx = sqlx::Connection::begin(&mut *conn).await
+                .map_err(|e| AppError::database_error("begin transaction", e))?;
+
+            let mut links_deleted = 0;
+            let mut attachments_deleted = 0;
+            let mut hashes = Vec::new();
+            for (entity_type, entity_id) in &entities {
+                links_deleted += sqlx::query("DELETE FROM external_links WHERE entity_type = ?1 AND entity_id = ?2")
+                    .bind(entity_type)
+                    .bind(entity_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| AppError::database_error("delete links", e))?
+                    .rows_affected();
+
+                let deleted: Vec<String> = sqlx::query_scalar(
+                    "DELETE FROM attachments WHERE entity_type = ?1 AND entity_id = ?2 RETURNING content_hash"
+                )
+                .bind(entity_type)
+                .bind(entity_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("delete attachments", e))?;
+                attachments_deleted += deleted.len() as u64;
+                hashes.extend(deleted);
+            }
+            hashes.sort();
+            hashes.dedup();
+
+            // Content shared with attachments outside the deleted subtree stays
+            let mut unreferenced_hashes = Vec::new();
+            for hash in hashes {
+                let still_used: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM attachments WHERE content_hash = ?1)")
+                    .bind(&hash)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| AppError::database_error("check attachment content", e))?;
+                if !still_used {
+                    unreferenced_hashes.push(hash);
+                }
+            }
+
+            for (entity_type, entity_id) in entities.iter().rev() {
+                let table = match entity_type.as_str() {
+                    "life_area" => "life_areas",
+                    "goal" => "goals",
+                    "project" => "projects",
+                    "task" => "tasks",
+                    _ => "notes",
+                };
+                sqlx::query(&format!("DELETE FROM {} WHERE id = ?1", table))
+                    .bind(entity_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| AppError::database_error("secure delete", e))?;
+            }
+
+            tx.commit().await
+                .map_err(|e| AppError::database_error("commit secure delete", e))?;
+            Ok::<_, AppError>((links_deleted, attachments_deleted, unreferenced_hashes))
+        }
+        .await;
+
+        // The pooled connection goes back with the default setting either way
+        let _ = sqlx::query("PRAGMA secure_delete = OFF").execute(&mut *conn).await;
+        let (links_deleted, attachments_deleted, unreferenced_hashes) = result?;
+
+        sqlx::query("VACUUM")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::database_error("vacuum database", e))?;
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::database_error("checkpoint database", e))?;
+
+        Ok(SecureDeleteReport {
+            entities_deleted: entities.len(),
+            links_deleted,
+            attachments_deleted,
+            unreferenced_hashes,
+        })
+    }
+
+    // Script hook operations
+    pub async fn get_script_hooks(&self) -> AppResult<Vec<ScriptHook>> {
+        sqlx::query_as::<_, ScriptHook>("SELECT * FROM script_hooks ORDER BY created_at")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get script hooks", e))
+    }
+
+    pub async fn get_active_script_hooks(&self) -> AppResult<Vec<ScriptHook>> {
+        sqlx::query_as::<_, ScriptHook>("SELECT * FROM script_hooks WHERE is_active = 1 ORDER BY created_at")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get active script hooks", e))
+    }
+
+    pub async fn get_script_hook(&self, id: &str) -> AppResult<ScriptHook> {
+        sqlx::query_as::<_, ScriptHook>("SELECT * FROM script_hooks WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("Script hook", id),
+                _ => AppError::database_error("get script hook", e),
+            })
+    }
+
+    pub async fn create_script_hook(&self, input: ScriptHookInput) -> AppResult<ScriptHook> {
+        let id = Uuid::new_v4().to_string();
+        let events = serde_json::to_string(&input.events)?;
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO script_hooks (id, name, events, script, is_active, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.name)
+        .bind(&events)
+        .bind(&input.script)
+        .bind(input.is_active)
+        .bind(&now)
+        .bind(&now)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("create script hook", e))?;
+
+        self.get_script_hook(&id).await
+    }
+
+    pub async fn update_script_hook(&self, id: &str, input: ScriptHookInput) -> AppResult<ScriptHook> {
+        let events = serde_json::to_string(&input.events)?;
+
+        sqlx::query(
+            r#"
+            UPDATE script_hooks
+            SET name = ?1, events = ?2, script = ?3, is_active = ?4
+            WHERE id = ?5
+            "#
+        )
+        .bind(&input.name)
+        .bind(&events)
+        .bind(&input.script)
+        .bind(input.is_active)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("update script hook", e))?;
+
+        self.get_script_hook(id).await
+    }
+
+    pub async fn delete_script_hook(&self, id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM script_hooks WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete script hook", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Script hook", id));
+        }
+
+        Ok(())
+    }
+
+    /// Stores the outcome of the most recent run
+    pub async fn record_script_hook_run(&self, id: &str, error: Option<&str>) -> AppResult<()> {
+        sqlx::query("UPDATE script_hooks SET last_run_at = ?1, last_error = ?2 WHERE id = ?3")
+            .bind(Utc::now())
+            .bind(error)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("record script hook run", e))?;
+
+        Ok(())
+    }
+
+    // Email import operations
+    pub async fn get_email_accounts(&self) -> AppResult<Vec<EmailAccount>> {
+        sqlx::query_as::<_, EmailAccount>("SELECT * FROM email_accounts ORDER BY created_at")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get email accounts", e))
+    }
+
+    pub async fn get_email_account(&self, id: &str) -> AppResult<EmailAccount> {
+        sqlx::query_as::<_, EmailAccount>("SELECT * FROM email_accounts WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("Email account", id),
+                _ => AppError::database_error("get email account", e),
+            })
+    }
+
+    pub async fn create_email_account(&self, input: EmailAccountInput) -> AppResult<EmailAccount> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO email_accounts (id, name, host, port, username, password, folder,
+                                        search_query, poll_interval_minutes, is_active,
+                                        created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.name)
+        .bind(&input.host)
+        .bind(input.port)
+        .bind(&input.username)
+        .bind(input.password.unwrap_or_default())
+        .bind(&input.folder)
+        .bind(&input.search_query)
+        .bind(input.poll_interval_minutes)
+        .bind(input.is_active)
+        .bind(&now)
+        .bind(&now)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("create email account", e))?;
+
+        self.get_email_account(&id).await
+    }
+
+    pub async fn update_email_account(&self, id: &str, input: EmailAccountInput) -> AppResult<EmailAccount> {
+
+        sqlx::query(
+            r#"
+            UPDATE email_accounts
+            SET name = ?1, host = ?2, port = ?3, username = ?4, password = COALESCE(?5, password),
+                folder = ?6, search_query = ?7, poll_interval_minutes = ?8, is_active = ?9
+            WHERE id = ?10
+            "#
+        )
+        .bind(&input.name)
+        .bind(&input.host)
+        .bind(input.port)
+        .bind(&input.username)
+        .bind(&input.password)
+        .bind(&input.folder)
+        .bind(&input.search_query)
+        .bind(input.poll_interval_minutes)
+        .bind(input.is_active)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("update email account", e))?;
+
+        self.get_email_account(id).await
+    }
+
+    pub async fn delete_email_account(&self, id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM email_accounts WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete email account", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Email account", id));
+        }
+
+        Ok(())
+    }
+
+    /// Stores the time and error (if any) of the latest poll
+    pub async fn record_email_poll(&self, id: &str, error: Option<&str>) -> AppResult<()> {
+        sqlx::query("UPDATE email_accounts SET last_polled_at = ?1, last_error = ?2 WHERE id = ?3")
+            .bind(Utc::now())
+            .bind(error)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("record email poll", e))?;
+
+        Ok(())
+    }
+
+    pub async fn is_email_imported(&self, account_id: &str, message_id: &str) -> AppResult<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM imported_emails WHERE account_id = ?1 AND message_id = ?2)"
+        )
+        .bind(account_id)
+        .bind(message_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("check imported email", e))?;
+
+        Ok(exists)
+    }
+
+    /// Creates an inbox task with the email body attached as a note
+    ///
+    /// Returns `None` without creating anything if the Message-ID was already
+    /// imported for this account.
+    pub async fn import_email_as_task(&self, account_id: &str, email: EmailImport) -> AppResult<Option<Task>> {
+        let mut tx = self.begin_transaction().await?;
+        let task_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        let claimed = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO imported_emails (account_id, message_id, task_id, imported_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#
+        )
+        .bind(account_id)
+        .bind(&email.message_id)
+        .bind(&task_id)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("record imported email", e))?;
+
+        if claimed.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let title: String = email.subject.chars().take(MAX_TITLE_LENGTH).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, title, priority, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#
+        )
+        .bind(&task_id)
+        .bind(&title)
+        .bind(TaskPriority::default().to_string())
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("create task from email", e))?;
+
+        let content = match &email.from {
+            Some(from) => format!("From: {}\n\n{}", from, email.body),
+            None => email.body,
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO notes (id, task_id, title, content, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&task_id)
+        .bind(&title)
+        .bind(&content)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("create note from email", e))?;
+
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit email import", e))?;
+
+        self.get_task(&task_id).await.map(Some)
+    }
+
+    // Job operations
+    pub async fn get_jobs(&self) -> AppResult<Vec<Job>> {
+        sqlx::query_as::<_, Job>("SELECT * FROM jobs ORDER BY job_type")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get jobs", e))
+    }
+
+    pub async fn get_job(&self, job_type: JobType) -> AppResult<Job> {
+        sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE job_type = ?1")
+            .bind(job_type)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("Job", &job_type.to_string()),
+                _ => AppError::database_error("get job", e),
+            })
+    }
+
+    pub async fn set_job_enabled(&self, job_type: JobType, enabled: bool) -> AppResult<Job> {
+        let result = sqlx::query("UPDATE jobs SET enabled = ?1, updated_at = ?2 WHERE job_type = ?3")
+            .bind(enabled)
+            .bind(Utc::now())
+            .bind(job_type)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set job enabled", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Job", &job_type.to_string()));
+        }
+
+        self.get_job(job_type).await
+    }
+
+    /// Replaces the schedule; the caller validates it with `Schedule::parse`
+    pub async fn set_job_schedule(&self, job_type: JobType, schedule: &str) -> AppResult<Job> {
+        let result = sqlx::query("UPDATE jobs SET schedule = ?1, updated_at = ?2 WHERE job_type = ?3")
+            .bind(schedule)
+            .bind(Utc::now())
+            .bind(job_type)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("set job schedule", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Job", &job_type.to_string()));
+        }
+
+        self.get_job(job_type).await
+    }
+
+    /// Stores when the latest run started, how long it took and its error, if any
+    pub async fn record_job_run(
+        &self,
+        job_type: JobType,
+        started_at: DateTime<Utc>,
+        duration_ms: i64,
+        error: Option<&str>,
+    ) -> AppResult<Job> {
+        sqlx::query(
+            "UPDATE jobs SET last_run_at = ?1, last_duration_ms = ?2, last_error = ?3 WHERE job_type = ?4"
+        )
+        .bind(started_at)
+        .bind(duration_ms)
+        .bind(error)
+        .bind(job_type)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("record job run", e))?;
+
+        self.get_job(job_type).await
+    }
+
+    // Attachment operations
+    pub async fn get_attachment(&self, id: &str) -> AppResult<Attachment> {
+        sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("Attachment", id),
+                _ => AppError::database_error("get attachment", e),
+            })
+    }
+
+    pub async fn get_attachments_for(&self, entity_type: &str, entity_id: &str) -> AppResult<Vec<Attachment>> {
+        sqlx::query_as::<_, Attachment>(
+            "SELECT * FROM attachments WHERE entity_type = ?1 AND entity_id = ?2 ORDER BY created_at"
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get attachments", e))
+    }
+
+    /// Records an attachment whose content is already in the attachment store
+    pub async fn create_attachment(
+        &self,
+        entity_type: &str,
+        entity_id: &str,
+        file_name: &str,
+        mime_type: Option<&str>,
+        size_bytes: u64,
+        content_hash: &str,
+    ) -> AppResult<Attachment> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO attachments (id, entity_type, entity_id, file_name, mime_type, size_bytes, content_hash, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#
+        )
+        .bind(&id)
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(file_name)
+        .bind(mime_type)
+        .bind(size_bytes as i64)
+        .bind(content_hash)
+        .bind(Utc::now())
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("create attachment", e))?;
+
+        self.get_attachment(&id).await
+    }
+
+    /// Deletes an attachment row; returns it with whether its content is
+    /// still used by another attachment
+    pub async fn delete_attachment(&self, id: &str) -> AppResult<(Attachment, bool)> {
+        let attachment = self.get_attachment(id).await?;
+
+        sqlx::query("DELETE FROM attachments WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete attachment", e))?;
+
+        let still_used = self.is_content_referenced(&attachment.content_hash).await?;
+        Ok((attachment, still_used))
+    }
+
+    pub async fn is_content_referenced(&self, content_hash: &str) -> AppResult<bool> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM attachments WHERE content_hash = ?1)")
+            .bind(content_hash)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("check attachment content", e))
+    }
+
+    /// Size of the stored attachment files, counting content attached more
+    /// than once a single time
+    pub async fn get_attachment_storage_bytes(&self) -> AppResult<i64> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(size_bytes), 0)
+            FROM (SELECT MAX(size_bytes) AS size_bytes FROM attachments GROUP BY content_hash)
+            "#
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get attachment storage", e))
+    }
+
+    /// Attachment count and size per entity, largest first
+    pub async fn get_attachment_usage_by_entity(&self) -> AppResult<Vec<EntityAttachmentUsage>> {
+        sqlx::query_as::<_, EntityAttachmentUsage>(
+            r#"
+            SELECT a.entity_type, a.entity_id,
+                   CASE a.entity_type
+                       WHEN 'life_area' THEN (SELECT name FROM life_areas WHERE id = a.entity_id)
+                       WHEN 'goal' THEN (SELECT title FROM goals WHERE id = a.entity_id)
+                       WHEN 'project' THEN (SELECT title FROM projects WHERE id = a.entity_id)
+                       WHEN 'task' THEN (SELECT title FROM tasks WHERE id = a.entity_id)
+                       WHEN 'note' THEN (SELECT title FROM notes WHERE id = a.entity_id)
+                   END AS title,
+                   COUNT(*) AS attachment_count,
+                   SUM(a.size_bytes) AS size_bytes
+            FROM attachments a
+            GROUP BY a.entity_type, a.entity_id
+            ORDER BY size_bytes DESC, a.entity_type, a.entity_id
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get attachment usage", e))
+    }
+
+    /// Content hashes referenced by any attachment
+    pub async fn get_attachment_hashes(&self) -> AppResult<std::collections::HashSet<String>> {
+        let hashes: Vec<String> = sqlx::query_scalar("SELECT DISTINCT content_hash FROM attachments")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get attachment hashes", e))?;
+        Ok(hashes.into_iter().collect())
+    }
+
+    /// Attachment rows whose entity no longer exists; deleted when `delete`
+    /// is set. Returns the number of such rows.
+    pub async fn purge_dangling_attachments(&self, delete: bool) -> AppResult<u64> {
+        const DANGLING: &str = r#"
+            (entity_type = 'life_area' AND entity_id NOT IN (SELECT id FROM life_areas))
+            OR (entity_type = 'goal' AND entity_id NOT IN (SELECT id FROM goals))
+            OR (entity_type = 'project' AND entity_id NOT IN (SELECT id FROM projects))
+            OR (entity_type = 'task' AND entity_id NOT IN (SELECT id FROM tasks))
+            OR (entity_type = 'note' AND entity_id NOT IN (SELECT id FROM notes))
+        "#;
+
+        if !delete {
+            let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM attachments WHERE {}", DANGLING))
+                .fetch_one(&*self.pool)
+                .await
+                .map_err(|e| AppError::database_error("count dangling attachments", e))?;
+            return Ok(count as u64);
+        }
+
+        let result = sqlx::query(&format!("DELETE FROM attachments WHERE {}", DANGLING))
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete dangling attachments", e))?;
+        Ok(result.rows_affected())
+    }
+
+    // Attachment text operations
+    /// Stored content no extraction was recorded for yet, oldest first
+    pub async fn get_pending_attachment_content(&self, limit: i64) -> AppResult<Vec<PendingAttachmentContent>> {
+        sqlx::query_as::<_, PendingAttachmentContent>(
+            r#"
+            SELECT content_hash, MIN(file_name) AS file_name, MIN(mime_type) AS mime_type,
+                   MAX(size_bytes) AS size_bytes
+            FROM attachments a
+            WHERE NOT EXISTS (SELECT 1 FROM attachment_extractions e WHERE e.content_hash = a.content_hash)
+            GROUP BY content_hash
+            ORDER BY MIN(created_at)
+            LIMIT ?1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get pending attachment content", e))
+    }
+
+    /// Records what extracting a stored file gave, replacing its indexed
+    /// text; `status` is `indexed`, `unsupported` or `failed`
+    pub async fn record_attachment_text(
+        &self,
+        content_hash: &str,
+        status: &str,
+        text: Option<&str>,
+        error: Option<&str>,
+    ) -> AppResult<()> {
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query("DELETE FROM attachment_text WHERE content_hash = ?1")
+            .bind(content_hash)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("clear attachment text", e))?;
+        if let Some(text) = text {
+            sqlx::query("INSERT INTO attachment_text (content_hash, text) VALUES (?1, ?2)")
+                .bind(content_hash)
+                .bind(text)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("index attachment text", e))?;
+        }
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO attachment_extractions (content_hash, status, text_length, error, extracted_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#
+        )
+        .bind(content_hash)
+        .bind(status)
+        .bind(text.map_or(0, |text| text.chars().count() as i64))
+        .bind(error)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("record attachment extraction", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit attachment text", e))
+    }
+
+    /// Drops the text of content no attachment refers to anymore; returns
+    /// how many files it was
+    pub async fn remove_unreferenced_attachment_text(&self) -> AppResult<u64> {
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query(
+            "DELETE FROM attachment_text WHERE content_hash NOT IN (SELECT content_hash FROM attachments)",
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("remove attachment text", e))?;
+        let removed = sqlx::query(
+            "DELETE FROM attachment_extractions WHERE content_hash NOT IN (SELECT content_hash FROM attachments)",
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("remove attachment extractions", e))?
+        .rows_affected();
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error("commit attachment text", e))?;
+        Ok(removed)
+    }
+
+    /// Attachments whose text contains every word of `query`, as a prefix,
+    /// best matches first; attachments of private notes are left out
+    pub async fn search_attachment_text(&self, query: &str, limit: i64) -> AppResult<Vec<AttachmentSearchHit>> {
+        // Each word quoted, so FTS syntax in the query is searched for as text
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        sqlx::query_as::<_, AttachmentSearchHit>(
+            r#"
+            SELECT a.id AS attachment_id, a.entity_type, a.entity_id,
+                   COALESCE(t.title, p.title, g.title, n.title, l.name) AS title,
+                   COALESCE(t.archived_at, p.archived_at, g.archived_at, n.archived_at, l.archived_at)
+                       IS NOT NULL AS archived,
+                   a.file_name,
+                   snippet(attachment_text, 1, '**', '**', '…', 16) AS snippet
+            FROM attachment_text x
+            JOIN attachments a ON a.content_hash = x.content_hash
+            LEFT JOIN tasks t ON a.entity_type = 'task' AND t.id = a.entity_id
+            LEFT JOIN projects p ON a.entity_type = 'project' AND p.id = a.entity_id
+            LEFT JOIN goals g ON a.entity_type = 'goal' AND g.id = a.entity_id
+            LEFT JOIN notes n ON a.entity_type = 'note' AND n.id = a.entity_id
+            LEFT JOIN life_areas l ON a.entity_type = 'life_area' AND l.id = a.entity_id
+            WHERE attachment_text MATCH ?1
+              AND COALESCE(t.title, p.title, g.title, n.title, l.name) IS NOT NULL
+              AND COALESCE(n.is_private, 0) = 0
+            ORDER BY x.rank, a.created_at
+            LIMIT ?2
+            "#
+        )
+        .bind(terms.join(" "))
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("search attachment text", e))
+    }
+
+    // Velocity operations
+    /// When each completed task of a project was completed, oldest first
+    pub async fn get_project_completion_times(&self, project_id: &str) -> AppResult<Vec<DateTime<Utc>>> {
+        sqlx::query_scalar(
+            "SELECT completed_at FROM tasks WHERE project_id = ?1 AND completed_at IS NOT NULL ORDER BY completed_at"
+        )
+        .bind(project_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project completion times", e))
+    }
+
+    pub async fn get_remaining_work(&self, project_id: &str) -> AppResult<RemainingWork> {
+        sqlx::query_as::<_, RemainingWork>(
+            r#"
+            SELECT COUNT(*) AS task_count,
+                   COALESCE(SUM(estimated_minutes), 0) AS estimated_minutes,
+                   COALESCE(SUM(estimated_minutes IS NULL), 0) AS unestimated_count
+            FROM tasks
+            WHERE project_id = ?1 AND completed_at IS NULL AND archived_at IS NULL
+            "#
+        )
+        .bind(project_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get remaining work", e))
+    }
+
+    /// Open tasks left in the projects of a goal and their estimated effort
+    pub async fn get_goal_remaining_work(&self, goal_id: &str) -> AppResult<RemainingWork> {
+        sqlx::query_as::<_, RemainingWork>(
+            r#"
+            SELECT COUNT(*) AS task_count,
+                   COALESCE(SUM(t.estimated_minutes), 0) AS estimated_minutes,
+                   COALESCE(SUM(t.estimated_minutes IS NULL), 0) AS unestimated_count
+            FROM tasks t
+            JOIN projects p ON p.id = t.project_id
+            WHERE p.goal_id = ?1 AND p.archived_at IS NULL
+              AND t.completed_at IS NULL AND t.archived_at IS NULL
+            "#
+        )
+        .bind(goal_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get goal remaining work", e))
+    }
+
+    /// When each estimated task of a goal's projects was completed, with its
+    /// estimate, oldest first
+    pub async fn get_goal_completed_estimates(&self, goal_id: &str) -> AppResult<Vec<(DateTime<Utc>, i64)>> {
+        sqlx::query_as(
+            r#"
+            SELECT t.completed_at, t.estimated_minutes
+            FROM tasks t
+            JOIN projects p ON p.id = t.project_id
+            WHERE p.goal_id = ?1 AND t.completed_at IS NOT NULL AND t.estimated_minutes IS NOT NULL
+            ORDER BY t.completed_at
+            "#
+        )
+        .bind(goal_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get goal completed estimates", e))
+    }
+
+    /// Estimated and tracked minutes of the open tasks per project, goal and
+    /// life area, from one grouped query
+    pub async fn get_effort_rollup(&self) -> AppResult<EffortRollup> {
+        // Project, goal, life area, then the effort of its open tasks
+        type ProjectEffort = (Option<String>, Option<String>, Option<String>, i64, i64, i64, i64);
+
+        let rows: Vec<ProjectEffort> = sqlx::query_as(
+            r#"
+            SELECT t.project_id, p.goal_id, g.life_area_id,
+                   COUNT(*),
+                   COALESCE(SUM(t.estimated_minutes), 0),
+                   COALESCE(SUM(t.actual_minutes), 0),
+                   COALESCE(SUM(t.estimated_minutes IS NULL), 0)
+            FROM tasks t
+            LEFT JOIN projects p ON p.id = t.project_id
+            LEFT JOIN goals g ON g.id = p.goal_id
+            WHERE t.archived_at IS NULL AND t.completed_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = t.project_id)
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = t.project_id)
+            GROUP BY t.project_id
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get effort rollup", e))?;
+
+        let mut rollup = EffortRollup::default();
+        for (project_id, goal_id, life_area_id, task_count, estimated, actual, unestimated) in rows {
+            let effort = Effort {
+                task_count,
+                estimated_minutes: estimated,
+                actual_minutes: actual,
+                unestimated_count: unestimated,
+            };
+            rollup.total += effort;
+            let Some(project_id) = project_id else {
+                rollup.inbox += effort;
+                continue;
+            };
+            rollup.projects.insert(project_id, effort);
+            if let Some(goal_id) = goal_id {
+                *rollup.goals.entry(goal_id).or_default() += effort;
+            }
+            if let Some(life_area_id) = life_area_id {
+                *rollup.life_areas.entry(life_area_id).or_default() += effort;
+            }
+        }
+        Ok(rollup)
+    }
+
+    /// Projects of a goal that are neither completed, cancelled nor archived
+    pub async fn count_open_projects(&self, goal_id: &str) -> AppResult<i64> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM projects
+            WHERE goal_id = ?1 AND archived_at IS NULL AND status NOT IN ('completed', 'cancelled')
+            "#
+        )
+        .bind(goal_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("count open projects", e))
+    }
+
+    // Aging operations
+    /// Every open, unarchived task with its priority, creation time and project
+    pub async fn get_open_task_ages(&self) -> AppResult<Vec<OpenTaskAge>> {
+        sqlx::query_as::<_, OpenTaskAge>(
+            r#"
+            SELECT t.id AS task_id, t.project_id, p.title AS project_title, t.priority, t.created_at
+            FROM tasks t
+            LEFT JOIN projects p ON p.id = t.project_id
+            WHERE t.completed_at IS NULL AND t.archived_at IS NULL
+            ORDER BY t.created_at
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get open task ages", e))
+    }
+
+    /// Creation and completion times of the tasks completed since `since`,
+    /// oldest completion first
+    pub async fn get_completion_spans(&self, since: DateTime<Utc>) -> AppResult<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        sqlx::query_as(
+            "SELECT created_at, completed_at FROM tasks WHERE completed_at >= ?1 ORDER BY completed_at"
+        )
+        .bind(since)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get completion spans", e))
+    }
+
+    /// Open tasks whose due date was changed more than `more_than` times
+    /// after they first had one, the most rescheduled first
+    pub async fn get_chronic_postponements(&self, more_than: i64) -> AppResult<Vec<ChronicPostponement>> {
+        sqlx::query_as::<_, ChronicPostponement>(
+            r#"
+            SELECT t.id AS task_id, t.title, t.project_id, p.title AS project_title, t.due_date,
+                   COUNT(*) AS reschedules,
+                   COALESCE(SUM(c.new_due_date IS NULL
+                                OR julianday(c.new_due_date) > julianday(c.old_due_date)), 0) AS postponements,
+                   (SELECT f.old_due_date FROM task_due_date_changes f
+                    WHERE f.task_id = t.id AND f.old_due_date IS NOT NULL
+                    ORDER BY f.id LIMIT 1) AS first_due_date,
+                   MAX(c.changed_at) AS last_rescheduled_at
+            FROM task_due_date_changes c
+            JOIN tasks t ON t.id = c.task_id
+            LEFT JOIN projects p ON p.id = t.project_id
+            WHERE c.old_due_date IS NOT NULL AND t.completed_at IS NULL AND t.archived_at IS NULL
+            GROUP BY t.id
+            HAVING COUNT(*) > ?1
+            ORDER BY reschedules DESC, first_due_date
+            "#
+        )
+        .bind(more_than)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get chronic postponements", e))
+    }
+
+    // Focus operations
+    /// Time tracked per task between `start` and `end`, with entries cut to
+    /// the range; a running entry counts up to `now`
+    pub async fn get_task_focus(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> AppResult<Vec<TaskFocus>> {
+        sqlx::query_as::<_, TaskFocus>(
+            r#"
+            SELECT t.id AS task_id, g.id AS goal_id, g.title AS goal_title,
+                   la.id AS life_area_id, la.name AS life_area_name,
+                   CAST(ROUND(SUM(
+                       (MIN(julianday(COALESCE(e.ended_at, ?3)), julianday(?2))
+                        - MAX(julianday(e.started_at), julianday(?1))) * 86400
+                   )) AS INTEGER) AS seconds,
+                   COUNT(*) AS entry_count
+            FROM time_entries e
+            JOIN tasks t ON t.id = e.task_id
+            LEFT JOIN projects p ON p.id = t.project_id
+            LEFT JOIN goals g ON g.id = p.goal_id
+            LEFT JOIN life_areas la ON la.id = g.life_area_id
+            WHERE julianday(e.started_at) < julianday(?2)
+              AND julianday(COALESCE(e.ended_at, ?3)) > julianday(?1)
+            GROUP BY t.id
+            "#
+        )
+        .bind(start)
+        .bind(end)
+        .bind(now)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get task focus", e))
+    }
+
+    // Digest operations
+    /// Tasks completed within `[start, end)`, in order of completion
+    pub async fn get_tasks_completed_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> AppResult<Vec<DigestTask>> {
+        sqlx::query_as::<_, DigestTask>(
+            r#"
+            SELECT t.id, t.title, p.title AS project_title, t.completed_at AS at
+            FROM tasks t
+            LEFT JOIN projects p ON p.id = t.project_id
+            WHERE t.completed_at >= ?1 AND t.completed_at < ?2 AND t.archived_at IS NULL
+            ORDER BY t.completed_at
+            "#
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get completed tasks", e))
+    }
+
+    /// Open tasks due within `[start, end)`, soonest first, leaving out tasks
+    /// of incubated projects and those hidden by the active context
+    pub async fn get_tasks_due_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> AppResult<Vec<DigestTask>> {
+        sqlx::query_as::<_, DigestTask>(
+            r#"
+            SELECT t.id, t.title, p.title AS project_title, t.due_date AS at
+            FROM tasks t
+            LEFT JOIN projects p ON p.id = t.project_id
+            WHERE t.due_date >= ?1 AND t.due_date < ?2
+              AND t.completed_at IS NULL AND t.archived_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM incubated_projects i WHERE i.id = t.project_id)
+              AND NOT EXISTS (SELECT 1 FROM hidden_projects h WHERE h.id = t.project_id)
+            ORDER BY t.due_date
+            "#
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get tasks due", e))
+    }
+
+    /// Open goals with their progress, the tasks of their projects completed
+    /// within `[start, end)` and those still open; goals with work done in
+    /// the period come first
+    pub async fn get_goal_progress_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> AppResult<Vec<DigestGoal>> {
+        sqlx::query_as::<_, DigestGoal>(
+            r#"
+            SELECT g.id, g.title, g.progress,
+                   COALESCE(SUM(t.completed_at >= ?1 AND t.completed_at < ?2), 0) AS completed_tasks,
+                   COALESCE(SUM(t.id IS NOT NULL AND t.completed_at IS NULL), 0) AS open_tasks
+            FROM goals g
+            LEFT JOIN projects p ON p.goal_id = g.id AND p.archived_at IS NULL
+            LEFT JOIN tasks t ON t.project_id = p.id AND t.archived_at IS NULL
+            WHERE g.archived_at IS NULL AND g.completed_at IS NULL AND g.incubated_at IS NULL
+              AND g.id NOT IN (SELECT id FROM hidden_goals)
+            GROUP BY g.id
+            ORDER BY completed_tasks DESC, g.sort_order, g.created_at
+            "#
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get goal progress", e))
+    }
+
+    // Stats history operations
+    /// Records the counts of `day`, which runs from `start` to `end`,
+    /// replacing an earlier snapshot of the same day
+    pub async fn record_stats_snapshot(
+        &self,
+        day: NaiveDate,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> AppResult<StatsSnapshot> {
+        sqlx::query_as::<_, StatsSnapshot>(
+            r#"
+            INSERT INTO stats_history (day, open_tasks, completed_tasks, notes_created, recorded_at)
+            VALUES (
+                ?1,
+                (SELECT COUNT(*) FROM tasks WHERE completed_at IS NULL AND archived_at IS NULL),
+                (SELECT COUNT(*) FROM tasks WHERE completed_at >= ?2 AND completed_at < ?3),
+                (SELECT COUNT(*) FROM notes WHERE created_at >= ?2 AND created_at < ?3),
+                ?4
+            )
+            ON CONFLICT(day) DO UPDATE SET
+                open_tasks = excluded.open_tasks,
+                completed_tasks = excluded.completed_tasks,
+                notes_created = excluded.notes_created,
+                recorded_at = excluded.recorded_at
+            RETURNING day, open_tasks, completed_tasks, notes_created, recorded_at
+            "#
+        )
+        .bind(day)
+        .bind(start)
+        .bind(end)
+        .bind(Utc::now())
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("record stats snapshot", e))
+    }
+
+    /// Snapshots of the days from `from` through `to`, oldest first; days
+    /// without one are missing
+    pub async fn get_stats_history(&self, from: NaiveDate, to: NaiveDate) -> AppResult<Vec<StatsSnapshot>> {
+        sqlx::query_as::<_, StatsSnapshot>(
+            r#"
+            SELECT day, open_tasks, completed_tasks, notes_created, recorded_at
+            FROM stats_history
+            WHERE day >= ?1 AND day <= ?2
+            ORDER BY day
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get stats history", e))
+    }
+
+    // Achievement operations
+    pub async fn get_completion_counts(&self) -> AppResult<CompletionCounts> {
+        sqlx::query_as::<_, CompletionCounts>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM tasks WHERE completed_at IS NOT NULL) AS tasks,
+                (SELECT COUNT(*) FROM projects WHERE completed_at IS NOT NULL) AS projects,
+                (SELECT COUNT(*) FROM goals WHERE completed_at IS NOT NULL) AS goals
+            "#
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get completion counts", e))
+    }
+
+    /// When each completed task was completed, oldest first
+    pub async fn get_task_completion_times(&self) -> AppResult<Vec<DateTime<Utc>>> {
+        sqlx::query_scalar("SELECT completed_at FROM tasks WHERE completed_at IS NOT NULL ORDER BY completed_at")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get task completion times", e))
+    }
+
+    /// Unlocked achievement keys with when they were unlocked
+    pub async fn get_unlocked_achievements(&self) -> AppResult<std::collections::HashMap<String, DateTime<Utc>>> {
+        let rows: Vec<(String, DateTime<Utc>)> = sqlx::query_as("SELECT key, unlocked_at FROM achievements")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get achievements", e))?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Records achievements as unlocked; returns the keys that weren't
+    /// unlocked before
+    pub async fn unlock_achievements(&self, keys: &[&str]) -> AppResult<Vec<String>> {
+        let mut tx = self.begin_transaction().await?;
+        let now = Utc::now();
+        let mut unlocked = Vec::new();
+
+        for key in keys {
+            let result = sqlx::query("INSERT OR IGNORE INTO achievements (key, unlocked_at) VALUES (?1, ?2)")
+                .bind(key)
+                .bind(now)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("unlock achievement", e))?;
+            if result.rows_affected() > 0 {
+                unlocked.push(key.to_string());
+            }
+        }
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit achievements", e))?;
+        Ok(unlocked)
+    }
+
+    // Holiday operations
+    pub async fn get_holiday_set(&self, id: &str) -> AppResult<HolidaySet> {
+        sqlx::query_as::<_, HolidaySet>(
+            r#"
+            SELECT s.*, (SELECT COUNT(*) FROM holidays h WHERE h.set_id = s.id) AS holiday_count
+            FROM holiday_sets s
+            WHERE s.id = ?1
+            "#
+        )
+        .bind(id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::not_found("Holiday set", id),
+            _ => AppError::database_error("get holiday set", e),
+        })
+    }
+
+    pub async fn get_holiday_sets(&self) -> AppResult<Vec<HolidaySet>> {
+        sqlx::query_as::<_, HolidaySet>(
+            r#"
+            SELECT s.*, (SELECT COUNT(*) FROM holidays h WHERE h.set_id = s.id) AS holiday_count
+            FROM holiday_sets s
+            ORDER BY s.name COLLATE NOCASE
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get holiday sets", e))
+    }
+
+    /// Creates a holiday set; a date listed twice keeps the last name
+    pub async fn create_holiday_set(&self, name: &str, holidays: &[HolidayInput]) -> AppResult<HolidaySet> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query("INSERT INTO holiday_sets (id, name, enabled, created_at, updated_at) VALUES (?1, ?2, 1, ?3, ?4)")
+            .bind(&id)
+            .bind(name)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("create holiday set", e))?;
+
+        for holiday in holidays {
+            Self::upsert_holiday(&mut tx, &id, holiday).await?;
+        }
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit holiday set", e))?;
+        self.get_holiday_set(&id).await
+    }
+
+    async fn upsert_holiday(tx: &mut Transaction<'_, Sqlite>, set_id: &str, holiday: &HolidayInput) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO holidays (set_id, date, name) VALUES (?1, ?2, ?3) ON CONFLICT(set_id, date) DO UPDATE SET name = excluded.name"
+        )
+        .bind(set_id)
+        .bind(holiday.date)
+        .bind(&holiday.name)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("add holiday", e))?;
+        Ok(())
+    }
+
+    /// Adds a holiday to a set, renaming it if the date is already there
+    pub async fn add_holiday(&self, set_id: &str, holiday: &HolidayInput) -> AppResult<HolidaySet> {
+        self.get_holiday_set(set_id).await?;
+        let mut tx = self.begin_transaction().await?;
+        Self::upsert_holiday(&mut tx, set_id, holiday).await?;
+        Self::touch_holiday_set(&mut tx, set_id).await?;
+        tx.commit().await.map_err(|e| AppError::database_error("commit holiday", e))?;
+        self.get_holiday_set(set_id).await
+    }
+
+    pub async fn remove_holiday(&self, set_id: &str, date: NaiveDate) -> AppResult<HolidaySet> {
+        let mut tx = self.begin_transaction().await?;
+        let result = sqlx::query("DELETE FROM holidays WHERE set_id = ?1 AND date = ?2")
+            .bind(set_id)
+            .bind(date)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("remove holiday", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Holiday", &date.to_string()));
+        }
+        Self::touch_holiday_set(&mut tx, set_id).await?;
+        tx.commit().await.map_err(|e| AppError::database_error("commit holiday", e))?;
+        self.get_holiday_set(set_id).await
+    }
+
+    async fn touch_holiday_set(tx: &mut Transaction<'_, Sqlite>, set_id: &str) -> AppResult<()> {
+        sqlx::query("UPDATE holiday_sets SET updated_at = ?1 WHERE id = ?2")
+            .bind(Utc::now())
+            .bind(set_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("update holiday set", e))?;
+        Ok(())
+    }
+
+    pub async fn set_holiday_set_enabled(&self, id: &str, enabled: bool) -> AppResult<HolidaySet> {
+        let result = sqlx::query("UPDATE holiday_sets SET enabled = ?1 WHERE id = ?2")
+            .bind(enabled)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("update holiday set", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Holiday set", id));
+        }
+        self.get_holiday_set(id).await
+    }
+
+    pub async fn delete_holiday_set(&self, id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM holiday_sets WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete holiday set", e))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Holiday set", id));
+        }
+        Ok(())
+    }
+
+    /// Holidays of enabled sets within `[start, end]`, by date
+    pub async fn get_holidays(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<Holiday>> {
+        sqlx::query_as::<_, Holiday>(
+            r#"
+            SELECT h.date, h.name, s.id AS set_id, s.name AS set_name
+            FROM holidays h
+            JOIN holiday_sets s ON s.id = h.set_id
+            WHERE s.enabled = 1 AND h.date BETWEEN ?1 AND ?2
+            ORDER BY h.date, s.name
+            "#
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get holidays", e))
+    }
+
+    /// Every date that is a holiday in an enabled set
+    pub async fn get_holiday_dates(&self) -> AppResult<Vec<NaiveDate>> {
+        sqlx::query_scalar(
+            "SELECT DISTINCT h.date FROM holidays h JOIN holiday_sets s ON s.id = h.set_id WHERE s.enabled = 1"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get holiday dates", e))
+    }
+
+    // Time entry operations
+    pub async fn get_time_entry(&self, id: &str) -> AppResult<TimeEntry> {
+        sqlx::query_as::<_, TimeEntry>("SELECT * FROM time_entries WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("Time entry", id),
+                _ => AppError::database_error("get time entry", e),
+            })
+    }
+
+    pub async fn get_time_entries(&self, task_id: &str) -> AppResult<Vec<TimeEntry>> {
+        sqlx::query_as::<_, TimeEntry>("SELECT * FROM time_entries WHERE task_id = ?1 ORDER BY started_at DESC")
+            .bind(task_id)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get time entries", e))
+    }
+
+    pub async fn get_running_time_entry(&self) -> AppResult<Option<TimeEntry>> {
+        sqlx::query_as::<_, TimeEntry>("SELECT * FROM time_entries WHERE ended_at IS NULL")
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("get running time entry", e))
+    }
+
+    /// Idle-stopped entries the user hasn't reconciled yet, newest first
+    pub async fn get_unreconciled_time_entries(&self) -> AppResult<Vec<TimeEntry>> {
+        sqlx::query_as::<_, TimeEntry>(
+            "SELECT * FROM time_entries WHERE idle_started_at IS NOT NULL AND reconciled_at IS NULL ORDER BY started_at DESC"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get unreconciled time entries", e))
+    }
+
+    /// Starts a timer on a task, stopping the running one first
+    pub async fn start_time_entry(&self, task_id: &str) -> AppResult<TimeEntry> {
+        self.get_task(task_id).await?;
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let mut tx = self.begin_transaction().await?;
+
+        sqlx::query("UPDATE time_entries SET ended_at = ?1 WHERE ended_at IS NULL")
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("stop time entry", e))?;
+
+        sqlx::query("INSERT INTO time_entries (id, task_id, started_at) VALUES (?1, ?2, ?3)")
+            .bind(&id)
+            .bind(task_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("start time entry", e))?;
+
+        tx.commit().await.map_err(|e| AppError::database_error("commit time entry", e))?;
+        self.get_time_entry(&id).await
+    }
+
+    /// Stops the running timer, if any
+    pub async fn stop_time_entry(&self) -> AppResult<Option<TimeEntry>> {
+        let Some(running) = self.get_running_time_entry().await? else {
+            return Ok(None);
+        };
+
+        sqlx::query("UPDATE time_entries SET ended_at = ?1 WHERE id = ?2 AND ended_at IS NULL")
+            .bind(Utc::now())
+            .bind(&running.id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("stop time entry", e))?;
+
+        self.get_time_entry(&running.id).await.map(Some)
+    }
+
+    /// Stops the running timer at `idle_since`, recording the idle gap up to
+    /// `now`; returns the stopped entry, if one was running
+    pub async fn stop_time_entry_idle(
+        &self,
+        idle_since: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> AppResult<Option<TimeEntry>> {
+        let Some(running) = self.get_running_time_entry().await? else {
+            return Ok(None);
+        };
+        // A timer started during the gap keeps no time at all
+        let ended_at = idle_since.max(running.started_at);
+
+        sqlx::query(
+            r#"
+            UPDATE time_entries
+            SET ended_at = ?1, idle_started_at = ?1, idle_ended_at = ?2
+            WHERE id = ?3 AND ended_at IS NULL
+            "#
+        )
+        .bind(ended_at)
+        .bind(now)
+        .bind(&running.id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("stop idle time entry", e))?;
+
+        self.get_time_entry(&running.id).await.map(Some)
+    }
+
+    /// Settles an idle-stopped entry: with `keep_idle_time` the gap counts as
+    /// tracked time and the entry ends when the gap did, otherwise it keeps
+    /// ending when the gap began
+    pub async fn reconcile_time_entry(&self, id: &str, keep_idle_time: bool) -> AppResult<TimeEntry> {
+        let entry = self.get_time_entry(id).await?;
+        if entry.idle_started_at.is_none() {
+            return Err(AppError::validation_error("id", "time entry was not stopped for idleness"));
+        }
+        if entry.reconciled_at.is_some() {
+            return Err(AppError::validation_error("id", "time entry is already reconciled"));
+        }
+
+        let ended_at = if keep_idle_time { entry.idle_ended_at } else { entry.ended_at };
+        sqlx::query("UPDATE time_entries SET ended_at = ?1, reconciled_at = ?2 WHERE id = ?3")
+            .bind(ended_at)
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("reconcile time entry", e))?;
+
+        self.get_time_entry(id).await
+    }
+
+    // External link operations
+    pub async fn get_external_link(&self, id: &str) -> AppResult<ExternalLink> {
+        sqlx::query_as::<_, ExternalLink>("SELECT * FROM external_links WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::not_found("External link", id),
+                _ => AppError::database_error("get external link", e),
+            })
+    }
+
+    pub async fn get_external_links_for(&self, entity_type: &str, entity_id: &str) -> AppResult<Vec<ExternalLink>> {
+        sqlx::query_as::<_, ExternalLink>(
+            "SELECT * FROM external_links WHERE entity_type = ?1 AND entity_id = ?2 ORDER BY created_at"
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get external links", e))
+    }
+
+    pub async fn get_external_links_by_provider(&self, provider: &str) -> AppResult<Vec<ExternalLink>> {
+        sqlx::query_as::<_, ExternalLink>(
+            "SELECT * FROM external_links WHERE provider = ?1 ORDER BY last_synced_at ASC NULLS FIRST"
+        )
+        .bind(provider)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get external links by provider", e))
+    }
+
+    pub async fn create_external_link(&self, input: ExternalLinkInput) -> AppResult<ExternalLink> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO external_links (id, entity_type, entity_id, url, provider, external_id,
+                                        title, complete_on_close, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#
+        )
+        .bind(&id)
+        .bind(&input.entity_type)
+        .bind(&input.entity_id)
+        .bind(&input.url)
+        .bind(&input.provider)
+        .bind(&input.external_id)
+        .bind(&input.title)
+        .bind(input.complete_on_close)
+        .bind(&now)
+        .bind(&now)
+        .execute(&*self.pool)
+        .await?;
+
+        self.get_external_link(&id).await
+    }
+
+    /// Stores the result of syncing a link with its remote source
+    pub async fn record_external_link_sync(
+        &self,
+        id: &str,
+        title: Option<&str>,
+        state: Option<&str>,
+        error: Option<&str>,
+    ) -> AppResult<()> {
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            UPDATE external_links
+            SET title = COALESCE(?1, title), state = COALESCE(?2, state), sync_error = ?3,
+                last_synced_at = ?4
+            WHERE id = ?5
+            "#
+        )
+        .bind(title)
+        .bind(state)
+        .bind(error)
+        .bind(&now)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("record external link sync", e))?;
+
+        Ok(())
+    }
+
+    /// Stores page metadata fetched for a web link
+    pub async fn record_link_metadata(
+        &self,
+        id: &str,
+        title: Option<&str>,
+        description: Option<&str>,
+        favicon: Option<&str>,
+        error: Option<&str>,
+    ) -> AppResult<()> {
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            UPDATE external_links
+            SET title = COALESCE(?1, title), description = COALESCE(?2, description),
+                favicon = COALESCE(?3, favicon), sync_error = ?4, last_synced_at = ?5
+            WHERE id = ?6
+            "#
+        )
+        .bind(title)
+        .bind(description)
+        .bind(favicon)
+        .bind(error)
+        .bind(&now)
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("record link metadata", e))?;
+
+        Ok(())
+    }
+
+    pub async fn delete_external_link(&self, id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM external_links WHERE id = ?1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| AppError::database_error("delete external link", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("External link", id));
+        }
+
+        Ok(())
+    }
+
+    // Project bundle operations
+    /// Collects a project with its tasks, notes, tags, task dependencies and
+    /// attachments, leaving out what is archived
+    pub async fn get_project_contents(&self, project_id: &str) -> AppResult<ProjectContents> {
+        let project = self.get_project(project_id).await?;
+        if project.archived_at.is_some() {
+            return Err(AppError::validation_error("project_id", "is archived"));
+        }
+
+        let tasks = sqlx::query_as::<_, Task>(
+            r#"
+            WITH RECURSIVE tree(id, depth) AS (
+                SELECT id, 0 FROM tasks WHERE project_id = ?1 AND parent_task_id IS NULL AND archived_at IS NULL
+                UNION
+                SELECT t.id, tree.depth + 1 FROM tasks t
+                JOIN tree ON t.parent_task_id = tree.id
+                WHERE t.archived_at IS NULL AND tree.depth < ?2
+            )
+            SELECT t.* FROM tasks t
+            JOIN tree ON tree.id = t.id
+            ORDER BY tree.depth, t.created_at
+            "#
+        )
+        .bind(project_id)
+        .bind(MAX_TASK_TREE_DEPTH)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project tasks", e))?;
+        let task_ids = serde_json::to_string(&tasks.iter().map(|task| &task.id).collect::<Vec<_>>())?;
+
+        let notes = sqlx::query_as::<_, Note>(
+            r#"
+            SELECT * FROM notes
+            WHERE archived_at IS NULL
+              AND (project_id = ?1 OR task_id IN (SELECT value FROM json_each(?2)))
+            ORDER BY created_at
+            "#
+        )
+        .bind(project_id)
+        .bind(&task_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project notes", e))?;
+        let (private, notes): (Vec<Note>, Vec<Note>) = notes.into_iter().partition(|note| note.is_private);
+        let note_ids = serde_json::to_string(&notes.iter().map(|note| &note.id).collect::<Vec<_>>())?;
+
+        let taggings = sqlx::query_as::<_, Tagging>(
+            r#"
+            SELECT 'project' AS entity_type, project_id AS entity_id, tag_id FROM project_tags WHERE project_id = ?1
+            UNION ALL
+            SELECT 'task', task_id, tag_id FROM task_tags WHERE task_id IN (SELECT value FROM json_each(?2))
+            "#
+        )
+        .bind(project_id)
+        .bind(&task_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project taggings", e))?;
+        let tag_ids = serde_json::to_string(&taggings.iter().map(|tagging| &tagging.tag_id).collect::<Vec<_>>())?;
+
+        let tags = sqlx::query_as::<_, Tag>(
+            "SELECT * FROM tags WHERE id IN (SELECT value FROM json_each(?1)) ORDER BY name"
+        )
+        .bind(&tag_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project tags", e))?;
+
+        let dependencies = sqlx::query_as::<_, TaskDependency>(
+            r#"
+            SELECT task_id, depends_on_id, created_at FROM task_dependencies
+            WHERE task_id IN (SELECT value FROM json_each(?1))
+              AND depends_on_id IN (SELECT value FROM json_each(?1))
+            ORDER BY created_at
+            "#
+        )
+        .bind(&task_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project dependencies", e))?;
+
+        let attachments = sqlx::query_as::<_, Attachment>(
+            r#"
+            SELECT * FROM attachments
+            WHERE (entity_type = 'project' AND entity_id = ?1)
+               OR (entity_type = 'task' AND entity_id IN (SELECT value FROM json_each(?2)))
+               OR (entity_type = 'note' AND entity_id IN (SELECT value FROM json_each(?3)))
+            ORDER BY created_at
+            "#
+        )
+        .bind(project_id)
+        .bind(&task_ids)
+        .bind(&note_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| AppError::database_error("get project attachments", e))?;
+
+        let workflow = self.get_workflow(Some(project_id)).await?;
+        let workflow = if workflow.is_custom {
+            workflow
+                .statuses
+                .into_iter()
+                .map(|status| WorkflowStatusInput {
+                    key: status.key,
+                    name: status.name,
+                    category: status.category,
+                    next_statuses: status.next_statuses,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(ProjectContents {
+            project,
+            tasks,
+            notes,
+            tags,
+            taggings,
+            dependencies,
+            attachments,
+            workflow,
+            private_notes: private.len(),
+        })
+    }
+
+    /// Replaces every ID of the bundle in `text` with its new one
+    fn remap_ids(text: &str, ids: &BTreeMap<String, String>) -> String {
+        ids.iter().fold(text.to_string(), |text, (old, new)| {
+            if text.contains(old.as_str()) {
+                text.replace(old.as_str(), new)
+            } else {
+                text
+            }
+        })
+    }
+
+    /// Recreates the contents of a project bundle under a goal
+    ///
+    /// Every project, task, note and attachment gets a new ID, and the
+    /// references between them, including links in task descriptions and
+    /// note content, point to the new ones. Tags are matched by name: a tag
+    /// that already exists is used as it is, color included. Anything that
+    /// refers to something outside the bundle is left out and listed in the
+    /// report. The attachment content must already be in the store.
+    pub async fn import_project_contents(
+        &self,
+        goal_id: &str,
+        contents: ProjectContents,
+    ) -> AppResult<ProjectImportReport> {
+        let goal = self.get_goal(goal_id).await?;
+        if goal.archived_at.is_some() {
+            return Err(AppError::validation_error("target_goal_id", "is archived"));
+        }
+        validate_title("title", &contents.project.title)?;
+        for task in &contents.tasks {
+            validate_title("title", &task.title)?;
+        }
+        let statuses = if contents.workflow.is_empty() {
+            Workflow::default_for(None)
+                .statuses
+                .into_iter()
+                .map(|status| (status.key, status.category))
+                .collect::<Vec<_>>()
+        } else {
+            validate_workflow(&contents.workflow)?;
+            contents
+                .workflow
+                .iter()
+                .map(|status| (status.key.clone(), status.category))
+                .collect()
+        };
+
+        let project_id = Uuid::new_v4().to_string();
+        let mut ids = BTreeMap::from([(contents.project.id.clone(), project_id.clone())]);
+        for id in contents.tasks.iter().map(|task| &task.id).chain(contents.notes.iter().map(|note| &note.id)) {
+            if ids.insert(id.clone(), Uuid::new_v4().to_string()).is_some() {
+                return Err(AppError::validation_error("bundle", &format!("contains ID {} twice", id)));
+            }
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        let now = Utc::now();
+        let project = &contents.project;
+        sqlx::query(
+            r#"
+            INSERT INTO projects (
+                id, goal_id, title, description, status, created_at, updated_at, completed_at,
+                review_interval_days, last_reviewed_at, incubated_at, sort_order, progress, color, icon
+            )
+            VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11,
+                (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM projects WHERE goal_id = ?2), ?12, ?13, ?14
+            )
+            "#
+        )
+        .bind(&project_id)
+        .bind(goal_id)
+        .bind(&project.title)
+        .bind(project.description.as_deref().map(|text| Self::remap_ids(text, &ids)))
+        .bind(project.status.to_string())
+        .bind(project.created_at)
+        .bind(now)
+        .bind(project.completed_at)
+        .bind(project.review_interval_days)
+        .bind(project.last_reviewed_at)
+        .bind(project.incubated_at)
+        .bind(project.progress)
+        .bind(&project.color)
+        .bind(&project.icon)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("import project", e))?;
+        Self::insert_workflow_statuses(&mut tx, &project_id, &contents.workflow, now).await?;
+
+        let mut skipped = Vec::new();
+        let mut imported_tasks = HashSet::new();
+        for task in &contents.tasks {
+            let id = &ids[&task.id];
+            let parent_task_id = match &task.parent_task_id {
+                Some(parent) if imported_tasks.contains(parent.as_str()) => Some(ids[parent].clone()),
+                Some(_) => {
+                    skipped.push(format!(
+                        "task '{}' moved to the top level, its parent isn't in the bundle",
+                        task.title
+                    ));
+                    None
+                }
+                None => None,
+            };
+            // A status that doesn't fit the task becomes the first todo or
+            // done one, as when a workflow changes
+            let done = task.completed_at.is_some();
+            let category = if done { StatusCategory::Done } else { StatusCategory::Todo };
+            let status = statuses
+                .iter()
+                .find(|(key, kind)| *key == task.status && (*kind == StatusCategory::Done) == done)
+                .or_else(|| statuses.iter().find(|(_, kind)| *kind == category))
+                .map(|(key, _)| key.clone())
+                .unwrap_or_else(|| task.status.clone());
+            let description = task.description.as_deref().map(|text| Self::remap_ids(text, &ids));
+
+            sqlx::query(
+                r#"
+                INSERT INTO tasks (
+                    id, project_id, parent_task_id, title, description, priority, due_date, created_at, updated_at,
+                    completed_at, status, estimated_minutes, actual_minutes, recurrence_rule
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                "#
+            )
+            .bind(id)
+            .bind(&project_id)
+            .bind(parent_task_id)
+            .bind(&task.title)
+            .bind(&description)
+            .bind(task.priority.to_string())
+            .bind(task.due_date)
+            .bind(task.created_at)
+            .bind(now)
+            .bind(task.completed_at)
+            .bind(status)
+            .bind(task.estimated_minutes)
+            .bind(task.actual_minutes)
+            .bind(&task.recurrence_rule)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import task", e))?;
+            Self::write_task_references(&mut tx, id, description.as_deref()).await?;
+            imported_tasks.insert(task.id.as_str());
+        }
+
+        let mut imported_notes = HashSet::new();
+        for note in &contents.notes {
+            if note.is_private {
+                skipped.push(format!("private note '{}', which can only be read in the vault it came from", note.title));
+                continue;
+            }
+            let id = &ids[&note.id];
+            let task_id = note
+                .task_id
+                .as_deref()
+                .filter(|task_id| imported_tasks.contains(task_id))
+                .map(|task_id| &ids[task_id]);
+            // Notes of a task that wasn't imported stay with the project
+            let note_project_id = (note.project_id.is_some() || task_id.is_none()).then_some(&project_id);
+            let content = Self::remap_ids(&note.content, &ids);
+
+            sqlx::query(
+                r#"
+                INSERT INTO notes (id, task_id, project_id, title, content, created_at, updated_at, note_date)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#
+            )
+            .bind(id)
+            .bind(task_id)
+            .bind(note_project_id)
+            .bind(&note.title)
+            .bind(&content)
+            .bind(note.created_at)
+            .bind(now)
+            .bind(note.note_date)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import note", e))?;
+
+            let tags = frontmatter::parse(&content).ok().flatten().and_then(|frontmatter| frontmatter.tags);
+            Self::set_note_tags(&mut tx, id, &tags.unwrap_or_default()).await?;
+            imported_notes.insert(note.id.as_str());
+        }
+
+        let mut tag_ids = HashMap::new();
+        let mut tags_created = Vec::new();
+        let mut tags_reused = Vec::new();
+        for tag in &contents.tags {
+            let name = tag.name.trim();
+            validate_title("tags", name)?;
+            let existing: Option<String> = sqlx::query_scalar("SELECT id FROM tags WHERE name = ?1")
+                .bind(name)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("get tag", e))?;
+            let id = match existing {
+                Some(id) => {
+                    tags_reused.push(name.to_string());
+                    id
+                }
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    sqlx::query("INSERT INTO tags (id, name, color, created_at) VALUES (?1, ?2, ?3, ?4)")
+                        .bind(&id)
+                        .bind(name)
+                        .bind(&tag.color)
+                        .bind(now)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| AppError::database_error("create tag", e))?;
+                    tags_created.push(name.to_string());
+                    id
+                }
+            };
+            tag_ids.insert(tag.id.as_str(), id);
+        }
+
+        for tagging in &contents.taggings {
+            let Some(tag_id) = tag_ids.get(tagging.tag_id.as_str()) else {
+                skipped.push(format!("tag {}, which isn't in the bundle", tagging.tag_id));
+                continue;
+            };
+            let (tag_table, id_column) = match tagging.entity_type.as_str() {
+                "project" if tagging.entity_id == project.id => ("project_tags", "project_id"),
+                "task" if imported_tasks.contains(tagging.entity_id.as_str()) => ("task_tags", "task_id"),
+                _ => {
+                    skipped.push(format!(
+                        "tag of {} {}, which isn't in the bundle",
+                        tagging.entity_type, tagging.entity_id
+                    ));
+                    continue;
+                }
+            };
+            sqlx::query(&format!("INSERT OR IGNORE INTO {} ({}, tag_id) VALUES (?1, ?2)", tag_table, id_column))
+                .bind(&ids[&tagging.entity_id])
+                .bind(tag_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::database_error("add tag", e))?;
+        }
+
+        let mut dependencies = 0;
+        for dependency in &contents.dependencies {
+            if !imported_tasks.contains(dependency.task_id.as_str())
+                || !imported_tasks.contains(dependency.depends_on_id.as_str())
+            {
+                skipped.push(format!(
+                    "dependency of task {} on {}, one of which isn't in the bundle",
+                    dependency.task_id, dependency.depends_on_id
+                ));
+                continue;
+            }
+            dependencies += sqlx::query(
+                "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id, created_at) VALUES (?1, ?2, ?3)"
+            )
+            .bind(&ids[&dependency.task_id])
+            .bind(&ids[&dependency.depends_on_id])
+            .bind(dependency.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import task dependency", e))?
+            .rows_affected() as usize;
+        }
+
+        let mut attachments = 0;
+        for attachment in &contents.attachments {
+            let imported = match attachment.entity_type.as_str() {
+                "project" => attachment.entity_id == project.id,
+                "task" => imported_tasks.contains(attachment.entity_id.as_str()),
+                "note" => imported_notes.contains(attachment.entity_id.as_str()),
+                _ => false,
+            };
+            if !imported {
+                skipped.push(format!(
+                    "attachment '{}' of {} {}, which isn't in the bundle",
+                    attachment.file_name, attachment.entity_type, attachment.entity_id
+                ));
+                continue;
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO attachments (id, entity_type, entity_id, file_name, mime_type, size_bytes, content_hash, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&attachment.entity_type)
+            .bind(&ids[&attachment.entity_id])
+            .bind(&attachment.file_name)
+            .bind(&attachment.mime_type)
+            .bind(attachment.size_bytes)
+            .bind(&attachment.content_hash)
+            .bind(attachment.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import attachment", e))?;
+            attachments += 1;
+        }
+
+        Self::record_audit(
+            &mut tx,
+            "import_project_bundle",
+            "project",
+            &project_id,
+            serde_json::json!({ "goal_id": goal_id, "source_id": project.id }),
+        )
+        .await?;
+        tx.commit().await.map_err(|e| AppError::database_error("commit project import", e))?;
+
+        ids.retain(|old, _| {
+            *old == project.id || imported_tasks.contains(old.as_str()) || imported_notes.contains(old.as_str())
+        });
+        Ok(ProjectImportReport {
+            project: self.get_project(&project_id).await?,
+            tasks: imported_tasks.len(),
+            notes: imported_notes.len(),
+            tags_created,
+            tags_reused,
+            dependencies,
+            attachments,
+            ids,
+            skipped,
+        })
+    }
+
+    // Export operations
+    pub async fn export_all_data(&self, include_archived: bool) -> AppResult<ExportResult> {
+        let mut data = serde_json::json!({});
+        let mut total_items = 0;
+
+        // Export life areas
+        let life_areas = if include_archived {
+            sqlx::query_as::<_, LifeArea>("SELECT * FROM life_areas ORDER BY created_at")
+                .fetch_all(&*self.pool)
+                .await?
+        } else {
+            self.get_life_areas().await?
+        };
+        total_items += life_areas.len();
+        data["life_areas"] = serde_json::to_value(&life_areas)?;
+
+        // Export goals
+        let goals = sqlx::query_as::<_, Goal>(
+            if include_archived {
+                "SELECT * FROM goals ORDER BY created_at"
+            } else {
+                "SELECT * FROM goals WHERE archived_at IS NULL ORDER BY created_at"
+            }
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        total_items += goals.len();
+        data["goals"] = serde_json::to_value(&goals)?;
+
+        // Export projects
+        let projects = sqlx::query_as::<_, Project>(
+            if include_archived {
+                "SELECT * FROM projects ORDER BY created_at"
+            } else {
+                "SELECT * FROM projects WHERE archived_at IS NULL ORDER BY created_at"
+            }
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        total_items += projects.len();
+        data["projects"] = serde_json::to_value(&projects)?;
+
+        // Export tasks
+        let tasks = sqlx::query_as::<_, Task>(
+            if include_archived {
+                "SELECT * FROM tasks ORDER BY created_at"
+            } else {
+                "SELECT * FROM tasks WHERE archived_at IS NULL ORDER BY created_at"
+            }
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        total_items += tasks.len();
+        data["tasks"] = serde_json::to_value(&tasks)?;
+
+        // Export notes
+        let notes = sqlx::query_as::<_, Note>(
+            if include_archived {
+                "SELECT * FROM notes ORDER BY created_at"
+            } else {
+                "SELECT * FROM notes WHERE archived_at IS NULL ORDER BY created_at"
+            }
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        total_items += notes.len();
+        data["notes"] = serde_json::to_value(&notes)?;
+
+        Ok(ExportResult {
+            data,
+            item_count: total_items,
+            export_date: Utc::now(),
+        })
+    }
+
+    // Full import operations
+    async fn row_exists(tx: &mut Transaction<'_, Sqlite>, table: &str, id: &str) -> AppResult<bool> {
+        sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = ?1)", table))
+            .bind(id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("check imported item", e))
+    }
+
+    /// Fails the import unless what an item refers to was imported before it
+    /// or is stored
+    async fn ensure_import_parent(
+        tx: &mut Transaction<'_, Sqlite>,
+        item: &str,
+        table: &str,
+        id: &str,
+    ) -> AppResult<()> {
+        if Self::row_exists(tx, table, id).await? {
+            return Ok(());
+        }
+        Err(AppError::validation_error(
+            "data",
+            &format!(
+                "{} refers to {} {}, which is neither in the export nor stored",
+                item,
+                table.trim_end_matches('s').replace('_', " "),
+                id
+            ),
+        ))
+    }
+
+    /// Counts an item of the export; returns whether it is written
+    fn count_import(section: &mut ImportSection, exists: bool, collision: ImportCollision) -> bool {
+        match (exists, collision) {
+            (false, _) => section.created += 1,
+            (true, ImportCollision::Skip) => {
+                section.skipped += 1;
+                return false;
+            }
+            (true, ImportCollision::Overwrite) => section.overwritten += 1,
+            (true, ImportCollision::Duplicate) => section.duplicated += 1,
+        }
+        true
+    }
+
+    /// Brings back the data of an `export_all_data` export, all or nothing
+    ///
+    /// Items keep their IDs and timestamps. An item whose ID is already
+    /// stored is skipped, overwrites the stored one or is imported as a copy
+    /// under a new ID, as `collision` says; references to a copy, including
+    /// links in descriptions and note content, point to the copy. An item
+    /// that refers to something neither in the export nor stored fails the
+    /// whole import. A dry run does all of it and rolls back, so its report
+    /// tells what the import would do. Private notes are skipped and counted
+    /// as such, as they can only be read in the vault they were exported
+    /// from.
+    pub async fn import_all_data(
+        &self,
+        export: &ExportResult,
+        collision: ImportCollision,
+        dry_run: bool,
+    ) -> AppResult<DataImportReport> {
+        fn section<T: serde::de::DeserializeOwned>(data: &serde_json::Value, name: &str) -> AppResult<Vec<T>> {
+            match data.get(name) {
+                None | Some(serde_json::Value::Null) => Ok(Vec::new()),
+                Some(items) => serde_json::from_value(items.clone())
+                    .map_err(|e| AppError::validation_error("data", &format!("{} can't be read: {}", name, e))),
+            }
+        }
+        let life_areas: Vec<LifeArea> = section(&export.data, "life_areas")?;
+        let goals: Vec<Goal> = section(&export.data, "goals")?;
+        let projects: Vec<Project> = section(&export.data, "projects")?;
+        let tasks: Vec<Task> = section(&export.data, "tasks")?;
+        let (private_notes, notes): (Vec<Note>, Vec<Note>) =
+            section::<Note>(&export.data, "notes")?.into_iter().partition(|note| note.is_private);
+
+        for life_area in &life_areas {
+            validate_title("name", &life_area.name)?;
+        }
+        for title in goals
+            .iter()
+            .map(|goal| &goal.title)
+            .chain(projects.iter().map(|project| &project.title))
+            .chain(tasks.iter().map(|task| &task.title))
+            .chain(notes.iter().map(|note| &note.title))
+        {
+            validate_title("title", title)?;
+        }
+        // Subtasks after their parents, so a parent is written first
+        let exported_tasks: HashSet<&str> = tasks.iter().map(|task| task.id.as_str()).collect();
+        let mut ordered_tasks = Vec::with_capacity(tasks.len());
+        let mut placed = HashSet::new();
+        let mut remaining: Vec<&Task> = tasks.iter().collect();
+        while !remaining.is_empty() {
+            let (ready, waiting): (Vec<&Task>, Vec<&Task>) = remaining.into_iter().partition(|task| {
+                task.parent_task_id
+                    .as_deref()
+                    .is_none_or(|parent| !exported_tasks.contains(parent) || placed.contains(parent))
+            });
+            if ready.is_empty() {
+                return Err(AppError::validation_error("data", "tasks are subtasks of each other"));
+            }
+            placed.extend(ready.iter().map(|task| task.id.as_str()));
+            ordered_tasks.extend(ready);
+            remaining = waiting;
+        }
+
+        let sections: [(&str, Vec<&str>); 5] = [
+            ("life_areas", life_areas.iter().map(|item| item.id.as_str()).collect()),
+            ("goals", goals.iter().map(|item| item.id.as_str()).collect()),
+            ("projects", projects.iter().map(|item| item.id.as_str()).collect()),
+            ("tasks", tasks.iter().map(|item| item.id.as_str()).collect()),
+            ("notes", notes.iter().map(|item| item.id.as_str()).collect()),
+        ];
+        let mut seen = HashSet::new();
+        for id in sections.iter().flat_map(|(_, ids)| ids) {
+            if !seen.insert(*id) {
+                return Err(AppError::validation_error("data", &format!("contains ID {} twice", id)));
+            }
+        }
+
+        let mut tx = self.begin_transaction().await?;
+
+        // Which items are stored already, and the new IDs of the copies
+        let mut existing = HashSet::new();
+        let mut ids = BTreeMap::new();
+        for (table, section_ids) in &sections {
+            for id in section_ids {
+                if Self::row_exists(&mut tx, table, id).await? {
+                    existing.insert(id.to_string());
+                    if collision == ImportCollision::Duplicate {
+                        ids.insert(id.to_string(), Uuid::new_v4().to_string());
+                    }
+                }
+            }
+        }
+        let target = |id: &str| ids.get(id).cloned().unwrap_or_else(|| id.to_string());
+        let remap = |text: &str| if ids.is_empty() { text.to_string() } else { Self::remap_ids(text, &ids) };
+        let mut report = DataImportReport {
+            dry_run,
+            collision,
+            sections: Vec::new(),
+            ids: BTreeMap::new(),
+        };
+
+        let mut counts = ImportSection { section: "life_areas".to_string(), ..Default::default() };
+        for life_area in &life_areas {
+            if !Self::count_import(&mut counts, existing.contains(&life_area.id), collision) {
+                continue;
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO life_areas (id, name, description, color, icon, created_at, updated_at, archived_at, context)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                ON CONFLICT(id) DO UPDATE SET
+                    name = excluded.name, description = excluded.description, color = excluded.color,
+                    icon = excluded.icon, created_at = excluded.created_at, updated_at = excluded.updated_at,
+                    archived_at = excluded.archived_at, context = excluded.context
+                "#
+            )
+            .bind(target(&life_area.id))
+            .bind(&life_area.name)
+            .bind(life_area.description.as_deref().map(remap))
+            .bind(&life_area.color)
+            .bind(&life_area.icon)
+            .bind(life_area.created_at)
+            .bind(life_area.updated_at)
+            .bind(life_area.archived_at)
+            .bind(life_area.context)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import life area", e))?;
+        }
+        report.sections.push(counts);
+
+        let mut counts = ImportSection { section: "goals".to_string(), ..Default::default() };
+        for goal in &goals {
+            if !Self::count_import(&mut counts, existing.contains(&goal.id), collision) {
+                continue;
+            }
+            let life_area_id = target(&goal.life_area_id);
+            Self::ensure_import_parent(&mut tx, &format!("goal '{}'", goal.title), "life_areas", &life_area_id).await?;
+            sqlx::query(
+                r#"
+                INSERT INTO goals (
+                    id, life_area_id, title, description, target_date, created_at, updated_at, completed_at,
+                    archived_at, incubated_at, sort_order, progress, color, icon
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                ON CONFLICT(id) DO UPDATE SET
+                    life_area_id = excluded.life_area_id, title = excluded.title, description = excluded.description,
+                    target_date = excluded.target_date, created_at = excluded.created_at,
+                    updated_at = excluded.updated_at, completed_at = excluded.completed_at,
+                    archived_at = excluded.archived_at, incubated_at = excluded.incubated_at,
+                    sort_order = excluded.sort_order, progress = excluded.progress, color = excluded.color,
+                    icon = excluded.icon
+                "#
+            )
+            .bind(target(&goal.id))
+            .bind(life_area_id)
+            .bind(&goal.title)
+            .bind(goal.description.as_deref().map(remap))
+            .bind(goal.target_date)
+            .bind(goal.created_at)
+            .bind(goal.updated_at)
+            .bind(goal.completed_at)
+            .bind(goal.archived_at)
+            .bind(goal.incubated_at)
+            .bind(goal.sort_order)
+            .bind(goal.progress)
+            .bind(&goal.color)
+            .bind(&goal.icon)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import goal", e))?;
+        }
+        report.sections.push(counts);
+
+        let mut counts = ImportSection { section: "projects".to_string(), ..Default::default() };
+        for project in &projects {
+            if !Self::count_import(&mut counts, existing.contains(&project.id), collision) {
+                continue;
+            }
+            let goal_id = target(&project.goal_id);
+            Self::ensure_import_parent(&mut tx, &format!("project '{}'", project.title), "goals", &goal_id).await?;
+            sqlx::query(
+                r#"
+                INSERT INTO projects (
+                    id, goal_id, title, description, status, created_at, updated_at, completed_at, archived_at,
+                    review_interval_days, last_reviewed_at, incubated_at, sort_order, progress, color, icon
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+                ON CONFLICT(id) DO UPDATE SET
+                    goal_id = excluded.goal_id, title = excluded.title, description = excluded.description,
+                    status = excluded.status, created_at = excluded.created_at, updated_at = excluded.updated_at,
+                    completed_at = excluded.completed_at, archived_at = excluded.archived_at,
+                    review_interval_days = excluded.review_interval_days,
+                    last_reviewed_at = excluded.last_reviewed_at, incubated_at = excluded.incubated_at,
+                    sort_order = excluded.sort_order, progress = excluded.progress, color = excluded.color,
+                    icon = excluded.icon
+                "#
+            )
+            .bind(target(&project.id))
+            .bind(goal_id)
+            .bind(&project.title)
+            .bind(project.description.as_deref().map(remap))
+            .bind(project.status.to_string())
+            .bind(project.created_at)
+            .bind(project.updated_at)
+            .bind(project.completed_at)
+            .bind(project.archived_at)
+            .bind(project.review_interval_days)
+            .bind(project.last_reviewed_at)
+            .bind(project.incubated_at)
+            .bind(project.sort_order)
+            .bind(project.progress)
+            .bind(&project.color)
+            .bind(&project.icon)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import project", e))?;
+        }
+        report.sections.push(counts);
+
+        let mut counts = ImportSection { section: "tasks".to_string(), ..Default::default() };
+        for task in ordered_tasks {
+            if !Self::count_import(&mut counts, existing.contains(&task.id), collision) {
+                continue;
+            }
+            let item = format!("task '{}'", task.title);
+            let project_id = task.project_id.as_deref().map(target);
+            if let Some(project_id) = &project_id {
+                Self::ensure_import_parent(&mut tx, &item, "projects", project_id).await?;
+            }
+            let parent_task_id = task.parent_task_id.as_deref().map(target);
+            if let Some(parent_task_id) = &parent_task_id {
+                Self::ensure_import_parent(&mut tx, &item, "tasks", parent_task_id).await?;
+            }
+            let id = target(&task.id);
+            let description = task.description.as_deref().map(remap);
+
+            // A status the project's workflow doesn't have is replaced by a
+            // trigger, as for any new task
+            sqlx::query(
+                r#"
+                INSERT INTO tasks (
+                    id, project_id, parent_task_id, title, description, priority, due_date, created_at, updated_at,
+                    completed_at, archived_at, status, estimated_minutes, actual_minutes, recurrence_rule
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                ON CONFLICT(id) DO UPDATE SET
+                    project_id = excluded.project_id, parent_task_id = excluded.parent_task_id,
+                    title = excluded.title, description = excluded.description, priority = excluded.priority,
+                    due_date = excluded.due_date, created_at = excluded.created_at, updated_at = excluded.updated_at,
+                    completed_at = excluded.completed_at, archived_at = excluded.archived_at,
+                    status = excluded.status, estimated_minutes = excluded.estimated_minutes,
+                    actual_minutes = excluded.actual_minutes, recurrence_rule = excluded.recurrence_rule
+                "#
+            )
+            .bind(&id)
+            .bind(project_id)
+            .bind(parent_task_id)
+            .bind(&task.title)
+            .bind(&description)
+            .bind(task.priority.to_string())
+            .bind(task.due_date)
+            .bind(task.created_at)
+            .bind(task.updated_at)
+            .bind(task.completed_at)
+            .bind(task.archived_at)
+            .bind(&task.status)
+            .bind(task.estimated_minutes)
+            .bind(task.actual_minutes)
+            .bind(&task.recurrence_rule)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import task", e))?;
+            Self::write_task_references(&mut tx, &id, description.as_deref()).await?;
+        }
+        report.sections.push(counts);
+
+        let mut counts = ImportSection {
+            section: "notes".to_string(),
+            skipped: private_notes.len(),
+            ..Default::default()
+        };
+        for note in &notes {
+            if !Self::count_import(&mut counts, existing.contains(&note.id), collision) {
+                continue;
+            }
+            let item = format!("note '{}'", note.title);
+            let parents = [
+                ("tasks", note.task_id.as_deref().map(target)),
+                ("projects", note.project_id.as_deref().map(target)),
+                ("goals", note.goal_id.as_deref().map(target)),
+                ("life_areas", note.life_area_id.as_deref().map(target)),
+            ];
+            for (table, parent_id) in &parents {
+                if let Some(parent_id) = parent_id {
+                    Self::ensure_import_parent(&mut tx, &item, table, parent_id).await?;
+                }
+            }
+            let [(_, task_id), (_, project_id), (_, goal_id), (_, life_area_id)] = parents;
+            let id = target(&note.id);
+            let content = remap(&note.content);
+
+            sqlx::query(
+                r#"
+                INSERT INTO notes (
+                    id, task_id, project_id, goal_id, life_area_id, title, content, created_at, updated_at,
+                    archived_at, is_private, note_date, review_stage, next_review_at, last_reviewed_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                ON CONFLICT(id) DO UPDATE SET
+                    task_id = excluded.task_id, project_id = excluded.project_id, goal_id = excluded.goal_id,
+                    life_area_id = excluded.life_area_id, title = excluded.title, content = excluded.content,
+                    created_at = excluded.created_at, updated_at = excluded.updated_at,
+                    archived_at = excluded.archived_at, is_private = excluded.is_private,
+                    note_date = excluded.note_date, review_stage = excluded.review_stage,
+                    next_review_at = excluded.next_review_at, last_reviewed_at = excluded.last_reviewed_at
+                "#
+            )
+            .bind(&id)
+            .bind(task_id)
+            .bind(project_id)
+            .bind(goal_id)
+            .bind(life_area_id)
+            .bind(&note.title)
+            .bind(&content)
+            .bind(note.created_at)
+            .bind(note.updated_at)
+            .bind(note.archived_at)
+            .bind(false)
+            .bind(note.note_date)
+            .bind(note.review_stage)
+            .bind(note.next_review_at)
+            .bind(note.last_reviewed_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database_error("import note", e))?;
+
+            let tags = frontmatter::parse(&content).ok().flatten().and_then(|frontmatter| frontmatter.tags);
+            Self::set_note_tags(&mut tx, &id, &tags.unwrap_or_default()).await?;
+        }
+        report.sections.push(counts);
+
+        if dry_run {
+            tx.rollback()
+                .await
+                .map_err(|e| AppError::database_error("roll back import", e))?;
+        } else {
+            tx.commit()
+                .await
+                .map_err(|e| AppError::database_error("commit import", e))?;
+        }
+        report.ids = ids;
+        Ok(report)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,210 @@
+//! Serialized bulk writes
+//!
+//! SQLite allows one writer at a time. A bulk transaction (an import, a
+//! batch, a merge) holds the write lock until it commits, so two of them
+//! started together make one wait out the busy timeout and fail with
+//! `database is locked`. Bulk operations therefore run through a
+//! `WriteQueue`, one at a time in the order they were submitted, and publish
+//! `BulkWriteProgress` updates while they wait and run.
+//!
+//! Reads never go through the queue: in WAL mode they keep seeing the last
+//! committed state while a bulk transaction is open. Single-row interactive
+//! writes don't queue either; they are short enough to wait for the lock.
+
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Minimum time between two progress updates of one operation
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const PROGRESS_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkWriteState {
+    /// Waiting for earlier bulk operations to finish
+    Queued,
+    Running,
+    Finished,
+    Failed,
+}
+
+/// State of one bulk operation, as published to subscribers
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkWriteProgress {
+    /// Identifies the operation across its updates
+    pub id: u64,
+    /// What is being written, e.g. `import_jira`
+    pub operation: String,
+    pub state: BulkWriteState,
+    /// Items processed so far
+    pub done: u64,
+    /// Items to process, once the operation knows
+    pub total: Option<u64>,
+    pub queued_at: DateTime<Utc>,
+    pub error: Option<String>,
+}
+
+/// Runs bulk write operations one at a time; clones share the queue
+#[derive(Clone)]
+pub struct WriteQueue {
+    inner: Arc<QueueInner>,
+}
+
+struct QueueInner {
+    /// Held by the running operation; tokio's mutex is fair, so waiters run
+    /// in submission order
+    turn: tokio::sync::Mutex<()>,
+    next_id: AtomicU64,
+    sender: broadcast::Sender<BulkWriteProgress>,
+    /// Queued and running operations, oldest first
+    pending: Mutex<Vec<BulkWriteProgress>>,
+}
+
+impl QueueInner {
+    fn publish(&self, progress: &BulkWriteProgress) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        match progress.state {
+            BulkWriteState::Queued | BulkWriteState::Running => {
+                match pending.iter_mut().find(|p| p.id == progress.id) {
+                    Some(entry) => *entry = progress.clone(),
+                    None => pending.push(progress.clone()),
+                }
+            }
+            BulkWriteState::Finished | BulkWriteState::Failed => pending.retain(|p| p.id != progress.id),
+        }
+        // No subscribers is fine
+        let _ = self.sender.send(progress.clone());
+    }
+}
+
+impl Default for WriteQueue {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(PROGRESS_CHANNEL_CAPACITY);
+        Self {
+            inner: Arc::new(QueueInner {
+                turn: tokio::sync::Mutex::new(()),
+                next_id: AtomicU64::new(1),
+                sender,
+                pending: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+}
+
+impl WriteQueue {
+    pub fn subscribe(&self) -> broadcast::Receiver<BulkWriteProgress> {
+        self.inner.sender.subscribe()
+    }
+
+    /// Queued and running operations, oldest first
+    pub fn pending(&self) -> Vec<BulkWriteProgress> {
+        self.inner.pending.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Waits for the operations submitted earlier, then runs `work`
+    ///
+    /// `work` gets a `BulkProgress` to report how far it is. If the caller
+    /// stops waiting, the operation is reported as failed and leaves the
+    /// queue; an open transaction is rolled back when it is dropped.
+    pub async fn run<T, F, Fut>(&self, operation: &str, work: F) -> AppResult<T>
+    where
+        F: FnOnce(BulkProgress) -> Fut,
+        Fut: Future<Output = AppResult<T>>,
+    {
+        let tracker = Arc::new(Tracker {
+            queue: self.inner.clone(),
+            state: Mutex::new((
+                BulkWriteProgress {
+                    id: self.inner.next_id.fetch_add(1, Ordering::Relaxed),
+                    operation: operation.to_string(),
+                    state: BulkWriteState::Queued,
+                    done: 0,
+                    total: None,
+                    queued_at: Utc::now(),
+                    error: None,
+                },
+                Instant::now(),
+            )),
+        });
+        tracker.update(true, |_| {});
+        let _ticket = Ticket(tracker.clone());
+
+        let _turn = self.inner.turn.lock().await;
+        tracker.update(true, |progress| progress.state = BulkWriteState::Running);
+
+        let result = work(BulkProgress { tracker: Some(tracker.clone()) }).await;
+
+        tracker.update(true, |progress| match &result {
+            Ok(_) => progress.state = BulkWriteState::Finished,
+            Err(e) => {
+                progress.state = BulkWriteState::Failed;
+                progress.error = Some(e.message.clone());
+            }
+        });
+        result
+    }
+}
+
+struct Tracker {
+    queue: Arc<QueueInner>,
+    /// Current progress and when it was last published
+    state: Mutex<(BulkWriteProgress, Instant)>,
+}
+
+impl Tracker {
+    /// Applies `change` and publishes the result, unless `force` is unset and
+    /// the last update went out less than `PROGRESS_INTERVAL` ago
+    fn update(&self, force: bool, change: impl FnOnce(&mut BulkWriteProgress)) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        change(&mut state.0);
+        if force || state.1.elapsed() >= PROGRESS_INTERVAL {
+            state.1 = Instant::now();
+            self.queue.publish(&state.0);
+        }
+    }
+}
+
+/// Marks an operation as failed if its caller stops waiting for it
+struct Ticket(Arc<Tracker>);
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(|e| e.into_inner());
+        if matches!(state.0.state, BulkWriteState::Queued | BulkWriteState::Running) {
+            state.0.state = BulkWriteState::Failed;
+            state.0.error = Some("Cancelled".to_string());
+            self.0.queue.publish(&state.0);
+        }
+    }
+}
+
+/// Progress reporting handle given to a bulk operation
+///
+/// The default handle reports nowhere, for callers that don't go through a
+/// `WriteQueue`.
+#[derive(Clone, Default)]
+pub struct BulkProgress {
+    tracker: Option<Arc<Tracker>>,
+}
+
+impl BulkProgress {
+    /// Sets the number of items the operation will process
+    pub fn set_total(&self, total: usize) {
+        if let Some(tracker) = &self.tracker {
+            tracker.update(true, |progress| progress.total = Some(total as u64));
+        }
+    }
+
+    /// Counts `items` more as processed
+    pub fn advance(&self, items: usize) {
+        if let Some(tracker) = &self.tracker {
+            tracker.update(false, |progress| progress.done += items as u64);
+        }
+    }
+}
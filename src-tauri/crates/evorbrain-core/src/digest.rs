@@ -0,0 +1,200 @@
+//! Periodic digests
+//!
+//! A digest summarizes a range of days: the tasks completed in it, the tasks
+//! due in the week after it, the completion streak and how the open goals
+//! moved. It is composed as both Markdown and HTML so it can be saved as a
+//! note or sent on as is. The `weekly_digest` job composes the digest of the
+//! previous calendar week and delivers it as the `digest_delivery` setting
+//! says.
+
+use crate::achievements;
+use crate::calendar::Calendar;
+use crate::db::models::{DigestGoal, DigestTask};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Days after the range whose due tasks are listed as coming up
+const UPCOMING_DAYS: i64 = 7;
+
+/// Longest range a digest may cover
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// Where the `weekly_digest` job delivers the digest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestDelivery {
+    /// Saved as a note dated the last day of the range
+    #[default]
+    Note,
+    /// Sent to the webhooks subscribed to `digest.generated`
+    Webhook,
+}
+
+/// Everything `generate_digest` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Digest {
+    /// First day of the range
+    pub from: NaiveDate,
+    /// Last day of the range, included
+    pub to: NaiveDate,
+    /// e.g. `Digest 2026-10-05 – 2026-10-11` in the user's date format
+    pub title: String,
+    /// Oldest completion first
+    pub completed: Vec<DigestTask>,
+    /// Tasks due in the seven days after the range, soonest first
+    pub upcoming: Vec<DigestTask>,
+    /// Working days in a row with a completed task, as of today
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    /// Open goals, those with tasks completed in the range first
+    pub goals: Vec<DigestGoal>,
+    pub markdown: String,
+    pub html: String,
+}
+
+/// The calendar week before the current one, as (first day, last day)
+pub fn previous_week(calendar: &Calendar) -> (NaiveDate, NaiveDate) {
+    let week = calendar.week(calendar.today() - Duration::weeks(1));
+    (week.first_day, week.last_day)
+}
+
+/// Composes the digest of `from` through `to`
+pub async fn generate_digest(repo: &Repository, from: NaiveDate, to: NaiveDate) -> AppResult<Digest> {
+    if to < from {
+        return Err(AppError::validation_error("to", "must not be before from"));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(AppError::validation_error("to", "a digest covers at most 366 days"));
+    }
+
+    let calendar = Calendar::load(repo).await?;
+    let start = calendar.day(from).start;
+    let end = calendar.day(to).end;
+    let upcoming_end = calendar.day(to + Duration::days(UPCOMING_DAYS)).end;
+
+    let completed = repo.get_tasks_completed_between(start, end).await?;
+    let upcoming = repo.get_tasks_due_between(end, upcoming_end).await?;
+    let goals = repo.get_goal_progress_between(start, end).await?;
+    let streaks = achievements::get_achievements(repo).await?;
+
+    let mut digest = Digest {
+        from,
+        to,
+        title: format!("Digest {} – {}", calendar.format_date(from), calendar.format_date(to)),
+        completed,
+        upcoming,
+        current_streak: streaks.current_streak,
+        longest_streak: streaks.longest_streak,
+        goals,
+        markdown: String::new(),
+        html: String::new(),
+    };
+    digest.markdown = render_markdown(&digest, &calendar);
+    digest.html = render_html(&digest, &calendar);
+    Ok(digest)
+}
+
+/// Text of the digest's sections, shared by both renderings
+struct Sections {
+    completed_heading: String,
+    completed: Vec<String>,
+    upcoming_heading: String,
+    upcoming: Vec<String>,
+    streak: String,
+    goals: Vec<String>,
+}
+
+impl Sections {
+    fn new(digest: &Digest, calendar: &Calendar) -> Self {
+        let locale = calendar.locale();
+        let number = |n: i64| locale.format_number(n);
+        let task_line = |task: &DigestTask, prefix: &str| {
+            let date = calendar.format_date(calendar.local_date(task.at));
+            match &task.project_title {
+                Some(project) => format!("{} ({}), {} {}", task.title, project, prefix, date),
+                None => format!("{}, {} {}", task.title, prefix, date),
+            }
+        };
+
+        let longest = number(digest.longest_streak);
+        let streak = match digest.current_streak {
+            0 => format!("No streak running; the longest was {} working days.", longest),
+            1 => format!("1 working day in a row; the longest was {}.", longest),
+            days => format!("{} working days in a row; the longest was {}.", number(days), longest),
+        };
+
+        Self {
+            completed_heading: format!("Completed ({})", number(digest.completed.len() as i64)),
+            completed: digest.completed.iter().map(|task| task_line(task, "done")).collect(),
+            upcoming_heading: format!("Coming up ({})", number(digest.upcoming.len() as i64)),
+            upcoming: digest.upcoming.iter().map(|task| task_line(task, "due")).collect(),
+            streak,
+            goals: digest
+                .goals
+                .iter()
+                .map(|goal| {
+                    format!(
+                        "{}: {}, {} tasks done in this period, {} open",
+                        goal.title,
+                        locale.format_percent(goal.progress),
+                        number(goal.completed_tasks),
+                        number(goal.open_tasks)
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+fn render_markdown(digest: &Digest, calendar: &Calendar) -> String {
+    let sections = Sections::new(digest, calendar);
+    let mut markdown = format!("# {}\n", digest.title);
+
+    let list = |markdown: &mut String, heading: &str, items: &[String], empty: &str| {
+        let _ = write!(markdown, "\n## {}\n\n", heading);
+        if items.is_empty() {
+            let _ = writeln!(markdown, "{}", empty);
+        }
+        for item in items {
+            let _ = writeln!(markdown, "- {}", item);
+        }
+    };
+    list(&mut markdown, &sections.completed_heading, &sections.completed, "Nothing was completed.");
+    list(&mut markdown, &sections.upcoming_heading, &sections.upcoming, "Nothing is due.");
+    let _ = write!(markdown, "\n## Streak\n\n{}\n", sections.streak);
+    list(&mut markdown, "Goals", &sections.goals, "No open goals.");
+    markdown
+}
+
+fn render_html(digest: &Digest, calendar: &Calendar) -> String {
+    let sections = Sections::new(digest, calendar);
+    let mut html = format!("<h1>{}</h1>\n", escape_html(&digest.title));
+
+    let list = |html: &mut String, heading: &str, items: &[String], empty: &str| {
+        let _ = writeln!(html, "<h2>{}</h2>", escape_html(heading));
+        if items.is_empty() {
+            let _ = writeln!(html, "<p>{}</p>", empty);
+            return;
+        }
+        html.push_str("<ul>\n");
+        for item in items {
+            let _ = writeln!(html, "<li>{}</li>", escape_html(item));
+        }
+        html.push_str("</ul>\n");
+    };
+    list(&mut html, &sections.completed_heading, &sections.completed, "Nothing was completed.");
+    list(&mut html, &sections.upcoming_heading, &sections.upcoming, "Nothing is due.");
+    let _ = write!(html, "<h2>Streak</h2>\n<p>{}</p>\n", escape_html(&sections.streak));
+    list(&mut html, "Goals", &sections.goals, "No open goals.");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,295 @@
+//! Data transfer objects for the v2 command API
+//!
+//! v2 commands answer with an `Envelope`: the payload under `data`, plus
+//! warnings about anything the caller should know but that didn't fail the
+//! request, pagination for lists and the server time. Payloads are DTOs with
+//! camelCase field names, converted from the models, so the frontend no
+//! longer sees the snake_case column names of the database. The models keep
+//! their own serialization for the v1 commands, the CLI and exports.
+
+use crate::db::models::{AreaContext, Goal, LifeArea, Note, Project, ProjectStatus, Task, TaskPriority};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Page size used when a list command isn't given one
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Largest page a list command returns
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// Response of every v2 command
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope<T> {
+    pub data: T,
+    pub warnings: Vec<String>,
+    /// Set for list commands
+    pub pagination: Option<Pagination>,
+    pub server_time: DateTime<Utc>,
+}
+
+impl<T> Envelope<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            warnings: Vec::new(),
+            pagination: None,
+            server_time: Utc::now(),
+        }
+    }
+
+    pub fn with_warning(mut self, warning: impl Into<String>) -> Self {
+        self.warnings.push(warning.into());
+        self
+    }
+}
+
+impl<T> Envelope<Vec<T>> {
+    /// Returns one page of `items`; pages are numbered from 1
+    ///
+    /// A page size above `MAX_PAGE_SIZE` is lowered to it, and a page past
+    /// the end comes back empty; both add a warning.
+    pub fn page(items: Vec<T>, page: Option<usize>, page_size: Option<usize>) -> Self {
+        let mut warnings = Vec::new();
+        let page = page.unwrap_or(1).max(1);
+        let mut page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+        if page_size > MAX_PAGE_SIZE {
+            warnings.push(format!("pageSize lowered from {} to {}", page_size, MAX_PAGE_SIZE));
+            page_size = MAX_PAGE_SIZE;
+        }
+
+        let total_items = items.len();
+        let total_pages = total_items.div_ceil(page_size);
+        if page > total_pages.max(1) {
+            warnings.push(format!("page {} is past the last page ({})", page, total_pages));
+        }
+
+        let data = items.into_iter().skip((page - 1) * page_size).take(page_size).collect();
+        Self {
+            data,
+            warnings,
+            pagination: Some(Pagination {
+                page,
+                page_size,
+                total_items,
+                total_pages,
+            }),
+            server_time: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    /// Page returned, from 1
+    pub page: usize,
+    pub page_size: usize,
+    pub total_items: usize,
+    pub total_pages: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifeAreaDto {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub context: Option<AreaContext>,
+}
+
+impl From<LifeArea> for LifeAreaDto {
+    fn from(area: LifeArea) -> Self {
+        Self {
+            id: area.id,
+            name: area.name,
+            description: area.description,
+            color: area.color,
+            icon: area.icon,
+            created_at: area.created_at,
+            updated_at: area.updated_at,
+            archived_at: area.archived_at,
+            context: area.context,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalDto {
+    pub id: String,
+    pub life_area_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub target_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub incubated_at: Option<DateTime<Utc>>,
+    pub sort_order: i64,
+    pub progress: i64,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub display_color: Option<String>,
+    pub display_icon: Option<String>,
+}
+
+impl From<Goal> for GoalDto {
+    fn from(goal: Goal) -> Self {
+        Self {
+            id: goal.id,
+            life_area_id: goal.life_area_id,
+            title: goal.title,
+            description: goal.description,
+            target_date: goal.target_date,
+            created_at: goal.created_at,
+            updated_at: goal.updated_at,
+            completed_at: goal.completed_at,
+            archived_at: goal.archived_at,
+            incubated_at: goal.incubated_at,
+            sort_order: goal.sort_order,
+            progress: goal.progress,
+            color: goal.color,
+            icon: goal.icon,
+            display_color: goal.display_color,
+            display_icon: goal.display_icon,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDto {
+    pub id: String,
+    pub goal_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: ProjectStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub review_interval_days: Option<i64>,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+    pub incubated_at: Option<DateTime<Utc>>,
+    pub sort_order: i64,
+    pub progress: i64,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub display_color: Option<String>,
+    pub display_icon: Option<String>,
+}
+
+impl From<Project> for ProjectDto {
+    fn from(project: Project) -> Self {
+        Self {
+            id: project.id,
+            goal_id: project.goal_id,
+            title: project.title,
+            description: project.description,
+            status: project.status,
+            created_at: project.created_at,
+            updated_at: project.updated_at,
+            completed_at: project.completed_at,
+            archived_at: project.archived_at,
+            review_interval_days: project.review_interval_days,
+            last_reviewed_at: project.last_reviewed_at,
+            incubated_at: project.incubated_at,
+            sort_order: project.sort_order,
+            progress: project.progress,
+            color: project.color,
+            icon: project.icon,
+            display_color: project.display_color,
+            display_icon: project.display_icon,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDto {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub parent_task_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: TaskPriority,
+    pub due_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub estimated_minutes: Option<i64>,
+    pub actual_minutes: i64,
+    pub recurrence_rule: Option<String>,
+    pub display_color: Option<String>,
+    pub display_icon: Option<String>,
+}
+
+impl From<Task> for TaskDto {
+    fn from(task: Task) -> Self {
+        Self {
+            id: task.id,
+            project_id: task.project_id,
+            parent_task_id: task.parent_task_id,
+            title: task.title,
+            description: task.description,
+            priority: task.priority,
+            due_date: task.due_date,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+            completed_at: task.completed_at,
+            archived_at: task.archived_at,
+            status: task.status,
+            estimated_minutes: task.estimated_minutes,
+            actual_minutes: task.actual_minutes,
+            recurrence_rule: task.recurrence_rule,
+            display_color: task.display_color,
+            display_icon: task.display_icon,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteDto {
+    pub id: String,
+    pub task_id: Option<String>,
+    pub project_id: Option<String>,
+    pub goal_id: Option<String>,
+    pub life_area_id: Option<String>,
+    pub title: String,
+    /// Empty for a private note while the note key is locked
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub is_private: bool,
+    pub note_date: Option<NaiveDate>,
+}
+
+impl From<Note> for NoteDto {
+    fn from(note: Note) -> Self {
+        Self {
+            id: note.id,
+            task_id: note.task_id,
+            project_id: note.project_id,
+            goal_id: note.goal_id,
+            life_area_id: note.life_area_id,
+            title: note.title,
+            content: note.content,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+            archived_at: note.archived_at,
+            is_private: note.is_private,
+            note_date: note.note_date,
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Due date suggestions from the planned workload
+//!
+//! A suggestion looks at the coming working days, starting tomorrow, and
+//! compares the work already planned on each (open tasks due or focused that
+//! day, as in `get_day_capacity`) with its working minutes. The first day
+//! that stays comfortably below capacity with the task added wins; failing
+//! that, the first day it fits at all; failing that, the least busy day.
+//! The reason is returned in words so the UI can show it as is.
+
+use crate::calendar::Calendar;
+use crate::db::models::Task;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Working days looked at, starting tomorrow
+const HORIZON_WORKING_DAYS: i64 = 10;
+
+/// Share of a day's working minutes planned work may take, with the task
+/// added, for the day to count as light
+const LIGHT_DAY_SHARE: f64 = 0.8;
+
+/// Minutes assumed for a task without an estimate
+pub const DEFAULT_TASK_MINUTES: i64 = 30;
+
+/// Workload of one candidate day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateDay {
+    pub date: NaiveDate,
+    pub capacity_minutes: i64,
+    /// Estimated work already planned, not counting the task itself
+    pub planned_minutes: i64,
+    pub task_count: i64,
+    /// Planned tasks without an estimate, so `planned_minutes` may be low
+    pub unestimated_count: i64,
+}
+
+impl CandidateDay {
+    fn free_minutes(&self) -> i64 {
+        self.capacity_minutes - self.planned_minutes
+    }
+}
+
+/// Everything `suggest_due_date` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DueDateSuggestion {
+    pub date: NaiveDate,
+    /// Start of `date` in the user's time zone, ready to store as the due date
+    pub due_date: DateTime<Utc>,
+    /// e.g. "Thursday 2026-10-22 looks light: 90 of 480 working minutes are planned"
+    pub reason: String,
+    /// The task's estimate, or the default when it has none
+    pub task_minutes: i64,
+    pub estimated: bool,
+    /// The working days considered, in order
+    pub candidates: Vec<CandidateDay>,
+}
+
+/// Suggests a due date for a task taking `estimated_minutes`
+///
+/// `task` is the existing task being rescheduled, if any: its estimate is
+/// used when `estimated_minutes` isn't given, and it isn't counted in the
+/// workload of the day it is currently due.
+pub async fn suggest_due_date(
+    repo: &Repository,
+    task: Option<&Task>,
+    estimated_minutes: Option<i64>,
+) -> AppResult<DueDateSuggestion> {
+    let calendar = Calendar::load(repo).await?;
+    let today = calendar.today();
+
+    let estimate = estimated_minutes.or(task.and_then(|task| task.estimated_minutes));
+    let task_minutes = estimate.unwrap_or(DEFAULT_TASK_MINUTES);
+    let current_due = task
+        .filter(|task| task.completed_at.is_none())
+        .and_then(|task| task.due_date)
+        .map(|due| calendar.local_date(due));
+
+    let mut candidates = Vec::new();
+    let mut date = today;
+    for _ in 0..HORIZON_WORKING_DAYS {
+        date = calendar.next_working_day(date + Duration::days(1));
+        let day = calendar.day(date);
+        let load = repo.get_day_load(date, day.start, day.end, false).await?;
+
+        let mut candidate = CandidateDay {
+            date,
+            capacity_minutes: calendar.working_minutes(date),
+            planned_minutes: load.planned_minutes,
+            task_count: load.task_count,
+            unestimated_count: load.unestimated_count,
+        };
+        if current_due == Some(date) {
+            // The day plan may hold its own minutes for the task; the
+            // estimate is close enough to take it back out
+            let own_minutes = task.and_then(|task| task.estimated_minutes).unwrap_or(0);
+            candidate.planned_minutes = (candidate.planned_minutes - own_minutes).max(0);
+            candidate.task_count -= 1;
+        }
+        candidates.push(candidate);
+    }
+
+    let light = candidates.iter().position(|day| {
+        (day.planned_minutes + task_minutes) as f64 <= day.capacity_minutes as f64 * LIGHT_DAY_SHARE
+    });
+    let fits = candidates.iter().position(|day| day.free_minutes() >= task_minutes);
+    let locale = calendar.locale();
+    let (index, reason) = match (light, fits) {
+        (Some(index), _) => {
+            let day = &candidates[index];
+            (index, format!("{} looks light: {}", calendar.format_date_with_weekday(day.date), describe(&calendar, day)))
+        }
+        (None, Some(index)) => {
+            let day = &candidates[index];
+            let reason = format!(
+                "{} is the first day with room for {} minutes: {}",
+                calendar.format_date_with_weekday(day.date),
+                locale.format_number(task_minutes),
+                describe(&calendar, day)
+            );
+            (index, reason)
+        }
+        (None, None) => {
+            // The earliest of the least busy days
+            let index = (0..candidates.len())
+                .rev()
+                .max_by_key(|index| candidates[*index].free_minutes())
+                .unwrap_or(0);
+            let day = &candidates[index];
+            let reason = format!(
+                "No day in the next {} working days has room for {} minutes; {} is the least busy: {}",
+                HORIZON_WORKING_DAYS,
+                locale.format_number(task_minutes),
+                calendar.format_date_with_weekday(day.date),
+                describe(&calendar, day)
+            );
+            (index, reason)
+        }
+    };
+
+    let date = candidates[index].date;
+    Ok(DueDateSuggestion {
+        date,
+        due_date: calendar.day(date).start,
+        reason,
+        task_minutes,
+        estimated: estimate.is_some(),
+        candidates,
+    })
+}
+
+fn describe(calendar: &Calendar, day: &CandidateDay) -> String {
+    let locale = calendar.locale();
+    let mut text = format!(
+        "{} of {} working minutes are planned",
+        locale.format_number(day.planned_minutes),
+        locale.format_number(day.capacity_minutes)
+    );
+    if day.unestimated_count > 0 {
+        text.push_str(&format!(
+            ", plus {} tasks without an estimate",
+            locale.format_number(day.unestimated_count)
+        ));
+    }
+    text
+}
@@ -8,6 +8,9 @@ pub struct AppError {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<String>,
+    /// Machine-readable context for errors the caller can act on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
@@ -28,15 +31,30 @@ pub enum ErrorCode {
     AlreadyExists,
     CannotDelete,
     CannotUpdate,
+    /// A restore was refused because a parent is still archived
+    ArchivedParent,
+    /// An update was refused because the entity changed since the client
+    /// loaded it; the data holds both versions
+    Conflict,
     
     // System errors
     InternalError,
     ConfigError,
     IoError,
     
-    // Auth errors (future use)
+    // Auth errors
     Unauthorized,
     Forbidden,
+    /// The app lock is engaged and must be unlocked first
+    Locked,
+    /// A destructive command was called without a valid confirmation token
+    ConfirmationRequired,
+    /// An attachment is over the size limit per file or the total quota
+    QuotaExceeded,
+    /// An expensive command was called too often; the data says when to retry
+    RateLimited,
+    /// Read-only mode is on, so nothing can be changed
+    ReadOnly,
 }
 
 impl AppError {
@@ -45,6 +63,7 @@ impl AppError {
             code,
             message: message.into(),
             details: None,
+            data: None,
         };
         
         // Log errors and warnings
@@ -75,6 +94,11 @@ impl AppError {
         self.details = Some(details_str);
         self
     }
+
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
     
     // Common error constructors
     pub fn not_found(entity: &str, id: &str) -> Self {
@@ -203,10 +227,13 @@ impl serde::Serialize for AppError {
     {
         use serde::ser::SerializeStruct;
         
-        let mut state = serializer.serialize_struct("AppError", 3)?;
+        let mut state = serializer.serialize_struct("AppError", 4)?;
         state.serialize_field("code", &self.code)?;
         state.serialize_field("message", &self.message)?;
         state.serialize_field("details", &self.details)?;
+        if let Some(data) = &self.data {
+            state.serialize_field("data", data)?;
+        }
         state.end()
     }
 }
\ No newline at end of file
@@ -0,0 +1,197 @@
+//! In-process event bus
+//!
+//! Domain events are derived from the `change_log` table by a polling change
+//! feed, so writes made through any path (IPC commands, the local REST API,
+//! the CLI) are observed the same way. Subscribers such as webhooks or the
+//! frontend bridge receive events through a tokio broadcast channel.
+
+use crate::db::models::{ChangeLogEntry, ChangeOperation};
+use crate::db::repository::Repository;
+use crate::log_error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Entity types recorded in the change log
+pub const ENTITY_TYPES: &[&str] = &["life_area", "goal", "project", "task", "note", "tag"];
+
+/// Actions an entity event can describe, in `<entity>.<action>` event names
+pub const ENTITY_ACTIONS: &[&str] = &[
+    "created", "updated", "deleted", "archived", "restored", "completed", "reopened",
+];
+
+/// Events that are published directly rather than derived from the change log
+pub const STANDALONE_EVENTS: &[&str] = &[
+    "reminder.fired",
+    "settings.updated",
+    "app.locked",
+    "app.unlocked",
+    "workflow.updated",
+    "achievement.unlocked",
+    "timer.idle_stopped",
+    "digest.generated",
+    "project.completion_suggested",
+    "project.auto_completed",
+    "goal.completion_suggested",
+    "goal.auto_completed",
+];
+
+const DEFAULT_BUS_CAPACITY: usize = 1024;
+const CHANGE_FEED_BATCH_SIZE: i64 = 500;
+
+/// A domain event such as `task.completed` or `goal.completed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppEvent {
+    /// Event name in `<entity>.<action>` form
+    pub name: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Change log sequence number the event was derived from, if any
+    pub seq: Option<i64>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl AppEvent {
+    pub fn new(name: impl Into<String>, entity_type: impl Into<String>, entity_id: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entity_type: entity_type.into(),
+            entity_id: entity_id.into(),
+            seq: None,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn from_change(entry: &ChangeLogEntry) -> Self {
+        let action = match entry.operation {
+            ChangeOperation::Insert => "created",
+            ChangeOperation::Update => "updated",
+            ChangeOperation::Delete => "deleted",
+            ChangeOperation::Archive => "archived",
+            ChangeOperation::Restore => "restored",
+            ChangeOperation::Complete => "completed",
+            ChangeOperation::Uncomplete => "reopened",
+        };
+
+        Self {
+            name: format!("{}.{}", entry.entity_type, action),
+            entity_type: entry.entity_type.clone(),
+            entity_id: entry.entity_id.clone(),
+            seq: Some(entry.seq),
+            occurred_at: entry.changed_at,
+        }
+    }
+}
+
+/// Returns true if `name` is an event the application can publish
+pub fn is_known_event(name: &str) -> bool {
+    if STANDALONE_EVENTS.contains(&name) {
+        return true;
+    }
+
+    match name.split_once('.') {
+        Some((entity, action)) => ENTITY_TYPES.contains(&entity) && ENTITY_ACTIONS.contains(&action),
+        None => false,
+    }
+}
+
+/// Returns true if `pattern` is a valid event filter
+///
+/// Filters are either an exact event name, `<entity>.*`, or `*` for everything.
+pub fn is_valid_event_filter(pattern: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    match pattern.strip_suffix(".*") {
+        Some(entity) => {
+            ENTITY_TYPES.contains(&entity)
+                || STANDALONE_EVENTS.iter().any(|e| e.split('.').next() == Some(entity))
+        }
+        None => is_known_event(pattern),
+    }
+}
+
+/// Checks whether an event name matches any of the given filters
+pub fn matches_event_filter(filters: &[String], name: &str) -> bool {
+    filters.iter().any(|pattern| {
+        pattern == "*"
+            || pattern == name
+            || pattern
+                .strip_suffix(".*")
+                .is_some_and(|prefix| name.split('.').next() == Some(prefix))
+    })
+}
+
+/// Broadcast channel shared by event producers and subscribers
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<AppEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(DEFAULT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publishes an event; it is silently dropped when nobody is subscribed
+    pub fn publish(&self, event: AppEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Polls the change log and publishes an event for every new entry
+///
+/// Starts from the current end of the log, so only changes made after
+/// startup are published. Runs until the task is dropped.
+pub async fn run_change_feed(db: Arc<SqlitePool>, bus: EventBus, poll_interval: Duration) {
+    let repo = Repository::new(db);
+    let mut cursor = match repo.get_latest_change_seq().await {
+        Ok(seq) => seq,
+        Err(e) => {
+            log_error!("Change feed failed to read the change log", e);
+            0
+        }
+    };
+
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        interval.tick().await;
+
+        let changes = match repo.get_changes_since(cursor, CHANGE_FEED_BATCH_SIZE).await {
+            Ok(changes) => changes,
+            Err(e) => {
+                log_error!("Change feed poll failed", e);
+                continue;
+            }
+        };
+
+        for change in &changes {
+            bus.publish(AppEvent::from_change(change));
+            cursor = change.seq;
+        }
+
+        // The log was reset (e.g. reset_database); start over from its new end
+        if changes.is_empty() {
+            if let Ok(latest) = repo.get_latest_change_seq().await {
+                if latest < cursor {
+                    cursor = latest;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Focus time by goal and life area
+//!
+//! Time tracked on tasks rolls up through their project to a goal and its
+//! life area, so the time actually spent can be held against the priorities
+//! the user stated. Entries are cut to the days of the range in the user's
+//! time zone, and a running timer counts up to now. Open goals and life
+//! areas without any tracked time are listed too, last, since an important
+//! goal getting no time is exactly what the report should show.
+//!
+//! Life areas can have a weekly time budget. The budget report holds a
+//! week's tracked time against it: over once the whole budget is used up,
+//! under while behind the share of it the days gone by account for, each
+//! with some slack.
+
+use crate::calendar::Calendar;
+use crate::db::models::{BudgetStatus, TaskFocus, TimeBudget, TimeBudgetReport};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+
+/// Share of a budget tracked time may fall short of or go past it by and
+/// still be on track
+const BUDGET_SLACK: f64 = 0.1;
+use serde::{Deserialize, Serialize};
+
+/// Time tracked towards one goal or life area
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusGroup {
+    pub id: String,
+    pub title: String,
+    /// The goal's life area; `None` for life area groups
+    pub life_area_id: Option<String>,
+    pub minutes: i64,
+    /// Percentage of all time tracked in the range
+    pub share: f64,
+    /// Tasks time was tracked on
+    pub task_count: i64,
+    pub entry_count: i64,
+}
+
+/// Everything `get_focus_by_goal` and `get_focus_by_life_area` return
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusReport {
+    pub from: NaiveDate,
+    /// Last day of the range, included
+    pub to: NaiveDate,
+    pub tracked_minutes: i64,
+    /// Time on tasks outside any project, which belong to no goal
+    pub unassigned_minutes: i64,
+    /// Most time first; those without tracked time last
+    pub groups: Vec<FocusGroup>,
+}
+
+/// Time tracked from `from` through `to` per open goal
+pub async fn get_focus_by_goal(repo: &Repository, from: NaiveDate, to: NaiveDate) -> AppResult<FocusReport> {
+    let goals = repo
+        .get_goals()
+        .await?
+        .into_iter()
+        .filter(|goal| goal.completed_at.is_none())
+        .map(|goal| (goal.id, goal.title, Some(goal.life_area_id)))
+        .collect();
+    focus_report(repo, from, to, goals, |task| {
+        Some((task.goal_id.clone()?, task.goal_title.clone()?, task.life_area_id.clone()))
+    })
+    .await
+}
+
+/// Time tracked from `from` through `to` per life area
+pub async fn get_focus_by_life_area(repo: &Repository, from: NaiveDate, to: NaiveDate) -> AppResult<FocusReport> {
+    let areas = repo
+        .get_life_areas()
+        .await?
+        .into_iter()
+        .map(|area| (area.id, area.name, None))
+        .collect();
+    focus_report(repo, from, to, areas, |task| {
+        Some((task.life_area_id.clone()?, task.life_area_name.clone()?, None))
+    })
+    .await
+}
+
+/// Groups as (id, title, life area id)
+type GroupKey = (String, String, Option<String>);
+
+async fn focus_report(
+    repo: &Repository,
+    from: NaiveDate,
+    to: NaiveDate,
+    open_groups: Vec<GroupKey>,
+    group_of: impl Fn(&TaskFocus) -> Option<GroupKey>,
+) -> AppResult<FocusReport> {
+    if to < from {
+        return Err(AppError::validation_error("to", "must not be before from"));
+    }
+
+    let calendar = Calendar::load(repo).await?;
+    let tasks = repo
+        .get_task_focus(calendar.day(from).start, calendar.day(to).end, Utc::now())
+        .await?;
+
+    let mut groups: Vec<FocusGroup> = Vec::new();
+    let mut seconds: Vec<i64> = Vec::new();
+    let mut unassigned_seconds = 0;
+    for task in &tasks {
+        let Some((id, title, life_area_id)) = group_of(task) else {
+            unassigned_seconds += task.seconds;
+            continue;
+        };
+        let index = match groups.iter().position(|group| group.id == id) {
+            Some(index) => index,
+            None => {
+                groups.push(FocusGroup {
+                    id,
+                    title,
+                    life_area_id,
+                    minutes: 0,
+                    share: 0.0,
+                    task_count: 0,
+                    entry_count: 0,
+                });
+                seconds.push(0);
+                groups.len() - 1
+            }
+        };
+        groups[index].task_count += 1;
+        groups[index].entry_count += task.entry_count;
+        seconds[index] += task.seconds;
+    }
+
+    let tracked_seconds: i64 = tasks.iter().map(|task| task.seconds).sum();
+    for (group, seconds) in groups.iter_mut().zip(seconds) {
+        group.minutes = seconds / 60;
+        if tracked_seconds > 0 {
+            group.share = seconds as f64 * 100.0 / tracked_seconds as f64;
+        }
+    }
+    groups.sort_by_key(|group| std::cmp::Reverse(group.minutes));
+
+    for (id, title, life_area_id) in open_groups {
+        if !groups.iter().any(|group| group.id == id) {
+            groups.push(FocusGroup {
+                id,
+                title,
+                life_area_id,
+                minutes: 0,
+                share: 0.0,
+                task_count: 0,
+                entry_count: 0,
+            });
+        }
+    }
+
+    Ok(FocusReport {
+        from,
+        to,
+        tracked_minutes: tracked_seconds / 60,
+        unassigned_minutes: unassigned_seconds / 60,
+        groups,
+    })
+}
+
+/// Tracked time against the budget of each life area that has one, over the
+/// week containing `date`
+pub async fn get_time_budget_report(repo: &Repository, date: NaiveDate) -> AppResult<TimeBudgetReport> {
+    let calendar = Calendar::load(repo).await?;
+    let week = calendar.week(date);
+    let today = calendar.today();
+    let elapsed_days = if today > week.last_day {
+        7
+    } else if today < week.first_day {
+        0
+    } else {
+        (today - week.first_day).num_days() + 1
+    };
+
+    let focus = get_focus_by_life_area(repo, week.first_day, week.last_day).await?;
+    let groups: HashMap<&str, &FocusGroup> = focus.groups.iter().map(|group| (group.id.as_str(), group)).collect();
+
+    let mut budgets: Vec<TimeBudget> = repo
+        .get_life_area_budgets()
+        .await?
+        .into_iter()
+        // Archived life areas aren't in the focus report
+        .filter_map(|budget| {
+            let group = groups.get(budget.life_area_id.as_str())?;
+            let budget_minutes = budget.weekly_minutes;
+            let tracked = group.minutes as f64;
+            let expected = budget_minutes as f64 * elapsed_days as f64 / 7.0;
+            let status = if tracked > budget_minutes as f64 * (1.0 + BUDGET_SLACK) {
+                BudgetStatus::Over
+            } else if tracked < expected * (1.0 - BUDGET_SLACK) {
+                BudgetStatus::Under
+            } else {
+                BudgetStatus::OnTrack
+            };
+            Some(TimeBudget {
+                life_area_id: budget.life_area_id,
+                name: group.title.clone(),
+                budget_minutes,
+                tracked_minutes: group.minutes,
+                expected_minutes: expected.round() as i64,
+                percent: tracked * 100.0 / budget_minutes as f64,
+                status,
+            })
+        })
+        .collect();
+    budgets.sort_by_key(|budget| budget.name.to_lowercase());
+
+    Ok(TimeBudgetReport {
+        from: week.first_day,
+        to: week.last_day,
+        budgets,
+    })
+}
@@ -0,0 +1,225 @@
+//! YAML frontmatter in note content
+//!
+//! A note may start with a frontmatter block between `---` lines. When it
+//! is saved, the recognized keys overwrite the note's structured fields, so
+//! power users can edit metadata as text:
+//!
+//! ```text
+//! ---
+//! tags: [reading, ideas]
+//! project: 2f6c…
+//! date: 2024-03-01
+//! ---
+//! ```
+//!
+//! `tags` gives the note its tags (notes get tags only this way, so a note
+//! without the key has none); `task`, `project`, `goal` and `life_area`
+//! link the note by id (an empty value unlinks it); `date` sets the date the
+//! note is about. Other keys that are left out keep the value from the save
+//! request, and unknown keys are ignored. Only the subset of YAML
+//! frontmatter needs in practice is understood: `key: value` pairs with
+//! plain or quoted scalars, flow lists (`[a, b]`) and block lists (`- a`).
+//! Private notes are never parsed, since their content is encrypted.
+
+use crate::db::models::NoteInput;
+use crate::error::{AppError, AppResult};
+use chrono::NaiveDate;
+
+/// Most tags one note's frontmatter may list
+pub const MAX_NOTE_TAGS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Scalar(String),
+    List(Vec<String>),
+    /// A nested mapping or anything else not understood; fine for unknown keys
+    Unsupported,
+}
+
+/// The recognized frontmatter keys of a note; `None` when a key is absent
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoteFrontmatter {
+    pub tags: Option<Vec<String>>,
+    pub task_id: Option<Option<String>>,
+    pub project_id: Option<Option<String>>,
+    pub goal_id: Option<Option<String>>,
+    pub life_area_id: Option<Option<String>>,
+    pub date: Option<Option<NaiveDate>>,
+}
+
+impl NoteFrontmatter {
+    /// Overwrites the fields of `input` the frontmatter sets
+    pub fn apply(&self, input: &mut NoteInput) {
+        let links = [
+            (&self.task_id, &mut input.task_id),
+            (&self.project_id, &mut input.project_id),
+            (&self.goal_id, &mut input.goal_id),
+            (&self.life_area_id, &mut input.life_area_id),
+        ];
+        for (value, field) in links {
+            if let Some(value) = value {
+                field.clone_from(value);
+            }
+        }
+        if let Some(date) = self.date {
+            input.note_date = date;
+        }
+    }
+}
+
+fn invalid(reason: &str) -> AppError {
+    AppError::validation_error("frontmatter", reason)
+}
+
+fn unquote(text: &str) -> &str {
+    let text = text.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = text.strip_prefix(quote).and_then(|t| t.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    text
+}
+
+/// Drops a trailing ` # comment` outside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '#') if previous.is_whitespace() => return &line[..index],
+            _ => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+fn parse_inline(text: &str) -> Value {
+    let text = text.trim();
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        _ if text.starts_with('[') && text.ends_with(']') => Value::List(
+            text[1..text.len() - 1]
+                .split(',')
+                .map(unquote)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+        ),
+        _ if text.starts_with('{') => Value::Unsupported,
+        _ => Value::Scalar(unquote(text).to_string()),
+    }
+}
+
+/// Splits content into its frontmatter lines, if it starts with a closed
+/// frontmatter block
+fn block(content: &str) -> Option<Vec<&str>> {
+    let mut lines = content.trim_start_matches('\u{feff}').lines();
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+    let mut block = Vec::new();
+    for line in lines {
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some(block);
+        }
+        block.push(line);
+    }
+    // Without a closing line the `---` was a horizontal rule
+    None
+}
+
+/// Top-level keys with their values, in order
+fn entries(lines: &[&str]) -> AppResult<Vec<(String, Value)>> {
+    let mut entries: Vec<(String, Value)> = Vec::new();
+
+    for (number, raw) in lines.iter().enumerate() {
+        let line = strip_comment(raw).trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let indented = line.starts_with([' ', '\t']);
+        let item = line.trim_start().strip_prefix("- ").or_else(|| (line.trim() == "-").then_some(""));
+        if indented || item.is_some() {
+            let Some((_, value)) = entries.last_mut() else {
+                return Err(invalid(&format!("line {} is not part of a key", number + 2)));
+            };
+            *value = match (std::mem::replace(value, Value::Null), item) {
+                (Value::Null, Some(item)) => Value::List(vec![unquote(item).to_string()]),
+                (Value::List(mut items), Some(item)) => {
+                    items.push(unquote(item).to_string());
+                    Value::List(items)
+                }
+                _ => Value::Unsupported,
+            };
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(invalid(&format!("line {} must be a `key: value` pair", number + 2)));
+        };
+        entries.push((key.trim().to_string(), parse_inline(value)));
+    }
+    Ok(entries)
+}
+
+fn link(key: &str, value: Value) -> AppResult<Option<String>> {
+    match value {
+        Value::Null => Ok(None),
+        Value::Scalar(id) if !id.is_empty() => Ok(Some(id)),
+        _ => Err(invalid(&format!("`{}` must be an id", key))),
+    }
+}
+
+/// Parses the frontmatter of note content; `None` if it has none
+pub fn parse(content: &str) -> AppResult<Option<NoteFrontmatter>> {
+    let Some(lines) = block(content) else {
+        return Ok(None);
+    };
+
+    let mut frontmatter = NoteFrontmatter::default();
+    for (key, value) in entries(&lines)? {
+        match key.as_str() {
+            "tags" => {
+                let listed = match value {
+                    Value::Null => Vec::new(),
+                    Value::List(tags) => tags,
+                    // `tags: a, b` is a common shorthand
+                    Value::Scalar(tags) => tags.split(',').map(|t| t.trim().to_string()).collect(),
+                    Value::Unsupported => return Err(invalid("`tags` must be a list")),
+                };
+                let mut tags: Vec<String> = Vec::new();
+                for tag in listed.into_iter().filter(|t| !t.is_empty()) {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+                if tags.len() > MAX_NOTE_TAGS {
+                    return Err(invalid(&format!("`tags` must list at most {} tags", MAX_NOTE_TAGS)));
+                }
+                frontmatter.tags = Some(tags);
+            }
+            "task" => frontmatter.task_id = Some(link(&key, value)?),
+            "project" => frontmatter.project_id = Some(link(&key, value)?),
+            "goal" => frontmatter.goal_id = Some(link(&key, value)?),
+            "life_area" => frontmatter.life_area_id = Some(link(&key, value)?),
+            "date" => {
+                frontmatter.date = Some(match value {
+                    Value::Null => None,
+                    Value::Scalar(date) => Some(
+                        NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                            .map_err(|_| invalid("`date` must be a YYYY-MM-DD date"))?,
+                    ),
+                    _ => return Err(invalid("`date` must be a YYYY-MM-DD date")),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(Some(frontmatter))
+}
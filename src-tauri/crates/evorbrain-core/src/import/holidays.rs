@@ -0,0 +1,92 @@
+//! Holiday calendars in iCalendar format
+//!
+//! Public holiday calendars are commonly published as `.ics` files. Every
+//! `VEVENT` becomes a holiday named after its `SUMMARY`; all-day events
+//! spanning several days (an exclusive `DTEND` after the day after
+//! `DTSTART`) become one holiday per day. Recurrence rules are not
+//! expanded, since holiday feeds list each year's dates explicitly.
+
+use crate::db::models::HolidayInput;
+use crate::error::{AppError, AppResult, ErrorCode};
+use chrono::{Duration, NaiveDate};
+
+/// Most holidays accepted from one file
+pub const MAX_IMPORTED_HOLIDAYS: usize = 5000;
+/// Longest single event expanded into holidays
+const MAX_EVENT_DAYS: i64 = 31;
+
+/// Joins folded lines: a line starting with a space or tab continues the
+/// previous one
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=x:value` into the upper-cased name and the value
+fn property(line: &str) -> Option<(String, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let name = key.split(';').next().unwrap_or(key);
+    Some((name.to_ascii_uppercase(), value))
+}
+
+/// The date of a `DATE` or `DATE-TIME` value, e.g. `20240101` or `20240101T000000Z`
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+fn invalid(details: impl Into<String>) -> AppError {
+    AppError::new(ErrorCode::InvalidInput, "Invalid iCalendar file").with_details(details)
+}
+
+/// Parses the holidays of an iCalendar file
+pub fn parse_ics(content: &str) -> AppResult<Vec<HolidayInput>> {
+    let lines = unfold(content);
+    if !lines.iter().any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err(invalid("missing BEGIN:VCALENDAR"));
+    }
+
+    let mut holidays = Vec::new();
+    let mut event: Option<(Option<NaiveDate>, Option<NaiveDate>, Option<String>)> = None;
+
+    for line in &lines {
+        let Some((name, value)) = property(line.trim_end()) else { continue };
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => event = Some((None, None, None)),
+            ("DTSTART", Some(event)) => event.0 = parse_date(value),
+            ("DTEND", Some(event)) => event.1 = parse_date(value),
+            ("SUMMARY", Some(event)) => event.2 = Some(unescape(value.trim())),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let Some((start, end, summary)) = event.take() else { continue };
+                let start = start.ok_or_else(|| invalid("event without a valid DTSTART"))?;
+                let days = end.map_or(1, |end| (end - start).num_days().clamp(1, MAX_EVENT_DAYS));
+                let name = summary.filter(|s| !s.is_empty()).unwrap_or_else(|| "Holiday".to_string());
+
+                for day in 0..days {
+                    holidays.push(HolidayInput {
+                        date: start + Duration::days(day),
+                        name: name.clone(),
+                    });
+                }
+                if holidays.len() > MAX_IMPORTED_HOLIDAYS {
+                    return Err(invalid(format!("more than {} holidays", MAX_IMPORTED_HOLIDAYS)));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(holidays)
+}
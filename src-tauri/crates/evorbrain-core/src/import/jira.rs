@@ -0,0 +1,670 @@
+//! Jira CSV/JSON importer
+//!
+//! Epics become goals (each with a project of the same name holding the
+//! epic's issues), other issues become tasks, sub-tasks become subtasks, and
+//! Jira statuses map to open or completed tasks. EvorBrain has no time blocks
+//! yet, so sprints are imported as `sprint:<name>` tags.
+//!
+//! Both formats are first flattened into records keyed by column name (CSV)
+//! or dotted field path (JSON, e.g. `fields.status.name`), so a single
+//! `JiraFieldMapping` drives the import. `preview` lists the available
+//! fields and a suggested mapping for the configuration step, and `import`
+//! can run as a dry run that reports what would be created.
+
+use crate::db::models::TaskPriority;
+use crate::db::repository::Repository;
+use crate::db::write_queue::BulkProgress;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::validation::MAX_TITLE_LENGTH;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Sqlite, Transaction};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JiraFormat {
+    Csv,
+    Json,
+}
+
+/// Which column (CSV) or field path (JSON) holds each piece of issue data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JiraFieldMapping {
+    pub key: String,
+    /// Numeric issue ID; CSV exports reference parents by ID
+    pub id: String,
+    pub summary: String,
+    pub description: String,
+    pub issue_type: String,
+    pub status: String,
+    pub priority: String,
+    /// Epic link or parent issue, as a key or ID
+    pub parent: String,
+    pub due_date: String,
+    pub sprint: String,
+    pub labels: String,
+}
+
+impl JiraFieldMapping {
+    pub fn default_for(format: JiraFormat) -> Self {
+        let fields = match format {
+            JiraFormat::Csv => [
+                "Issue key", "Issue id", "Summary", "Description", "Issue Type", "Status",
+                "Priority", "Parent", "Due date", "Sprint", "Labels",
+            ],
+            JiraFormat::Json => [
+                "key", "id", "fields.summary", "fields.description", "fields.issuetype.name",
+                "fields.status.name", "fields.priority.name", "fields.parent.key",
+                "fields.duedate", "fields.customfield_10020.name", "fields.labels",
+            ],
+        };
+        let [key, id, summary, description, issue_type, status, priority, parent, due_date, sprint, labels] =
+            fields.map(String::from);
+
+        Self {
+            key,
+            id,
+            summary,
+            description,
+            issue_type,
+            status,
+            priority,
+            parent,
+            due_date,
+            sprint,
+            labels,
+        }
+    }
+}
+
+/// What a Jira status becomes in EvorBrain
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MappedStatus {
+    Open,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JiraImportOptions {
+    /// Life area the imported goals are created in
+    pub life_area_id: String,
+    /// Project for issues without an epic; they go to the inbox when unset
+    pub default_project_id: Option<String>,
+    /// Defaults to `JiraFieldMapping::default_for(format)`
+    pub mapping: Option<JiraFieldMapping>,
+    /// Jira status name → open/done; unlisted statuses use built-in defaults
+    #[serde(default)]
+    pub status_map: HashMap<String, MappedStatus>,
+    /// Jira priority name → task priority; unlisted names use built-in defaults
+    #[serde(default)]
+    pub priority_map: HashMap<String, TaskPriority>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Input for the field-mapping configuration step
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JiraPreview {
+    pub issue_count: usize,
+    /// Every column or field path found in the file
+    pub fields: Vec<String>,
+    pub suggested_mapping: JiraFieldMapping,
+    /// Distinct values found under the suggested mapping
+    pub issue_types: Vec<String>,
+    pub statuses: Vec<String>,
+    pub priorities: Vec<String>,
+    pub sprints: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JiraImportReport {
+    pub dry_run: bool,
+    pub goals_created: usize,
+    pub projects_created: usize,
+    pub tasks_created: usize,
+    /// Imported tasks (and epics) marked completed from their status
+    pub completed: usize,
+    pub tags_created: usize,
+    pub skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+/// One flattened issue: field name → values (multi-valued for repeated
+/// CSV columns and JSON arrays)
+#[derive(Debug, Default)]
+struct JiraRecord {
+    values: HashMap<String, Vec<String>>,
+}
+
+impl JiraRecord {
+    fn push(&mut self, field: &str, value: &str) {
+        let value = value.trim();
+        if !value.is_empty() {
+            self.values.entry(field.to_string()).or_default().push(value.to_string());
+        }
+    }
+
+    fn first(&self, field: &str) -> Option<&str> {
+        self.values.get(field).and_then(|v| v.first()).map(String::as_str)
+    }
+
+    fn all(&self, field: &str) -> &[String] {
+        self.values.get(field).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Text of a field, joining rich-text (Atlassian document) fragments
+    fn text(&self, field: &str) -> Option<String> {
+        if let Some(value) = self.first(field) {
+            return Some(value.to_string());
+        }
+
+        let prefix = format!("{}.", field);
+        let mut paths: Vec<&String> = self
+            .values
+            .keys()
+            .filter(|k| k.starts_with(&prefix) && k.ends_with(".text"))
+            .collect();
+        paths.sort();
+        let text = paths
+            .into_iter()
+            .flat_map(|p| self.values[p].iter().cloned())
+            .collect::<Vec<_>>()
+            .join("\n");
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+fn parse_records(content: &str, format: JiraFormat) -> AppResult<Vec<JiraRecord>> {
+    match format {
+        JiraFormat::Csv => parse_csv(content),
+        JiraFormat::Json => parse_json(content),
+    }
+}
+
+fn parse_csv(content: &str) -> AppResult<Vec<JiraRecord>> {
+    let invalid = |e: csv::Error| {
+        AppError::new(ErrorCode::InvalidInput, "Invalid Jira CSV").with_details(e.to_string())
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(invalid)?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row.map_err(invalid)?;
+        let mut record = JiraRecord::default();
+        for (header, value) in headers.iter().zip(row.iter()) {
+            record.push(header, value);
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn parse_json(content: &str) -> AppResult<Vec<JiraRecord>> {
+    let value: serde_json::Value = serde_json::from_str(content).map_err(|e| {
+        AppError::new(ErrorCode::InvalidInput, "Invalid Jira JSON").with_details(e.to_string())
+    })?;
+
+    // Accept a search API response ({"issues": [...]}) or a bare array
+    let issues = match &value {
+        serde_json::Value::Array(issues) => issues,
+        serde_json::Value::Object(map) => match map.get("issues") {
+            Some(serde_json::Value::Array(issues)) => issues,
+            _ => return Err(AppError::new(ErrorCode::InvalidInput, "Jira JSON has no 'issues' array")),
+        },
+        _ => return Err(AppError::new(ErrorCode::InvalidInput, "Jira JSON must be an object or array")),
+    };
+
+    Ok(issues
+        .iter()
+        .map(|issue| {
+            let mut record = JiraRecord::default();
+            flatten_json(&mut record, "", issue);
+            record
+        })
+        .collect())
+}
+
+fn flatten_json(record: &mut JiraRecord, path: &str, value: &serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                flatten_json(record, &child_path, child);
+            }
+        }
+        // Array elements share their parent's path
+        serde_json::Value::Array(items) => {
+            for item in items {
+                flatten_json(record, path, item);
+            }
+        }
+        serde_json::Value::String(s) => record.push(path, s),
+        serde_json::Value::Number(n) => record.push(path, &n.to_string()),
+        serde_json::Value::Bool(b) => record.push(path, &b.to_string()),
+        serde_json::Value::Null => {}
+    }
+}
+
+fn distinct(records: &[JiraRecord], field: &str) -> Vec<String> {
+    records
+        .iter()
+        .flat_map(|r| r.all(field).iter().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Parses a Jira export and describes it for the field-mapping step
+pub fn preview(content: &str, format: JiraFormat) -> AppResult<JiraPreview> {
+    let records = parse_records(content, format)?;
+    let mapping = JiraFieldMapping::default_for(format);
+
+    let fields = records
+        .iter()
+        .flat_map(|r| r.values.keys().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Ok(JiraPreview {
+        issue_count: records.len(),
+        fields,
+        issue_types: distinct(&records, &mapping.issue_type),
+        statuses: distinct(&records, &mapping.status),
+        priorities: distinct(&records, &mapping.priority),
+        sprints: distinct(&records, &mapping.sprint),
+        suggested_mapping: mapping,
+    })
+}
+
+fn default_status(status: &str) -> MappedStatus {
+    match status.to_lowercase().as_str() {
+        "done" | "closed" | "resolved" | "complete" | "completed" | "won't do" | "cancelled" => {
+            MappedStatus::Done
+        }
+        _ => MappedStatus::Open,
+    }
+}
+
+fn default_priority(priority: &str) -> TaskPriority {
+    match priority.to_lowercase().as_str() {
+        "highest" | "blocker" | "critical" => TaskPriority::Urgent,
+        "high" | "major" => TaskPriority::High,
+        "low" | "lowest" | "minor" | "trivial" => TaskPriority::Low,
+        _ => TaskPriority::Medium,
+    }
+}
+
+fn parse_jira_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    // Jira CSV exports use e.g. "12/Jan/24 9:30 AM"
+    for format in ["%d/%b/%y %I:%M %p", "%d/%b/%Y %I:%M %p"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(dt.and_utc());
+        }
+    }
+    for format in ["%Y-%m-%d", "%d/%b/%y", "%d/%b/%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+        }
+    }
+    None
+}
+
+fn truncate_title(title: &str) -> String {
+    title.chars().take(MAX_TITLE_LENGTH).collect()
+}
+
+struct Importer<'a> {
+    options: &'a JiraImportOptions,
+    mapping: JiraFieldMapping,
+    now: DateTime<Utc>,
+    report: JiraImportReport,
+    tag_ids: HashMap<String, String>,
+}
+
+impl Importer<'_> {
+    fn status_of(&self, record: &JiraRecord) -> MappedStatus {
+        match record.first(&self.mapping.status) {
+            Some(status) => self
+                .options
+                .status_map
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(status))
+                .map(|(_, mapped)| *mapped)
+                .unwrap_or_else(|| default_status(status)),
+            None => MappedStatus::Open,
+        }
+    }
+
+    fn priority_of(&self, record: &JiraRecord) -> TaskPriority {
+        match record.first(&self.mapping.priority) {
+            Some(priority) => self
+                .options
+                .priority_map
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(priority))
+                .map(|(_, mapped)| *mapped)
+                .unwrap_or_else(|| default_priority(priority)),
+            None => TaskPriority::default(),
+        }
+    }
+
+    fn label(&self, record: &JiraRecord) -> String {
+        record
+            .first(&self.mapping.key)
+            .or_else(|| record.first(&self.mapping.id))
+            .unwrap_or("(no key)")
+            .to_string()
+    }
+
+    fn references(&self, record: &JiraRecord) -> Vec<String> {
+        [record.first(&self.mapping.key), record.first(&self.mapping.id)]
+            .into_iter()
+            .flatten()
+            .map(String::from)
+            .collect()
+    }
+
+    async fn import_epic(
+        &mut self,
+        tx: &mut Transaction<'_, Sqlite>,
+        record: &JiraRecord,
+        title: &str,
+    ) -> AppResult<String> {
+        let goal_id = Uuid::new_v4().to_string();
+        let project_id = Uuid::new_v4().to_string();
+        let done = self.status_of(record) == MappedStatus::Done;
+        let completed_at = done.then_some(self.now);
+        let description = record.text(&self.mapping.description);
+
+        sqlx::query(
+            r#"
+            INSERT INTO goals (id, life_area_id, title, description, target_date, created_at, updated_at, completed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#
+        )
+        .bind(&goal_id)
+        .bind(&self.options.life_area_id)
+        .bind(title)
+        .bind(&description)
+        .bind(record.first(&self.mapping.due_date).and_then(parse_jira_date))
+        .bind(self.now)
+        .bind(self.now)
+        .bind(completed_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("import epic", e))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO projects (id, goal_id, title, description, status, created_at, updated_at, completed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#
+        )
+        .bind(&project_id)
+        .bind(&goal_id)
+        .bind(title)
+        .bind(&description)
+        .bind(if done { "completed" } else { "active" })
+        .bind(self.now)
+        .bind(self.now)
+        .bind(completed_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("import epic project", e))?;
+
+        self.report.goals_created += 1;
+        self.report.projects_created += 1;
+        if done {
+            self.report.completed += 1;
+        }
+        Ok(project_id)
+    }
+
+    async fn import_task(
+        &mut self,
+        tx: &mut Transaction<'_, Sqlite>,
+        record: &JiraRecord,
+        title: &str,
+        project_id: Option<&str>,
+        parent_task_id: Option<&str>,
+    ) -> AppResult<String> {
+        let task_id = Uuid::new_v4().to_string();
+        let done = self.status_of(record) == MappedStatus::Done;
+
+        let due_date = match record.first(&self.mapping.due_date) {
+            Some(raw) => {
+                let parsed = parse_jira_date(raw);
+                if parsed.is_none() {
+                    let warning = format!("{}: could not parse due date '{}'", self.label(record), raw);
+                    self.report.warnings.push(warning);
+                }
+                parsed
+            }
+            None => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, due_date,
+                               created_at, updated_at, completed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#
+        )
+        .bind(&task_id)
+        .bind(project_id)
+        .bind(parent_task_id)
+        .bind(title)
+        .bind(record.text(&self.mapping.description))
+        .bind(self.priority_of(record).to_string())
+        .bind(due_date)
+        .bind(self.now)
+        .bind(self.now)
+        .bind(done.then_some(self.now))
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::database_error("import issue", e))?;
+
+        self.report.tasks_created += 1;
+        if done {
+            self.report.completed += 1;
+        }
+
+        let tags: Vec<String> = record
+            .all(&self.mapping.sprint)
+            .iter()
+            .map(|sprint| format!("sprint:{}", sprint))
+            .chain(record.all(&self.mapping.labels).iter().cloned())
+            .collect();
+        for tag in tags {
+            let tag_id = self.tag_id(tx, &tag).await?;
+            sqlx::query("INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)")
+                .bind(&task_id)
+                .bind(&tag_id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| AppError::database_error("tag imported issue", e))?;
+        }
+
+        Ok(task_id)
+    }
+
+    /// Finds or creates a tag by name
+    async fn tag_id(&mut self, tx: &mut Transaction<'_, Sqlite>, name: &str) -> AppResult<String> {
+        if let Some(id) = self.tag_ids.get(name) {
+            return Ok(id.clone());
+        }
+
+        let existing: Option<String> = sqlx::query_scalar("SELECT id FROM tags WHERE name = ?1")
+            .bind(name)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| AppError::database_error("find tag", e))?;
+
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let id = Uuid::new_v4().to_string();
+                sqlx::query("INSERT INTO tags (id, name, created_at) VALUES (?1, ?2, ?3)")
+                    .bind(&id)
+                    .bind(name)
+                    .bind(self.now)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| AppError::database_error("create tag", e))?;
+                self.report.tags_created += 1;
+                id
+            }
+        };
+
+        self.tag_ids.insert(name.to_string(), id.clone());
+        Ok(id)
+    }
+}
+
+/// Imports a Jira export in a single transaction
+///
+/// With `dry_run` the transaction is rolled back, so the report describes
+/// exactly what a real import would create without changing anything.
+/// `progress` counts the records handled.
+pub async fn import(
+    repo: &Repository,
+    content: &str,
+    format: JiraFormat,
+    options: &JiraImportOptions,
+    progress: &BulkProgress,
+) -> AppResult<JiraImportReport> {
+    let records = parse_records(content, format)?;
+    progress.set_total(records.len());
+    let mut importer = Importer {
+        options,
+        mapping: options.mapping.clone().unwrap_or_else(|| JiraFieldMapping::default_for(format)),
+        now: Utc::now(),
+        report: JiraImportReport {
+            dry_run: options.dry_run,
+            ..Default::default()
+        },
+        tag_ids: HashMap::new(),
+    };
+
+    let area = repo.get_life_area(&options.life_area_id).await?;
+    if area.is_archived() {
+        return Err(AppError::validation_error("life_area_id", "life area is archived"));
+    }
+    let mut tx = repo.begin_transaction().await?;
+
+    if let Some(project_id) = &options.default_project_id {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND archived_at IS NULL)"
+        )
+        .bind(project_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error("check default project", e))?;
+        if !exists {
+            return Err(AppError::not_found("Project", project_id));
+        }
+    }
+
+    // Jira key/ID → (project_id, task_id) of what it was imported as
+    let mut imported: HashMap<String, (Option<String>, Option<String>)> = HashMap::new();
+    let mut pending = Vec::new();
+
+    for record in &records {
+        let Some(summary) = record.first(&importer.mapping.summary) else {
+            let warning = format!("{}: skipped, no summary", importer.label(record));
+            importer.report.warnings.push(warning);
+            importer.report.skipped += 1;
+            progress.advance(1);
+            continue;
+        };
+        let title = truncate_title(summary);
+
+        let is_epic = record
+            .first(&importer.mapping.issue_type)
+            .is_some_and(|t| t.eq_ignore_ascii_case("epic"));
+        if is_epic {
+            let project_id = importer.import_epic(&mut tx, record, &title).await?;
+            for reference in importer.references(record) {
+                imported.insert(reference, (Some(project_id.clone()), None));
+            }
+            progress.advance(1);
+        } else {
+            pending.push((record, title));
+        }
+    }
+
+    // Issues are imported once their parent (epic or parent issue) is, so
+    // sub-tasks end up under their parent task whatever the file order
+    while !pending.is_empty() {
+        let before = pending.len();
+        let mut waiting = Vec::new();
+
+        for (record, title) in pending {
+            let parent = record.first(&importer.mapping.parent);
+            let in_file = parent.is_some_and(|p| {
+                records.iter().any(|r| importer.references(r).iter().any(|k| k == p))
+            });
+
+            let (project_id, parent_task_id) = match parent.and_then(|p| imported.get(p)) {
+                Some((project_id, parent_task_id)) => (project_id.clone(), parent_task_id.clone()),
+                None if in_file => {
+                    waiting.push((record, title));
+                    continue;
+                }
+                None => (importer.options.default_project_id.clone(), None),
+            };
+
+            let task_id = importer
+                .import_task(&mut tx, record, &title, project_id.as_deref(), parent_task_id.as_deref())
+                .await?;
+            for reference in importer.references(record) {
+                imported.insert(reference, (project_id.clone(), Some(task_id.clone())));
+            }
+            progress.advance(1);
+        }
+
+        if waiting.len() == before {
+            // Parent cycle or a parent that was skipped: import at the top level
+            for (record, title) in waiting {
+                let warning = format!("{}: parent could not be resolved", importer.label(record));
+                importer.report.warnings.push(warning);
+                let project_id = importer.options.default_project_id.clone();
+                importer
+                    .import_task(&mut tx, record, &title, project_id.as_deref(), None)
+                    .await?;
+                progress.advance(1);
+            }
+            break;
+        }
+        pending = waiting;
+    }
+
+    if options.dry_run {
+        tx.rollback().await
+            .map_err(|e| AppError::database_error("roll back jira dry run", e))?;
+    } else {
+        tx.commit().await
+            .map_err(|e| AppError::database_error("commit jira import", e))?;
+    }
+
+    Ok(importer.report)
+}
@@ -0,0 +1,4 @@
+//! Importers for data exported from other tools
+
+pub mod holidays;
+pub mod jira;
@@ -0,0 +1,49 @@
+//! Core library for EvorBrain
+//!
+//! Contains everything that does not depend on the Tauri shell: the database
+//! layer (models, repository, migrations), error types, logging, settings,
+//! the event bus, importers, onboarding templates, calendar helpers, the
+//! locale of weekdays and numbers in generated text, achievements, automatic
+//! project and goal completion, project velocity, critical paths of dependent
+//! tasks, occurrences of repeating tasks, task aging, task urgency scores,
+//! due date suggestions, digests, focus time by goal and life area, workspace
+//! statistics over time, storage advice, conflicting edit detection, note
+//! frontmatter, note checklists, meeting notes, the tasks and projects notes
+//! mention, the graph of notes and their links, task description references,
+//! the quick switcher's title index, job schedules, the DTOs of the v2
+//! command API and input validation.
+//! The desktop app and the `evorbrain-cli` binary are both built on top of
+//! this crate.
+
+pub mod achievements;
+pub mod aging;
+pub mod auto_complete;
+pub mod calendar;
+pub mod checklists;
+pub mod conflicts;
+pub mod critical_path;
+pub mod db;
+pub mod digest;
+pub mod dto;
+pub mod due_dates;
+pub mod error;
+pub mod events;
+pub mod focus;
+pub mod frontmatter;
+pub mod import;
+pub mod locale;
+pub mod logger;
+pub mod meetings;
+pub mod mentions;
+pub mod note_graph;
+pub mod onboarding;
+pub mod recurrence;
+pub mod references;
+pub mod schedule;
+pub mod settings;
+pub mod stats_history;
+pub mod storage;
+pub mod switcher;
+pub mod urgency;
+pub mod validation;
+pub mod velocity;
@@ -0,0 +1,103 @@
+//! Language of dates and numbers in generated text
+//!
+//! Digests, day capacity warnings, due date suggestions and other text the
+//! backend writes name weekdays and print numbers the way the `locale`
+//! setting says, e.g. `Donnerstag`, `10.080` and `45 %` in German. The order
+//! of day, month and year stays with the `date_format` setting. The
+//! sentences around them are still English.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Language whose weekday names and number separators reports use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// English: Thursday, 1,250, 45%
+    #[default]
+    En,
+    /// German: Donnerstag, 1.250, 45 %
+    De,
+    /// French: jeudi, 1 250, 45 %
+    Fr,
+    /// Spanish: jueves, 1.250, 45 %
+    Es,
+    /// Italian: giovedì, 1.250, 45%
+    It,
+    /// Dutch: donderdag, 1.250, 45%
+    Nl,
+    /// Portuguese: quinta-feira, 1.250, 45%
+    Pt,
+}
+
+impl Locale {
+    /// Weekday names, Monday first
+    fn weekdays(self) -> [&'static str; 7] {
+        match self {
+            Locale::En => ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+            Locale::De => ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+            Locale::Fr => ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+            Locale::Es => ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+            Locale::It => ["lunedì", "martedì", "mercoledì", "giovedì", "venerdì", "sabato", "domenica"],
+            Locale::Nl => ["maandag", "dinsdag", "woensdag", "donderdag", "vrijdag", "zaterdag", "zondag"],
+            Locale::Pt => [
+                "segunda-feira",
+                "terça-feira",
+                "quarta-feira",
+                "quinta-feira",
+                "sexta-feira",
+                "sábado",
+                "domingo",
+            ],
+        }
+    }
+
+    /// Separator between groups of thousands
+    fn group_separator(self) -> &'static str {
+        match self {
+            Locale::En => ",",
+            // Narrow no-break space, so a number never wraps
+            Locale::Fr => "\u{202f}",
+            Locale::De | Locale::Es | Locale::It | Locale::Nl | Locale::Pt => ".",
+        }
+    }
+
+    /// What goes between a number and its `%`
+    fn percent_separator(self) -> &'static str {
+        match self {
+            Locale::En | Locale::It | Locale::Nl | Locale::Pt => "",
+            Locale::De | Locale::Es => "\u{a0}",
+            Locale::Fr => "\u{202f}",
+        }
+    }
+
+    pub fn weekday_name(self, weekday: Weekday) -> &'static str {
+        self.weekdays()[weekday.num_days_from_monday() as usize]
+    }
+
+    /// The weekday a date falls on, e.g. `Thursday`
+    pub fn format_weekday(self, date: NaiveDate) -> &'static str {
+        self.weekday_name(date.weekday())
+    }
+
+    /// A whole number with its thousands grouped, e.g. `10,080`
+    pub fn format_number(self, number: i64) -> String {
+        let digits = number.unsigned_abs().to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * 3 + 1);
+        if number < 0 {
+            grouped.push('-');
+        }
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(self.group_separator());
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    /// A percentage, e.g. `45%` or `45 %`
+    pub fn format_percent(self, percent: i64) -> String {
+        format!("{}{}%", self.format_number(percent), self.percent_separator())
+    }
+}
@@ -2,9 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs::{self, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -49,14 +48,9 @@ pub struct Logger {
 }
 
 impl Logger {
-    pub fn new(app_handle: &AppHandle) -> Result<Self, Box<dyn std::error::Error>> {
-        let log_dir = app_handle
-            .path()
-            .app_log_dir()
-            .expect("Failed to get app log directory");
-        
+    pub fn new(log_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         // Create logs directory if it doesn't exist
-        fs::create_dir_all(&log_dir)?;
+        fs::create_dir_all(log_dir)?;
         
         // Create log file with date in filename
         let log_filename = format!("evorbrain_{}.log", Utc::now().format("%Y-%m-%d"));
@@ -77,7 +71,7 @@ impl Logger {
     pub fn log(&self, level: LogLevel, message: impl AsRef<str>, context: Option<String>, error: Option<&dyn std::error::Error>) {
         // Check if we should log this level
         if let Ok(filter_level) = self.log_level.lock() {
-            if !level.should_log(&*filter_level) {
+            if !level.should_log(&filter_level) {
                 return;
             }
         }
@@ -189,30 +183,31 @@ impl Logger {
 }
 
 // Global logger instance
-pub static mut LOGGER: Option<Logger> = None;
+static LOGGER: OnceLock<Logger> = OnceLock::new();
 static LOGGER_INIT: std::sync::Once = std::sync::Once::new();
 
-pub fn init_logger(app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    unsafe {
-        LOGGER_INIT.call_once(|| {
-            match Logger::new(app_handle) {
-                Ok(logger) => {
-                    LOGGER = Some(logger);
-                }
-                Err(e) => {
-                    eprintln!("Failed to initialize logger: {}", e);
-                }
+pub fn init_logger(log_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    LOGGER_INIT.call_once(|| {
+        match Logger::new(log_dir) {
+            Ok(logger) => {
+                let _ = LOGGER.set(logger);
             }
-        });
-    }
+            Err(e) => {
+                eprintln!("Failed to initialize logger: {}", e);
+            }
+        }
+    });
     Ok(())
 }
 
+/// The global logger, once `init_logger` has set it up
+pub fn logger() -> Option<&'static Logger> {
+    LOGGER.get()
+}
+
 pub fn log(level: LogLevel, message: impl AsRef<str>, context: Option<String>, error: Option<&dyn std::error::Error>) {
-    unsafe {
-        if let Some(logger) = &LOGGER {
-            logger.log(level, message, context, error);
-        }
+    if let Some(logger) = logger() {
+        logger.log(level, message, context, error);
     }
 }
 
@@ -0,0 +1,122 @@
+//! Meeting notes
+//!
+//! A note tagged `meeting` holds decisions and action items. Decisions are
+//! the list items under a `Decisions` heading and lines starting with
+//! `Decision:`. Action items are open `- [ ]` items mentioning someone,
+//! `- [ ] @jane send the slides`; each becomes a task delegated to that
+//! person, and the item a link to it like any extracted checklist item.
+//! A mention matches a person by full name or first name, ignoring case,
+//! spaces and punctuation (`@jane`, `@JaneDoe` and `@jane.doe` all find
+//! Jane Doe), and only when exactly one person matches.
+
+use crate::checklists::ChecklistItem;
+use crate::db::models::Person;
+
+/// Tag that marks a note as meeting notes
+pub const MEETING_TAG: &str = "meeting";
+
+fn heading(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let text = trimmed.trim_start_matches('#');
+    (text.len() < trimmed.len() && (text.is_empty() || text.starts_with(' '))).then(|| text.trim())
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let text = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))?;
+    // Checklist items are action items, not decisions
+    let text = ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find_map(|checkbox| text.strip_prefix(checkbox))
+        .unwrap_or(text);
+    Some(text.trim())
+}
+
+/// The decisions recorded in a meeting note, in order
+pub fn decisions(markdown: &str) -> Vec<String> {
+    let mut decisions = Vec::new();
+    let mut in_section = false;
+
+    for line in markdown.lines() {
+        if let Some(title) = heading(line) {
+            in_section = title.to_lowercase().starts_with("decision");
+            continue;
+        }
+        let text = list_item(line).unwrap_or(line.trim());
+        let labelled = text
+            .get(..9)
+            .filter(|label| label.eq_ignore_ascii_case("decision:"))
+            .map(|_| text[9..].trim());
+
+        let decision = match (labelled, in_section) {
+            (Some(decision), _) => decision,
+            (None, true) => match list_item(line) {
+                Some(item) => item,
+                None => continue,
+            },
+            (None, false) => continue,
+        };
+        if !decision.is_empty() {
+            decisions.push(decision.to_string());
+        }
+    }
+    decisions
+}
+
+fn normalize(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// The `@mentions` in a line, without the `@`
+pub fn mentions(text: &str) -> Vec<&str> {
+    text.split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .map(|mention| mention.trim_end_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|mention| !mention.is_empty())
+        .collect()
+}
+
+/// The line without its `@mentions`, as the title of its task
+pub fn strip_mentions(text: &str) -> String {
+    text.split_whitespace()
+        .filter(|word| !word.starts_with('@') || word.len() == 1)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The one person a mention refers to
+pub fn find_person<'a>(mention: &str, people: &'a [Person]) -> Option<&'a Person> {
+    let mention = normalize(mention);
+    let mut matches = people.iter().filter(|person| {
+        normalize(&person.name) == mention
+            || person.name.split_whitespace().next().is_some_and(|first| normalize(first) == mention)
+    });
+    let person = matches.next()?;
+    matches.next().is_none().then_some(person)
+}
+
+/// An open item that names someone, with who it names
+#[derive(Debug, Clone)]
+pub struct ActionItem<'a> {
+    pub item: ChecklistItem,
+    /// `None` when no mention matches exactly one person
+    pub person: Option<&'a Person>,
+}
+
+/// The open checklist items that mention someone
+pub fn action_items(items: Vec<ChecklistItem>, people: &[Person]) -> Vec<ActionItem<'_>> {
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let mentioned = mentions(&item.title);
+            if mentioned.is_empty() {
+                return None;
+            }
+            let person = mentioned.iter().find_map(|mention| find_person(mention, people));
+            Some(ActionItem { item, person })
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+//! Tasks and projects mentioned in notes
+//!
+//! A note mentions a task or project when its content has the title as
+//! whole words, in any case, or the ID as `#<id>`. Whenever a note is saved
+//! the mentions in its content replace the ones recorded before, unless the
+//! `auto_link_mentions` setting is off, so related notes show up without
+//! being linked by hand.
+//!
+//! Titles shorter than `MIN_TITLE_CHARS` are left out, as are titles shared
+//! by more than one task or project, since they can't tell which one is
+//! meant. Where titles overlap, as `Launch` does inside `Launch website`,
+//! only the longer one counts. Renaming a task doesn't go through the notes
+//! again; `rescan` brings every note up to date. Private notes can't be
+//! read, so they don't mention anything.
+
+use crate::db::models::NoteMention;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+/// Shortest title matched in note content
+pub const MIN_TITLE_CHARS: usize = 4;
+
+/// Most mentions recorded for one note
+pub const MAX_MENTIONS: usize = 100;
+
+/// Length of an ID in a `#<id>` reference
+const ID_LENGTH: usize = 36;
+
+/// What `rescan` did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MentionRescan {
+    pub notes_scanned: usize,
+    /// Private notes, whose mentions were cleared
+    pub private_notes: usize,
+    pub mentions: usize,
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Finds the mentions of `candidates`, given as `(entity_type, id, title)`,
+/// in order of first appearance
+pub fn find_mentions(content: &str, candidates: &[(String, String, String)]) -> Vec<NoteMention> {
+    let text = content.to_lowercase();
+    // (start, end, entity_type, id, matched_by)
+    let mut found: Vec<(usize, usize, &str, &str, &str)> = Vec::new();
+
+    let ids: HashMap<String, &str> = candidates
+        .iter()
+        .map(|(entity_type, id, _)| (id.to_lowercase(), entity_type.as_str()))
+        .collect();
+    for (start, _) in text.match_indices('#') {
+        let Some(id) = text.get(start + 1..start + 1 + ID_LENGTH) else { continue };
+        let end = start + 1 + ID_LENGTH;
+        if is_word_char(text[end..].chars().next()) || text[end..].starts_with('-') {
+            continue;
+        }
+        if let Some((id, entity_type)) = ids.get_key_value(id) {
+            found.push((start, end, entity_type, id, "id"));
+        }
+    }
+
+    let mut titles: HashMap<String, Vec<(&str, &str)>> = HashMap::new();
+    for (entity_type, id, title) in candidates {
+        let title = title.trim().to_lowercase();
+        if title.chars().count() >= MIN_TITLE_CHARS {
+            titles.entry(title).or_default().push((entity_type, id));
+        }
+    }
+    let mut title_matches = Vec::new();
+    for (title, entities) in &titles {
+        let [(entity_type, id)] = entities.as_slice() else { continue };
+        for (start, _) in text.match_indices(title.as_str()) {
+            let end = start + title.len();
+            let starts_word = !is_word_char(title.chars().next()) || !is_word_char(text[..start].chars().next_back());
+            let ends_word = !is_word_char(title.chars().next_back()) || !is_word_char(text[end..].chars().next());
+            if starts_word && ends_word {
+                title_matches.push((start, end, *entity_type, *id, "title"));
+            }
+        }
+    }
+    // Longest first, so a title inside a longer one doesn't count
+    title_matches.sort_by_key(|&(start, end, ..)| (Reverse(end - start), start));
+    let mut taken: Vec<(usize, usize)> = Vec::new();
+    for found_match in title_matches {
+        let (start, end, ..) = found_match;
+        if !taken.iter().any(|&(taken_start, taken_end)| start < taken_end && taken_start < end) {
+            taken.push((start, end));
+            found.push(found_match);
+        }
+    }
+
+    found.sort_by_key(|&(start, ..)| start);
+    let mut seen = HashSet::new();
+    found
+        .into_iter()
+        .filter(|&(_, _, entity_type, id, _)| seen.insert((entity_type, id)))
+        .take(MAX_MENTIONS)
+        .map(|(_, _, entity_type, id, matched_by)| NoteMention {
+            entity_type: entity_type.to_string(),
+            entity_id: id.to_string(),
+            matched_by: matched_by.to_string(),
+        })
+        .collect()
+}
+
+/// Records the mentions in a note's current content and returns how many
+/// there are
+pub async fn link_note(repo: &Repository, note_id: &str) -> AppResult<usize> {
+    let note = repo.get_note(note_id).await?;
+    let mentions = if note.is_private {
+        Vec::new()
+    } else {
+        find_mentions(&note.content, &repo.get_mention_candidates().await?)
+    };
+    repo.set_note_mentions(note_id, &mentions).await?;
+    Ok(mentions.len())
+}
+
+/// Records the mentions of every note again, whether or not
+/// `auto_link_mentions` is on
+pub async fn rescan(repo: &Repository) -> AppResult<MentionRescan> {
+    let candidates = repo.get_mention_candidates().await?;
+    let mut rescan = MentionRescan::default();
+
+    for (note_id, content, is_private) in repo.get_note_contents().await? {
+        let mentions = if is_private {
+            rescan.private_notes += 1;
+            Vec::new()
+        } else {
+            find_mentions(&content, &candidates)
+        };
+        repo.set_note_mentions(&note_id, &mentions).await?;
+        rescan.notes_scanned += 1;
+        rescan.mentions += mentions.len();
+    }
+    Ok(rescan)
+}
@@ -0,0 +1,244 @@
+//! Graph of notes and what they link to
+//!
+//! Every note that isn't archived is a node, connected to the task, project,
+//! goal or life area it is attached to, to the entities its content links to
+//! with `evorbrain://` references or mentions and to its tags. The entities
+//! reached that way come with the projects, goals and life areas above them,
+//! so the graph shows where notes sit in the hierarchy; entities no note
+//! leads to are left out. Links of private notes can't be read, so those
+//! notes are only connected through what they are attached to and their tags.
+//!
+//! The graph can be used as is, or written as GraphML for tools like Gephi.
+
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::references::{self, REFERENCE_TYPES};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+/// Formats the graph can be written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// The `NoteGraph` itself as JSON
+    #[default]
+    Json,
+    GraphMl,
+}
+
+/// How two nodes are connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphEdgeKind {
+    /// A note is attached to the entity
+    Attached,
+    /// A note's content links to the entity
+    Links,
+    /// A note mentions the task or project by title or `#<id>`
+    Mentions,
+    /// A note has the tag
+    Tagged,
+    /// An entity sits under another, e.g. a project under its goal
+    Parent,
+}
+
+impl GraphEdgeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GraphEdgeKind::Attached => "attached",
+            GraphEdgeKind::Links => "links",
+            GraphEdgeKind::Mentions => "mentions",
+            GraphEdgeKind::Tagged => "tagged",
+            GraphEdgeKind::Parent => "parent",
+        }
+    }
+}
+
+/// A note, entity or tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    /// `note`, `task`, `project`, `goal`, `life_area` or `tag`
+    pub node_type: String,
+    /// Title, or name for life areas and tags
+    pub label: String,
+}
+
+/// A directed connection, from the note or the entity below
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: GraphEdgeKind,
+}
+
+/// Everything `build_note_graph` returns
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteGraph {
+    /// Notes first, then the entities and tags in the order they were reached
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl NoteGraph {
+    fn add_node(&mut self, seen: &mut HashSet<String>, id: &str, node_type: &str, label: &str) {
+        if seen.insert(id.to_string()) {
+            self.nodes.push(GraphNode {
+                id: id.to_string(),
+                node_type: node_type.to_string(),
+                label: label.to_string(),
+            });
+        }
+    }
+
+    /// The graph as a GraphML document
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <graph id=\"notes\" edgedefault=\"directed\">\n",
+        ));
+        for node in &self.nodes {
+            let _ = writeln!(
+                xml,
+                "    <node id=\"{}\"><data key=\"type\">{}</data><data key=\"label\">{}</data></node>",
+                escape_xml(&node.id),
+                escape_xml(&node.node_type),
+                escape_xml(&node.label)
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"kind\">{}</data></edge>",
+                escape_xml(&edge.source),
+                escape_xml(&edge.target),
+                edge.kind.as_str()
+            );
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters aren't allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds the graph of all notes that aren't archived or hidden by the
+/// active context
+pub async fn build_note_graph(repo: &Repository) -> AppResult<NoteGraph> {
+    let notes = repo.get_notes().await?;
+    let titles: HashMap<(String, String), String> = repo
+        .get_switcher_entries()
+        .await?
+        .into_iter()
+        .map(|entry| ((entry.entity_type, entry.id), entry.title))
+        .collect();
+    let parents: HashMap<(String, String), (String, String)> = repo
+        .get_entity_parents()
+        .await?
+        .into_iter()
+        .map(|(entity_type, id, parent_type, parent_id)| ((entity_type, id), (parent_type, parent_id)))
+        .collect();
+    let mut mentions: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    for (note_id, entity_type, id) in repo.get_note_mention_links().await? {
+        mentions.entry(note_id).or_default().push((entity_type, id));
+    }
+
+    let mut graph = NoteGraph::default();
+    let mut seen = HashSet::new();
+    let mut edges = HashSet::new();
+    let mut reached = Vec::new();
+    let mut connect = |graph: &mut NoteGraph, source: &str, target: &str, kind: GraphEdgeKind| {
+        if source != target && edges.insert((source.to_string(), target.to_string(), kind)) {
+            graph.edges.push(GraphEdge {
+                source: source.to_string(),
+                target: target.to_string(),
+                kind,
+            });
+        }
+    };
+
+    for note in &notes {
+        graph.add_node(&mut seen, &note.id, "note", &note.title);
+    }
+    for note in &notes {
+        let attached = [
+            ("task", &note.task_id),
+            ("project", &note.project_id),
+            ("goal", &note.goal_id),
+            ("life_area", &note.life_area_id),
+        ];
+        let mut targets: Vec<(String, String, GraphEdgeKind)> = attached
+            .into_iter()
+            .filter_map(|(entity_type, id)| Some((entity_type.to_string(), id.clone()?, GraphEdgeKind::Attached)))
+            .collect();
+        if !note.is_private {
+            targets.extend(
+                references::extract(&note.content)
+                    .entities
+                    .into_iter()
+                    .filter(|entity| REFERENCE_TYPES.contains(&entity.entity_type.as_str()))
+                    .map(|entity| (entity.entity_type, entity.id, GraphEdgeKind::Links)),
+            );
+        }
+        if let Some(mentioned) = mentions.remove(&note.id) {
+            targets.extend(
+                mentioned
+                    .into_iter()
+                    .map(|(entity_type, id)| (entity_type, id, GraphEdgeKind::Mentions)),
+            );
+        }
+
+        for (entity_type, id, kind) in targets {
+            let Some(title) = titles.get(&(entity_type.clone(), id.clone())) else {
+                // Archived or deleted since the link was written
+                continue;
+            };
+            graph.add_node(&mut seen, &id, &entity_type, title);
+            connect(&mut graph, &note.id, &id, kind);
+            reached.push((entity_type, id));
+        }
+    }
+
+    let visible: HashSet<&str> = notes.iter().map(|note| note.id.as_str()).collect();
+    for (note_id, tag_id, name) in repo.get_note_tag_links().await? {
+        if visible.contains(note_id.as_str()) {
+            graph.add_node(&mut seen, &tag_id, "tag", &name);
+            connect(&mut graph, &note_id, &tag_id, GraphEdgeKind::Tagged);
+        }
+    }
+
+    // Climb from every entity reached to its life area
+    let mut climbed = HashSet::new();
+    while let Some(key) = reached.pop() {
+        if !climbed.insert(key.clone()) {
+            continue;
+        }
+        let Some((parent_type, parent_id)) = parents.get(&key) else { continue };
+        let Some(title) = titles.get(&(parent_type.clone(), parent_id.clone())) else { continue };
+        graph.add_node(&mut seen, parent_id, parent_type, title);
+        connect(&mut graph, &key.1, parent_id, GraphEdgeKind::Parent);
+        reached.push((parent_type.clone(), parent_id.clone()));
+    }
+
+    Ok(graph)
+}
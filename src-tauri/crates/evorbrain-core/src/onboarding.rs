@@ -0,0 +1,251 @@
+//! First-run starter templates
+//!
+//! Seeds a fresh workspace with common life areas, example goals and a
+//! getting-started project. Applying a preset is idempotent: items are
+//! matched by name within their parent and only missing ones are created,
+//! so running it twice (or after the user already created "Health") doesn't
+//! produce duplicates. Every preset, including `skip`, marks onboarding as
+//! completed so the first-run screen isn't shown again.
+
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::settings;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map};
+use sqlx::{Sqlite, Transaction};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StarterPreset {
+    /// Life areas with an example goal each, plus the getting-started project
+    Standard,
+    /// Life areas and the getting-started project only
+    Minimal,
+    /// Seed nothing, just finish onboarding
+    Skip,
+}
+
+/// Counts of the items a preset created; existing items are not counted
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StarterTemplateReport {
+    pub life_areas_created: usize,
+    pub goals_created: usize,
+    pub projects_created: usize,
+    pub tasks_created: usize,
+}
+
+struct AreaTemplate {
+    name: &'static str,
+    description: &'static str,
+    color: &'static str,
+    icon: &'static str,
+    example_goals: &'static [&'static str],
+}
+
+const AREAS: &[AreaTemplate] = &[
+    AreaTemplate {
+        name: "Health",
+        description: "Physical and mental well-being",
+        color: "#22c55e",
+        icon: "💪",
+        example_goals: &["Exercise three times a week", "Sleep at least seven hours a night"],
+    },
+    AreaTemplate {
+        name: "Career",
+        description: "Work, skills and professional growth",
+        color: "#3b82f6",
+        icon: "💼",
+        example_goals: &["Learn a new skill for work"],
+    },
+    AreaTemplate {
+        name: "Finance",
+        description: "Budgeting, saving and investing",
+        color: "#eab308",
+        icon: "💰",
+        example_goals: &["Build an emergency fund"],
+    },
+    AreaTemplate {
+        name: "Relationships",
+        description: "Family, friends and community",
+        color: "#ec4899",
+        icon: "❤️",
+        example_goals: &["Call a friend or family member every week"],
+    },
+];
+
+/// Area that holds the getting-started project
+const GETTING_STARTED_AREA: AreaTemplate = AreaTemplate {
+    name: "Personal Growth",
+    description: "Learning, habits and reflection",
+    color: "#8b5cf6",
+    icon: "🌱",
+    example_goals: &[],
+};
+const GETTING_STARTED_GOAL: &str = "Get organized with EvorBrain";
+const GETTING_STARTED_PROJECT: &str = "Getting started";
+const GETTING_STARTED_TASKS: &[&str] = &[
+    "Review your life areas and rename or archive the ones you don't need",
+    "Add a goal you want to reach this year",
+    "Capture an idea with quick add",
+    "Create a task with a due date for today",
+    "Write a note on one of your goals",
+];
+
+/// Seeds the workspace with `preset` and marks onboarding as completed
+pub async fn create_starter_template(repo: &Repository, preset: StarterPreset) -> AppResult<StarterTemplateReport> {
+    let mut report = StarterTemplateReport::default();
+
+    if preset != StarterPreset::Skip {
+        let mut seeder = Seeder {
+            tx: repo.begin_transaction().await?,
+            report: StarterTemplateReport::default(),
+        };
+
+        for area in AREAS {
+            let area_id = seeder.life_area(area).await?;
+            if preset == StarterPreset::Standard {
+                for goal in area.example_goals {
+                    seeder.goal(&area_id, goal).await?;
+                }
+            }
+        }
+
+        let area_id = seeder.life_area(&GETTING_STARTED_AREA).await?;
+        let goal_id = seeder.goal(&area_id, GETTING_STARTED_GOAL).await?;
+        let project_id = seeder.project(&goal_id, GETTING_STARTED_PROJECT).await?;
+        for task in GETTING_STARTED_TASKS {
+            seeder.task(&project_id, task).await?;
+        }
+
+        seeder.tx.commit().await
+            .map_err(|e| AppError::database_error("commit starter template", e))?;
+        report = seeder.report;
+    }
+
+    let mut changes = Map::new();
+    changes.insert("onboarding_completed".to_string(), json!(true));
+    settings::update(repo, changes).await?;
+
+    Ok(report)
+}
+
+struct Seeder<'r> {
+    tx: Transaction<'r, Sqlite>,
+    report: StarterTemplateReport,
+}
+
+impl Seeder<'_> {
+    /// Finds an unarchived row by name within its parent
+    async fn existing(&mut self, sql: &str, parent_id: &str, name: &str) -> AppResult<Option<String>> {
+        sqlx::query_scalar(sql)
+            .bind(name)
+            .bind(parent_id)
+            .fetch_optional(&mut *self.tx)
+            .await
+            .map_err(|e| AppError::database_error("find starter item", e))
+    }
+
+    async fn life_area(&mut self, area: &AreaTemplate) -> AppResult<String> {
+        let existing: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM life_areas WHERE name = ?1 COLLATE NOCASE AND archived_at IS NULL"
+        )
+        .bind(area.name)
+        .fetch_optional(&mut *self.tx)
+        .await
+        .map_err(|e| AppError::database_error("find starter item", e))?;
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO life_areas (id, name, description, color, icon, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+        )
+        .bind(&id)
+        .bind(area.name)
+        .bind(area.description)
+        .bind(area.color)
+        .bind(area.icon)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *self.tx)
+        .await
+        .map_err(|e| AppError::database_error("create starter life area", e))?;
+
+        self.report.life_areas_created += 1;
+        Ok(id)
+    }
+
+    async fn goal(&mut self, life_area_id: &str, title: &str) -> AppResult<String> {
+        let sql = "SELECT id FROM goals WHERE title = ?1 COLLATE NOCASE AND life_area_id = ?2 AND archived_at IS NULL";
+        if let Some(id) = self.existing(sql, life_area_id, title).await? {
+            return Ok(id);
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        sqlx::query("INSERT INTO goals (id, life_area_id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)")
+            .bind(&id)
+            .bind(life_area_id)
+            .bind(title)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *self.tx)
+            .await
+            .map_err(|e| AppError::database_error("create starter goal", e))?;
+
+        self.report.goals_created += 1;
+        Ok(id)
+    }
+
+    async fn project(&mut self, goal_id: &str, title: &str) -> AppResult<String> {
+        let sql = "SELECT id FROM projects WHERE title = ?1 COLLATE NOCASE AND goal_id = ?2 AND archived_at IS NULL";
+        if let Some(id) = self.existing(sql, goal_id, title).await? {
+            return Ok(id);
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO projects (id, goal_id, title, status, created_at, updated_at) VALUES (?1, ?2, ?3, 'active', ?4, ?5)"
+        )
+        .bind(&id)
+        .bind(goal_id)
+        .bind(title)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *self.tx)
+        .await
+        .map_err(|e| AppError::database_error("create starter project", e))?;
+
+        self.report.projects_created += 1;
+        Ok(id)
+    }
+
+    async fn task(&mut self, project_id: &str, title: &str) -> AppResult<String> {
+        let sql = "SELECT id FROM tasks WHERE title = ?1 COLLATE NOCASE AND project_id = ?2 AND archived_at IS NULL";
+        if let Some(id) = self.existing(sql, project_id, title).await? {
+            return Ok(id);
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, title, priority, created_at, updated_at) VALUES (?1, ?2, ?3, 'medium', ?4, ?5)"
+        )
+        .bind(&id)
+        .bind(project_id)
+        .bind(title)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *self.tx)
+        .await
+        .map_err(|e| AppError::database_error("create starter task", e))?;
+
+        self.report.tasks_created += 1;
+        Ok(id)
+    }
+}
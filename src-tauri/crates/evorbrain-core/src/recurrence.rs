@@ -0,0 +1,364 @@
+//! Occurrences of repeating tasks
+//!
+//! A task repeats by its `recurrence_rule`, an iCalendar RRULE as
+//! `validation::normalize_recurrence_rule` accepts it. The series starts on
+//! the day the task is due, or today for a task without a due date, and
+//! that day is always its first occurrence. Exception dates are skipped but,
+//! as with EXDATE, still count towards COUNT.
+//!
+//! Only the open instance of a series exists as a task. Completing it with
+//! `complete_recurring_task` creates the next one, due on the next
+//! occurrence at the same time of day, and hands the rule and the remaining
+//! exception dates over to it. Occurrences that already passed are skipped,
+//! so completing a task late doesn't leave a trail of overdue copies.
+//!
+//! The rule is expanded with the usual RFC 5545 meaning of its parts, with
+//! weeks starting on Monday. BYDAY ordinals such as `-1FR` count within the
+//! month, or within the year for yearly rules without BYMONTH, and are
+//! ignored for daily and weekly rules.
+
+use crate::calendar::Calendar;
+use crate::db::models::{Task, TaskOccurrence};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::validation::normalize_recurrence_rule;
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Occurrences returned when no limit is given
+pub const DEFAULT_OCCURRENCE_LIMIT: u32 = 10;
+/// Most occurrences returned at once
+pub const MAX_OCCURRENCE_LIMIT: u32 = 100;
+
+/// Years after the start of a series that are looked through; a rule that
+/// matches nothing, like February 30th, ends there
+const MAX_SERIES_YEARS: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A parsed recurrence rule
+#[derive(Debug, Clone)]
+pub struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDate>,
+    /// Weekdays, with the ordinal of `2MO` or `-1FR`
+    by_day: Vec<(Option<i64>, Weekday)>,
+    by_month_day: Vec<i64>,
+    by_month: Vec<u32>,
+}
+
+fn numbers<T: std::str::FromStr>(value: &str) -> Vec<T> {
+    value.split(',').filter_map(|n| n.parse().ok()).collect()
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).unwrap_or(date);
+    first
+        .checked_add_months(Months::new(1))
+        .map_or(31, |next| (next - first).num_days() as u32)
+}
+
+fn days_in_year(date: NaiveDate) -> u32 {
+    if date.leap_year() {
+        366
+    } else {
+        365
+    }
+}
+
+/// Every day from `first` up to, not including, `end`
+fn days_between(first: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    first.iter_days().take_while(|day| *day < end).collect()
+}
+
+/// Replaces the COUNT of a normalized rule
+pub fn with_count(rule: &str, count: u32) -> String {
+    rule.split(';')
+        .map(|part| {
+            if part.starts_with("COUNT=") {
+                format!("COUNT={}", count)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+impl Recurrence {
+    /// Parses a rule, normalizing it first
+    pub fn parse(rule: &str) -> AppResult<Self> {
+        let rule = normalize_recurrence_rule(rule)?;
+        let mut recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+        };
+
+        for (key, value) in rule.split(';').filter_map(|part| part.split_once('=')) {
+            match key {
+                "FREQ" => {
+                    recurrence.frequency = match value {
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => Frequency::Daily,
+                    }
+                }
+                "INTERVAL" => recurrence.interval = value.parse().unwrap_or(1),
+                "COUNT" => recurrence.count = value.parse().ok(),
+                // The date part of UNTIL=20261231T235959Z as well
+                "UNTIL" => recurrence.until = NaiveDate::parse_from_str(value.get(..8).unwrap_or(value), "%Y%m%d").ok(),
+                "BYDAY" => {
+                    recurrence.by_day = value
+                        .split(',')
+                        .filter_map(|day| {
+                            let (ordinal, code) = day.split_at(day.len().saturating_sub(2));
+                            Some((ordinal.parse().ok(), weekday(code)?))
+                        })
+                        .collect()
+                }
+                "BYMONTHDAY" => recurrence.by_month_day = numbers(value),
+                "BYMONTH" => recurrence.by_month = numbers(value),
+                _ => {}
+            }
+        }
+        Ok(recurrence)
+    }
+
+    /// Most occurrences in the series, counting the first
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// The days of the `index`th period of a series starting on `start`, or
+    /// `None` once past `MAX_SERIES_YEARS`
+    fn period(&self, start: NaiveDate, index: u32) -> Option<Vec<NaiveDate>> {
+        let steps = index.checked_mul(self.interval)?;
+        let days = match self.frequency {
+            Frequency::Daily => vec![start.checked_add_signed(Duration::days(i64::from(steps)))?],
+            Frequency::Weekly => {
+                let monday = start - Duration::days(i64::from(start.weekday().num_days_from_monday()));
+                let first = monday.checked_add_signed(Duration::weeks(i64::from(steps)))?;
+                days_between(first, first + Duration::days(7))
+            }
+            Frequency::Monthly => {
+                let first = start.with_day(1)?.checked_add_months(Months::new(steps))?;
+                days_between(first, first.checked_add_months(Months::new(1))?)
+            }
+            Frequency::Yearly => {
+                let first = NaiveDate::from_ymd_opt(start.year().checked_add(i32::try_from(steps).ok()?)?, 1, 1)?;
+                days_between(first, first.checked_add_months(Months::new(12))?)
+            }
+        };
+        if days.first()?.year() > start.year() + MAX_SERIES_YEARS {
+            return None;
+        }
+        Some(self.select(start, days))
+    }
+
+    /// The days of a period the rule picks
+    fn select(&self, start: NaiveDate, mut days: Vec<NaiveDate>) -> Vec<NaiveDate> {
+        if !self.by_month.is_empty() {
+            days.retain(|day| self.by_month.contains(&day.month()));
+        }
+        let on_month_day = |day: &NaiveDate| {
+            self.by_month_day.iter().any(|&n| {
+                let from_end = i64::from(days_in_month(*day)) + n + 1;
+                i64::from(day.day()) == if n > 0 { n } else { from_end }
+            })
+        };
+
+        if !self.by_day.is_empty() {
+            let within_year = self.frequency == Frequency::Yearly && self.by_month.is_empty();
+            let ordinals = matches!(self.frequency, Frequency::Monthly | Frequency::Yearly);
+            days.retain(|day| {
+                let (position, length) = if within_year {
+                    (day.ordinal(), days_in_year(*day))
+                } else {
+                    (day.day(), days_in_month(*day))
+                };
+                let nth = i64::from((position - 1) / 7 + 1);
+                let nth_from_end = -i64::from((length - position) / 7 + 1);
+                self.by_day.iter().any(|&(ordinal, weekday)| {
+                    day.weekday() == weekday
+                        && match ordinal {
+                            Some(n) if ordinals => n == nth || n == nth_from_end,
+                            _ => true,
+                        }
+                })
+            });
+            if !self.by_month_day.is_empty() {
+                days.retain(on_month_day);
+            }
+        } else if !self.by_month_day.is_empty() {
+            days.retain(on_month_day);
+        } else {
+            match self.frequency {
+                Frequency::Daily => {}
+                Frequency::Weekly => days.retain(|day| day.weekday() == start.weekday()),
+                Frequency::Monthly => days.retain(|day| day.day() == start.day()),
+                Frequency::Yearly => days.retain(|day| {
+                    day.day() == start.day() && (!self.by_month.is_empty() || day.month() == start.month())
+                }),
+            }
+        }
+        days
+    }
+
+    /// The occurrences of a series starting on `start`, `start` first
+    pub fn occurrences(&self, start: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        let until = self.until;
+        std::iter::once(start)
+            .chain(
+                (0..)
+                    .map_while(move |index| self.period(start, index))
+                    .flatten()
+                    .filter(move |day| *day > start),
+            )
+            .take_while(move |day| until.is_none_or(|until| *day <= until))
+            .take(self.count.map_or(usize::MAX, |count| count as usize))
+    }
+}
+
+/// The coming occurrences of a repeating task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingOccurrences {
+    pub task_id: String,
+    pub recurrence_rule: String,
+    /// Dates from today on, the task's own due date first if it is still
+    /// ahead; exception dates are left out
+    pub occurrences: Vec<NaiveDate>,
+    pub exceptions: Vec<NaiveDate>,
+}
+
+/// Most exception dates one task can have
+pub const MAX_EXCEPTIONS: usize = 1000;
+
+/// What `complete_recurring_task` did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringCompletion {
+    pub completed: Task,
+    /// The next instance; `None` when the series ended or the task doesn't
+    /// repeat
+    pub next: Option<Task>,
+}
+
+/// The day a task's series starts on
+fn series_start(calendar: &Calendar, task: &Task) -> NaiveDate {
+    task.due_date.map_or_else(|| calendar.today(), |due| calendar.local_date(due))
+}
+
+/// Lists up to `limit` coming occurrences of a repeating task
+pub async fn get_upcoming_occurrences(repo: &Repository, task_id: &str, limit: u32) -> AppResult<UpcomingOccurrences> {
+    let task = repo.get_task(task_id).await?;
+    let Some(rule) = task.recurrence_rule.clone() else {
+        return Err(AppError::validation_error("id", "the task doesn't repeat"));
+    };
+    let recurrence = Recurrence::parse(&rule)?;
+    let exceptions = repo.get_recurrence_exceptions(task_id).await?;
+    let calendar = Calendar::load(repo).await?;
+    let today = calendar.today();
+
+    let occurrences = recurrence
+        .occurrences(series_start(&calendar, &task))
+        .filter(|day| *day >= today && !exceptions.contains(day))
+        .take(limit as usize)
+        .collect();
+    Ok(UpcomingOccurrences {
+        task_id: task.id,
+        recurrence_rule: rule,
+        occurrences,
+        exceptions,
+    })
+}
+
+/// Completes a task and, if it repeats, creates its next instance
+///
+/// Tasks that don't repeat are only completed.
+pub async fn complete_recurring_task(repo: &Repository, task_id: &str) -> AppResult<RecurringCompletion> {
+    let task = repo.get_task(task_id).await?;
+    if task.completed_at.is_some() {
+        return Err(AppError::new(ErrorCode::CannotUpdate, "The task is already completed"));
+    }
+
+    let mut next = None;
+    if let Some(rule) = &task.recurrence_rule {
+        let recurrence = Recurrence::parse(rule)?;
+        let exceptions = repo.get_recurrence_exceptions(task_id).await?;
+        let calendar = Calendar::load(repo).await?;
+        let start = series_start(&calendar, &task);
+        let today = calendar.today();
+
+        // The position in the series tells how much of COUNT is left
+        let found = recurrence
+            .occurrences(start)
+            .enumerate()
+            .skip(1)
+            .find(|(_, day)| *day >= today && !exceptions.contains(day));
+        if let Some((position, day)) = found {
+            let rule = match recurrence.count() {
+                Some(count) => with_count(rule, count - position as u32),
+                None => rule.clone(),
+            };
+            let due_date = match task.due_date {
+                Some(due) => calendar.with_date(due, day),
+                None => calendar.day(day).start,
+            };
+            next = Some(TaskOccurrence {
+                date: day,
+                due_date,
+                recurrence_rule: rule,
+            });
+        }
+    }
+
+    let next = repo.complete_and_repeat_task(task_id, next).await?;
+    Ok(RecurringCompletion {
+        completed: repo.get_task(task_id).await?,
+        next,
+    })
+}
+
+/// Replaces the dates a repeating task skips
+pub async fn set_exceptions(repo: &Repository, task_id: &str, mut dates: Vec<NaiveDate>) -> AppResult<Vec<NaiveDate>> {
+    let task = repo.get_task(task_id).await?;
+    if task.recurrence_rule.is_none() {
+        return Err(AppError::validation_error("task_id", "the task doesn't repeat"));
+    }
+    dates.sort_unstable();
+    dates.dedup();
+    if dates.len() > MAX_EXCEPTIONS {
+        return Err(AppError::validation_error(
+            "dates",
+            &format!("must list at most {} dates", MAX_EXCEPTIONS),
+        ));
+    }
+    repo.set_recurrence_exceptions(task_id, &dates).await?;
+    Ok(dates)
+}
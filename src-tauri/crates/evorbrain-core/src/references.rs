@@ -0,0 +1,112 @@
+//! URLs and entity references in task descriptions
+//!
+//! When a task is saved, its description is scanned for `http(s)://` URLs
+//! and `evorbrain://task/<id>` style references (also `project`, `goal` and
+//! `note`), bare or as the target of a Markdown link. URLs are kept as
+//! external links of the task and references in the `task_references`
+//! table, so related material shows up without being linked by hand.
+
+use crate::validation::validate_id;
+use url::Url;
+
+/// Entity types an `evorbrain://` reference can point to
+pub const REFERENCE_TYPES: &[&str] = &["task", "project", "goal", "note"];
+
+/// Most URLs and references taken from one description
+pub const MAX_REFERENCES: usize = 50;
+
+/// Longest URL kept; longer ones are skipped
+const MAX_URL_LENGTH: usize = 2048;
+
+const SCHEMES: &[&str] = &["https://", "http://", "evorbrain://"];
+
+/// An `evorbrain://` reference to another entity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityReference {
+    pub entity_type: String,
+    pub id: String,
+}
+
+/// What `extract` found, each in order of first appearance without duplicates
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct References {
+    pub urls: Vec<String>,
+    pub entities: Vec<EntityReference>,
+}
+
+/// Finds the URLs and entity references in Markdown text
+pub fn extract(text: &str) -> References {
+    let mut references = References::default();
+    let mut rest = text;
+
+    while let Some((start, scheme)) = SCHEMES
+        .iter()
+        .filter_map(|scheme| find_ignore_case(rest, scheme).map(|start| (start, *scheme)))
+        .min_by_key(|(start, _)| *start)
+    {
+        let candidate = url_at(&rest[start..]);
+        rest = &rest[start + candidate.len().max(scheme.len())..];
+
+        if references.urls.len() + references.entities.len() >= MAX_REFERENCES {
+            break;
+        }
+        if candidate.len() <= scheme.len() || candidate.len() > MAX_URL_LENGTH {
+            continue;
+        }
+        let Ok(url) = Url::parse(candidate) else { continue };
+
+        if url.scheme() == "evorbrain" {
+            let entity_type = url.host_str().unwrap_or_default();
+            let id = url.path().trim_matches('/');
+            if !REFERENCE_TYPES.contains(&entity_type) || validate_id(id).is_err() {
+                continue;
+            }
+            let reference = EntityReference {
+                entity_type: entity_type.to_string(),
+                id: id.to_lowercase(),
+            };
+            if !references.entities.contains(&reference) {
+                references.entities.push(reference);
+            }
+        } else if url.host_str().is_some() && !references.urls.iter().any(|known| known == candidate) {
+            references.urls.push(candidate.to_string());
+        }
+    }
+    references
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .char_indices()
+        .map(|(index, _)| index)
+        .find(|index| {
+            haystack
+                .get(*index..*index + needle.len())
+                .is_some_and(|part| part.eq_ignore_ascii_case(needle))
+        })
+}
+
+/// The URL starting at the beginning of `text`, without the Markdown or
+/// sentence punctuation around it
+fn url_at(text: &str) -> &str {
+    let mut end = text.len();
+    let mut open_parens = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => open_parens += 1,
+            // Closes a Markdown link target unless the URL opened it itself,
+            // as in Wikipedia links
+            ')' if open_parens == 0 => {
+                end = index;
+                break;
+            }
+            ')' => open_parens -= 1,
+            c if c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`' | ']' | '[' | '{' | '}' | '|') => {
+                end = index;
+                break;
+            }
+            _ => {}
+        }
+    }
+    text[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '*', '_'])
+}
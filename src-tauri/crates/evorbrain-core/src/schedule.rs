@@ -0,0 +1,173 @@
+//! Cron-like schedules for background jobs
+//!
+//! A schedule is a five-field cron expression (minute, hour, day of month,
+//! month, day of week) evaluated in the user's time zone, or one of the
+//! shorthands `@hourly`, `@daily`, `@weekly` and `@monthly`. Fields take `*`,
+//! numbers, ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `9-17/2`). Days
+//! of the week run from 0 (Sunday) to 6, with 7 also meaning Sunday. As in
+//! cron, when both day fields are restricted a day matching either counts.
+//! Local times skipped by a DST change never match.
+
+use crate::error::{AppError, AppResult};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+
+/// How far ahead `next_after` looks before deciding a schedule never fires,
+/// e.g. for `0 0 30 2 *`
+const MAX_LOOKAHEAD_DAYS: i64 = 5 * 366;
+
+/// A parsed schedule; each field is a bit set of the values it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// The day of month field isn't `*`
+    days_restricted: bool,
+    /// The day of week field isn't `*`
+    weekdays_restricted: bool,
+}
+
+impl Schedule {
+    /// Parses a cron expression or shorthand
+    pub fn parse(expression: &str) -> AppResult<Self> {
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(AppError::validation_error(
+                "schedule",
+                "must have five fields: minute hour day-of-month month day-of-week",
+            ));
+        };
+
+        let mut weekdays = parse_field(weekday, "day of week", 0, 7)?;
+        // 7 is Sunday too
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, "minute", 0, 59)?,
+            hours: parse_field(hour, "hour", 0, 23)?,
+            days: parse_field(day, "day of month", 1, 31)?,
+            months: parse_field(month, "month", 1, 12)?,
+            weekdays,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        })
+    }
+
+    /// The first time after `after` the schedule fires, or `None` if it
+    /// doesn't within the next five years
+    pub fn next_after(&self, after: DateTime<Utc>, timezone: Tz) -> Option<DateTime<Utc>> {
+        let start = after.with_timezone(&timezone).naive_local();
+        let mut time = start.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+
+        while time < limit {
+            let date = time.date();
+            if !matches(self.months, date.month()) {
+                time = first_of_next_month(date)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(date) {
+                time = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !matches(self.hours, time.hour()) {
+                time = date.and_hms_opt(time.hour(), 0, 0)? + Duration::hours(1);
+            } else if !matches(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                // Ambiguous times after a DST change fire on the first pass
+                if let Some(fire) = timezone.from_local_datetime(&time).earliest() {
+                    let fire = fire.with_timezone(&Utc);
+                    if fire > after {
+                        return Some(fire);
+                    }
+                }
+                time += Duration::minutes(1);
+            }
+        }
+
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = matches(self.days, date.day());
+        let weekday = matches(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+impl std::str::FromStr for Schedule {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+fn matches(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    match date.month() {
+        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+    }
+}
+
+/// Parses one field into a bit set of the values between `min` and `max` it matches
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> AppResult<u64> {
+    let invalid = || {
+        AppError::validation_error(
+            "schedule",
+            &format!("invalid {} field '{}', values must be between {} and {}", name, field, min, max),
+        )
+    };
+    let number = |value: &str| -> AppResult<u32> {
+        value
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (min..=max).contains(n))
+            .ok_or_else(invalid)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (number(start)?, number(end)?),
+                // `5/15` means every 15 starting at 5
+                None if part.contains('/') => (number(range)?, max),
+                None => {
+                    let value = number(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+
+    Ok(set)
+}
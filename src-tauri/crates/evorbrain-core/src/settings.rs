@@ -0,0 +1,317 @@
+//! Typed application settings stored in the `settings` table
+//!
+//! Every setting has a default; only values the user changed are stored, as
+//! JSON keyed by field name. `load` overlays the stored values on the
+//! defaults, and `update` validates a partial change set against the typed
+//! struct before writing it, so the table never holds a value that doesn't
+//! decode.
+
+use crate::auto_complete::AutoComplete;
+use crate::calendar::{DateFormat, WeekStart};
+use crate::db::models::AreaContext;
+use crate::digest::DigestDelivery;
+use crate::locale::Locale;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::logger::LogLevel;
+use crate::urgency::UrgencyWeights;
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Keys accepted by `update`, one per `Settings` field
+pub const SETTING_KEYS: &[&str] = &[
+    "log_level",
+    "timezone",
+    "week_start",
+    "date_format",
+    "locale",
+    "working_days",
+    "holidays",
+    "working_hours_start",
+    "working_hours_end",
+    "archive_retention_days",
+    "archive_retention_exclusions",
+    "review_interval_days",
+    "urgency_weights",
+    "api_server_port",
+    "github_token",
+    "script_time_limit_ms",
+    "max_change_limit",
+    "auto_lock_minutes",
+    "idle_timeout_minutes",
+    "onboarding_completed",
+    "active_context",
+    "digest_delivery",
+    "attachment_max_file_mb",
+    "attachment_quota_mb",
+    "auto_complete",
+    "auto_link_mentions",
+];
+
+/// Upper bound on the `holidays` list
+const MAX_HOLIDAYS: usize = 1000;
+
+/// Longest review interval, globally or per project
+pub const MAX_REVIEW_INTERVAL_DAYS: u32 = 365;
+
+/// Upper bound on the `attachment_max_file_mb` setting
+pub const MAX_ATTACHMENT_FILE_MB: u32 = 1024;
+
+/// Entity types archive retention can delete, children before parents
+pub const PURGEABLE_ENTITY_TYPES: &[&str] = &["note", "task", "project", "goal", "life_area"];
+
+/// Values kept in the settings table that aren't `Settings` fields, so they
+/// are never returned by `load` or writable through `update`
+#[derive(Debug, Clone, Copy)]
+pub enum Secret {
+    /// Argon2 hash of the app lock passphrase
+    AppLockHash,
+    /// Note encryption key, wrapped with a key derived from the passphrase
+    WrappedNoteKey,
+}
+
+impl Secret {
+    fn key(self) -> &'static str {
+        match self {
+            Secret::AppLockHash => "app_lock_passphrase_hash",
+            Secret::WrappedNoteKey => "wrapped_note_key",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub log_level: LogLevel,
+    /// IANA time zone name used for day boundaries, e.g. `Europe/Amsterdam`
+    pub timezone: String,
+    /// First day of the week for week ranges and grouping
+    pub week_start: WeekStart,
+    /// How the backend writes dates, e.g. in CLI output
+    pub date_format: DateFormat,
+    /// Language of weekday names and number separators in digests and other
+    /// generated text, e.g. `de`
+    pub locale: Locale,
+    /// Days of the week that count as working days, e.g. `["Mon", "Tue"]`
+    pub working_days: Vec<Weekday>,
+    /// Dates (`YYYY-MM-DD`) that are never working days
+    pub holidays: Vec<NaiveDate>,
+    /// Local time the working day starts, e.g. `09:00`
+    pub working_hours_start: NaiveTime,
+    /// Local time the working day ends; together with the start this gives
+    /// the daily capacity for planning
+    pub working_hours_end: NaiveTime,
+    /// Archived items older than this many days are deleted; kept forever when unset
+    pub archive_retention_days: Option<u32>,
+    /// Entity types (`life_area`, `goal`, `project`, `task`, `note`) that
+    /// retention never deletes
+    pub archive_retention_exclusions: Vec<String>,
+    /// Days after which an open project without activity or a review counts
+    /// as stale, unless the project sets its own interval
+    pub review_interval_days: u32,
+    /// Weights for the task urgency score used by the `urgency` sort
+    pub urgency_weights: UrgencyWeights,
+    /// Port the local REST API server listens on unless one is given explicitly
+    pub api_server_port: u16,
+    /// Token for private GitHub repositories; falls back to `EVORBRAIN_GITHUB_TOKEN`
+    #[serde(skip_serializing)]
+    pub github_token: Option<String>,
+    /// Wall-clock limit for one script hook run
+    pub script_time_limit_ms: u64,
+    /// Upper bound on changes returned by one `get_changes_since` call
+    pub max_change_limit: i64,
+    /// Minutes of inactivity before the app lock engages; 0 disables auto-lock
+    pub auto_lock_minutes: u32,
+    /// Minutes without activity, or asleep, after which a running timer is
+    /// stopped and the idle gap recorded; 0 disables idle detection
+    pub idle_timeout_minutes: u32,
+    /// The first-run starter template was applied or skipped
+    pub onboarding_completed: bool,
+    /// Only life areas of this context, or without one, show up in lists
+    /// and dashboards; everything shows when unset
+    pub active_context: Option<AreaContext>,
+    /// Where the `weekly_digest` job sends the digest: `note` or `webhook`
+    pub digest_delivery: DigestDelivery,
+    /// Largest file that can be attached
+    pub attachment_max_file_mb: u32,
+    /// Total size of all stored attachment files, counting content attached
+    /// more than once a single time; no quota when unset
+    pub attachment_quota_mb: Option<u32>,
+    /// What happens once the last open task of a project, or the last open
+    /// project of a goal, is completed: `off`, `suggest` or `complete`
+    pub auto_complete: AutoComplete,
+    /// Record the tasks and projects a note mentions by title or `#<id>`
+    /// whenever it is saved
+    pub auto_link_mentions: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            log_level: LogLevel::Info,
+            timezone: "UTC".to_string(),
+            week_start: WeekStart::Monday,
+            date_format: DateFormat::Iso,
+            locale: Locale::En,
+            working_days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            holidays: Vec::new(),
+            working_hours_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+            working_hours_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap_or_default(),
+            archive_retention_days: None,
+            archive_retention_exclusions: Vec::new(),
+            review_interval_days: 7,
+            urgency_weights: UrgencyWeights::default(),
+            api_server_port: 17420,
+            github_token: None,
+            script_time_limit_ms: 2000,
+            max_change_limit: 5000,
+            auto_lock_minutes: 15,
+            idle_timeout_minutes: 10,
+            onboarding_completed: false,
+            active_context: None,
+            digest_delivery: DigestDelivery::Note,
+            attachment_max_file_mb: 100,
+            attachment_quota_mb: None,
+            auto_complete: AutoComplete::Off,
+            auto_link_mentions: true,
+        }
+    }
+}
+
+impl Settings {
+    fn validate(&self) -> AppResult<()> {
+        if self.timezone.parse::<chrono_tz::Tz>().is_err() {
+            return Err(AppError::validation_error("timezone", "must be an IANA time zone name"));
+        }
+        if self.working_days.is_empty() {
+            return Err(AppError::validation_error("working_days", "must include at least one day"));
+        }
+        if self.holidays.len() > MAX_HOLIDAYS {
+            return Err(AppError::validation_error(
+                "holidays",
+                &format!("must list at most {} dates", MAX_HOLIDAYS),
+            ));
+        }
+        if self.working_hours_end <= self.working_hours_start {
+            return Err(AppError::validation_error(
+                "working_hours_end",
+                "must be later than working_hours_start",
+            ));
+        }
+        if self.archive_retention_days == Some(0) {
+            return Err(AppError::validation_error("archive_retention_days", "must be at least 1"));
+        }
+        if let Some(unknown) = self
+            .archive_retention_exclusions
+            .iter()
+            .find(|t| !PURGEABLE_ENTITY_TYPES.contains(&t.as_str()))
+        {
+            return Err(AppError::validation_error(
+                "archive_retention_exclusions",
+                &format!("'{}' is not one of {}", unknown, PURGEABLE_ENTITY_TYPES.join(", ")),
+            ));
+        }
+        if !(1..=MAX_REVIEW_INTERVAL_DAYS).contains(&self.review_interval_days) {
+            return Err(AppError::validation_error(
+                "review_interval_days",
+                &format!("must be between 1 and {}", MAX_REVIEW_INTERVAL_DAYS),
+            ));
+        }
+        self.urgency_weights.validate()?;
+        if self.api_server_port < 1024 {
+            return Err(AppError::validation_error("api_server_port", "must be 1024 or higher"));
+        }
+        if !(100..=60_000).contains(&self.script_time_limit_ms) {
+            return Err(AppError::validation_error(
+                "script_time_limit_ms",
+                "must be between 100 and 60000",
+            ));
+        }
+        if !(1..=100_000).contains(&self.max_change_limit) {
+            return Err(AppError::validation_error("max_change_limit", "must be between 1 and 100000"));
+        }
+        if self.auto_lock_minutes > 24 * 60 {
+            return Err(AppError::validation_error("auto_lock_minutes", "must be at most 1440"));
+        }
+        if self.idle_timeout_minutes > 24 * 60 {
+            return Err(AppError::validation_error("idle_timeout_minutes", "must be at most 1440"));
+        }
+        if !(1..=MAX_ATTACHMENT_FILE_MB).contains(&self.attachment_max_file_mb) {
+            return Err(AppError::validation_error(
+                "attachment_max_file_mb",
+                &format!("must be between 1 and {}", MAX_ATTACHMENT_FILE_MB),
+            ));
+        }
+        if self.attachment_quota_mb == Some(0) {
+            return Err(AppError::validation_error("attachment_quota_mb", "must be at least 1"));
+        }
+        Ok(())
+    }
+}
+
+fn decode(values: Map<String, Value>) -> AppResult<Settings> {
+    serde_json::from_value(Value::Object(values))
+        .map_err(|e| AppError::validation_error("settings", &e.to_string()))
+}
+
+/// Loads the current settings, falling back to defaults for unset keys
+pub async fn load(repo: &Repository) -> AppResult<Settings> {
+    let mut values = repo.get_setting_values().await?;
+    // Ignore values for settings that no longer exist
+    values.retain(|key, _| SETTING_KEYS.contains(&key.as_str()));
+    decode(values)
+}
+
+/// Applies a partial change set and returns the new settings with the keys
+/// whose value actually changed
+///
+/// A `null` value resets that setting to its default. Nothing is written if
+/// any key is unknown or any resulting value is invalid.
+pub async fn update(repo: &Repository, changes: Map<String, Value>) -> AppResult<(Settings, Vec<String>)> {
+    if let Some(unknown) = changes.keys().find(|k| !SETTING_KEYS.contains(&k.as_str())) {
+        return Err(AppError::validation_error(unknown, "unknown setting"));
+    }
+
+    let current = repo.get_setting_values().await?;
+    let mut merged = current.clone();
+    for (key, value) in &changes {
+        if value.is_null() {
+            merged.remove(key);
+        } else {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+
+    let settings = decode(merged)?;
+    settings.validate()?;
+
+    let changed: Map<String, Value> = changes
+        .into_iter()
+        .filter(|(key, value)| current.get(key).unwrap_or(&Value::Null) != value)
+        .collect();
+    repo.write_setting_values(&changed).await?;
+
+    Ok((settings, changed.into_iter().map(|(key, _)| key).collect()))
+}
+
+/// Returns a stored secret, if set
+pub async fn get_secret(repo: &Repository, secret: Secret) -> AppResult<Option<String>> {
+    let mut values = repo.get_setting_values().await?;
+    Ok(match values.remove(secret.key()) {
+        Some(Value::String(value)) => Some(value),
+        _ => None,
+    })
+}
+
+/// Stores secrets in one transaction; `None` removes a secret
+pub async fn set_secrets(repo: &Repository, secrets: &[(Secret, Option<&str>)]) -> AppResult<()> {
+    let values: Map<String, Value> = secrets
+        .iter()
+        .map(|(secret, value)| {
+            let value = value.map_or(Value::Null, |value| Value::String(value.to_string()));
+            (secret.key().to_string(), value)
+        })
+        .collect();
+    repo.write_setting_values(&values).await
+}
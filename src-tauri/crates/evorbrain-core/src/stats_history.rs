@@ -0,0 +1,57 @@
+//! Workspace statistics over time
+//!
+//! The `stats_snapshot` job runs shortly after midnight and records a few
+//! counts of the day that just ended in the user's time zone: tasks still
+//! open, tasks completed that day and notes created that day. Trends over
+//! months are read from those rows instead of going through every task and
+//! note again.
+//!
+//! Open tasks can only be counted as they are when the job runs, so a day
+//! recorded late, e.g. when the app was closed overnight, has the count of
+//! that moment. Days the job didn't run for have no snapshot; the history
+//! leaves them out rather than guessing.
+
+use crate::calendar::Calendar;
+use crate::db::models::StatsSnapshot;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use chrono::{Duration, NaiveDate};
+
+/// Days covered when no range is given
+pub const DEFAULT_HISTORY_DAYS: i64 = 30;
+/// Longest range that can be requested, about ten years
+pub const MAX_HISTORY_DAYS: i64 = 3660;
+
+/// Records the counts of yesterday, for the `stats_snapshot` job
+pub async fn record_snapshot(repo: &Repository) -> AppResult<StatsSnapshot> {
+    let calendar = Calendar::load(repo).await?;
+    let day = calendar.today() - Duration::days(1);
+    let range = calendar.day(day);
+    repo.record_stats_snapshot(day, range.start, range.end).await
+}
+
+/// Snapshots from `from` through `to`, oldest first
+///
+/// The range defaults to the last `DEFAULT_HISTORY_DAYS` days up to
+/// yesterday, the latest day with a snapshot.
+pub async fn get_stats_history(
+    repo: &Repository,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> AppResult<Vec<StatsSnapshot>> {
+    let to = match to {
+        Some(to) => to,
+        None => Calendar::load(repo).await?.today() - Duration::days(1),
+    };
+    let from = from.unwrap_or(to - Duration::days(DEFAULT_HISTORY_DAYS - 1));
+    if to < from {
+        return Err(AppError::validation_error("to", "must not be before from"));
+    }
+    if (to - from).num_days() >= MAX_HISTORY_DAYS {
+        return Err(AppError::validation_error(
+            "to",
+            &format!("the history covers at most {} days", MAX_HISTORY_DAYS),
+        ));
+    }
+    repo.get_stats_history(from, to).await
+}
@@ -0,0 +1,125 @@
+//! Storage advice for large databases
+//!
+//! Years of use pile up completed tasks that stay in every list query and
+//! archived rows that are never purged. Past a soft limit the advice names
+//! the cleanup that keeps queries fast, with the command and arguments that
+//! carry it out so the UI can offer it as a single click. Nothing is
+//! enforced; below the limits there is no advice at all.
+
+use crate::db::models::StorageCounts;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::settings;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Completed tasks left unarchived before archiving them is suggested
+const COMPLETED_TASKS_LIMIT: i64 = 10_000;
+
+/// Age in days of the completed tasks suggested for archiving
+const ARCHIVE_COMPLETED_AFTER_DAYS: u32 = 90;
+
+/// Archived rows kept before enabling archive retention is suggested
+const ARCHIVED_ITEMS_LIMIT: i64 = 10_000;
+
+/// Retention period suggested when none is set
+const SUGGESTED_RETENTION_DAYS: u32 = 365;
+
+/// Reclaimable space, in bytes and as a share of the file, past which a
+/// vacuum is suggested
+const FREE_BYTES_LIMIT: i64 = 64 * 1024 * 1024;
+const FREE_SHARE_LIMIT: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageAdviceKind {
+    ArchiveCompletedTasks,
+    EnableArchiveRetention,
+    Vacuum,
+}
+
+/// A command call that carries out a piece of advice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageAction {
+    pub command: String,
+    /// Arguments as the command takes them
+    pub args: Value,
+    /// The command also needs a token from `request_destructive_confirmation`
+    pub needs_confirmation: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageAdvice {
+    pub kind: StorageAdviceKind,
+    /// e.g. "12400 completed tasks aren't archived; archiving the 11800
+    /// completed over 90 days ago keeps lists fast"
+    pub message: String,
+    pub action: StorageAction,
+}
+
+/// Everything `get_storage_advice` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageReport {
+    pub counts: StorageCounts,
+    /// Empty while the database is within the soft limits
+    pub advice: Vec<StorageAdvice>,
+}
+
+/// Checks the database against the soft limits
+pub async fn get_storage_advice(repo: &Repository) -> AppResult<StorageReport> {
+    let cutoff = Utc::now() - Duration::days(i64::from(ARCHIVE_COMPLETED_AFTER_DAYS));
+    let counts = repo.get_storage_counts(cutoff).await?;
+    let settings = settings::load(repo).await?;
+    let mut advice = Vec::new();
+
+    if counts.completed_tasks >= COMPLETED_TASKS_LIMIT && counts.archivable_tasks > 0 {
+        advice.push(StorageAdvice {
+            kind: StorageAdviceKind::ArchiveCompletedTasks,
+            message: format!(
+                "{} completed tasks aren't archived; archiving the {} completed over {} days ago keeps lists fast",
+                counts.completed_tasks, counts.archivable_tasks, ARCHIVE_COMPLETED_AFTER_DAYS
+            ),
+            action: StorageAction {
+                command: "archive_completed_tasks".to_string(),
+                args: json!({ "olderThanDays": ARCHIVE_COMPLETED_AFTER_DAYS }),
+                needs_confirmation: false,
+            },
+        });
+    }
+
+    if counts.archived_items >= ARCHIVED_ITEMS_LIMIT && settings.archive_retention_days.is_none() {
+        advice.push(StorageAdvice {
+            kind: StorageAdviceKind::EnableArchiveRetention,
+            message: format!(
+                "{} archived items are kept forever; deleting those archived over {} days ago keeps the database small",
+                counts.archived_items, SUGGESTED_RETENTION_DAYS
+            ),
+            action: StorageAction {
+                command: "update_settings".to_string(),
+                args: json!({ "changes": { "archive_retention_days": SUGGESTED_RETENTION_DAYS } }),
+                needs_confirmation: true,
+            },
+        });
+    }
+
+    if counts.free_bytes >= FREE_BYTES_LIMIT
+        && counts.free_bytes as f64 >= counts.database_bytes as f64 * FREE_SHARE_LIMIT
+    {
+        advice.push(StorageAdvice {
+            kind: StorageAdviceKind::Vacuum,
+            message: format!(
+                "{} MB of the {} MB database file is space of deleted rows; a vacuum gives it back",
+                counts.free_bytes / (1024 * 1024),
+                counts.database_bytes / (1024 * 1024)
+            ),
+            action: StorageAction {
+                command: "cleanup_database".to_string(),
+                args: json!({ "options": { "delete_archived_older_than_days": null, "vacuum_database": true } }),
+                needs_confirmation: true,
+            },
+        });
+    }
+
+    Ok(StorageReport { counts, advice })
+}
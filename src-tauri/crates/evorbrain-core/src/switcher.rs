@@ -0,0 +1,166 @@
+//! Quick switcher
+//!
+//! The command palette jumps to a life area, goal, project, task or note by
+//! its title. To answer within a few milliseconds it searches an in-memory
+//! index of the titles, IDs and types rather than the database: the index
+//! is loaded once at startup and every change feed event refreshes just the
+//! entity it names. Restores and imports, which change many entities at
+//! once, invalidate it so it is reloaded whole right away instead of one
+//! entity at a time as the feed catches up.
+//!
+//! Archived entities are left out. A title matches when it is the query,
+//! starts with it, has a word starting with it, contains it, or contains
+//! its letters in order, and ranks in that order; shorter titles come first
+//! within a rank.
+
+use crate::db::models::SwitcherEntry;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tokio::sync::Notify;
+
+/// Entity types the switcher finds
+pub const SWITCHER_ENTITY_TYPES: &[&str] = &["life_area", "goal", "project", "task", "note"];
+
+/// Matches returned when the caller doesn't say
+pub const DEFAULT_SWITCHER_LIMIT: usize = 10;
+
+/// Most matches one search returns
+pub const MAX_SWITCHER_LIMIT: usize = 50;
+
+/// Most waiting change feed events refreshed one by one; more reload the
+/// whole index
+pub const MAX_SWITCHER_REFRESHES: usize = 200;
+
+/// How well a title matches the query, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    Exact,
+    Prefix,
+    WordPrefix,
+    Contains,
+    Fuzzy,
+}
+
+/// Lowercases and collapses whitespace, so titles and queries compare as typed
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn rank(title: &str, query: &str) -> Option<Rank> {
+    if title == query {
+        return Some(Rank::Exact);
+    }
+    if title.starts_with(query) {
+        return Some(Rank::Prefix);
+    }
+    let mut found = title.match_indices(query).peekable();
+    if found.peek().is_some() {
+        let word_start = found.any(|(at, _)| title[..at].ends_with(|c: char| !c.is_alphanumeric()));
+        return Some(if word_start { Rank::WordPrefix } else { Rank::Contains });
+    }
+    let mut chars = title.chars();
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|wanted| chars.any(|c| c == wanted))
+        .then_some(Rank::Fuzzy)
+}
+
+struct Indexed {
+    entry: SwitcherEntry,
+    /// The title as compared with queries
+    key: String,
+}
+
+impl From<SwitcherEntry> for Indexed {
+    fn from(entry: SwitcherEntry) -> Self {
+        let key = normalize(&entry.title);
+        Self { entry, key }
+    }
+}
+
+/// Titles of everything the switcher finds, by entity ID
+#[derive(Default)]
+pub struct SwitcherIndex {
+    entries: RwLock<HashMap<String, Indexed>>,
+    invalidated: Notify,
+}
+
+impl SwitcherIndex {
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Indexed>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Indexed>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replaces the index with the titles in the database; returns how many
+    /// there are
+    pub async fn load(&self, repo: &Repository) -> AppResult<usize> {
+        let entries: HashMap<_, _> = repo
+            .get_switcher_entries()
+            .await?
+            .into_iter()
+            .map(|entry| (entry.id.clone(), Indexed::from(entry)))
+            .collect();
+        let count = entries.len();
+        *self.write() = entries;
+        Ok(count)
+    }
+
+    /// Asks for the whole index to be reloaded, after a restore or import
+    pub fn invalidate(&self) {
+        self.invalidated.notify_one();
+    }
+
+    /// Waits until the index is invalidated
+    pub async fn invalidated(&self) {
+        self.invalidated.notified().await;
+    }
+
+    /// Re-reads an entity after it changed, dropping it once it is deleted
+    /// or archived
+    pub async fn refresh(&self, repo: &Repository, entity_type: &str, id: &str) -> AppResult<()> {
+        if !SWITCHER_ENTITY_TYPES.contains(&entity_type) {
+            return Ok(());
+        }
+        match repo.get_switcher_entry(entity_type, id).await? {
+            Some(entry) => {
+                self.write().insert(entry.id.clone(), Indexed::from(entry));
+            }
+            None => {
+                self.write().remove(id);
+            }
+        }
+        Ok(())
+    }
+
+    /// The titles best matching `query`, best first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SwitcherEntry> {
+        let query = normalize(query);
+        let limit = limit.min(MAX_SWITCHER_LIMIT);
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let entries = self.read();
+        let mut matches: Vec<(Rank, &Indexed)> = entries
+            .values()
+            .filter_map(|indexed| rank(&indexed.key, &query).map(|rank| (rank, indexed)))
+            .collect();
+        let order = |a: &(Rank, &Indexed), b: &(Rank, &Indexed)| -> Ordering {
+            (a.0, a.1.key.len(), &a.1.key).cmp(&(b.0, b.1.key.len(), &b.1.key))
+        };
+        // Only the best few are sorted
+        if matches.len() > limit {
+            matches.select_nth_unstable_by(limit, order);
+            matches.truncate(limit);
+        }
+        matches.sort_unstable_by(order);
+        matches.into_iter().map(|(_, indexed)| indexed.entry.clone()).collect()
+    }
+}
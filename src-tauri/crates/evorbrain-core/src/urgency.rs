@@ -0,0 +1,175 @@
+//! Task urgency scores
+//!
+//! A Taskwarrior-style urgency: every open task gets a score that adds up
+//! weighted factors for its priority, how close (or overdue) its due date
+//! is, its age, its tags, whether it is in progress, and whether it blocks
+//! or is blocked by other tasks. Blocking follows the open task
+//! dependencies; on top of those, a subtask blocks its open parent and a
+//! parent with open subtasks is blocked. The weights are the
+//! `urgency_weights` setting. Completed tasks score 0.
+
+use crate::db::models::{Task, TaskPriority, UrgencyFactors};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::settings;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Largest absolute value accepted for a weight
+const MAX_WEIGHT: f64 = 100.0;
+/// Due dates further away than this count as "not due soon"
+const DUE_HORIZON_DAYS: f64 = 14.0;
+/// Tasks overdue by this much get the full due weight
+const OVERDUE_DAYS: f64 = 7.0;
+
+/// Weights of the urgency factors; a weight of 0 disables a factor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrgencyWeights {
+    pub priority_urgent: f64,
+    pub priority_high: f64,
+    pub priority_medium: f64,
+    pub priority_low: f64,
+    /// Applied in full to tasks a week overdue, scaled down to a fifth for
+    /// tasks due in two weeks or later
+    pub due: f64,
+    /// Applied in full to tasks `max_age_days` old, scaled for younger ones
+    pub age: f64,
+    pub max_age_days: u32,
+    /// Applied in full with three or more tags
+    pub tags: f64,
+    /// The task is in an in-progress workflow status
+    pub in_progress: f64,
+    /// Another open task waits on this one
+    pub blocking: f64,
+    /// The task waits on other open tasks; usually negative
+    pub blocked: f64,
+}
+
+impl Default for UrgencyWeights {
+    fn default() -> Self {
+        Self {
+            priority_urgent: 9.0,
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            due: 12.0,
+            age: 2.0,
+            max_age_days: 365,
+            tags: 1.0,
+            in_progress: 4.0,
+            blocking: 8.0,
+            blocked: -5.0,
+        }
+    }
+}
+
+impl UrgencyWeights {
+    pub fn validate(&self) -> AppResult<()> {
+        let weights = [
+            self.priority_urgent,
+            self.priority_high,
+            self.priority_medium,
+            self.priority_low,
+            self.due,
+            self.age,
+            self.tags,
+            self.in_progress,
+            self.blocking,
+            self.blocked,
+        ];
+        if weights.iter().any(|w| !w.is_finite() || w.abs() > MAX_WEIGHT) {
+            return Err(AppError::validation_error(
+                "urgency_weights",
+                &format!("weights must be between -{} and {}", MAX_WEIGHT, MAX_WEIGHT),
+            ));
+        }
+        if self.max_age_days == 0 {
+            return Err(AppError::validation_error("urgency_weights", "max_age_days must be at least 1"));
+        }
+        Ok(())
+    }
+
+    fn priority(&self, priority: &TaskPriority) -> f64 {
+        match priority {
+            TaskPriority::Urgent => self.priority_urgent,
+            TaskPriority::High => self.priority_high,
+            TaskPriority::Medium => self.priority_medium,
+            TaskPriority::Low => self.priority_low,
+        }
+    }
+}
+
+/// 0.2 for tasks due in two weeks or later, rising linearly to 1.0 for
+/// tasks a week overdue; 0 without a due date
+fn due_factor(due_date: Option<DateTime<Utc>>, now: DateTime<Utc>) -> f64 {
+    let Some(due_date) = due_date else { return 0.0 };
+    let days_overdue = (now - due_date).num_seconds() as f64 / 86_400.0;
+    if days_overdue >= OVERDUE_DAYS {
+        1.0
+    } else if days_overdue >= -DUE_HORIZON_DAYS {
+        (days_overdue + DUE_HORIZON_DAYS) * 0.8 / (DUE_HORIZON_DAYS + OVERDUE_DAYS) + 0.2
+    } else {
+        0.2
+    }
+}
+
+fn tags_factor(tag_count: i64) -> f64 {
+    match tag_count {
+        0 => 0.0,
+        1 => 0.8,
+        2 => 0.9,
+        _ => 1.0,
+    }
+}
+
+/// Urgency of one task; `factors` is `None` for completed tasks
+pub fn score(task: &Task, factors: Option<&UrgencyFactors>, weights: &UrgencyWeights, now: DateTime<Utc>) -> f64 {
+    let Some(factors) = factors.filter(|_| task.completed_at.is_none()) else {
+        return 0.0;
+    };
+
+    let age_days = (now - task.created_at).num_days().max(0) as f64;
+    let mut score = weights.priority(&task.priority)
+        + weights.due * due_factor(task.due_date, now)
+        + weights.age * (age_days / f64::from(weights.max_age_days)).min(1.0)
+        + weights.tags * tags_factor(factors.tag_count);
+    if factors.in_progress {
+        score += weights.in_progress;
+    }
+    if factors.blocking {
+        score += weights.blocking;
+    }
+    if factors.blocked {
+        score += weights.blocked;
+    }
+    score
+}
+
+/// Sorts tasks by urgency, most urgent first; completed tasks go last
+pub async fn sort_by_urgency(repo: &Repository, tasks: &mut [Task]) -> AppResult<()> {
+    let weights = settings::load(repo).await?.urgency_weights;
+    let factors: HashMap<String, UrgencyFactors> = repo
+        .get_urgency_factors()
+        .await?
+        .into_iter()
+        .map(|f| (f.task_id.clone(), f))
+        .collect();
+
+    let now = Utc::now();
+    let scores: HashMap<String, f64> = tasks
+        .iter()
+        .map(|task| (task.id.clone(), score(task, factors.get(&task.id), &weights, now)))
+        .collect();
+    let score_of = |task: &Task| scores.get(&task.id).copied().unwrap_or(0.0);
+
+    tasks.sort_by(|a, b| {
+        a.completed_at
+            .is_some()
+            .cmp(&b.completed_at.is_some())
+            .then_with(|| score_of(b).partial_cmp(&score_of(a)).unwrap_or(Ordering::Equal))
+    });
+    Ok(())
+}
@@ -0,0 +1,295 @@
+//! Input validation shared by the IPC commands, the REST API and the CLI
+
+use crate::error::{AppError, AppResult};
+use uuid::Uuid;
+
+/// Maximum length accepted for titles and names
+pub const MAX_TITLE_LENGTH: usize = 500;
+
+/// Ensures `id` is a well-formed UUID
+pub fn validate_id(id: &str) -> AppResult<()> {
+    Uuid::parse_str(id).map_err(|_| AppError::invalid_id(id))?;
+    Ok(())
+}
+
+/// Ensures an optional reference is a well-formed UUID when present
+pub fn validate_optional_id(id: Option<&str>) -> AppResult<()> {
+    match id {
+        Some(id) => validate_id(id),
+        None => Ok(()),
+    }
+}
+
+/// Ensures a title (or name) is non-blank and within the length limit
+pub fn validate_title(field: &str, value: &str) -> AppResult<()> {
+    if value.trim().is_empty() {
+        return Err(AppError::validation_error(field, "must not be empty"));
+    }
+    if value.chars().count() > MAX_TITLE_LENGTH {
+        return Err(AppError::validation_error(
+            field,
+            &format!("must be at most {} characters", MAX_TITLE_LENGTH),
+        ));
+    }
+    Ok(())
+}
+
+/// Ensures `value` is an absolute http(s) URL
+pub fn validate_http_url(field: &str, value: &str) -> AppResult<()> {
+    let url = url::Url::parse(value.trim())
+        .map_err(|e| AppError::validation_error(field, &format!("is not a valid URL ({})", e)))?;
+
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(AppError::validation_error(field, "must be an http or https URL"));
+    }
+    Ok(())
+}
+
+/// Ensures a note search scope names at most one well-formed parent
+pub fn validate_note_search_scope(scope: &crate::db::models::NoteSearchScope) -> AppResult<()> {
+    let ids = [
+        ("project_id", &scope.project_id),
+        ("goal_id", &scope.goal_id),
+        ("life_area_id", &scope.life_area_id),
+    ];
+    if ids.iter().filter(|(_, id)| id.is_some()).count() > 1 {
+        return Err(AppError::validation_error(
+            "scope",
+            "give at most one of project_id, goal_id and life_area_id",
+        ));
+    }
+    for (_, id) in ids {
+        validate_optional_id(id.as_deref())?;
+    }
+    Ok(())
+}
+
+/// Ensures a private note's content was encrypted before it reaches the database
+pub fn validate_note_privacy(is_private: bool, content: &str) -> AppResult<()> {
+    if is_private && !content.starts_with(crate::db::models::ENCRYPTED_CONTENT_PREFIX) {
+        return Err(AppError::validation_error("content", "must be encrypted for private notes"));
+    }
+    Ok(())
+}
+
+/// Rejects private notes from callers without the note key: the prefix
+/// check above can't tell real ciphertext from fake or foreign content, so
+/// only the app, which encrypts the content itself, may set `is_private`
+pub fn validate_not_private(is_private: bool) -> AppResult<()> {
+    if is_private {
+        return Err(AppError::validation_error("is_private", "private notes can only be saved in the app"));
+    }
+    Ok(())
+}
+
+/// Longest accepted workflow status key
+pub const MAX_STATUS_KEY_LENGTH: usize = 64;
+
+/// Ensures a custom workflow is well formed: unique lowercase keys, at least
+/// one todo and one done status, and transitions that only name its own keys
+pub fn validate_workflow(statuses: &[crate::db::models::WorkflowStatusInput]) -> AppResult<()> {
+    use crate::db::models::StatusCategory;
+
+    for (i, status) in statuses.iter().enumerate() {
+        let valid_key = !status.key.is_empty()
+            && status.key.len() <= MAX_STATUS_KEY_LENGTH
+            && status.key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_key {
+            return Err(AppError::validation_error(
+                "key",
+                &format!("'{}' must be 1-{} lowercase letters, digits or underscores", status.key, MAX_STATUS_KEY_LENGTH),
+            ));
+        }
+        if statuses[..i].iter().any(|other| other.key == status.key) {
+            return Err(AppError::validation_error("key", &format!("'{}' is used more than once", status.key)));
+        }
+        validate_title("name", &status.name)?;
+    }
+
+    for (category, name) in [(StatusCategory::Todo, "todo"), (StatusCategory::Done, "done")] {
+        if !statuses.iter().any(|s| s.category == category) {
+            return Err(AppError::validation_error(
+                "statuses",
+                &format!("must include a status in the {} category", name),
+            ));
+        }
+    }
+
+    for next in statuses.iter().filter_map(|s| s.next_statuses.as_ref()).flatten() {
+        if !statuses.iter().any(|s| &s.key == next) {
+            return Err(AppError::validation_error(
+                "next_statuses",
+                &format!("'{}' is not a status in this workflow", next),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Task fields the project views can sort by and show
+pub const TASK_VIEW_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "status",
+    "priority",
+    "due_date",
+    "tags",
+    "contexts",
+    "estimated_minutes",
+    "actual_minutes",
+    "recurrence_rule",
+    "created_at",
+    "updated_at",
+    "completed_at",
+];
+
+/// Task fields the project views can group by
+pub const TASK_GROUP_FIELDS: &[&str] = &["status", "priority", "due_date", "tags", "contexts"];
+
+/// Most groups a view keeps collapsed
+pub const MAX_COLLAPSED_COLUMNS: usize = 100;
+
+/// Ensures a view arrangement only names task fields the views know and
+/// lists each field and collapsed group once
+pub fn validate_view_preferences(input: &crate::db::models::ViewPreferencesInput) -> AppResult<()> {
+    let unknown =
+        |field: &str, value: &str| AppError::validation_error(field, &format!("'{}' is not a task field", value));
+
+    if let Some(group_by) = input.group_by.as_deref().filter(|f| !TASK_GROUP_FIELDS.contains(f)) {
+        return Err(AppError::validation_error(
+            "group_by",
+            &format!("must be one of {}, not '{}'", TASK_GROUP_FIELDS.join(", "), group_by),
+        ));
+    }
+    if let Some(sort_by) = input.sort_by.as_deref().filter(|f| !TASK_VIEW_FIELDS.contains(f)) {
+        return Err(unknown("sort_by", sort_by));
+    }
+    for (i, field) in input.visible_fields.iter().flatten().enumerate() {
+        if !TASK_VIEW_FIELDS.contains(&field.as_str()) {
+            return Err(unknown("visible_fields", field));
+        }
+        if input.visible_fields.iter().flatten().take(i).any(|other| other == field) {
+            return Err(AppError::validation_error("visible_fields", &format!("'{}' is listed more than once", field)));
+        }
+    }
+
+    if input.collapsed_columns.len() > MAX_COLLAPSED_COLUMNS {
+        return Err(AppError::validation_error(
+            "collapsed_columns",
+            &format!("can't hold more than {} columns", MAX_COLLAPSED_COLUMNS),
+        ));
+    }
+    for (i, column) in input.collapsed_columns.iter().enumerate() {
+        validate_title("collapsed_columns", column)?;
+        if input.collapsed_columns[..i].contains(column) {
+            return Err(AppError::validation_error(
+                "collapsed_columns",
+                &format!("'{}' is listed more than once", column),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Longest accepted context name
+pub const MAX_CONTEXT_NAME_LENGTH: usize = 50;
+
+/// Normalizes a context name (`@Home` -> `home`) and checks it is 1-50
+/// letters, digits, `-` or `_`
+pub fn normalize_context_name(name: &str) -> AppResult<String> {
+    let name = name.trim().trim_start_matches('@').to_lowercase();
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_CONTEXT_NAME_LENGTH
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(AppError::validation_error(
+            "context",
+            &format!("must be 1-{} letters, digits, '-' or '_'", MAX_CONTEXT_NAME_LENGTH),
+        ));
+    }
+    Ok(name)
+}
+
+/// Ensures a person has a name and, if given, a plausible email address
+pub fn validate_person(input: &crate::db::models::PersonInput) -> AppResult<()> {
+    validate_title("name", &input.name)?;
+    if let Some(email) = input.email.as_deref().map(str::trim).filter(|e| !e.is_empty()) {
+        let plausible = email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.') && !email.contains(char::is_whitespace));
+        if !plausible {
+            return Err(AppError::validation_error("email", "is not a valid email address"));
+        }
+    }
+    Ok(())
+}
+
+/// Ensures a goal or project progress is a percentage
+pub fn validate_progress(progress: u8) -> AppResult<()> {
+    if progress > 100 {
+        return Err(AppError::validation_error("progress", "must be between 0 and 100"));
+    }
+    Ok(())
+}
+
+/// Ensures a weekly time budget fits in a week
+pub fn validate_weekly_minutes(weekly_minutes: u32) -> AppResult<()> {
+    if weekly_minutes == 0 || weekly_minutes > 7 * 24 * 60 {
+        return Err(AppError::validation_error("weekly_minutes", "must be between 1 and 10080 (a whole week)"));
+    }
+    Ok(())
+}
+
+/// Normalizes an iCalendar recurrence rule (`rrule:freq=weekly;byday=mo` ->
+/// `FREQ=WEEKLY;BYDAY=MO`) and checks it only uses the parts the app
+/// understands: FREQ (required), INTERVAL, COUNT or UNTIL, BYDAY,
+/// BYMONTHDAY and BYMONTH
+pub fn normalize_recurrence_rule(rule: &str) -> AppResult<String> {
+    let invalid = |reason: &str| AppError::validation_error("recurrence_rule", reason);
+    let rule = rule.trim().to_uppercase();
+    let rule = rule.strip_prefix("RRULE:").unwrap_or(&rule);
+
+    let mut seen = Vec::new();
+    for part in rule.split(';') {
+        let (key, value) = part
+            .split_once('=')
+            .filter(|(_, value)| !value.is_empty())
+            .ok_or_else(|| invalid(&format!("'{}' is not a KEY=VALUE part", part)))?;
+        if seen.contains(&key) {
+            return Err(invalid(&format!("{} is given more than once", key)));
+        }
+        seen.push(key);
+
+        let numbers_within = |min: i64, max: i64| {
+            value.split(',').all(|n| n.parse::<i64>().is_ok_and(|n| n != 0 && (min..=max).contains(&n)))
+        };
+        let valid = match key {
+            "FREQ" => matches!(value, "DAILY" | "WEEKLY" | "MONTHLY" | "YEARLY"),
+            "INTERVAL" | "COUNT" => value.parse::<u32>().is_ok_and(|n| n > 0),
+            "UNTIL" => {
+                chrono::NaiveDate::parse_from_str(value, "%Y%m%d").is_ok()
+                    || chrono::NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ").is_ok()
+            }
+            "BYDAY" => value.split(',').all(|day| {
+                let weekday = day.trim_start_matches(|c: char| c.is_ascii_digit() || c == '+' || c == '-');
+                let ordinal = &day[..day.len() - weekday.len()];
+                matches!(weekday, "MO" | "TU" | "WE" | "TH" | "FR" | "SA" | "SU")
+                    && (ordinal.is_empty() || ordinal.parse::<i64>().is_ok_and(|n| n != 0 && n.abs() <= 53))
+            }),
+            "BYMONTHDAY" => numbers_within(-31, 31),
+            "BYMONTH" => numbers_within(1, 12),
+            _ => return Err(invalid(&format!("{} is not supported", key))),
+        };
+        if !valid {
+            return Err(invalid(&format!("{}={} is not valid", key, value)));
+        }
+    }
+
+    if !seen.contains(&"FREQ") {
+        return Err(invalid("must include FREQ"));
+    }
+    if seen.contains(&"COUNT") && seen.contains(&"UNTIL") {
+        return Err(invalid("cannot have both COUNT and UNTIL"));
+    }
+    Ok(rule.to_string())
+}
@@ -0,0 +1,211 @@
+//! Project burndown and velocity
+//!
+//! Velocity is the average number of tasks a project completes per week,
+//! over the last few weeks of the user's calendar (weeks start on the
+//! `week_start` setting). Weeks before the project was created don't count,
+//! so a new project isn't dragged down by weeks it didn't exist. The
+//! projected completion date assumes the remaining open tasks keep getting
+//! done at that pace.
+//!
+//! Goals are measured in estimated minutes instead: a goal with a target
+//! date is at risk when the estimates of its open tasks take longer to work
+//! off, at the estimated minutes per week completed lately, than the days
+//! left, when nothing estimated got done lately, or when the date passed
+//! with work still open.
+
+use crate::calendar::{Calendar, Week};
+use crate::db::models::RemainingWork;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Weeks of history, including the current one, that velocity averages over
+const VELOCITY_WEEKS: i64 = 8;
+
+/// Tasks completed in one calendar week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekCompletions {
+    pub first_day: NaiveDate,
+    /// e.g. `31/03/2024 – 06/04/2024` in the user's date format
+    pub label: String,
+    pub completed: i64,
+}
+
+/// Everything `get_project_velocity` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectVelocity {
+    pub project_id: String,
+    /// The last weeks, oldest first, ending with the current week
+    pub weeks: Vec<WeekCompletions>,
+    pub remaining: RemainingWork,
+    /// Average completed tasks per week over the weeks the project existed
+    pub tasks_per_week: f64,
+    /// Today when nothing is left; `None` while nothing is getting done
+    pub projected_completion: Option<NaiveDate>,
+}
+
+/// Date the remaining tasks are done at `tasks_per_week`, counted from `today`
+fn projected_completion(remaining: i64, tasks_per_week: f64, today: NaiveDate) -> Option<NaiveDate> {
+    if remaining == 0 {
+        return Some(today);
+    }
+    if tasks_per_week <= 0.0 {
+        return None;
+    }
+    let days = (remaining as f64 / tasks_per_week * 7.0).ceil() as i64;
+    Some(today + Duration::days(days))
+}
+
+/// The weeks velocity averages over, oldest first, ending with the week of
+/// `today`
+fn history_weeks(calendar: &Calendar, today: NaiveDate) -> Vec<Week> {
+    let current_week = calendar.week(today).first_day;
+    (0..VELOCITY_WEEKS)
+        .rev()
+        .map(|ago| calendar.week(current_week - Duration::weeks(ago)))
+        .collect()
+}
+
+/// Returns weekly completions, remaining work and the projected completion
+/// date of a project
+pub async fn get_project_velocity(repo: &Repository, project_id: &str) -> AppResult<ProjectVelocity> {
+    let project = repo.get_project(project_id).await?;
+    let calendar = Calendar::load(repo).await?;
+    let today = calendar.today();
+    let weeks = history_weeks(&calendar, today);
+
+    let completed_on: Vec<NaiveDate> = repo
+        .get_project_completion_times(project_id)
+        .await?
+        .into_iter()
+        .map(|instant| calendar.local_date(instant))
+        .collect();
+
+    let weeks: Vec<WeekCompletions> = weeks
+        .into_iter()
+        .map(|week| WeekCompletions {
+            completed: completed_on
+                .iter()
+                .filter(|date| (week.first_day..=week.last_day).contains(date))
+                .count() as i64,
+            first_day: week.first_day,
+            label: week.label,
+        })
+        .collect();
+
+    let created_week = calendar.week(calendar.local_date(project.created_at)).first_day;
+    let counted: Vec<&WeekCompletions> = weeks.iter().filter(|week| week.first_day >= created_week).collect();
+    let tasks_per_week = if counted.is_empty() {
+        0.0
+    } else {
+        counted.iter().map(|week| week.completed).sum::<i64>() as f64 / counted.len() as f64
+    };
+
+    let remaining = repo.get_remaining_work(project_id).await?;
+
+    Ok(ProjectVelocity {
+        project_id: project.id,
+        projected_completion: projected_completion(remaining.task_count, tasks_per_week, today),
+        weeks,
+        remaining,
+        tasks_per_week,
+    })
+}
+
+/// Why a goal may miss its target date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalRiskReason {
+    /// The target date passed with tasks still open
+    Overdue,
+    /// No estimated task was completed lately, so there is no pace to go by
+    Stalled,
+    /// At the current pace the open tasks are done after the target date
+    BehindPace,
+}
+
+/// A goal at risk of missing its target date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalRisk {
+    pub goal_id: String,
+    pub title: String,
+    pub target_date: NaiveDate,
+    /// Days until the target date; negative once it has passed
+    pub days_left: i64,
+    pub remaining: RemainingWork,
+    /// Average estimated minutes of the tasks completed per week over the
+    /// weeks the goal existed
+    pub minutes_per_week: f64,
+    /// `None` while nothing is getting done
+    pub projected_completion: Option<NaiveDate>,
+    pub reason: GoalRiskReason,
+}
+
+/// Returns the open goals with a target date that are at risk of missing
+/// it, the closest target date first
+///
+/// Goals whose open tasks have no estimates are only flagged once overdue,
+/// as there is nothing to measure them by before.
+pub async fn get_goals_at_risk(repo: &Repository) -> AppResult<Vec<GoalRisk>> {
+    let calendar = Calendar::load(repo).await?;
+    let today = calendar.today();
+    let weeks = history_weeks(&calendar, today);
+    let mut risks = Vec::new();
+
+    for goal in repo.get_goals().await? {
+        let Some(target) = goal.target_date.filter(|_| goal.completed_at.is_none()) else {
+            continue;
+        };
+        let remaining = repo.get_goal_remaining_work(&goal.id).await?;
+        if remaining.task_count == 0 {
+            continue;
+        }
+
+        let created_week = calendar.week(calendar.local_date(goal.created_at)).first_day;
+        let counted: Vec<&Week> = weeks.iter().filter(|week| week.first_day >= created_week).collect();
+        let minutes_per_week = match counted.first() {
+            Some(first) => {
+                let completed: i64 = repo
+                    .get_goal_completed_estimates(&goal.id)
+                    .await?
+                    .into_iter()
+                    .filter(|(instant, _)| calendar.local_date(*instant) >= first.first_day)
+                    .map(|(_, minutes)| minutes)
+                    .sum();
+                completed as f64 / counted.len() as f64
+            }
+            None => 0.0,
+        };
+
+        let target_date = calendar.local_date(target);
+        let projected = (remaining.estimated_minutes > 0)
+            .then(|| projected_completion(remaining.estimated_minutes, minutes_per_week, today))
+            .flatten();
+        let reason = if target_date < today {
+            GoalRiskReason::Overdue
+        } else if remaining.estimated_minutes == 0 {
+            continue;
+        } else {
+            match projected {
+                None => GoalRiskReason::Stalled,
+                Some(date) if date > target_date => GoalRiskReason::BehindPace,
+                Some(_) => continue,
+            }
+        };
+
+        risks.push(GoalRisk {
+            goal_id: goal.id,
+            title: goal.title,
+            target_date,
+            days_left: (target_date - today).num_days(),
+            remaining,
+            minutes_per_week,
+            projected_completion: projected,
+            reason,
+        });
+    }
+
+    risks.sort_by_key(|risk| risk.target_date);
+    Ok(risks)
+}
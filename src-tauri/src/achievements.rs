@@ -0,0 +1,55 @@
+//! Achievement watcher
+//!
+//! Checks the achievement rules whenever a task, project or goal is
+//! completed (and once at startup, for completions made while the app was
+//! closed) and publishes `achievement.unlocked` for each one that unlocks.
+
+use crate::db::repository::Repository;
+use crate::read_only::ReadOnlyMode;
+use crate::{log_error, log_info};
+use evorbrain_core::achievements;
+use evorbrain_core::events::{AppEvent, EventBus};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+const TRIGGER_EVENTS: &[&str] = &["task.completed", "project.completed", "goal.completed"];
+
+async fn check(repo: &Repository, bus: &EventBus) {
+    match achievements::check_achievements(repo).await {
+        Ok(unlocked) => {
+            for achievement in unlocked {
+                log_info!("Achievement unlocked", &achievement.name);
+                bus.publish(AppEvent::new("achievement.unlocked", "achievement", achievement.key));
+            }
+        }
+        Err(e) => log_error!("Failed to check achievements", e),
+    }
+}
+
+/// Starts the background task that unlocks achievements
+pub fn spawn_achievement_watcher(db: Arc<SqlitePool>, read_only: Arc<ReadOnlyMode>, bus: EventBus) {
+    let mut receiver = bus.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+        if !read_only.is_enabled() {
+            check(&repo, &bus).await;
+        }
+
+        loop {
+            match receiver.recv().await {
+                Ok(event) if !TRIGGER_EVENTS.contains(&event.name.as_str()) => continue,
+                Ok(_) | Err(RecvError::Lagged(_)) => {
+                    // Coalesce a burst of completions into a single check
+                    while receiver.try_recv().is_ok() {}
+                }
+                Err(RecvError::Closed) => break,
+            }
+            if read_only.is_enabled() {
+                continue;
+            }
+            check(&repo, &bus).await;
+        }
+    });
+}
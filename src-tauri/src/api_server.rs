@@ -0,0 +1,342 @@
+//! Optional local REST API server
+//!
+//! Exposes task and note CRUD plus a quick-capture endpoint over HTTP so
+//! scripts, browser extensions and other local tools can talk to EvorBrain.
+//! The server is off by default, only ever binds to 127.0.0.1, and every
+//! request must carry the bearer token issued when the server was started.
+//! Requests are refused with 423 Locked while the app lock is engaged, and
+//! writes with 503 while read-only mode is on. Private notes are served
+//! without their content and can't be edited.
+
+use crate::app_lock::AppLock;
+use crate::db::models::{Note, NoteInput, NoteSearchScope, Task, TaskInput};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::read_only::ReadOnlyMode;
+use crate::{log_error, log_info};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use evorbrain_core::validation::{validate_id, validate_not_private, validate_note_search_scope, validate_title};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+#[derive(Clone)]
+struct ApiContext {
+    db: Arc<SqlitePool>,
+    token: Arc<str>,
+    lock: Arc<AppLock>,
+    read_only: Arc<ReadOnlyMode>,
+}
+
+impl ApiContext {
+    fn repo(&self) -> Repository {
+        Repository::new(self.db.clone())
+    }
+}
+
+/// Handle to a running server; dropping the shutdown sender stops it
+pub struct ApiServerHandle {
+    pub port: u16,
+    pub token: String,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl ApiServerHandle {
+    pub fn stop(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Starts the server on 127.0.0.1 with a freshly generated access token
+///
+/// Passing port 0 lets the OS pick a free port; the chosen port is reported
+/// on the returned handle.
+pub async fn start(
+    db: Arc<SqlitePool>,
+    lock: Arc<AppLock>,
+    read_only: Arc<ReadOnlyMode>,
+    port: u16,
+) -> AppResult<ApiServerHandle> {
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+    let port = listener.local_addr()?.port();
+
+    let context = ApiContext {
+        db,
+        token: Arc::from(token.as_str()),
+        lock,
+        read_only,
+    };
+    let app = router(context);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tauri::async_runtime::spawn(async move {
+        let result = axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+
+        match result {
+            Ok(()) => log_info!("Local API server stopped"),
+            Err(e) => log_error!("Local API server failed", e),
+        }
+    });
+
+    log_info!("Local API server listening", &format!("127.0.0.1:{}", port));
+
+    Ok(ApiServerHandle {
+        port,
+        token,
+        shutdown: Some(shutdown_tx),
+    })
+}
+
+fn router(context: ApiContext) -> Router {
+    Router::new()
+        .route("/api/tasks", get(list_tasks).post(create_task))
+        .route("/api/tasks/{id}", get(get_task).put(update_task).delete(delete_task))
+        .route("/api/tasks/{id}/complete", post(complete_task))
+        .route("/api/notes", get(list_notes).post(create_note))
+        .route("/api/notes/{id}", get(get_note).put(update_note).delete(delete_note))
+        .route("/api/capture", post(capture))
+        .layer(middleware::from_fn_with_state(context.clone(), require_token))
+        .with_state(context)
+}
+
+async fn require_token(State(context): State<ApiContext>, request: Request, next: Next) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if constant_time_eq(token.as_bytes(), context.token.as_bytes()) => {
+            let writes = !matches!(*request.method(), Method::GET | Method::HEAD);
+            let allowed = context
+                .lock
+                .check()
+                .and_then(|()| if writes { context.read_only.check() } else { Ok(()) });
+            match allowed {
+                Ok(()) => next.run(request).await,
+                Err(e) => ApiError(e).into_response(),
+            }
+        }
+        _ => ApiError(AppError::new(
+            ErrorCode::Unauthorized,
+            "Missing or invalid API token",
+        ))
+        .into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Wraps `AppError` so handlers can use `?` and still produce JSON errors
+struct ApiError(AppError);
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0.code {
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::ValidationError | ErrorCode::InvalidInput | ErrorCode::InvalidId => {
+                StatusCode::BAD_REQUEST
+            }
+            ErrorCode::AlreadyExists | ErrorCode::ArchivedParent | ErrorCode::Conflict => {
+                StatusCode::CONFLICT
+            }
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::Locked => StatusCode::LOCKED,
+            ErrorCode::QuotaExceeded => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::ReadOnly => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        (status, Json(self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+// Task endpoints
+
+async fn list_tasks(State(context): State<ApiContext>) -> ApiResult<Json<Vec<Task>>> {
+    Ok(Json(context.repo().get_tasks().await?))
+}
+
+async fn create_task(
+    State(context): State<ApiContext>,
+    Json(input): Json<TaskInput>,
+) -> ApiResult<(StatusCode, Json<Task>)> {
+    validate_title("title", &input.title)?;
+    let task = context.repo().create_task(input).await?;
+    Ok((StatusCode::CREATED, Json(task)))
+}
+
+async fn get_task(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Task>> {
+    validate_id(&id)?;
+    Ok(Json(context.repo().get_task(&id).await?))
+}
+
+async fn update_task(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+    Json(input): Json<TaskInput>,
+) -> ApiResult<Json<Task>> {
+    validate_id(&id)?;
+    validate_title("title", &input.title)?;
+    let repo = context.repo();
+    repo.get_task(&id).await?;
+    Ok(Json(repo.update_task(&id, input).await?))
+}
+
+async fn delete_task(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    validate_id(&id)?;
+    let repo = context.repo();
+    repo.get_task(&id).await?;
+    repo.archive_task_cascade(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn complete_task(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Task>> {
+    validate_id(&id)?;
+    let repo = context.repo();
+    repo.get_task(&id).await?;
+    repo.complete_task(&id).await?;
+    Ok(Json(repo.get_task(&id).await?))
+}
+
+// Note endpoints
+
+#[derive(Debug, Deserialize)]
+struct NoteListQuery {
+    q: Option<String>,
+    /// Limits a search to a project, goal or life area; ignored without `q`
+    #[serde(flatten)]
+    scope: NoteSearchScope,
+}
+
+async fn list_notes(
+    State(context): State<ApiContext>,
+    Query(query): Query<NoteListQuery>,
+) -> ApiResult<Json<Vec<Note>>> {
+    let repo = context.repo();
+    let notes = match query.q {
+        Some(q) if !q.trim().is_empty() => {
+            validate_note_search_scope(&query.scope)?;
+            repo.search_notes(q.trim(), &query.scope, 50).await?
+        }
+        _ => repo.get_notes().await?,
+    };
+    Ok(Json(notes.into_iter().map(hide_private_content).collect()))
+}
+
+async fn create_note(
+    State(context): State<ApiContext>,
+    Json(input): Json<NoteInput>,
+) -> ApiResult<(StatusCode, Json<Note>)> {
+    validate_title("title", &input.title)?;
+    validate_not_private(input.is_private)?;
+    let note = context.repo().create_note(input).await?;
+    Ok((StatusCode::CREATED, Json(note)))
+}
+
+async fn get_note(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Note>> {
+    validate_id(&id)?;
+    Ok(Json(hide_private_content(context.repo().get_note(&id).await?)))
+}
+
+async fn update_note(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+    Json(input): Json<NoteInput>,
+) -> ApiResult<Json<Note>> {
+    validate_id(&id)?;
+    validate_title("title", &input.title)?;
+    validate_not_private(input.is_private)?;
+    let repo = context.repo();
+    if repo.get_note(&id).await?.is_private {
+        return Err(ApiError(AppError::new(
+            ErrorCode::Forbidden,
+            "Private notes can only be edited in the app",
+        )));
+    }
+    Ok(Json(repo.update_note(&id, input).await?))
+}
+
+/// Private note content stays encrypted at rest and is never served here
+fn hide_private_content(mut note: Note) -> Note {
+    if note.is_private {
+        note.content.clear();
+    }
+    note
+}
+
+async fn delete_note(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    validate_id(&id)?;
+    let repo = context.repo();
+    repo.get_note(&id).await?;
+    repo.archive_note(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Quick capture
+
+#[derive(Debug, Deserialize)]
+struct CaptureRequest {
+    text: String,
+    project_id: Option<String>,
+}
+
+/// Same semantics as the `quick_add` command: unknown projects fall back to the inbox
+async fn capture(
+    State(context): State<ApiContext>,
+    Json(request): Json<CaptureRequest>,
+) -> ApiResult<(StatusCode, Json<Task>)> {
+    let title = request.text.trim();
+    validate_title("text", title)?;
+
+    let task = context
+        .repo()
+        .quick_add_task(title, request.project_id.as_deref())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(task)))
+}
@@ -0,0 +1,182 @@
+//! App lock
+//!
+//! Users can protect EvorBrain with a passphrase. Only its Argon2 hash is
+//! stored (in the settings table). While the lock is engaged every command
+//! not flagged `available_while_locked` in the command registry is rejected
+//! with a `LOCKED` error, and so is every local API request. The app starts
+//! locked when a passphrase is set, and the idle timer locks it again after
+//! `auto_lock_minutes` without any command being invoked. Unlocking also
+//! loads the private note key, and locking drops it again.
+
+use crate::command_registry;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::log_error;
+use crate::note_encryption::NoteKey;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use evorbrain_core::events::{AppEvent, EventBus};
+use evorbrain_core::settings;
+use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+/// Shortest passphrase accepted by `set_app_lock`
+pub const MIN_PASSPHRASE_LENGTH: usize = 6;
+/// How often the idle timer checks for inactivity
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Lock state shared by the command guard, the API server and the idle timer
+pub struct AppLock {
+    /// A passphrase is set
+    enabled: AtomicBool,
+    locked: AtomicBool,
+    last_activity: Mutex<Instant>,
+    /// Key for private note content, only held while unlocked
+    note_key: Mutex<Option<NoteKey>>,
+}
+
+impl Default for AppLock {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            locked: AtomicBool::new(false),
+            last_activity: Mutex::new(Instant::now()),
+            note_key: Mutex::new(None),
+        }
+    }
+}
+
+impl AppLock {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+        if !enabled {
+            self.locked.store(false, Ordering::SeqCst);
+            self.set_note_key(None);
+        }
+    }
+
+    /// Engages the lock; returns false if it was already engaged
+    pub fn lock(&self) -> bool {
+        self.set_note_key(None);
+        !self.locked.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn unlock(&self, note_key: NoteKey) {
+        self.set_note_key(Some(note_key));
+        self.locked.store(false, Ordering::SeqCst);
+        self.touch();
+    }
+
+    pub fn note_key(&self) -> Option<NoteKey> {
+        self.note_key.lock().ok().and_then(|key| key.clone())
+    }
+
+    pub fn set_note_key(&self, note_key: Option<NoteKey>) {
+        if let Ok(mut key) = self.note_key.lock() {
+            *key = note_key;
+        }
+    }
+
+    /// Records user activity, resetting the idle timer
+    pub fn touch(&self) {
+        if let Ok(mut last_activity) = self.last_activity.lock() {
+            *last_activity = Instant::now();
+        }
+    }
+
+    /// Time since the last command was invoked
+    pub fn idle_for(&self) -> Duration {
+        self.last_activity
+            .lock()
+            .map(|last_activity| last_activity.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Rejects commands that aren't available while locked
+    pub fn check(&self) -> AppResult<()> {
+        if self.is_locked() {
+            return Err(AppError::new(ErrorCode::Locked, "EvorBrain is locked"));
+        }
+        Ok(())
+    }
+}
+
+/// Wraps the generated command handler so locked commands are rejected
+/// before they run
+pub fn guard<R: Runtime>(
+    lock: Arc<AppLock>,
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke: Invoke<R>| {
+        let available_while_locked = command_registry::find(invoke.message.command())
+            .is_some_and(|spec| spec.available_while_locked);
+
+        if !available_while_locked {
+            if let Err(e) = lock.check() {
+                invoke.resolver.reject(e);
+                return true;
+            }
+            lock.touch();
+        }
+        handler(invoke)
+    }
+}
+
+/// Hashes a passphrase with Argon2id and a random salt
+pub fn hash_passphrase(passphrase: &str) -> AppResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::new(ErrorCode::InternalError, "Failed to hash passphrase").with_details(e.to_string()))
+}
+
+/// Checks a passphrase against a stored hash
+pub fn verify_passphrase(passphrase: &str, hash: &str) -> AppResult<bool> {
+    let hash = PasswordHash::new(hash)
+        .map_err(|e| AppError::new(ErrorCode::InternalError, "Stored passphrase hash is invalid").with_details(e.to_string()))?;
+    Ok(Argon2::default().verify_password(passphrase.as_bytes(), &hash).is_ok())
+}
+
+/// Starts the idle timer that engages the lock after inactivity
+pub fn spawn_idle_timer(db: Arc<SqlitePool>, lock: Arc<AppLock>, bus: EventBus) {
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+        let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if !lock.is_enabled() || lock.is_locked() {
+                continue;
+            }
+
+            let minutes = match settings::load(&repo).await {
+                Ok(settings) => settings.auto_lock_minutes,
+                Err(e) => {
+                    log_error!("Failed to load settings", e);
+                    continue;
+                }
+            };
+            if minutes == 0 || lock.idle_for() < Duration::from_secs(u64::from(minutes) * 60) {
+                continue;
+            }
+
+            if lock.lock() {
+                bus.publish(AppEvent::new("app.locked", "app", "idle"));
+            }
+        }
+    });
+}
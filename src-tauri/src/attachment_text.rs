@@ -0,0 +1,468 @@
+//! Text of attachments for search
+//!
+//! The `attachment_text` job reads the stored files it hasn't looked at yet
+//! and indexes their text in the `attachment_text` full-text table, so
+//! `search_attachments` finds items by what their attached files say. Files
+//! are extracted once per content, however often they are attached.
+//!
+//! Text files, by MIME type or else extension, are read as UTF-8. PDFs give
+//! the strings their page content streams show, which covers what office
+//! suites and browsers write; scanned pages, encrypted files and fonts with
+//! two-byte character codes have no text to read without OCR or the fonts'
+//! character maps. Everything else is recorded as unsupported.
+//!
+//! Files over `MAX_SOURCE_BYTES` aren't read, a PDF's streams are inflated
+//! to `MAX_INFLATED_BYTES` at most and only the first `MAX_TEXT_CHARS` of
+//! the text are indexed. A file gets `FILE_TIME_LIMIT` and a run
+//! `RUN_TIME_LIMIT`; what is left waits for the next run.
+
+use crate::attachments::AttachmentStore;
+use crate::db::models::PendingAttachmentContent;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::log_info;
+use flate2::read::ZlibDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Larger files aren't read
+pub const MAX_SOURCE_BYTES: i64 = 20 * 1024 * 1024;
+/// Longest text indexed for one file
+pub const MAX_TEXT_CHARS: usize = 1_000_000;
+/// Most bytes the streams of one PDF are inflated to, all together
+const MAX_INFLATED_BYTES: u64 = 50 * 1024 * 1024;
+/// Extracting one file is given up after this long
+const FILE_TIME_LIMIT: Duration = Duration::from_secs(10);
+/// No file is started after a run has taken this long
+const RUN_TIME_LIMIT: Duration = Duration::from_secs(60);
+/// Files looked up at a time
+const BATCH_SIZE: i64 = 50;
+/// Bytes before a stream searched for its dictionary
+const MAX_DICTIONARY_BYTES: usize = 2048;
+/// Gap in a `TJ` array, in thousandths of the font size, taken as a space
+const WORD_GAP: f64 = 200.0;
+
+/// Extensions read as text when the MIME type doesn't tell
+const TEXT_EXTENSIONS: &[&str] = &[
+    "csv", "htm", "html", "ini", "json", "log", "markdown", "md", "tsv", "txt", "xml", "yaml", "yml",
+];
+
+/// Streams that hold images, fonts, metadata or other objects rather than
+/// page content
+const SKIPPED_STREAMS: &[&[u8]] = &[
+    b"/Image",
+    b"/Length1",
+    b"/Length2",
+    b"/FontFile",
+    b"/Type1C",
+    b"/CIDFontType0C",
+    b"/OpenType",
+    b"/Metadata",
+    b"/ObjStm",
+    b"/XRef",
+    b"/EmbeddedFile",
+];
+
+/// What a run of the job did
+#[derive(Debug, Default)]
+pub struct ExtractionRun {
+    pub indexed: usize,
+    pub unsupported: usize,
+    pub failed: usize,
+    /// Files whose text was dropped since no attachment refers to them anymore
+    pub removed: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Text,
+    Pdf,
+}
+
+/// What extracting one file gave
+enum Extraction {
+    Indexed(String),
+    /// Not a format text is read from, with the reason if it looked like one
+    Unsupported(Option<String>),
+    Failed(String),
+}
+
+/// The format to read a file as, judging by its MIME type or else its
+/// extension
+fn format_of(mime_type: Option<&str>, file_name: &str) -> Option<Format> {
+    if let Some(mime_type) = mime_type {
+        let mime_type = mime_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        if mime_type == "application/pdf" {
+            return Some(Format::Pdf);
+        }
+        if mime_type.starts_with("text/") || matches!(mime_type.as_str(), "application/json" | "application/xml") {
+            return Some(Format::Text);
+        }
+        if mime_type != "application/octet-stream" {
+            return None;
+        }
+    }
+    let extension = Path::new(file_name).extension()?.to_str()?.to_ascii_lowercase();
+    if extension == "pdf" {
+        Some(Format::Pdf)
+    } else {
+        TEXT_EXTENSIONS.contains(&extension.as_str()).then_some(Format::Text)
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|window| window == needle)
+}
+
+/// Collapses whitespace, drops control characters and cuts the text at
+/// `MAX_TEXT_CHARS`
+fn tidy(text: &str) -> String {
+    let mut tidied = String::new();
+    let mut chars = 0;
+    for word in text.split_whitespace() {
+        let word: String = word.chars().filter(|c| !c.is_control()).collect();
+        let word_chars = word.chars().count();
+        if word_chars == 0 {
+            continue;
+        }
+        if chars + word_chars + 1 > MAX_TEXT_CHARS {
+            break;
+        }
+        if !tidied.is_empty() {
+            tidied.push(' ');
+        }
+        tidied.push_str(&word);
+        chars += word_chars + 1;
+    }
+    tidied
+}
+
+/// The text of a text file; `None` for binary content
+fn plain_text(bytes: &[u8]) -> Option<String> {
+    // NUL bytes mean binary content, or UTF-16
+    if bytes.contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(bytes);
+    Some(text.strip_prefix('\u{feff}').unwrap_or(&text).to_string())
+}
+
+/// Decodes a PDF string, UTF-16 with a byte order mark or else taken as
+/// Latin-1; `None` for strings of two-byte character codes
+fn decode_pdf_string(bytes: &[u8]) -> Option<String> {
+    if let Some(utf16) = bytes.strip_prefix(&[0xfe, 0xff]) {
+        let units = utf16.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+        return Some(char::decode_utf16(units).filter_map(Result::ok).collect());
+    }
+    if bytes.contains(&0) {
+        return None;
+    }
+    Some(bytes.iter().map(|&byte| char::from(byte)).collect())
+}
+
+/// Reads a literal string starting after its `(`; returns its bytes and the
+/// position after its `)`
+fn literal_string(content: &[u8], mut i: usize) -> (Vec<u8>, usize) {
+    let mut bytes = Vec::new();
+    let mut depth = 0;
+    while let Some(&byte) = content.get(i) {
+        i += 1;
+        match byte {
+            b'\\' => {
+                let Some(&escaped) = content.get(i) else { break };
+                i += 1;
+                match escaped {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'b' | b'f' | b'\n' => {}
+                    b'\r' => {
+                        if content.get(i) == Some(&b'\n') {
+                            i += 1;
+                        }
+                    }
+                    b'0'..=b'7' => {
+                        let mut value = u32::from(escaped - b'0');
+                        for _ in 0..2 {
+                            let Some(&digit @ b'0'..=b'7') = content.get(i) else { break };
+                            value = value * 8 + u32::from(digit - b'0');
+                            i += 1;
+                        }
+                        // Overflow past a byte is ignored
+                        bytes.push((value & 0xff) as u8);
+                    }
+                    other => bytes.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                bytes.push(byte);
+            }
+            b')' if depth == 0 => break,
+            b')' => {
+                depth -= 1;
+                bytes.push(byte);
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    (bytes, i)
+}
+
+/// Reads a hex string starting after its `<`; returns its bytes and the
+/// position after its `>`
+fn hex_string(content: &[u8], mut i: usize) -> (Vec<u8>, usize) {
+    let mut digits = Vec::new();
+    while let Some(&byte) = content.get(i) {
+        i += 1;
+        match byte {
+            b'>' => break,
+            byte if byte.is_ascii_hexdigit() => digits.push(char::from(byte).to_digit(16).unwrap_or(0) as u8),
+            _ => {}
+        }
+    }
+    // A missing last digit is taken as 0
+    let bytes = digits.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0)).collect();
+    (bytes, i)
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    matches!(byte, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+fn is_regular(byte: u8) -> bool {
+    !byte.is_ascii_whitespace() && byte != 0 && !is_delimiter(byte)
+}
+
+/// Appends the strings a content stream shows to `text`
+fn content_text(content: &[u8], text: &mut String) {
+    // Strings since the last operator, shown by the next one
+    let mut operands = String::new();
+    let mut in_text = false;
+    let mut in_array = false;
+    let mut i = 0;
+
+    while let Some(&byte) = content.get(i) {
+        match byte {
+            b'%' => {
+                while content.get(i).is_some_and(|&byte| byte != b'\n' && byte != b'\r') {
+                    i += 1;
+                }
+            }
+            b'(' => {
+                let (bytes, end) = literal_string(content, i + 1);
+                operands.extend(decode_pdf_string(&bytes));
+                i = end;
+            }
+            b'<' if content.get(i + 1) == Some(&b'<') => i += 2,
+            b'<' => {
+                let (bytes, end) = hex_string(content, i + 1);
+                operands.extend(decode_pdf_string(&bytes));
+                i = end;
+            }
+            b'[' | b']' => {
+                in_array = byte == b'[';
+                i += 1;
+            }
+            b'/' => {
+                i += 1;
+                while content.get(i).is_some_and(|&byte| is_regular(byte)) {
+                    i += 1;
+                }
+            }
+            byte if !is_regular(byte) => i += 1,
+            _ => {
+                let start = i;
+                while content.get(i).is_some_and(|&byte| is_regular(byte)) {
+                    i += 1;
+                }
+                let token = &content[start..i];
+                if let Some(number) = std::str::from_utf8(token).ok().and_then(|token| token.parse::<f64>().ok()) {
+                    if in_array && number < -WORD_GAP {
+                        operands.push(' ');
+                    }
+                    continue;
+                }
+
+                match token {
+                    b"BT" => in_text = true,
+                    b"ET" => {
+                        in_text = false;
+                        text.push('\n');
+                    }
+                    b"Tj" | b"TJ" if in_text => text.push_str(&operands),
+                    b"'" | b"\"" if in_text => {
+                        text.push('\n');
+                        text.push_str(&operands);
+                    }
+                    b"Td" | b"TD" | b"T*" | b"Tm" if in_text => text.push(' '),
+                    // Inline image data, up to the EI operator
+                    b"ID" => {
+                        while i < content.len() && !(content[i..].starts_with(b"EI") && content[i - 1].is_ascii_whitespace()) {
+                            i += 1;
+                        }
+                        i = (i + 2).min(content.len());
+                    }
+                    _ => {}
+                }
+                operands.clear();
+            }
+        }
+    }
+}
+
+/// The text shown on the pages of a PDF; `None` when it is encrypted
+fn pdf_text(bytes: &[u8]) -> AppResult<Option<String>> {
+    if find(&bytes[..bytes.len().min(1024)], b"%PDF-").is_none() {
+        return Err(AppError::validation_error("file", "is not a PDF"));
+    }
+    if find(bytes, b"/Encrypt").is_some() {
+        return Ok(None);
+    }
+
+    let mut text = String::new();
+    let mut inflate_budget = MAX_INFLATED_BYTES;
+    let mut position = 0;
+    while let Some(found) = find(&bytes[position..], b"stream") {
+        let keyword = position + found;
+        position = keyword + b"stream".len();
+        if bytes[..keyword].ends_with(b"end") {
+            continue;
+        }
+        // The data starts on the line after the keyword
+        let data_start = match &bytes[position..] {
+            [b'\r', b'\n', ..] => position + 2,
+            [b'\n', ..] => position + 1,
+            _ => continue,
+        };
+        let Some(length) = find(&bytes[data_start..], b"endstream") else { break };
+        let data = &bytes[data_start..data_start + length];
+        position = data_start + length + b"endstream".len();
+
+        let window = &bytes[keyword.saturating_sub(MAX_DICTIONARY_BYTES)..keyword];
+        let dictionary = rfind(window, b"obj").map_or(window, |start| &window[start..]);
+        if SKIPPED_STREAMS.iter().any(|key| find(dictionary, key).is_some()) {
+            continue;
+        }
+
+        let content = if find(dictionary, b"/Filter").is_none() {
+            data.to_vec()
+        } else if find(dictionary, b"/FlateDecode").is_some() {
+            let mut content = Vec::new();
+            // A damaged stream still gives the text up to where it breaks
+            let _ = ZlibDecoder::new(data).take(inflate_budget + 1).read_to_end(&mut content);
+            if content.len() as u64 > inflate_budget {
+                break;
+            }
+            inflate_budget -= content.len() as u64;
+            content
+        } else {
+            continue;
+        };
+        content_text(&content, &mut text);
+        // Enough to fill the index, however the whitespace collapses
+        if text.len() > 2 * MAX_TEXT_CHARS {
+            break;
+        }
+    }
+    Ok(Some(text))
+}
+
+/// Reads the text of a stored file
+fn extract(path: &Path, format: Format) -> Extraction {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => return Extraction::Failed(format!("Failed to read file: {}", e)),
+    };
+    let text = match format {
+        Format::Text => plain_text(&bytes).ok_or("Binary content"),
+        Format::Pdf => match pdf_text(&bytes) {
+            Ok(text) => text.ok_or("Encrypted PDF"),
+            Err(e) => return Extraction::Failed(e.to_string()),
+        },
+    };
+    match text {
+        Ok(text) => Extraction::Indexed(tidy(&text)),
+        Err(reason) => Extraction::Unsupported(Some(reason.to_string())),
+    }
+}
+
+async fn extract_content(store: &AttachmentStore, content: &PendingAttachmentContent) -> Extraction {
+    let Some(format) = format_of(content.mime_type.as_deref(), &content.file_name) else {
+        return Extraction::Unsupported(None);
+    };
+    if content.size_bytes > MAX_SOURCE_BYTES {
+        return Extraction::Unsupported(Some(format!("Larger than {} MB", MAX_SOURCE_BYTES / 1024 / 1024)));
+    }
+    let path = match store.path_for(&content.content_hash) {
+        Ok(path) => path,
+        Err(e) => return Extraction::Failed(e.to_string()),
+    };
+
+    // The work itself is bounded by the size limits, so one that runs over
+    // still ends soon after
+    let work = tauri::async_runtime::spawn_blocking(move || extract(&path, format));
+    match tokio::time::timeout(FILE_TIME_LIMIT, work).await {
+        Ok(Ok(extraction)) => extraction,
+        Ok(Err(e)) => Extraction::Failed(
+            AppError::new(ErrorCode::InternalError, "Extraction task failed").with_details(e.to_string()).to_string(),
+        ),
+        Err(_) => Extraction::Failed(format!("Took longer than {} seconds", FILE_TIME_LIMIT.as_secs())),
+    }
+}
+
+/// Indexes the text of stored files no extraction was recorded for, until
+/// none are left or the run has taken `RUN_TIME_LIMIT`, and drops the text
+/// of files no attachment refers to anymore
+pub async fn extract_pending(repo: &Repository, store: &AttachmentStore) -> AppResult<ExtractionRun> {
+    let mut run = ExtractionRun {
+        removed: repo.remove_unreferenced_attachment_text().await?,
+        ..Default::default()
+    };
+    let started = Instant::now();
+
+    'run: loop {
+        let pending = repo.get_pending_attachment_content(BATCH_SIZE).await?;
+        if pending.is_empty() {
+            break;
+        }
+        for content in pending {
+            if started.elapsed() > RUN_TIME_LIMIT {
+                break 'run;
+            }
+            match extract_content(store, &content).await {
+                Extraction::Indexed(text) => {
+                    let text = (!text.is_empty()).then_some(text.as_str());
+                    repo.record_attachment_text(&content.content_hash, "indexed", text, None).await?;
+                    run.indexed += 1;
+                }
+                Extraction::Unsupported(reason) => {
+                    repo.record_attachment_text(&content.content_hash, "unsupported", None, reason.as_deref())
+                        .await?;
+                    run.unsupported += 1;
+                }
+                Extraction::Failed(error) => {
+                    repo.record_attachment_text(&content.content_hash, "failed", None, Some(&error)).await?;
+                    run.failed += 1;
+                }
+            }
+        }
+    }
+
+    if run.indexed + run.unsupported + run.failed > 0 || run.removed > 0 {
+        log_info!(
+            "Attachment text extracted",
+            &format!(
+                "{} indexed, {} unsupported, {} failed, {} removed",
+                run.indexed, run.unsupported, run.failed, run.removed
+            )
+        );
+    }
+    Ok(run)
+}
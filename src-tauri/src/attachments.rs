@@ -0,0 +1,285 @@
+//! Content-addressed attachment storage
+//!
+//! Attachment contents are stored under the app data directory as
+//! `attachments/<first two hex digits>/<sha256 hex>`, so adding the same file
+//! twice (to the same or different entities) keeps one copy on disk. The
+//! `attachments` table maps entities to content hashes; a file is removed
+//! once no row references its hash. Files that end up unreferenced anyway
+//! (e.g. after a crash between the row delete and the file delete) are found
+//! by `scan_orphans`.
+//!
+//! Image attachments get PNG thumbnails, cached as
+//! `attachments/thumbnails/<sha256 hex>-<size>.png` for a few fixed sizes.
+//! Thumbnails belong to the content, not the attachment, so they are shared
+//! the same way and removed together with it.
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use image::{ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+const HASH_LENGTH: usize = 64;
+/// Temporary files being hashed live here until they are moved into place
+const INCOMING_DIR: &str = "incoming";
+/// Temporary files older than this are leftovers from an interrupted copy
+const STALE_INCOMING_AGE: Duration = Duration::from_secs(60 * 60);
+/// Cached thumbnails live here
+const THUMBNAILS_DIR: &str = "thumbnails";
+/// Thumbnail sizes (longest edge, in pixels) that can be requested
+pub const THUMBNAIL_SIZES: &[u32] = &[64, 128, 256, 512];
+/// Size generated when an image is attached
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+/// Larger images aren't decoded, to keep memory use bounded
+const MAX_IMAGE_DIMENSION: u32 = 16_384;
+
+fn io_error(message: &str, error: io::Error) -> AppError {
+    AppError::new(ErrorCode::IoError, message).with_details(error.to_string())
+}
+
+fn is_content_hash(name: &str) -> bool {
+    name.len() == HASH_LENGTH && name.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase())
+}
+
+/// The content hash a cached thumbnail was generated from
+fn thumbnail_source(name: &str) -> Option<&str> {
+    let (hash, size) = name.strip_suffix(".png")?.split_once('-')?;
+    (is_content_hash(hash) && size.parse::<u32>().is_ok()).then_some(hash)
+}
+
+/// Whether a file looks like an image the thumbnailer can read, judging by
+/// its MIME type or else its extension
+pub fn is_image(mime_type: Option<&str>, file_name: &str) -> bool {
+    match mime_type {
+        Some(mime_type) => mime_type.starts_with("image/"),
+        None => ImageFormat::from_path(file_name).is_ok_and(|format| format.reading_enabled()),
+    }
+}
+
+/// Rejects a file for exceeding the per-file limit or the total quota of
+/// the `attachment_max_file_mb` and `attachment_quota_mb` settings; the data
+/// says which limit and by how much
+pub fn quota_exceeded(limit: &str, limit_bytes: u64, size_bytes: u64, used_bytes: u64) -> AppError {
+    let message = match limit {
+        "file" => format!("File is larger than the {} MB limit per attachment", limit_bytes / 1024 / 1024),
+        _ => format!("Attachments would exceed the {} MB storage quota", limit_bytes / 1024 / 1024),
+    };
+    AppError::new(ErrorCode::QuotaExceeded, message).with_data(serde_json::json!({
+        "limit": limit,
+        "limit_bytes": limit_bytes,
+        "size_bytes": size_bytes,
+        "used_bytes": used_bytes,
+    }))
+}
+
+/// A stored file's content hash and size
+#[derive(Debug, Clone)]
+pub struct StoredContent {
+    pub content_hash: String,
+    pub size_bytes: u64,
+    /// The content wasn't stored before, so it takes up new space
+    pub is_new: bool,
+}
+
+pub struct AttachmentStore {
+    root: PathBuf,
+}
+
+impl AttachmentStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Where the content with `content_hash` is stored
+    pub fn path_for(&self, content_hash: &str) -> AppResult<PathBuf> {
+        if !is_content_hash(content_hash) {
+            return Err(AppError::validation_error("content_hash", "is not a SHA-256 hex digest"));
+        }
+        Ok(self.root.join(&content_hash[..2]).join(content_hash))
+    }
+
+    /// Copies a file of at most `max_size` bytes into the store, returning
+    /// its hash; content that is already stored is not written again
+    pub fn store_file(&self, source: &Path, max_size: u64) -> AppResult<StoredContent> {
+        let mut input = File::open(source).map_err(|e| io_error("Failed to open file", e))?;
+        let size_bytes = input.metadata().map_err(|e| io_error("Failed to read file", e))?.len();
+        if size_bytes > max_size {
+            return Err(quota_exceeded("file", max_size, size_bytes, 0));
+        }
+        self.store(&mut input, size_bytes)
+    }
+
+    /// Stores content of at most `max_size` bytes that is already in memory,
+    /// such as a file from a project bundle
+    pub fn store_bytes(&self, mut content: &[u8], max_size: u64) -> AppResult<StoredContent> {
+        let size_bytes = content.len() as u64;
+        if size_bytes > max_size {
+            return Err(quota_exceeded("file", max_size, size_bytes, 0));
+        }
+        self.store(&mut content, size_bytes)
+    }
+
+    fn store(&self, input: &mut impl Read, size_bytes: u64) -> AppResult<StoredContent> {
+        let incoming = self.root.join(INCOMING_DIR);
+        fs::create_dir_all(&incoming).map_err(|e| io_error("Failed to create attachment directory", e))?;
+        let temp_path = incoming.join(Uuid::new_v4().to_string());
+
+        let result = (|| {
+            let mut output = File::create(&temp_path).map_err(|e| io_error("Failed to store file", e))?;
+            let mut hasher = Sha256::new();
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let read = input.read(&mut buffer).map_err(|e| io_error("Failed to read file", e))?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+                output.write_all(&buffer[..read]).map_err(|e| io_error("Failed to store file", e))?;
+            }
+            output.sync_all().map_err(|e| io_error("Failed to store file", e))?;
+
+            let content_hash = hex::encode(hasher.finalize());
+            let target = self.path_for(&content_hash)?;
+            let is_new = !target.exists();
+            if is_new {
+                if let Some(dir) = target.parent() {
+                    fs::create_dir_all(dir).map_err(|e| io_error("Failed to create attachment directory", e))?;
+                }
+                fs::rename(&temp_path, &target).map_err(|e| io_error("Failed to store file", e))?;
+            }
+            Ok(StoredContent { content_hash, size_bytes, is_new })
+        })();
+
+        let _ = fs::remove_file(&temp_path);
+        result
+    }
+
+    /// Removes stored content and its thumbnails; missing files are ignored
+    pub fn remove(&self, content_hash: &str) -> AppResult<()> {
+        match fs::remove_file(self.path_for(content_hash)?) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(io_error("Failed to delete attachment", e)),
+            _ => {}
+        }
+        for size in THUMBNAIL_SIZES {
+            let _ = fs::remove_file(self.thumbnail_path(content_hash, *size));
+        }
+        Ok(())
+    }
+
+    fn thumbnail_path(&self, content_hash: &str, size: u32) -> PathBuf {
+        self.root.join(THUMBNAILS_DIR).join(format!("{}-{}.png", content_hash, size))
+    }
+
+    /// Returns the path of a thumbnail of stored image content, generating
+    /// and caching it on first use
+    pub fn thumbnail(&self, content_hash: &str, size: u32) -> AppResult<PathBuf> {
+        if !THUMBNAIL_SIZES.contains(&size) {
+            let sizes: Vec<String> = THUMBNAIL_SIZES.iter().map(u32::to_string).collect();
+            return Err(AppError::validation_error(
+                "size",
+                &format!("must be one of {}", sizes.join(", ")),
+            ));
+        }
+        let source = self.path_for(content_hash)?;
+        let target = self.thumbnail_path(content_hash, size);
+        if target.exists() {
+            return Ok(target);
+        }
+
+        let mut reader = ImageReader::open(&source)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| io_error("Failed to open attachment", e))?;
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+        limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+        reader.limits(limits);
+        let image = reader
+            .decode()
+            .map_err(|e| AppError::validation_error("attachment", &format!("is not a readable image: {}", e)))?;
+
+        let dir = self.root.join(THUMBNAILS_DIR);
+        fs::create_dir_all(&dir).map_err(|e| io_error("Failed to create thumbnail directory", e))?;
+        // Written under a temporary name so a half-written thumbnail is never served
+        let temp_path = dir.join(format!("{}.tmp", Uuid::new_v4()));
+        let result = image
+            .thumbnail(size, size)
+            .save_with_format(&temp_path, ImageFormat::Png)
+            .map_err(|e| AppError::new(ErrorCode::IoError, "Failed to write thumbnail").with_details(e.to_string()))
+            .and_then(|_| fs::rename(&temp_path, &target).map_err(|e| io_error("Failed to write thumbnail", e)));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result.map(|_| target)
+    }
+
+    /// Overwrites stored content with zeros before removing it, for
+    /// `secure_delete`
+    pub fn shred(&self, content_hash: &str) -> AppResult<()> {
+        let path = self.path_for(content_hash)?;
+        let mut file = match fs::OpenOptions::new().write(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(io_error("Failed to open attachment", e)),
+        };
+
+        let len = file.metadata().map_err(|e| io_error("Failed to read attachment", e))?.len();
+        let zeros = vec![0u8; 64 * 1024];
+        let mut written = 0;
+        file.rewind().map_err(|e| io_error("Failed to shred attachment", e))?;
+        while written < len {
+            let chunk = zeros.len().min((len - written) as usize);
+            file.write_all(&zeros[..chunk]).map_err(|e| io_error("Failed to shred attachment", e))?;
+            written += chunk as u64;
+        }
+        file.sync_all().map_err(|e| io_error("Failed to shred attachment", e))?;
+        drop(file);
+
+        self.remove(content_hash)
+    }
+
+    /// Lists stored files (path and size) whose hash isn't in `referenced`,
+    /// thumbnails of such content, plus stale temporary files
+    pub fn scan_orphans(&self, referenced: &HashSet<String>) -> AppResult<Vec<(PathBuf, u64)>> {
+        let mut orphans = Vec::new();
+        let dirs = match fs::read_dir(&self.root) {
+            Ok(dirs) => dirs,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(orphans),
+            Err(e) => return Err(io_error("Failed to read attachment directory", e)),
+        };
+
+        for dir in dirs.flatten() {
+            let is_incoming = dir.file_name() == INCOMING_DIR;
+            let is_thumbnails = dir.file_name() == THUMBNAILS_DIR;
+            let Ok(files) = fs::read_dir(dir.path()) else { continue };
+            for file in files.flatten() {
+                let Ok(metadata) = file.metadata() else { continue };
+                let name = file.file_name().to_string_lossy().into_owned();
+                let is_stale = || {
+                    metadata
+                        .modified()
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_INCOMING_AGE)
+                };
+                let orphaned = if is_incoming {
+                    is_stale()
+                } else if is_thumbnails {
+                    match thumbnail_source(&name) {
+                        Some(hash) => !referenced.contains(hash),
+                        None => is_stale(),
+                    }
+                } else {
+                    is_content_hash(&name) && !referenced.contains(&name)
+                };
+                if orphaned {
+                    orphans.push((file.path(), metadata.len()));
+                }
+            }
+        }
+        Ok(orphans)
+    }
+}
@@ -0,0 +1,56 @@
+//! Completion watcher
+//!
+//! Checks, whenever a task or project is completed, whether that finished
+//! its project or goal, and completes it or suggests doing so as the
+//! `auto_complete` setting says. A project completed this way shows up in
+//! the change feed as `project.completed` in turn, so its goal is checked
+//! right after. Nothing is written while read-only mode is on.
+
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::read_only::ReadOnlyMode;
+use crate::{log_error, log_info};
+use evorbrain_core::auto_complete::{self, Finished};
+use evorbrain_core::events::{AppEvent, EventBus};
+use evorbrain_core::settings;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+async fn check(repo: &Repository, event: &AppEvent) -> AppResult<Option<Finished>> {
+    let mode = settings::load(repo).await?.auto_complete;
+    match event.name.as_str() {
+        "task.completed" => auto_complete::after_task_completed(repo, &event.entity_id, mode).await,
+        "project.completed" => auto_complete::after_project_completed(repo, &event.entity_id, mode).await,
+        _ => Ok(None),
+    }
+}
+
+/// Starts the background task that completes finished projects and goals
+pub fn spawn_completion_watcher(db: Arc<SqlitePool>, read_only: Arc<ReadOnlyMode>, bus: EventBus) {
+    let mut receiver = bus.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            if read_only.is_enabled() {
+                continue;
+            }
+
+            match check(&repo, &event).await {
+                Ok(Some(finished)) => {
+                    log_info!("Auto complete", &format!("{} {}", finished.event_name(), finished.id));
+                    bus.publish(AppEvent::new(finished.event_name(), finished.entity_type, finished.id));
+                }
+                Ok(None) => {}
+                Err(e) => log_error!("Failed to check for finished projects and goals", e),
+            }
+        }
+    });
+}
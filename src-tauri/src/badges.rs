@@ -0,0 +1,63 @@
+//! Badge count provider for tray, menu-bar and dock badges
+//!
+//! Recomputes the counts whenever a task event arrives on the event bus (and
+//! once a minute, so items become overdue at midnight) and emits
+//! `badge-counts-changed` only when the numbers actually change.
+
+use crate::db::models::BadgeCounts;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::log_error;
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::events::EventBus;
+use evorbrain_core::settings;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counts for today in the user's time zone, matching `get_todays_tasks`
+pub async fn current_counts(repo: &Repository) -> AppResult<BadgeCounts> {
+    let calendar = Calendar::from_settings(&settings::load(repo).await?);
+    let today = calendar.day(calendar.today());
+    repo.get_badge_counts(today.start, today.end).await
+}
+
+/// Starts the background task that pushes `badge-counts-changed` events
+pub fn spawn_badge_watcher(app_handle: AppHandle, db: Arc<SqlitePool>, bus: &EventBus) {
+    let mut receiver = bus.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+        let mut last_counts: Option<BadgeCounts> = None;
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                received = receiver.recv() => match received {
+                    Ok(event) if event.entity_type != "task" => continue,
+                    Ok(_) | Err(RecvError::Lagged(_)) => {
+                        // Coalesce a burst of changes into a single recount
+                        while receiver.try_recv().is_ok() {}
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+
+            match current_counts(&repo).await {
+                Ok(counts) if last_counts != Some(counts) => {
+                    last_counts = Some(counts);
+                    if let Err(e) = app_handle.emit("badge-counts-changed", counts) {
+                        log_error!("Failed to emit badge counts", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log_error!("Failed to compute badge counts", e),
+            }
+        }
+    });
+}
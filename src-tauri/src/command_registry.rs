@@ -0,0 +1,911 @@
+//! Machine-readable metadata for every registered Tauri command
+//!
+//! `COMMANDS` mirrors the `generate_handler!` list in `lib.rs` and must be
+//! updated alongside it. Besides powering `list_commands` (for the command
+//! palette and external automation), the `mutating` and `destructive` flags
+//! tell callers which commands write data and which can't be undone, and
+//! `available_while_locked` marks the few commands the app lock lets through
+//! and `available_while_read_only` the few mutating ones read-only mode does.
+//! `rate_limit` caps how often the expensive ones may be invoked.
+//!
+//! Parameter names are given as the frontend passes them: Tauri converts
+//! snake_case Rust arguments to camelCase.
+
+use crate::confirmation::CONFIRMED_COMMANDS;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+#[derive(Debug, Clone, Copy)]
+pub enum ParamKind {
+    String,
+    Integer,
+    Boolean,
+    /// One of a fixed set of strings
+    Enum(&'static [&'static str]),
+    /// A structured argument, named after its Rust type
+    Object(&'static str),
+    /// A list of the named Rust type
+    Array(&'static str),
+    /// A list of strings
+    StringArray,
+}
+
+impl ParamKind {
+    fn schema(&self) -> Value {
+        match self {
+            ParamKind::String => json!({ "type": "string" }),
+            ParamKind::Integer => json!({ "type": "integer" }),
+            ParamKind::Boolean => json!({ "type": "boolean" }),
+            ParamKind::Enum(values) => json!({ "type": "string", "enum": values }),
+            ParamKind::Object(name) => json!({ "type": "object", "title": name }),
+            ParamKind::Array(name) => json!({ "type": "array", "items": { "type": "object", "title": name } }),
+            ParamKind::StringArray => json!({ "type": "array", "items": { "type": "string" } }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub required: bool,
+}
+
+const fn required(name: &'static str, kind: ParamKind) -> ParamSpec {
+    ParamSpec { name, kind, required: true }
+}
+
+const fn optional(name: &'static str, kind: ParamKind) -> ParamSpec {
+    ParamSpec { name, kind, required: false }
+}
+
+const ID: ParamSpec = required("id", ParamKind::String);
+/// Token from `request_destructive_confirmation`
+const CONFIRMATION_TOKEN: ParamSpec = required("confirmationToken", ParamKind::String);
+/// The same, for commands that only need one for their destructive variant
+const OPTIONAL_CONFIRMATION_TOKEN: ParamSpec = optional("confirmationToken", ParamKind::String);
+/// Page of a v2 list command, from 1
+const PAGE: ParamSpec = optional("page", ParamKind::Integer);
+const PAGE_SIZE: ParamSpec = optional("pageSize", ParamKind::Integer);
+/// Caller-chosen UUID carried by the chunk events of a streaming command
+const STREAM_ID: ParamSpec = required("streamId", ParamKind::String);
+
+/// Most calls of a command accepted within a window of seconds
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RateLimit {
+    pub calls: u32,
+    pub per_secs: u32,
+}
+
+/// Searches run as the user types
+const SEARCH_LIMIT: RateLimit = RateLimit { calls: 10, per_secs: 1 };
+/// Statistics and reports that scan whole tables
+const REPORT_LIMIT: RateLimit = RateLimit { calls: 5, per_secs: 10 };
+/// Exports that serialize the whole database
+const EXPORT_LIMIT: RateLimit = RateLimit { calls: 2, per_secs: 30 };
+
+#[derive(Debug, Clone, Copy)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: &'static [ParamSpec],
+    /// Writes to the database or changes application state
+    pub mutating: bool,
+    /// Removes data or otherwise can't be undone with a restore command
+    pub destructive: bool,
+    /// Can be invoked while the app lock is engaged
+    pub available_while_locked: bool,
+    /// Can be invoked in read-only mode although it's mutating, as it
+    /// doesn't write to the database
+    pub available_while_read_only: bool,
+    /// Calls past the limit are rejected with `RATE_LIMITED`
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl CommandSpec {
+    const fn new(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            params: &[],
+            mutating: false,
+            destructive: false,
+            available_while_locked: false,
+            available_while_read_only: false,
+            rate_limit: None,
+        }
+    }
+
+    const fn params(mut self, params: &'static [ParamSpec]) -> Self {
+        self.params = params;
+        self
+    }
+
+    const fn mutating(mut self) -> Self {
+        self.mutating = true;
+        self
+    }
+
+    const fn destructive(mut self) -> Self {
+        self.mutating = true;
+        self.destructive = true;
+        self
+    }
+
+    const fn available_while_locked(mut self) -> Self {
+        self.available_while_locked = true;
+        self
+    }
+
+    const fn available_while_read_only(mut self) -> Self {
+        self.available_while_read_only = true;
+        self
+    }
+
+    const fn rate_limited(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// JSON Schema describing the command's arguments object
+    pub fn params_schema(&self) -> Value {
+        let mut properties = Map::new();
+        for param in self.params {
+            properties.insert(param.name.to_string(), param.kind.schema());
+        }
+        let required: Vec<&str> = self.params.iter().filter(|p| p.required).map(|p| p.name).collect();
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+}
+
+const PROJECT_STATUSES: &[&str] = &["planning", "active", "onhold", "completed", "cancelled"];
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+const JIRA_FORMATS: &[&str] = &["csv", "json"];
+const STARTER_PRESETS: &[&str] = &["standard", "minimal", "skip"];
+const SECURE_DELETE_TYPES: &[&str] = &["life_area", "goal", "project", "task", "note"];
+const TASK_SORTS: &[&str] = &["priority", "urgency"];
+const INCUBATABLE_TYPES: &[&str] = &["goal", "project"];
+const REPAIR_STRATEGIES: &[&str] = &["detach", "archive", "clear_timestamp"];
+const GRAPH_FORMATS: &[&str] = &["json", "graphml"];
+const JOB_TYPES: &[&str] = &[
+    "archive_retention",
+    "attachment_text",
+    "email_import",
+    "github_sync",
+    "stats_snapshot",
+    "weekly_digest",
+];
+const AREA_CONTEXTS: &[&str] = &["personal", "work"];
+const VIEW_TYPES: &[&str] = &["board", "list", "calendar", "timeline"];
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("greet", "Returns a greeting, for testing the IPC bridge")
+        .params(&[required("name", ParamKind::String)])
+        .available_while_locked(),
+    CommandSpec::new("test_database", "Checks that the database answers queries"),
+    CommandSpec::new("list_commands", "Lists every command with its parameters and flags")
+        .available_while_locked(),
+    // App lock commands
+    CommandSpec::new("get_app_lock_status", "Shows whether the app lock is set and engaged")
+        .available_while_locked(),
+    CommandSpec::new("set_app_lock", "Sets, changes or removes the app lock passphrase")
+        .params(&[
+            optional("currentPassphrase", ParamKind::String),
+            optional("passphrase", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("unlock", "Unlocks the app with the passphrase")
+        .params(&[required("passphrase", ParamKind::String)])
+        .available_while_locked(),
+    CommandSpec::new("lock", "Engages the app lock immediately").available_while_locked(),
+    // Read-only mode commands
+    CommandSpec::new("get_read_only_mode", "Shows whether read-only mode is on"),
+    CommandSpec::new("set_read_only_mode", "Turns read-only mode, which blocks all changes, on or off")
+        .params(&[required("enabled", ParamKind::Boolean)])
+        .mutating()
+        .available_while_read_only(),
+    // Onboarding commands
+    CommandSpec::new("create_starter_template", "Seeds starter life areas, goals and a getting-started project")
+        .params(&[required("preset", ParamKind::Enum(STARTER_PRESETS))])
+        .mutating(),
+    // Workflow commands
+    CommandSpec::new("get_workflow", "Returns the task statuses in effect for a project")
+        .params(&[optional("projectId", ParamKind::String)]),
+    CommandSpec::new("set_workflow", "Replaces a project's task statuses; an empty list restores the default")
+        .params(&[
+            required("projectId", ParamKind::String),
+            required("statuses", ParamKind::Array("WorkflowStatusInput")),
+        ])
+        .mutating(),
+    CommandSpec::new("set_task_status", "Moves a task to another status of its project's workflow")
+        .params(&[required("id", ParamKind::String), required("status", ParamKind::String)])
+        .mutating(),
+    // View preference commands
+    CommandSpec::new("get_view_preferences", "Returns how a view of a project is grouped, sorted and laid out")
+        .params(&[
+            required("projectId", ParamKind::String),
+            required("viewType", ParamKind::Enum(VIEW_TYPES)),
+        ]),
+    CommandSpec::new("set_view_preferences", "Saves how a view of a project is laid out; omit it to restore the defaults")
+        .params(&[
+            required("projectId", ParamKind::String),
+            required("viewType", ParamKind::Enum(VIEW_TYPES)),
+            optional("preferences", ParamKind::Object("ViewPreferencesInput")),
+        ])
+        .mutating(),
+    // Scheduling commands
+    CommandSpec::new("add_working_days", "Counts working days from a date, skipping weekends and holidays")
+        .params(&[optional("date", ParamKind::String), required("days", ParamKind::Integer)]),
+    CommandSpec::new("defer_task_to_next_working_day", "Moves a task's due date to the next working day")
+        .params(&[required("id", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("set_task_estimate", "Sets how many minutes a task is expected to take")
+        .params(&[required("id", ParamKind::String), optional("minutes", ParamKind::Integer)])
+        .mutating(),
+    CommandSpec::new("set_task_actual_minutes", "Corrects how many minutes were spent on a task")
+        .params(&[required("id", ParamKind::String), required("minutes", ParamKind::Integer)])
+        .mutating(),
+    CommandSpec::new("set_task_recurrence", "Sets or clears the RRULE a task repeats by")
+        .params(&[required("id", ParamKind::String), optional("rule", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("get_upcoming_occurrences", "Lists the coming occurrences of a repeating task")
+        .params(&[required("id", ParamKind::String), optional("limit", ParamKind::Integer)]),
+    CommandSpec::new("complete_recurring_task", "Completes a task and creates the next instance if it repeats")
+        .params(&[required("id", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("set_recurrence_exceptions", "Sets the dates a repeating task skips")
+        .params(&[required("id", ParamKind::String), required("dates", ParamKind::StringArray)])
+        .mutating(),
+    CommandSpec::new("suggest_due_date", "Proposes a due date on a working day with room for the task, with the reason")
+        .params(&[optional("taskId", ParamKind::String), optional("estimatedMinutes", ParamKind::Integer)]),
+    // My Day commands
+    CommandSpec::new("get_my_day", "Shows a day's focus list with the tasks due that day")
+        .params(&[optional("date", ParamKind::String)]),
+    CommandSpec::new("add_to_my_day", "Adds a task to a day's focus list")
+        .params(&[
+            required("taskId", ParamKind::String),
+            optional("date", ParamKind::String),
+            optional("plannedMinutes", ParamKind::Integer),
+        ])
+        .mutating(),
+    CommandSpec::new("remove_from_my_day", "Removes a task from a day's focus list")
+        .params(&[required("taskId", ParamKind::String), optional("date", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("get_day_capacity", "Compares a day's estimated work with its working hours")
+        .params(&[optional("date", ParamKind::String)]),
+    CommandSpec::new("get_day_plan", "Shows a day's focus list as a timeboxed plan")
+        .params(&[optional("date", ParamKind::String)]),
+    CommandSpec::new("set_planned_minutes", "Sets the minutes planned for a task on a day's plan")
+        .params(&[
+            required("taskId", ParamKind::String),
+            optional("minutes", ParamKind::Integer),
+            optional("date", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("reorder_day_plan", "Reorders the tasks on a day's plan")
+        .params(&[required("taskIds", ParamKind::StringArray), optional("date", ParamKind::String)])
+        .mutating(),
+    // Context commands
+    CommandSpec::new("get_contexts", "Lists GTD contexts with their open task counts"),
+    CommandSpec::new("get_task_contexts", "Lists the contexts of a task")
+        .params(&[required("taskId", ParamKind::String)]),
+    CommandSpec::new("set_task_contexts", "Replaces a task's contexts, creating new ones as needed")
+        .params(&[
+            required("taskId", ParamKind::String),
+            required("contexts", ParamKind::StringArray),
+        ])
+        .mutating(),
+    CommandSpec::new("get_tasks_by_context", "Lists open tasks in a context")
+        .params(&[required("context", ParamKind::String)]),
+    CommandSpec::new("delete_context", "Deletes a context; its tasks are kept")
+        .params(&[required("id", ParamKind::String), CONFIRMATION_TOKEN])
+        .destructive(),
+    // People commands
+    CommandSpec::new("get_people", "Lists people tasks can be delegated to"),
+    CommandSpec::new("create_person", "Adds a person")
+        .params(&[required("input", ParamKind::Object("PersonInput"))])
+        .mutating(),
+    CommandSpec::new("update_person", "Updates a person")
+        .params(&[
+            required("id", ParamKind::String),
+            required("input", ParamKind::Object("PersonInput")),
+        ])
+        .mutating(),
+    CommandSpec::new("delete_person", "Deletes a person; their tasks are no longer delegated")
+        .params(&[required("id", ParamKind::String), CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("delegate_task", "Delegates a task to a person with an optional follow-up date")
+        .params(&[
+            required("taskId", ParamKind::String),
+            required("personId", ParamKind::String),
+            optional("followUpAt", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("undelegate_task", "Takes a delegated task back")
+        .params(&[required("taskId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("get_task_delegation", "Shows who a task is delegated to")
+        .params(&[required("taskId", ParamKind::String)]),
+    CommandSpec::new("get_waiting_for", "Lists open delegated tasks grouped by person")
+        .params(&[optional("followUpDue", ParamKind::Boolean)]),
+    // Dependency and snooze commands
+    CommandSpec::new("add_task_dependency", "Makes a task wait for another one to be completed")
+        .params(&[required("taskId", ParamKind::String), required("dependsOnId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("remove_task_dependency", "Stops a task waiting for another one")
+        .params(&[required("taskId", ParamKind::String), required("dependsOnId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("get_task_dependencies", "Lists the tasks a task depends on")
+        .params(&[required("taskId", ParamKind::String)]),
+    CommandSpec::new("get_blocked_tasks", "Lists open tasks waiting for open tasks they depend on")
+        .params(&[optional("projectId", ParamKind::String)]),
+    CommandSpec::new("snooze_task", "Keeps a task out of the Today view until a later time")
+        .params(&[required("taskId", ParamKind::String), required("until", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("unsnooze_task", "Brings a snoozed task back right away")
+        .params(&[required("taskId", ParamKind::String)])
+        .mutating(),
+    // Attachment commands
+    CommandSpec::new("add_attachment", "Attaches a file to an entity")
+        .params(&[
+            required("entityType", ParamKind::Enum(SECURE_DELETE_TYPES)),
+            required("entityId", ParamKind::String),
+            required("path", ParamKind::String),
+            optional("mimeType", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("get_attachments", "Lists the files attached to an entity")
+        .params(&[
+            required("entityType", ParamKind::Enum(SECURE_DELETE_TYPES)),
+            required("entityId", ParamKind::String),
+        ]),
+    CommandSpec::new("get_attachment_path", "Returns where an attachment's file is stored")
+        .params(&[required("id", ParamKind::String)]),
+    CommandSpec::new("get_attachment_thumbnail", "Returns a cached thumbnail of an image attachment")
+        .params(&[required("id", ParamKind::String), optional("size", ParamKind::Integer)]),
+    CommandSpec::new("delete_attachment", "Removes an attachment and its file once unused")
+        .params(&[required("id", ParamKind::String), CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("scan_orphan_attachments", "Finds, and optionally deletes, unreferenced attachment files")
+        .params(&[optional("delete", ParamKind::Boolean), OPTIONAL_CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("get_attachment_usage", "Reports attachment storage per entity and the size limits")
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("search_attachments", "Searches the text extracted from attached text files and PDFs")
+        .params(&[required("query", ParamKind::String), optional("limit", ParamKind::Integer)])
+        .rate_limited(SEARCH_LIMIT),
+    // Achievement commands
+    CommandSpec::new("get_achievements", "Returns achievements, completion streaks and points"),
+    // Review commands
+    CommandSpec::new("get_stale_projects", "Lists open projects overdue for a review"),
+    CommandSpec::new("mark_reviewed", "Marks a project as reviewed now")
+        .params(&[required("id", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("set_project_review_interval", "Sets how often a project should be reviewed")
+        .params(&[required("id", ParamKind::String), optional("days", ParamKind::Integer)])
+        .mutating(),
+    CommandSpec::new("set_note_review", "Schedules a note to resurface after a week, a month and three months, or unschedules it")
+        .params(&[required("id", ParamKind::String), required("enabled", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("mark_note_reviewed", "Marks a resurfaced note as reviewed and schedules its next review")
+        .params(&[required("id", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("get_notes_due_for_review", "Lists notes due to resurface by the end of today")
+        .params(&[optional("limit", ParamKind::Integer)]),
+    // Someday/maybe commands
+    CommandSpec::new("park_item", "Parks a goal or project on the someday/maybe list")
+        .params(&[
+            required("entityType", ParamKind::Enum(INCUBATABLE_TYPES)),
+            required("id", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("reactivate_item", "Moves a parked goal or project back to the active lists")
+        .params(&[
+            required("entityType", ParamKind::Enum(INCUBATABLE_TYPES)),
+            required("id", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("get_someday_maybe", "Lists goals and projects on the someday/maybe list"),
+    // Time tracking commands
+    CommandSpec::new("start_timer", "Starts a timer on a task, stopping the running one")
+        .params(&[required("taskId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("stop_timer", "Stops the running timer").mutating(),
+    CommandSpec::new("get_running_timer", "Shows the running timer"),
+    CommandSpec::new("get_time_entries", "Lists the time entries of a task")
+        .params(&[required("taskId", ParamKind::String)]),
+    CommandSpec::new("get_unreconciled_time_entries", "Lists timers stopped while idle that need reconciling"),
+    CommandSpec::new("reconcile_time_entry", "Decides whether an idle gap counts as tracked time")
+        .params(&[required("id", ParamKind::String), required("keepIdleTime", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("get_focus_by_goal", "Sums tracked time per goal over a range of days; defaults to this week")
+        .params(&[optional("from", ParamKind::String), optional("to", ParamKind::String)])
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("get_focus_by_life_area", "Sums tracked time per life area over a range of days; defaults to this week")
+        .params(&[optional("from", ParamKind::String), optional("to", ParamKind::String)])
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("get_time_budget_report", "Compares a week's tracked time per life area with its budget; defaults to this week")
+        .params(&[optional("date", ParamKind::String)])
+        .rate_limited(REPORT_LIMIT),
+    // Holiday commands
+    CommandSpec::new("get_holiday_sets", "Lists the holiday sets"),
+    CommandSpec::new("create_holiday_set", "Defines a holiday set from a list of dates")
+        .params(&[required("name", ParamKind::String), required("holidays", ParamKind::Array("HolidayInput"))])
+        .mutating(),
+    CommandSpec::new("import_holidays", "Imports a holiday set from an iCalendar file")
+        .params(&[required("name", ParamKind::String), required("content", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("add_holiday", "Adds a holiday to a set")
+        .params(&[
+            required("setId", ParamKind::String),
+            required("date", ParamKind::String),
+            required("name", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("remove_holiday", "Removes a holiday from a set")
+        .params(&[required("setId", ParamKind::String), required("date", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("set_holiday_set_enabled", "Enables or disables a holiday set")
+        .params(&[required("id", ParamKind::String), required("enabled", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("delete_holiday_set", "Deletes a holiday set with its holidays")
+        .params(&[required("id", ParamKind::String), CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("get_holidays", "Lists the holidays in a date range")
+        .params(&[required("start", ParamKind::String), required("end", ParamKind::String)]),
+    // Velocity commands
+    CommandSpec::new("get_project_velocity", "Returns a project's weekly completions, remaining work and projected completion date")
+        .params(&[required("projectId", ParamKind::String)])
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("get_critical_path", "Finds the longest chain of dependent open tasks in a project, weighted by estimates")
+        .params(&[required("projectId", ParamKind::String)])
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("get_goals_at_risk", "Lists goals at risk of missing their target date at the current pace")
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("get_effort_rollup", "Sums estimated and tracked minutes of open tasks per project, goal and life area")
+        .rate_limited(REPORT_LIMIT),
+    // Aging commands
+    CommandSpec::new("get_task_aging_report", "Shows how long open tasks have waited, by age, priority and project, and the time-to-complete trend")
+        .rate_limited(REPORT_LIMIT),
+    // Job commands
+    CommandSpec::new("get_chronic_postponements", "Lists open tasks whose due date keeps getting rescheduled")
+        .params(&[optional("moreThan", ParamKind::Integer)])
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("get_jobs", "Lists the scheduled background jobs with their last and next run"),
+    CommandSpec::new("set_job_enabled", "Enables or disables a scheduled job")
+        .params(&[required("jobType", ParamKind::Enum(JOB_TYPES)), required("enabled", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("set_job_schedule", "Changes a job's cron schedule")
+        .params(&[required("jobType", ParamKind::Enum(JOB_TYPES)), required("schedule", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("run_job_now", "Runs a scheduled job immediately")
+        .params(&[required("jobType", ParamKind::Enum(JOB_TYPES)), OPTIONAL_CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("generate_digest", "Composes a digest of completed and upcoming tasks, streaks and goal progress; defaults to last week")
+        .params(&[
+            optional("from", ParamKind::String),
+            optional("to", ParamKind::String),
+            optional("deliver", ParamKind::Boolean),
+        ])
+        .mutating(),
+    // Settings commands
+    CommandSpec::new("get_settings", "Shows the current application settings"),
+    CommandSpec::new("update_settings", "Changes settings; null resets a setting to its default")
+        .params(&[required("changes", ParamKind::Object("Settings")), OPTIONAL_CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("set_active_context", "Hides life areas of the other context (personal or work) everywhere; omit to show all")
+        .params(&[optional("context", ParamKind::Enum(AREA_CONTEXTS))])
+        .mutating(),
+    CommandSpec::new("get_week", "Returns the week containing a date, honoring the week start setting")
+        .params(&[optional("date", ParamKind::String)]),
+    CommandSpec::new("export_settings", "Exports settings, workflows, webhooks, script hooks and job schedules without other data"),
+    CommandSpec::new("import_settings", "Imports a bundle from export_settings; settings it lacks reset to their defaults")
+        .params(&[required("bundle", ParamKind::Object("SettingsBundle")), OPTIONAL_CONFIRMATION_TOKEN])
+        .destructive(),
+    // Migration commands
+    CommandSpec::new("get_migration_status", "Shows applied and pending schema migrations"),
+    CommandSpec::new("run_migrations", "Applies pending schema migrations").mutating(),
+    CommandSpec::new("rollback_migration", "Rolls the schema back to an earlier version")
+        .params(&[optional("targetVersion", ParamKind::Integer), CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("reset_database", "Drops all data and recreates the schema")
+        .params(&[CONFIRMATION_TOKEN])
+        .destructive(),
+    // Life area commands
+    CommandSpec::new("create_life_area", "Creates a life area")
+        .params(&[required("request", ParamKind::Object("CreateLifeAreaRequest"))])
+        .mutating(),
+    CommandSpec::new("get_life_areas", "Lists active life areas"),
+    CommandSpec::new("get_life_area", "Gets a life area by ID").params(&[ID]),
+    CommandSpec::new("update_life_area", "Updates a life area")
+        .params(&[required("request", ParamKind::Object("UpdateLifeAreaRequest"))])
+        .mutating(),
+    CommandSpec::new("set_life_area_context", "Marks a life area as personal or work for context switching")
+        .params(&[ID, optional("context", ParamKind::Enum(AREA_CONTEXTS))])
+        .mutating(),
+    CommandSpec::new("set_life_area_budget", "Sets or removes the weekly time budget of a life area")
+        .params(&[ID, optional("weeklyMinutes", ParamKind::Integer)])
+        .mutating(),
+    CommandSpec::new("get_delete_impact", "Counts what deleting a life area would archive with it")
+        .params(&[ID]),
+    CommandSpec::new("delete_life_area", "Archives a life area with its goals, projects and tasks")
+        .params(&[ID, optional("force", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("restore_life_area", "Restores an archived life area")
+        .params(&[ID, optional("cascade", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("get_archive_summary", "Shows what was archived with a life area and what a cascading restore brings back")
+        .params(&[required("lifeAreaId", ParamKind::String)]),
+    // Goal commands
+    CommandSpec::new("create_goal", "Creates a goal in a life area")
+        .params(&[required("request", ParamKind::Object("CreateGoalRequest"))])
+        .mutating(),
+    CommandSpec::new("get_goals", "Lists active goals"),
+    CommandSpec::new("get_goals_by_life_area", "Lists the goals of a life area")
+        .params(&[required("lifeAreaId", ParamKind::String)]),
+    CommandSpec::new("reorder_goals", "Reorders the goals of a life area")
+        .params(&[required("lifeAreaId", ParamKind::String), required("goalIds", ParamKind::StringArray)])
+        .mutating(),
+    CommandSpec::new("get_goal", "Gets a goal by ID").params(&[ID]),
+    CommandSpec::new("get_goal_breakdown", "Shows a goal with its projects, task counts, latest notes and next due tasks")
+        .params(&[ID]),
+    CommandSpec::new("update_goal", "Updates a goal")
+        .params(&[required("request", ParamKind::Object("UpdateGoalRequest"))])
+        .mutating(),
+    CommandSpec::new("complete_goal", "Marks a goal as completed").params(&[ID]).mutating(),
+    CommandSpec::new("uncomplete_goal", "Reopens a completed goal").params(&[ID]).mutating(),
+    CommandSpec::new("set_goal_progress", "Sets a goal's completion percentage")
+        .params(&[ID, required("progress", ParamKind::Integer)])
+        .mutating(),
+    CommandSpec::new("delete_goal", "Archives a goal with its projects and tasks").params(&[ID]).mutating(),
+    CommandSpec::new("restore_goal", "Restores an archived goal")
+        .params(&[ID, optional("cascade", ParamKind::Boolean)])
+        .mutating(),
+    // Project commands
+    CommandSpec::new("create_project", "Creates a project under a goal")
+        .params(&[required("request", ParamKind::Object("CreateProjectRequest"))])
+        .mutating(),
+    CommandSpec::new("get_projects", "Lists active projects"),
+    CommandSpec::new("get_projects_by_goal", "Lists the projects of a goal")
+        .params(&[required("goalId", ParamKind::String)]),
+    CommandSpec::new("reorder_projects", "Reorders the projects of a goal")
+        .params(&[required("goalId", ParamKind::String), required("projectIds", ParamKind::StringArray)])
+        .mutating(),
+    CommandSpec::new("get_project", "Gets a project by ID").params(&[ID]),
+    CommandSpec::new("update_project", "Updates a project")
+        .params(&[required("request", ParamKind::Object("UpdateProjectRequest"))])
+        .mutating(),
+    CommandSpec::new("update_project_status", "Changes a project's status")
+        .params(&[ID, required("status", ParamKind::Enum(PROJECT_STATUSES))])
+        .mutating(),
+    CommandSpec::new("set_project_progress", "Sets a project's completion percentage")
+        .params(&[ID, required("progress", ParamKind::Integer)])
+        .mutating(),
+    CommandSpec::new("delete_project", "Archives a project with its tasks").params(&[ID]).mutating(),
+    CommandSpec::new("restore_project", "Restores an archived project")
+        .params(&[ID, optional("cascade", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("merge_projects", "Moves a project's tasks, notes and tags into another and archives it")
+        .params(&[required("sourceId", ParamKind::String), required("targetId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("export_project_bundle", "Writes a project with its tasks, notes, tags and attachments to one file")
+        .params(&[required("projectId", ParamKind::String), required("path", ParamKind::String)])
+        .rate_limited(EXPORT_LIMIT),
+    CommandSpec::new("import_project_bundle", "Imports a bundle from export_project_bundle as a new project with new IDs")
+        .params(&[required("path", ParamKind::String), required("targetGoalId", ParamKind::String)])
+        .mutating(),
+    // Task commands
+    CommandSpec::new("create_task", "Creates a task")
+        .params(&[required("request", ParamKind::Object("CreateTaskRequest"))])
+        .mutating(),
+    CommandSpec::new("create_task_with_subtasks", "Creates a task and its subtasks in one transaction")
+        .params(&[required("request", ParamKind::Object("CreateTaskWithSubtasksRequest"))])
+        .mutating(),
+    CommandSpec::new("get_tasks", "Lists active tasks")
+        .params(&[optional("sort", ParamKind::Enum(TASK_SORTS))]),
+    CommandSpec::new("get_tasks_by_project", "Lists the tasks of a project")
+        .params(&[required("projectId", ParamKind::String)]),
+    CommandSpec::new("get_subtasks", "Lists the subtasks of a task")
+        .params(&[required("parentTaskId", ParamKind::String)]),
+    CommandSpec::new("get_task_tree", "Gets a task with all of its subtasks, nested")
+        .params(&[required("rootId", ParamKind::String)]),
+    CommandSpec::new("get_task", "Gets a task by ID").params(&[ID]),
+    CommandSpec::new("update_task", "Updates a task")
+        .params(&[required("request", ParamKind::Object("UpdateTaskRequest"))])
+        .mutating(),
+    CommandSpec::new("complete_task", "Marks a task as completed").params(&[ID]).mutating(),
+    CommandSpec::new("uncomplete_task", "Reopens a completed task").params(&[ID]).mutating(),
+    CommandSpec::new("delete_task", "Archives a task with its subtasks and notes").params(&[ID]).mutating(),
+    CommandSpec::new("restore_task", "Restores an archived task")
+        .params(&[ID, optional("cascade", ParamKind::Boolean)])
+        .mutating(),
+    CommandSpec::new("get_todays_tasks", "Lists open tasks due today that aren't blocked or snoozed"),
+    CommandSpec::new("get_todays_exclusions", "Lists tasks due today left out because they are blocked or snoozed"),
+    // Note commands
+    CommandSpec::new("create_note", "Creates a note")
+        .params(&[required("request", ParamKind::Object("CreateNoteRequest"))])
+        .mutating(),
+    CommandSpec::new("get_notes", "Lists active notes"),
+    CommandSpec::new("stream_notes", "Streams active notes as stream-chunk events")
+        .params(&[STREAM_ID]),
+    CommandSpec::new("get_notes_by_task", "Lists the notes of a task")
+        .params(&[required("taskId", ParamKind::String)]),
+    CommandSpec::new("get_notes_by_project", "Lists the notes of a project")
+        .params(&[required("projectId", ParamKind::String)]),
+    CommandSpec::new("get_notes_by_goal", "Lists the notes of a goal")
+        .params(&[required("goalId", ParamKind::String)]),
+    CommandSpec::new("get_notes_by_life_area", "Lists the notes of a life area")
+        .params(&[required("lifeAreaId", ParamKind::String)]),
+    CommandSpec::new("get_note", "Gets a note by ID").params(&[ID]),
+    CommandSpec::new("update_note", "Updates a note")
+        .params(&[required("request", ParamKind::Object("UpdateNoteRequest"))])
+        .mutating(),
+    CommandSpec::new("delete_note", "Archives a note").params(&[ID]).mutating(),
+    CommandSpec::new("restore_note", "Restores an archived note").params(&[ID]).mutating(),
+    CommandSpec::new("search_notes", "Searches note titles and content, optionally within a project, goal or life area")
+        .params(&[
+            required("query", ParamKind::String),
+            optional("projectId", ParamKind::String),
+            optional("goalId", ParamKind::String),
+            optional("lifeAreaId", ParamKind::String),
+        ])
+        .rate_limited(SEARCH_LIMIT),
+    CommandSpec::new("get_note_tags", "Lists the tags a note's frontmatter gives it")
+        .params(&[required("noteId", ParamKind::String)]),
+    CommandSpec::new("convert_note_to_task", "Turns a note into a task with its open checklist items as subtasks")
+        .params(&[required("noteId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("extract_tasks_from_note", "Turns the open checklist items of a note into tasks linked back to it")
+        .params(&[required("noteId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("process_meeting_note", "Collects a meeting note's decisions and delegates its action items")
+        .params(&[required("noteId", ParamKind::String)])
+        .mutating(),
+    CommandSpec::new("export_note_graph", "Builds the graph of notes and what they link to, optionally writing it as JSON or GraphML")
+        .params(&[optional("format", ParamKind::Enum(GRAPH_FORMATS)), optional("path", ParamKind::String)])
+        .rate_limited(EXPORT_LIMIT),
+    CommandSpec::new("get_note_mentions", "Returns the tasks and projects a note mentions by title or #id")
+        .params(&[required("noteId", ParamKind::String)]),
+    CommandSpec::new("rescan_note_mentions", "Records the task and project mentions of every note again").mutating(),
+    // Quick capture commands
+    CommandSpec::new("quick_add", "Captures a task, falling back to the inbox")
+        .params(&[required("text", ParamKind::String), optional("target", ParamKind::String)])
+        .mutating(),
+    // Deep link commands
+    CommandSpec::new("open_deep_link", "Handles an evorbrain:// URL")
+        .params(&[required("url", ParamKind::String)])
+        .mutating(),
+    // Badge commands
+    CommandSpec::new("get_badge_counts", "Counts tasks due today, overdue and in the inbox"),
+    CommandSpec::new("get_sidebar_counts", "Counts open tasks per life area, goal and project, plus inbox, today and overdue"),
+    // Dashboard commands
+    CommandSpec::new("get_dashboard", "Returns today's task counts and summaries of the pinned entities"),
+    CommandSpec::new("pin_entity", "Pins a life area, goal, project, task or note to the dashboard")
+        .params(&[
+            required("entityType", ParamKind::Enum(SECURE_DELETE_TYPES)),
+            required("entityId", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("unpin_entity", "Removes an entity from the dashboard")
+        .params(&[
+            required("entityType", ParamKind::Enum(SECURE_DELETE_TYPES)),
+            required("entityId", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("reorder_pins", "Rearranges the pinned entities of the dashboard")
+        .params(&[required("pins", ParamKind::Array("Pin"))])
+        .mutating(),
+    CommandSpec::new("get_stats_history", "Lists the daily open, completed and created counts; defaults to the last 30 days")
+        .params(&[optional("from", ParamKind::String), optional("to", ParamKind::String)]),
+    // Quick switcher commands
+    CommandSpec::new("quick_switch", "Finds life areas, goals, projects, tasks and notes by title as you type")
+        .params(&[required("query", ParamKind::String), optional("limit", ParamKind::Integer)]),
+    // Logging commands
+    CommandSpec::new("get_recent_logs", "Returns recent log entries")
+        .params(&[required("request", ParamKind::Object("GetLogsRequest"))]),
+    CommandSpec::new("set_log_level", "Changes the minimum log level")
+        .params(&[required("level", ParamKind::Enum(LOG_LEVELS))])
+        .mutating()
+        .available_while_read_only(),
+    CommandSpec::new("get_startup_report", "Shows how long each startup phase took"),
+    // Repository commands
+    CommandSpec::new("check_repository_health", "Checks that transactions work"),
+    CommandSpec::new("batch_delete", "Archives several entities of one type")
+        .params(&[required("request", ParamKind::Object("BatchDeleteRequest"))])
+        .mutating(),
+    CommandSpec::new("bulk_tag", "Adds and removes tags on several tasks or projects at once")
+        .params(&[
+            required("entityType", ParamKind::Enum(&["task", "project"])),
+            required("ids", ParamKind::StringArray),
+            optional("addTags", ParamKind::StringArray),
+            optional("removeTags", ParamKind::StringArray),
+        ])
+        .mutating(),
+    CommandSpec::new("execute_batch", "Runs several operations in one transaction")
+        .params(&[required("operations", ParamKind::Array("BatchOperation"))])
+        .mutating(),
+    CommandSpec::new("get_write_queue", "Lists the bulk operations that are running or queued"),
+    CommandSpec::new("get_database_stats", "Counts entities in the database")
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("get_storage_advice", "Suggests cleanups, with the command for each, when the database grows very large")
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("archive_completed_tasks", "Archives tasks completed more than a number of days ago")
+        .params(&[required("olderThanDays", ParamKind::Integer)])
+        .mutating(),
+    CommandSpec::new("scan_data_issues", "Finds orphaned rows, missed archives and future completion dates")
+        .rate_limited(REPORT_LIMIT),
+    CommandSpec::new("repair_data_issues", "Fixes scanned data issues with one strategy, recording each in the audit log")
+        .params(&[
+            required("issueIds", ParamKind::StringArray),
+            required("strategy", ParamKind::Enum(REPAIR_STRATEGIES)),
+        ])
+        .mutating(),
+    CommandSpec::new("request_destructive_confirmation", "Issues a single-use token for a destructive command")
+        .params(&[required("command", ParamKind::Enum(CONFIRMED_COMMANDS))]),
+    CommandSpec::new("cleanup_database", "Deletes old archived items and vacuums the database")
+        .params(&[required("options", ParamKind::Object("CleanupOptions")), CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("secure_delete", "Permanently deletes an entity and everything below it, unrecoverably")
+        .params(&[
+            required("entityType", ParamKind::Enum(SECURE_DELETE_TYPES)),
+            required("entityId", ParamKind::String),
+            CONFIRMATION_TOKEN,
+        ])
+        .destructive(),
+    CommandSpec::new("export_all_data", "Exports all data as JSON")
+        .params(&[required("request", ParamKind::Object("ExportRequest"))])
+        .rate_limited(EXPORT_LIMIT),
+    CommandSpec::new("stream_export_all_data", "Streams a full export as stream-chunk events")
+        .params(&[required("request", ParamKind::Object("ExportRequest")), STREAM_ID])
+        .rate_limited(EXPORT_LIMIT),
+    CommandSpec::new("import_all_data", "Imports an export of export_all_data, or reports what that would do")
+        .params(&[required("request", ParamKind::Object("ImportRequest")), OPTIONAL_CONFIRMATION_TOKEN])
+        .destructive(),
+    // Change log commands
+    CommandSpec::new("get_changes_since", "Lists changes after a change log sequence number")
+        .params(&[required("sinceSeq", ParamKind::Integer), optional("limit", ParamKind::Integer)]),
+    CommandSpec::new("get_audit_log", "Lists recent audit log entries")
+        .params(&[optional("limit", ParamKind::Integer)]),
+    // Local API server commands
+    CommandSpec::new("start_api_server", "Starts the local REST API server")
+        .params(&[optional("port", ParamKind::Integer)])
+        .mutating()
+        .available_while_read_only(),
+    CommandSpec::new("stop_api_server", "Stops the local REST API server")
+        .mutating()
+        .available_while_read_only(),
+    CommandSpec::new("get_api_server_status", "Shows whether the local REST API server is running"),
+    // Webhook commands
+    CommandSpec::new("get_webhooks", "Lists webhooks"),
+    CommandSpec::new("create_webhook", "Creates a webhook")
+        .params(&[required("request", ParamKind::Object("WebhookInput"))])
+        .mutating(),
+    CommandSpec::new("update_webhook", "Updates a webhook")
+        .params(&[required("request", ParamKind::Object("UpdateWebhookRequest"))])
+        .mutating(),
+    CommandSpec::new("delete_webhook", "Deletes a webhook")
+        .params(&[ID, CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("test_webhook", "Sends a test delivery to a webhook").params(&[ID]).mutating(),
+    // Script hook commands
+    CommandSpec::new("get_script_hooks", "Lists script hooks"),
+    CommandSpec::new("create_script_hook", "Registers a Rhai script to run on matching events")
+        .params(&[required("request", ParamKind::Object("ScriptHookInput"))])
+        .mutating(),
+    CommandSpec::new("update_script_hook", "Updates a script hook")
+        .params(&[required("request", ParamKind::Object("UpdateScriptHookRequest"))])
+        .mutating(),
+    CommandSpec::new("delete_script_hook", "Deletes a script hook")
+        .params(&[ID, CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("test_script_hook", "Runs a script hook now against an entity")
+        .params(&[ID, required("event", ParamKind::String), required("entityId", ParamKind::String)])
+        .mutating(),
+    // Email import commands
+    CommandSpec::new("get_email_accounts", "Lists email import accounts"),
+    CommandSpec::new("create_email_account", "Adds an IMAP account for email-to-task import")
+        .params(&[required("request", ParamKind::Object("EmailAccountInput"))])
+        .mutating(),
+    CommandSpec::new("update_email_account", "Updates an email import account")
+        .params(&[required("request", ParamKind::Object("UpdateEmailAccountRequest"))])
+        .mutating(),
+    CommandSpec::new("delete_email_account", "Deletes an email import account")
+        .params(&[ID, CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("poll_email_account", "Imports new emails from an account now").params(&[ID]).mutating(),
+    // External link commands
+    CommandSpec::new("link_github_issue", "Links a task to a GitHub issue or pull request")
+        .params(&[
+            required("taskId", ParamKind::String),
+            required("url", ParamKind::String),
+            optional("completeOnClose", ParamKind::Boolean),
+        ])
+        .mutating(),
+    CommandSpec::new("get_external_links", "Lists the external links of an entity")
+        .params(&[required("entityType", ParamKind::String), required("entityId", ParamKind::String)]),
+    CommandSpec::new("delete_external_link", "Removes an external link")
+        .params(&[ID, CONFIRMATION_TOKEN])
+        .destructive(),
+    CommandSpec::new("sync_github_links", "Syncs all GitHub links now").mutating(),
+    CommandSpec::new("add_web_link", "Attaches a web link to an entity and fetches its metadata")
+        .params(&[
+            required("entityType", ParamKind::String),
+            required("entityId", ParamKind::String),
+            required("url", ParamKind::String),
+        ])
+        .mutating(),
+    CommandSpec::new("refresh_link_metadata", "Re-fetches a web link's title and favicon")
+        .params(&[ID])
+        .mutating(),
+    CommandSpec::new("get_task_references", "Lists a task's links, the items its description refers to and tasks referring to it")
+        .params(&[required("taskId", ParamKind::String)]),
+    // Import commands
+    CommandSpec::new("preview_jira_import", "Parses a Jira export for the field-mapping step")
+        .params(&[required("content", ParamKind::String), required("format", ParamKind::Enum(JIRA_FORMATS))]),
+    CommandSpec::new("import_jira", "Imports a Jira export, optionally as a dry run")
+        .params(&[
+            required("content", ParamKind::String),
+            required("format", ParamKind::Enum(JIRA_FORMATS)),
+            required("options", ParamKind::Object("JiraImportOptions")),
+        ])
+        .mutating(),
+    // v2 commands
+    CommandSpec::new("v2_list_life_areas", "Lists life areas in the v2 envelope, one page at a time")
+        .params(&[PAGE, PAGE_SIZE]),
+    CommandSpec::new("v2_get_life_area", "Shows one life area in the v2 envelope").params(&[ID]),
+    CommandSpec::new("v2_list_goals", "Lists goals, optionally of one life area, in the v2 envelope")
+        .params(&[optional("lifeAreaId", ParamKind::String), PAGE, PAGE_SIZE]),
+    CommandSpec::new("v2_get_goal", "Shows one goal in the v2 envelope").params(&[ID]),
+    CommandSpec::new("v2_list_projects", "Lists projects, optionally of one goal, in the v2 envelope")
+        .params(&[optional("goalId", ParamKind::String), PAGE, PAGE_SIZE]),
+    CommandSpec::new("v2_get_project", "Shows one project in the v2 envelope").params(&[ID]),
+    CommandSpec::new("v2_list_tasks", "Lists tasks, optionally of one project, in the v2 envelope")
+        .params(&[
+            optional("projectId", ParamKind::String),
+            optional("sort", ParamKind::Enum(TASK_SORTS)),
+            PAGE,
+            PAGE_SIZE,
+        ]),
+    CommandSpec::new("v2_get_task", "Shows one task in the v2 envelope").params(&[ID]),
+    CommandSpec::new("v2_list_notes", "Lists notes in the v2 envelope, one page at a time")
+        .params(&[PAGE, PAGE_SIZE]),
+    CommandSpec::new("v2_get_note", "Shows one note in the v2 envelope").params(&[ID]),
+];
+
+/// Serializable view of a `CommandSpec`
+#[derive(Debug, Serialize)]
+pub struct CommandInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params_schema: Value,
+    pub mutating: bool,
+    pub destructive: bool,
+    pub available_while_locked: bool,
+    pub available_while_read_only: bool,
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl From<&CommandSpec> for CommandInfo {
+    fn from(spec: &CommandSpec) -> Self {
+        Self {
+            name: spec.name,
+            description: spec.description,
+            params_schema: spec.params_schema(),
+            mutating: spec.mutating,
+            destructive: spec.destructive,
+            available_while_locked: spec.available_while_locked,
+            available_while_read_only: spec.available_while_read_only,
+            rate_limit: spec.rate_limit,
+        }
+    }
+}
+
+/// Looks up a command by name
+pub fn find(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
@@ -0,0 +1,22 @@
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::achievements::{self, AchievementSummary};
+use tauri::State;
+
+/// Returns all achievements with progress, the current and longest
+/// completion streak and the total points
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<AchievementSummary>` - Achievements, streaks and points
+///
+/// # Errors
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_achievements(state: State<'_, AppState>) -> AppResult<AchievementSummary> {
+    let repo = Repository::new(state.db.clone());
+    achievements::get_achievements(&repo).await
+}
@@ -0,0 +1,48 @@
+use crate::db::models::ChronicPostponement;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::aging::{self, TaskAgingReport};
+use tauri::State;
+
+/// Returns how long open tasks have been waiting and how long completed
+/// tasks took, to spot where work keeps getting put off
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<TaskAgingReport>` - Age buckets by priority, per-priority and
+///   per-project ages, and the weekly time-to-complete trend
+///
+/// # Errors
+/// * Returns `AppError` if the settings or tasks cannot be loaded
+#[tauri::command]
+pub async fn get_task_aging_report(state: State<'_, AppState>) -> AppResult<TaskAgingReport> {
+    let repo = Repository::new(state.db.clone());
+    aging::get_task_aging_report(&repo).await
+}
+
+/// Lists open tasks rescheduled more than `more_than` times (by default
+/// `CHRONIC_POSTPONEMENT_THRESHOLD`), for the weekly review to break down
+/// or drop
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `more_than` - Reschedules a task may have before it is listed
+///
+/// # Returns
+/// * `AppResult<Vec<ChronicPostponement>>` - The tasks with their reschedule
+///   counts, the most rescheduled first
+///
+/// # Errors
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_chronic_postponements(
+    state: State<'_, AppState>,
+    more_than: Option<u32>,
+) -> AppResult<Vec<ChronicPostponement>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_chronic_postponements(i64::from(more_than.unwrap_or(aging::CHRONIC_POSTPONEMENT_THRESHOLD)))
+        .await
+}
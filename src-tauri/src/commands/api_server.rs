@@ -0,0 +1,100 @@
+use crate::api_server;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::AppState;
+use evorbrain_core::settings;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Current state of the local REST API server
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Bearer token clients must send in the `Authorization` header
+    pub token: Option<String>,
+}
+
+/// Starts the opt-in local REST API server on 127.0.0.1
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `port` - Port to listen on (defaults to the `api_server_port` setting)
+///
+/// # Returns
+/// * `AppResult<ApiServerStatus>` - Port and access token of the running server
+///
+/// # Errors
+/// * Returns `AppError` if the server is already running or the port is unavailable
+#[tauri::command]
+pub async fn start_api_server(
+    state: State<'_, AppState>,
+    port: Option<u16>,
+) -> AppResult<ApiServerStatus> {
+    let mut server = state.api_server.lock().await;
+    if server.is_some() {
+        return Err(AppError::new(
+            ErrorCode::AlreadyExists,
+            "Local API server is already running",
+        ));
+    }
+
+    let port = match port {
+        Some(port) => port,
+        None => settings::load(&Repository::new(state.db.clone())).await?.api_server_port,
+    };
+    let handle = api_server::start(state.db.clone(), state.lock.clone(), state.read_only.clone(), port).await?;
+    let status = ApiServerStatus {
+        running: true,
+        port: Some(handle.port),
+        token: Some(handle.token.clone()),
+    };
+    *server = Some(handle);
+
+    Ok(status)
+}
+
+/// Stops the local REST API server if it is running
+///
+/// # Arguments
+/// * `state` - Application state containing the server handle
+///
+/// # Returns
+/// * `AppResult<ApiServerStatus>` - The (stopped) server status
+#[tauri::command]
+pub async fn stop_api_server(state: State<'_, AppState>) -> AppResult<ApiServerStatus> {
+    if let Some(handle) = state.api_server.lock().await.take() {
+        handle.stop();
+    }
+
+    Ok(ApiServerStatus {
+        running: false,
+        port: None,
+        token: None,
+    })
+}
+
+/// Reports whether the local REST API server is running
+///
+/// # Arguments
+/// * `state` - Application state containing the server handle
+///
+/// # Returns
+/// * `AppResult<ApiServerStatus>` - Port and token when running
+#[tauri::command]
+pub async fn get_api_server_status(state: State<'_, AppState>) -> AppResult<ApiServerStatus> {
+    let server = state.api_server.lock().await;
+
+    Ok(match server.as_ref() {
+        Some(handle) => ApiServerStatus {
+            running: true,
+            port: Some(handle.port),
+            token: Some(handle.token.clone()),
+        },
+        None => ApiServerStatus {
+            running: false,
+            port: None,
+            token: None,
+        },
+    })
+}
@@ -0,0 +1,177 @@
+use crate::app_lock::{self, MIN_PASSPHRASE_LENGTH};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::note_encryption::{self, NoteKey};
+use crate::AppState;
+use evorbrain_core::events::AppEvent;
+use evorbrain_core::settings::{self, Secret};
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Debug, Serialize)]
+pub struct AppLockStatus {
+    /// A passphrase is set
+    pub enabled: bool,
+    pub locked: bool,
+}
+
+/// Reports whether the app lock is set and engaged
+///
+/// # Arguments
+/// * `state` - Application state containing the lock state
+///
+/// # Returns
+/// * `AppLockStatus` - Whether a passphrase is set and whether the app is locked
+#[tauri::command]
+pub fn get_app_lock_status(state: State<'_, AppState>) -> AppLockStatus {
+    AppLockStatus {
+        enabled: state.lock.is_enabled(),
+        locked: state.lock.is_locked(),
+    }
+}
+
+/// Sets, changes or removes the app lock passphrase
+///
+/// Changing the passphrase re-wraps the private note key with it. The lock
+/// can't be removed while private notes exist.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `current_passphrase` - The existing passphrase, required when a lock is already set
+/// * `passphrase` - The new passphrase, or `None` to remove the lock
+///
+/// # Returns
+/// * `AppResult<AppLockStatus>` - The lock status after the change
+///
+/// # Errors
+/// * Returns `AppError` if the current passphrase is wrong, the new one is too short or saving fails
+#[tauri::command]
+pub async fn set_app_lock(
+    state: State<'_, AppState>,
+    current_passphrase: Option<String>,
+    passphrase: Option<String>,
+) -> AppResult<AppLockStatus> {
+    let repo = Repository::new(state.db.clone());
+    let mut note_key = state.lock.note_key();
+
+    if let Some(hash) = settings::get_secret(&repo, Secret::AppLockHash).await? {
+        let current = current_passphrase.unwrap_or_default();
+        if !app_lock::verify_passphrase(&current, &hash)? {
+            return Err(AppError::new(ErrorCode::Unauthorized, "Current passphrase is incorrect"));
+        }
+        if let Some(wrapped) = settings::get_secret(&repo, Secret::WrappedNoteKey).await? {
+            note_key = Some(note_encryption::unwrap_key(&wrapped, &current)?);
+        }
+    }
+
+    match passphrase {
+        Some(passphrase) => {
+            if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+                return Err(AppError::validation_error(
+                    "passphrase",
+                    &format!("must be at least {} characters", MIN_PASSPHRASE_LENGTH),
+                ));
+            }
+            let hash = app_lock::hash_passphrase(&passphrase)?;
+            let note_key = note_key.unwrap_or_else(NoteKey::generate);
+            let wrapped = note_encryption::wrap_key(&note_key, &passphrase)?;
+            settings::set_secrets(&repo, &[
+                (Secret::AppLockHash, Some(&hash)),
+                (Secret::WrappedNoteKey, Some(&wrapped)),
+            ])
+            .await?;
+            state.lock.set_enabled(true);
+            state.lock.set_note_key(Some(note_key));
+            crate::log_info!("App lock passphrase set");
+        }
+        None => {
+            if repo.count_private_notes().await? > 0 {
+                return Err(AppError::new(
+                    ErrorCode::CannotUpdate,
+                    "Make all private notes public before removing the app lock",
+                ));
+            }
+            settings::set_secrets(&repo, &[(Secret::AppLockHash, None), (Secret::WrappedNoteKey, None)]).await?;
+            state.lock.set_enabled(false);
+            crate::log_info!("App lock removed");
+        }
+    }
+
+    Ok(get_app_lock_status(state))
+}
+
+/// Unlocks the app with the passphrase and loads the private note key
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `passphrase` - The app lock passphrase
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns `AppError` with code `UNAUTHORIZED` if the passphrase is wrong
+#[tauri::command]
+pub async fn unlock(state: State<'_, AppState>, passphrase: String) -> AppResult<()> {
+    if !state.lock.is_locked() {
+        return Ok(());
+    }
+
+    let repo = Repository::new(state.db.clone());
+    let Some(hash) = settings::get_secret(&repo, Secret::AppLockHash).await? else {
+        state.lock.set_enabled(false);
+        return Ok(());
+    };
+    let wrapped = settings::get_secret(&repo, Secret::WrappedNoteKey).await?;
+
+    // Argon2 is deliberately slow, so keep it off the async workers
+    let unlocked = tauri::async_runtime::spawn_blocking(move || -> AppResult<Option<(NoteKey, Option<String>)>> {
+        if !app_lock::verify_passphrase(&passphrase, &hash)? {
+            return Ok(None);
+        }
+        match wrapped {
+            Some(wrapped) => Ok(Some((note_encryption::unwrap_key(&wrapped, &passphrase)?, None))),
+            // Locks set before private notes existed don't have a note key yet
+            None => {
+                let note_key = NoteKey::generate();
+                let wrapped = note_encryption::wrap_key(&note_key, &passphrase)?;
+                Ok(Some((note_key, Some(wrapped))))
+            }
+        }
+    })
+    .await
+    .map_err(|e| AppError::new(ErrorCode::InternalError, "Passphrase check failed").with_details(e.to_string()))??;
+
+    let Some((note_key, new_wrapped)) = unlocked else {
+        crate::log_warn!("Failed unlock attempt");
+        return Err(AppError::new(ErrorCode::Unauthorized, "Incorrect passphrase"));
+    };
+    if let Some(wrapped) = new_wrapped {
+        settings::set_secrets(&repo, &[(Secret::WrappedNoteKey, Some(&wrapped))]).await?;
+    }
+
+    state.lock.unlock(note_key);
+    state.events.publish(AppEvent::new("app.unlocked", "app", "passphrase"));
+    Ok(())
+}
+
+/// Engages the app lock immediately
+///
+/// # Arguments
+/// * `state` - Application state containing the lock state
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns `AppError` if no passphrase is set
+#[tauri::command]
+pub fn lock(state: State<'_, AppState>) -> AppResult<()> {
+    if !state.lock.is_enabled() {
+        return Err(AppError::new(ErrorCode::InvalidInput, "No app lock passphrase is set"));
+    }
+    if state.lock.lock() {
+        state.events.publish(AppEvent::new("app.locked", "app", "manual"));
+    }
+    Ok(())
+}
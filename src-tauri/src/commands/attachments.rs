@@ -0,0 +1,348 @@
+use crate::db::models::{Attachment, AttachmentSearchHit, AttachmentUsage, OrphanAttachmentReport};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::attachments::{is_image, quota_exceeded, DEFAULT_THUMBNAIL_SIZE};
+use crate::AppState;
+use crate::{log_info, log_warn};
+use evorbrain_core::events::ENTITY_TYPES;
+use evorbrain_core::settings;
+use evorbrain_core::validation::validate_id;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Hits `search_attachments` returns unless asked for fewer or more
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+/// Most hits `search_attachments` returns
+const MAX_SEARCH_LIMIT: u32 = 100;
+
+/// Runs blocking file work off the async runtime
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> AppResult<T> + Send + 'static) -> AppResult<T> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::new(ErrorCode::InternalError, "Attachment task failed").with_details(e.to_string()))?
+}
+
+/// Attaches a file to an entity
+///
+/// The file is copied into the attachment store. Identical content is only
+/// stored once, however often it is attached, and only new content counts
+/// against the `attachment_quota_mb` setting. Images also get a thumbnail
+/// in the default size up front.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - One of `life_area`, `goal`, `project`, `task`, `note`
+/// * `entity_id` - The entity ID
+/// * `path` - Path of the file to attach
+/// * `mime_type` - Content type, if known
+///
+/// # Returns
+/// * `AppResult<Attachment>` - The new attachment
+///
+/// # Errors
+/// * Returns `AppError` if the entity doesn't exist or the file can't be
+///   read
+/// * Returns a `QuotaExceeded` error if the file is larger than
+///   `attachment_max_file_mb` or would take the stored files over
+///   `attachment_quota_mb`; its data names the limit
+#[tauri::command]
+pub async fn add_attachment(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+    path: String,
+    mime_type: Option<String>,
+) -> AppResult<Attachment> {
+    if !ENTITY_TYPES.contains(&entity_type.as_str()) || entity_type == "tag" {
+        return Err(AppError::validation_error("entity_type", "files can't be attached to this entity type"));
+    }
+    validate_id(&entity_id)?;
+
+    let repo = Repository::new(state.db.clone());
+    if repo.get_entity_snapshot(&entity_type, &entity_id).await?.is_none() {
+        return Err(AppError::not_found(&entity_type, &entity_id));
+    }
+
+    let source = PathBuf::from(&path);
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| AppError::validation_error("path", "must point to a file"))?;
+
+    let settings = settings::load(&repo).await?;
+    let max_size = u64::from(settings.attachment_max_file_mb) * 1024 * 1024;
+    let used_bytes = repo.get_attachment_storage_bytes().await? as u64;
+
+    let store = state.attachments.clone();
+    let stored = blocking(move || store.store_file(&source, max_size)).await?;
+
+    if let Some(quota_mb) = settings.attachment_quota_mb {
+        let quota = u64::from(quota_mb) * 1024 * 1024;
+        if stored.is_new && used_bytes + stored.size_bytes > quota {
+            let store = state.attachments.clone();
+            let content_hash = stored.content_hash.clone();
+            blocking(move || store.remove(&content_hash)).await?;
+            return Err(quota_exceeded("total", quota, stored.size_bytes, used_bytes));
+        }
+    }
+
+    if is_image(mime_type.as_deref(), &file_name) {
+        let store = state.attachments.clone();
+        let content_hash = stored.content_hash.clone();
+        if let Err(e) = blocking(move || store.thumbnail(&content_hash, DEFAULT_THUMBNAIL_SIZE)).await {
+            // get_attachment_thumbnail retries, so the attachment is still added
+            log_warn!(&format!("Failed to generate thumbnail for {}: {}", file_name, e.message));
+        }
+    }
+
+    repo.create_attachment(
+        &entity_type,
+        &entity_id,
+        &file_name,
+        mime_type.as_deref(),
+        stored.size_bytes,
+        &stored.content_hash,
+    )
+    .await
+}
+
+/// Lists the files attached to an entity
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - The entity type
+/// * `entity_id` - The entity ID
+///
+/// # Returns
+/// * `AppResult<Vec<Attachment>>` - Attachments, oldest first
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_attachments(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> AppResult<Vec<Attachment>> {
+    validate_id(&entity_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_attachments_for(&entity_type, &entity_id).await
+}
+
+/// Returns the path of an attachment's stored content, for opening it
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The attachment ID
+///
+/// # Returns
+/// * `AppResult<String>` - Absolute path of the stored file
+///
+/// # Errors
+/// * Returns `AppError` if the attachment doesn't exist
+#[tauri::command]
+pub async fn get_attachment_path(state: State<'_, AppState>, id: String) -> AppResult<String> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    let attachment = repo.get_attachment(&id).await?;
+    let path = state.attachments.path_for(&attachment.content_hash)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Returns the path of a cached thumbnail of an image attachment,
+/// generating it if needed
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The attachment ID
+/// * `size` - Longest edge in pixels: 64, 128, 256 (default) or 512
+///
+/// # Returns
+/// * `AppResult<String>` - Absolute path of the PNG thumbnail
+///
+/// # Errors
+/// * Returns `AppError` if the attachment doesn't exist, isn't an image or
+///   the size isn't supported
+#[tauri::command]
+pub async fn get_attachment_thumbnail(
+    state: State<'_, AppState>,
+    id: String,
+    size: Option<u32>,
+) -> AppResult<String> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    let attachment = repo.get_attachment(&id).await?;
+    if !is_image(attachment.mime_type.as_deref(), &attachment.file_name) {
+        return Err(AppError::validation_error("id", "attachment is not an image"));
+    }
+
+    let store = state.attachments.clone();
+    let size = size.unwrap_or(DEFAULT_THUMBNAIL_SIZE);
+    let path = blocking(move || store.thumbnail(&attachment.content_hash, size)).await?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Removes an attachment; the stored file is deleted once no other
+/// attachment shares its content
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The attachment ID
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the attachment doesn't exist
+#[tauri::command]
+pub async fn delete_attachment(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_attachment", &confirmation_token)?;
+    let repo = Repository::new(state.db.clone());
+    let (attachment, still_used) = repo.delete_attachment(&id).await?;
+
+    if !still_used {
+        // A file left behind here is picked up by scan_orphan_attachments
+        let store = state.attachments.clone();
+        if let Err(e) = blocking(move || store.remove(&attachment.content_hash)).await {
+            log_warn!(&format!("Failed to delete attachment file: {}", e.message));
+        }
+    }
+    Ok(())
+}
+
+/// Finds stored files no attachment refers to, and attachment rows whose
+/// entity no longer exists
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `delete` - Delete what was found instead of only reporting it
+/// * `confirmation_token` - Token from `request_destructive_confirmation`,
+///   needed only with `delete`
+///
+/// # Returns
+/// * `AppResult<OrphanAttachmentReport>` - The orphaned files and dangling rows
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if deleting without a valid token
+/// * Returns `AppError` if the store or database can't be read
+#[tauri::command]
+pub async fn scan_orphan_attachments(
+    state: State<'_, AppState>,
+    delete: Option<bool>,
+    confirmation_token: Option<String>,
+) -> AppResult<OrphanAttachmentReport> {
+    let delete = delete.unwrap_or(false);
+    if delete {
+        state
+            .confirmations
+            .consume("scan_orphan_attachments", confirmation_token.as_deref().unwrap_or_default())?;
+    }
+    let repo = Repository::new(state.db.clone());
+
+    // Rows first, so content only they referenced shows up as orphaned
+    let dangling_rows = repo.purge_dangling_attachments(delete).await?;
+    let referenced = repo.get_attachment_hashes().await?;
+
+    let store = state.attachments.clone();
+    let orphans = blocking(move || store.scan_orphans(&referenced)).await?;
+
+    let mut report = OrphanAttachmentReport {
+        dangling_rows,
+        deleted: delete,
+        ..Default::default()
+    };
+    for (path, size) in &orphans {
+        report.reclaimable_bytes += size;
+        report
+            .orphaned_files
+            .push(path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default());
+    }
+
+    if delete {
+        for (path, _) in &orphans {
+            std::fs::remove_file(path)
+                .map_err(|e| AppError::new(ErrorCode::IoError, "Failed to delete orphaned attachment").with_details(e.to_string()))?;
+        }
+        if !orphans.is_empty() || dangling_rows > 0 {
+            log_info!(
+                "Attachment cleanup",
+                &format!(
+                    "Deleted {} orphaned files ({} bytes) and {} dangling rows",
+                    orphans.len(),
+                    report.reclaimable_bytes,
+                    dangling_rows
+                )
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Reports attachment storage in total and per entity, with the limits set
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<AttachmentUsage>` - Stored bytes, limits and the entities
+///   using the most storage first
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_attachment_usage(state: State<'_, AppState>) -> AppResult<AttachmentUsage> {
+    let repo = Repository::new(state.db.clone());
+    let settings = settings::load(&repo).await?;
+    let entities = repo.get_attachment_usage_by_entity().await?;
+
+    Ok(AttachmentUsage {
+        total_bytes: repo.get_attachment_storage_bytes().await?,
+        attachment_count: entities.iter().map(|entity| entity.attachment_count).sum(),
+        max_file_bytes: u64::from(settings.attachment_max_file_mb) * 1024 * 1024,
+        quota_bytes: settings.attachment_quota_mb.map(|quota_mb| u64::from(quota_mb) * 1024 * 1024),
+        entities,
+    })
+}
+
+/// Searches the text of attached files
+///
+/// The text is extracted by the `attachment_text` job, so files attached
+/// since its last run aren't found yet. Only text files and PDFs have text;
+/// attachments of private notes are left out.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `query` - Words that must all appear, each matching as a prefix
+/// * `limit` - Most hits to return, 20 by default and at most 100
+///
+/// # Returns
+/// * `AppResult<Vec<AttachmentSearchHit>>` - The attachments with the item
+///   they belong to and the matching part of the text, best matches first
+///
+/// # Errors
+/// * Returns `AppError` if the query is empty, the limit is out of range or
+///   the search fails
+#[tauri::command]
+pub async fn search_attachments(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<u32>,
+) -> AppResult<Vec<AttachmentSearchHit>> {
+    if query.trim().is_empty() {
+        return Err(AppError::validation_error("query", "must not be empty"));
+    }
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if !(1..=MAX_SEARCH_LIMIT).contains(&limit) {
+        return Err(AppError::validation_error("limit", &format!("must be between 1 and {}", MAX_SEARCH_LIMIT)));
+    }
+
+    let repo = Repository::new(state.db.clone());
+    repo.search_attachment_text(&query, i64::from(limit)).await
+}
@@ -0,0 +1,45 @@
+use crate::badges;
+use crate::db::models::{BadgeCounts, SidebarCounts};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::settings;
+use tauri::State;
+
+/// Retrieves open task counts for tray and dock badges
+///
+/// Computed with a single query. Listen for the `badge-counts-changed` event
+/// to receive updates instead of polling.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<BadgeCounts>` - Tasks due today, overdue, and in the inbox
+#[tauri::command]
+pub async fn get_badge_counts(state: State<'_, AppState>) -> AppResult<BadgeCounts> {
+    let repo = Repository::new(state.db.clone());
+    badges::current_counts(&repo).await
+}
+
+/// Retrieves open task counts for the sidebar in one query
+///
+/// Replaces per-entity count queries: one call returns the counts of every
+/// life area, goal and project along with the inbox, today and overdue totals.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<SidebarCounts>` - Open tasks per entity ID, and the totals
+///
+/// # Errors
+/// * Returns `AppError` if the settings or counts cannot be loaded
+#[tauri::command]
+pub async fn get_sidebar_counts(state: State<'_, AppState>) -> AppResult<SidebarCounts> {
+    let repo = Repository::new(state.db.clone());
+    let calendar = Calendar::from_settings(&settings::load(&repo).await?);
+    let today = calendar.day(calendar.today());
+    repo.get_sidebar_counts(today.start, today.end).await
+}
@@ -0,0 +1,135 @@
+use crate::db::models::{BlockedTask, TaskDependency, TaskSnooze};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+/// Makes a task wait for another one to be completed
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task that waits
+/// * `depends_on_id` - The task that has to be completed first
+///
+/// # Returns
+/// * `AppResult<TaskDependency>` - The dependency
+///
+/// # Errors
+/// * Returns `AppError` if either task doesn't exist, or the dependency
+///   would make the tasks wait for each other
+#[tauri::command]
+pub async fn add_task_dependency(
+    state: State<'_, AppState>,
+    task_id: String,
+    depends_on_id: String,
+) -> AppResult<TaskDependency> {
+    validate_id(&task_id)?;
+    validate_id(&depends_on_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.add_task_dependency(&task_id, &depends_on_id).await
+}
+
+/// Stops a task waiting for another one
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task that waits
+/// * `depends_on_id` - The task it waits for
+///
+/// # Returns
+/// * `AppResult<bool>` - False if the task didn't depend on the other one
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn remove_task_dependency(
+    state: State<'_, AppState>,
+    task_id: String,
+    depends_on_id: String,
+) -> AppResult<bool> {
+    validate_id(&task_id)?;
+    validate_id(&depends_on_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.remove_task_dependency(&task_id, &depends_on_id).await
+}
+
+/// Lists the tasks a task depends on
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task
+///
+/// # Returns
+/// * `AppResult<Vec<TaskDependency>>` - Its dependencies, completed ones included
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_task_dependencies(state: State<'_, AppState>, task_id: String) -> AppResult<Vec<TaskDependency>> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_task_dependencies(&task_id).await
+}
+
+/// Lists the open tasks that wait for open tasks they depend on
+///
+/// These tasks can't be completed and stay out of the Today view until
+/// their blockers are completed or archived.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - Only tasks of this project, if given
+///
+/// # Returns
+/// * `AppResult<Vec<BlockedTask>>` - The blocked tasks with their open
+///   blockers, earliest due first
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_blocked_tasks(state: State<'_, AppState>, project_id: Option<String>) -> AppResult<Vec<BlockedTask>> {
+    if let Some(project_id) = &project_id {
+        validate_id(project_id)?;
+    }
+    let repo = Repository::new(state.db.clone());
+    repo.get_blocked_tasks(project_id.as_deref()).await
+}
+
+/// Keeps a task out of the Today view until a later time
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task to snooze
+/// * `until` - When it shows up again
+///
+/// # Returns
+/// * `AppResult<TaskSnooze>` - The snooze
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or `until` has passed
+#[tauri::command]
+pub async fn snooze_task(state: State<'_, AppState>, task_id: String, until: DateTime<Utc>) -> AppResult<TaskSnooze> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.snooze_task(&task_id, until).await
+}
+
+/// Brings a snoozed task back right away
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The snoozed task
+///
+/// # Returns
+/// * `AppResult<bool>` - False if the task wasn't snoozed
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn unsnooze_task(state: State<'_, AppState>, task_id: String) -> AppResult<bool> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.unsnooze_task(&task_id).await
+}
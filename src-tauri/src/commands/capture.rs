@@ -0,0 +1,51 @@
+use crate::db::models::Task;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::validation::validate_title;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Result of a quick capture, shaped for a confirmation toast
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuickAddResult {
+    pub task: Task,
+    /// True when a target project was given but could not be used, so the
+    /// task was put in the inbox instead
+    pub fell_back_to_inbox: bool,
+}
+
+/// Captures a task from the global-shortcut quick capture window
+///
+/// Optimized for speed: the task is created with a single insert. A missing,
+/// archived or malformed target project never fails the capture; the task is
+/// put in the inbox instead.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `text` - Captured text, used as the task title
+/// * `target` - Optional project ID to add the task to
+///
+/// # Returns
+/// * `AppResult<QuickAddResult>` - The created task and whether it fell back to the inbox
+///
+/// # Errors
+/// * Returns `AppError` if the text is empty or too long, or the insert fails
+#[tauri::command]
+pub async fn quick_add(
+    state: State<'_, AppState>,
+    text: String,
+    target: Option<String>,
+) -> AppResult<QuickAddResult> {
+    let title = text.trim();
+    validate_title("text", title)?;
+
+    let target = target.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let repo = Repository::new(state.db.clone());
+    let task = repo.quick_add_task(title, target).await?;
+
+    Ok(QuickAddResult {
+        fell_back_to_inbox: target.is_some() && task.project_id.is_none(),
+        task,
+    })
+}
@@ -0,0 +1,96 @@
+use crate::db::models::{AuditLogEntry, ChangeLogEntry};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+use evorbrain_core::settings;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const DEFAULT_CHANGE_LIMIT: i64 = 500;
+const DEFAULT_AUDIT_LIMIT: i64 = 100;
+
+/// Ordered batch of mutations returned by `get_changes_since`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangeSet {
+    pub changes: Vec<ChangeLogEntry>,
+    /// Sequence number to pass as `since_seq` on the next call
+    pub next_seq: i64,
+    /// Highest sequence number currently recorded
+    pub latest_seq: i64,
+    /// True when more changes are available beyond this batch
+    pub has_more: bool,
+    /// True when the client's cursor is ahead of the log (e.g. after a
+    /// database reset) and it must do a full reload
+    pub reset_required: bool,
+}
+
+/// Retrieves all mutations recorded after the given sequence number
+///
+/// Clients keep the returned `next_seq` and pass it back on the next call to
+/// apply incremental updates instead of reloading everything.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `since_seq` - Last sequence number the client has seen (0 for everything)
+/// * `limit` - Maximum number of changes to return (defaults to 500, capped by the `max_change_limit` setting)
+///
+/// # Returns
+/// * `AppResult<ChangeSet>` - Changes ordered by sequence number
+///
+/// # Errors
+/// * Returns `AppError` if the arguments are invalid or the query fails
+#[tauri::command]
+pub async fn get_changes_since(
+    state: State<'_, AppState>,
+    since_seq: i64,
+    limit: Option<i64>,
+) -> AppResult<ChangeSet> {
+    if since_seq < 0 {
+        return Err(AppError::validation_error("since_seq", "must not be negative"));
+    }
+
+    let repo = Repository::new(state.db.clone());
+    let max_limit = settings::load(&repo).await?.max_change_limit;
+
+    let limit = limit.unwrap_or(DEFAULT_CHANGE_LIMIT.min(max_limit));
+    if limit <= 0 || limit > max_limit {
+        return Err(AppError::validation_error(
+            "limit",
+            &format!("must be between 1 and {}", max_limit),
+        ));
+    }
+
+    let changes = repo.get_changes_since(since_seq, limit).await?;
+    let latest_seq = repo.get_latest_change_seq().await?;
+    let next_seq = changes.last().map(|c| c.seq).unwrap_or(since_seq);
+
+    Ok(ChangeSet {
+        has_more: next_seq < latest_seq,
+        reset_required: since_seq > latest_seq,
+        changes,
+        next_seq,
+        latest_seq,
+    })
+}
+
+/// Lists the most recent audit log entries, newest first
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `limit` - Maximum number of entries to return (defaults to 100)
+///
+/// # Returns
+/// * `AppResult<Vec<AuditLogEntry>>` - Recorded operations with their details
+///
+/// # Errors
+/// * Returns `AppError` if the limit isn't positive or the query fails
+#[tauri::command]
+pub async fn get_audit_log(state: State<'_, AppState>, limit: Option<i64>) -> AppResult<Vec<AuditLogEntry>> {
+    let limit = limit.unwrap_or(DEFAULT_AUDIT_LIMIT);
+    if limit <= 0 {
+        return Err(AppError::validation_error("limit", "must be positive"));
+    }
+
+    let repo = Repository::new(state.db.clone());
+    repo.get_audit_log(limit).await
+}
@@ -0,0 +1,108 @@
+use crate::db::models::{Context, Task};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+/// Lists all GTD contexts (`@home`, `@computer`, ...) with their open task counts
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<Context>>` - Contexts sorted by name
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_contexts(state: State<'_, AppState>) -> AppResult<Vec<Context>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_contexts().await
+}
+
+/// Lists the contexts of a task
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task
+///
+/// # Returns
+/// * `AppResult<Vec<Context>>` - The task's contexts
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the query fails
+#[tauri::command]
+pub async fn get_task_contexts(state: State<'_, AppState>, task_id: String) -> AppResult<Vec<Context>> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_task_contexts(&task_id).await
+}
+
+/// Replaces a task's contexts
+///
+/// Names may be given with or without the leading `@` and are matched
+/// case-insensitively; contexts that don't exist yet are created.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task
+/// * `contexts` - Context names, e.g. `["@home", "computer"]`; empty clears them
+///
+/// # Returns
+/// * `AppResult<Vec<Context>>` - The task's contexts after the change
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or a name is invalid
+#[tauri::command]
+pub async fn set_task_contexts(
+    state: State<'_, AppState>,
+    task_id: String,
+    contexts: Vec<String>,
+) -> AppResult<Vec<Context>> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_task_contexts(&task_id, &contexts).await
+}
+
+/// Lists the open tasks in a context, most urgent first
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `context` - Context name, with or without the leading `@`
+///
+/// # Returns
+/// * `AppResult<Vec<Task>>` - Open tasks in the context
+///
+/// # Errors
+/// * Returns `AppError` if the name is invalid or the query fails
+#[tauri::command]
+pub async fn get_tasks_by_context(state: State<'_, AppState>, context: String) -> AppResult<Vec<Task>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_tasks_by_context(&context).await
+}
+
+/// Deletes a context; its tasks are kept
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The context to delete
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the context doesn't exist
+#[tauri::command]
+pub async fn delete_context(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_context", &confirmation_token)?;
+    let repo = Repository::new(state.db.clone());
+    repo.delete_context(&id).await
+}
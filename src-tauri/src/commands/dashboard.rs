@@ -0,0 +1,139 @@
+use crate::badges;
+use crate::db::models::{Dashboard, Pin, PinnedItem, StatsSnapshot};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+use chrono::NaiveDate;
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::events::ENTITY_TYPES;
+use evorbrain_core::focus;
+use evorbrain_core::stats_history;
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+fn validate_pin(entity_type: &str, entity_id: &str) -> AppResult<()> {
+    if !ENTITY_TYPES.contains(&entity_type) || entity_type == "tag" {
+        return Err(AppError::validation_error("entity_type", "this entity type can't be pinned"));
+    }
+    validate_id(entity_id)
+}
+
+/// Retrieves the dashboard: open task counts, the pinned entities and this
+/// week's time budgets
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Dashboard>` - Tasks due today, overdue and in the inbox, a
+///   summary of each pinned entity and tracked time against each budget
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_dashboard(state: State<'_, AppState>) -> AppResult<Dashboard> {
+    let repo = Repository::new(state.db.clone());
+    Ok(Dashboard {
+        counts: badges::current_counts(&repo).await?,
+        pins: repo.get_pinned_items().await?,
+        budgets: focus::get_time_budget_report(&repo, Calendar::load(&repo).await?.today())
+            .await?
+            .budgets,
+    })
+}
+
+/// Pins an entity to the end of the dashboard
+///
+/// Pinning an entity that already is pinned keeps its place.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - One of `life_area`, `goal`, `project`, `task`, `note`
+/// * `entity_id` - The entity ID
+///
+/// # Returns
+/// * `AppResult<Vec<PinnedItem>>` - All pinned entities, in order
+///
+/// # Errors
+/// * Returns `AppError` if the entity doesn't exist or is archived
+#[tauri::command]
+pub async fn pin_entity(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> AppResult<Vec<PinnedItem>> {
+    validate_pin(&entity_type, &entity_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.pin_entity(&entity_type, &entity_id).await?;
+    repo.get_pinned_items().await
+}
+
+/// Removes an entity from the dashboard
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - The entity type
+/// * `entity_id` - The entity ID
+///
+/// # Returns
+/// * `AppResult<Vec<PinnedItem>>` - The remaining pinned entities, in order
+///
+/// # Errors
+/// * Returns `AppError` if the entity isn't pinned
+#[tauri::command]
+pub async fn unpin_entity(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> AppResult<Vec<PinnedItem>> {
+    validate_pin(&entity_type, &entity_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.unpin_entity(&entity_type, &entity_id).await?;
+    repo.get_pinned_items().await
+}
+
+/// Rearranges the pinned entities
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `pins` - Pins in their new order; those left out follow in their
+///   current order
+///
+/// # Returns
+/// * `AppResult<Vec<PinnedItem>>` - All pinned entities, in the new order
+///
+/// # Errors
+/// * Returns `AppError` if one of the given entities isn't pinned
+#[tauri::command]
+pub async fn reorder_pins(state: State<'_, AppState>, pins: Vec<Pin>) -> AppResult<Vec<PinnedItem>> {
+    let repo = Repository::new(state.db.clone());
+    repo.reorder_pins(&pins).await?;
+    repo.get_pinned_items().await
+}
+
+/// Retrieves the daily workspace statistics recorded by the
+/// `stats_snapshot` job, for charts of long-term trends
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `from` - First day of the range; defaults to 30 days before `to`
+/// * `to` - Last day of the range, included; defaults to yesterday
+///
+/// # Returns
+/// * `AppResult<Vec<StatsSnapshot>>` - Open tasks, completed tasks and
+///   created notes per day, oldest first; days without a snapshot are
+///   missing
+///
+/// # Errors
+/// * `VALIDATION_ERROR` - `to` is before `from` or the range is over ten
+///   years
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_stats_history(
+    state: State<'_, AppState>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> AppResult<Vec<StatsSnapshot>> {
+    let repo = Repository::new(state.db.clone());
+    stats_history::get_stats_history(&repo, from, to).await
+}
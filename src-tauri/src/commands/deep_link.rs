@@ -0,0 +1,26 @@
+use crate::db::repository::Repository;
+use crate::deep_link::{self, DeepLinkOutcome};
+use crate::error::AppResult;
+use crate::AppState;
+use tauri::State;
+
+/// Routes an `evorbrain://` URL received by the frontend
+///
+/// URLs opened through the OS are handled automatically; this command covers
+/// links the frontend receives itself (e.g. clicked inside a note).
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `url` - The deep link, e.g. `evorbrain://task/<id>`
+///
+/// # Returns
+/// * `AppResult<DeepLinkOutcome>` - The entity to open or the captured task
+///
+/// # Errors
+/// * Returns `AppError` if the URL is malformed, has an invalid ID, or the entity doesn't exist
+#[tauri::command]
+pub async fn open_deep_link(state: State<'_, AppState>, url: String) -> AppResult<DeepLinkOutcome> {
+    let link = deep_link::parse(&url)?;
+    let repo = Repository::new(state.db.clone());
+    deep_link::execute(&repo, link).await
+}
@@ -0,0 +1,139 @@
+use crate::db::models::{EmailAccount, EmailAccountInput};
+use crate::db::repository::Repository;
+use crate::email_import::{self, EmailPollSummary};
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+use evorbrain_core::validation::{validate_id, validate_title};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Request structure for updating an existing email account
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateEmailAccountRequest {
+    pub id: String,
+    #[serde(flatten)]
+    pub account: EmailAccountInput,
+}
+
+fn validate_email_account_input(input: &EmailAccountInput) -> AppResult<()> {
+    validate_title("name", &input.name)?;
+    validate_title("host", &input.host)?;
+    validate_title("username", &input.username)?;
+    validate_title("folder", &input.folder)?;
+    validate_title("search_query", &input.search_query)?;
+
+    if !(1..=65535).contains(&input.port) {
+        return Err(AppError::validation_error("port", "must be between 1 and 65535"));
+    }
+    if input.poll_interval_minutes < 1 {
+        return Err(AppError::validation_error("poll_interval_minutes", "must be at least 1"));
+    }
+    Ok(())
+}
+
+/// Retrieves all configured email accounts (passwords are never returned)
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<EmailAccount>>` - All accounts with their last poll status
+#[tauri::command]
+pub async fn get_email_accounts(state: State<'_, AppState>) -> AppResult<Vec<EmailAccount>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_email_accounts().await
+}
+
+/// Adds an IMAP account whose matching emails are imported as inbox tasks
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Connection settings, folder, SEARCH criteria and poll interval
+///
+/// # Returns
+/// * `AppResult<EmailAccount>` - The newly created account
+///
+/// # Errors
+/// * Returns `AppError` if validation fails or no password is given
+#[tauri::command]
+pub async fn create_email_account(
+    state: State<'_, AppState>,
+    request: EmailAccountInput,
+) -> AppResult<EmailAccount> {
+    validate_email_account_input(&request)?;
+    if request.password.as_deref().unwrap_or_default().is_empty() {
+        return Err(AppError::validation_error("password", "must not be empty"));
+    }
+
+    let repo = Repository::new(state.db.clone());
+    repo.create_email_account(request).await
+}
+
+/// Updates an email account; omit `password` to keep the stored one
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Account ID and the new settings
+///
+/// # Returns
+/// * `AppResult<EmailAccount>` - The updated account
+///
+/// # Errors
+/// * Returns `AppError` if the account doesn't exist or validation fails
+#[tauri::command]
+pub async fn update_email_account(
+    state: State<'_, AppState>,
+    request: UpdateEmailAccountRequest,
+) -> AppResult<EmailAccount> {
+    validate_id(&request.id)?;
+    validate_email_account_input(&request.account)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.get_email_account(&request.id).await?;
+    repo.update_email_account(&request.id, request.account).await
+}
+
+/// Removes an email account and its import history
+///
+/// Tasks already created from its emails are kept.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The account ID
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the account doesn't exist
+#[tauri::command]
+pub async fn delete_email_account(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_email_account", &confirmation_token)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.delete_email_account(&id).await
+}
+
+/// Polls an email account immediately instead of waiting for its interval
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The account ID
+///
+/// # Returns
+/// * `AppResult<EmailPollSummary>` - How many emails matched, were imported and skipped
+///
+/// # Errors
+/// * Returns `AppError` if the account doesn't exist or the IMAP server can't be reached
+#[tauri::command]
+pub async fn poll_email_account(state: State<'_, AppState>, id: String) -> AppResult<EmailPollSummary> {
+    validate_id(&id)?;
+
+    let repo = Repository::new(state.db.clone());
+    let account = repo.get_email_account(&id).await?;
+    email_import::poll_account(&repo, &account).await
+}
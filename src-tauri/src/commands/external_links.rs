@@ -0,0 +1,209 @@
+use crate::db::models::{ExternalLink, ExternalLinkInput, TaskReferences};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::github::{self, GitHubSyncSummary};
+use crate::link_metadata;
+use crate::AppState;
+use evorbrain_core::events::ENTITY_TYPES;
+use evorbrain_core::validation::{validate_http_url, validate_id};
+use tauri::State;
+
+/// Links a task to a GitHub issue or pull request
+///
+/// The item's title and state are fetched right away and then kept in sync by
+/// a background job.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task to link
+/// * `url` - github.com issue or pull request URL
+/// * `complete_on_close` - Complete the task when the item is closed or merged
+///
+/// # Returns
+/// * `AppResult<ExternalLink>` - The created link (with `sync_error` set if the first fetch failed)
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist, the URL is invalid or already linked
+#[tauri::command]
+pub async fn link_github_issue(
+    state: State<'_, AppState>,
+    task_id: String,
+    url: String,
+    complete_on_close: Option<bool>,
+) -> AppResult<ExternalLink> {
+    validate_id(&task_id)?;
+    let item = github::parse_url(&url)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.get_task(&task_id).await?;
+
+    let link = repo
+        .create_external_link(ExternalLinkInput {
+            entity_type: "task".to_string(),
+            entity_id: task_id,
+            url: url.trim().to_string(),
+            provider: github::PROVIDER.to_string(),
+            external_id: Some(item.external_id()),
+            title: None,
+            complete_on_close: complete_on_close.unwrap_or(false),
+        })
+        .await?;
+
+    // A failed first fetch is recorded on the link and retried by the sync job
+    let _ = github::sync_link(&repo, &github::http_client(), &link).await;
+    repo.get_external_link(&link.id).await
+}
+
+/// Attaches a web link to any entity and fetches its title and favicon
+///
+/// The page is fetched with a timeout and size limit. A failed fetch doesn't
+/// fail the command; the link is saved with `sync_error` set and can be
+/// retried with `refresh_link_metadata`.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - One of `life_area`, `goal`, `project`, `task`, `note`
+/// * `entity_id` - The entity ID
+/// * `url` - http or https URL to link
+///
+/// # Returns
+/// * `AppResult<ExternalLink>` - The created link with whatever metadata could be fetched
+///
+/// # Errors
+/// * Returns `AppError` if the entity doesn't exist, the URL is invalid or already linked
+#[tauri::command]
+pub async fn add_web_link(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+    url: String,
+) -> AppResult<ExternalLink> {
+    if !ENTITY_TYPES.contains(&entity_type.as_str()) || entity_type == "tag" {
+        return Err(AppError::validation_error("entity_type", "links can't be attached to this entity type"));
+    }
+    validate_id(&entity_id)?;
+    validate_http_url("url", &url)?;
+
+    let repo = Repository::new(state.db.clone());
+    if repo.get_entity_snapshot(&entity_type, &entity_id).await?.is_none() {
+        return Err(AppError::not_found(&entity_type, &entity_id));
+    }
+
+    let link = repo
+        .create_external_link(ExternalLinkInput {
+            entity_type,
+            entity_id,
+            url: url.trim().to_string(),
+            provider: link_metadata::PROVIDER.to_string(),
+            ..Default::default()
+        })
+        .await?;
+
+    link_metadata::refresh_link(&repo, &link_metadata::http_client(), &link).await
+}
+
+/// Re-fetches the title, description and favicon of a web link
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The link ID
+///
+/// # Returns
+/// * `AppResult<ExternalLink>` - The updated link; `sync_error` is set if the fetch failed
+///
+/// # Errors
+/// * Returns `AppError` if the link doesn't exist or isn't a web link
+#[tauri::command]
+pub async fn refresh_link_metadata(state: State<'_, AppState>, id: String) -> AppResult<ExternalLink> {
+    validate_id(&id)?;
+
+    let repo = Repository::new(state.db.clone());
+    let link = repo.get_external_link(&id).await?;
+    if link.provider != link_metadata::PROVIDER {
+        return Err(AppError::validation_error("id", "only web links have page metadata"));
+    }
+
+    link_metadata::refresh_link(&repo, &link_metadata::http_client(), &link).await
+}
+
+/// Retrieves the external links attached to an entity
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - One of `life_area`, `goal`, `project`, `task`, `note`
+/// * `entity_id` - The entity ID
+///
+/// # Returns
+/// * `AppResult<Vec<ExternalLink>>` - Links in creation order
+#[tauri::command]
+pub async fn get_external_links(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> AppResult<Vec<ExternalLink>> {
+    if !ENTITY_TYPES.contains(&entity_type.as_str()) {
+        return Err(AppError::validation_error("entity_type", "unknown entity type"));
+    }
+    validate_id(&entity_id)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.get_external_links_for(&entity_type, &entity_id).await
+}
+
+/// Gathers the material related to a task: its external links, including
+/// the URLs found in its description, the tasks, projects, goals and notes
+/// the description refers to with `evorbrain://` links, and the tasks whose
+/// description refers to it
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task
+///
+/// # Returns
+/// * `AppResult<TaskReferences>` - Links, references and back-references
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the task doesn't exist
+#[tauri::command]
+pub async fn get_task_references(state: State<'_, AppState>, task_id: String) -> AppResult<TaskReferences> {
+    validate_id(&task_id)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.get_task_references(&task_id).await
+}
+
+/// Removes an external link
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The link ID
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the link doesn't exist
+#[tauri::command]
+pub async fn delete_external_link(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_external_link", &confirmation_token)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.delete_external_link(&id).await
+}
+
+/// Syncs all GitHub links now instead of waiting for the background job
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<GitHubSyncSummary>` - Synced and failed link counts and tasks completed
+#[tauri::command]
+pub async fn sync_github_links(state: State<'_, AppState>) -> AppResult<GitHubSyncSummary> {
+    let repo = Repository::new(state.db.clone());
+    github::sync_all(&repo).await
+}
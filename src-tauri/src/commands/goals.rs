@@ -1,27 +1,26 @@
-use crate::db::models::Goal;
+use crate::db::models::{Goal, GoalBreakdown, GoalInput};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::note_encryption::reveal_all;
 use crate::AppState;
 use chrono::{DateTime, Utc};
+use evorbrain_core::conflicts::ensure_unchanged;
+use evorbrain_core::validation::{validate_id, validate_progress};
 use serde::{Deserialize, Serialize};
 use tauri::State;
-use uuid::Uuid;
 
 /// Request structure for creating a new goal
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateGoalRequest {
-    pub life_area_id: String,
-    pub title: String,
-    pub description: Option<String>,
-    pub target_date: Option<DateTime<Utc>>,
-}
+pub type CreateGoalRequest = GoalInput;
 
 /// Request structure for updating an existing goal
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateGoalRequest {
     pub id: String,
-    pub life_area_id: String,
-    pub title: String,
-    pub description: Option<String>,
-    pub target_date: Option<DateTime<Utc>>,
+    /// `updated_at` as the client loaded it, to refuse conflicting edits
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub goal: GoalInput,
 }
 
 /// Creates a new goal within a life area
@@ -37,30 +36,12 @@ pub async fn create_goal(
     state: State<'_, AppState>,
     request: CreateGoalRequest,
 ) -> Result<Goal, String> {
-    let id = Uuid::new_v4().to_string();
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        INSERT INTO goals (id, life_area_id, title, description, target_date, created_at, updated_at)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-        "#
-    )
-    .bind(&id)
-    .bind(&request.life_area_id)
-    .bind(&request.title)
-    .bind(&request.description)
-    .bind(&request.target_date)
-    .bind(&now)
-    .bind(&now)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_goal(state, id).await
+    let repo = Repository::new(state.db.clone());
+    repo.create_goal(request).await.map_err(|e| e.to_string())
 }
 
-/// Retrieves all non-archived goals from the database
+/// Retrieves all goals that are neither archived nor parked on the
+/// someday/maybe list
 /// 
 /// # Arguments
 /// * `state` - Application state containing the database connection
@@ -69,18 +50,8 @@ pub async fn create_goal(
 /// * `Result<Vec<Goal>, String>` - List of all active goals or error message
 #[tauri::command]
 pub async fn get_goals(state: State<'_, AppState>) -> Result<Vec<Goal>, String> {
-    sqlx::query_as::<_, Goal>(
-        r#"
-        SELECT id, life_area_id, title, description, target_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM goals
-        WHERE archived_at IS NULL
-        ORDER BY created_at DESC
-        "#
-    )
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_goals().await.map_err(|e| e.to_string())
 }
 
 /// Retrieves all goals for a specific life area
@@ -96,19 +67,32 @@ pub async fn get_goals_by_life_area(
     state: State<'_, AppState>,
     life_area_id: String,
 ) -> Result<Vec<Goal>, String> {
-    sqlx::query_as::<_, Goal>(
-        r#"
-        SELECT id, life_area_id, title, description, target_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM goals
-        WHERE life_area_id = ?1 AND archived_at IS NULL
-        ORDER BY created_at DESC
-        "#
-    )
-    .bind(&life_area_id)
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_goals_by_life_area(&life_area_id).await.map_err(|e| e.to_string())
+}
+
+/// Reorders the goals of a life area
+/// 
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `life_area_id` - UUID string of the life area
+/// * `goal_ids` - Goals in their new order; goals of the life area that aren't
+///   listed keep their relative order after them
+/// 
+/// # Returns
+/// * `Result<Vec<Goal>, String>` - The life area's goals in the new order or error message
+#[tauri::command]
+pub async fn reorder_goals(
+    state: State<'_, AppState>,
+    life_area_id: String,
+    goal_ids: Vec<String>,
+) -> Result<Vec<Goal>, String> {
+    let repo = Repository::new(state.db.clone());
+    repo.reorder_goals(&life_area_id, &goal_ids)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    get_goals_by_life_area(state, life_area_id).await
 }
 
 /// Retrieves a specific goal by ID
@@ -121,18 +105,29 @@ pub async fn get_goals_by_life_area(
 /// * `Result<Goal, String>` - The requested goal or error message
 #[tauri::command]
 pub async fn get_goal(state: State<'_, AppState>, id: String) -> Result<Goal, String> {
-    sqlx::query_as::<_, Goal>(
-        r#"
-        SELECT id, life_area_id, title, description, target_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM goals
-        WHERE id = ?1
-        "#
-    )
-    .bind(&id)
-    .fetch_one(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_goal(&id).await.map_err(|e| e.to_string())
+}
+
+/// Retrieves a goal with everything its detail page shows in one call
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - UUID string of the goal
+///
+/// # Returns
+/// * `AppResult<GoalBreakdown>` - The goal, its projects with task counts,
+///   the latest notes and the next due tasks
+///
+/// # Errors
+/// * Returns `AppError` if the goal doesn't exist or a query fails
+#[tauri::command]
+pub async fn get_goal_breakdown(state: State<'_, AppState>, id: String) -> AppResult<GoalBreakdown> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    let mut breakdown = repo.get_goal_breakdown(&id, Utc::now()).await?;
+    breakdown.latest_notes = reveal_all(&state.lock, breakdown.latest_notes);
+    Ok(breakdown)
 }
 
 /// Updates an existing goal
@@ -142,32 +137,18 @@ pub async fn get_goal(state: State<'_, AppState>, id: String) -> Result<Goal, St
 /// * `request` - Update request containing ID and fields to update
 /// 
 /// # Returns
-/// * `Result<Goal, String>` - The updated goal or error message
+/// * `AppResult<Goal>` - The updated goal
+///
+/// # Errors
+/// * Returns `AppError` if the goal doesn't exist or the update fails
+/// * Returns a `CONFLICT` error holding the stored goal and the rejected
+///   change if the goal changed after `expected_updated_at`
 #[tauri::command]
-pub async fn update_goal(
-    state: State<'_, AppState>,
-    request: UpdateGoalRequest,
-) -> Result<Goal, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE goals 
-        SET life_area_id = ?1, title = ?2, description = ?3, target_date = ?4, updated_at = ?5
-        WHERE id = ?6
-        "#
-    )
-    .bind(&request.life_area_id)
-    .bind(&request.title)
-    .bind(&request.description)
-    .bind(&request.target_date)
-    .bind(&now)
-    .bind(&request.id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_goal(state, request.id).await
+pub async fn update_goal(state: State<'_, AppState>, request: UpdateGoalRequest) -> AppResult<Goal> {
+    let repo = Repository::new(state.db.clone());
+    let current = repo.get_goal(&request.id).await?;
+    ensure_unchanged("goal", &request.id, request.expected_updated_at, current.updated_at, &current, &request)?;
+    repo.update_goal(&request.id, request.goal).await
 }
 
 /// Marks a goal as completed
@@ -180,23 +161,8 @@ pub async fn update_goal(
 /// * `Result<Goal, String>` - The completed goal or error message
 #[tauri::command]
 pub async fn complete_goal(state: State<'_, AppState>, id: String) -> Result<Goal, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE goals 
-        SET completed_at = ?1, updated_at = ?2
-        WHERE id = ?3
-        "#
-    )
-    .bind(&now)
-    .bind(&now)
-    .bind(&id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_goal(state, id).await
+    let repo = Repository::new(state.db.clone());
+    repo.set_goal_completed(&id, true).await.map_err(|e| e.to_string())
 }
 
 /// Marks a completed goal as incomplete
@@ -209,22 +175,28 @@ pub async fn complete_goal(state: State<'_, AppState>, id: String) -> Result<Goa
 /// * `Result<Goal, String>` - The uncompleted goal or error message
 #[tauri::command]
 pub async fn uncomplete_goal(state: State<'_, AppState>, id: String) -> Result<Goal, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE goals 
-        SET completed_at = NULL, updated_at = ?1
-        WHERE id = ?2
-        "#
-    )
-    .bind(&now)
-    .bind(&id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_goal(state, id).await
+    let repo = Repository::new(state.db.clone());
+    repo.set_goal_completed(&id, false).await.map_err(|e| e.to_string())
+}
+
+/// Sets how far along a goal is
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - UUID string of the goal
+/// * `progress` - Completion percentage, 0-100
+///
+/// # Returns
+/// * `AppResult<Goal>` - The updated goal
+///
+/// # Errors
+/// * Returns `AppError` if the progress is above 100 or the goal doesn't exist
+#[tauri::command]
+pub async fn set_goal_progress(state: State<'_, AppState>, id: String, progress: u8) -> AppResult<Goal> {
+    validate_id(&id)?;
+    validate_progress(progress)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_goal_progress(&id, progress).await
 }
 
 /// Soft deletes a goal (marks as archived) and cascades to all related entities
@@ -237,8 +209,6 @@ pub async fn uncomplete_goal(state: State<'_, AppState>, id: String) -> Result<G
 /// * `Result<(), String>` - Success or error message
 #[tauri::command]
 pub async fn delete_goal(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    use crate::db::repository::Repository;
-    
     let repo = Repository::new(state.db.clone());
     repo.archive_goal_cascade(&id)
         .await
@@ -250,25 +220,23 @@ pub async fn delete_goal(state: State<'_, AppState>, id: String) -> Result<(), S
 /// # Arguments
 /// * `state` - Application state containing the database connection
 /// * `id` - UUID string of the goal to restore
+/// * `cascade` - Also restore the projects, tasks and notes archived
+///   together with the goal
 /// 
 /// # Returns
-/// * `Result<Goal, String>` - The restored goal or error message
+/// * `AppResult<Goal>` - The restored goal
+///
+/// # Errors
+/// * `ARCHIVED_PARENT` - The goal's life area is still archived; the error
+///   data lists it under `archived_parents`
 #[tauri::command]
-pub async fn restore_goal(state: State<'_, AppState>, id: String) -> Result<Goal, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE goals 
-        SET archived_at = NULL, updated_at = ?1
-        WHERE id = ?2
-        "#
-    )
-    .bind(&now)
-    .bind(&id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_goal(state, id).await
+pub async fn restore_goal(
+    state: State<'_, AppState>,
+    id: String,
+    cascade: Option<bool>,
+) -> AppResult<Goal> {
+    let repo = Repository::new(state.db.clone());
+    repo.restore_goal(&id, cascade.unwrap_or(false)).await?;
+    state.switcher.invalidate();
+    repo.get_goal(&id).await
 }
\ No newline at end of file
@@ -0,0 +1,225 @@
+use crate::db::models::{Holiday, HolidayInput, HolidaySet};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+use chrono::NaiveDate;
+use evorbrain_core::import::holidays::{self, MAX_IMPORTED_HOLIDAYS};
+use evorbrain_core::settings;
+use evorbrain_core::validation::{validate_id, validate_title};
+use tauri::State;
+
+/// Longest range `get_holidays` accepts, in days
+const MAX_HOLIDAY_RANGE_DAYS: i64 = 3660;
+
+fn validate_holidays(holidays: &[HolidayInput]) -> AppResult<()> {
+    if holidays.len() > MAX_IMPORTED_HOLIDAYS {
+        return Err(AppError::validation_error(
+            "holidays",
+            &format!("must list at most {} dates", MAX_IMPORTED_HOLIDAYS),
+        ));
+    }
+    for holiday in holidays {
+        validate_title("name", &holiday.name)?;
+    }
+    Ok(())
+}
+
+/// Lists the holiday sets
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<HolidaySet>>` - Every set with its number of holidays
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_holiday_sets(state: State<'_, AppState>) -> AppResult<Vec<HolidaySet>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_holiday_sets().await
+}
+
+/// Defines a holiday set from a list of dates
+///
+/// Holidays of enabled sets are non-working days for working-day deferral,
+/// day capacity and streaks, like the dates in the `holidays` setting.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `name` - Name of the set, e.g. `Netherlands 2025`
+/// * `holidays` - Dates with their names
+///
+/// # Returns
+/// * `AppResult<HolidaySet>` - The created set, enabled
+///
+/// # Errors
+/// * Returns `AppError` if a name is empty or too long, there are too many
+///   dates or the query fails
+#[tauri::command]
+pub async fn create_holiday_set(
+    state: State<'_, AppState>,
+    name: String,
+    holidays: Vec<HolidayInput>,
+) -> AppResult<HolidaySet> {
+    validate_title("name", &name)?;
+    validate_holidays(&holidays)?;
+    let repo = Repository::new(state.db.clone());
+    repo.create_holiday_set(name.trim(), &holidays).await
+}
+
+/// Imports a holiday set from an iCalendar (`.ics`) file
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `name` - Name of the new set
+/// * `content` - The file's contents
+///
+/// # Returns
+/// * `AppResult<HolidaySet>` - The created set, enabled
+///
+/// # Errors
+/// * Returns `AppError` if the file isn't valid iCalendar, has too many
+///   holidays or the query fails
+#[tauri::command]
+pub async fn import_holidays(state: State<'_, AppState>, name: String, content: String) -> AppResult<HolidaySet> {
+    validate_title("name", &name)?;
+    let holidays = holidays::parse_ics(&content)?;
+    validate_holidays(&holidays)?;
+    let repo = Repository::new(state.db.clone());
+    repo.create_holiday_set(name.trim(), &holidays).await
+}
+
+/// Adds a holiday to a set, or renames it if the date is already in the set
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `set_id` - The holiday set
+/// * `date` - The holiday (`YYYY-MM-DD`)
+/// * `name` - Its name
+///
+/// # Returns
+/// * `AppResult<HolidaySet>` - The updated set
+///
+/// # Errors
+/// * Returns `AppError` if the set doesn't exist, the name is invalid or the
+///   query fails
+#[tauri::command]
+pub async fn add_holiday(
+    state: State<'_, AppState>,
+    set_id: String,
+    date: NaiveDate,
+    name: String,
+) -> AppResult<HolidaySet> {
+    validate_id(&set_id)?;
+    validate_title("name", &name)?;
+    let repo = Repository::new(state.db.clone());
+    repo.add_holiday(&set_id, &HolidayInput { date, name: name.trim().to_string() }).await
+}
+
+/// Removes a holiday from a set
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `set_id` - The holiday set
+/// * `date` - The holiday to remove (`YYYY-MM-DD`)
+///
+/// # Returns
+/// * `AppResult<HolidaySet>` - The updated set
+///
+/// # Errors
+/// * Returns `AppError` if the date isn't in the set or the query fails
+#[tauri::command]
+pub async fn remove_holiday(state: State<'_, AppState>, set_id: String, date: NaiveDate) -> AppResult<HolidaySet> {
+    validate_id(&set_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.remove_holiday(&set_id, date).await
+}
+
+/// Enables or disables a holiday set without deleting its holidays
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The holiday set
+/// * `enabled` - Whether its holidays count as non-working days
+///
+/// # Returns
+/// * `AppResult<HolidaySet>` - The updated set
+///
+/// # Errors
+/// * Returns `AppError` if the set doesn't exist or the query fails
+#[tauri::command]
+pub async fn set_holiday_set_enabled(state: State<'_, AppState>, id: String, enabled: bool) -> AppResult<HolidaySet> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_holiday_set_enabled(&id, enabled).await
+}
+
+/// Deletes a holiday set with its holidays
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The holiday set
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the set doesn't exist or the query fails
+#[tauri::command]
+pub async fn delete_holiday_set(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_holiday_set", &confirmation_token)?;
+    let repo = Repository::new(state.db.clone());
+    repo.delete_holiday_set(&id).await
+}
+
+/// Lists the holidays in a date range, for the agenda
+///
+/// Includes the holidays of enabled sets and the dates in the `holidays`
+/// setting; a date in several sets is listed once per set.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `start` - First day (`YYYY-MM-DD`)
+/// * `end` - Last day, inclusive
+///
+/// # Returns
+/// * `AppResult<Vec<Holiday>>` - Holidays by date
+///
+/// # Errors
+/// * Returns `AppError` if the range is reversed or longer than ten years,
+///   or the query fails
+#[tauri::command]
+pub async fn get_holidays(state: State<'_, AppState>, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<Holiday>> {
+    if end < start {
+        return Err(AppError::validation_error("end", "must not be before start"));
+    }
+    if (end - start).num_days() > MAX_HOLIDAY_RANGE_DAYS {
+        return Err(AppError::validation_error("end", "range must be at most ten years"));
+    }
+    let repo = Repository::new(state.db.clone());
+
+    let mut holidays = repo.get_holidays(start, end).await?;
+    holidays.extend(
+        settings::load(&repo)
+            .await?
+            .holidays
+            .into_iter()
+            .filter(|date| (start..=end).contains(date))
+            .map(|date| Holiday {
+                date,
+                name: "Holiday".to_string(),
+                set_id: None,
+                set_name: None,
+            }),
+    );
+    holidays.sort_by_key(|holiday| holiday.date);
+    Ok(holidays)
+}
@@ -0,0 +1,61 @@
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::import::jira::{self, JiraFormat, JiraImportOptions, JiraImportReport, JiraPreview};
+use evorbrain_core::validation::{validate_id, validate_optional_id};
+use tauri::State;
+
+/// Parses a Jira export for the field-mapping step of the import
+///
+/// # Arguments
+/// * `content` - The exported file's contents
+/// * `format` - `csv` or `json`
+///
+/// # Returns
+/// * `AppResult<JiraPreview>` - Available fields, a suggested mapping and the statuses, priorities and sprints found
+///
+/// # Errors
+/// * Returns `AppError` if the content can't be parsed in the given format
+#[tauri::command]
+pub async fn preview_jira_import(content: String, format: JiraFormat) -> AppResult<JiraPreview> {
+    jira::preview(&content, format)
+}
+
+/// Imports a Jira export
+///
+/// Epics become goals with a project of the same name, issues become tasks
+/// and sub-tasks become subtasks. Everything is imported in one transaction;
+/// with `dry_run` set it is rolled back and only the report is returned.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `content` - The exported file's contents
+/// * `format` - `csv` or `json`
+/// * `options` - Target life area, field mapping, status/priority maps and dry run flag
+///
+/// # Returns
+/// * `AppResult<JiraImportReport>` - Counts of what was (or would be) created, and warnings
+///
+/// # Errors
+/// * Returns `AppError` if the content can't be parsed, the life area or
+///   default project doesn't exist, or the import fails (nothing is imported then)
+#[tauri::command]
+pub async fn import_jira(
+    state: State<'_, AppState>,
+    content: String,
+    format: JiraFormat,
+    options: JiraImportOptions,
+) -> AppResult<JiraImportReport> {
+    validate_id(&options.life_area_id)?;
+    validate_optional_id(options.default_project_id.as_deref())?;
+
+    let repo = Repository::new(state.db.clone());
+    let report = state
+        .write_queue
+        .run("import_jira", |progress| async move {
+            jira::import(&repo, &content, format, &options, &progress).await
+        })
+        .await?;
+    state.switcher.invalidate();
+    Ok(report)
+}
@@ -0,0 +1,14 @@
+use crate::command_registry::{CommandInfo, COMMANDS};
+
+/// Lists every registered command with machine-readable metadata
+///
+/// Used by the command palette and by external automation to discover what
+/// can be invoked.
+///
+/// # Returns
+/// * `Vec<CommandInfo>` - Name, description, JSON Schema of the arguments and
+///   `mutating`/`destructive` flags for each command
+#[tauri::command]
+pub fn list_commands() -> Vec<CommandInfo> {
+    COMMANDS.iter().map(CommandInfo::from).collect()
+}
@@ -0,0 +1,128 @@
+use crate::db::models::{Job, JobType};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::jobs;
+use crate::AppState;
+use chrono::NaiveDate;
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::digest::{self, Digest};
+use evorbrain_core::schedule::Schedule;
+use tauri::State;
+
+/// Lists the scheduled background jobs
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<Job>>` - Every job with its last run and, if enabled, its next run
+///
+/// # Errors
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_jobs(state: State<'_, AppState>) -> AppResult<Vec<Job>> {
+    let repo = Repository::new(state.db.clone());
+    jobs::list_jobs(&repo).await
+}
+
+/// Enables or disables a job; a disabled job only runs through `run_job_now`
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `job_type` - The job, e.g. `github_sync`
+/// * `enabled` - Whether the scheduler runs the job
+///
+/// # Returns
+/// * `AppResult<Job>` - The updated job
+///
+/// # Errors
+/// * Returns `AppError` if the database update fails
+#[tauri::command]
+pub async fn set_job_enabled(state: State<'_, AppState>, job_type: JobType, enabled: bool) -> AppResult<Job> {
+    let repo = Repository::new(state.db.clone());
+    repo.set_job_enabled(job_type, enabled).await
+}
+
+/// Changes when a job runs
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `job_type` - The job, e.g. `github_sync`
+/// * `schedule` - Cron expression in the user's time zone, e.g. `0 9 * * 1-5`,
+///   or `@hourly`, `@daily`, `@weekly` or `@monthly`
+///
+/// # Returns
+/// * `AppResult<Job>` - The updated job
+///
+/// # Errors
+/// * Returns `AppError` if the schedule is invalid or the database update fails
+#[tauri::command]
+pub async fn set_job_schedule(state: State<'_, AppState>, job_type: JobType, schedule: String) -> AppResult<Job> {
+    Schedule::parse(&schedule)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_job_schedule(job_type, schedule.trim()).await
+}
+
+/// Runs a job immediately, outside its schedule
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `job_type` - The job, e.g. `github_sync`
+/// * `confirmation_token` - Token from `request_destructive_confirmation`,
+///   needed only for `archive_retention`, which deletes archived items
+///
+/// # Returns
+/// * `AppResult<Job>` - The job with the outcome of this run; a failed run
+///   sets `last_error`
+///
+/// # Errors
+/// * `ALREADY_EXISTS` - The job is already running
+/// * `CONFIRMATION_REQUIRED` - `archive_retention` without a valid token
+#[tauri::command]
+pub async fn run_job_now(
+    state: State<'_, AppState>,
+    job_type: JobType,
+    confirmation_token: Option<String>,
+) -> AppResult<Job> {
+    if matches!(job_type, JobType::ArchiveRetention) {
+        state
+            .confirmations
+            .consume("run_job_now", confirmation_token.as_deref().unwrap_or_default())?;
+    }
+    let repo = Repository::new(state.db.clone());
+    jobs::run_job(&repo, &state.write_queue, &state.attachments, job_type).await
+}
+
+/// Composes a digest of a range of days: completed and upcoming tasks, the
+/// streak and goal progress, as Markdown and HTML
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `from` - First day of the range; defaults to the start of last week
+/// * `to` - Last day of the range, included; defaults to the end of last week
+/// * `deliver` - Also save or send the digest as the `digest_delivery`
+///   setting says, as the `weekly_digest` job does
+///
+/// # Returns
+/// * `AppResult<Digest>` - The digest
+///
+/// # Errors
+/// * `VALIDATION_ERROR` - `to` is before `from` or the range is over a year
+/// * `NOT_FOUND` - Delivery is by webhook and no webhook subscribes to
+///   `digest.generated`
+/// * Returns `AppError` if the database query or the delivery fails
+#[tauri::command]
+pub async fn generate_digest(
+    state: State<'_, AppState>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    deliver: Option<bool>,
+) -> AppResult<Digest> {
+    let repo = Repository::new(state.db.clone());
+    let (last_week_from, last_week_to) = digest::previous_week(&Calendar::load(&repo).await?);
+    let digest = digest::generate_digest(&repo, from.unwrap_or(last_week_from), to.unwrap_or(last_week_to)).await?;
+    if deliver.unwrap_or(false) {
+        crate::digest::deliver(&repo, &digest).await?;
+    }
+    Ok(digest)
+}
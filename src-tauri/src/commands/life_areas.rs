@@ -1,7 +1,10 @@
-use crate::db::models::LifeArea;
+use crate::db::models::{AreaContext, ArchiveSummary, DeleteImpact, LifeArea, LifeAreaBudget};
 use crate::db::repository::Repository;
 use crate::error::{AppError, AppResult};
 use crate::AppState;
+use chrono::{DateTime, Utc};
+use evorbrain_core::conflicts::ensure_unchanged;
+use evorbrain_core::validation::validate_weekly_minutes;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use uuid::Uuid;
@@ -23,6 +26,9 @@ pub struct UpdateLifeAreaRequest {
     pub description: Option<String>,
     pub color: Option<String>,
     pub icon: Option<String>,
+    /// `updated_at` as the client loaded it, to refuse conflicting edits
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
 /// Creates a new life area in the system
@@ -97,6 +103,8 @@ pub async fn get_life_area(state: State<'_, AppState>, id: String) -> AppResult<
 /// 
 /// # Errors
 /// * Returns `AppError` if the ID is invalid, life area not found, or update fails
+/// * Returns a `CONFLICT` error holding the stored life area and the
+///   rejected change if the life area changed after `expected_updated_at`
 #[tauri::command]
 pub async fn update_life_area(
     state: State<'_, AppState>,
@@ -104,7 +112,9 @@ pub async fn update_life_area(
 ) -> AppResult<LifeArea> {
     let _ = Uuid::parse_str(&request.id).map_err(|_| AppError::invalid_id(&request.id))?;
     let repo = Repository::new(state.db.clone());
-    
+    let current = repo.get_life_area(&request.id).await?;
+    ensure_unchanged("life_area", &request.id, request.expected_updated_at, current.updated_at, &current, &request)?;
+
     repo.update_life_area(
         &request.id,
         request.name,
@@ -115,22 +125,93 @@ pub async fn update_life_area(
     .await
 }
 
-/// Soft deletes a life area (marks as archived)
+/// Marks a life area as personal or work, for the active context filter
+/// 
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - UUID string of the life area
+/// * `context` - `personal` or `work`; omit to show the life area in every context
+/// 
+/// # Returns
+/// * `AppResult<LifeArea>` - The updated life area
+/// 
+/// # Errors
+/// * Returns `AppError` if the ID is invalid or the life area doesn't exist or is archived
+#[tauri::command]
+pub async fn set_life_area_context(
+    state: State<'_, AppState>,
+    id: String,
+    context: Option<AreaContext>,
+) -> AppResult<LifeArea> {
+    let _ = Uuid::parse_str(&id).map_err(|_| AppError::invalid_id(&id))?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_life_area_context(&id, context).await
+}
+
+/// Sets how much time the user means to spend on a life area each week
+/// 
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - UUID string of the life area
+/// * `weekly_minutes` - The budget in minutes; omit to remove it
+/// 
+/// # Returns
+/// * `AppResult<Option<LifeAreaBudget>>` - The budget, or `None` once removed
+/// 
+/// # Errors
+/// * Returns `AppError` if the ID or budget is invalid or the life area doesn't exist or is archived
+#[tauri::command]
+pub async fn set_life_area_budget(
+    state: State<'_, AppState>,
+    id: String,
+    weekly_minutes: Option<u32>,
+) -> AppResult<Option<LifeAreaBudget>> {
+    let _ = Uuid::parse_str(&id).map_err(|_| AppError::invalid_id(&id))?;
+    if let Some(weekly_minutes) = weekly_minutes {
+        validate_weekly_minutes(weekly_minutes)?;
+    }
+    let repo = Repository::new(state.db.clone());
+    repo.set_life_area_budget(&id, weekly_minutes.map(i64::from)).await
+}
+
+/// Reports what deleting a life area would archive along with it
+/// 
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - UUID string of the life area
+/// 
+/// # Returns
+/// * `AppResult<DeleteImpact>` - Counts of the active goals, projects, tasks and notes
+/// 
+/// # Errors
+/// * Returns `AppError` if the ID is invalid or life area not found
+#[tauri::command]
+pub async fn get_delete_impact(state: State<'_, AppState>, id: String) -> AppResult<DeleteImpact> {
+    let _ = Uuid::parse_str(&id).map_err(|_| AppError::invalid_id(&id))?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_delete_impact(&id).await
+}
+
+/// Soft deletes a life area (marks as archived) with its goals, projects,
+/// tasks and notes
 /// 
 /// # Arguments
 /// * `state` - Application state containing the database connection
 /// * `id` - UUID string of the life area to delete
+/// * `force` - Required when anything besides the life area would be archived
 /// 
 /// # Returns
 /// * `AppResult<()>` - Success or error
 /// 
 /// # Errors
-/// * Returns `AppError` if the ID is invalid, life area not found, or has active goals
+/// * Returns `AppError` if the ID is invalid or life area not found
+/// * `CANNOT_DELETE` without `force` when the life area isn't empty; the
+///   error data holds the same counts as `get_delete_impact`
 #[tauri::command]
-pub async fn delete_life_area(state: State<'_, AppState>, id: String) -> AppResult<()> {
+pub async fn delete_life_area(state: State<'_, AppState>, id: String, force: Option<bool>) -> AppResult<()> {
     let _ = Uuid::parse_str(&id).map_err(|_| AppError::invalid_id(&id))?;
     let repo = Repository::new(state.db.clone());
-    repo.delete_life_area(&id).await
+    repo.delete_life_area(&id, force.unwrap_or(false)).await
 }
 
 /// Restores a previously deleted life area
@@ -138,6 +219,8 @@ pub async fn delete_life_area(state: State<'_, AppState>, id: String) -> AppResu
 /// # Arguments
 /// * `state` - Application state containing the database connection
 /// * `id` - UUID string of the life area to restore
+/// * `cascade` - Also restore the goals, projects, tasks and notes archived
+///   together with the life area; `get_archive_summary` previews them
 /// 
 /// # Returns
 /// * `AppResult<LifeArea>` - The restored life area
@@ -145,8 +228,37 @@ pub async fn delete_life_area(state: State<'_, AppState>, id: String) -> AppResu
 /// # Errors
 /// * Returns `AppError` if the ID is invalid, life area not found, or not archived
 #[tauri::command]
-pub async fn restore_life_area(state: State<'_, AppState>, id: String) -> AppResult<LifeArea> {
+pub async fn restore_life_area(
+    state: State<'_, AppState>,
+    id: String,
+    cascade: Option<bool>,
+) -> AppResult<LifeArea> {
     let _ = Uuid::parse_str(&id).map_err(|_| AppError::invalid_id(&id))?;
     let repo = Repository::new(state.db.clone());
-    repo.restore_life_area(&id).await
+    let area = repo.restore_life_area(&id, cascade.unwrap_or(false)).await?;
+    state.switcher.invalidate();
+    Ok(area)
+}
+
+/// Reports what was archived along with an archived life area and what a
+/// cascading restore would bring back
+///
+/// Goals, projects, tasks and notes that were archived on their own before
+/// the life area are counted separately; a restore leaves them archived.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `life_area_id` - UUID string of the archived life area
+///
+/// # Returns
+/// * `AppResult<ArchiveSummary>` - Archive time, counts and the restore preview
+///
+/// # Errors
+/// * Returns `AppError` if the ID is invalid or the life area doesn't exist
+///   or isn't archived
+#[tauri::command]
+pub async fn get_archive_summary(state: State<'_, AppState>, life_area_id: String) -> AppResult<ArchiveSummary> {
+    let _ = Uuid::parse_str(&life_area_id).map_err(|_| AppError::invalid_id(&life_area_id))?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_archive_summary(&life_area_id).await
 }
\ No newline at end of file
@@ -1,9 +1,18 @@
 //! Migration command handlers for database schema management
 
+use crate::db::migrations::{all, MigrationRunner};
 use crate::AppState;
 use anyhow::Result;
+use std::path::Path;
 use tauri::State;
 
+/// ` (backup: <path>)` for result messages, or nothing without a backup
+pub(crate) fn backup_note(backup: Option<&Path>) -> String {
+    backup
+        .map(|path| format!(" (backup: {})", path.display()))
+        .unwrap_or_default()
+}
+
 /// Gets the current migration status showing applied and pending migrations
 /// 
 /// # Arguments
@@ -13,13 +22,13 @@ use tauri::State;
 /// * `Result<String, String>` - Formatted status of all migrations
 #[tauri::command]
 pub async fn get_migration_status(state: State<'_, AppState>) -> Result<String, String> {
-    let runner = super::MigrationRunner::new((*state.db).clone());
+    let runner = MigrationRunner::new((*state.db).clone());
     
     let applied = runner.get_applied_migrations()
         .await
         .map_err(|e| e.to_string())?;
     
-    let all_migrations = super::all::get_migrations();
+    let all_migrations = all::get_migrations();
     
     let mut status = String::from("Migration Status:\n\n");
     
@@ -45,23 +54,25 @@ pub async fn get_migration_status(state: State<'_, AppState>) -> Result<String,
 }
 
 /// Runs all pending database migrations
+///
+/// An existing database is backed up before any migration is applied.
 /// 
 /// # Arguments
 /// * `state` - Application state containing the database connection
 /// 
 /// # Returns
-/// * `Result<String, String>` - Success message with count of applied migrations
+/// * `Result<String, String>` - Success message with count of applied migrations and the backup path
 #[tauri::command]
 pub async fn run_migrations(state: State<'_, AppState>) -> Result<String, String> {
-    let runner = super::MigrationRunner::new((*state.db).clone());
-    let all_migrations = super::all::get_migrations();
+    let runner = MigrationRunner::new((*state.db).clone());
+    let all_migrations = all::get_migrations();
     
     let before_count = runner.get_applied_migrations()
         .await
         .map_err(|e| e.to_string())?
         .len();
     
-    runner.migrate(&all_migrations)
+    let backup = runner.migrate(&all_migrations)
         .await
         .map_err(|e| e.to_string())?;
     
@@ -73,7 +84,7 @@ pub async fn run_migrations(state: State<'_, AppState>) -> Result<String, String
     let applied = after_count - before_count;
     
     if applied > 0 {
-        Ok(format!("Successfully applied {} migration(s)", applied))
+        Ok(format!("Successfully applied {} migration(s){}", applied, backup_note(backup.as_deref())))
     } else {
         Ok("All migrations are already up to date".to_string())
     }
@@ -84,12 +95,21 @@ pub async fn run_migrations(state: State<'_, AppState>) -> Result<String, String
 /// # Arguments
 /// * `state` - Application state containing the database connection
 /// * `target_version` - Optional target version to rollback to (None rolls back one migration)
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
 /// 
 /// # Returns
-/// * `Result<String, String>` - Success message with rollback details
+/// * `Result<String, String>` - Success message with rollback details, or an error if the token isn't valid
 #[tauri::command]
-pub async fn rollback_migration(state: State<'_, AppState>, target_version: Option<i64>) -> Result<String, String> {
-    let runner = super::MigrationRunner::new((*state.db).clone());
+pub async fn rollback_migration(
+    state: State<'_, AppState>,
+    target_version: Option<i64>,
+    confirmation_token: String,
+) -> Result<String, String> {
+    state.confirmations
+        .consume("rollback_migration", &confirmation_token)
+        .map_err(|e| e.to_string())?;
+
+    let runner = MigrationRunner::new((*state.db).clone());
     
     let before_version = runner.get_latest_version()
         .await
@@ -98,6 +118,7 @@ pub async fn rollback_migration(state: State<'_, AppState>, target_version: Opti
     runner.rollback(target_version)
         .await
         .map_err(|e| e.to_string())?;
+    state.switcher.invalidate();
     
     let after_version = runner.get_latest_version()
         .await
@@ -118,15 +139,21 @@ pub async fn rollback_migration(state: State<'_, AppState>, target_version: Opti
 
 /// Resets the database by rolling back all migrations and re-applying them
 /// 
-/// **Note**: Only available in debug builds for safety
+/// **Note**: Only available in debug builds for safety. The database is
+/// backed up first.
 /// 
 /// # Arguments
-/// * `_state` - Application state containing the database connection
+/// * `state` - Application state containing the database connection
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
 /// 
 /// # Returns
-/// * `Result<String, String>` - Success message or error if not in debug mode
+/// * `Result<String, String>` - Success message, or an error if not in debug mode or the token isn't valid
 #[tauri::command]
-pub async fn reset_database(_state: State<'_, AppState>) -> Result<String, String> {
+pub async fn reset_database(state: State<'_, AppState>, confirmation_token: String) -> Result<String, String> {
+    state.confirmations
+        .consume("reset_database", &confirmation_token)
+        .map_err(|e| e.to_string())?;
+
     #[cfg(not(debug_assertions))]
     {
         return Err("Database reset is only available in development mode".to_string());
@@ -134,23 +161,30 @@ pub async fn reset_database(_state: State<'_, AppState>) -> Result<String, Strin
     
     #[cfg(debug_assertions)]
     {
+        use crate::db::backup;
         use sqlx::Executor;
         
-        let runner = super::MigrationRunner::new((*_state.db).clone());
+        let backup = backup::snapshot(&state.db, "reset")
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let runner = MigrationRunner::new((*state.db).clone());
         
         runner.rollback(Some(0))
             .await
             .map_err(|e| e.to_string())?;
         
-        (*_state.db).execute("DROP TABLE IF EXISTS _migrations")
+        (*state.db).execute("DROP TABLE IF EXISTS _migrations")
             .await
             .map_err(|e| e.to_string())?;
         
-        let all_migrations = super::all::get_migrations();
+        let all_migrations = all::get_migrations();
         runner.migrate(&all_migrations)
             .await
             .map_err(|e| e.to_string())?;
+
+        state.switcher.invalidate();
         
-        Ok("Database reset successfully".to_string())
+        Ok(format!("Database reset successfully{}", backup_note(backup.as_deref())))
     }
 }
\ No newline at end of file
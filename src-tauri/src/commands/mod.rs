@@ -13,15 +13,121 @@ pub mod projects;
 pub mod tasks;
 /// Commands for managing notes attached to various entities
 pub mod notes;
+/// Commands for fast quick capture
+pub mod capture;
+/// Commands for routing evorbrain:// deep links
+pub mod deep_link;
+/// Commands for tray and dock badge counts
+pub mod badges;
+/// Commands for configuring email-to-task import
+pub mod email;
+/// Commands for external links, web link metadata and GitHub issue sync
+pub mod external_links;
+/// Commands for importing data from other tools
+pub mod import;
 /// Commands for application logging and diagnostics
 pub mod logging;
 /// Commands for database maintenance and repository operations
 pub mod repository;
+/// Commands for reading the change log for incremental updates
+pub mod changes;
+/// Commands for controlling the optional local REST API server
+pub mod api_server;
+/// Commands for database schema migration management
+pub mod migrations;
+/// Commands for managing outgoing webhooks
+pub mod webhooks;
+/// Commands for managing user scripting hooks
+pub mod script_hooks;
+/// Commands for discovering the available commands
+pub mod introspection;
+/// Commands for reading and changing application settings
+pub mod settings;
+/// Commands for the passphrase app lock
+pub mod app_lock;
+/// Commands for first-run onboarding
+pub mod onboarding;
+/// Commands for per-project task workflows
+pub mod workflows;
+/// Commands for saved per-project view layouts
+pub mod views;
+/// Commands for the quick switcher
+pub mod switcher;
+/// Commands for working-day aware scheduling
+pub mod scheduling;
+/// Commands for the daily focus list
+pub mod my_day;
+/// Commands for GTD contexts
+pub mod contexts;
+/// Commands for people and delegated (waiting-for) tasks
+pub mod people;
+/// Commands for task dependencies and snoozes
+pub mod blockers;
+/// Commands for file attachments
+pub mod attachments;
+/// Commands for achievements, streaks and points
+pub mod achievements;
+/// Commands for project review cadence
+pub mod reviews;
+/// Commands for the someday/maybe list
+pub mod someday;
+/// Commands for timers and time entries
+pub mod time_tracking;
+/// Commands for holiday sets
+pub mod holidays;
+/// Commands for project velocity
+pub mod velocity;
+/// Commands for task aging and completion latency
+pub mod aging;
+/// Commands for scheduled background jobs
+pub mod jobs;
+/// Commands for startup diagnostics
+pub mod startup;
+pub mod read_only;
+pub mod dashboard;
+/// Versioned commands answering with a consistent envelope and camelCase DTOs
+pub mod v2;
 
 pub use life_areas::*;
 pub use goals::*;
 pub use projects::*;
 pub use tasks::*;
 pub use notes::*;
+pub use capture::*;
+pub use deep_link::*;
+pub use badges::*;
+pub use email::*;
+pub use external_links::*;
+pub use import::*;
 pub use logging::*;
-pub use repository::*;
\ No newline at end of file
+pub use repository::*;
+pub use changes::*;
+pub use api_server::*;
+pub use migrations::*;
+pub use webhooks::*;
+pub use script_hooks::*;
+pub use introspection::*;
+pub use settings::*;
+pub use app_lock::*;
+pub use onboarding::*;
+pub use workflows::*;
+pub use views::*;
+pub use switcher::*;
+pub use scheduling::*;
+pub use my_day::*;
+pub use contexts::*;
+pub use people::*;
+pub use blockers::*;
+pub use attachments::*;
+pub use achievements::*;
+pub use reviews::*;
+pub use someday::*;
+pub use time_tracking::*;
+pub use holidays::*;
+pub use velocity::*;
+pub use aging::*;
+pub use jobs::*;
+pub use startup::*;
+pub use read_only::*;
+pub use dashboard::*;
+pub use v2::*;
\ No newline at end of file
@@ -0,0 +1,273 @@
+use crate::db::models::{DayCapacity, DayPlan, MyDay};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+use chrono::{Duration, NaiveDate};
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+/// Most minutes that can be planned for one task on one day
+const MAX_PLANNED_MINUTES: u32 = 24 * 60;
+
+fn validate_planned_minutes(minutes: Option<u32>) -> AppResult<()> {
+    if minutes.is_some_and(|minutes| minutes > MAX_PLANNED_MINUTES) {
+        return Err(AppError::validation_error(
+            "planned_minutes",
+            &format!("must be at most {} (one day)", MAX_PLANNED_MINUTES),
+        ));
+    }
+    Ok(())
+}
+
+/// Resolves an optional date to a day in the user's time zone
+async fn resolve_date(repo: &Repository, date: Option<NaiveDate>) -> AppResult<(Calendar, NaiveDate)> {
+    let calendar = Calendar::load(repo).await?;
+    let date = date.unwrap_or_else(|| calendar.today());
+    Ok((calendar, date))
+}
+
+/// Adds a task to a day's focus list ("My Day")
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task to focus on
+/// * `date` - The day (`YYYY-MM-DD`); defaults to today
+/// * `planned_minutes` - Time set aside for the task that day; defaults to
+///   its estimate
+///
+/// # Returns
+/// * `AppResult<MyDay>` - The updated day
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist, the minutes exceed a day
+///   or the query fails
+#[tauri::command]
+pub async fn add_to_my_day(
+    state: State<'_, AppState>,
+    task_id: String,
+    date: Option<NaiveDate>,
+    planned_minutes: Option<u32>,
+) -> AppResult<MyDay> {
+    validate_id(&task_id)?;
+    validate_planned_minutes(planned_minutes)?;
+    let repo = Repository::new(state.db.clone());
+    let (calendar, date) = resolve_date(&repo, date).await?;
+
+    repo.add_to_day_plan(date, &task_id, planned_minutes).await?;
+    my_day(&repo, &calendar, date).await
+}
+
+/// Removes a task from a day's focus list
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task to remove
+/// * `date` - The day (`YYYY-MM-DD`); defaults to today
+///
+/// # Returns
+/// * `AppResult<MyDay>` - The updated day
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn remove_from_my_day(
+    state: State<'_, AppState>,
+    task_id: String,
+    date: Option<NaiveDate>,
+) -> AppResult<MyDay> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    let (calendar, date) = resolve_date(&repo, date).await?;
+
+    repo.remove_from_day_plan(date, &task_id).await?;
+    my_day(&repo, &calendar, date).await
+}
+
+/// Returns a day's focus list together with the tasks due that day
+///
+/// Every day starts with an empty focus list. Unfinished tasks from the
+/// previous plan are returned as `carried_over` so they can be added again,
+/// and today's view also lists overdue tasks as due.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `date` - The day (`YYYY-MM-DD`); defaults to today
+///
+/// # Returns
+/// * `AppResult<MyDay>` - Focused, due and carried-over tasks
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_my_day(state: State<'_, AppState>, date: Option<NaiveDate>) -> AppResult<MyDay> {
+    let repo = Repository::new(state.db.clone());
+    let (calendar, date) = resolve_date(&repo, date).await?;
+    my_day(&repo, &calendar, date).await
+}
+
+/// Returns a day's focus list as a timeboxed plan
+///
+/// Items are laid out back to back in plan order from the start of the
+/// working day, each taking its planned minutes or else its estimate. Items
+/// with neither get no timebox, and items ending after the working day are
+/// flagged as overflowing.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `date` - The day (`YYYY-MM-DD`); defaults to today
+///
+/// # Returns
+/// * `AppResult<DayPlan>` - The plan items with their timeboxes
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_day_plan(state: State<'_, AppState>, date: Option<NaiveDate>) -> AppResult<DayPlan> {
+    let repo = Repository::new(state.db.clone());
+    let (calendar, date) = resolve_date(&repo, date).await?;
+    day_plan(&repo, &calendar, date).await
+}
+
+/// Sets the minutes planned for a task on a day's plan
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - A task on the day's plan
+/// * `minutes` - Time set aside for it; `None` falls back to its estimate
+/// * `date` - The day (`YYYY-MM-DD`); defaults to today
+///
+/// # Returns
+/// * `AppResult<DayPlan>` - The updated plan
+///
+/// # Errors
+/// * Returns `AppError` if the task isn't on the plan, the minutes exceed a
+///   day or the query fails
+#[tauri::command]
+pub async fn set_planned_minutes(
+    state: State<'_, AppState>,
+    task_id: String,
+    minutes: Option<u32>,
+    date: Option<NaiveDate>,
+) -> AppResult<DayPlan> {
+    validate_id(&task_id)?;
+    validate_planned_minutes(minutes)?;
+    let repo = Repository::new(state.db.clone());
+    let (calendar, date) = resolve_date(&repo, date).await?;
+
+    repo.set_day_plan_minutes(date, &task_id, minutes).await?;
+    day_plan(&repo, &calendar, date).await
+}
+
+/// Reorders a day's plan
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_ids` - Tasks in their new order; tasks on the plan that aren't
+///   listed keep their relative order after them
+/// * `date` - The day (`YYYY-MM-DD`); defaults to today
+///
+/// # Returns
+/// * `AppResult<DayPlan>` - The reordered plan
+///
+/// # Errors
+/// * Returns `AppError` if a task isn't on the plan or the query fails
+#[tauri::command]
+pub async fn reorder_day_plan(
+    state: State<'_, AppState>,
+    task_ids: Vec<String>,
+    date: Option<NaiveDate>,
+) -> AppResult<DayPlan> {
+    for task_id in &task_ids {
+        validate_id(task_id)?;
+    }
+    let repo = Repository::new(state.db.clone());
+    let (calendar, date) = resolve_date(&repo, date).await?;
+
+    repo.reorder_day_plan(date, &task_ids).await?;
+    day_plan(&repo, &calendar, date).await
+}
+
+/// Compares the estimated work planned for a day with its working hours
+///
+/// Planned work is every open task on the day's focus list or due that day
+/// (for today, overdue tasks too), taking the minutes planned on the day's
+/// plan over the estimate. Tasks with neither are counted separately, since
+/// they make the total an underestimate.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `date` - The day (`YYYY-MM-DD`); defaults to today
+///
+/// # Returns
+/// * `AppResult<DayCapacity>` - Capacity, planned minutes and a warning
+///   when the day is over-committed
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_day_capacity(state: State<'_, AppState>, date: Option<NaiveDate>) -> AppResult<DayCapacity> {
+    let repo = Repository::new(state.db.clone());
+    let (calendar, date) = resolve_date(&repo, date).await?;
+    let day = calendar.day(date);
+    let load = repo.get_day_load(date, day.start, day.end, date == calendar.today()).await?;
+
+    let capacity_minutes = calendar.working_minutes(date);
+    let over_committed = load.planned_minutes > capacity_minutes;
+    let locale = calendar.locale();
+    let warning = over_committed.then(|| {
+        if capacity_minutes == 0 {
+            format!(
+                "{} is not a working day but has {} minutes of work planned",
+                calendar.format_date_with_weekday(date),
+                locale.format_number(load.planned_minutes)
+            )
+        } else {
+            format!(
+                "Planned work exceeds the {} working minutes on {} by {} minutes",
+                locale.format_number(capacity_minutes),
+                calendar.format_date_with_weekday(date),
+                locale.format_number(load.planned_minutes - capacity_minutes)
+            )
+        }
+    });
+
+    Ok(DayCapacity {
+        date,
+        capacity_minutes,
+        planned_minutes: load.planned_minutes,
+        task_count: load.task_count,
+        unestimated_count: load.unestimated_count,
+        over_committed,
+        warning,
+    })
+}
+
+async fn my_day(repo: &Repository, calendar: &Calendar, date: NaiveDate) -> AppResult<MyDay> {
+    let day = calendar.day(date);
+    repo.get_my_day(date, day.start, day.end, date == calendar.today()).await
+}
+
+async fn day_plan(repo: &Repository, calendar: &Calendar, date: NaiveDate) -> AppResult<DayPlan> {
+    let mut items = repo.get_day_plan_items(date).await?;
+    let hours = calendar.working_hours(date);
+
+    let mut cursor = hours.start;
+    let mut planned_minutes = 0;
+    for item in &mut items {
+        let Some(minutes) = item.duration_minutes() else { continue };
+        let ends_at = cursor + Duration::minutes(minutes);
+        item.starts_at = Some(cursor);
+        item.ends_at = Some(ends_at);
+        item.overflows = ends_at > hours.end;
+        cursor = ends_at;
+        planned_minutes += minutes;
+    }
+
+    Ok(DayPlan {
+        date,
+        items,
+        planned_minutes,
+        capacity_minutes: calendar.working_minutes(date),
+    })
+}
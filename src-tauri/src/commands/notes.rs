@@ -1,9 +1,16 @@
-use crate::db::models::Note;
+use crate::db::models::{MeetingNoteSummary, Note, NoteInput, NoteSearchScope, ReferencedEntity, Tag, Task};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::note_encryption::{reveal, reveal_all, seal_content};
+use crate::streaming::{self, StreamStarted};
 use crate::AppState;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, Utc};
+use evorbrain_core::conflicts::ensure_unchanged;
+use evorbrain_core::mentions::{self, MentionRescan};
+use evorbrain_core::note_graph::{self, GraphFormat, NoteGraph};
+use evorbrain_core::validation::{validate_id, validate_note_search_scope};
 use serde::{Deserialize, Serialize};
-use tauri::State;
-use uuid::Uuid;
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateNoteRequest {
@@ -13,6 +20,11 @@ pub struct CreateNoteRequest {
     pub life_area_id: Option<String>,
     pub title: String,
     pub content: String,
+    /// Encrypt the content; requires the app lock to be set and unlocked
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub note_date: Option<NaiveDate>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,6 +36,13 @@ pub struct UpdateNoteRequest {
     pub life_area_id: Option<String>,
     pub title: String,
     pub content: String,
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub note_date: Option<NaiveDate>,
+    /// `updated_at` as the client loaded it, to refuse conflicting edits
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
 #[tauri::command]
@@ -31,45 +50,58 @@ pub async fn create_note(
     state: State<'_, AppState>,
     request: CreateNoteRequest,
 ) -> Result<Note, String> {
-    let id = Uuid::new_v4().to_string();
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        INSERT INTO notes (id, task_id, project_id, goal_id, life_area_id, title, content, created_at, updated_at)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-        "#
-    )
-    .bind(&id)
-    .bind(&request.task_id)
-    .bind(&request.project_id)
-    .bind(&request.goal_id)
-    .bind(&request.life_area_id)
-    .bind(&request.title)
-    .bind(&request.content)
-    .bind(&now)
-    .bind(&now)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_note(state, id).await
+    let repo = Repository::new(state.db.clone());
+    let content = seal_content(&state.lock, request.is_private, &request.content).map_err(|e| e.to_string())?;
+
+    let note = repo
+        .create_note(NoteInput {
+            task_id: request.task_id,
+            project_id: request.project_id,
+            goal_id: request.goal_id,
+            life_area_id: request.life_area_id,
+            title: request.title,
+            content,
+            is_private: request.is_private,
+            note_date: request.note_date,
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    reveal(&state.lock, note).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn get_notes(state: State<'_, AppState>) -> Result<Vec<Note>, String> {
-    sqlx::query_as::<_, Note>(
-        r#"
-        SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
-               created_at, updated_at, archived_at
-        FROM notes
-        WHERE archived_at IS NULL
-        ORDER BY updated_at DESC
-        "#
-    )
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    let notes = repo.get_notes().await.map_err(|e| e.to_string())?;
+    Ok(reveal_all(&state.lock, notes))
+}
+
+/// Streams all active notes as chunk events instead of one response
+///
+/// # Arguments
+/// * `app_handle` - Used to emit the chunk events
+/// * `state` - Application state containing the database connection
+/// * `stream_id` - ID the chunk events carry, chosen by the caller (a UUID)
+///
+/// # Returns
+/// * `AppResult<StreamStarted>` - The number of notes that will be streamed
+///
+/// # Errors
+/// * Returns `AppError` if the stream ID is malformed, the query fails or a
+///   private note can't be decrypted
+#[tauri::command]
+pub async fn stream_notes(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    stream_id: String,
+) -> AppResult<StreamStarted> {
+    validate_id(&stream_id)?;
+    let repo = Repository::new(state.db.clone());
+    let notes = reveal_all(&state.lock, repo.get_notes().await?);
+
+    let items = notes.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    Ok(streaming::start(app_handle, stream_id, vec![("notes".to_string(), items)]))
 }
 
 #[tauri::command]
@@ -77,10 +109,11 @@ pub async fn get_notes_by_task(
     state: State<'_, AppState>,
     task_id: String,
 ) -> Result<Vec<Note>, String> {
-    sqlx::query_as::<_, Note>(
+    let notes = sqlx::query_as::<_, Note>(
         r#"
         SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
-               created_at, updated_at, archived_at
+               created_at, updated_at, archived_at, is_private, note_date,
+               review_stage, next_review_at, last_reviewed_at
         FROM notes
         WHERE task_id = ?1 AND archived_at IS NULL
         ORDER BY created_at DESC
@@ -89,7 +122,9 @@ pub async fn get_notes_by_task(
     .bind(&task_id)
     .fetch_all(&*state.db)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    Ok(reveal_all(&state.lock, notes))
 }
 
 #[tauri::command]
@@ -97,10 +132,11 @@ pub async fn get_notes_by_project(
     state: State<'_, AppState>,
     project_id: String,
 ) -> Result<Vec<Note>, String> {
-    sqlx::query_as::<_, Note>(
+    let notes = sqlx::query_as::<_, Note>(
         r#"
         SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
-               created_at, updated_at, archived_at
+               created_at, updated_at, archived_at, is_private, note_date,
+               review_stage, next_review_at, last_reviewed_at
         FROM notes
         WHERE project_id = ?1 AND archived_at IS NULL
         ORDER BY created_at DESC
@@ -109,7 +145,9 @@ pub async fn get_notes_by_project(
     .bind(&project_id)
     .fetch_all(&*state.db)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    Ok(reveal_all(&state.lock, notes))
 }
 
 #[tauri::command]
@@ -117,10 +155,11 @@ pub async fn get_notes_by_goal(
     state: State<'_, AppState>,
     goal_id: String,
 ) -> Result<Vec<Note>, String> {
-    sqlx::query_as::<_, Note>(
+    let notes = sqlx::query_as::<_, Note>(
         r#"
         SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
-               created_at, updated_at, archived_at
+               created_at, updated_at, archived_at, is_private, note_date,
+               review_stage, next_review_at, last_reviewed_at
         FROM notes
         WHERE goal_id = ?1 AND archived_at IS NULL
         ORDER BY created_at DESC
@@ -129,7 +168,9 @@ pub async fn get_notes_by_goal(
     .bind(&goal_id)
     .fetch_all(&*state.db)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    Ok(reveal_all(&state.lock, notes))
 }
 
 #[tauri::command]
@@ -137,10 +178,11 @@ pub async fn get_notes_by_life_area(
     state: State<'_, AppState>,
     life_area_id: String,
 ) -> Result<Vec<Note>, String> {
-    sqlx::query_as::<_, Note>(
+    let notes = sqlx::query_as::<_, Note>(
         r#"
         SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
-               created_at, updated_at, archived_at
+               created_at, updated_at, archived_at, is_private, note_date,
+               review_stage, next_review_at, last_reviewed_at
         FROM notes
         WHERE life_area_id = ?1 AND archived_at IS NULL
         ORDER BY created_at DESC
@@ -149,59 +191,60 @@ pub async fn get_notes_by_life_area(
     .bind(&life_area_id)
     .fetch_all(&*state.db)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    Ok(reveal_all(&state.lock, notes))
 }
 
 #[tauri::command]
 pub async fn get_note(state: State<'_, AppState>, id: String) -> Result<Note, String> {
-    sqlx::query_as::<_, Note>(
-        r#"
-        SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
-               created_at, updated_at, archived_at
-        FROM notes
-        WHERE id = ?1
-        "#
-    )
-    .bind(&id)
-    .fetch_one(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    let note = repo.get_note(&id).await.map_err(|e| e.to_string())?;
+    reveal(&state.lock, note).map_err(|e| e.to_string())
 }
 
+/// Updates an existing note
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Update request containing ID and fields to update
+///
+/// # Returns
+/// * `AppResult<Note>` - The updated note, with private content revealed
+///
+/// # Errors
+/// * Returns `AppError` if the note doesn't exist, private content can't be
+///   sealed or the update fails
+/// * Returns a `CONFLICT` error holding the stored note and the rejected
+///   change if the note changed after `expected_updated_at`
 #[tauri::command]
-pub async fn update_note(
-    state: State<'_, AppState>,
-    request: UpdateNoteRequest,
-) -> Result<Note, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE notes 
-        SET task_id = ?1, project_id = ?2, goal_id = ?3, life_area_id = ?4, 
-            title = ?5, content = ?6, updated_at = ?7
-        WHERE id = ?8
-        "#
-    )
-    .bind(&request.task_id)
-    .bind(&request.project_id)
-    .bind(&request.goal_id)
-    .bind(&request.life_area_id)
-    .bind(&request.title)
-    .bind(&request.content)
-    .bind(&now)
-    .bind(&request.id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_note(state, request.id).await
+pub async fn update_note(state: State<'_, AppState>, request: UpdateNoteRequest) -> AppResult<Note> {
+    let repo = Repository::new(state.db.clone());
+    let current = reveal(&state.lock, repo.get_note(&request.id).await?)?;
+    ensure_unchanged("note", &request.id, request.expected_updated_at, current.updated_at, &current, &request)?;
+    let content = seal_content(&state.lock, request.is_private, &request.content)?;
+
+    let note = repo
+        .update_note(
+            &request.id,
+            NoteInput {
+                task_id: request.task_id,
+                project_id: request.project_id,
+                goal_id: request.goal_id,
+                life_area_id: request.life_area_id,
+                title: request.title,
+                content,
+                is_private: request.is_private,
+                note_date: request.note_date,
+            },
+        )
+        .await?;
+
+    reveal(&state.lock, note)
 }
 
 #[tauri::command]
 pub async fn delete_note(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    use crate::db::repository::Repository;
-    
     let repo = Repository::new(state.db.clone());
     repo.archive_note(&id)
         .await
@@ -210,44 +253,199 @@ pub async fn delete_note(state: State<'_, AppState>, id: String) -> Result<(), S
 
 #[tauri::command]
 pub async fn restore_note(state: State<'_, AppState>, id: String) -> Result<Note, String> {
-    let now = Utc::now();
-    
     sqlx::query(
         r#"
         UPDATE notes 
-        SET archived_at = NULL, updated_at = ?1
-        WHERE id = ?2
+        SET archived_at = NULL
+        WHERE id = ?1
         "#
     )
-    .bind(&now)
     .bind(&id)
     .execute(&*state.db)
     .await
     .map_err(|e| e.to_string())?;
+    state.switcher.invalidate();
     
     get_note(state, id).await
 }
 
+/// Searches note titles, and the content of notes that aren't private
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `query` - Text to look for
+/// * `project_id` - Only notes of this project, its tasks included
+/// * `goal_id` - Only notes of this goal and everything beneath it
+/// * `life_area_id` - Only notes of this life area and everything beneath it
+///
+/// # Returns
+/// * `Result<Vec<Note>, String>` - Up to 50 notes, most recently updated first
+///
+/// # Errors
+/// * Returns an error if more than one scope is given, an ID is malformed or
+///   the query fails
 #[tauri::command]
 pub async fn search_notes(
     state: State<'_, AppState>,
     query: String,
+    project_id: Option<String>,
+    goal_id: Option<String>,
+    life_area_id: Option<String>,
 ) -> Result<Vec<Note>, String> {
-    let search_pattern = format!("%{}%", query);
-    
-    sqlx::query_as::<_, Note>(
-        r#"
-        SELECT id, task_id, project_id, goal_id, life_area_id, title, content,
-               created_at, updated_at, archived_at
-        FROM notes
-        WHERE archived_at IS NULL
-          AND (title LIKE ?1 OR content LIKE ?1)
-        ORDER BY updated_at DESC
-        LIMIT 50
-        "#
-    )
-    .bind(&search_pattern)
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
-}
\ No newline at end of file
+    let scope = NoteSearchScope {
+        project_id,
+        goal_id,
+        life_area_id,
+    };
+    validate_note_search_scope(&scope).map_err(|e| e.to_string())?;
+
+    let repo = Repository::new(state.db.clone());
+    let notes = repo.search_notes(&query, &scope, 50).await.map_err(|e| e.to_string())?;
+    Ok(reveal_all(&state.lock, notes))
+}
+#[tauri::command]
+pub async fn get_note_tags(state: State<'_, AppState>, note_id: String) -> Result<Vec<Tag>, String> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_note_tags(&note_id).await.map_err(|e| e.to_string())
+}
+
+/// Turns a note into a task, with the note's open `- [ ]` items as subtasks
+///
+/// The task goes under the note's task if it has one, else into the note's
+/// project, else into the inbox; the note is kept, attached to the task.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `note_id` - The note to convert
+///
+/// # Returns
+/// * `AppResult<Task>` - The new task
+///
+/// # Errors
+/// * Returns `AppError` if the note doesn't exist, is archived or is private
+#[tauri::command]
+pub async fn convert_note_to_task(state: State<'_, AppState>, note_id: String) -> AppResult<Task> {
+    validate_id(&note_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.convert_note_to_task(&note_id).await
+}
+
+/// Turns the open `- [ ]` items of a note into tasks linked back to it
+///
+/// The tasks are subtasks of the note's task if it has one, else they go
+/// into the note's project or the inbox. Each item becomes a link to its
+/// task, so extracting again only picks up new items.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `note_id` - The note to extract tasks from
+///
+/// # Returns
+/// * `AppResult<Vec<Task>>` - The new tasks, in the order of the items
+///
+/// # Errors
+/// * Returns `AppError` if the note doesn't exist, is archived or is private
+#[tauri::command]
+pub async fn extract_tasks_from_note(state: State<'_, AppState>, note_id: String) -> AppResult<Vec<Task>> {
+    validate_id(&note_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.extract_tasks_from_note(&note_id).await
+}
+
+/// Collects the decisions of a meeting note and turns its action items into
+/// delegated tasks
+///
+/// The note has to be tagged `meeting`. Action items are open `- [ ]` items
+/// mentioning someone (`@jane`); each becomes a task delegated to that
+/// person, referring back to the note, and the item a link to the task.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `note_id` - The meeting note
+///
+/// # Returns
+/// * `AppResult<MeetingNoteSummary>` - The decisions, the new delegated tasks
+///   and the action items no person could be found for
+///
+/// # Errors
+/// * Returns `AppError` if the note doesn't exist, is archived, is private or
+///   isn't tagged as a meeting
+#[tauri::command]
+pub async fn process_meeting_note(state: State<'_, AppState>, note_id: String) -> AppResult<MeetingNoteSummary> {
+    validate_id(&note_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.process_meeting_note(&note_id).await
+}
+
+/// Builds the graph of notes and the tasks, projects, goals, life areas and
+/// tags they are attached to or link to, for a graph view in the app or in
+/// tools like Gephi
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `format` - `json` (default) or `graphml`; only used with `path`
+/// * `path` - Where to also write the graph, if anywhere
+///
+/// # Returns
+/// * `AppResult<NoteGraph>` - The nodes and directed edges
+///
+/// # Errors
+/// * Returns `AppError` if the database query fails or the file can't be
+///   written
+#[tauri::command]
+pub async fn export_note_graph(
+    state: State<'_, AppState>,
+    format: Option<GraphFormat>,
+    path: Option<String>,
+) -> AppResult<NoteGraph> {
+    let repo = Repository::new(state.db.clone());
+    let graph = note_graph::build_note_graph(&repo).await?;
+
+    if let Some(path) = path {
+        let content = match format.unwrap_or_default() {
+            GraphFormat::Json => serde_json::to_string_pretty(&graph)?,
+            GraphFormat::GraphMl => graph.to_graphml(),
+        };
+        tokio::fs::write(&path, content).await.map_err(|e| {
+            AppError::new(ErrorCode::IoError, "Failed to write the note graph").with_details(e.to_string())
+        })?;
+    }
+    Ok(graph)
+}
+
+/// Returns the tasks and projects a note mentions by title or `#<id>`
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `note_id` - The note whose mentions to return
+///
+/// # Returns
+/// * `AppResult<Vec<ReferencedEntity>>` - The mentioned tasks and projects
+///   that still exist, in the order the note mentions them
+///
+/// # Errors
+/// * Returns `AppError` if the note doesn't exist or the query fails
+#[tauri::command]
+pub async fn get_note_mentions(state: State<'_, AppState>, note_id: String) -> AppResult<Vec<ReferencedEntity>> {
+    validate_id(&note_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_note_mentions(&note_id).await
+}
+
+/// Records the mentions of every note again, e.g. after tasks were renamed
+/// or with `auto_link_mentions` turned off for a while
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<MentionRescan>` - How many notes were scanned and how many
+///   mentions they have
+///
+/// # Errors
+/// * Returns `AppError` if reading the notes or writing their mentions fails
+#[tauri::command]
+pub async fn rescan_note_mentions(state: State<'_, AppState>) -> AppResult<MentionRescan> {
+    let repo = Repository::new(state.db.clone());
+    mentions::rescan(&repo).await
+}
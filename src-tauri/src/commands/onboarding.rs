@@ -0,0 +1,33 @@
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::events::AppEvent;
+use evorbrain_core::onboarding::{self, StarterPreset, StarterTemplateReport};
+use tauri::State;
+
+/// Seeds the workspace with starter life areas, goals and a getting-started project
+///
+/// Safe to run more than once: items that already exist (matched by name)
+/// are left alone. The `skip` preset seeds nothing. Every preset marks
+/// onboarding as completed.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `preset` - `standard`, `minimal` or `skip`
+///
+/// # Returns
+/// * `AppResult<StarterTemplateReport>` - How many items were created
+///
+/// # Errors
+/// * Returns `AppError` if seeding fails; nothing is created in that case
+#[tauri::command]
+pub async fn create_starter_template(
+    state: State<'_, AppState>,
+    preset: StarterPreset,
+) -> AppResult<StarterTemplateReport> {
+    let repo = Repository::new(state.db.clone());
+    let report = onboarding::create_starter_template(&repo, preset).await?;
+
+    state.events.publish(AppEvent::new("settings.updated", "settings", "onboarding_completed"));
+    Ok(report)
+}
@@ -0,0 +1,170 @@
+use crate::db::models::{Delegation, Person, PersonInput, WaitingForGroup};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+/// Lists everyone tasks can be delegated to
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<Person>>` - People sorted by name
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_people(state: State<'_, AppState>) -> AppResult<Vec<Person>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_people().await
+}
+
+/// Adds a person
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `input` - Name and optional email and notes
+///
+/// # Returns
+/// * `AppResult<Person>` - The new person
+///
+/// # Errors
+/// * Returns `AppError` if the name is empty or the email is invalid
+#[tauri::command]
+pub async fn create_person(state: State<'_, AppState>, input: PersonInput) -> AppResult<Person> {
+    let repo = Repository::new(state.db.clone());
+    repo.create_person(input).await
+}
+
+/// Updates a person
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The person to update
+/// * `input` - The new name, email and notes
+///
+/// # Returns
+/// * `AppResult<Person>` - The updated person
+///
+/// # Errors
+/// * Returns `AppError` if the person doesn't exist or the input is invalid
+#[tauri::command]
+pub async fn update_person(state: State<'_, AppState>, id: String, input: PersonInput) -> AppResult<Person> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.update_person(&id, input).await
+}
+
+/// Deletes a person; their delegated tasks are kept but no longer delegated
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The person to delete
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the person doesn't exist
+#[tauri::command]
+pub async fn delete_person(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_person", &confirmation_token)?;
+    let repo = Repository::new(state.db.clone());
+    repo.delete_person(&id).await
+}
+
+/// Delegates a task to a person, optionally with a follow-up date
+///
+/// Delegating an already delegated task replaces the person and follow-up.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task to delegate
+/// * `person_id` - Who the task is delegated to
+/// * `follow_up_at` - When to check in on the task
+///
+/// # Returns
+/// * `AppResult<Delegation>` - The delegation
+///
+/// # Errors
+/// * Returns `AppError` if the task or person doesn't exist
+#[tauri::command]
+pub async fn delegate_task(
+    state: State<'_, AppState>,
+    task_id: String,
+    person_id: String,
+    follow_up_at: Option<DateTime<Utc>>,
+) -> AppResult<Delegation> {
+    validate_id(&task_id)?;
+    validate_id(&person_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.delegate_task(&task_id, &person_id, follow_up_at).await
+}
+
+/// Takes a delegated task back
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The delegated task
+///
+/// # Returns
+/// * `AppResult<bool>` - False if the task wasn't delegated
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn undelegate_task(state: State<'_, AppState>, task_id: String) -> AppResult<bool> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.undelegate_task(&task_id).await
+}
+
+/// Returns who a task is delegated to, if anyone
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task
+///
+/// # Returns
+/// * `AppResult<Option<Delegation>>` - The delegation, or `None`
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_task_delegation(state: State<'_, AppState>, task_id: String) -> AppResult<Option<Delegation>> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_task_delegation(&task_id).await
+}
+
+/// Lists open delegated tasks grouped by person ("waiting for")
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `follow_up_due` - Only include tasks whose follow-up date has passed
+///
+/// # Returns
+/// * `AppResult<Vec<WaitingForGroup>>` - One group per person with open
+///   delegated tasks, earliest follow-up first
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_waiting_for(
+    state: State<'_, AppState>,
+    follow_up_due: Option<bool>,
+) -> AppResult<Vec<WaitingForGroup>> {
+    let repo = Repository::new(state.db.clone());
+    let before = follow_up_due.unwrap_or(false).then(Utc::now);
+    repo.get_waiting_for(before).await
+}
@@ -1,27 +1,26 @@
-use crate::db::models::{Project, ProjectStatus};
+use crate::db::models::{Project, ProjectImportReport, ProjectInput, ProjectMergeReport, ProjectStatus};
 use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::project_bundle::{self, ProjectBundleReport};
 use crate::AppState;
-use anyhow::Result;
 use chrono::{DateTime, Utc};
+use anyhow::Result;
+use evorbrain_core::conflicts::ensure_unchanged;
+use evorbrain_core::validation::{validate_id, validate_progress};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tauri::State;
-use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateProjectRequest {
-    pub goal_id: String,
-    pub title: String,
-    pub description: Option<String>,
-    pub status: Option<ProjectStatus>,
-}
+pub type CreateProjectRequest = ProjectInput;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateProjectRequest {
     pub id: String,
-    pub goal_id: String,
-    pub title: String,
-    pub description: Option<String>,
-    pub status: ProjectStatus,
+    /// `updated_at` as the client loaded it, to refuse conflicting edits
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub project: ProjectInput,
 }
 
 #[tauri::command]
@@ -29,44 +28,14 @@ pub async fn create_project(
     state: State<'_, AppState>,
     request: CreateProjectRequest,
 ) -> Result<Project, String> {
-    let id = Uuid::new_v4().to_string();
-    let now = Utc::now();
-    let status = request.status.unwrap_or(ProjectStatus::Planning);
-    
-    sqlx::query(
-        r#"
-        INSERT INTO projects (id, goal_id, title, description, status, created_at, updated_at)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-        "#
-    )
-    .bind(&id)
-    .bind(&request.goal_id)
-    .bind(&request.title)
-    .bind(&request.description)
-    .bind(status.to_string())
-    .bind(&now)
-    .bind(&now)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_project(state, id).await
+    let repo = Repository::new(state.db.clone());
+    repo.create_project(request).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn get_projects(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
-    sqlx::query_as::<_, Project>(
-        r#"
-        SELECT id, goal_id, title, description, status,
-               created_at, updated_at, completed_at, archived_at
-        FROM projects
-        WHERE archived_at IS NULL
-        ORDER BY created_at DESC
-        "#
-    )
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_projects().await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -74,62 +43,51 @@ pub async fn get_projects_by_goal(
     state: State<'_, AppState>,
     goal_id: String,
 ) -> Result<Vec<Project>, String> {
-    sqlx::query_as::<_, Project>(
-        r#"
-        SELECT id, goal_id, title, description, status,
-               created_at, updated_at, completed_at, archived_at
-        FROM projects
-        WHERE goal_id = ?1 AND archived_at IS NULL
-        ORDER BY created_at DESC
-        "#
-    )
-    .bind(&goal_id)
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_projects_by_goal(&goal_id).await.map_err(|e| e.to_string())
+}
+
+/// Reorders the projects of a goal; projects that aren't listed keep their
+/// relative order after the listed ones
+#[tauri::command]
+pub async fn reorder_projects(
+    state: State<'_, AppState>,
+    goal_id: String,
+    project_ids: Vec<String>,
+) -> Result<Vec<Project>, String> {
+    let repo = Repository::new(state.db.clone());
+    repo.reorder_projects(&goal_id, &project_ids)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    get_projects_by_goal(state, goal_id).await
 }
 
 #[tauri::command]
 pub async fn get_project(state: State<'_, AppState>, id: String) -> Result<Project, String> {
-    sqlx::query_as::<_, Project>(
-        r#"
-        SELECT id, goal_id, title, description, status,
-               created_at, updated_at, completed_at, archived_at
-        FROM projects
-        WHERE id = ?1
-        "#
-    )
-    .bind(&id)
-    .fetch_one(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_project(&id).await.map_err(|e| e.to_string())
 }
 
+/// Updates an existing project
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Update request containing ID and fields to update
+///
+/// # Returns
+/// * `AppResult<Project>` - The updated project
+///
+/// # Errors
+/// * Returns `AppError` if the project doesn't exist or the update fails
+/// * Returns a `CONFLICT` error holding the stored project and the rejected
+///   change if the project changed after `expected_updated_at`
 #[tauri::command]
-pub async fn update_project(
-    state: State<'_, AppState>,
-    request: UpdateProjectRequest,
-) -> Result<Project, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE projects 
-        SET goal_id = ?1, title = ?2, description = ?3, status = ?4, updated_at = ?5
-        WHERE id = ?6
-        "#
-    )
-    .bind(&request.goal_id)
-    .bind(&request.title)
-    .bind(&request.description)
-    .bind(request.status.to_string())
-    .bind(&now)
-    .bind(&request.id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_project(state, request.id).await
+pub async fn update_project(state: State<'_, AppState>, request: UpdateProjectRequest) -> AppResult<Project> {
+    let repo = Repository::new(state.db.clone());
+    let current = repo.get_project(&request.id).await?;
+    ensure_unchanged("project", &request.id, request.expected_updated_at, current.updated_at, &current, &request)?;
+    repo.update_project(&request.id, request.project).await
 }
 
 #[tauri::command]
@@ -138,29 +96,28 @@ pub async fn update_project_status(
     id: String,
     status: ProjectStatus,
 ) -> Result<Project, String> {
-    let now = Utc::now();
-    let mut completed_at: Option<DateTime<Utc>> = None;
-    
-    if status == ProjectStatus::Completed {
-        completed_at = Some(now);
-    }
-    
-    sqlx::query(
-        r#"
-        UPDATE projects 
-        SET status = ?1, completed_at = ?2, updated_at = ?3
-        WHERE id = ?4
-        "#
-    )
-    .bind(status.to_string())
-    .bind(&completed_at)
-    .bind(&now)
-    .bind(&id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_project(state, id).await
+    let repo = Repository::new(state.db.clone());
+    repo.update_project_status(&id, status).await.map_err(|e| e.to_string())
+}
+
+/// Sets how far along a project is
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - UUID string of the project
+/// * `progress` - Completion percentage, 0-100
+///
+/// # Returns
+/// * `AppResult<Project>` - The updated project
+///
+/// # Errors
+/// * Returns `AppError` if the progress is above 100 or the project doesn't exist
+#[tauri::command]
+pub async fn set_project_progress(state: State<'_, AppState>, id: String, progress: u8) -> AppResult<Project> {
+    validate_id(&id)?;
+    validate_progress(progress)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_project_progress(&id, progress).await
 }
 
 #[tauri::command]
@@ -171,26 +128,115 @@ pub async fn delete_project(state: State<'_, AppState>, id: String) -> Result<()
         .map_err(|e| e.to_string())
 }
 
+/// Restores an archived project; with `cascade`, also the tasks and notes
+/// archived together with it
+///
+/// Fails with `ARCHIVED_PARENT`, listing the archived parents in the error
+/// data, while the project's goal or life area is still archived.
+#[tauri::command]
+pub async fn restore_project(
+    state: State<'_, AppState>,
+    id: String,
+    cascade: Option<bool>,
+) -> AppResult<Project> {
+    let repo = Repository::new(state.db.clone());
+    repo.restore_project(&id, cascade.unwrap_or(false)).await?;
+    state.switcher.invalidate();
+    repo.get_project(&id).await
+}
+
+/// Merges one project into another, e.g. after a duplicate import
+///
+/// Moves the tasks, notes and tags of the source project into the target,
+/// archives the source and records the merge in the audit log.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `source_id` - Project to merge and archive
+/// * `target_id` - Project that receives everything; must not be archived
+///
+/// # Returns
+/// * `AppResult<ProjectMergeReport>` - How much was moved
+///
+/// # Errors
+/// * Returns `AppError` if either project doesn't exist, they are the same
+///   project, the target is archived or the database update fails
+#[tauri::command]
+pub async fn merge_projects(
+    state: State<'_, AppState>,
+    source_id: String,
+    target_id: String,
+) -> AppResult<ProjectMergeReport> {
+    let repo = Repository::new(state.db.clone());
+    state
+        .write_queue
+        .run("merge_projects", |_| async move { repo.merge_projects(&source_id, &target_id).await })
+        .await
+}
+
+/// Exports a project as a bundle file another vault can import
+///
+/// The file holds the project, its tasks, notes, tags, task dependencies
+/// and attachments, with the attachment content inline. Private notes are
+/// left out since they can only be read in this vault.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - The project to export; must not be archived
+/// * `path` - Where to write the bundle
+///
+/// # Returns
+/// * `AppResult<ProjectBundleReport>` - What was written
+///
+/// # Errors
+/// * Returns `AppError` if the project doesn't exist or is archived, an
+///   attachment can't be read or the file can't be written
 #[tauri::command]
-pub async fn restore_project(state: State<'_, AppState>, id: String) -> Result<Project, String> {
-    let now = Utc::now();
-    
-    // Restore the project
-    sqlx::query(
-        r#"
-        UPDATE projects 
-        SET archived_at = NULL, updated_at = ?1
-        WHERE id = ?2
-        "#
-    )
-    .bind(&now)
-    .bind(&id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    // Optionally restore associated tasks and notes
-    // This could be a separate command if you want more control
-    
-    get_project(state, id).await
-}
\ No newline at end of file
+pub async fn export_project_bundle(
+    state: State<'_, AppState>,
+    project_id: String,
+    path: String,
+) -> AppResult<ProjectBundleReport> {
+    validate_id(&project_id)?;
+    let repo = Repository::new(state.db.clone());
+    project_bundle::export(&repo, state.attachments.clone(), &project_id, PathBuf::from(path)).await
+}
+
+/// Imports a bundle written by `export_project_bundle` as a new project
+///
+/// Everything gets a new ID, so the same bundle can be imported more than
+/// once. Links between the bundled tasks and notes are updated to the new
+/// IDs, and tags are matched by name, reusing those that already exist.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `path` - Path of the bundle file
+/// * `target_goal_id` - Goal the project is created under; must not be
+///   archived
+///
+/// # Returns
+/// * `AppResult<ProjectImportReport>` - The new project, the new ID of
+///   everything imported and what was left out
+///
+/// # Errors
+/// * Returns `AppError` if the file isn't a project bundle, the goal doesn't
+///   exist or is archived, or the attachments exceed the attachment limits;
+///   nothing is imported then
+#[tauri::command]
+pub async fn import_project_bundle(
+    state: State<'_, AppState>,
+    path: String,
+    target_goal_id: String,
+) -> AppResult<ProjectImportReport> {
+    validate_id(&target_goal_id)?;
+    let repo = Repository::new(state.db.clone());
+    let store = state.attachments.clone();
+    let report = state
+        .write_queue
+        .run("import_project_bundle", |_| async move {
+            project_bundle::import(&repo, store, PathBuf::from(path), &target_goal_id).await
+        })
+        .await?;
+    state.switcher.invalidate();
+    Ok(report)
+}
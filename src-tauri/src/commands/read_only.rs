@@ -0,0 +1,37 @@
+use crate::AppState;
+use evorbrain_core::events::AppEvent;
+use tauri::State;
+
+/// Reports whether read-only mode is on
+///
+/// # Arguments
+/// * `state` - Application state containing the read-only mode
+///
+/// # Returns
+/// * `bool` - Whether changes are blocked
+#[tauri::command]
+pub fn get_read_only_mode(state: State<'_, AppState>) -> bool {
+    state.read_only.is_enabled()
+}
+
+/// Turns read-only mode on or off
+///
+/// While it's on every mutating command except this one and a few that
+/// don't touch the database is rejected with a `READ_ONLY` error, and so are
+/// writes through the local API.
+///
+/// # Arguments
+/// * `state` - Application state containing the read-only mode
+/// * `enabled` - Whether to block changes
+///
+/// # Returns
+/// * `bool` - Whether read-only mode is on now
+#[tauri::command]
+pub fn set_read_only_mode(state: State<'_, AppState>, enabled: bool) -> bool {
+    if state.read_only.set_enabled(enabled) {
+        let event = if enabled { "app.read_only_enabled" } else { "app.read_only_disabled" };
+        state.events.publish(AppEvent::new(event, "app", "command"));
+        crate::log_info!("Read-only mode changed", if enabled { "on" } else { "off" });
+    }
+    enabled
+}
@@ -1,8 +1,21 @@
+use crate::confirmation::DestructiveConfirmation;
+use crate::db::backup;
+use crate::db::batch::{self, BatchOperation, BatchResult};
+use crate::db::models::{
+    BulkTagResult, DataImportReport, DataIssue, DataRepairReport, ExportResult, ImportCollision, RepairStrategy,
+    SecureDeleteReport,
+};
 use crate::db::repository::Repository;
-use crate::error::AppResult;
+use crate::db::write_queue::BulkWriteProgress;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::streaming::{self, StreamStarted};
 use crate::AppState;
+use evorbrain_core::settings;
+use evorbrain_core::storage::{self, StorageReport};
+use evorbrain_core::validation::validate_id;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use serde_json::Value;
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionResult {
@@ -33,6 +46,9 @@ pub async fn check_repository_health(state: State<'_, AppState>) -> AppResult<Tr
 pub struct BatchDeleteRequest {
     pub entity_type: EntityType,
     pub ids: Vec<String>,
+    /// Archive life areas even when they still contain goals or notes
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,42 +61,44 @@ pub enum EntityType {
     Note,
 }
 
+impl EntityType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EntityType::LifeArea => "life_area",
+            EntityType::Goal => "goal",
+            EntityType::Project => "project",
+            EntityType::Task => "task",
+            EntityType::Note => "note",
+        }
+    }
+}
+
+/// Archives entities of one type in a single transaction
+///
+/// Each entity cascades to its children as its single delete command does.
+/// If any ID fails, the whole batch is rolled back.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Entity type, IDs, and `force` for non-empty life areas
+///
+/// # Returns
+/// * `AppResult<TransactionResult>` - How many entities were archived
+///
+/// # Errors
+/// * Returns `AppError` for the first ID that fails; its data has the
+///   `failed_id`, its `index` and the underlying error's own data
 #[tauri::command]
 pub async fn batch_delete(
     state: State<'_, AppState>,
     request: BatchDeleteRequest,
 ) -> AppResult<TransactionResult> {
     let repo = Repository::new(state.db.clone());
-    let mut affected = 0;
-    
-    match request.entity_type {
-        EntityType::LifeArea => {
-            for id in &request.ids {
-                repo.delete_life_area(id).await?;
-                affected += 1;
-            }
-        }
-        EntityType::Project => {
-            for id in &request.ids {
-                repo.archive_project_cascade(id).await?;
-                affected += 1;
-            }
-        }
-        EntityType::Task => {
-            // We'll need to add delete_task to repository
-            // For now, return an error
-            return Err(crate::error::AppError::new(
-                crate::error::ErrorCode::InternalError,
-                "Task batch delete not yet implemented",
-            ));
-        }
-        _ => {
-            return Err(crate::error::AppError::new(
-                crate::error::ErrorCode::InternalError,
-                format!("Batch delete not implemented for {:?}", request.entity_type),
-            ));
-        }
-    }
+    let entity_type = request.entity_type.as_str();
+    let affected = state
+        .write_queue
+        .run("batch_delete", |_| async move { repo.batch_delete(entity_type, &request.ids, request.force).await })
+        .await?;
     
     Ok(TransactionResult {
         success: true,
@@ -89,6 +107,88 @@ pub async fn batch_delete(
     })
 }
 
+/// Adds and removes tags on several tasks or projects in a single
+/// transaction, so a multi-selection can be retagged in one call
+///
+/// Tags that don't exist yet are created. If any ID doesn't exist, nothing
+/// changes.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - `task` or `project`
+/// * `ids` - The entities to retag
+/// * `add_tags` - Tag names to put on each entity
+/// * `remove_tags` - Tag names to take off each entity
+///
+/// # Returns
+/// * `AppResult<BulkTagResult>` - How many entities were retagged and how
+///   many tags were added and removed
+///
+/// # Errors
+/// * Returns `AppError` for an entity type without tags, a tag both added
+///   and removed, or the first ID that doesn't exist; its data has the
+///   `failed_id` and its `index`
+#[tauri::command]
+pub async fn bulk_tag(
+    state: State<'_, AppState>,
+    entity_type: EntityType,
+    ids: Vec<String>,
+    add_tags: Option<Vec<String>>,
+    remove_tags: Option<Vec<String>>,
+) -> AppResult<BulkTagResult> {
+    let repo = Repository::new(state.db.clone());
+    let add_tags = add_tags.unwrap_or_default();
+    let remove_tags = remove_tags.unwrap_or_default();
+    state
+        .write_queue
+        .run("bulk_tag", |_| async move {
+            repo.bulk_tag(entity_type.as_str(), &ids, &add_tags, &remove_tags).await
+        })
+        .await
+}
+
+/// Runs a list of operations in a single transaction
+///
+/// Operations can refer to an entity created earlier in the batch with
+/// `"$<index>"` in place of an ID. If any operation fails the whole batch is
+/// rolled back and the result says which one failed.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `operations` - Operations tagged by `op`, e.g. `create_task`, `tag_task`
+///
+/// # Returns
+/// * `AppResult<BatchResult>` - Whether the batch was committed, and a result per operation
+///
+/// # Errors
+/// * Returns `AppError` if there are too many operations or the transaction can't be started or committed
+#[tauri::command]
+pub async fn execute_batch(
+    state: State<'_, AppState>,
+    operations: Vec<BatchOperation>,
+) -> AppResult<BatchResult> {
+    let repo = Repository::new(state.db.clone());
+    state
+        .write_queue
+        .run("execute_batch", |progress| async move { batch::execute_batch(&repo, operations, &progress).await })
+        .await
+}
+
+/// Lists the bulk operations (imports, batches, merges, cleanups) that are
+/// running or waiting for their turn
+///
+/// Updates are also pushed as `bulk-write-progress` events.
+///
+/// # Arguments
+/// * `state` - Application state holding the write queue
+///
+/// # Returns
+/// * `Vec<BulkWriteProgress>` - Queued and running operations, oldest first
+#[tauri::command]
+pub fn get_write_queue(state: State<'_, AppState>) -> Vec<BulkWriteProgress> {
+    state.write_queue.pending()
+}
+
 // Database statistics
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabaseStats {
@@ -161,6 +261,132 @@ pub async fn get_database_stats(state: State<'_, AppState>) -> AppResult<Databas
     })
 }
 
+// Storage advice
+/// Checks for datasets large enough to slow the app down, such as many
+/// thousands of completed tasks left unarchived, and suggests cleanups
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<StorageReport>` - The counts checked and the advice, each
+///   with the command call that carries it out; no advice below the limits
+///
+/// # Errors
+/// * Returns `AppError` if a query fails
+#[tauri::command]
+pub async fn get_storage_advice(state: State<'_, AppState>) -> AppResult<StorageReport> {
+    let repo = Repository::new(state.db.clone());
+    storage::get_storage_advice(&repo).await
+}
+
+/// Archives completed tasks older than a number of days, with their
+/// subtasks and notes; tasks with open subtasks are left alone
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `older_than_days` - Archive tasks completed more than this many days ago
+///
+/// # Returns
+/// * `AppResult<TransactionResult>` - How many tasks were archived
+///
+/// # Errors
+/// * Returns `AppError` if the update fails
+#[tauri::command]
+pub async fn archive_completed_tasks(
+    state: State<'_, AppState>,
+    older_than_days: u32,
+) -> AppResult<TransactionResult> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(older_than_days));
+    let repo = Repository::new(state.db.clone());
+    let archived = state
+        .write_queue
+        .run("archive_completed_tasks", |_| async move { repo.archive_completed_tasks(cutoff).await })
+        .await?;
+
+    Ok(TransactionResult {
+        success: true,
+        message: format!("Archived {} completed tasks", archived),
+        affected_rows: Some(archived as usize),
+    })
+}
+
+// Data integrity
+/// Scans the database for inconsistent rows
+///
+/// Reports rows whose parent no longer exists, active rows under an archived
+/// parent (split into ones the archive cascade missed and ones changed
+/// since) and goals, projects and tasks completed in the future.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<DataIssue>>` - The issues found, each with a stable ID
+///
+/// # Errors
+/// * Returns `AppError` if a scan query fails
+#[tauri::command]
+pub async fn scan_data_issues(state: State<'_, AppState>) -> AppResult<Vec<DataIssue>> {
+    let repo = Repository::new(state.db.clone());
+    repo.scan_data_issues().await
+}
+
+/// Repairs issues found by `scan_data_issues`
+///
+/// All issues are fixed with the same strategy in one transaction: `detach`
+/// moves orphaned tasks to the inbox and unlinks notes, `archive` archives
+/// the affected rows with their children and `clear_timestamp` clears future
+/// completion times. Every repair is recorded in the audit log.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `issue_ids` - IDs of the issues, as returned by the scan
+/// * `strategy` - How to fix them
+///
+/// # Returns
+/// * `AppResult<DataRepairReport>` - The repaired issues and how many remain
+///
+/// # Errors
+/// * Returns `AppError` without changing anything if an issue no longer
+///   exists, the strategy doesn't apply to one of them or the update fails
+#[tauri::command]
+pub async fn repair_data_issues(
+    state: State<'_, AppState>,
+    issue_ids: Vec<String>,
+    strategy: RepairStrategy,
+) -> AppResult<DataRepairReport> {
+    let repo = Repository::new(state.db.clone());
+    state
+        .write_queue
+        .run("repair_data_issues", |_| async move { repo.repair_data_issues(&issue_ids, strategy).await })
+        .await
+}
+
+// Destructive operation confirmation
+/// Issues a single-use token for one call of a destructive command
+///
+/// The commands flagged `destructive` in the command registry refuse to run
+/// without a token from here, so the frontend asks for one once the user
+/// has confirmed the operation.
+///
+/// # Arguments
+/// * `state` - Application state holding the outstanding tokens
+/// * `command` - Name of the command the token is for
+///
+/// # Returns
+/// * `AppResult<DestructiveConfirmation>` - The token and when it expires
+///
+/// # Errors
+/// * Returns `AppError` if the command doesn't need a confirmation
+#[tauri::command]
+pub fn request_destructive_confirmation(
+    state: State<'_, AppState>,
+    command: String,
+) -> AppResult<DestructiveConfirmation> {
+    state.confirmations.issue(&command)
+}
+
 // Cleanup operations
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CleanupOptions {
@@ -168,62 +394,116 @@ pub struct CleanupOptions {
     pub vacuum_database: bool,
 }
 
+/// Deletes old archived items now and optionally vacuums the database
+///
+/// The entity types in the `archive_retention_exclusions` setting are kept,
+/// as they are by the scheduled retention job. The database is backed up
+/// before anything is deleted. Needs a token from
+/// `request_destructive_confirmation`.
 #[tauri::command]
 pub async fn cleanup_database(
     state: State<'_, AppState>,
     options: CleanupOptions,
+    confirmation_token: String,
 ) -> AppResult<TransactionResult> {
-    let mut messages = Vec::new();
-    let mut total_deleted = 0;
+    state.confirmations.consume("cleanup_database", &confirmation_token)?;
+    let db = state.db.clone();
+    state.write_queue.run("cleanup_database", |_| async move {
+        let mut messages = Vec::new();
+        let mut total_deleted = 0;
     
-    // Delete old archived items if requested
-    if let Some(days) = options.delete_archived_older_than_days {
-        let cutoff_date = chrono::Utc::now() - chrono::Duration::days(days as i64);
-        
-        // Delete from each table
-        for (table, name) in [
-            ("life_areas", "life areas"),
-            ("goals", "goals"),
-            ("projects", "projects"),
-            ("tasks", "tasks"),
-            ("notes", "notes"),
-        ] {
-            let result = sqlx::query(&format!(
-                "DELETE FROM {} WHERE archived_at IS NOT NULL AND archived_at < ?1",
-                table
-            ))
-            .bind(cutoff_date)
-            .execute(&*state.db)
-            .await?;
-            
-            let deleted = result.rows_affected();
-            if deleted > 0 {
+        // Delete old archived items if requested
+        if let Some(days) = options.delete_archived_older_than_days {
+            if let Some(path) = backup::snapshot(&db, "cleanup").await.map_err(|e| {
+                AppError::new(ErrorCode::IoError, "Failed to back up the database").with_details(e.to_string())
+            })? {
+                messages.push(format!("Backed up the database to {}", path.display()));
+            }
+
+            let cutoff_date = chrono::Utc::now() - chrono::Duration::days(days as i64);
+            let repo = Repository::new(db.clone());
+            let excluded = settings::load(&repo).await?.archive_retention_exclusions;
+
+            for (table, deleted) in repo.purge_archived_before(cutoff_date, &excluded).await? {
                 total_deleted += deleted;
-                messages.push(format!("Deleted {} archived {}", deleted, name));
+                messages.push(format!("Deleted {} archived {}", deleted, table.replace('_', " ")));
             }
         }
-    }
     
-    // Vacuum database if requested
-    if options.vacuum_database {
-        sqlx::query("VACUUM")
-            .execute(&*state.db)
-            .await
-            .map_err(|e| crate::error::AppError::database_error("vacuum database", e))?;
-        messages.push("Database vacuumed successfully".to_string());
-    }
+        // Vacuum database if requested
+        if options.vacuum_database {
+            sqlx::query("VACUUM")
+                .execute(&*db)
+                .await
+                .map_err(|e| crate::error::AppError::database_error("vacuum database", e))?;
+            messages.push("Database vacuumed successfully".to_string());
+        }
     
-    let message = if messages.is_empty() {
-        "No cleanup operations performed".to_string()
-    } else {
-        messages.join(", ")
-    };
+        let message = if messages.is_empty() {
+            "No cleanup operations performed".to_string()
+        } else {
+            messages.join(", ")
+        };
     
-    Ok(TransactionResult {
-        success: true,
-        message,
-        affected_rows: if total_deleted > 0 { Some(total_deleted as usize) } else { None },
-    })
+        Ok(TransactionResult {
+            success: true,
+            message,
+            affected_rows: if total_deleted > 0 { Some(total_deleted as usize) } else { None },
+        })
+    }).await
+}
+
+/// Permanently deletes an entity and everything below it, leaving no
+/// recoverable copy in the database file
+///
+/// Unlike archiving this can't be undone. Links and attachments of the
+/// deleted entities are removed as well, and the database is vacuumed
+/// afterwards. Attachment files no other entity uses are overwritten before
+/// they are deleted.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - One of `life_area`, `goal`, `project`, `task` or `note`
+/// * `entity_id` - ID of the entity to delete
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Returns
+/// * `AppResult<SecureDeleteReport>` - How many entities and links were deleted
+///
+/// # Errors
+/// * Returns `AppError` if the token isn't valid, the entity doesn't exist or the deletion fails
+#[tauri::command]
+pub async fn secure_delete(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+    confirmation_token: String,
+) -> AppResult<SecureDeleteReport> {
+    validate_id(&entity_id)?;
+    state.confirmations.consume("secure_delete", &confirmation_token)?;
+    let repo = Repository::new(state.db.clone());
+    let report = state
+        .write_queue
+        .run("secure_delete", |_| async { repo.secure_delete(&entity_type, &entity_id).await })
+        .await?;
+
+    for hash in &report.unreferenced_hashes {
+        if let Err(e) = state.attachments.shred(hash) {
+            crate::log_error!("Failed to shred attachment", e);
+        }
+    }
+    // The extracted text would outlive the files otherwise
+    if !report.unreferenced_hashes.is_empty() {
+        if let Err(e) = repo.remove_unreferenced_attachment_text().await {
+            crate::log_error!("Failed to remove attachment text", e);
+        }
+    }
+
+    crate::log_info!(
+        "Secure delete",
+        &format!("Deleted {} {} with {} related entities", entity_type, entity_id, report.entities_deleted - 1)
+    );
+    Ok(report)
 }
 
 // Export data
@@ -240,13 +520,6 @@ pub enum ExportFormat {
     // Future: CSV, Markdown
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExportResult {
-    pub data: serde_json::Value,
-    pub item_count: usize,
-    pub export_date: chrono::DateTime<chrono::Utc>,
-}
-
 #[tauri::command]
 pub async fn export_all_data(
     state: State<'_, AppState>,
@@ -256,80 +529,117 @@ pub async fn export_all_data(
     
     // For now, only implement JSON export
     match request.format {
-        ExportFormat::Json => {
-            let mut data = serde_json::json!({});
-            let mut total_items = 0;
-            
-            // Export life areas
-            let life_areas = if request.include_archived {
-                sqlx::query_as::<_, crate::db::models::LifeArea>(
-                    "SELECT * FROM life_areas ORDER BY created_at"
-                )
-                .fetch_all(&*state.db)
-                .await?
-            } else {
-                repo.get_life_areas().await?
-            };
-            total_items += life_areas.len();
-            data["life_areas"] = serde_json::to_value(&life_areas)?;
-            
-            // Export goals
-            let goals = sqlx::query_as::<_, crate::db::models::Goal>(
-                if request.include_archived {
-                    "SELECT * FROM goals ORDER BY created_at"
-                } else {
-                    "SELECT * FROM goals WHERE archived_at IS NULL ORDER BY created_at"
-                }
-            )
-            .fetch_all(&*state.db)
-            .await?;
-            total_items += goals.len();
-            data["goals"] = serde_json::to_value(&goals)?;
-            
-            // Export projects
-            let projects = sqlx::query_as::<_, crate::db::models::Project>(
-                if request.include_archived {
-                    "SELECT * FROM projects ORDER BY created_at"
-                } else {
-                    "SELECT * FROM projects WHERE archived_at IS NULL ORDER BY created_at"
-                }
-            )
-            .fetch_all(&*state.db)
-            .await?;
-            total_items += projects.len();
-            data["projects"] = serde_json::to_value(&projects)?;
-            
-            // Export tasks
-            let tasks = sqlx::query_as::<_, crate::db::models::Task>(
-                if request.include_archived {
-                    "SELECT * FROM tasks ORDER BY created_at"
-                } else {
-                    "SELECT * FROM tasks WHERE archived_at IS NULL ORDER BY created_at"
-                }
-            )
-            .fetch_all(&*state.db)
-            .await?;
-            total_items += tasks.len();
-            data["tasks"] = serde_json::to_value(&tasks)?;
-            
-            // Export notes
-            let notes = sqlx::query_as::<_, crate::db::models::Note>(
-                if request.include_archived {
-                    "SELECT * FROM notes ORDER BY created_at"
-                } else {
-                    "SELECT * FROM notes WHERE archived_at IS NULL ORDER BY created_at"
-                }
-            )
-            .fetch_all(&*state.db)
-            .await?;
-            total_items += notes.len();
-            data["notes"] = serde_json::to_value(&notes)?;
-            
-            Ok(ExportResult {
-                data,
-                item_count: total_items,
-                export_date: chrono::Utc::now(),
-            })
-        }
+        ExportFormat::Json => repo.export_all_data(request.include_archived).await,
     }
+}
+
+// Import data
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportRequest {
+    /// The export as `export_all_data` returned it
+    pub export: ExportResult,
+    /// What happens to items whose ID is already stored; skipped by default
+    #[serde(default)]
+    pub collision: ImportCollision,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Imports the data of an `export_all_data` export
+///
+/// Life areas, goals, projects, tasks and notes keep their IDs. Items whose
+/// ID is already stored are skipped, overwrite the stored ones or are
+/// imported as copies under new IDs, as `collision` says. Either everything
+/// is imported or nothing is; a dry run reports what the import would do
+/// without writing anything, and is the only kind that runs without a
+/// confirmation token.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - The export, the collision strategy and whether it is a dry run
+/// * `confirmation_token` - Token from `request_destructive_confirmation`,
+///   needed unless it is a dry run
+///
+/// # Returns
+/// * `AppResult<DataImportReport>` - What was, or would be, created,
+///   overwritten, duplicated and skipped per section, and the new IDs of
+///   the copies
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if importing without a valid token
+/// * Returns `AppError` if the export can't be read, holds an ID twice or
+///   has items referring to something neither in it nor stored; nothing is
+///   imported then
+#[tauri::command]
+pub async fn import_all_data(
+    state: State<'_, AppState>,
+    request: ImportRequest,
+    confirmation_token: Option<String>,
+) -> AppResult<DataImportReport> {
+    if !request.dry_run {
+        state
+            .confirmations
+            .consume("import_all_data", confirmation_token.as_deref().unwrap_or_default())?;
+    }
+    let repo = Repository::new(state.db.clone());
+    let report = state
+        .write_queue
+        .run("import_all_data", |_| async {
+            repo.import_all_data(&request.export, request.collision, request.dry_run).await
+        })
+        .await?;
+
+    if !report.dry_run {
+        state.switcher.invalidate();
+        let imported: usize = report
+            .sections
+            .iter()
+            .map(|section| section.created + section.overwritten + section.duplicated)
+            .sum();
+        crate::log_info!("Data import", &format!("Imported {} items ({:?} on collision)", imported, report.collision));
+    }
+    Ok(report)
+}
+
+/// Streams a full export as chunk events instead of one response
+///
+/// Each entity type (`life_areas`, `goals`, `projects`, `tasks`, `notes`) is
+/// a section of the stream.
+///
+/// # Arguments
+/// * `app_handle` - Used to emit the chunk events
+/// * `state` - Application state containing the database connection
+/// * `request` - Whether to include archived items, and the format
+/// * `stream_id` - ID the chunk events carry, chosen by the caller (a UUID)
+///
+/// # Returns
+/// * `AppResult<StreamStarted>` - The number of items that will be streamed
+///
+/// # Errors
+/// * Returns `AppError` if the stream ID is malformed or the export fails
+#[tauri::command]
+pub async fn stream_export_all_data(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    request: ExportRequest,
+    stream_id: String,
+) -> AppResult<StreamStarted> {
+    validate_id(&stream_id)?;
+    let repo = Repository::new(state.db.clone());
+
+    let mut export = match request.format {
+        ExportFormat::Json => repo.export_all_data(request.include_archived).await?,
+    };
+    let sections = ["life_areas", "goals", "projects", "tasks", "notes"]
+        .into_iter()
+        .map(|section| {
+            let items = match export.data[section].take() {
+                Value::Array(items) => items,
+                _ => Vec::new(),
+            };
+            (section.to_string(), items)
+        })
+        .collect();
+
+    Ok(streaming::start(app_handle, stream_id, sections))
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+use crate::db::models::{Note, Project, StaleProject};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::note_encryption::{reveal, reveal_all};
+use crate::AppState;
+use chrono::Utc;
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::settings::{self, MAX_REVIEW_INTERVAL_DAYS};
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+const DEFAULT_NOTE_REVIEW_LIMIT: i64 = 20;
+
+/// Lists open projects with no activity and no review within their review
+/// interval, for the weekly review
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<StaleProject>>` - Stale projects, most overdue first
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_stale_projects(state: State<'_, AppState>) -> AppResult<Vec<StaleProject>> {
+    let repo = Repository::new(state.db.clone());
+    let default_interval = settings::load(&repo).await?.review_interval_days;
+    repo.get_stale_projects(Utc::now(), default_interval).await
+}
+
+/// Marks a project as reviewed now, so it stops being stale until its
+/// review interval passes again
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The project ID
+///
+/// # Returns
+/// * `AppResult<Project>` - The project with its new `last_reviewed_at`
+///
+/// # Errors
+/// * Returns `AppError` if the project doesn't exist
+#[tauri::command]
+pub async fn mark_reviewed(state: State<'_, AppState>, id: String) -> AppResult<Project> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.mark_project_reviewed(&id).await
+}
+
+/// Sets how many days may pass between reviews of a project
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The project ID
+/// * `days` - The interval; omit to use the `review_interval_days` setting
+///
+/// # Returns
+/// * `AppResult<Project>` - The updated project
+///
+/// # Errors
+/// * Returns `AppError` if the interval is out of range or the project
+///   doesn't exist
+#[tauri::command]
+pub async fn set_project_review_interval(
+    state: State<'_, AppState>,
+    id: String,
+    days: Option<u32>,
+) -> AppResult<Project> {
+    validate_id(&id)?;
+    if days.is_some_and(|days| !(1..=MAX_REVIEW_INTERVAL_DAYS).contains(&days)) {
+        return Err(AppError::validation_error(
+            "days",
+            &format!("must be between 1 and {}", MAX_REVIEW_INTERVAL_DAYS),
+        ));
+    }
+    let repo = Repository::new(state.db.clone());
+    repo.set_project_review_interval(&id, days).await
+}
+
+/// Puts a note on the resurface schedule, so it comes up for review a week,
+/// then a month, then three months later; or takes it off
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The note ID
+/// * `enabled` - Whether the note resurfaces; enabling restarts the schedule
+///
+/// # Returns
+/// * `AppResult<Note>` - The note with its `next_review_at`
+///
+/// # Errors
+/// * Returns `AppError` if the note doesn't exist or is archived
+#[tauri::command]
+pub async fn set_note_review(state: State<'_, AppState>, id: String, enabled: bool) -> AppResult<Note> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    reveal(&state.lock, repo.set_note_review(&id, enabled).await?)
+}
+
+/// Marks a resurfaced note as reviewed, scheduling its next review
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The note ID
+///
+/// # Returns
+/// * `AppResult<Note>` - The note with its next `next_review_at`, or none
+///   once the last review is done
+///
+/// # Errors
+/// * `VALIDATION_ERROR` - The note isn't scheduled for review
+/// * Returns `AppError` if the note doesn't exist
+#[tauri::command]
+pub async fn mark_note_reviewed(state: State<'_, AppState>, id: String) -> AppResult<Note> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    reveal(&state.lock, repo.mark_note_reviewed(&id).await?)
+}
+
+/// Lists the notes due to resurface by the end of today
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `limit` - Maximum number of notes to return (defaults to 20)
+///
+/// # Returns
+/// * `AppResult<Vec<Note>>` - Due notes, the longest overdue first
+///
+/// # Errors
+/// * Returns `AppError` if the limit isn't positive or the query fails
+#[tauri::command]
+pub async fn get_notes_due_for_review(state: State<'_, AppState>, limit: Option<i64>) -> AppResult<Vec<Note>> {
+    let limit = limit.unwrap_or(DEFAULT_NOTE_REVIEW_LIMIT);
+    if limit <= 0 {
+        return Err(AppError::validation_error("limit", "must be positive"));
+    }
+
+    let repo = Repository::new(state.db.clone());
+    let calendar = Calendar::load(&repo).await?;
+    let end_of_today = calendar.day(calendar.today()).end;
+    let notes = repo.get_notes_due_for_review(end_of_today, limit).await?;
+    Ok(reveal_all(&state.lock, notes))
+}
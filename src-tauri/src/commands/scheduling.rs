@@ -0,0 +1,259 @@
+use crate::db::models::Task;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+use chrono::{Duration, NaiveDate};
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::due_dates::{self, DueDateSuggestion};
+use evorbrain_core::recurrence::{self, RecurringCompletion, UpcomingOccurrences};
+use evorbrain_core::validation::{normalize_recurrence_rule, validate_id};
+use tauri::State;
+
+/// Largest accepted task estimate
+const MAX_ESTIMATE_MINUTES: u32 = 7 * 24 * 60;
+
+/// Counts working days forward (or backward) from a date, skipping weekends
+/// and holidays as configured in the `working_days` and `holidays` settings
+/// and the enabled holiday sets
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `date` - Start date (`YYYY-MM-DD`); defaults to today
+/// * `days` - Working days to move; 0 gives the next working day on or after `date`
+///
+/// # Returns
+/// * `AppResult<NaiveDate>` - The resulting working day
+///
+/// # Errors
+/// * Returns `AppError` if the settings cannot be loaded
+#[tauri::command]
+pub async fn add_working_days(
+    state: State<'_, AppState>,
+    date: Option<NaiveDate>,
+    days: i64,
+) -> AppResult<NaiveDate> {
+    let repo = Repository::new(state.db.clone());
+    let calendar = Calendar::load(&repo).await?;
+    Ok(calendar.add_working_days(date.unwrap_or_else(|| calendar.today()), days))
+}
+
+/// Defers a task to the next working day
+///
+/// The new due date is the first working day after today, or after the
+/// current due date if that is later. The local time of day of an existing
+/// due date is kept.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task to defer
+///
+/// # Returns
+/// * `AppResult<Task>` - The task with its new due date
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or the update fails
+#[tauri::command]
+pub async fn defer_task_to_next_working_day(state: State<'_, AppState>, id: String) -> AppResult<Task> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    let calendar = Calendar::load(&repo).await?;
+    let task = repo.get_task(&id).await?;
+
+    let from = match task.due_date {
+        Some(due) => calendar.local_date(due).max(calendar.today()),
+        None => calendar.today(),
+    };
+    let next = calendar.next_working_day(from + Duration::days(1));
+    let due_date = match task.due_date {
+        Some(due) => calendar.with_date(due, next),
+        None => calendar.day(next).start,
+    };
+
+    repo.set_task_due_date(&id, Some(due_date)).await
+}
+
+/// Sets how long a task is expected to take
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task ID
+/// * `minutes` - Estimated minutes; omit to clear the estimate
+///
+/// # Returns
+/// * `AppResult<Task>` - The updated task
+///
+/// # Errors
+/// * Returns `AppError` if the estimate is too large or the task doesn't exist
+#[tauri::command]
+pub async fn set_task_estimate(state: State<'_, AppState>, id: String, minutes: Option<u32>) -> AppResult<Task> {
+    validate_id(&id)?;
+    if minutes.is_some_and(|minutes| minutes > MAX_ESTIMATE_MINUTES) {
+        return Err(AppError::validation_error(
+            "minutes",
+            &format!("must be at most {} (one week)", MAX_ESTIMATE_MINUTES),
+        ));
+    }
+    let repo = Repository::new(state.db.clone());
+    repo.set_task_estimate(&id, minutes).await
+}
+
+/// Corrects the time spent on a task; timers stopped afterwards add to the
+/// new total
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task ID
+/// * `minutes` - Minutes actually spent so far
+///
+/// # Returns
+/// * `AppResult<Task>` - The updated task
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist
+#[tauri::command]
+pub async fn set_task_actual_minutes(state: State<'_, AppState>, id: String, minutes: u32) -> AppResult<Task> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_task_actual_minutes(&id, minutes).await
+}
+
+/// Makes a task repeat, or stops it repeating
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task ID
+/// * `rule` - iCalendar RRULE such as `FREQ=WEEKLY;BYDAY=MO,TH`; omit to clear
+///
+/// # Returns
+/// * `AppResult<Task>` - The updated task, with the rule normalized
+///
+/// # Errors
+/// * Returns `AppError` if the rule uses unsupported or malformed parts or the
+///   task doesn't exist
+#[tauri::command]
+pub async fn set_task_recurrence(
+    state: State<'_, AppState>,
+    id: String,
+    rule: Option<String>,
+) -> AppResult<Task> {
+    validate_id(&id)?;
+    let rule = rule.as_deref().map(normalize_recurrence_rule).transpose()?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_task_recurrence(&id, rule.as_deref()).await
+}
+
+/// Lists the coming occurrences of a repeating task
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task ID
+/// * `limit` - Most occurrences to return; defaults to 10, at most 100
+///
+/// # Returns
+/// * `AppResult<UpcomingOccurrences>` - The dates of the coming occurrences
+///   from today on, without the exception dates, and the exception dates
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or doesn't repeat
+#[tauri::command]
+pub async fn get_upcoming_occurrences(
+    state: State<'_, AppState>,
+    id: String,
+    limit: Option<u32>,
+) -> AppResult<UpcomingOccurrences> {
+    validate_id(&id)?;
+    let limit = limit.unwrap_or(recurrence::DEFAULT_OCCURRENCE_LIMIT);
+    if !(1..=recurrence::MAX_OCCURRENCE_LIMIT).contains(&limit) {
+        return Err(AppError::validation_error(
+            "limit",
+            &format!("must be between 1 and {}", recurrence::MAX_OCCURRENCE_LIMIT),
+        ));
+    }
+    let repo = Repository::new(state.db.clone());
+    recurrence::get_upcoming_occurrences(&repo, &id, limit).await
+}
+
+/// Completes a task and, if it repeats, creates its next instance due on
+/// the next occurrence that isn't an exception date or already past
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task ID
+///
+/// # Returns
+/// * `AppResult<RecurringCompletion>` - The completed task and the next
+///   instance, if the series goes on
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or is already completed
+/// * Returns a `CANNOT_UPDATE` error listing the open blockers if the task
+///   waits for open tasks
+#[tauri::command]
+pub async fn complete_recurring_task(state: State<'_, AppState>, id: String) -> AppResult<RecurringCompletion> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    recurrence::complete_recurring_task(&repo, &id).await
+}
+
+/// Sets the dates a repeating task skips, replacing the ones set before
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task ID
+/// * `dates` - Dates (`YYYY-MM-DD`) without an occurrence; empty to clear
+///
+/// # Returns
+/// * `AppResult<Vec<NaiveDate>>` - The exception dates, earliest first
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or doesn't repeat, or too
+///   many dates are given
+#[tauri::command]
+pub async fn set_recurrence_exceptions(
+    state: State<'_, AppState>,
+    id: String,
+    dates: Vec<NaiveDate>,
+) -> AppResult<Vec<NaiveDate>> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    recurrence::set_exceptions(&repo, &id, dates).await
+}
+
+/// Proposes a realistic due date from the work already planned on the coming
+/// working days and their working hours
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task being scheduled, if it exists already; its estimate
+///   is used and it isn't counted in the day it is currently due
+/// * `estimated_minutes` - Minutes the task will take; defaults to the task's
+///   estimate, or 30 minutes without one
+///
+/// # Returns
+/// * `AppResult<DueDateSuggestion>` - The date with the reason and the load
+///   of each day considered
+///
+/// # Errors
+/// * Returns `AppError` if the estimate is too large or the task doesn't exist
+#[tauri::command]
+pub async fn suggest_due_date(
+    state: State<'_, AppState>,
+    task_id: Option<String>,
+    estimated_minutes: Option<u32>,
+) -> AppResult<DueDateSuggestion> {
+    if estimated_minutes.is_some_and(|minutes| minutes > MAX_ESTIMATE_MINUTES) {
+        return Err(AppError::validation_error(
+            "estimated_minutes",
+            &format!("must be at most {} (one week)", MAX_ESTIMATE_MINUTES),
+        ));
+    }
+    let repo = Repository::new(state.db.clone());
+    let task = match task_id {
+        Some(task_id) => {
+            validate_id(&task_id)?;
+            Some(repo.get_task(&task_id).await?)
+        }
+        None => None,
+    };
+    due_dates::suggest_due_date(&repo, task.as_ref(), estimated_minutes.map(i64::from)).await
+}
@@ -0,0 +1,151 @@
+use crate::db::models::{ScriptHook, ScriptHookInput};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::script_hooks::{self, ScriptRunResult};
+use crate::AppState;
+use evorbrain_core::events::{is_valid_event_filter, AppEvent, ENTITY_TYPES};
+use evorbrain_core::validation::{validate_id, validate_title};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Request structure for updating an existing script hook
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateScriptHookRequest {
+    pub id: String,
+    #[serde(flatten)]
+    pub hook: ScriptHookInput,
+}
+
+pub(crate) fn validate_script_hook_input(input: &ScriptHookInput) -> AppResult<()> {
+    validate_title("name", &input.name)?;
+
+    if input.events.is_empty() {
+        return Err(AppError::validation_error("events", "at least one event filter is required"));
+    }
+    if let Some(invalid) = input.events.iter().find(|e| !is_valid_event_filter(e)) {
+        return Err(AppError::validation_error(
+            "events",
+            &format!("unknown event filter '{}'", invalid),
+        ));
+    }
+
+    script_hooks::compile(&input.script)?;
+    Ok(())
+}
+
+/// Retrieves all script hooks
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<ScriptHook>>` - All hooks, including inactive ones
+#[tauri::command]
+pub async fn get_script_hooks(state: State<'_, AppState>) -> AppResult<Vec<ScriptHook>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_script_hooks().await
+}
+
+/// Registers a Rhai script to run on matching events
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Name, event filters (`task.completed`, `note.*`, `*`) and script source
+///
+/// # Returns
+/// * `AppResult<ScriptHook>` - The newly created hook
+///
+/// # Errors
+/// * Returns `AppError` if the event filters are invalid or the script doesn't compile
+#[tauri::command]
+pub async fn create_script_hook(
+    state: State<'_, AppState>,
+    request: ScriptHookInput,
+) -> AppResult<ScriptHook> {
+    validate_script_hook_input(&request)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.create_script_hook(request).await
+}
+
+/// Updates a script hook's name, event filters, script or active flag
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Hook ID and the new values
+///
+/// # Returns
+/// * `AppResult<ScriptHook>` - The updated hook
+///
+/// # Errors
+/// * Returns `AppError` if the hook doesn't exist or the input is invalid
+#[tauri::command]
+pub async fn update_script_hook(
+    state: State<'_, AppState>,
+    request: UpdateScriptHookRequest,
+) -> AppResult<ScriptHook> {
+    validate_id(&request.id)?;
+    validate_script_hook_input(&request.hook)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.get_script_hook(&request.id).await?;
+    repo.update_script_hook(&request.id, request.hook).await
+}
+
+/// Permanently removes a script hook
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The hook ID
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the hook doesn't exist
+#[tauri::command]
+pub async fn delete_script_hook(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_script_hook", &confirmation_token)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.delete_script_hook(&id).await
+}
+
+/// Runs a script hook now against an entity, as if `event` had occurred
+///
+/// The run is real: changes the script makes are kept.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The hook ID
+/// * `event` - Event name to simulate, e.g. `task.completed`
+/// * `entity_id` - ID of the entity of the event's type
+///
+/// # Returns
+/// * `AppResult<ScriptRunResult>` - Printed output, error and duration of the run
+///
+/// # Errors
+/// * Returns `AppError` if the hook doesn't exist or the event name is invalid
+#[tauri::command]
+pub async fn test_script_hook(
+    state: State<'_, AppState>,
+    id: String,
+    event: String,
+    entity_id: String,
+) -> AppResult<ScriptRunResult> {
+    validate_id(&id)?;
+    validate_id(&entity_id)?;
+
+    let entity_type = event.split('.').next().unwrap_or_default();
+    if !ENTITY_TYPES.contains(&entity_type) || !is_valid_event_filter(&event) || event.ends_with('*') {
+        return Err(AppError::validation_error("event", "must be an entity event such as task.completed"));
+    }
+
+    let repo = Repository::new(state.db.clone());
+    let hook = repo.get_script_hook(&id).await?;
+    script_hooks::run_hook(state.db.clone(), hook, AppEvent::new(&event, entity_type, entity_id)).await
+}
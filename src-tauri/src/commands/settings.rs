@@ -0,0 +1,216 @@
+use crate::db::models::AreaContext;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::logger::LogLevel;
+use crate::settings_transfer::{self, SettingsBundle, SettingsImportReport};
+use crate::AppState;
+use chrono::NaiveDate;
+use evorbrain_core::calendar::{Calendar, Week};
+use evorbrain_core::events::AppEvent;
+use evorbrain_core::settings::{self, Settings};
+use serde_json::{Map, Value};
+use tauri::State;
+
+/// Applies a log level to the global logger, if it has been initialized
+pub(crate) fn apply_log_level(level: LogLevel) {
+    if let Some(logger) = crate::logger::logger() {
+        logger.set_level(level);
+    }
+}
+
+/// Consumes a confirmation token when the new `archive_retention_days`
+/// makes the retention job delete archived items it kept so far, that is
+/// when retention is turned on or shortened
+async fn confirm_retention(
+    state: &AppState,
+    repo: &Repository,
+    command: &str,
+    days: Option<&Value>,
+    confirmation_token: Option<&str>,
+) -> AppResult<()> {
+    let Some(days) = days.and_then(Value::as_u64).and_then(|days| u32::try_from(days).ok()) else {
+        return Ok(());
+    };
+    let current = settings::load(repo).await?.archive_retention_days;
+    if current.is_none_or(|current| days < current) {
+        state.confirmations.consume(command, confirmation_token.unwrap_or_default())?;
+    }
+    Ok(())
+}
+
+/// Retrieves the current settings, with defaults for anything not changed
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Settings>` - The current settings (the GitHub token is never returned)
+///
+/// # Errors
+/// * Returns `AppError` if the settings cannot be loaded
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> AppResult<Settings> {
+    let repo = Repository::new(state.db.clone());
+    settings::load(&repo).await
+}
+
+/// Changes one or more settings
+///
+/// Only the given keys are changed; a `null` value resets that setting to its
+/// default. A `settings.updated` event is published for every key whose
+/// value changed.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `changes` - Map of setting keys to new values
+/// * `confirmation_token` - Token from `request_destructive_confirmation`,
+///   needed only to turn on or shorten `archive_retention_days`
+///
+/// # Returns
+/// * `AppResult<Settings>` - The settings after the change
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if archive retention is turned
+///   on or shortened without a valid token
+/// * Returns `AppError` if a key is unknown, a value is invalid or the update fails
+#[tauri::command]
+pub async fn update_settings(
+    state: State<'_, AppState>,
+    changes: Map<String, Value>,
+    confirmation_token: Option<String>,
+) -> AppResult<Settings> {
+    let repo = Repository::new(state.db.clone());
+    confirm_retention(
+        &state,
+        &repo,
+        "update_settings",
+        changes.get("archive_retention_days"),
+        confirmation_token.as_deref(),
+    )
+    .await?;
+    let (settings, changed) = settings::update(&repo, changes).await?;
+
+    if changed.iter().any(|key| key == "log_level") {
+        apply_log_level(settings.log_level);
+        crate::log_info!("Log level changed", &format!("New level: {:?}", settings.log_level));
+    }
+    for key in changed {
+        state.events.publish(AppEvent::new("settings.updated", "settings", &key));
+    }
+
+    Ok(settings)
+}
+
+/// Switches between personal and work: while a context is active, life areas
+/// marked with the other one drop out of every list and dashboard together
+/// with their goals, projects, tasks and notes
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `context` - `personal` or `work`; omit to show everything again
+///
+/// # Returns
+/// * `AppResult<Settings>` - The updated settings
+///
+/// # Errors
+/// * Returns `AppError` if the setting cannot be saved
+#[tauri::command]
+pub async fn set_active_context(state: State<'_, AppState>, context: Option<AreaContext>) -> AppResult<Settings> {
+    let repo = Repository::new(state.db.clone());
+    let mut changes = Map::new();
+    changes.insert("active_context".to_string(), serde_json::to_value(context)?);
+    let (settings, changed) = settings::update(&repo, changes).await?;
+
+    for key in changed {
+        state.events.publish(AppEvent::new("settings.updated", "settings", &key));
+    }
+    Ok(settings)
+}
+
+/// Returns the week containing a date, using the `week_start`, `timezone`
+/// and `date_format` settings
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `date` - Any day of the week (`YYYY-MM-DD`); defaults to today
+///
+/// # Returns
+/// * `AppResult<Week>` - First and last day, the UTC range and a display label
+///
+/// # Errors
+/// * Returns `AppError` if the settings cannot be loaded
+#[tauri::command]
+pub async fn get_week(state: State<'_, AppState>, date: Option<NaiveDate>) -> AppResult<Week> {
+    let repo = Repository::new(state.db.clone());
+    let calendar = Calendar::from_settings(&settings::load(&repo).await?);
+    Ok(calendar.week(date.unwrap_or_else(|| calendar.today())))
+}
+
+/// Exports settings, custom workflows, webhooks, script hooks and job
+/// schedules without any other data
+///
+/// The GitHub token and webhook signing secrets are left out.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<SettingsBundle>` - The configuration, ready for `import_settings`
+///
+/// # Errors
+/// * Returns `AppError` if the configuration cannot be read
+#[tauri::command]
+pub async fn export_settings(state: State<'_, AppState>) -> AppResult<SettingsBundle> {
+    let repo = Repository::new(state.db.clone());
+    settings_transfer::export(&repo).await
+}
+
+/// Imports a bundle produced by `export_settings`
+///
+/// Settings missing from the bundle reset to their defaults. Workflows go to
+/// the project with the same ID or, failing that, the same title; webhooks
+/// and script hooks that already exist are skipped.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `bundle` - The exported configuration
+/// * `confirmation_token` - Token from `request_destructive_confirmation`,
+///   needed only if the bundle turns on or shortens `archive_retention_days`
+///
+/// # Returns
+/// * `AppResult<SettingsImportReport>` - What changed and what was skipped
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if archive retention is turned
+///   on or shortened without a valid token
+/// * Returns `AppError` if any part of the bundle is invalid (nothing is
+///   written then) or a write fails
+#[tauri::command]
+pub async fn import_settings(
+    state: State<'_, AppState>,
+    bundle: SettingsBundle,
+    confirmation_token: Option<String>,
+) -> AppResult<SettingsImportReport> {
+    let repo = Repository::new(state.db.clone());
+    confirm_retention(
+        &state,
+        &repo,
+        "import_settings",
+        bundle.settings.get("archive_retention_days"),
+        confirmation_token.as_deref(),
+    )
+    .await?;
+    let report = settings_transfer::import(&repo, bundle).await?;
+
+    if report.settings_changed.iter().any(|key| key == "log_level") {
+        apply_log_level(settings::load(&repo).await?.log_level);
+    }
+    for key in &report.settings_changed {
+        state.events.publish(AppEvent::new("settings.updated", "settings", key));
+    }
+    for project_id in &report.workflows_applied {
+        state.events.publish(AppEvent::new("workflow.updated", "project", project_id));
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,64 @@
+use crate::db::models::SomedayMaybe;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+/// Parks a goal or project on the someday/maybe list
+///
+/// Parked items and the tasks of parked projects (directly or through their
+/// goal) are left out of the default goal and project lists, today's tasks,
+/// My Day and badge counts until they are reactivated.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - `goal` or `project`
+/// * `id` - The item to park
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns `AppError` if the item doesn't exist or is archived or completed
+#[tauri::command]
+pub async fn park_item(state: State<'_, AppState>, entity_type: String, id: String) -> AppResult<()> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_incubated(&entity_type, &id, true).await
+}
+
+/// Moves a parked goal or project back to the active lists
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `entity_type` - `goal` or `project`
+/// * `id` - The item to reactivate
+///
+/// # Returns
+/// * `AppResult<()>` - Success or error
+///
+/// # Errors
+/// * Returns `AppError` if the item doesn't exist
+#[tauri::command]
+pub async fn reactivate_item(state: State<'_, AppState>, entity_type: String, id: String) -> AppResult<()> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_incubated(&entity_type, &id, false).await
+}
+
+/// Lists the goals and projects parked on the someday/maybe list
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<SomedayMaybe>` - Parked goals and projects, most recent first
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_someday_maybe(state: State<'_, AppState>) -> AppResult<SomedayMaybe> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_someday_maybe().await
+}
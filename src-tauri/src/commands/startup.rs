@@ -0,0 +1,17 @@
+use crate::startup::StartupReport;
+use crate::AppState;
+use tauri::State;
+
+/// Returns how long each startup phase took, including the work deferred
+/// until the window was ready
+///
+/// # Arguments
+/// * `state` - Application state holding the startup timings
+///
+/// # Returns
+/// * `StartupReport` - Phase timings in milliseconds since the app started;
+///   `deferred_finished_ms` stays unset while deferred work is still running
+#[tauri::command]
+pub fn get_startup_report(state: State<'_, AppState>) -> StartupReport {
+    state.startup.report()
+}
@@ -0,0 +1,25 @@
+use crate::db::models::SwitcherEntry;
+use crate::AppState;
+use evorbrain_core::switcher::DEFAULT_SWITCHER_LIMIT;
+use tauri::State;
+
+/// Finds life areas, goals, projects, tasks and notes by title for the
+/// command palette
+///
+/// Searches an in-memory index instead of the database, so it can run on
+/// every keystroke. Exact and prefix matches come first, then titles with a
+/// word starting with the query, titles containing it and titles containing
+/// its letters in order. Archived entities aren't found.
+///
+/// # Arguments
+/// * `state` - Application state holding the switcher index
+/// * `query` - What was typed so far
+/// * `limit` - Most matches to return; 10 by default, at most 50
+///
+/// # Returns
+/// * `Vec<SwitcherEntry>` - Type, ID and title of the matches, best first
+#[tauri::command]
+pub fn quick_switch(state: State<'_, AppState>, query: String, limit: Option<u32>) -> Vec<SwitcherEntry> {
+    let limit = limit.map_or(DEFAULT_SWITCHER_LIMIT, |limit| limit as usize);
+    state.switcher.search(&query, limit)
+}
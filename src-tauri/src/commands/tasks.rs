@@ -1,21 +1,16 @@
-use crate::db::models::{Task, TaskPriority};
+use crate::db::models::{Task, TaskInput, TaskTreeNode, TodayExclusion};
 use crate::db::repository::Repository;
+use crate::error::AppResult;
 use crate::AppState;
-use anyhow::Result;
 use chrono::{DateTime, Utc};
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::conflicts::ensure_unchanged;
+use evorbrain_core::settings;
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tauri::State;
-use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateTaskRequest {
-    pub project_id: Option<String>,
-    pub parent_task_id: Option<String>,
-    pub title: String,
-    pub description: Option<String>,
-    pub priority: Option<TaskPriority>,
-    pub due_date: Option<DateTime<Utc>>,
-}
+pub type CreateTaskRequest = TaskInput;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateTaskWithSubtasksRequest {
@@ -23,15 +18,25 @@ pub struct CreateTaskWithSubtasksRequest {
     pub subtasks: Vec<CreateTaskRequest>,
 }
 
+/// Order of the tasks returned by `get_tasks`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskSort {
+    /// Priority, then due date, then newest first
+    #[default]
+    Priority,
+    /// Computed urgency score, most urgent first
+    Urgency,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateTaskRequest {
     pub id: String,
-    pub project_id: Option<String>,
-    pub parent_task_id: Option<String>,
-    pub title: String,
-    pub description: Option<String>,
-    pub priority: TaskPriority,
-    pub due_date: Option<DateTime<Utc>>,
+    /// `updated_at` as the client loaded it, to refuse conflicting edits
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub task: TaskInput,
 }
 
 #[tauri::command]
@@ -39,30 +44,8 @@ pub async fn create_task(
     state: State<'_, AppState>,
     request: CreateTaskRequest,
 ) -> Result<Task, String> {
-    let id = Uuid::new_v4().to_string();
-    let now = Utc::now();
-    let priority = request.priority.unwrap_or_default();
-    
-    sqlx::query(
-        r#"
-        INSERT INTO tasks (id, project_id, parent_task_id, title, description, priority, due_date, created_at, updated_at)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-        "#
-    )
-    .bind(&id)
-    .bind(&request.project_id)
-    .bind(&request.parent_task_id)
-    .bind(&request.title)
-    .bind(&request.description)
-    .bind(priority.to_string())
-    .bind(&request.due_date)
-    .bind(&now)
-    .bind(&now)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_task(state, id).await
+    let repo = Repository::new(state.db.clone());
+    repo.create_task(request).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -73,33 +56,14 @@ pub async fn create_task_with_subtasks(
     let repo = Repository::new(state.db.clone());
     
     // Create main task
-    let main_task = Task {
-        id: Uuid::new_v4().to_string(),
-        project_id: request.task.project_id,
-        parent_task_id: request.task.parent_task_id,
-        title: request.task.title,
-        description: request.task.description,
-        priority: request.task.priority.unwrap_or_default(),
-        due_date: request.task.due_date,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-        completed_at: None,
-        archived_at: None,
-    };
+    let main_task = Task::from_input(request.task);
     
     // Create subtasks
-    let subtasks: Vec<Task> = request.subtasks.into_iter().map(|req| Task {
-        id: Uuid::new_v4().to_string(),
-        project_id: req.project_id.or(main_task.project_id.clone()),
-        parent_task_id: Some(main_task.id.clone()),
-        title: req.title,
-        description: req.description,
-        priority: req.priority.unwrap_or_default(),
-        due_date: req.due_date,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-        completed_at: None,
-        archived_at: None,
+    let subtasks: Vec<Task> = request.subtasks.into_iter().map(|req| {
+        let mut subtask = Task::from_input(req);
+        subtask.project_id = subtask.project_id.or(main_task.project_id.clone());
+        subtask.parent_task_id = Some(main_task.id.clone());
+        subtask
     }).collect();
     
     let task_id = repo.create_task_with_subtasks(main_task.clone(), subtasks)
@@ -110,27 +74,9 @@ pub async fn create_task_with_subtasks(
 }
 
 #[tauri::command]
-pub async fn get_tasks(state: State<'_, AppState>) -> Result<Vec<Task>, String> {
-    sqlx::query_as::<_, Task>(
-        r#"
-        SELECT id, project_id, parent_task_id, title, description, priority, due_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM tasks
-        WHERE archived_at IS NULL
-        ORDER BY 
-            CASE priority 
-                WHEN 'urgent' THEN 1
-                WHEN 'high' THEN 2
-                WHEN 'medium' THEN 3
-                WHEN 'low' THEN 4
-            END,
-            due_date ASC NULLS LAST,
-            created_at DESC
-        "#
-    )
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+pub async fn get_tasks(state: State<'_, AppState>, sort: Option<TaskSort>) -> Result<Vec<Task>, String> {
+    let repo = Repository::new(state.db.clone());
+    super::v2::list_tasks(&repo, sort).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -138,27 +84,8 @@ pub async fn get_tasks_by_project(
     state: State<'_, AppState>,
     project_id: String,
 ) -> Result<Vec<Task>, String> {
-    sqlx::query_as::<_, Task>(
-        r#"
-        SELECT id, project_id, parent_task_id, title, description, priority, due_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM tasks
-        WHERE project_id = ?1 AND archived_at IS NULL
-        ORDER BY 
-            CASE priority 
-                WHEN 'urgent' THEN 1
-                WHEN 'high' THEN 2
-                WHEN 'medium' THEN 3
-                WHEN 'low' THEN 4
-            END,
-            due_date ASC NULLS LAST,
-            created_at DESC
-        "#
-    )
-    .bind(&project_id)
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_tasks_by_project(&project_id).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -166,156 +93,124 @@ pub async fn get_subtasks(
     state: State<'_, AppState>,
     parent_task_id: String,
 ) -> Result<Vec<Task>, String> {
-    sqlx::query_as::<_, Task>(
-        r#"
-        SELECT id, project_id, parent_task_id, title, description, priority, due_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM tasks
-        WHERE parent_task_id = ?1 AND archived_at IS NULL
-        ORDER BY created_at ASC
-        "#
-    )
-    .bind(&parent_task_id)
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_subtasks(&parent_task_id).await.map_err(|e| e.to_string())
+}
+
+/// Returns a task with all of its subtasks, nested, in one query
+#[tauri::command]
+pub async fn get_task_tree(state: State<'_, AppState>, root_id: String) -> Result<TaskTreeNode, String> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_task_tree(&root_id).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn get_task(state: State<'_, AppState>, id: String) -> Result<Task, String> {
-    sqlx::query_as::<_, Task>(
-        r#"
-        SELECT id, project_id, parent_task_id, title, description, priority, due_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM tasks
-        WHERE id = ?1
-        "#
-    )
-    .bind(&id)
-    .fetch_one(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
+    let repo = Repository::new(state.db.clone());
+    repo.get_task(&id).await.map_err(|e| e.to_string())
 }
 
+/// Updates an existing task
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Update request containing ID and fields to update
+///
+/// # Returns
+/// * `AppResult<Task>` - The updated task
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or the update fails
+/// * Returns a `CONFLICT` error holding the stored task and the rejected
+///   change if the task changed after `expected_updated_at`
 #[tauri::command]
-pub async fn update_task(
-    state: State<'_, AppState>,
-    request: UpdateTaskRequest,
-) -> Result<Task, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE tasks 
-        SET project_id = ?1, parent_task_id = ?2, title = ?3, description = ?4, 
-            priority = ?5, due_date = ?6, updated_at = ?7
-        WHERE id = ?8
-        "#
-    )
-    .bind(&request.project_id)
-    .bind(&request.parent_task_id)
-    .bind(&request.title)
-    .bind(&request.description)
-    .bind(request.priority.to_string())
-    .bind(&request.due_date)
-    .bind(&now)
-    .bind(&request.id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_task(state, request.id).await
+pub async fn update_task(state: State<'_, AppState>, request: UpdateTaskRequest) -> AppResult<Task> {
+    let repo = Repository::new(state.db.clone());
+    let current = repo.get_task(&request.id).await?;
+    ensure_unchanged("task", &request.id, request.expected_updated_at, current.updated_at, &current, &request)?;
+    repo.update_task(&request.id, request.task).await
 }
 
+/// Marks a task as completed
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - ID of the task to complete
+///
+/// # Returns
+/// * `AppResult<Task>` - The completed task
+///
+/// # Errors
+/// * Returns a `CANNOT_UPDATE` error whose data lists the open tasks under
+///   `blocked_by` if the task depends on tasks that aren't completed
+/// * Returns `AppError` if the task doesn't exist or the update fails
 #[tauri::command]
-pub async fn complete_task(state: State<'_, AppState>, id: String) -> Result<Task, String> {
+pub async fn complete_task(state: State<'_, AppState>, id: String) -> AppResult<Task> {
     let repo = Repository::new(state.db.clone());
-    repo.complete_task(&id)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    get_task(state, id).await
+    repo.complete_task(&id).await?;
+    repo.get_task(&id).await
 }
 
 #[tauri::command]
 pub async fn uncomplete_task(state: State<'_, AppState>, id: String) -> Result<Task, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE tasks 
-        SET completed_at = NULL, updated_at = ?1
-        WHERE id = ?2
-        "#
-    )
-    .bind(&now)
-    .bind(&id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    let repo = Repository::new(state.db.clone());
+    repo.uncomplete_task(&id)
+        .await
+        .map_err(|e| e.to_string())?;
     
     get_task(state, id).await
 }
 
 #[tauri::command]
 pub async fn delete_task(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    use crate::db::repository::Repository;
-    
     let repo = Repository::new(state.db.clone());
     repo.archive_task_cascade(&id)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Restores an archived task; with `cascade`, also the subtasks and notes
+/// archived together with it
+///
+/// Fails with `ARCHIVED_PARENT`, listing the archived parents in the error
+/// data, while a parent task, the project or its goal is still archived.
 #[tauri::command]
-pub async fn restore_task(state: State<'_, AppState>, id: String) -> Result<Task, String> {
-    let now = Utc::now();
-    
-    sqlx::query(
-        r#"
-        UPDATE tasks 
-        SET archived_at = NULL, updated_at = ?1
-        WHERE id = ?2
-        "#
-    )
-    .bind(&now)
-    .bind(&id)
-    .execute(&*state.db)
-    .await
-    .map_err(|e| e.to_string())?;
-    
-    get_task(state, id).await
+pub async fn restore_task(
+    state: State<'_, AppState>,
+    id: String,
+    cascade: Option<bool>,
+) -> AppResult<Task> {
+    let repo = Repository::new(state.db.clone());
+    repo.restore_task(&id, cascade.unwrap_or(false)).await?;
+    state.switcher.invalidate();
+    repo.get_task(&id).await
 }
 
 #[tauri::command]
 pub async fn get_todays_tasks(state: State<'_, AppState>) -> Result<Vec<Task>, String> {
-    let today_start = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
-    let today_end = Utc::now().date_naive().and_hms_opt(23, 59, 59).unwrap().and_utc();
+    let repo = Repository::new(state.db.clone());
+    let calendar = Calendar::from_settings(&settings::load(&repo).await.map_err(|e| e.to_string())?);
+    let today = calendar.day(calendar.today());
     
-    sqlx::query_as::<_, Task>(
-        r#"
-        SELECT id, project_id, parent_task_id, title, description, priority, due_date,
-               created_at, updated_at, completed_at, archived_at
-        FROM tasks
-        WHERE archived_at IS NULL
-          AND completed_at IS NULL
-          AND (
-              (due_date >= ?1 AND due_date <= ?2)
-              OR priority = 'urgent'
-          )
-        ORDER BY 
-            CASE priority 
-                WHEN 'urgent' THEN 1
-                WHEN 'high' THEN 2
-                WHEN 'medium' THEN 3
-                WHEN 'low' THEN 4
-            END,
-            due_date ASC NULLS LAST
-        "#
-    )
-    .bind(&today_start)
-    .bind(&today_end)
-    .fetch_all(&*state.db)
-    .await
-    .map_err(|e| e.to_string())
-}
\ No newline at end of file
+    repo.get_todays_tasks(today.start, today.end).await.map_err(|e| e.to_string())
+}
+
+/// Lists the tasks left out of `get_todays_tasks` because they are blocked
+/// by open dependencies or snoozed
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<TodayExclusion>>` - The tasks, each with why it was left out
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_todays_exclusions(state: State<'_, AppState>) -> AppResult<Vec<TodayExclusion>> {
+    let repo = Repository::new(state.db.clone());
+    let calendar = Calendar::from_settings(&settings::load(&repo).await?);
+    let today = calendar.day(calendar.today());
+
+    repo.get_todays_exclusions(today.start, today.end).await
+}
@@ -0,0 +1,206 @@
+use crate::db::models::{TimeBudgetReport, TimeEntry};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use chrono::NaiveDate;
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::focus::{self, FocusReport};
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+/// Starts a timer on a task, stopping the running timer if there is one
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task to track time on
+///
+/// # Returns
+/// * `AppResult<TimeEntry>` - The running entry
+///
+/// # Errors
+/// * Returns `AppError` if the task doesn't exist or the query fails
+#[tauri::command]
+pub async fn start_timer(state: State<'_, AppState>, task_id: String) -> AppResult<TimeEntry> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.start_time_entry(&task_id).await
+}
+
+/// Stops the running timer
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Option<TimeEntry>>` - The stopped entry, or `None` if no
+///   timer was running
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn stop_timer(state: State<'_, AppState>) -> AppResult<Option<TimeEntry>> {
+    let repo = Repository::new(state.db.clone());
+    repo.stop_time_entry().await
+}
+
+/// Returns the running timer, if any
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Option<TimeEntry>>` - The running entry
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_running_timer(state: State<'_, AppState>) -> AppResult<Option<TimeEntry>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_running_time_entry().await
+}
+
+/// Returns the time entries of a task, newest first
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `task_id` - The task
+///
+/// # Returns
+/// * `AppResult<Vec<TimeEntry>>` - The task's entries
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_time_entries(state: State<'_, AppState>, task_id: String) -> AppResult<Vec<TimeEntry>> {
+    validate_id(&task_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_time_entries(&task_id).await
+}
+
+/// Returns entries stopped because the machine went idle or asleep that
+/// still need to be reconciled
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<TimeEntry>>` - Unreconciled entries, newest first
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn get_unreconciled_time_entries(state: State<'_, AppState>) -> AppResult<Vec<TimeEntry>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_unreconciled_time_entries().await
+}
+
+/// Decides whether the idle gap of an idle-stopped entry counts as tracked
+/// time
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The idle-stopped entry
+/// * `keep_idle_time` - Extend the entry over the gap instead of ending it
+///   when the gap began
+///
+/// # Returns
+/// * `AppResult<TimeEntry>` - The reconciled entry
+///
+/// # Errors
+/// * Returns `AppError` if the entry doesn't exist, wasn't stopped for
+///   idleness or is already reconciled
+#[tauri::command]
+pub async fn reconcile_time_entry(
+    state: State<'_, AppState>,
+    id: String,
+    keep_idle_time: bool,
+) -> AppResult<TimeEntry> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.reconcile_time_entry(&id, keep_idle_time).await
+}
+
+/// The range of a focus report, the current week unless given
+async fn focus_range(
+    repo: &Repository,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> AppResult<(NaiveDate, NaiveDate)> {
+    let calendar = Calendar::load(repo).await?;
+    let week = calendar.week(calendar.today());
+    Ok((from.unwrap_or(week.first_day), to.unwrap_or(week.last_day)))
+}
+
+/// Sums the time tracked on tasks per goal, to compare where time went with
+/// the goals that matter
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `from` - First day of the range; defaults to the start of this week
+/// * `to` - Last day of the range, included; defaults to the end of this week
+///
+/// # Returns
+/// * `AppResult<FocusReport>` - Minutes and share of tracked time per goal,
+///   open goals without tracked time last
+///
+/// # Errors
+/// * `VALIDATION_ERROR` - `to` is before `from`
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_focus_by_goal(
+    state: State<'_, AppState>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> AppResult<FocusReport> {
+    let repo = Repository::new(state.db.clone());
+    let (from, to) = focus_range(&repo, from, to).await?;
+    focus::get_focus_by_goal(&repo, from, to).await
+}
+
+/// Sums the time tracked on tasks per life area
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `from` - First day of the range; defaults to the start of this week
+/// * `to` - Last day of the range, included; defaults to the end of this week
+///
+/// # Returns
+/// * `AppResult<FocusReport>` - Minutes and share of tracked time per life
+///   area, areas without tracked time last
+///
+/// # Errors
+/// * `VALIDATION_ERROR` - `to` is before `from`
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_focus_by_life_area(
+    state: State<'_, AppState>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> AppResult<FocusReport> {
+    let repo = Repository::new(state.db.clone());
+    let (from, to) = focus_range(&repo, from, to).await?;
+    focus::get_focus_by_life_area(&repo, from, to).await
+}
+
+/// Compares the time tracked per life area over a week with the life
+/// area's weekly budget
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `date` - A day of the week to report on; defaults to today
+///
+/// # Returns
+/// * `AppResult<TimeBudgetReport>` - Budget, tracked and expected minutes and
+///   an under, on track or over status per life area with a budget
+///
+/// # Errors
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_time_budget_report(state: State<'_, AppState>, date: Option<NaiveDate>) -> AppResult<TimeBudgetReport> {
+    let repo = Repository::new(state.db.clone());
+    let date = match date {
+        Some(date) => date,
+        None => Calendar::load(&repo).await?.today(),
+    };
+    focus::get_time_budget_report(&repo, date).await
+}
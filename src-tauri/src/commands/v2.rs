@@ -0,0 +1,279 @@
+use crate::commands::tasks::TaskSort;
+use crate::db::models::{Note, Task};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::note_encryption::{reveal, reveal_all};
+use crate::AppState;
+use evorbrain_core::dto::{Envelope, GoalDto, LifeAreaDto, NoteDto, ProjectDto, TaskDto};
+use evorbrain_core::urgency;
+use evorbrain_core::validation::{validate_id, validate_optional_id};
+use tauri::State;
+
+/// Tasks in the requested order; shared with the v1 `get_tasks`
+pub(crate) async fn list_tasks(repo: &Repository, sort: Option<TaskSort>) -> AppResult<Vec<Task>> {
+    let mut tasks = repo.get_tasks().await?;
+    if let Some(TaskSort::Urgency) = sort {
+        urgency::sort_by_urgency(repo, &mut tasks).await?;
+    }
+    Ok(tasks)
+}
+
+/// Warning for private notes whose content is hidden because the app is locked
+fn hidden_notes_warning(state: &AppState, notes: &[Note]) -> Option<String> {
+    if state.lock.note_key().is_some() {
+        return None;
+    }
+    match notes.iter().filter(|note| note.is_private).count() {
+        0 => None,
+        n => Some(format!("content of {} private notes is hidden until the app is unlocked", n)),
+    }
+}
+
+/// Lists life areas, one page at a time
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `page` - Page to return, from 1; defaults to 1
+/// * `page_size` - Items per page; defaults to 100, at most 500
+///
+/// # Returns
+/// * `AppResult<Envelope<Vec<LifeAreaDto>>>` - Active life areas with pagination
+///
+/// # Errors
+/// * Returns `AppError` if the query fails
+#[tauri::command]
+pub async fn v2_list_life_areas(
+    state: State<'_, AppState>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> AppResult<Envelope<Vec<LifeAreaDto>>> {
+    let repo = Repository::new(state.db.clone());
+    let areas = repo.get_life_areas().await?;
+    Ok(Envelope::page(areas.into_iter().map(LifeAreaDto::from).collect(), page, page_size))
+}
+
+/// Retrieves one life area
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The life area's ID
+///
+/// # Returns
+/// * `AppResult<Envelope<LifeAreaDto>>` - The life area
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the life area doesn't exist
+#[tauri::command]
+pub async fn v2_get_life_area(state: State<'_, AppState>, id: String) -> AppResult<Envelope<LifeAreaDto>> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    Ok(Envelope::new(repo.get_life_area(&id).await?.into()))
+}
+
+/// Lists goals, optionally of one life area, one page at a time
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `life_area_id` - Only goals of this life area; all active goals when omitted
+/// * `page` - Page to return, from 1; defaults to 1
+/// * `page_size` - Items per page; defaults to 100, at most 500
+///
+/// # Returns
+/// * `AppResult<Envelope<Vec<GoalDto>>>` - Goals with pagination
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the query fails
+#[tauri::command]
+pub async fn v2_list_goals(
+    state: State<'_, AppState>,
+    life_area_id: Option<String>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> AppResult<Envelope<Vec<GoalDto>>> {
+    validate_optional_id(life_area_id.as_deref())?;
+    let repo = Repository::new(state.db.clone());
+    let goals = match life_area_id {
+        Some(life_area_id) => repo.get_goals_by_life_area(&life_area_id).await?,
+        None => repo.get_goals().await?,
+    };
+    Ok(Envelope::page(goals.into_iter().map(GoalDto::from).collect(), page, page_size))
+}
+
+/// Retrieves one goal
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The goal's ID
+///
+/// # Returns
+/// * `AppResult<Envelope<GoalDto>>` - The goal
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the goal doesn't exist
+#[tauri::command]
+pub async fn v2_get_goal(state: State<'_, AppState>, id: String) -> AppResult<Envelope<GoalDto>> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    Ok(Envelope::new(repo.get_goal(&id).await?.into()))
+}
+
+/// Lists projects, optionally of one goal, one page at a time
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `goal_id` - Only projects of this goal; all active projects when omitted
+/// * `page` - Page to return, from 1; defaults to 1
+/// * `page_size` - Items per page; defaults to 100, at most 500
+///
+/// # Returns
+/// * `AppResult<Envelope<Vec<ProjectDto>>>` - Projects with pagination
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the query fails
+#[tauri::command]
+pub async fn v2_list_projects(
+    state: State<'_, AppState>,
+    goal_id: Option<String>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> AppResult<Envelope<Vec<ProjectDto>>> {
+    validate_optional_id(goal_id.as_deref())?;
+    let repo = Repository::new(state.db.clone());
+    let projects = match goal_id {
+        Some(goal_id) => repo.get_projects_by_goal(&goal_id).await?,
+        None => repo.get_projects().await?,
+    };
+    Ok(Envelope::page(projects.into_iter().map(ProjectDto::from).collect(), page, page_size))
+}
+
+/// Retrieves one project
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The project's ID
+///
+/// # Returns
+/// * `AppResult<Envelope<ProjectDto>>` - The project
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the project doesn't exist
+#[tauri::command]
+pub async fn v2_get_project(state: State<'_, AppState>, id: String) -> AppResult<Envelope<ProjectDto>> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    Ok(Envelope::new(repo.get_project(&id).await?.into()))
+}
+
+/// Lists tasks, optionally of one project, one page at a time
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - Only tasks of this project; all active tasks when omitted
+/// * `sort` - Order of all active tasks; ignored with `project_id`
+/// * `page` - Page to return, from 1; defaults to 1
+/// * `page_size` - Items per page; defaults to 100, at most 500
+///
+/// # Returns
+/// * `AppResult<Envelope<Vec<TaskDto>>>` - Tasks with pagination
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the query fails
+#[tauri::command]
+pub async fn v2_list_tasks(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    sort: Option<TaskSort>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> AppResult<Envelope<Vec<TaskDto>>> {
+    validate_optional_id(project_id.as_deref())?;
+    let repo = Repository::new(state.db.clone());
+    let tasks = match &project_id {
+        Some(project_id) => repo.get_tasks_by_project(project_id).await?,
+        None => list_tasks(&repo, sort).await?,
+    };
+
+    let envelope = Envelope::page(tasks.into_iter().map(TaskDto::from).collect(), page, page_size);
+    Ok(match (project_id, sort) {
+        (Some(_), Some(_)) => envelope.with_warning("sort is ignored when listing the tasks of a project"),
+        _ => envelope,
+    })
+}
+
+/// Retrieves one task
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task's ID
+///
+/// # Returns
+/// * `AppResult<Envelope<TaskDto>>` - The task
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the task doesn't exist
+#[tauri::command]
+pub async fn v2_get_task(state: State<'_, AppState>, id: String) -> AppResult<Envelope<TaskDto>> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    Ok(Envelope::new(repo.get_task(&id).await?.into()))
+}
+
+/// Lists notes, one page at a time
+///
+/// Private notes are decrypted when the app is unlocked; otherwise their
+/// content is empty and the envelope carries a warning.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `page` - Page to return, from 1; defaults to 1
+/// * `page_size` - Items per page; defaults to 100, at most 500
+///
+/// # Returns
+/// * `AppResult<Envelope<Vec<NoteDto>>>` - Active notes with pagination
+///
+/// # Errors
+/// * Returns `AppError` if the query fails or a private note can't be decrypted
+#[tauri::command]
+pub async fn v2_list_notes(
+    state: State<'_, AppState>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> AppResult<Envelope<Vec<NoteDto>>> {
+    let repo = Repository::new(state.db.clone());
+    let notes = repo.get_notes().await?;
+    let warning = hidden_notes_warning(&state, &notes);
+
+    let notes = reveal_all(&state.lock, notes);
+    let envelope = Envelope::page(notes.into_iter().map(NoteDto::from).collect(), page, page_size);
+    Ok(match warning {
+        Some(warning) => envelope.with_warning(warning),
+        None => envelope,
+    })
+}
+
+/// Retrieves one note
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The note's ID
+///
+/// # Returns
+/// * `AppResult<Envelope<NoteDto>>` - The note; a private note's content is
+///   empty, with a warning, while the app is locked
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed, the note doesn't exist or
+///   can't be decrypted
+#[tauri::command]
+pub async fn v2_get_note(state: State<'_, AppState>, id: String) -> AppResult<Envelope<NoteDto>> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    let note = repo.get_note(&id).await?;
+    let warning = hidden_notes_warning(&state, std::slice::from_ref(&note));
+
+    let envelope = Envelope::new(NoteDto::from(reveal(&state.lock, note)?));
+    Ok(match warning {
+        Some(warning) => envelope.with_warning(warning),
+        None => envelope,
+    })
+}
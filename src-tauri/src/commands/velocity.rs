@@ -0,0 +1,83 @@
+use crate::db::models::EffortRollup;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::critical_path::{self, CriticalPath};
+use evorbrain_core::validation::validate_id;
+use evorbrain_core::velocity::{self, GoalRisk, ProjectVelocity};
+use tauri::State;
+
+/// Returns a project's completed tasks per week, the work left and when it
+/// is projected to be done at the current pace
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - ID of the project
+///
+/// # Returns
+/// * `AppResult<ProjectVelocity>` - Weekly completions, remaining work and projection
+///
+/// # Errors
+/// * Returns `AppError` if the project doesn't exist or the database query fails
+#[tauri::command]
+pub async fn get_project_velocity(state: State<'_, AppState>, project_id: String) -> AppResult<ProjectVelocity> {
+    let repo = Repository::new(state.db.clone());
+    velocity::get_project_velocity(&repo, &project_id).await
+}
+
+/// Returns the longest chain of dependent open tasks in a project, weighted
+/// by their estimates, and the slack of every open task
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - ID of the project
+///
+/// # Returns
+/// * `AppResult<CriticalPath>` - The critical path, its length and estimated
+///   completion date, and each open task's earliest start and slack; tasks
+///   without slack gate the completion
+///
+/// # Errors
+/// * Returns `AppError` if the project doesn't exist or the database query fails
+#[tauri::command]
+pub async fn get_critical_path(state: State<'_, AppState>, project_id: String) -> AppResult<CriticalPath> {
+    validate_id(&project_id)?;
+    let repo = Repository::new(state.db.clone());
+    critical_path::get_critical_path(&repo, &project_id).await
+}
+
+/// Returns the goals at risk of missing their target date: overdue, stalled
+/// or, at the estimated minutes per week completed lately, behind pace
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<GoalRisk>>` - The goals at risk with their countdown,
+///   remaining work and pace, the closest target date first
+///
+/// # Errors
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_goals_at_risk(state: State<'_, AppState>) -> AppResult<Vec<GoalRisk>> {
+    let repo = Repository::new(state.db.clone());
+    velocity::get_goals_at_risk(&repo).await
+}
+
+/// Sums the estimated and tracked minutes of open tasks per project and up
+/// the hierarchy to goals and life areas, for planning at those levels
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<EffortRollup>` - Effort per project, goal and life area ID,
+///   of the inbox and in total
+///
+/// # Errors
+/// * Returns `AppError` if the database query fails
+#[tauri::command]
+pub async fn get_effort_rollup(state: State<'_, AppState>) -> AppResult<EffortRollup> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_effort_rollup().await
+}
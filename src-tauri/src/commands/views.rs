@@ -0,0 +1,62 @@
+use crate::db::models::{ViewPreferences, ViewPreferencesInput, ViewType};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::validation::validate_id;
+use tauri::State;
+
+/// Returns how a view of a project is arranged
+///
+/// Views the user never rearranged come back with their defaults: boards
+/// grouped by status, the other views ungrouped, all in manual order.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - The project the view shows
+/// * `view_type` - `board`, `list`, `calendar` or `timeline`
+///
+/// # Returns
+/// * `AppResult<ViewPreferences>` - Grouping, sorting, collapsed columns and
+///   visible fields of the view
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the project doesn't exist
+#[tauri::command]
+pub async fn get_view_preferences(
+    state: State<'_, AppState>,
+    project_id: String,
+    view_type: ViewType,
+) -> AppResult<ViewPreferences> {
+    validate_id(&project_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_view_preferences(&project_id, view_type).await
+}
+
+/// Saves how a view of a project is arranged
+///
+/// The whole arrangement is replaced; omitting `preferences` restores the
+/// view's defaults.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - The project the view shows
+/// * `view_type` - `board`, `list`, `calendar` or `timeline`
+/// * `preferences` - Grouping, sorting, collapsed columns and visible fields
+///
+/// # Returns
+/// * `AppResult<ViewPreferences>` - The saved arrangement
+///
+/// # Errors
+/// * Returns `AppError` if the project doesn't exist or a field named isn't
+///   a task field the views know
+#[tauri::command]
+pub async fn set_view_preferences(
+    state: State<'_, AppState>,
+    project_id: String,
+    view_type: ViewType,
+    preferences: Option<ViewPreferencesInput>,
+) -> AppResult<ViewPreferences> {
+    validate_id(&project_id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_view_preferences(&project_id, view_type, preferences).await
+}
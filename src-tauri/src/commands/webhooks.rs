@@ -0,0 +1,138 @@
+use crate::db::models::{Webhook, WebhookInput};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use crate::webhooks;
+use crate::AppState;
+use evorbrain_core::events::is_valid_event_filter;
+use evorbrain_core::validation::{validate_http_url, validate_id, validate_title};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Request structure for updating an existing webhook
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub id: String,
+    #[serde(flatten)]
+    pub webhook: WebhookInput,
+}
+
+pub(crate) fn validate_webhook_input(input: &WebhookInput) -> AppResult<()> {
+    validate_title("name", &input.name)?;
+    validate_http_url("url", &input.url)?;
+
+    if input.events.is_empty() {
+        return Err(AppError::validation_error("events", "at least one event filter is required"));
+    }
+    if let Some(invalid) = input.events.iter().find(|e| !is_valid_event_filter(e)) {
+        return Err(AppError::validation_error(
+            "events",
+            &format!("unknown event filter '{}'", invalid),
+        ));
+    }
+    Ok(())
+}
+
+/// Retrieves all registered webhooks
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+///
+/// # Returns
+/// * `AppResult<Vec<Webhook>>` - All webhooks, including inactive ones
+#[tauri::command]
+pub async fn get_webhooks(state: State<'_, AppState>) -> AppResult<Vec<Webhook>> {
+    let repo = Repository::new(state.db.clone());
+    repo.get_webhooks().await
+}
+
+/// Registers a new webhook
+///
+/// A signing secret is generated for the webhook and returned once here (it
+/// is also visible through `get_webhooks`).
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Name, http(s) URL and event filters (`task.completed`, `goal.*`, `*`)
+///
+/// # Returns
+/// * `AppResult<Webhook>` - The newly created webhook including its secret
+///
+/// # Errors
+/// * Returns `AppError` if the URL or event filters are invalid
+#[tauri::command]
+pub async fn create_webhook(
+    state: State<'_, AppState>,
+    request: WebhookInput,
+) -> AppResult<Webhook> {
+    validate_webhook_input(&request)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.create_webhook(request).await
+}
+
+/// Updates a webhook's name, URL, event filters or active flag
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `request` - Webhook ID and the new values
+///
+/// # Returns
+/// * `AppResult<Webhook>` - The updated webhook
+///
+/// # Errors
+/// * Returns `AppError` if the webhook doesn't exist or the input is invalid
+#[tauri::command]
+pub async fn update_webhook(
+    state: State<'_, AppState>,
+    request: UpdateWebhookRequest,
+) -> AppResult<Webhook> {
+    validate_id(&request.id)?;
+    validate_webhook_input(&request.webhook)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.get_webhook(&request.id).await?;
+    repo.update_webhook(&request.id, request.webhook).await
+}
+
+/// Permanently removes a webhook
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The webhook ID
+/// * `confirmation_token` - Token from `request_destructive_confirmation`
+///
+/// # Errors
+/// * Returns a `CONFIRMATION_REQUIRED` error if the token isn't valid
+/// * Returns `AppError` if the webhook doesn't exist
+#[tauri::command]
+pub async fn delete_webhook(
+    state: State<'_, AppState>,
+    id: String,
+    confirmation_token: String,
+) -> AppResult<()> {
+    validate_id(&id)?;
+    state.confirmations.consume("delete_webhook", &confirmation_token)?;
+
+    let repo = Repository::new(state.db.clone());
+    repo.delete_webhook(&id).await
+}
+
+/// Sends a signed `webhook.test` delivery to verify a receiver
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The webhook ID
+///
+/// # Returns
+/// * `AppResult<u16>` - HTTP status code returned by the receiver
+///
+/// # Errors
+/// * Returns `AppError` if the request fails or the receiver responds with an error status
+#[tauri::command]
+pub async fn test_webhook(state: State<'_, AppState>, id: String) -> AppResult<u16> {
+    validate_id(&id)?;
+
+    let repo = Repository::new(state.db.clone());
+    let webhook = repo.get_webhook(&id).await?;
+    webhooks::send_test_delivery(&repo, &webhook).await
+}
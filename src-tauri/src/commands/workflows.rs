@@ -0,0 +1,82 @@
+use crate::db::models::{Task, Workflow, WorkflowStatusInput};
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::AppState;
+use evorbrain_core::events::AppEvent;
+use evorbrain_core::validation::{validate_id, validate_optional_id};
+use tauri::State;
+
+/// Returns the task statuses in effect for a project
+///
+/// Projects without custom statuses (and tasks without a project) use the
+/// default todo -> in_progress -> done workflow.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - Project to look up; omit for tasks without a project
+///
+/// # Returns
+/// * `AppResult<Workflow>` - The statuses in order
+///
+/// # Errors
+/// * Returns `AppError` if the ID is malformed or the query fails
+#[tauri::command]
+pub async fn get_workflow(state: State<'_, AppState>, project_id: Option<String>) -> AppResult<Workflow> {
+    validate_optional_id(project_id.as_deref())?;
+    let repo = Repository::new(state.db.clone());
+    repo.get_workflow(project_id.as_deref()).await
+}
+
+/// Replaces a project's task statuses
+///
+/// Statuses are saved in the given order. Each can limit which statuses tasks
+/// may move to next. An empty list restores the default workflow. Tasks in a
+/// removed status move to the first todo (or done) status.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `project_id` - Project whose workflow to replace
+/// * `statuses` - The new statuses, in order
+///
+/// # Returns
+/// * `AppResult<Workflow>` - The saved workflow
+///
+/// # Errors
+/// * Returns `AppError` if the project doesn't exist or the workflow is invalid
+#[tauri::command]
+pub async fn set_workflow(
+    state: State<'_, AppState>,
+    project_id: String,
+    statuses: Vec<WorkflowStatusInput>,
+) -> AppResult<Workflow> {
+    validate_id(&project_id)?;
+    let repo = Repository::new(state.db.clone());
+    let workflow = repo.set_workflow(&project_id, statuses).await?;
+
+    state.events.publish(AppEvent::new("workflow.updated", "project", &project_id));
+    Ok(workflow)
+}
+
+/// Moves a task to another status of its project's workflow
+///
+/// Moving into a done status completes the task; moving out of one reopens it.
+///
+/// # Arguments
+/// * `state` - Application state containing the database connection
+/// * `id` - The task to move
+/// * `status` - Key of the target status
+///
+/// # Returns
+/// * `AppResult<Task>` - The updated task
+///
+/// # Errors
+/// * Returns `AppError` if the status isn't in the workflow or the workflow
+///   doesn't allow moving there from the current status
+/// * Returns a `CANNOT_UPDATE` error listing the open blockers if a done
+///   status would complete a task that waits for open tasks
+#[tauri::command]
+pub async fn set_task_status(state: State<'_, AppState>, id: String, status: String) -> AppResult<Task> {
+    validate_id(&id)?;
+    let repo = Repository::new(state.db.clone());
+    repo.set_task_status(&id, &status).await
+}
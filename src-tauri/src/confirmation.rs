@@ -0,0 +1,97 @@
+//! Confirmation tokens for destructive commands
+//!
+//! Commands that permanently delete or overwrite data, the ones flagged
+//! `destructive` in the command registry, only run with a token issued by
+//! `request_destructive_confirmation` for that same command; dry runs,
+//! scans that delete nothing and settings changes that don't turn on or
+//! shorten archive retention don't need one. A token is
+//! single-use and expires after `CONFIRMATION_TTL`, so one accidental IPC
+//! call can't wipe anything: the frontend has to ask for a token first,
+//! normally once the user has confirmed the operation in a dialog.
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long an issued token stays valid
+pub const CONFIRMATION_TTL: Duration = Duration::from_secs(60);
+
+/// Commands that need a confirmation token: every command flagged
+/// `destructive` in the command registry
+pub const CONFIRMED_COMMANDS: &[&str] = &[
+    "cleanup_database",
+    "delete_attachment",
+    "delete_context",
+    "delete_email_account",
+    "delete_external_link",
+    "delete_holiday_set",
+    "delete_person",
+    "delete_script_hook",
+    "delete_webhook",
+    "import_all_data",
+    "import_settings",
+    "reset_database",
+    "rollback_migration",
+    "run_job_now",
+    "scan_orphan_attachments",
+    "secure_delete",
+    "update_settings",
+];
+
+/// A token that allows one call of `command` until `expires_at`
+#[derive(Debug, Clone, Serialize)]
+pub struct DestructiveConfirmation {
+    pub token: String,
+    pub command: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Outstanding tokens, each with its command and expiry
+#[derive(Default)]
+pub struct Confirmations {
+    pending: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl Confirmations {
+    /// Issues a token for one call of `command`
+    pub fn issue(&self, command: &str) -> AppResult<DestructiveConfirmation> {
+        if !CONFIRMED_COMMANDS.contains(&command) {
+            return Err(AppError::validation_error(
+                "command",
+                &format!("must be one of {}", CONFIRMED_COMMANDS.join(", ")),
+            ));
+        }
+
+        let token = Uuid::new_v4().to_string();
+        let now = Instant::now();
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|_, (_, expires)| *expires > now);
+        pending.insert(token.clone(), (command.to_string(), now + CONFIRMATION_TTL));
+
+        Ok(DestructiveConfirmation {
+            token,
+            command: command.to_string(),
+            expires_at: Utc::now() + CONFIRMATION_TTL,
+        })
+    }
+
+    /// Uses up `token`; fails unless it was issued for `command` and hasn't
+    /// expired. A token for another command stays valid.
+    pub fn consume(&self, command: &str, token: &str) -> AppResult<()> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        match pending.get(token) {
+            Some((issued_for, expires)) if issued_for == command && *expires > Instant::now() => {
+                pending.remove(token);
+                Ok(())
+            }
+            _ => Err(AppError::new(
+                ErrorCode::ConfirmationRequired,
+                format!("'{}' needs a valid confirmation token; request one first", command),
+            )),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! `evorbrain://` deep link routing
+//!
+//! Supported URLs:
+//! * `evorbrain://task/<id>` (also `project`, `goal` and `note`) opens the entity
+//! * `evorbrain://capture?text=...&project=<id>` captures a task
+//!
+//! Parsed links are executed in the backend and the outcome is emitted to the
+//! frontend as a `deep-link` event so it can navigate or show a toast.
+
+use crate::db::models::Task;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::{log_error, log_info, AppState};
+use evorbrain_core::validation::{validate_id, validate_title};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use url::Url;
+
+pub const SCHEME: &str = "evorbrain";
+
+/// A validated deep link request
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    Open { entity_type: String, id: String },
+    Capture { text: String, project_id: Option<String> },
+}
+
+/// Result of handling a deep link, sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLinkOutcome {
+    Open { entity_type: String, id: String },
+    Captured { task: Box<Task> },
+}
+
+/// Parses and validates an incoming deep link URL
+pub fn parse(raw: &str) -> AppResult<DeepLink> {
+    let url = Url::parse(raw.trim())
+        .map_err(|_| AppError::new(ErrorCode::InvalidInput, format!("Invalid deep link '{}'", raw)))?;
+
+    if url.scheme() != SCHEME {
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            format!("Unsupported URL scheme '{}'", url.scheme()),
+        ));
+    }
+
+    let route = url.host_str().unwrap_or_default();
+    match route {
+        "task" | "project" | "goal" | "note" => {
+            let id = url.path().trim_matches('/');
+            validate_id(id)?;
+            Ok(DeepLink::Open {
+                entity_type: route.to_string(),
+                id: id.to_string(),
+            })
+        }
+        "capture" => {
+            let mut text = None;
+            let mut project_id = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "text" => text = Some(value.trim().to_string()),
+                    "project" => project_id = Some(value.into_owned()),
+                    _ => {}
+                }
+            }
+
+            let text = text.unwrap_or_default();
+            validate_title("text", &text)?;
+            Ok(DeepLink::Capture { text, project_id })
+        }
+        _ => Err(AppError::new(
+            ErrorCode::InvalidInput,
+            format!("Unknown deep link route '{}'", route),
+        )),
+    }
+}
+
+/// Executes a parsed deep link against the database
+pub async fn execute(repo: &Repository, link: DeepLink) -> AppResult<DeepLinkOutcome> {
+    match link {
+        DeepLink::Open { entity_type, id } => {
+            if repo.get_entity_snapshot(&entity_type, &id).await?.is_none() {
+                return Err(AppError::not_found(&entity_type, &id));
+            }
+            Ok(DeepLinkOutcome::Open { entity_type, id })
+        }
+        DeepLink::Capture { text, project_id } => {
+            let task = repo.quick_add_task(&text, project_id.as_deref()).await?;
+            Ok(DeepLinkOutcome::Captured { task: Box::new(task) })
+        }
+    }
+}
+
+/// Handles URLs delivered by the OS: executes them, emits the outcome and
+/// brings the main window to the front
+pub fn handle_urls(app_handle: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        let app_handle = app_handle.clone();
+
+        tauri::async_runtime::spawn(async move {
+            log_info!("Handling deep link", url.as_str());
+
+            let state = app_handle.state::<AppState>();
+            let repo = Repository::new(state.db.clone());
+            let result = match parse(url.as_str()) {
+                Ok(link) => execute(&repo, link).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(outcome) => {
+                    if let Err(e) = app_handle.emit("deep-link", &outcome) {
+                        log_error!("Failed to emit deep link event", e);
+                    }
+                }
+                Err(e) => {
+                    let _ = app_handle.emit("deep-link-error", &e);
+                }
+            }
+
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        });
+    }
+}
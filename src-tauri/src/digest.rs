@@ -0,0 +1,47 @@
+//! Digest delivery
+//!
+//! Hands a composed digest to where the `digest_delivery` setting points:
+//! saved as a note dated the last day of its range, or sent as a
+//! `digest.generated` event carrying the whole digest to the webhooks
+//! subscribed to it.
+
+use crate::db::models::NoteInput;
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::{log_info, webhooks};
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::digest::{self, Digest, DigestDelivery};
+use evorbrain_core::events::AppEvent;
+use evorbrain_core::settings;
+
+/// Delivers a digest as the `digest_delivery` setting says
+pub async fn deliver(repo: &Repository, digest: &Digest) -> AppResult<()> {
+    match settings::load(repo).await?.digest_delivery {
+        DigestDelivery::Note => {
+            let note = repo
+                .create_note(NoteInput {
+                    title: digest.title.clone(),
+                    content: digest.markdown.clone(),
+                    note_date: Some(digest.to),
+                    ..Default::default()
+                })
+                .await?;
+            log_info!("Digest", &format!("Saved '{}' as note {}", digest.title, note.id));
+        }
+        DigestDelivery::Webhook => {
+            let event = AppEvent::new("digest.generated", "digest", digest.to.to_string());
+            let delivered = webhooks::deliver_event(repo, &event, serde_json::to_value(digest)?).await?;
+            log_info!("Digest", &format!("Sent '{}' to {} webhooks", digest.title, delivered));
+        }
+    }
+    Ok(())
+}
+
+/// Composes and delivers the digest of the previous calendar week, for the
+/// `weekly_digest` job
+pub async fn deliver_weekly_digest(repo: &Repository) -> AppResult<()> {
+    let calendar = Calendar::load(repo).await?;
+    let (from, to) = digest::previous_week(&calendar);
+    let digest = digest::generate_digest(repo, from, to).await?;
+    deliver(repo, &digest).await
+}
@@ -0,0 +1,212 @@
+//! Email-to-task connector
+//!
+//! Polls each active IMAP account whose interval has elapsed, selects
+//! messages with the account's SEARCH criteria (flagged by default) and turns
+//! every not-yet-imported message into an inbox task with the email body
+//! attached as a note. Messages are de-duplicated by Message-ID and fetched
+//! with `BODY.PEEK[]`, so their read state on the server is left untouched.
+
+use crate::db::models::{EmailAccount, EmailImport};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::{log_error, log_info};
+use chrono::Utc;
+use mailparse::{MailHeaderMap, ParsedMail};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on messages imported per poll, to keep a first sync bounded
+const MAX_MESSAGES_PER_POLL: usize = 100;
+
+/// Outcome of polling one account
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmailPollSummary {
+    /// Messages matching the search criteria
+    pub matched: usize,
+    pub imported: usize,
+    /// Messages skipped because they were imported before
+    pub skipped: usize,
+}
+
+/// Polls every active account whose interval has elapsed; run by the
+/// `email_import` job
+pub async fn poll_due_accounts(repo: &Repository) -> AppResult<()> {
+    let now = Utc::now();
+    for account in repo.get_email_accounts().await?.iter().filter(|a| a.is_active && a.is_due(now)) {
+        // Errors are recorded on the account by poll_account
+        let _ = poll_account(repo, account).await;
+    }
+
+    Ok(())
+}
+
+/// Polls one account now and records the outcome on it
+pub async fn poll_account(repo: &Repository, account: &EmailAccount) -> AppResult<EmailPollSummary> {
+    let result = import_new_messages(repo, account).await;
+
+    let error = result.as_ref().err().map(|e| match &e.details {
+        Some(details) => format!("{}: {}", e.message, details),
+        None => e.message.clone(),
+    });
+    repo.record_email_poll(&account.id, error.as_deref()).await?;
+
+    if let Ok(summary) = &result {
+        if summary.imported > 0 {
+            log_info!(
+                "Imported emails as tasks",
+                &format!("{} ({} new)", account.name, summary.imported)
+            );
+        }
+    }
+
+    result
+}
+
+async fn import_new_messages(repo: &Repository, account: &EmailAccount) -> AppResult<EmailPollSummary> {
+    // Only look at headers first, so already-imported messages are never downloaded
+    let account_for_headers = account.clone();
+    let headers = tauri::async_runtime::spawn_blocking(move || fetch_message_ids(&account_for_headers))
+        .await
+        .map_err(|e| AppError::new(ErrorCode::InternalError, "Email poll task failed").with_details(e.to_string()))??;
+
+    let mut summary = EmailPollSummary {
+        matched: headers.len(),
+        ..Default::default()
+    };
+
+    let mut new_uids = Vec::new();
+    for (uid, message_id) in headers {
+        if repo.is_email_imported(&account.id, &message_id).await? {
+            summary.skipped += 1;
+        } else if new_uids.len() < MAX_MESSAGES_PER_POLL {
+            new_uids.push(uid);
+        }
+    }
+    if new_uids.is_empty() {
+        return Ok(summary);
+    }
+
+    let account_for_bodies = account.clone();
+    let emails = tauri::async_runtime::spawn_blocking(move || fetch_messages(&account_for_bodies, &new_uids))
+        .await
+        .map_err(|e| AppError::new(ErrorCode::InternalError, "Email poll task failed").with_details(e.to_string()))??;
+
+    for email in emails {
+        match repo.import_email_as_task(&account.id, email).await? {
+            Some(_) => summary.imported += 1,
+            None => summary.skipped += 1,
+        }
+    }
+
+    Ok(summary)
+}
+
+type ImapSession = imap::Session<native_tls::TlsStream<std::net::TcpStream>>;
+
+fn imap_error(error: impl std::fmt::Display) -> AppError {
+    AppError::new(ErrorCode::IoError, "IMAP request failed").with_details(error.to_string())
+}
+
+fn open_session(account: &EmailAccount) -> AppResult<ImapSession> {
+    let port = u16::try_from(account.port)
+        .map_err(|_| AppError::validation_error("port", "must be between 1 and 65535"))?;
+    let tls = native_tls::TlsConnector::builder().build().map_err(imap_error)?;
+    let client = imap::connect((account.host.as_str(), port), &account.host, &tls).map_err(imap_error)?;
+
+    let mut session = client
+        .login(&account.username, &account.password)
+        .map_err(|(e, _)| imap_error(e))?;
+    session.select(&account.folder).map_err(imap_error)?;
+    Ok(session)
+}
+
+/// Returns (UID, Message-ID) for every message matching the search criteria
+fn fetch_message_ids(account: &EmailAccount) -> AppResult<Vec<(u32, String)>> {
+    let mut session = open_session(account)?;
+
+    let mut uids: Vec<u32> = session
+        .uid_search(&account.search_query)
+        .map_err(imap_error)?
+        .into_iter()
+        .collect();
+    uids.sort_unstable();
+
+    let mut results = Vec::new();
+    if !uids.is_empty() {
+        let fetches = session
+            .uid_fetch(uid_set(&uids), "RFC822.HEADER")
+            .map_err(imap_error)?;
+
+        for fetch in fetches.iter() {
+            let (Some(uid), Some(header)) = (fetch.uid, fetch.header()) else {
+                continue;
+            };
+            let message_id = mailparse::parse_headers(header)
+                .ok()
+                .and_then(|(headers, _)| headers.get_first_value("Message-ID"))
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                // Messages without a Message-ID are keyed by folder and UID
+                .unwrap_or_else(|| format!("{}:{}", account.folder, uid));
+            results.push((uid, message_id));
+        }
+    }
+
+    let _ = session.logout();
+    Ok(results)
+}
+
+/// Downloads and parses the given messages without marking them as read
+fn fetch_messages(account: &EmailAccount, uids: &[u32]) -> AppResult<Vec<EmailImport>> {
+    let mut session = open_session(account)?;
+    let fetches = session.uid_fetch(uid_set(uids), "BODY.PEEK[]").map_err(imap_error)?;
+
+    let mut emails = Vec::new();
+    for fetch in fetches.iter() {
+        let (Some(uid), Some(raw)) = (fetch.uid, fetch.body()) else {
+            continue;
+        };
+        match mailparse::parse_mail(raw) {
+            Ok(parsed) => emails.push(to_email_import(account, uid, &parsed)),
+            Err(e) => log_error!("Failed to parse email", e),
+        }
+    }
+
+    let _ = session.logout();
+    Ok(emails)
+}
+
+fn to_email_import(account: &EmailAccount, uid: u32, parsed: &ParsedMail) -> EmailImport {
+    let headers = &parsed.headers;
+    let subject = headers
+        .get_first_value("Subject")
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "(no subject)".to_string());
+
+    EmailImport {
+        message_id: headers
+            .get_first_value("Message-ID")
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| format!("{}:{}", account.folder, uid)),
+        subject,
+        from: headers.get_first_value("From"),
+        body: plain_text_body(parsed).unwrap_or_default(),
+    }
+}
+
+/// Finds the first text/plain part, falling back to the top-level body
+fn plain_text_body(parsed: &ParsedMail) -> Option<String> {
+    if parsed.ctype.mimetype == "text/plain" {
+        return parsed.get_body().ok();
+    }
+    parsed
+        .subparts
+        .iter()
+        .find_map(plain_text_body)
+        .or_else(|| parsed.subparts.is_empty().then(|| parsed.get_body().ok()).flatten())
+}
+
+fn uid_set(uids: &[u32]) -> String {
+    uids.iter().map(u32::to_string).collect::<Vec<_>>().join(",")
+}
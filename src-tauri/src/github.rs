@@ -0,0 +1,212 @@
+//! GitHub issue and pull request sync
+//!
+//! Tasks can be linked to GitHub issues/PRs through `external_links` rows with
+//! provider `github`. The `github_sync` job periodically pulls each linked
+//! item's title and state, and completes the task when the item closes if the
+//! link asks for it. Requests are unauthenticated unless the `github_token`
+//! setting (or the `EVORBRAIN_GITHUB_TOKEN`/`GITHUB_TOKEN` environment
+//! variable) is set, which is needed for private repositories.
+
+use crate::db::models::ExternalLink;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::log_info;
+use evorbrain_core::settings;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+pub const PROVIDER: &str = "github";
+
+const API_BASE: &str = "https://api.github.com";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// An issue or pull request reference parsed from a github.com URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHubRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+impl GitHubRef {
+    /// Short form used as the link's external ID, e.g. `owner/repo#123`
+    pub fn external_id(&self) -> String {
+        format!("{}/{}#{}", self.owner, self.repo, self.number)
+    }
+}
+
+/// Parses `https://github.com/<owner>/<repo>/(issues|pull)/<number>`
+pub fn parse_url(raw: &str) -> AppResult<GitHubRef> {
+    let invalid = || {
+        AppError::validation_error("url", "must be a github.com issue or pull request URL")
+    };
+
+    let url = Url::parse(raw.trim()).map_err(|_| invalid())?;
+    if url.scheme() != "https" || url.host_str() != Some("github.com") {
+        return Err(invalid());
+    }
+
+    let segments: Vec<&str> = url.path_segments().map(|s| s.collect()).unwrap_or_default();
+    match segments.as_slice() {
+        [owner, repo, "issues" | "pull", number, ..] if !owner.is_empty() && !repo.is_empty() => {
+            Ok(GitHubRef {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                number: number.parse().map_err(|_| invalid())?,
+            })
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Title and state of a remote issue or pull request
+#[derive(Debug, Clone)]
+pub struct RemoteItem {
+    pub title: String,
+    /// `open`, `closed`, or `merged` for merged pull requests
+    pub state: String,
+}
+
+#[derive(Deserialize)]
+struct IssueResponse {
+    title: String,
+    state: String,
+    pull_request: Option<PullRequestInfo>,
+}
+
+#[derive(Deserialize)]
+struct PullRequestInfo {
+    merged_at: Option<String>,
+}
+
+/// HTTP client with the timeout and user agent GitHub requires
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("EvorBrain/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Token from settings, falling back to the environment
+async fn api_token(repo: &Repository) -> AppResult<Option<String>> {
+    let token = settings::load(repo)
+        .await?
+        .github_token
+        .or_else(|| std::env::var("EVORBRAIN_GITHUB_TOKEN").ok())
+        .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+        .filter(|token| !token.is_empty());
+    Ok(token)
+}
+
+fn request_error(error: impl std::fmt::Display) -> AppError {
+    AppError::new(ErrorCode::IoError, "GitHub request failed").with_details(error.to_string())
+}
+
+/// Fetches an issue or pull request (the issues API serves both)
+pub async fn fetch_item(client: &reqwest::Client, token: Option<&str>, item: &GitHubRef) -> AppResult<RemoteItem> {
+    let url = format!("{}/repos/{}/{}/issues/{}", API_BASE, item.owner, item.repo, item.number);
+    let mut request = client
+        .get(&url)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await.map_err(request_error)?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(AppError::new(
+            ErrorCode::NotFound,
+            format!("GitHub item {} not found or not accessible", item.external_id()),
+        ));
+    }
+    if !status.is_success() {
+        return Err(request_error(format!("GitHub responded with {}", status)));
+    }
+
+    let issue: IssueResponse = response.json().await.map_err(request_error)?;
+    let merged = issue.pull_request.and_then(|pr| pr.merged_at).is_some();
+
+    Ok(RemoteItem {
+        title: issue.title,
+        state: if merged { "merged".to_string() } else { issue.state },
+    })
+}
+
+/// Outcome of syncing all GitHub links
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitHubSyncSummary {
+    pub synced: usize,
+    pub failed: usize,
+    /// Tasks completed because their linked item closed
+    pub tasks_completed: usize,
+}
+
+/// Syncs one link; returns true if its task was completed as a result
+pub async fn sync_link(repo: &Repository, client: &reqwest::Client, link: &ExternalLink) -> AppResult<bool> {
+    let token = api_token(repo).await?;
+    let result = match parse_url(&link.url) {
+        Ok(item) => fetch_item(client, token.as_deref(), &item).await,
+        Err(e) => Err(e),
+    };
+
+    let remote = match result {
+        Ok(remote) => remote,
+        Err(e) => {
+            repo.record_external_link_sync(&link.id, None, None, Some(&e.message)).await?;
+            return Err(e);
+        }
+    };
+
+    repo.record_external_link_sync(&link.id, Some(&remote.title), Some(&remote.state), None)
+        .await?;
+
+    let closed_now = remote.state != "open" && link.state.as_deref() != Some(remote.state.as_str());
+    if !(closed_now && link.complete_on_close && link.entity_type == "task") {
+        return Ok(false);
+    }
+
+    let task = repo.get_task(&link.entity_id).await?;
+    if task.is_completed() || task.is_archived() {
+        return Ok(false);
+    }
+
+    // A task waiting for open tasks can't be completed, closed item or not
+    let blockers = repo.get_open_blockers(&task.id).await?;
+    if !blockers.is_empty() {
+        log_info!(
+            "Not completing task whose GitHub item closed, it waits for open tasks",
+            &format!("{} ({})", task.title, link.url)
+        );
+        return Ok(false);
+    }
+
+    repo.complete_task(&task.id).await?;
+    log_info!(
+        "Completed task because its GitHub item closed",
+        &format!("{} ({})", task.title, link.url)
+    );
+    Ok(true)
+}
+
+/// Syncs every GitHub link, oldest sync first
+pub async fn sync_all(repo: &Repository) -> AppResult<GitHubSyncSummary> {
+    let client = http_client();
+    let mut summary = GitHubSyncSummary::default();
+
+    for link in repo.get_external_links_by_provider(PROVIDER).await? {
+        match sync_link(repo, &client, &link).await {
+            Ok(completed) => {
+                summary.synced += 1;
+                if completed {
+                    summary.tasks_completed += 1;
+                }
+            }
+            Err(_) => summary.failed += 1,
+        }
+    }
+
+    Ok(summary)
+}
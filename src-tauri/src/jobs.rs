@@ -0,0 +1,132 @@
+//! Job scheduler
+//!
+//! Periodic background work is defined by rows in the `jobs` table, one per
+//! job type, each with a cron schedule and an enabled flag. Once a minute the
+//! scheduler runs every enabled job whose next scheduled time has passed, one
+//! after another, and records when it started, how long it took and its
+//! error. A job that came up several times while the app was closed runs once
+//! on start. `run_job_now` goes through the same `run_job`, so a job never
+//! runs twice at the same time.
+
+use crate::attachments::AttachmentStore;
+use crate::db::models::{Job, JobType};
+use crate::db::repository::Repository;
+use crate::db::write_queue::WriteQueue;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::read_only::ReadOnlyMode;
+use crate::{attachment_text, digest, email_import, github, log_error, retention};
+use chrono::Utc;
+use evorbrain_core::calendar::Calendar;
+use evorbrain_core::{settings, stats_history};
+use sqlx::SqlitePool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often due jobs are looked for, the granularity of a cron schedule
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+/// Jobs that are running, started by the scheduler or by `run_job_now`
+static RUNNING: Mutex<Vec<JobType>> = Mutex::new(Vec::new());
+
+/// Marks a job as running until dropped
+struct RunningJob(JobType);
+
+impl RunningJob {
+    fn start(job_type: JobType) -> AppResult<Self> {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        if running.contains(&job_type) {
+            return Err(AppError::new(ErrorCode::AlreadyExists, "Job is already running")
+                .with_details(job_type.to_string()));
+        }
+        running.push(job_type);
+        Ok(Self(job_type))
+    }
+}
+
+impl Drop for RunningJob {
+    fn drop(&mut self) {
+        RUNNING.lock().unwrap_or_else(|e| e.into_inner()).retain(|job_type| *job_type != self.0);
+    }
+}
+
+/// Every job with its next run time; disabled jobs have none
+pub async fn list_jobs(repo: &Repository) -> AppResult<Vec<Job>> {
+    let timezone = Calendar::from_settings(&settings::load(repo).await?).timezone();
+    let mut jobs = repo.get_jobs().await?;
+    for job in jobs.iter_mut().filter(|job| job.enabled) {
+        job.next_run_at = job.next_run(timezone);
+    }
+    Ok(jobs)
+}
+
+/// Runs a job now, whether or not it is enabled or due, and records the outcome
+///
+/// Archive retention deletes in bulk, so it waits its turn in the write
+/// queue. An error of the job itself is stored as its `last_error` rather
+/// than returned; the error returned means the job couldn't be started.
+pub async fn run_job(
+    repo: &Repository,
+    write_queue: &WriteQueue,
+    attachments: &AttachmentStore,
+    job_type: JobType,
+) -> AppResult<Job> {
+    let _running = RunningJob::start(job_type)?;
+    let started_at = Utc::now();
+    let clock = Instant::now();
+
+    let result = match job_type {
+        JobType::ArchiveRetention => {
+            write_queue.run("archive_retention", |_| retention::apply_retention(repo)).await
+        }
+        JobType::EmailImport => email_import::poll_due_accounts(repo).await,
+        JobType::GithubSync => github::sync_all(repo).await.map(|_| ()),
+        JobType::WeeklyDigest => digest::deliver_weekly_digest(repo).await,
+        JobType::StatsSnapshot => stats_history::record_snapshot(repo).await.map(|_| ()),
+        JobType::AttachmentText => attachment_text::extract_pending(repo, attachments).await.map(|_| ()),
+    };
+
+    let error = result.err().map(|e| {
+        log_error!("Job failed", job_type, e);
+        match &e.details {
+            Some(details) => format!("{}: {}", e.message, details),
+            None => e.message.clone(),
+        }
+    });
+    let duration_ms = i64::try_from(clock.elapsed().as_millis()).unwrap_or(i64::MAX);
+    repo.record_job_run(job_type, started_at, duration_ms, error.as_deref()).await
+}
+
+/// Starts the background task that runs due jobs; none are run while
+/// read-only mode is on
+pub fn spawn_scheduler(
+    db: Arc<SqlitePool>,
+    write_queue: WriteQueue,
+    read_only: Arc<ReadOnlyMode>,
+    attachments: Arc<AttachmentStore>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+
+        loop {
+            interval.tick().await;
+            if read_only.is_enabled() {
+                continue;
+            }
+
+            let jobs = match list_jobs(&repo).await {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    log_error!("Failed to load jobs", e);
+                    continue;
+                }
+            };
+
+            let now = Utc::now();
+            for job in jobs.iter().filter(|job| job.next_run_at.is_some_and(|at| at <= now)) {
+                // A run started with run_job_now is simply not repeated
+                let _ = run_job(&repo, &write_queue, &attachments, job.job_type).await;
+            }
+        }
+    });
+}
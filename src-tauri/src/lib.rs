@@ -1,14 +1,69 @@
-mod db;
 mod commands;
-mod error;
-mod logger;
+mod achievements;
+mod app_lock;
+mod attachment_text;
+mod attachments;
+mod auto_complete;
+mod command_registry;
+mod confirmation;
+mod api_server;
+mod badges;
+mod deep_link;
+mod digest;
+mod email_import;
+mod github;
+mod jobs;
+mod script_hooks;
+mod settings_transfer;
+mod link_metadata;
+mod mentions;
+mod note_encryption;
+mod project_bundle;
+mod rate_limit;
+mod read_only;
+mod retention;
+mod startup;
+mod streaming;
+mod switcher;
+mod time_tracking;
+mod webhooks;
 
+use evorbrain_core::events::{self, EventBus};
+use evorbrain_core::settings;
+use evorbrain_core::switcher::SwitcherIndex;
+use evorbrain_core::{db, error, logger};
+use evorbrain_core::db::write_queue::WriteQueue;
+use evorbrain_core::{log_error, log_info, log_warn};
 use sqlx::SqlitePool;
 use std::sync::Arc;
-use tauri::Manager;
+use std::time::Duration;
+use tauri::webview::PageLoadEvent;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// How often the change log is polled for new events
+const CHANGE_FEED_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct AppState {
     pub db: Arc<SqlitePool>,
+    /// Domain events derived from the change log
+    pub events: EventBus,
+    /// Running local REST API server, if the user has enabled it
+    pub api_server: tokio::sync::Mutex<Option<api_server::ApiServerHandle>>,
+    /// Passphrase lock state, shared with the command guard
+    pub lock: Arc<app_lock::AppLock>,
+    /// Blocks all changes while on, shared with the command guard
+    pub read_only: Arc<read_only::ReadOnlyMode>,
+    /// Content-addressed storage for attachment files
+    pub attachments: Arc<attachments::AttachmentStore>,
+    /// Outstanding confirmation tokens for destructive commands
+    pub confirmations: confirmation::Confirmations,
+    /// Startup timings for `get_startup_report`
+    pub startup: Arc<startup::Startup>,
+    /// Runs imports, batches and other bulk writes one at a time
+    pub write_queue: WriteQueue,
+    /// Titles `quick_switch` searches, kept current from the change feed
+    pub switcher: Arc<SwitcherIndex>,
 }
 
 /// Simple greeting command for testing
@@ -40,73 +95,366 @@ async fn test_database(state: tauri::State<'_, AppState>) -> Result<String, Stri
     Ok(format!("Database is working! Number of tables: {}", result.0))
 }
 
+/// Resolves the database file inside the platform app data directory
+fn get_database_path(app_handle: &tauri::AppHandle) -> anyhow::Result<String> {
+    let app_dir = app_handle.path().app_data_dir()?;
+    db::connection::database_path_in(&app_dir)
+}
+
+/// Re-emits every bus event to the frontend as an `app-event`
+fn forward_events_to_frontend(app_handle: tauri::AppHandle, bus: &EventBus) {
+    let mut receiver = bus.subscribe();
+    
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app_handle.emit("app-event", &event) {
+                        log_error!("Failed to emit app event", e);
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Re-emits bulk write queue updates to the frontend as `bulk-write-progress`
+fn forward_bulk_progress_to_frontend(app_handle: tauri::AppHandle, write_queue: &WriteQueue) {
+    let mut receiver = write_queue.subscribe();
+    
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(progress) => {
+                    if let Err(e) = app_handle.emit("bulk-write-progress", &progress) {
+                        log_error!("Failed to emit bulk write progress", e);
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let startup = Arc::new(startup::Startup::default());
+    let setup_startup = startup.clone();
+    let lock = Arc::new(app_lock::AppLock::default());
+    let setup_lock = lock.clone();
+    let read_only = Arc::new(read_only::ReadOnlyMode::new(read_only::requested_at_startup()));
+    let setup_read_only = read_only.clone();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .plugin(tauri_plugin_deep_link::init())
+        .setup(move |app| {
             let app_handle = app.handle().clone();
             
             // Initialize logger
-            logger::init_logger(&app_handle)?;
+            let log_dir = app_handle.path().app_log_dir()?;
+            setup_startup.time("logger", || logger::init_logger(&log_dir))?;
             log_info!("EvorBrain application starting up");
             
-            let db_path = db::connection::get_database_path(&app_handle)?;
+            let db_path = get_database_path(&app_handle)?;
             log_info!("Database path", &db_path);
+            let attachment_store = Arc::new(attachments::AttachmentStore::new(
+                app_handle.path().app_data_dir()?.join("attachments"),
+            ));
             
             // Use Tauri's async runtime instead of creating a new one
             tauri::async_runtime::block_on(async move {
                 log_info!("Initializing database connection");
-                let db_pool = db::init_database(&db_path).await?;
+                let pool = setup_startup.time_async("database_pool", db::open_pool(&db_path)).await?;
+                if setup_read_only.is_enabled() {
+                    // The queries below expect the current schema, which an
+                    // older database only gets by being migrated
+                    if db::needs_migration(&pool).await? {
+                        return Err(error::AppError::new(
+                            error::ErrorCode::DatabaseMigration,
+                            "The database needs upgrading; open it normally once before using read-only mode",
+                        )
+                        .into());
+                    }
+                    log_warn!("Started in read-only mode");
+                } else {
+                    setup_startup.time_async("migrations", db::migrate(&pool)).await?;
+                }
+                let db_pool = Arc::new(pool);
+
+                let repo = db::repository::Repository::new(db_pool.clone());
+                let (app_settings, has_passphrase) = setup_startup
+                    .time_async("first_queries", async {
+                        let app_settings = settings::load(&repo).await?;
+                        let has_passphrase = settings::get_secret(&repo, settings::Secret::AppLockHash).await?.is_some();
+                        Ok::<_, error::AppError>((app_settings, has_passphrase))
+                    })
+                    .await?;
+                commands::settings::apply_log_level(app_settings.log_level);
+
+                // Start locked whenever a passphrase is set
+                if has_passphrase {
+                    setup_lock.set_enabled(true);
+                    setup_lock.lock();
+                }
+                
+                let event_bus = EventBus::new();
+                let write_queue = WriteQueue::default();
+                let switcher_index = Arc::new(SwitcherIndex::default());
+                tauri::async_runtime::spawn(events::run_change_feed(
+                    db_pool.clone(),
+                    event_bus.clone(),
+                    CHANGE_FEED_INTERVAL,
+                ));
+                webhooks::spawn_dispatcher(db_pool.clone(), &event_bus);
+                script_hooks::spawn_runner(db_pool.clone(), setup_read_only.clone(), &event_bus);
+                badges::spawn_badge_watcher(app_handle.clone(), db_pool.clone(), &event_bus);
+                achievements::spawn_achievement_watcher(
+                    db_pool.clone(),
+                    setup_read_only.clone(),
+                    event_bus.clone(),
+                );
+                switcher::spawn_switcher_indexer(db_pool.clone(), switcher_index.clone(), &event_bus);
+                auto_complete::spawn_completion_watcher(
+                    db_pool.clone(),
+                    setup_read_only.clone(),
+                    event_bus.clone(),
+                );
+                mentions::spawn_mention_linker(db_pool.clone(), setup_read_only.clone(), &event_bus);
+                app_lock::spawn_idle_timer(db_pool.clone(), setup_lock.clone(), event_bus.clone());
+                time_tracking::spawn_idle_watcher(
+                    db_pool.clone(),
+                    setup_lock.clone(),
+                    setup_read_only.clone(),
+                    event_bus.clone(),
+                );
+                forward_events_to_frontend(app_handle.clone(), &event_bus);
+                forward_bulk_progress_to_frontend(app_handle.clone(), &write_queue);
+                // The foreign key check, planner statistics and the job
+                // scheduler wait until the window is ready
+                startup::spawn_deferred_work(
+                    db_pool.clone(),
+                    write_queue.clone(),
+                    setup_read_only.clone(),
+                    setup_startup.clone(),
+                    attachment_store.clone(),
+                );
                 
                 app_handle.manage(AppState {
-                    db: Arc::new(db_pool),
+                    db: db_pool,
+                    events: event_bus,
+                    api_server: tokio::sync::Mutex::new(None),
+                    lock: setup_lock,
+                    read_only: setup_read_only,
+                    attachments: attachment_store,
+                    confirmations: confirmation::Confirmations::default(),
+                    startup: setup_startup.clone(),
+                    write_queue,
+                    switcher: switcher_index,
                 });
                 
+                setup_startup.setup_finished();
                 log_info!("Application setup complete");
-                Ok(())
-            })
+                Ok::<(), Box<dyn std::error::Error>>(())
+            })?;
+            
+            // Development builds and Linux/Windows installs register the scheme at runtime
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                log_error!("Failed to register deep link scheme", e);
+            }
+            
+            let deep_link_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                deep_link::handle_urls(&deep_link_handle, event.urls());
+            });
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                deep_link::handle_urls(app.handle(), urls);
+            }
+            
+            Ok(())
+        })
+        .on_page_load(move |_webview, payload| {
+            if matches!(payload.event(), PageLoadEvent::Finished) {
+                startup.window_ready();
+            }
         })
-        .invoke_handler(tauri::generate_handler![
+        // Keep command_registry::COMMANDS in sync with this list
+        .invoke_handler(app_lock::guard(lock, read_only::guard(read_only, rate_limit::guard(tauri::generate_handler![
             greet, 
             test_database,
+            commands::list_commands,
+            // Settings commands
+            commands::get_settings,
+            commands::update_settings,
+            commands::set_active_context,
+            commands::get_week,
+            commands::export_settings,
+            commands::import_settings,
+            // App lock commands
+            commands::get_app_lock_status,
+            commands::set_app_lock,
+            commands::unlock,
+            commands::lock,
+            // Read-only mode commands
+            commands::get_read_only_mode,
+            commands::set_read_only_mode,
+            // Onboarding commands
+            commands::create_starter_template,
+            // Workflow commands
+            commands::get_workflow,
+            commands::set_workflow,
+            commands::set_task_status,
+            // View preference commands
+            commands::get_view_preferences,
+            commands::set_view_preferences,
+            // Scheduling commands
+            commands::add_working_days,
+            commands::defer_task_to_next_working_day,
+            commands::set_task_estimate,
+            commands::set_task_actual_minutes,
+            commands::set_task_recurrence,
+            commands::get_upcoming_occurrences,
+            commands::complete_recurring_task,
+            commands::set_recurrence_exceptions,
+            commands::suggest_due_date,
+            // My Day commands
+            commands::get_my_day,
+            commands::add_to_my_day,
+            commands::remove_from_my_day,
+            commands::get_day_capacity,
+            commands::get_day_plan,
+            commands::set_planned_minutes,
+            commands::reorder_day_plan,
+            // Context commands
+            commands::get_contexts,
+            commands::get_task_contexts,
+            commands::set_task_contexts,
+            commands::get_tasks_by_context,
+            commands::delete_context,
+            // People commands
+            commands::get_people,
+            commands::create_person,
+            commands::update_person,
+            commands::delete_person,
+            commands::delegate_task,
+            commands::undelegate_task,
+            commands::get_task_delegation,
+            commands::get_waiting_for,
+            // Dependency and snooze commands
+            commands::add_task_dependency,
+            commands::remove_task_dependency,
+            commands::get_task_dependencies,
+            commands::get_blocked_tasks,
+            commands::snooze_task,
+            commands::unsnooze_task,
+            // Attachment commands
+            commands::add_attachment,
+            commands::get_attachments,
+            commands::get_attachment_path,
+            commands::get_attachment_thumbnail,
+            commands::delete_attachment,
+            commands::scan_orphan_attachments,
+            commands::get_attachment_usage,
+            commands::search_attachments,
+            // Achievement commands
+            commands::get_achievements,
+            // Review commands
+            commands::get_stale_projects,
+            commands::mark_reviewed,
+            commands::set_project_review_interval,
+            commands::set_note_review,
+            commands::mark_note_reviewed,
+            commands::get_notes_due_for_review,
+            // Someday/maybe commands
+            commands::park_item,
+            commands::reactivate_item,
+            commands::get_someday_maybe,
+            // Time tracking commands
+            commands::start_timer,
+            commands::stop_timer,
+            commands::get_running_timer,
+            commands::get_time_entries,
+            commands::get_unreconciled_time_entries,
+            commands::reconcile_time_entry,
+            commands::get_focus_by_goal,
+            commands::get_focus_by_life_area,
+            commands::get_time_budget_report,
+            // Holiday commands
+            commands::get_holiday_sets,
+            commands::create_holiday_set,
+            commands::import_holidays,
+            commands::add_holiday,
+            commands::remove_holiday,
+            commands::set_holiday_set_enabled,
+            commands::delete_holiday_set,
+            commands::get_holidays,
+            // Velocity commands
+            commands::get_project_velocity,
+            commands::get_critical_path,
+            commands::get_goals_at_risk,
+            commands::get_effort_rollup,
+            commands::get_task_aging_report,
+            commands::get_chronic_postponements,
+            // Job commands
+            commands::get_jobs,
+            commands::set_job_enabled,
+            commands::set_job_schedule,
+            commands::run_job_now,
+            commands::generate_digest,
             // Migration commands
-            db::migrations::commands::get_migration_status,
-            db::migrations::commands::run_migrations,
-            db::migrations::commands::rollback_migration,
-            db::migrations::commands::reset_database,
+            commands::get_migration_status,
+            commands::run_migrations,
+            commands::rollback_migration,
+            commands::reset_database,
             // Life Area commands
             commands::create_life_area,
             commands::get_life_areas,
             commands::get_life_area,
             commands::update_life_area,
+            commands::set_life_area_context,
+            commands::set_life_area_budget,
+            commands::get_delete_impact,
             commands::delete_life_area,
             commands::restore_life_area,
+            commands::get_archive_summary,
             // Goal commands
             commands::create_goal,
             commands::get_goals,
             commands::get_goals_by_life_area,
+            commands::reorder_goals,
             commands::get_goal,
+            commands::get_goal_breakdown,
             commands::update_goal,
             commands::complete_goal,
             commands::uncomplete_goal,
+            commands::set_goal_progress,
             commands::delete_goal,
             commands::restore_goal,
             // Project commands
             commands::create_project,
             commands::get_projects,
             commands::get_projects_by_goal,
+            commands::reorder_projects,
             commands::get_project,
             commands::update_project,
             commands::update_project_status,
+            commands::set_project_progress,
             commands::delete_project,
             commands::restore_project,
+            commands::merge_projects,
+            commands::export_project_bundle,
+            commands::import_project_bundle,
             // Task commands
             commands::create_task,
             commands::create_task_with_subtasks,
             commands::get_tasks,
             commands::get_tasks_by_project,
             commands::get_subtasks,
+            commands::get_task_tree,
             commands::get_task,
             commands::update_task,
             commands::complete_task,
@@ -114,9 +462,11 @@ pub fn run() {
             commands::delete_task,
             commands::restore_task,
             commands::get_todays_tasks,
+            commands::get_todays_exclusions,
             // Note commands
             commands::create_note,
             commands::get_notes,
+            commands::stream_notes,
             commands::get_notes_by_task,
             commands::get_notes_by_project,
             commands::get_notes_by_goal,
@@ -126,16 +476,96 @@ pub fn run() {
             commands::delete_note,
             commands::restore_note,
             commands::search_notes,
+            commands::get_note_tags,
+            commands::convert_note_to_task,
+            commands::extract_tasks_from_note,
+            commands::process_meeting_note,
+            commands::export_note_graph,
+            commands::get_note_mentions,
+            commands::rescan_note_mentions,
+            // Quick capture commands
+            commands::quick_add,
+            // Deep link commands
+            commands::open_deep_link,
+            // Badge commands
+            commands::get_badge_counts,
+            commands::get_sidebar_counts,
+            // Dashboard commands
+            commands::get_dashboard,
+            commands::pin_entity,
+            commands::unpin_entity,
+            commands::reorder_pins,
+            commands::get_stats_history,
+            // Quick switcher commands
+            commands::quick_switch,
             // Logging commands
             commands::get_recent_logs,
             commands::set_log_level,
+            commands::get_startup_report,
+            commands::v2_list_life_areas,
+            commands::v2_get_life_area,
+            commands::v2_list_goals,
+            commands::v2_get_goal,
+            commands::v2_list_projects,
+            commands::v2_get_project,
+            commands::v2_list_tasks,
+            commands::v2_get_task,
+            commands::v2_list_notes,
+            commands::v2_get_note,
             // Repository commands
             commands::check_repository_health,
             commands::batch_delete,
+            commands::bulk_tag,
+            commands::execute_batch,
+            commands::get_write_queue,
             commands::get_database_stats,
+            commands::get_storage_advice,
+            commands::archive_completed_tasks,
+            commands::scan_data_issues,
+            commands::repair_data_issues,
+            commands::request_destructive_confirmation,
             commands::cleanup_database,
-            commands::export_all_data
-        ])
+            commands::secure_delete,
+            commands::export_all_data,
+            commands::stream_export_all_data,
+            commands::import_all_data,
+            // Change log commands
+            commands::get_changes_since,
+            commands::get_audit_log,
+            // Local API server commands
+            commands::start_api_server,
+            commands::stop_api_server,
+            commands::get_api_server_status,
+            // Webhook commands
+            commands::get_webhooks,
+            commands::create_webhook,
+            commands::update_webhook,
+            commands::delete_webhook,
+            commands::test_webhook,
+            // Script hook commands
+            commands::get_script_hooks,
+            commands::create_script_hook,
+            commands::update_script_hook,
+            commands::delete_script_hook,
+            commands::test_script_hook,
+            // Email import commands
+            commands::get_email_accounts,
+            commands::create_email_account,
+            commands::update_email_account,
+            commands::delete_email_account,
+            commands::poll_email_account,
+            // External link commands
+            commands::link_github_issue,
+            commands::get_external_links,
+            commands::delete_external_link,
+            commands::sync_github_links,
+            commands::add_web_link,
+            commands::refresh_link_metadata,
+            commands::get_task_references,
+            // Import commands
+            commands::preview_jira_import,
+            commands::import_jira
+        ]))))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
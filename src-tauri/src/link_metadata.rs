@@ -0,0 +1,309 @@
+//! Page metadata for web links
+//!
+//! When a web link is saved its page is fetched once to pick up the title,
+//! description and favicon. Pages and icons are read with a timeout and a
+//! byte limit, so a slow or huge response can never stall the app; only the
+//! start of the page is parsed, which is where `<head>` lives.
+
+use crate::db::models::ExternalLink;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use base64::Engine;
+use evorbrain_core::validation::MAX_TITLE_LENGTH;
+use std::time::Duration;
+use url::Url;
+
+pub const PROVIDER: &str = "web";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REDIRECTS: usize = 5;
+/// Only the start of a page is read; metadata lives in `<head>`
+const MAX_PAGE_BYTES: usize = 512 * 1024;
+/// Favicons above this size are not stored
+const MAX_FAVICON_BYTES: usize = 64 * 1024;
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+/// Metadata extracted from a page
+#[derive(Debug, Default, Clone)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Favicon as a `data:` URI
+    pub favicon: Option<String>,
+}
+
+fn request_error(error: impl std::fmt::Display) -> AppError {
+    AppError::new(ErrorCode::IoError, "Fetching link metadata failed").with_details(error.to_string())
+}
+
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+        .user_agent(concat!("EvorBrain/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Reads a response body up to `limit` bytes; returns None if the body is
+/// larger and `truncate` is false
+async fn read_limited(mut response: reqwest::Response, limit: usize, truncate: bool) -> AppResult<Option<Vec<u8>>> {
+    if !truncate && response.content_length().is_some_and(|len| len > limit as u64) {
+        return Ok(None);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(request_error)? {
+        if body.len() + chunk.len() > limit {
+            if !truncate {
+                return Ok(None);
+            }
+            body.extend_from_slice(&chunk[..limit - body.len()]);
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(body))
+}
+
+fn content_type(response: &reqwest::Response) -> String {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or_default().trim().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Fetches a page's title, description and favicon
+pub async fn fetch(client: &reqwest::Client, url: &str) -> AppResult<PageMetadata> {
+    let response = client.get(url).send().await.map_err(request_error)?;
+    if !response.status().is_success() {
+        return Err(request_error(format!("server responded with {}", response.status())));
+    }
+
+    // Resolve relative icon links against the final URL after redirects
+    let base = response.url().clone();
+    let mime = content_type(&response);
+    if !mime.is_empty() && mime != "text/html" && mime != "application/xhtml+xml" {
+        // Not a page (PDF, image, ...): nothing to parse, but still try the site icon
+        return Ok(PageMetadata {
+            favicon: fetch_favicon(client, &base, None).await,
+            ..Default::default()
+        });
+    }
+
+    let body = read_limited(response, MAX_PAGE_BYTES, true).await?.unwrap_or_default();
+    let html = String::from_utf8_lossy(&body);
+    let head = parse_head(&html);
+
+    Ok(PageMetadata {
+        title: head.title.map(|t| t.chars().take(MAX_TITLE_LENGTH).collect()),
+        description: head
+            .description
+            .map(|d| d.chars().take(MAX_DESCRIPTION_LENGTH).collect()),
+        favicon: fetch_favicon(client, &base, head.icon_href.as_deref()).await,
+    })
+}
+
+/// Downloads the page's declared icon, falling back to `/favicon.ico`
+async fn fetch_favicon(client: &reqwest::Client, base: &Url, href: Option<&str>) -> Option<String> {
+    if let Some(data) = href.and_then(|h| h.strip_prefix("data:")) {
+        return (data.len() <= MAX_FAVICON_BYTES).then(|| format!("data:{}", data));
+    }
+
+    let icon_url = match href {
+        Some(href) => base.join(href).ok()?,
+        None => base.join("/favicon.ico").ok()?,
+    };
+    if !matches!(icon_url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let response = client.get(icon_url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let mime = content_type(&response);
+    if !mime.starts_with("image/") {
+        return None;
+    }
+
+    let bytes = read_limited(response, MAX_FAVICON_BYTES, false).await.ok()??;
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Fetches and stores metadata for a link; fetch errors are recorded on the link
+pub async fn refresh_link(repo: &Repository, client: &reqwest::Client, link: &ExternalLink) -> AppResult<ExternalLink> {
+    match fetch(client, &link.url).await {
+        Ok(meta) => {
+            repo.record_link_metadata(
+                &link.id,
+                meta.title.as_deref(),
+                meta.description.as_deref(),
+                meta.favicon.as_deref(),
+                None,
+            )
+            .await?
+        }
+        Err(e) => {
+            let error = match &e.details {
+                Some(details) => format!("{}: {}", e.message, details),
+                None => e.message.clone(),
+            };
+            repo.record_link_metadata(&link.id, None, None, None, Some(&error)).await?
+        }
+    }
+    repo.get_external_link(&link.id).await
+}
+
+#[derive(Debug, Default)]
+struct HeadMetadata {
+    title: Option<String>,
+    description: Option<String>,
+    icon_href: Option<String>,
+}
+
+/// Extracts metadata from the start of an HTML document
+///
+/// A small tag scanner rather than a full HTML parser: it only needs
+/// `<title>`, `<meta>` and `<link>` and stops at `</head>` or `<body>`.
+fn parse_head(html: &str) -> HeadMetadata {
+    let mut head = HeadMetadata::default();
+    let mut og_title = None;
+    let mut og_description = None;
+    let mut icon: Option<(u8, String)> = None;
+
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(offset) = lower[pos..].find('<') {
+        let start = pos + offset;
+        let Some(len) = lower[start..].find('>') else { break };
+        let end = start + len;
+        let tag = &html[start + 1..end];
+        let tag_lower = &lower[start + 1..end];
+        pos = end + 1;
+
+        let name = tag_lower
+            .split(char::is_whitespace)
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        match name {
+            "title" if head.title.is_none() => {
+                if let Some(close) = lower[pos..].find("</title") {
+                    head.title = clean_text(&html[pos..pos + close]);
+                    pos += close;
+                }
+            }
+            "meta" => {
+                let attrs = parse_attributes(tag);
+                let key = attrs
+                    .iter()
+                    .find(|(k, _)| k == "name" || k == "property")
+                    .map(|(_, v)| v.to_lowercase());
+                let content = attrs.iter().find(|(k, _)| k == "content").and_then(|(_, v)| clean_text(v));
+                match key.as_deref() {
+                    Some("og:title") => og_title = og_title.or(content),
+                    Some("description") => head.description = head.description.take().or(content),
+                    Some("og:description") => og_description = og_description.or(content),
+                    _ => {}
+                }
+            }
+            "link" => {
+                let attrs = parse_attributes(tag);
+                let rel = attrs.iter().find(|(k, _)| k == "rel").map(|(_, v)| v.to_lowercase());
+                let href = attrs.iter().find(|(k, _)| k == "href").map(|(_, v)| v.trim().to_string());
+                // Prefer a plain icon, then Apple touch icons
+                let rank = match rel.as_deref() {
+                    Some(rel) if rel.split_whitespace().any(|r| r == "icon") => 2,
+                    Some(rel) if rel.starts_with("apple-touch-icon") => 1,
+                    _ => 0,
+                };
+                if let Some(href) = href.filter(|h| !h.is_empty()) {
+                    if rank > icon.as_ref().map_or(0, |(r, _)| *r) {
+                        icon = Some((rank, href));
+                    }
+                }
+            }
+            "script" | "style" => {
+                // Skip raw text so markup inside scripts isn't mistaken for tags
+                if let Some(close) = lower[pos..].find(&format!("</{}", name)) {
+                    pos += close;
+                }
+            }
+            "/head" | "body" => break,
+            _ => {}
+        }
+    }
+
+    head.title = head.title.or(og_title);
+    head.description = head.description.or(og_description);
+    head.icon_href = icon.map(|(_, href)| href);
+    head
+}
+
+/// Parses `key="value"` pairs from the inside of a tag
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    // Skip the tag name
+    let mut rest = tag.trim_start();
+    rest = rest.trim_start_matches(|c: char| !c.is_whitespace());
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+        let key_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let close = inner.find(quote).unwrap_or(inner.len());
+                    value = inner[..close].to_string();
+                    rest = inner.get(close + 1..).unwrap_or_default();
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    value = after_eq[..end].to_string();
+                    rest = &after_eq[end..];
+                }
+            }
+        }
+        if key.is_empty() {
+            break;
+        }
+        attrs.push((key, value));
+    }
+    attrs
+}
+
+/// Decodes common entities, collapses whitespace and drops empty text
+fn clean_text(raw: &str) -> Option<String> {
+    let decoded = raw
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    let text = decoded.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
@@ -0,0 +1,52 @@
+//! Mention linker
+//!
+//! Records the tasks and projects a note mentions whenever the note is
+//! created, changed or restored, while the `auto_link_mentions` setting is
+//! on. Saves show up through the change feed, so notes written by the REST
+//! API or an import are linked the same way. Nothing is written while
+//! read-only mode is on.
+
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::log_error;
+use crate::read_only::ReadOnlyMode;
+use evorbrain_core::events::{AppEvent, EventBus};
+use evorbrain_core::{mentions, settings};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+async fn link(repo: &Repository, event: &AppEvent) -> AppResult<()> {
+    if !matches!(event.name.as_str(), "note.created" | "note.updated" | "note.restored") {
+        return Ok(());
+    }
+    if !settings::load(repo).await?.auto_link_mentions {
+        return Ok(());
+    }
+    mentions::link_note(repo, &event.entity_id).await?;
+    Ok(())
+}
+
+/// Starts the background task that records the mentions of saved notes
+pub fn spawn_mention_linker(db: Arc<SqlitePool>, read_only: Arc<ReadOnlyMode>, bus: &EventBus) {
+    let mut receiver = bus.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            if read_only.is_enabled() {
+                continue;
+            }
+
+            if let Err(e) = link(&repo, &event).await {
+                log_error!("Failed to link the mentions of a note", e);
+            }
+        }
+    });
+}
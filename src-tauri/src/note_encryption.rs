@@ -0,0 +1,170 @@
+//! Private note encryption
+//!
+//! Private notes store their content encrypted with ChaCha20-Poly1305 under a
+//! random note key. The note key itself is stored wrapped (encrypted) with a
+//! key derived from the app lock passphrase via Argon2id, so it only exists
+//! in memory between `unlock` and the next lock, and changing the passphrase
+//! only re-wraps the key instead of re-encrypting every note. Titles stay in
+//! plain text; search skips the content of private notes.
+
+use crate::app_lock::AppLock;
+use crate::db::models::{Note, ENCRYPTED_CONTENT_PREFIX};
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::log_warn;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use zeroize::Zeroize;
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const SALT_LENGTH: usize = 16;
+
+/// Symmetric key for private note content, wiped from memory on drop
+#[derive(Clone)]
+pub struct NoteKey([u8; KEY_LENGTH]);
+
+impl Drop for NoteKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl NoteKey {
+    pub fn generate() -> Self {
+        let mut key = [0u8; KEY_LENGTH];
+        OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.0))
+    }
+}
+
+fn crypto_error(message: &str) -> AppError {
+    AppError::new(ErrorCode::InternalError, message)
+}
+
+/// Encrypts `plaintext` as nonce followed by ciphertext
+fn seal(key: &NoteKey, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = key
+        .cipher()
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| crypto_error("Failed to encrypt note"))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn open(key: &NoteKey, sealed: &[u8]) -> AppResult<Vec<u8>> {
+    if sealed.len() < NONCE_LENGTH {
+        return Err(crypto_error("Encrypted data is truncated"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    key.cipher()
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| crypto_error("Failed to decrypt note"))
+}
+
+/// Derives the key-wrapping key from the passphrase
+fn derive_wrapping_key(passphrase: &str, salt: &[u8]) -> AppResult<NoteKey> {
+    let mut key = [0u8; KEY_LENGTH];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| crypto_error("Failed to derive key").with_details(e.to_string()))?;
+    Ok(NoteKey(key))
+}
+
+/// Encrypts the note key with the passphrase, as `<salt>$<sealed key>` in base64
+pub fn wrap_key(key: &NoteKey, passphrase: &str) -> AppResult<String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let sealed = seal(&derive_wrapping_key(passphrase, &salt)?, &key.0)?;
+    Ok(format!("{}${}", BASE64.encode(salt), BASE64.encode(sealed)))
+}
+
+/// Recovers the note key from its wrapped form
+pub fn unwrap_key(wrapped: &str, passphrase: &str) -> AppResult<NoteKey> {
+    let invalid = || crypto_error("Stored note key is invalid");
+    let (salt, sealed) = wrapped.split_once('$').ok_or_else(invalid)?;
+    let salt = BASE64.decode(salt).map_err(|_| invalid())?;
+    let sealed = BASE64.decode(sealed).map_err(|_| invalid())?;
+
+    let mut key = open(&derive_wrapping_key(passphrase, &salt)?, &sealed)?;
+    let note_key = <[u8; KEY_LENGTH]>::try_from(key.as_slice()).map(NoteKey).map_err(|_| invalid());
+    key.zeroize();
+    note_key
+}
+
+/// Encrypts note content for storage
+pub fn encrypt_content(key: &NoteKey, content: &str) -> AppResult<String> {
+    Ok(format!("{}{}", ENCRYPTED_CONTENT_PREFIX, BASE64.encode(seal(key, content.as_bytes())?)))
+}
+
+/// Decrypts stored note content; content without the prefix is returned as is
+pub fn decrypt_content(key: &NoteKey, stored: &str) -> AppResult<String> {
+    let Some(encoded) = stored.strip_prefix(ENCRYPTED_CONTENT_PREFIX) else {
+        return Ok(stored.to_string());
+    };
+    let sealed = BASE64
+        .decode(encoded)
+        .map_err(|_| crypto_error("Encrypted note content is invalid"))?;
+    String::from_utf8(open(key, &sealed)?).map_err(|_| crypto_error("Decrypted note is not valid UTF-8"))
+}
+
+/// Prepares content for saving: private content is encrypted with the
+/// in-memory note key, which requires the app lock to be set and unlocked
+pub fn seal_content(lock: &AppLock, is_private: bool, content: &str) -> AppResult<String> {
+    if !is_private {
+        return Ok(content.to_string());
+    }
+    if !lock.is_enabled() {
+        return Err(AppError::validation_error(
+            "is_private",
+            "private notes require an app lock passphrase",
+        ));
+    }
+    let key = lock
+        .note_key()
+        .ok_or_else(|| AppError::new(ErrorCode::Locked, "Unlock EvorBrain to edit private notes"))?;
+    encrypt_content(&key, content)
+}
+
+/// Decrypts a private note's content for display; without the note key the
+/// content is blanked rather than returned encrypted
+pub fn reveal(lock: &AppLock, mut note: Note) -> AppResult<Note> {
+    if note.is_private {
+        note.content = match lock.note_key() {
+            Some(key) => decrypt_content(&key, &note.content)?,
+            None => String::new(),
+        };
+    }
+    Ok(note)
+}
+
+/// `reveal` for a list of notes; a note that can't be decrypted is blanked
+/// and logged, so it doesn't take the whole list down with it
+pub fn reveal_all(lock: &AppLock, notes: Vec<Note>) -> Vec<Note> {
+    let key = lock.note_key();
+    notes
+        .into_iter()
+        .map(|mut note| {
+            if note.is_private {
+                note.content = match &key {
+                    Some(key) => decrypt_content(key, &note.content).unwrap_or_else(|e| {
+                        log_warn!(&format!("Failed to decrypt private note {}: {}", note.id, e.message));
+                        String::new()
+                    }),
+                    None => String::new(),
+                };
+            }
+            note
+        })
+        .collect()
+}
@@ -0,0 +1,243 @@
+//! Project bundles
+//!
+//! `export` writes a project with its tasks, notes, tags, task dependencies
+//! and attachments into one JSON file. The file holds everything another
+//! vault needs to recreate the project, including the attachment content in
+//! base64, so it can be imported elsewhere or handed to someone else running
+//! EvorBrain.
+//!
+//! `import` recreates the project under a goal of this vault with new IDs,
+//! so a bundle can be imported any number of times, even into the vault it
+//! came from. Tags are matched by name.
+//!
+//! Private notes stay behind: their content is encrypted with this vault's
+//! note key and couldn't be read anywhere else. Archived tasks and notes are
+//! left out as well.
+
+use crate::attachments::{quota_exceeded, AttachmentStore};
+use crate::db::models::{
+    Attachment, Note, Project, ProjectContents, ProjectImportReport, Tag, Tagging, Task, TaskDependency,
+    WorkflowStatusInput,
+};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use evorbrain_core::settings;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Format version written by `export`; bundles from newer versions are refused
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// A project and everything that belongs to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub project: Project,
+    /// Parents come before their subtasks
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub taggings: Vec<Tagging>,
+    #[serde(default)]
+    pub dependencies: Vec<TaskDependency>,
+    #[serde(default)]
+    pub attachments: Vec<BundleAttachment>,
+    /// Custom statuses of the project; empty for the default workflow
+    #[serde(default)]
+    pub workflow: Vec<WorkflowStatusInput>,
+}
+
+/// An attachment with its content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleAttachment {
+    #[serde(flatten)]
+    pub attachment: Attachment,
+    /// The file, base64-encoded
+    pub content: String,
+}
+
+/// What `export` wrote
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectBundleReport {
+    pub path: String,
+    pub tasks: usize,
+    pub notes: usize,
+    pub tags: usize,
+    pub attachments: usize,
+    /// Size of the written file
+    pub size_bytes: u64,
+    /// Private notes that were left out
+    pub private_notes: usize,
+}
+
+fn io_error(message: &str, error: std::io::Error) -> AppError {
+    AppError::new(ErrorCode::IoError, message).with_details(error.to_string())
+}
+
+/// Runs blocking file work off the async runtime
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> AppResult<T> + Send + 'static) -> AppResult<T> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::new(ErrorCode::InternalError, "Project bundle task failed").with_details(e.to_string()))?
+}
+
+/// Writes a project and everything that belongs to it to `path`
+pub async fn export(
+    repo: &Repository,
+    store: Arc<AttachmentStore>,
+    project_id: &str,
+    path: PathBuf,
+) -> AppResult<ProjectBundleReport> {
+    let contents = repo.get_project_contents(project_id).await?;
+
+    blocking(move || {
+        let mut attachments = Vec::with_capacity(contents.attachments.len());
+        for attachment in contents.attachments {
+            let stored = store.path_for(&attachment.content_hash)?;
+            let bytes = std::fs::read(&stored).map_err(|e| io_error("Failed to read attachment", e))?;
+            attachments.push(BundleAttachment {
+                content: BASE64.encode(bytes),
+                attachment,
+            });
+        }
+
+        let bundle = ProjectBundle {
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            project: contents.project,
+            tasks: contents.tasks,
+            notes: contents.notes,
+            tags: contents.tags,
+            taggings: contents.taggings,
+            dependencies: contents.dependencies,
+            attachments,
+            workflow: contents.workflow,
+        };
+        let json = serde_json::to_vec(&bundle).map_err(|e| {
+            AppError::new(ErrorCode::InternalError, "Failed to serialize project bundle").with_details(e.to_string())
+        })?;
+        std::fs::write(&path, &json).map_err(|e| io_error("Failed to write project bundle", e))?;
+
+        Ok(ProjectBundleReport {
+            path: path.display().to_string(),
+            tasks: bundle.tasks.len(),
+            notes: bundle.notes.len(),
+            tags: bundle.tags.len(),
+            attachments: bundle.attachments.len(),
+            size_bytes: json.len() as u64,
+            private_notes: contents.private_notes,
+        })
+    })
+    .await
+}
+
+/// Imports the bundle at `path` as a new project under `goal_id`
+///
+/// The attachment content is stored first; if the import fails or the
+/// `attachment_quota_mb` setting would be exceeded, what was newly stored is
+/// removed again and nothing else changes.
+pub async fn import(
+    repo: &Repository,
+    store: Arc<AttachmentStore>,
+    path: PathBuf,
+    goal_id: &str,
+) -> AppResult<ProjectImportReport> {
+    let bundle: ProjectBundle = blocking(move || {
+        let json = std::fs::read(&path).map_err(|e| io_error("Failed to read project bundle", e))?;
+        serde_json::from_slice(&json).map_err(|e| {
+            AppError::validation_error("path", "is not a project bundle").with_details(e.to_string())
+        })
+    })
+    .await?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(AppError::validation_error(
+            "version",
+            &format!("bundle version {} is newer than the supported version {}", bundle.version, BUNDLE_VERSION),
+        ));
+    }
+
+    let settings = settings::load(repo).await?;
+    let max_size = u64::from(settings.attachment_max_file_mb) * 1024 * 1024;
+    let used_bytes = repo.get_attachment_storage_bytes().await? as u64;
+
+    let (attachments, stored) = {
+        let store = store.clone();
+        let bundled = bundle.attachments;
+        blocking(move || {
+            let mut attachments = Vec::with_capacity(bundled.len());
+            let mut stored = Vec::new();
+            for BundleAttachment { mut attachment, content } in bundled {
+                let content = BASE64.decode(&content).map_err(|e| {
+                    AppError::validation_error("attachments", &format!("'{}' is not valid base64", attachment.file_name))
+                        .with_details(e.to_string())
+                });
+                let result = content.and_then(|content| store.store_bytes(&content, max_size));
+                let content = match result {
+                    Ok(content) => content,
+                    Err(e) => {
+                        remove_stored(&store, &stored);
+                        return Err(e);
+                    }
+                };
+                if content.is_new {
+                    stored.push((content.content_hash.clone(), content.size_bytes));
+                }
+                attachment.content_hash = content.content_hash;
+                attachment.size_bytes = content.size_bytes as i64;
+                attachments.push(attachment);
+            }
+            Ok((attachments, stored))
+        })
+        .await?
+    };
+
+    if let Some(quota_mb) = settings.attachment_quota_mb {
+        let quota = u64::from(quota_mb) * 1024 * 1024;
+        let new_bytes: u64 = stored.iter().map(|(_, size_bytes)| size_bytes).sum();
+        if used_bytes + new_bytes > quota {
+            blocking(move || {
+                remove_stored(&store, &stored);
+                Ok(())
+            })
+            .await?;
+            return Err(quota_exceeded("total", quota, new_bytes, used_bytes));
+        }
+    }
+
+    let contents = ProjectContents {
+        project: bundle.project,
+        tasks: bundle.tasks,
+        notes: bundle.notes,
+        tags: bundle.tags,
+        taggings: bundle.taggings,
+        dependencies: bundle.dependencies,
+        attachments,
+        workflow: bundle.workflow,
+        private_notes: 0,
+    };
+    let result = repo.import_project_contents(goal_id, contents).await;
+    if result.is_err() {
+        blocking(move || {
+            remove_stored(&store, &stored);
+            Ok(())
+        })
+        .await?;
+    }
+    result
+}
+
+/// Removes content stored for an import that didn't go through
+fn remove_stored(store: &AttachmentStore, stored: &[(String, u64)]) {
+    for (content_hash, _) in stored {
+        let _ = store.remove(content_hash);
+    }
+}
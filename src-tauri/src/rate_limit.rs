@@ -0,0 +1,75 @@
+//! Rate limiting of expensive commands
+//!
+//! Searches, exports and statistics scan whole tables, so a frontend stuck
+//! in a loop calling one of them could keep the database busy for everyone
+//! else. Commands flagged `rate_limited` in the command registry may only be
+//! invoked so many times per window; further calls are rejected with a
+//! `RATE_LIMITED` error whose data carries `retry_after_ms`, the time until
+//! the next call is accepted. Each command has its own budget, and rejected
+//! calls don't use it up.
+
+use crate::command_registry::{self, RateLimit};
+use crate::error::{AppError, AppResult, ErrorCode};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+/// Recent calls of each rate limited command
+#[derive(Default)]
+pub struct RateLimiter {
+    calls: Mutex<HashMap<&'static str, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    /// Records a call of `command`, or rejects it if its budget is used up
+    pub fn check(&self, command: &'static str, limit: RateLimit) -> AppResult<()> {
+        let Ok(mut calls) = self.calls.lock() else {
+            return Ok(());
+        };
+        let calls = calls.entry(command).or_default();
+        let window = Duration::from_secs(u64::from(limit.per_secs));
+        let now = Instant::now();
+        while calls.front().is_some_and(|call| now.duration_since(*call) >= window) {
+            calls.pop_front();
+        }
+
+        if calls.len() >= limit.calls as usize {
+            let retry_after = calls
+                .front()
+                .map(|oldest| window.saturating_sub(now.duration_since(*oldest)))
+                .unwrap_or(window);
+            return Err(AppError::new(
+                ErrorCode::RateLimited,
+                format!("{} was called too often; try again shortly", command),
+            )
+            .with_data(serde_json::json!({
+                "retry_after_ms": retry_after.as_millis() as u64,
+                "calls": limit.calls,
+                "per_secs": limit.per_secs,
+            })));
+        }
+        calls.push_back(now);
+        Ok(())
+    }
+}
+
+/// Wraps the generated command handler so rate limited commands are
+/// rejected once their budget is used up
+pub fn guard<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    let limiter = RateLimiter::default();
+    move |invoke: Invoke<R>| {
+        if let Some((name, limit)) = command_registry::find(invoke.message.command())
+            .and_then(|spec| Some((spec.name, spec.rate_limit?)))
+        {
+            if let Err(e) = limiter.check(name, limit) {
+                invoke.resolver.reject(e);
+                return true;
+            }
+        }
+        handler(invoke)
+    }
+}
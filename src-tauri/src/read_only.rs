@@ -0,0 +1,89 @@
+//! Read-only mode
+//!
+//! For when the database must not be written to: it lives in a synced
+//! folder that is in the middle of a conflict, or someone is looking over
+//! the user's shoulder during a support session. While the mode is on every
+//! command flagged `mutating` in the command registry is rejected with a
+//! `READ_ONLY` error, except the few flagged `available_while_read_only`,
+//! and so are the local API's writes. Scheduled jobs, the idle timer,
+//! script hooks and the achievement check stop making changes too, while
+//! reads keep working as usual.
+//!
+//! Starting the app with `--read-only` (or `EVORBRAIN_READ_ONLY=1`) turns
+//! the mode on before the database is touched, so pending migrations and
+//! the deferred `optimize` aren't run either; a database that still needs
+//! migrating is refused instead. `set_read_only_mode` turns it on and off
+//! at runtime.
+
+use crate::command_registry;
+use crate::error::{AppError, AppResult, ErrorCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+/// Command-line flag that starts the app in read-only mode
+const READ_ONLY_FLAG: &str = "--read-only";
+/// Environment variable that does the same when set to `1` or `true`
+const READ_ONLY_ENV: &str = "EVORBRAIN_READ_ONLY";
+
+/// Read-only state shared by the command guard, the API server and the
+/// background tasks
+#[derive(Default)]
+pub struct ReadOnlyMode {
+    enabled: AtomicBool,
+}
+
+impl ReadOnlyMode {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Turns the mode on or off; returns false if it already was
+    pub fn set_enabled(&self, enabled: bool) -> bool {
+        self.enabled.swap(enabled, Ordering::SeqCst) != enabled
+    }
+
+    /// Rejects writes while the mode is on
+    pub fn check(&self) -> AppResult<()> {
+        if self.is_enabled() {
+            return Err(AppError::new(
+                ErrorCode::ReadOnly,
+                "EvorBrain is in read-only mode; turn it off to make changes",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Whether the app was started with the read-only flag or variable
+pub fn requested_at_startup() -> bool {
+    std::env::args().any(|arg| arg == READ_ONLY_FLAG)
+        || std::env::var(READ_ONLY_ENV).is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+}
+
+/// Wraps the generated command handler so mutating commands are rejected
+/// while the mode is on
+pub fn guard<R: Runtime>(
+    mode: Arc<ReadOnlyMode>,
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke: Invoke<R>| {
+        let blocked = command_registry::find(invoke.message.command())
+            .is_some_and(|spec| spec.mutating && !spec.available_while_read_only);
+
+        if blocked {
+            if let Err(e) = mode.check() {
+                invoke.resolver.reject(e);
+                return true;
+            }
+        }
+        handler(invoke)
+    }
+}
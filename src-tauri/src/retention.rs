@@ -0,0 +1,35 @@
+//! Archive retention
+//!
+//! When the `archive_retention_days` setting is set, archived items older
+//! than that many days are deleted by the `archive_retention` job, except
+//! for the entity types listed in `archive_retention_exclusions`. Every purge
+//! is logged with the number of items deleted per type. With the setting
+//! unset archived items are kept until cleaned up by hand.
+
+use crate::db::repository::Repository;
+use crate::error::AppResult;
+use crate::log_info;
+use chrono::Utc;
+use evorbrain_core::settings;
+
+/// Deletes archived items past the retention period, if one is set
+pub async fn apply_retention(repo: &Repository) -> AppResult<()> {
+    let settings = settings::load(repo).await?;
+    let Some(days) = settings.archive_retention_days else { return Ok(()) };
+
+    let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
+    let purged = repo.purge_archived_before(cutoff, &settings.archive_retention_exclusions).await?;
+    if !purged.is_empty() {
+        let summary = purged
+            .iter()
+            .map(|(table, deleted)| format!("{} {}", deleted, table.replace('_', " ")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        log_info!(
+            "Archive retention",
+            &format!("Deleted items archived more than {} days ago: {}", days, summary)
+        );
+    }
+
+    Ok(())
+}
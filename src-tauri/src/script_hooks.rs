@@ -0,0 +1,317 @@
+//! User scripting hooks
+//!
+//! Users register Rhai scripts that run when matching events occur, e.g. on
+//! `task.completed` or `note.created`. Scripts are sandboxed: Rhai has no
+//! file, network or process access, `eval` and module imports are disabled,
+//! and every run is bounded by a wall-clock time limit (the
+//! `script_time_limit_ms` setting) plus limits on
+//! operations, call depth and data sizes. The only way to touch data is the
+//! small API registered below, which goes through the batch executor:
+//!
+//! * `get_entity(type, id)` returns the entity as a map, or `()`
+//! * `create_task(title)` / `create_task(title, project_id)` returns the new task ID
+//! * `complete_task(id)`, `tag_task(task_id, tag)`
+//! * `add_note(task_id, title, content)` returns the new note ID
+//! * `print(...)` writes to the hook's output and the application log
+//!
+//! Each script sees the triggering event as `event` (`name`, `entity_type`,
+//! `entity_id`, `seq`, `occurred_at`) and the entity snapshot as `entity`.
+//! Scripts that create entities emit events themselves, so runs per hook are
+//! rate limited to stop a hook from triggering itself forever.
+
+use crate::db::batch::{self, BatchOperation, BatchOperationStatus};
+use crate::db::models::{NoteInput, ScriptHook, TaskInput};
+use crate::db::repository::Repository;
+use crate::db::write_queue::BulkProgress;
+use crate::read_only::ReadOnlyMode;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::{log_error, log_info, log_warn};
+use evorbrain_core::events::{matches_event_filter, AppEvent, EventBus};
+use evorbrain_core::settings;
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+/// Upper bound on a script's source length
+pub const MAX_SCRIPT_LENGTH: usize = 64 * 1024;
+/// Runs allowed per hook within `RATE_WINDOW`
+const MAX_RUNS_PER_WINDOW: usize = 30;
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+/// Lines of script output kept per run
+const MAX_OUTPUT_LINES: usize = 200;
+
+/// Outcome of one script run
+#[derive(Debug, Default, Serialize)]
+pub struct ScriptRunResult {
+    /// Lines written with `print`
+    pub output: Vec<String>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+type ScriptError = Box<EvalAltResult>;
+
+fn script_error(error: AppError) -> ScriptError {
+    let message = match error.details {
+        Some(details) => format!("{}: {}", error.message, details),
+        None => error.message,
+    };
+    message.into()
+}
+
+/// Builds a sandboxed engine with the limits applied
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .set_max_modules(0)
+        .set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine
+}
+
+/// Parses a script, returning a validation error describing the first problem
+pub fn compile(script: &str) -> AppResult<AST> {
+    if script.len() > MAX_SCRIPT_LENGTH {
+        return Err(AppError::validation_error(
+            "script",
+            &format!("must be at most {} bytes", MAX_SCRIPT_LENGTH),
+        ));
+    }
+    sandboxed_engine()
+        .compile(script)
+        .map_err(|e| AppError::validation_error("script", &e.to_string()))
+}
+
+/// Runs one operation through the batch executor from a script thread
+fn run_operation(db: &Arc<SqlitePool>, operation: BatchOperation) -> Result<String, ScriptError> {
+    let repo = Repository::new(db.clone());
+    let mut result = tauri::async_runtime::block_on(batch::execute_batch(&repo, vec![operation], &BulkProgress::default()))
+        .map_err(script_error)?;
+
+    match result.results.pop() {
+        Some(op) if op.status == BatchOperationStatus::Ok => Ok(op.id.unwrap_or_default()),
+        Some(op) => Err(op
+            .error
+            .map(script_error)
+            .unwrap_or_else(|| "operation failed".into())),
+        None => Err("operation failed".into()),
+    }
+}
+
+/// Registers the repository API scripts are allowed to use
+fn register_api(engine: &mut Engine, db: Arc<SqlitePool>) {
+    let pool = db.clone();
+    engine.register_fn("get_entity", move |entity_type: &str, id: &str| -> Result<Dynamic, ScriptError> {
+        let repo = Repository::new(pool.clone());
+        let snapshot = tauri::async_runtime::block_on(repo.get_entity_snapshot(entity_type, id))
+            .map_err(script_error)?;
+        match snapshot {
+            Some(value) => rhai::serde::to_dynamic(value),
+            None => Ok(Dynamic::UNIT),
+        }
+    });
+
+    let pool = db.clone();
+    engine.register_fn("create_task", move |title: &str| {
+        run_operation(&pool, BatchOperation::CreateTask {
+            input: TaskInput {
+                title: title.to_string(),
+                ..Default::default()
+            },
+        })
+    });
+
+    let pool = db.clone();
+    engine.register_fn("create_task", move |title: &str, project_id: &str| {
+        run_operation(&pool, BatchOperation::CreateTask {
+            input: TaskInput {
+                title: title.to_string(),
+                project_id: Some(project_id.to_string()),
+                ..Default::default()
+            },
+        })
+    });
+
+    let pool = db.clone();
+    engine.register_fn("complete_task", move |id: &str| {
+        run_operation(&pool, BatchOperation::CompleteTask { id: id.to_string() }).map(|_| ())
+    });
+
+    let pool = db.clone();
+    engine.register_fn("tag_task", move |task_id: &str, tag: &str| {
+        run_operation(&pool, BatchOperation::TagTask {
+            task_id: task_id.to_string(),
+            tag: tag.to_string(),
+        })
+        .map(|_| ())
+    });
+
+    let pool = db;
+    engine.register_fn("add_note", move |task_id: &str, title: &str, content: &str| {
+        run_operation(&pool, BatchOperation::CreateNote {
+            input: NoteInput {
+                task_id: Some(task_id.to_string()),
+                title: title.to_string(),
+                content: content.to_string(),
+                ..Default::default()
+            },
+        })
+    });
+}
+
+/// Runs a hook's script for an event on the current (blocking) thread
+fn run_blocking(
+    db: Arc<SqlitePool>,
+    hook: &ScriptHook,
+    event: &AppEvent,
+    entity: Option<serde_json::Value>,
+    time_limit: Duration,
+) -> ScriptRunResult {
+    let started = Instant::now();
+    let output = Arc::new(Mutex::new(Vec::new()));
+
+    let mut engine = sandboxed_engine();
+    engine.on_progress(move |_| {
+        (started.elapsed() > time_limit).then(|| Dynamic::from("time limit exceeded"))
+    });
+    let sink = output.clone();
+    let hook_name = hook.name.clone();
+    engine.on_print(move |line| {
+        log_info!("Script hook output", &format!("{}: {}", hook_name, line));
+        if let Ok(mut lines) = sink.lock() {
+            if lines.len() < MAX_OUTPUT_LINES {
+                lines.push(line.to_string());
+            }
+        }
+    });
+    register_api(&mut engine, db);
+
+    let result = (|| -> Result<(), ScriptError> {
+        let ast = engine.compile(&hook.script)?;
+        let mut scope = Scope::new();
+        scope.push_constant("event", rhai::serde::to_dynamic(event)?);
+        scope.push_constant(
+            "entity",
+            match entity {
+                Some(value) => rhai::serde::to_dynamic(value)?,
+                None => Dynamic::UNIT,
+            },
+        );
+        engine.run_ast_with_scope(&mut scope, &ast)
+    })();
+
+    let output = output.lock().map(|lines| lines.clone()).unwrap_or_default();
+    ScriptRunResult {
+        output,
+        error: result.err().map(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) => "time limit exceeded".to_string(),
+            e => e.to_string(),
+        }),
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Runs a hook for an event and records the outcome on the hook
+pub async fn run_hook(db: Arc<SqlitePool>, hook: ScriptHook, event: AppEvent) -> AppResult<ScriptRunResult> {
+    let repo = Repository::new(db.clone());
+    let entity = repo
+        .get_entity_snapshot(&event.entity_type, &event.entity_id)
+        .await
+        .unwrap_or(None);
+
+    let time_limit = Duration::from_millis(settings::load(&repo).await?.script_time_limit_ms);
+
+    let hook_id = hook.id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || run_blocking(db, &hook, &event, entity, time_limit))
+        .await
+        .map_err(|e| AppError::new(ErrorCode::InternalError, "Script hook task failed").with_details(e.to_string()))?;
+
+    repo.record_script_hook_run(&hook_id, result.error.as_deref()).await?;
+    Ok(result)
+}
+
+/// Tracks recent runs per hook to stop runaway self-triggering hooks
+#[derive(Default)]
+struct RateLimiter {
+    runs: HashMap<String, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    fn allow(&mut self, hook_id: &str) -> bool {
+        let now = Instant::now();
+        let runs = self.runs.entry(hook_id.to_string()).or_default();
+        while runs.front().is_some_and(|t| now.duration_since(*t) > RATE_WINDOW) {
+            runs.pop_front();
+        }
+        if runs.len() >= MAX_RUNS_PER_WINDOW {
+            return false;
+        }
+        runs.push_back(now);
+        true
+    }
+}
+
+/// Starts the background task that runs matching hooks for every event
+pub fn spawn_runner(db: Arc<SqlitePool>, read_only: Arc<ReadOnlyMode>, bus: &EventBus) {
+    let mut receiver = bus.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db.clone());
+        let mut limiter = RateLimiter::default();
+
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    log_warn!(&format!("Script hook runner skipped {} events", skipped));
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if read_only.is_enabled() {
+                continue;
+            }
+
+            let hooks = match repo.get_active_script_hooks().await {
+                Ok(hooks) => hooks,
+                Err(e) => {
+                    log_error!("Failed to load script hooks", e);
+                    continue;
+                }
+            };
+
+            for hook in hooks.into_iter().filter(|h| matches_event_filter(&h.events, &event.name)) {
+                if !limiter.allow(&hook.id) {
+                    let error = format!("Skipped: more than {} runs per minute", MAX_RUNS_PER_WINDOW);
+                    let _ = repo.record_script_hook_run(&hook.id, Some(&error)).await;
+                    continue;
+                }
+
+                // Hooks run one at a time so they see each other's changes in order
+                let name = hook.name.clone();
+                match run_hook(db.clone(), hook, event.clone()).await {
+                    Ok(result) => {
+                        if let Some(error) = result.error {
+                            log_warn!(&format!("Script hook '{}' failed: {}", name, error));
+                        }
+                    }
+                    Err(e) => log_error!("Failed to run script hook", e),
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,261 @@
+//! Settings export and import
+//!
+//! `export` collects the configuration a user builds up over time (settings,
+//! custom project workflows, webhooks, script hooks and job schedules) into a
+//! `SettingsBundle` that can be loaded into another vault with `import`,
+//! without moving any life areas, goals, projects, tasks or notes.
+//!
+//! Credentials stay behind: the GitHub token isn't exported, and webhooks get
+//! a new signing secret when they are created on import. The whole bundle is
+//! validated before anything is written.
+
+use crate::commands::script_hooks::validate_script_hook_input;
+use crate::commands::webhooks::validate_webhook_input;
+use crate::db::models::{JobType, ScriptHookInput, WebhookInput, WorkflowStatusInput};
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult};
+use chrono::{DateTime, Utc};
+use evorbrain_core::schedule::Schedule;
+use evorbrain_core::settings::{self, SETTING_KEYS};
+use evorbrain_core::validation::validate_workflow;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Format version written by `export`; bundles from newer versions are refused
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Settings that are credentials rather than preferences
+const NOT_EXPORTED: &[&str] = &["github_token"];
+
+/// Configuration exported from one vault for import into another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Settings changed from their defaults, keyed by setting name
+    #[serde(default)]
+    pub settings: Map<String, Value>,
+    #[serde(default)]
+    pub workflows: Vec<ProjectWorkflow>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookInput>,
+    #[serde(default)]
+    pub script_hooks: Vec<ScriptHookInput>,
+    #[serde(default)]
+    pub jobs: Vec<JobConfig>,
+}
+
+/// Custom statuses of one project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectWorkflow {
+    pub project_id: String,
+    /// Used to find the project when the target vault has other IDs
+    pub project_title: String,
+    pub statuses: Vec<WorkflowStatusInput>,
+}
+
+/// Schedule and enabled flag of a background job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobConfig {
+    pub job_type: JobType,
+    pub schedule: String,
+    pub enabled: bool,
+}
+
+/// What `import` changed and what it left out
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SettingsImportReport {
+    /// Settings whose value changed
+    pub settings_changed: Vec<String>,
+    /// Projects whose workflow was replaced
+    pub workflows_applied: Vec<String>,
+    pub webhooks_created: usize,
+    pub script_hooks_created: usize,
+    /// Jobs whose schedule or enabled flag changed
+    pub jobs_updated: usize,
+    /// Human-readable reasons for every part of the bundle that wasn't imported
+    pub skipped: Vec<String>,
+}
+
+/// Collects the current configuration into a bundle
+pub async fn export(repo: &Repository) -> AppResult<SettingsBundle> {
+    let mut values = repo.get_setting_values().await?;
+    values.retain(|key, _| SETTING_KEYS.contains(&key.as_str()) && !NOT_EXPORTED.contains(&key.as_str()));
+
+    let mut workflows = Vec::new();
+    for workflow in repo.get_custom_workflows().await? {
+        let Some(project_id) = workflow.project_id else { continue };
+        let project = repo.get_project(&project_id).await?;
+        workflows.push(ProjectWorkflow {
+            project_id,
+            project_title: project.title,
+            statuses: workflow
+                .statuses
+                .into_iter()
+                .map(|status| WorkflowStatusInput {
+                    key: status.key,
+                    name: status.name,
+                    category: status.category,
+                    next_statuses: status.next_statuses,
+                })
+                .collect(),
+        });
+    }
+
+    let webhooks = repo
+        .get_webhooks()
+        .await?
+        .into_iter()
+        .map(|webhook| WebhookInput {
+            name: webhook.name,
+            url: webhook.url,
+            events: webhook.events,
+            is_active: webhook.is_active,
+        })
+        .collect();
+
+    let script_hooks = repo
+        .get_script_hooks()
+        .await?
+        .into_iter()
+        .map(|hook| ScriptHookInput {
+            name: hook.name,
+            events: hook.events,
+            script: hook.script,
+            is_active: hook.is_active,
+        })
+        .collect();
+
+    let jobs = repo
+        .get_jobs()
+        .await?
+        .into_iter()
+        .map(|job| JobConfig {
+            job_type: job.job_type,
+            schedule: job.schedule,
+            enabled: job.enabled,
+        })
+        .collect();
+
+    Ok(SettingsBundle {
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        settings: values,
+        workflows,
+        webhooks,
+        script_hooks,
+        jobs,
+    })
+}
+
+/// Applies a bundle to this vault
+///
+/// Settings are replaced as a whole: a setting missing from the bundle goes
+/// back to its default, except the GitHub token, which is kept. Workflows are
+/// applied to the project with the same ID, or else to the only active
+/// project with the same title. Webhooks and script hooks are added unless
+/// one with the same URL or name already exists, so importing a bundle twice
+/// doesn't duplicate them.
+pub async fn import(repo: &Repository, bundle: SettingsBundle) -> AppResult<SettingsImportReport> {
+    if bundle.version > BUNDLE_VERSION {
+        return Err(AppError::validation_error(
+            "version",
+            &format!("bundle version {} is newer than the supported version {}", bundle.version, BUNDLE_VERSION),
+        ));
+    }
+
+    let mut report = SettingsImportReport::default();
+
+    let mut changes = Map::new();
+    for (key, value) in bundle.settings {
+        if NOT_EXPORTED.contains(&key.as_str()) {
+            continue;
+        }
+        if SETTING_KEYS.contains(&key.as_str()) {
+            changes.insert(key, value);
+        } else {
+            report.skipped.push(format!("unknown setting '{}'", key));
+        }
+    }
+    for key in SETTING_KEYS.iter().filter(|key| !NOT_EXPORTED.contains(key)) {
+        changes.entry(key.to_string()).or_insert(Value::Null);
+    }
+
+    for workflow in &bundle.workflows {
+        validate_workflow(&workflow.statuses)?;
+    }
+    for webhook in &bundle.webhooks {
+        validate_webhook_input(webhook)?;
+    }
+    for hook in &bundle.script_hooks {
+        validate_script_hook_input(hook)?;
+    }
+    for job in &bundle.jobs {
+        Schedule::parse(&job.schedule)?;
+    }
+
+    // Settings are validated as a whole before the first write
+    let (_, changed) = settings::update(repo, changes).await?;
+    report.settings_changed = changed;
+
+    let projects = repo.get_projects().await?;
+    for workflow in bundle.workflows {
+        let project_id = match repo.get_project(&workflow.project_id).await {
+            Ok(project) => Some(project.id),
+            Err(_) => {
+                let mut matches = projects.iter().filter(|p| p.title == workflow.project_title);
+                match (matches.next(), matches.next()) {
+                    (Some(project), None) => Some(project.id.clone()),
+                    _ => None,
+                }
+            }
+        };
+        match project_id {
+            Some(project_id) => {
+                repo.set_workflow(&project_id, workflow.statuses).await?;
+                report.workflows_applied.push(project_id);
+            }
+            None => report
+                .skipped
+                .push(format!("workflow of project '{}': no single matching project", workflow.project_title)),
+        }
+    }
+
+    let existing_webhooks = repo.get_webhooks().await?;
+    for webhook in bundle.webhooks {
+        if existing_webhooks.iter().any(|w| w.url == webhook.url) {
+            report.skipped.push(format!("webhook '{}': a webhook for {} exists", webhook.name, webhook.url));
+            continue;
+        }
+        repo.create_webhook(webhook).await?;
+        report.webhooks_created += 1;
+    }
+
+    let existing_hooks = repo.get_script_hooks().await?;
+    for hook in bundle.script_hooks {
+        if existing_hooks.iter().any(|h| h.name == hook.name) {
+            report.skipped.push(format!("script hook '{}': a hook with that name exists", hook.name));
+            continue;
+        }
+        repo.create_script_hook(hook).await?;
+        report.script_hooks_created += 1;
+    }
+
+    for config in bundle.jobs {
+        let job = repo.get_job(config.job_type).await?;
+        let mut updated = false;
+        if job.schedule != config.schedule {
+            repo.set_job_schedule(config.job_type, &config.schedule).await?;
+            updated = true;
+        }
+        if job.enabled != config.enabled {
+            repo.set_job_enabled(config.job_type, config.enabled).await?;
+            updated = true;
+        }
+        if updated {
+            report.jobs_updated += 1;
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,166 @@
+//! Startup timing and deferred initialization
+//!
+//! Setup records how long each of its phases takes (logger, database pool,
+//! migrations, the first queries), and `get_startup_report` returns the
+//! timings. Work the first window doesn't need, such as the foreign key
+//! check, refreshing planner statistics and starting the job scheduler,
+//! waits until the page has loaded (or `DEFERRED_WORK_TIMEOUT` passed, for
+//! a window that never reports it) so it doesn't compete with the first
+//! queries of the frontend.
+
+use crate::attachments::AttachmentStore;
+use crate::db::write_queue::WriteQueue;
+use crate::read_only::ReadOnlyMode;
+use crate::{db, jobs, log_error, log_info};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Longest deferred work waits for the page to load
+const DEFERRED_WORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One timed step of startup
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// Milliseconds since the app started
+    pub started_at_ms: f64,
+    pub duration_ms: f64,
+    /// Set when the step failed
+    pub error: Option<String>,
+}
+
+/// Startup timings, in milliseconds since the app started
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+    /// Steps run before the window is shown, in order
+    pub phases: Vec<StartupPhase>,
+    pub setup_finished_ms: Option<f64>,
+    /// When the first page finished loading
+    pub window_ready_ms: Option<f64>,
+    /// Steps run once the window was ready, in order
+    pub deferred: Vec<StartupPhase>,
+    pub deferred_finished_ms: Option<f64>,
+}
+
+/// Collects the startup report; shared between setup and `AppState`
+pub struct Startup {
+    started: Instant,
+    report: Mutex<StartupReport>,
+    window_ready: Notify,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            report: Mutex::default(),
+            window_ready: Notify::new(),
+        }
+    }
+}
+
+impl Startup {
+    pub fn report(&self) -> StartupReport {
+        self.report.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Runs a setup step and records how long it took
+    pub fn time<T, E: std::fmt::Display>(&self, name: &str, step: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let started = Instant::now();
+        let result = step();
+        self.record(name, started, result.as_ref().err(), false);
+        result
+    }
+
+    /// Awaits a setup step and records how long it took
+    pub async fn time_async<T, E: std::fmt::Display>(
+        &self,
+        name: &str,
+        step: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        self.measure(name, false, step).await
+    }
+
+    /// Marks the end of setup
+    pub fn setup_finished(&self) {
+        let elapsed = self.elapsed_ms(Instant::now());
+        self.report.lock().unwrap_or_else(|e| e.into_inner()).setup_finished_ms = Some(elapsed);
+    }
+
+    /// Marks the window as ready, releasing the deferred work; later page
+    /// loads (reloads, other windows) are ignored
+    pub fn window_ready(&self) {
+        let elapsed = self.elapsed_ms(Instant::now());
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        if report.window_ready_ms.is_none() {
+            report.window_ready_ms = Some(elapsed);
+            self.window_ready.notify_one();
+        }
+    }
+
+    async fn measure<T, E: std::fmt::Display>(
+        &self,
+        name: &str,
+        deferred: bool,
+        step: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let started = Instant::now();
+        let result = step.await;
+        self.record(name, started, result.as_ref().err(), deferred);
+        result
+    }
+
+    fn record<E: std::fmt::Display>(&self, name: &str, started: Instant, error: Option<&E>, deferred: bool) {
+        let phase = StartupPhase {
+            name: name.to_string(),
+            started_at_ms: self.elapsed_ms(started),
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+            error: error.map(|e| e.to_string()),
+        };
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        if deferred {
+            report.deferred.push(phase);
+        } else {
+            report.phases.push(phase);
+        }
+    }
+
+    fn elapsed_ms(&self, instant: Instant) -> f64 {
+        instant.duration_since(self.started).as_secs_f64() * 1000.0
+    }
+}
+
+/// Starts the background task that runs the deferred startup work once the
+/// window is ready
+pub fn spawn_deferred_work(
+    db: Arc<SqlitePool>,
+    write_queue: WriteQueue,
+    read_only: Arc<ReadOnlyMode>,
+    startup: Arc<Startup>,
+    attachments: Arc<AttachmentStore>,
+) {
+    tauri::async_runtime::spawn(async move {
+        if tokio::time::timeout(DEFERRED_WORK_TIMEOUT, startup.window_ready.notified()).await.is_err() {
+            log_info!("Window not ready in time, running deferred startup work");
+        }
+
+        if let Err(e) = startup.measure("foreign_key_check", true, db::report_foreign_key_violations(&db)).await {
+            log_error!(&format!("Foreign key check failed: {}", e));
+        }
+        // Refreshing the planner statistics writes to the database
+        if !read_only.is_enabled() {
+            if let Err(e) = startup.measure("optimize", true, db::optimize(&db)).await {
+                log_error!(&format!("Failed to optimize the database: {}", e));
+            }
+        }
+        jobs::spawn_scheduler(db, write_queue, read_only, attachments);
+
+        let elapsed = startup.elapsed_ms(Instant::now());
+        startup.report.lock().unwrap_or_else(|e| e.into_inner()).deferred_finished_ms = Some(elapsed);
+        log_info!("Deferred startup work finished");
+    });
+}
@@ -0,0 +1,117 @@
+//! Chunked delivery of large result sets
+//!
+//! A full export or the complete note list can run to many megabytes, too
+//! much for a single IPC response. Streaming commands instead answer at once
+//! with a `StreamStarted` summary and deliver the items as a sequence of
+//! `stream-chunk` events, each at most about `CHUNK_BYTES` of JSON, followed
+//! by one `stream-complete` event.
+//!
+//! The caller picks the stream ID and passes it to the command, so it can
+//! start listening before the first chunk is emitted. Chunks carry an index
+//! counting up from 0; the completion event gives the number of chunks, so a
+//! missing one can be detected.
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::log_error;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+pub const STREAM_CHUNK_EVENT: &str = "stream-chunk";
+pub const STREAM_COMPLETE_EVENT: &str = "stream-complete";
+
+/// Serialized size a chunk is filled up to; a single larger item gets a chunk
+/// of its own
+const CHUNK_BYTES: usize = 256 * 1024;
+
+/// Returned by a streaming command before the first chunk is emitted
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamStarted {
+    pub stream_id: String,
+    pub total_items: usize,
+}
+
+/// One `stream-chunk` event
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamChunk {
+    pub stream_id: String,
+    /// Position of the chunk in the stream, from 0
+    pub index: usize,
+    /// Which part of the result the items belong to, e.g. `notes`
+    pub section: String,
+    pub items: Vec<Value>,
+}
+
+/// The `stream-complete` event
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamComplete {
+    pub stream_id: String,
+    pub chunks: usize,
+    pub items: usize,
+    /// Set when the stream stopped early; chunks before it were delivered
+    pub error: Option<String>,
+}
+
+/// Emits `sections` as chunk events in the background
+///
+/// Sections are delivered in order, and a chunk never mixes items of two
+/// sections.
+pub fn start(app_handle: AppHandle, stream_id: String, sections: Vec<(String, Vec<Value>)>) -> StreamStarted {
+    let started = StreamStarted {
+        stream_id: stream_id.clone(),
+        total_items: sections.iter().map(|(_, items)| items.len()).sum(),
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let mut chunks = 0;
+        let mut items = 0;
+        let mut error = None;
+
+        'sections: for (section, section_items) in sections {
+            let mut chunk = Vec::new();
+            let mut chunk_bytes = 0;
+            let count = section_items.len();
+
+            for (position, item) in section_items.into_iter().enumerate() {
+                chunk_bytes += serde_json::to_vec(&item).map_or(0, |json| json.len());
+                chunk.push(item);
+
+                if chunk_bytes >= CHUNK_BYTES || position + 1 == count {
+                    let event = StreamChunk {
+                        stream_id: stream_id.clone(),
+                        index: chunks,
+                        section: section.clone(),
+                        items: std::mem::take(&mut chunk),
+                    };
+                    if let Err(e) = emit(&app_handle, STREAM_CHUNK_EVENT, &event) {
+                        error = Some(e.message);
+                        break 'sections;
+                    }
+                    chunks += 1;
+                    items += event.items.len();
+                    chunk_bytes = 0;
+                    // Let other IPC traffic through between chunks
+                    tokio::task::yield_now().await;
+                }
+            }
+        }
+
+        let complete = StreamComplete {
+            stream_id,
+            chunks,
+            items,
+            error,
+        };
+        // A failure is logged by `emit`; there is nobody else to tell
+        let _ = emit(&app_handle, STREAM_COMPLETE_EVENT, &complete);
+    });
+
+    started
+}
+
+fn emit<T: Serialize + Clone>(app_handle: &AppHandle, event: &str, payload: &T) -> AppResult<()> {
+    app_handle.emit(event, payload).map_err(|e| {
+        log_error!("Failed to emit stream event", e);
+        AppError::new(ErrorCode::InternalError, "Failed to emit stream event").with_details(e.to_string())
+    })
+}
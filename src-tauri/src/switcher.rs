@@ -0,0 +1,73 @@
+//! Quick switcher indexer
+//!
+//! Loads the quick switcher's title index at startup and keeps it current:
+//! each change feed event re-reads the entity it names. The whole index is
+//! reloaded instead when it is invalidated (after a restore or import),
+//! when more events are waiting than are worth refreshing one by one, and
+//! when events were missed because the bus lagged behind.
+
+use crate::db::repository::Repository;
+use crate::{log_error, log_info};
+use evorbrain_core::events::{AppEvent, EventBus};
+use evorbrain_core::switcher::{SwitcherIndex, MAX_SWITCHER_REFRESHES};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::broadcast::Receiver;
+
+async fn load(repo: &Repository, index: &SwitcherIndex) {
+    match index.load(repo).await {
+        Ok(count) => log_info!("Quick switcher index loaded", &format!("{} titles", count)),
+        Err(e) => log_error!("Failed to load the quick switcher index", e),
+    }
+}
+
+/// The first event and whatever else is already waiting, or `None` when
+/// there are too many (or some were missed) and the index should be reloaded
+fn pending(first: AppEvent, receiver: &mut Receiver<AppEvent>) -> Option<Vec<AppEvent>> {
+    let mut events = vec![first];
+    loop {
+        match receiver.try_recv() {
+            Ok(event) => events.push(event),
+            Err(TryRecvError::Empty | TryRecvError::Closed) => return Some(events),
+            Err(TryRecvError::Lagged(_)) => return None,
+        }
+        if events.len() > MAX_SWITCHER_REFRESHES {
+            // Skip the rest; the reload covers them
+            while receiver.try_recv().is_ok() {}
+            return None;
+        }
+    }
+}
+
+/// Starts the background task that keeps the quick switcher's index current
+pub fn spawn_switcher_indexer(db: Arc<SqlitePool>, index: Arc<SwitcherIndex>, bus: &EventBus) {
+    // Subscribe before loading so no change falls between the two
+    let mut receiver = bus.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+        load(&repo, &index).await;
+
+        loop {
+            let events = tokio::select! {
+                _ = index.invalidated() => None,
+                received = receiver.recv() => match received {
+                    Ok(event) => pending(event, &mut receiver),
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => break,
+                },
+            };
+
+            let Some(events) = events else {
+                load(&repo, &index).await;
+                continue;
+            };
+            for event in events {
+                if let Err(e) = index.refresh(&repo, &event.entity_type, &event.entity_id).await {
+                    log_error!("Failed to update the quick switcher index", e);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,77 @@
+//! Idle detection for running timers
+//!
+//! The backend can't see OS input, so "idle" means what it means for the
+//! app lock: no command invoked for `idle_timeout_minutes`. Sleep is
+//! detected separately, as a jump in wall-clock time between two checks
+//! (the monotonic clock the checks run on doesn't advance while the
+//! machine is asleep). Either way the running timer is stopped when the
+//! gap began, the gap is recorded on the entry, and `timer.idle_stopped`
+//! is published so the user can reconcile the entry. Nothing is stopped
+//! while read-only mode is on.
+
+use crate::app_lock::AppLock;
+use crate::db::repository::Repository;
+use crate::read_only::ReadOnlyMode;
+use crate::{log_error, log_info};
+use chrono::{DateTime, TimeDelta, Utc};
+use evorbrain_core::events::{AppEvent, EventBus};
+use evorbrain_core::settings;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the running timer is checked for idleness
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// When the machine went idle, if it has been idle or asleep for at least
+/// `timeout`
+fn idle_since(lock: &AppLock, previous_check: DateTime<Utc>, now: DateTime<Utc>, timeout: TimeDelta) -> Option<DateTime<Utc>> {
+    let check_interval = TimeDelta::from_std(IDLE_CHECK_INTERVAL).unwrap_or_default();
+    let idle_for = TimeDelta::from_std(lock.idle_for()).unwrap_or_default();
+
+    let asleep_since = (now - previous_check - check_interval >= timeout).then_some(previous_check);
+    let inactive_since = (idle_for >= timeout).then(|| now - idle_for);
+    asleep_since.into_iter().chain(inactive_since).min()
+}
+
+/// Starts the background task that stops timers left running while idle
+pub fn spawn_idle_watcher(db: Arc<SqlitePool>, lock: Arc<AppLock>, read_only: Arc<ReadOnlyMode>, bus: EventBus) {
+    tauri::async_runtime::spawn(async move {
+        let repo = Repository::new(db);
+        let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        let mut last_check = Utc::now();
+
+        loop {
+            interval.tick().await;
+            let now = Utc::now();
+            let previous_check = std::mem::replace(&mut last_check, now);
+            if read_only.is_enabled() {
+                continue;
+            }
+
+            let minutes = match settings::load(&repo).await {
+                Ok(settings) => settings.idle_timeout_minutes,
+                Err(e) => {
+                    log_error!("Failed to load settings", e);
+                    continue;
+                }
+            };
+            if minutes == 0 {
+                continue;
+            }
+            let timeout = TimeDelta::minutes(i64::from(minutes));
+            let Some(idle_since) = idle_since(&lock, previous_check, now, timeout) else {
+                continue;
+            };
+
+            match repo.stop_time_entry_idle(idle_since, now).await {
+                Ok(Some(entry)) => {
+                    log_info!("Stopped idle timer", &entry.id);
+                    bus.publish(AppEvent::new("timer.idle_stopped", "time_entry", entry.id));
+                }
+                Ok(None) => {}
+                Err(e) => log_error!("Failed to stop idle timer", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,209 @@
+//! Outgoing webhook delivery
+//!
+//! Subscribes to the event bus and POSTs a JSON payload to every active
+//! webhook whose event filters match. Each request carries an
+//! `X-EvorBrain-Signature: sha256=<hex>` header, the HMAC-SHA256 of
+//! `"<timestamp>.<body>"` keyed with the webhook secret, where the timestamp
+//! is sent in `X-EvorBrain-Timestamp` so receivers can reject replays.
+
+use crate::db::models::Webhook;
+use crate::db::repository::Repository;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::{log_error, log_warn};
+use chrono::{DateTime, Utc};
+use evorbrain_core::events::{matches_event_filter, AppEvent, EventBus};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+pub const SIGNATURE_HEADER: &str = "X-EvorBrain-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-EvorBrain-Timestamp";
+pub const EVENT_HEADER: &str = "X-EvorBrain-Event";
+pub const DELIVERY_HEADER: &str = "X-EvorBrain-Delivery";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Body of every webhook request
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    /// Unique delivery ID, also sent in `X-EvorBrain-Delivery`
+    pub id: String,
+    pub event: String,
+    pub occurred_at: DateTime<Utc>,
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Current state of the entity, when it still exists
+    pub data: Option<serde_json::Value>,
+}
+
+impl WebhookPayload {
+    fn new(event: &AppEvent, data: Option<serde_json::Value>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            event: event.name.clone(),
+            occurred_at: event.occurred_at,
+            entity_type: event.entity_type.clone(),
+            entity_id: event.entity_id.clone(),
+            data,
+        }
+    }
+}
+
+/// Starts the background task that delivers events to registered webhooks
+pub fn spawn_dispatcher(db: Arc<SqlitePool>, bus: &EventBus) {
+    let mut receiver = bus.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let client = http_client();
+        let repo = Repository::new(db);
+
+        loop {
+            match receiver.recv().await {
+                Ok(event) => dispatch(&client, &repo, &event).await,
+                Err(RecvError::Lagged(skipped)) => {
+                    log_warn!(&format!("Webhook dispatcher skipped {} events", skipped));
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .user_agent(concat!("EvorBrain-Webhooks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Active webhooks whose event filters match `event`
+async fn targets(repo: &Repository, event: &AppEvent) -> AppResult<Vec<Webhook>> {
+    Ok(repo
+        .get_active_webhooks()
+        .await?
+        .into_iter()
+        .filter(|hook| matches_event_filter(&hook.events, &event.name))
+        .collect())
+}
+
+async fn dispatch(client: &reqwest::Client, repo: &Repository, event: &AppEvent) {
+    let targets = match targets(repo, event).await {
+        Ok(targets) => targets,
+        Err(e) => {
+            log_error!("Failed to load webhooks", e);
+            return;
+        }
+    };
+    if targets.is_empty() {
+        return;
+    }
+
+    let data = repo
+        .get_entity_snapshot(&event.entity_type, &event.entity_id)
+        .await
+        .unwrap_or(None);
+    let payload = WebhookPayload::new(event, data);
+
+    for hook in targets {
+        // A failed delivery is recorded on the webhook and not retried
+        let _ = deliver(client, repo, &hook, &payload).await;
+    }
+}
+
+/// Delivers a standalone event carrying `data` to the matching webhooks
+/// right away, rather than through the event bus
+///
+/// Returns how many webhooks accepted it; fails if none is subscribed or
+/// none accepted it, so the caller knows the data went nowhere.
+pub async fn deliver_event(repo: &Repository, event: &AppEvent, data: serde_json::Value) -> AppResult<usize> {
+    let targets = targets(repo, event).await?;
+    if targets.is_empty() {
+        return Err(AppError::new(ErrorCode::NotFound, "No active webhook is subscribed to this event")
+            .with_details(event.name.clone()));
+    }
+
+    let client = http_client();
+    let payload = WebhookPayload::new(event, Some(data));
+    let mut delivered = 0;
+    let mut last_error = None;
+    for hook in &targets {
+        match deliver(&client, repo, hook, &payload).await {
+            Ok(_) => delivered += 1,
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match last_error {
+        Some(e) if delivered == 0 => Err(e),
+        _ => Ok(delivered),
+    }
+}
+
+/// Sends one signed delivery and records its outcome on the webhook
+async fn deliver(
+    client: &reqwest::Client,
+    repo: &Repository,
+    hook: &Webhook,
+    payload: &WebhookPayload,
+) -> AppResult<u16> {
+    let body = serde_json::to_string(payload)?;
+    let timestamp = Utc::now().timestamp().to_string();
+    let signature = sign(&hook.secret, &timestamp, &body);
+
+    let result = client
+        .post(&hook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, &payload.event)
+        .header(DELIVERY_HEADER, &payload.id)
+        .header(TIMESTAMP_HEADER, &timestamp)
+        .header(SIGNATURE_HEADER, format!("sha256={}", signature))
+        .body(body)
+        .send()
+        .await;
+
+    let outcome = match result {
+        Ok(response) if response.status().is_success() => Ok(response.status().as_u16()),
+        Ok(response) => Err((
+            Some(response.status().as_u16()),
+            format!("Receiver responded with {}", response.status()),
+        )),
+        Err(e) => Err((None, e.to_string())),
+    };
+
+    let recorded = match &outcome {
+        Ok(status) => repo.record_webhook_delivery(&hook.id, Some(i64::from(*status)), None).await,
+        Err((status, message)) => {
+            log_warn!(&format!("Webhook '{}' delivery failed: {}", hook.name, message));
+            repo.record_webhook_delivery(&hook.id, status.map(i64::from), Some(message))
+                .await
+        }
+    };
+    if let Err(e) = recorded {
+        log_error!("Failed to record webhook delivery", e);
+    }
+
+    outcome.map_err(|(_, message)| {
+        AppError::new(ErrorCode::InternalError, "Webhook delivery failed").with_details(message)
+    })
+}
+
+/// Sends a `webhook.test` delivery to a single webhook, ignoring its filters
+pub async fn send_test_delivery(repo: &Repository, hook: &Webhook) -> AppResult<u16> {
+    let event = AppEvent::new("webhook.test", "webhook", hook.id.clone());
+    let payload = WebhookPayload::new(&event, None);
+    deliver(&http_client(), repo, hook, &payload).await
+}
+
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
@@ -349,11 +349,23 @@ describe('API Client', () => {
         message: 'Rolled back to version 3',
         version: 3,
       };
-      vi.mocked(invoke).mockResolvedValueOnce(mockResult);
+      vi.mocked(invoke)
+        .mockResolvedValueOnce({
+          token: 'token-1',
+          command: 'rollback_migration',
+          expires_at: createTimestamp(),
+        })
+        .mockResolvedValueOnce(mockResult);
 
       const result = await api.migration.rollback(version);
 
-      expect(invoke).toHaveBeenCalledWith('rollback_migration', { version });
+      expect(invoke).toHaveBeenCalledWith('request_destructive_confirmation', {
+        command: 'rollback_migration',
+      });
+      expect(invoke).toHaveBeenCalledWith('rollback_migration', {
+        version,
+        confirmationToken: 'token-1',
+      });
       expect(result).toEqual(mockResult);
     });
   });
@@ -462,11 +474,19 @@ describe('API Client', () => {
         message: 'Cleanup complete',
         affected_rows: 25,
       };
-      vi.mocked(invoke).mockResolvedValueOnce(mockResult);
+      vi.mocked(invoke)
+        .mockResolvedValueOnce({ token: 'token-1', command: 'cleanup_database', expires_at: '' })
+        .mockResolvedValueOnce(mockResult);
 
       const result = await api.repository.cleanup(options);
 
-      expect(invoke).toHaveBeenCalledWith('cleanup_database', { options });
+      expect(invoke).toHaveBeenCalledWith('request_destructive_confirmation', {
+        command: 'cleanup_database',
+      });
+      expect(invoke).toHaveBeenCalledWith('cleanup_database', {
+        options,
+        confirmationToken: 'token-1',
+      });
       expect(result).toEqual(mockResult);
     });
 
@@ -14,6 +14,7 @@ import type {
   BatchDeleteRequest,
   DatabaseStats,
   CleanupOptions,
+  DestructiveConfirmation,
   ExportRequest,
   ExportResult,
 } from '../types';
@@ -54,8 +55,16 @@ export const repositoryApi = {
   batchDelete: (request: BatchDeleteRequest) =>
     tauriClient['invokeCommand']<TransactionResult>('batch_delete', { request }),
   getStats: () => tauriClient['invokeCommand']<DatabaseStats>('get_database_stats'),
-  cleanup: (options: CleanupOptions) =>
-    tauriClient['invokeCommand']<TransactionResult>('cleanup_database', { options }),
+  cleanup: async (options: CleanupOptions) => {
+    const { token } = await tauriClient['invokeCommand']<DestructiveConfirmation>(
+      'request_destructive_confirmation',
+      { command: 'cleanup_database' },
+    );
+    return tauriClient['invokeCommand']<TransactionResult>('cleanup_database', {
+      options,
+      confirmationToken: token,
+    });
+  },
   exportData: (request: ExportRequest) =>
     tauriClient['invokeCommand']<ExportResult>('export_all_data', { request }),
 };
@@ -10,6 +10,7 @@ import type {
   UpdateTaskRequest,
   CreateNoteRequest,
   UpdateNoteRequest,
+  NoteSearchScope,
 } from '../../types/commands';
 
 /**
@@ -89,7 +90,7 @@ export interface ApiClient {
     update: (data: UpdateNoteRequest) => Promise<Note>;
     delete: (id: string) => Promise<void>;
     restore: (id: string) => Promise<Note>;
-    search: (query: string) => Promise<Note[]>;
+    search: (query: string, scope?: NoteSearchScope) => Promise<Note[]>;
   };
 }
 
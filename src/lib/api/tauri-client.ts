@@ -14,7 +14,9 @@ import type {
   UpdateTaskRequest,
   CreateNoteRequest,
   UpdateNoteRequest,
+  NoteSearchScope,
 } from '../../types/commands';
+import type { DestructiveConfirmation } from '../../types/repository';
 import { EvorBrainError } from '../../types/errors';
 
 /**
@@ -52,8 +54,20 @@ export class TauriApiClient implements ApiClient {
   migration = {
     getStatus: () => this.invokeCommand<MigrationStatus>('get_migration_status'),
     run: () => this.invokeCommand<void>('run_migrations'),
-    rollback: (version: number) => this.invokeCommand<void>('rollback_migration', { version }),
-    reset: () => this.invokeCommand<void>('reset_database'),
+    rollback: async (version: number) => {
+      const { token } = await this.invokeCommand<DestructiveConfirmation>(
+        'request_destructive_confirmation',
+        { command: 'rollback_migration' },
+      );
+      return this.invokeCommand<void>('rollback_migration', { version, confirmationToken: token });
+    },
+    reset: async () => {
+      const { token } = await this.invokeCommand<DestructiveConfirmation>(
+        'request_destructive_confirmation',
+        { command: 'reset_database' },
+      );
+      return this.invokeCommand<void>('reset_database', { confirmationToken: token });
+    },
   };
 
   // Life Area operations
@@ -135,6 +149,7 @@ export class TauriApiClient implements ApiClient {
     update: (data: UpdateNoteRequest) => this.invokeCommand<Note>('update_note', { request: data }),
     delete: (id: string) => this.invokeCommand<void>('delete_note', { id }),
     restore: (id: string) => this.invokeCommand<Note>('restore_note', { id }),
-    search: (query: string) => this.invokeCommand<Note[]>('search_notes', { query }),
+    search: (query: string, scope?: NoteSearchScope) =>
+      this.invokeCommand<Note[]>('search_notes', { query, ...scope }),
   };
 }
@@ -11,6 +11,7 @@ import type {
   UpdateTaskRequest,
   CreateNoteRequest,
   UpdateNoteRequest,
+  NoteSearchScope,
 } from '../../types/commands';
 
 /**
@@ -496,12 +497,31 @@ export class TestApiClient implements ApiClient {
       note.updated_at = new Date().toISOString();
       return note;
     },
-    search: async (query: string) => {
+    search: async (query: string, scope?: NoteSearchScope) => {
       const lowerQuery = query.toLowerCase();
+      const goals = Array.from(this.data.goals.values()).filter(
+        (goal) => goal.id === scope?.goalId || goal.life_area_id === scope?.lifeAreaId,
+      );
+      const projects = Array.from(this.data.projects.values()).filter(
+        (project) =>
+          project.id === scope?.projectId || goals.some((goal) => goal.id === project.goal_id),
+      );
+      const inScope = (note: Note) =>
+        !scope ||
+        (!scope.projectId && !scope.goalId && !scope.lifeAreaId) ||
+        (scope.lifeAreaId !== undefined && note.life_area_id === scope.lifeAreaId) ||
+        goals.some((goal) => goal.id === note.goal_id) ||
+        projects.some(
+          (project) =>
+            project.id === note.project_id ||
+            (note.task_id !== undefined &&
+              this.data.tasks.get(note.task_id)?.project_id === project.id),
+        );
       return Array.from(this.data.notes.values()).filter(
         (note) =>
-          note.title.toLowerCase().includes(lowerQuery) ||
-          note.content.toLowerCase().includes(lowerQuery),
+          inScope(note) &&
+          (note.title.toLowerCase().includes(lowerQuery) ||
+            note.content.toLowerCase().includes(lowerQuery)),
       );
     },
   };
@@ -16,6 +16,8 @@ export interface UpdateLifeAreaRequest {
   description?: string;
   color?: string;
   icon?: string;
+  /** `updated_at` as loaded; a newer stored version fails the update with CONFLICT */
+  expected_updated_at?: string;
 }
 
 // Goal Commands
@@ -24,6 +26,8 @@ export interface CreateGoalRequest {
   title: string;
   description?: string;
   target_date?: string;
+  color?: string;
+  icon?: string;
 }
 
 export interface UpdateGoalRequest {
@@ -32,6 +36,10 @@ export interface UpdateGoalRequest {
   title: string;
   description?: string;
   target_date?: string;
+  color?: string;
+  icon?: string;
+  /** `updated_at` as loaded; a newer stored version fails the update with CONFLICT */
+  expected_updated_at?: string;
 }
 
 // Project Commands
@@ -40,6 +48,8 @@ export interface CreateProjectRequest {
   title: string;
   description?: string;
   status?: ProjectStatus;
+  color?: string;
+  icon?: string;
 }
 
 export interface UpdateProjectRequest {
@@ -48,6 +58,10 @@ export interface UpdateProjectRequest {
   title: string;
   description?: string;
   status: ProjectStatus;
+  color?: string;
+  icon?: string;
+  /** `updated_at` as loaded; a newer stored version fails the update with CONFLICT */
+  expected_updated_at?: string;
 }
 
 // Task Commands
@@ -73,6 +87,8 @@ export interface UpdateTaskRequest {
   description?: string;
   priority: TaskPriority;
   due_date?: string;
+  /** `updated_at` as loaded; a newer stored version fails the update with CONFLICT */
+  expected_updated_at?: string;
 }
 
 // Note Commands
@@ -93,6 +109,18 @@ export interface UpdateNoteRequest {
   life_area_id?: string;
   title: string;
   content: string;
+  /** `updated_at` as loaded; a newer stored version fails the update with CONFLICT */
+  expected_updated_at?: string;
+}
+
+/**
+ * Limits a note search to one project, goal or life area and everything
+ * beneath it; give at most one
+ */
+export interface NoteSearchScope {
+  projectId?: string;
+  goalId?: string;
+  lifeAreaId?: string;
 }
 
 // Migration Commands
@@ -18,6 +18,7 @@ export enum ErrorCode {
   ALREADY_EXISTS = 'ALREADY_EXISTS',
   CANNOT_DELETE = 'CANNOT_DELETE',
   CANNOT_UPDATE = 'CANNOT_UPDATE',
+  CONFLICT = 'CONFLICT',
 
   // System errors
   INTERNAL_ERROR = 'INTERNAL_ERROR',
@@ -37,6 +38,8 @@ export interface AppError {
   code: ErrorCode;
   message: string;
   details?: string;
+  /** Machine-readable context, e.g. both versions of a CONFLICT */
+  data?: unknown;
 }
 
 /**
@@ -48,6 +51,7 @@ export interface AppError {
 export class EvorBrainError extends Error {
   code: ErrorCode;
   details?: string;
+  data?: unknown;
 
   /**
    * Creates a new EvorBrainError instance
@@ -58,6 +62,7 @@ export class EvorBrainError extends Error {
     this.name = 'EvorBrainError';
     this.code = error.code;
     this.details = error.details;
+    this.data = error.data;
   }
 
   /**
@@ -86,6 +91,7 @@ export class EvorBrainError extends Error {
       code: this.code,
       message: this.message,
       details: this.details,
+      data: this.data,
     };
   }
 }
@@ -14,6 +14,7 @@ export enum ProjectStatus {
 
 /**
  * Priority levels for tasks
+ * The backend still accepts `critical` as an alias for `urgent`
  * @enum {string}
  */
 export enum TaskPriority {
@@ -56,6 +57,18 @@ export interface Goal {
   updated_at: string;
   completed_at?: string;
   archived_at?: string;
+  /** Set while the goal is parked on the someday/maybe list */
+  incubated_at?: string;
+  /** Position among the goals of the same life area */
+  sort_order?: number;
+  /** Completion percentage, 0-100 */
+  progress?: number;
+  /** Own color and icon; unset shows those of the level above */
+  color?: string;
+  icon?: string;
+  /** Color and icon to show, inherited from above unless overridden */
+  display_color?: string;
+  display_icon?: string;
 }
 
 /**
@@ -73,6 +86,21 @@ export interface Project {
   updated_at: string;
   completed_at?: string;
   archived_at?: string;
+  /** Days between reviews; the `review_interval_days` setting applies when unset */
+  review_interval_days?: number;
+  last_reviewed_at?: string;
+  /** Set while the project is parked on the someday/maybe list */
+  incubated_at?: string;
+  /** Position among the projects of the same goal */
+  sort_order?: number;
+  /** Completion percentage, 0-100 */
+  progress?: number;
+  /** Own color and icon; unset shows those of the level above */
+  color?: string;
+  icon?: string;
+  /** Color and icon to show, inherited from above unless overridden */
+  display_color?: string;
+  display_icon?: string;
 }
 
 /**
@@ -92,6 +120,17 @@ export interface Task {
   updated_at: string;
   completed_at?: string;
   archived_at?: string;
+  /** Key of a status in the project's workflow */
+  status?: string;
+  /** Expected effort in minutes */
+  estimated_minutes?: number;
+  /** Time spent so far in minutes; stopped timers add to it */
+  actual_minutes?: number;
+  /** iCalendar RRULE, e.g. `FREQ=WEEKLY;BYDAY=MO` */
+  recurrence_rule?: string;
+  /** Color and icon of the task's project, as displayed */
+  display_color?: string;
+  display_icon?: string;
 }
 
 /**
@@ -110,6 +149,14 @@ export interface Note {
   created_at: string;
   updated_at: string;
   archived_at?: string;
+  is_private?: boolean;
+  /** The day the note is about (`YYYY-MM-DD`), e.g. for a journal entry */
+  note_date?: string;
+  /** Reviews done on the resurface schedule; absent when never scheduled */
+  review_stage?: number;
+  /** When the note resurfaces next; absent when unscheduled or finished */
+  next_review_at?: string;
+  last_reviewed_at?: string;
 }
 
 /**
@@ -28,6 +28,31 @@ export interface DatabaseStats {
   archived_items_count: number;
 }
 
+export type DestructiveCommand =
+  | 'cleanup_database'
+  | 'delete_attachment'
+  | 'delete_context'
+  | 'delete_email_account'
+  | 'delete_external_link'
+  | 'delete_holiday_set'
+  | 'delete_person'
+  | 'delete_script_hook'
+  | 'delete_webhook'
+  | 'import_all_data'
+  | 'import_settings'
+  | 'reset_database'
+  | 'rollback_migration'
+  | 'run_job_now'
+  | 'scan_orphan_attachments'
+  | 'secure_delete'
+  | 'update_settings';
+
+export interface DestructiveConfirmation {
+  token: string;
+  command: DestructiveCommand;
+  expires_at: string; // ISO 8601 datetime
+}
+
 export interface CleanupOptions {
   delete_archived_older_than_days?: number;
   vacuum_database: boolean;